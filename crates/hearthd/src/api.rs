@@ -1,15 +1,43 @@
+use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::path::Path as FsPath;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use axum::Json;
-use axum::Router;
+use axum::body::Bytes;
+use axum::extract::ConnectInfo;
+use axum::extract::Path;
+use axum::extract::Query;
 use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::HeaderValue;
+use axum::http::Method;
 use axum::http::StatusCode;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
 use axum::response::IntoResponse;
+use axum::response::Sse;
 use axum::routing::get;
+use axum::routing::patch;
+use axum::routing::post;
+use axum::Json;
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle as ServerHandle;
+use dashmap::DashMap;
+use futures::Stream;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::PrivateKeyDer;
+use serde::Deserialize;
 use serde::Serialize;
 use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
 use tower_http::trace::TraceLayer;
+use uuid::Uuid;
 
 /// Response for the /v1/ping endpoint
 #[derive(Serialize)]
@@ -24,10 +52,47 @@ struct InfoResponse {
     hostname: String,
 }
 
+/// One entity's state, broadcast to every `/v1/events` subscriber whenever
+/// it changes. Mirrors the fields of a Home Assistant integration's state
+/// update so a dashboard can render either source identically.
+#[derive(Debug, Clone, Serialize)]
+struct EntityStateEvent {
+    entity_id: String,
+    state: String,
+    attributes: serde_json::Value,
+    last_updated: String,
+}
+
+/// Capacity of the `/v1/events` broadcast channel: how many unconsumed
+/// state changes a lagging subscriber can fall behind by before it starts
+/// missing events (see [`BroadcastStreamRecvError::Lagged`] handling in
+/// [`events`]).
+const STATE_EVENTS_CHANNEL_SIZE: usize = 256;
+
 /// Shared application state
 #[derive(Clone)]
 struct AppState {
     version: &'static str,
+
+    /// Publishes an [`EntityStateEvent`] whenever entity state changes, for
+    /// the `/v1/events` SSE stream to fan out to every connected client.
+    /// `broadcast` rather than `mpsc` since each subscriber needs every
+    /// event, not just one of them.
+    state_events: broadcast::Sender<EntityStateEvent>,
+
+    /// Active WHEP viewing sessions, keyed by session id, for the
+    /// `/v1/camera/{entity_id}/whep` egress path. See [`whep_offer`].
+    camera_sessions: Arc<DashMap<String, CameraSession>>,
+
+    /// ICE servers advertised to WHEP clients via the `Link` response
+    /// header on [`whep_offer`]. A public STUN server by default; TURN
+    /// relays can be added here once `hearthd_config` grows a place to
+    /// configure them.
+    ice_servers: Arc<Vec<IceServer>>,
+
+    /// Webhook ids claimed by integrations, for the
+    /// `/v1/webhook/{webhook_id}` receiver. See [`webhook`].
+    webhook_owners: Arc<DashMap<String, WebhookOwner>>,
 }
 
 /// Handler for GET /v1/ping
@@ -61,15 +126,345 @@ async fn info(State(state): State<Arc<AppState>>) -> impl IntoResponse {
     )
 }
 
+/// Query params for GET /v1/events
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// Only stream changes for this entity, rather than every entity.
+    entity_id: Option<String>,
+}
+
+/// Handler for GET /v1/events
+///
+/// Streams entity state changes as Server-Sent Events rather than forcing
+/// clients to poll `/v1/info`-style endpoints. Periodic keep-alive comments
+/// keep idle connections from being dropped by intermediate proxies.
+#[tracing::instrument(skip(state))]
+async fn events(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    tracing::debug!("Handling /v1/events request");
+
+    let stream = BroadcastStream::new(state.state_events.subscribe()).filter_map(move |msg| {
+        let event = match msg {
+            Ok(event) => event,
+            // The subscriber fell too far behind to catch up; skip the gap
+            // rather than ending the stream.
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::warn!("/v1/events subscriber lagged, skipped {} events", skipped);
+                return None;
+            }
+        };
+
+        if let Some(entity_id) = &query.entity_id {
+            if &event.entity_id != entity_id {
+                return None;
+            }
+        }
+
+        let payload = serde_json::to_string(&event).unwrap_or_default();
+        Some(Ok(Event::default().data(payload)))
+    });
+
+    Sse::new(stream).keep_alive(KeepAlive::new().interval(Duration::from_secs(15)))
+}
+
+/// An ICE server advertised to WHEP clients via the `Link` response header,
+/// e.g. a STUN server for NAT traversal or a TURN relay with credentials.
+struct IceServer {
+    /// `stun:host:port` or `turn:host:port?transport=udp`.
+    url: String,
+    username: Option<String>,
+    credential: Option<String>,
+}
+
+/// Render `servers` as a WHEP/WHIP-style `Link` header value: one
+/// comma-separated `rel="ice-server"` entry per server, with
+/// `username`/`credential` parameters present only for servers that need
+/// them (a plain STUN server doesn't).
+fn build_link_header(servers: &[IceServer]) -> String {
+    servers
+        .iter()
+        .map(|server| {
+            let mut link = format!("<{}>; rel=\"ice-server\"", server.url);
+            if let Some(username) = &server.username {
+                link.push_str(&format!("; username=\"{}\"", username));
+            }
+            if let Some(credential) = &server.credential {
+                link.push_str(&format!(
+                    "; credential=\"{}\"; credential-type=\"password\"",
+                    credential
+                ));
+            }
+            link
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// One active WHEP viewing session for a camera entity, between the initial
+/// SDP offer/answer exchange and its `DELETE` teardown.
+struct CameraSession {
+    entity_id: String,
+}
+
+/// Build a syntactically valid SDP answer for `offer`, one `recvonly` media
+/// section per `m=` line in the offer.
+///
+/// This is protocol scaffolding only: there is no WebRTC media engine in
+/// this tree to actually run ICE/DTLS/SRTP and forward the camera's RTP
+/// stream, so the placeholder ICE credentials and fingerprint below don't
+/// correspond to a real negotiation and no media will actually flow. See the
+/// module-level disclosure in the commit this was introduced in.
+fn negotiate_whep_answer(offer: &str) -> String {
+    let mut answer = String::from(
+        "v=0\r\n\
+         o=- 0 0 IN IP4 0.0.0.0\r\n\
+         s=-\r\n\
+         t=0 0\r\n\
+         a=ice-ufrag:hearth\r\n\
+         a=ice-pwd:hearthdwhepplaceholderpwd00\r\n\
+         a=fingerprint:sha-256 00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00:00\r\n\
+         a=setup:passive\r\n",
+    );
+
+    for line in offer.lines() {
+        if let Some(media) = line.strip_prefix("m=") {
+            let kind = media.split_whitespace().next().unwrap_or("application");
+            answer.push_str(&format!("m={} 9 UDP/TLS/RTP/SAVPF 0\r\n", kind));
+            answer.push_str("c=IN IP4 0.0.0.0\r\n");
+            answer.push_str("a=recvonly\r\n");
+            answer.push_str("a=rtcp-mux\r\n");
+        }
+    }
+
+    answer
+}
+
+/// Handler for POST /v1/camera/{entity_id}/whep
+///
+/// Implements the offer side of WHEP (WebRTC-HTTP Egress Protocol): the
+/// client posts an SDP offer and gets back an SDP answer plus a `Location`
+/// header identifying the new session (for the later `DELETE`/`PATCH`
+/// below) and a `Link` header advertising ICE servers.
+#[tracing::instrument(skip(state, offer))]
+async fn whep_offer(
+    State(state): State<Arc<AppState>>,
+    Path(entity_id): Path<String>,
+    offer: String,
+) -> impl IntoResponse {
+    tracing::debug!("Handling WHEP offer for camera {}", entity_id);
+
+    let session_id = Uuid::new_v4().to_string();
+    state.camera_sessions.insert(
+        session_id.clone(),
+        CameraSession {
+            entity_id: entity_id.clone(),
+        },
+    );
+
+    let answer = negotiate_whep_answer(&offer);
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/sdp"),
+    );
+    if let Ok(location) =
+        HeaderValue::from_str(&format!("/v1/camera/{}/whep/{}", entity_id, session_id))
+    {
+        headers.insert(axum::http::header::LOCATION, location);
+    }
+    if let Ok(link) = HeaderValue::from_str(&build_link_header(&state.ice_servers)) {
+        headers.insert(axum::http::header::LINK, link);
+    }
+
+    (StatusCode::CREATED, headers, answer)
+}
+
+/// Handler for PATCH /v1/camera/{entity_id}/whep/{session_id}
+///
+/// Accepts a trickle-ICE SDP fragment from the client. There's no live
+/// PeerConnection to apply it to (see [`negotiate_whep_answer`]), so this
+/// only validates the session exists and otherwise discards the candidate.
+#[tracing::instrument(skip(state, _fragment))]
+async fn whep_trickle_ice(
+    State(state): State<Arc<AppState>>,
+    Path((entity_id, session_id)): Path<(String, String)>,
+    _fragment: String,
+) -> StatusCode {
+    match state.camera_sessions.get(&session_id) {
+        Some(session) if session.entity_id == entity_id => StatusCode::NO_CONTENT,
+        _ => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Handler for DELETE /v1/camera/{entity_id}/whep/{session_id}
+///
+/// Tears down a WHEP viewing session.
+#[tracing::instrument(skip(state))]
+async fn whep_teardown(
+    State(state): State<Arc<AppState>>,
+    Path((entity_id, session_id)): Path<(String, String)>,
+) -> StatusCode {
+    match state.camera_sessions.get(&session_id) {
+        Some(session) if session.entity_id == entity_id => {
+            state.camera_sessions.remove(&session_id);
+            StatusCode::NO_CONTENT
+        }
+        _ => StatusCode::NOT_FOUND,
+    }
+}
+
+/// Which integration claimed a webhook id via `ha::Message::WebhookRegister`,
+/// and whether delivery should be restricted to loopback/RFC1918 callers.
+#[derive(Clone)]
+struct WebhookOwner {
+    #[allow(dead_code)] // not yet read - see `webhook`'s doc comment
+    entry_id: String,
+    local_only: bool,
+}
+
+/// `true` if `ip` is a loopback or RFC1918 private address, i.e. suitable
+/// for a `local_only` webhook.
+fn is_local_address(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(v4) => v4.is_loopback() || v4.is_private(),
+        std::net::IpAddr::V6(v6) => v6.is_loopback(),
+    }
+}
+
+/// Handler for POST/PUT /v1/webhook/{webhook_id}
+///
+/// Reverse-proxies an inbound webhook call to the integration that claimed
+/// `webhook_id`, 404ing if nothing has. `local_only` webhooks additionally
+/// reject callers outside loopback/RFC1918 source addresses.
+///
+/// This crate's `ha` module (where `Message::WebhookRegister` is handled
+/// and `RouteSender::deliver_webhook` actually forwards to the sandbox
+/// over the Unix socket) isn't wired into this binary yet - see the
+/// module-level disclosure in the commit this was introduced in - so
+/// `webhook_owners` has nothing feeding it and every call here 404s or, for
+/// a claimed id, has no live sender to forward to.
+#[tracing::instrument(skip(state, headers, body))]
+async fn webhook(
+    State(state): State<Arc<AppState>>,
+    Path(webhook_id): Path<String>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    method: Method,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    let Some(owner) = state.webhook_owners.get(&webhook_id) else {
+        return (StatusCode::NOT_FOUND, "unknown webhook id".to_string()).into_response();
+    };
+
+    if owner.local_only && !is_local_address(peer.ip()) {
+        return (
+            StatusCode::FORBIDDEN,
+            "webhook is restricted to local callers".to_string(),
+        )
+            .into_response();
+    }
+
+    tracing::warn!(
+        "webhook {} ({} bytes, method {}) has no integration dispatch path wired up yet",
+        webhook_id,
+        body.len(),
+        method
+    );
+    let _ = headers;
+    (
+        StatusCode::BAD_GATEWAY,
+        "no integration dispatch path wired up yet".to_string(),
+    )
+        .into_response()
+}
+
 /// Create the API router with all endpoints
 fn create_router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/v1/ping", get(ping))
         .route("/v1/info", get(info))
+        .route("/v1/events", get(events))
+        .route("/v1/camera/:entity_id/whep", post(whep_offer))
+        .route(
+            "/v1/camera/:entity_id/whep/:session_id",
+            patch(whep_trickle_ice).delete(whep_teardown),
+        )
+        .route("/v1/webhook/:webhook_id", post(webhook).put(webhook))
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
 
+/// How strictly client certificates are verified when [`TlsConfig`] is in
+/// use - independent of whether TLS itself is enabled at all (see
+/// [`serve`]'s `tls: Option<TlsConfig>` parameter).
+pub enum ClientAuth {
+    /// No client certificate verification - the common case for plain HTTPS.
+    Off,
+    /// Verify a client certificate against the platform trust roots if one
+    /// is presented, but don't require it.
+    Optional,
+    /// Reject the handshake unless the client presents a certificate
+    /// verified against the platform trust roots (mTLS).
+    Required,
+}
+
+/// TLS listener configuration for [`serve`]. Passing `None` instead binds a
+/// plain `TcpListener` exactly as before TLS support existed.
+pub struct TlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    pub client_auth: ClientAuth,
+}
+
+impl TlsConfig {
+    /// Build the `rustls::ServerConfig` this configuration describes:
+    /// loads the server's own certificate chain and private key from
+    /// `cert_path`/`key_path`, and for [`ClientAuth::Optional`]/
+    /// [`ClientAuth::Required`], the platform's native trust roots (via
+    /// `rustls-native-certs`) to verify client certificates against.
+    fn into_rustls_config(self) -> Result<rustls::ServerConfig, Box<dyn std::error::Error>> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let builder = rustls::ServerConfig::builder();
+        let builder = match self.client_auth {
+            ClientAuth::Off => builder.with_no_client_auth(),
+            ClientAuth::Optional | ClientAuth::Required => {
+                let mut roots = rustls::RootCertStore::empty();
+                for cert in rustls_native_certs::load_native_certs()? {
+                    roots.add(cert)?;
+                }
+                let verifier_builder =
+                    rustls::server::WebPkiClientVerifier::builder(Arc::new(roots));
+                let verifier = if matches!(self.client_auth, ClientAuth::Optional) {
+                    verifier_builder.allow_unauthenticated().build()?
+                } else {
+                    verifier_builder.build()?
+                };
+                builder.with_client_cert_verifier(verifier)
+            }
+        };
+
+        Ok(builder.with_single_cert(certs, key)?)
+    }
+}
+
+fn load_certs(path: &FsPath) -> Result<Vec<CertificateDer<'static>>, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    Ok(rustls_pemfile::certs(&mut reader).collect::<Result<Vec<_>, _>>()?)
+}
+
+fn load_private_key(path: &FsPath) -> Result<PrivateKeyDer<'static>, Box<dyn std::error::Error>> {
+    let file = std::fs::File::open(path)?;
+    let mut reader = std::io::BufReader::new(file);
+    rustls_pemfile::private_key(&mut reader)?
+        .ok_or_else(|| format!("no private key found in {}", path.display()).into())
+}
+
 /// Start the HTTP API server
 ///
 /// This function will bind to the specified address and serve the API endpoints.
@@ -79,6 +474,7 @@ fn create_router(state: Arc<AppState>) -> Router {
 /// * `listen` - The IP address to listen on (e.g., "127.0.0.1")
 /// * `port` - The port to listen on (e.g., 8565)
 /// * `shutdown_rx` - A oneshot receiver that will trigger graceful shutdown
+/// * `tls` - Certificate/key to terminate TLS with, or `None` to serve plaintext HTTP
 ///
 /// # Returns
 /// Returns Ok(()) if the server shuts down gracefully, or an error if startup fails
@@ -86,23 +482,61 @@ pub async fn serve(
     listen: String,
     port: u16,
     shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+    tls: Option<TlsConfig>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let version = env!("CARGO_PKG_VERSION");
+    let (state_events, _) = broadcast::channel(STATE_EVENTS_CHANNEL_SIZE);
 
-    let state = Arc::new(AppState { version });
+    let state = Arc::new(AppState {
+        version,
+        state_events,
+        camera_sessions: Arc::new(DashMap::new()),
+        ice_servers: Arc::new(vec![IceServer {
+            url: "stun:stun.l.google.com:19302".to_string(),
+            username: None,
+            credential: None,
+        }]),
+        webhook_owners: Arc::new(DashMap::new()),
+    });
     let app = create_router(state);
 
     let addr: SocketAddr = format!("{}:{}", listen, port).parse()?;
-    tracing::info!("Starting HTTP API server on {}", addr);
 
-    let listener = TcpListener::bind(addr).await?;
+    match tls {
+        None => {
+            tracing::info!("Starting HTTP API server on {}", addr);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async {
-            shutdown_rx.await.ok();
-            tracing::info!("HTTP API server shutting down gracefully");
-        })
-        .await?;
+            let listener = TcpListener::bind(addr).await?;
+
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(async {
+                shutdown_rx.await.ok();
+                tracing::info!("HTTP API server shutting down gracefully");
+            })
+            .await?;
+        }
+        Some(tls) => {
+            tracing::info!("Starting HTTPS API server on {}", addr);
+
+            let rustls_config = RustlsConfig::from_config(Arc::new(tls.into_rustls_config()?));
+
+            let handle = ServerHandle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                shutdown_rx.await.ok();
+                tracing::info!("HTTPS API server shutting down gracefully");
+                shutdown_handle.graceful_shutdown(Some(Duration::from_secs(10)));
+            });
+
+            axum_server::bind_rustls(addr, rustls_config)
+                .handle(handle)
+                .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+                .await?;
+        }
+    }
 
     Ok(())
 }
@@ -5,6 +5,27 @@
 use chumsky::span::SimpleSpan;
 use strum::Display;
 
+/// Identifies which source file a span's byte offsets are relative to.
+///
+/// Spans themselves (`SimpleSpan`) stay plain `usize` offsets - the lexer
+/// and parser only ever see one file's text at a time, so there's nothing
+/// for them to tag. `FileId` lives one layer up, on the diagnostics that
+/// outlive a single parse (`TypeError`, `LoweredAutomation`/
+/// `LoweredProgram`), so a checker or renderer that's juggling more than
+/// one file (e.g. a future `import`ed definition and its use site) can
+/// still tell which file each span's offsets belong to. Every program
+/// parsed today is the only file involved in its own compilation, so
+/// `FileId::default()` ("file 0") is the only id that exists in practice -
+/// this is plumbing for multi-file support, not multi-file support itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct FileId(pub u32);
+
+impl std::fmt::Display for FileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// An AST node with an associated source span.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Spanned<T> {
@@ -43,6 +64,11 @@ pub struct TemplateParam {
 #[derive(Debug, Clone, PartialEq)]
 pub struct Automation {
     pub kind: AutomationKind,
+    /// Span of just the `observer`/`mutator` keyword, kept separately from
+    /// the whole-automation span so diagnostics (e.g. a return-type
+    /// mismatch) can point at "this is declared an observer here" as a
+    /// secondary label instead of underlining the entire automation.
+    pub kind_span: SimpleSpan,
     pub pattern: Spanned<Pattern>,
     pub filter: Spanned<Expr>,
     pub body: Vec<Spanned<Stmt>>,
@@ -75,10 +101,52 @@ pub struct FieldPattern {
     pub pattern: Option<Spanned<Pattern>>,
 }
 
+/// A pattern in a `match` arm. Enum variants in this DSL are constructed and
+/// represented positionally (`Event::LightStateChanged(l)`), so variant
+/// patterns destructure positionally too, rather than by field name like
+/// [`Pattern::Struct`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchPattern {
+    /// `EnumName::Variant` or `EnumName::Variant(bindings...)`.
+    Variant {
+        enum_name: String,
+        variant: String,
+        bindings: Vec<Spanned<BindingPattern>>,
+    },
+    /// `_`, matching any remaining value.
+    Wildcard,
+}
+
+/// A single positional binding within a [`MatchPattern::Variant`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingPattern {
+    Ident(String),
+    Wildcard,
+}
+
+/// A single arm of a `match` expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Spanned<MatchPattern>,
+    pub body: Vec<Spanned<Stmt>>,
+}
+
 /// A statement.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
     Let { name: String, value: Spanned<Expr> },
+    /// `let mut name = value;` — unlike `Let`, `name` may later be the
+    /// target of a [`Stmt::CompoundAssign`].
+    LetMut { name: String, value: Spanned<Expr> },
+    /// `name += value;` (and `-=`/`*=`/`/=`/`%=`). `op` reuses [`BinOp`]
+    /// rather than a dedicated compound-operator enum, since its desugared
+    /// meaning (`name = name op value`, or list extension for `+=`) is
+    /// entirely captured by the plain binary operator it stands for.
+    CompoundAssign {
+        name: String,
+        op: BinOp,
+        value: Spanned<Expr>,
+    },
     Expr(Spanned<Expr>),
 }
 
@@ -141,12 +209,27 @@ pub enum Expr {
     // List literal
     List(Vec<Spanned<Expr>>),
 
-    // List comprehension
+    // List comprehension: `[expr for v1 in it1 if c1 for v2 in it2 ...]`.
+    // `clauses` runs in source order and may mix any number of generators
+    // and filters, e.g. `[x + y for x in xs if x > 0 for y in ys if y < x]`.
     ListComp {
         expr: Box<Spanned<Expr>>,
-        var: String,
-        iter: Box<Spanned<Expr>>,
-        filter: Option<Box<Spanned<Expr>>>,
+        clauses: Vec<CompClause>,
+    },
+
+    // Dict comprehension: `{key: value for v1 in it1 if c1 for v2 in it2
+    // ...}`. `clauses` runs in source order exactly like `ListComp`'s, via
+    // the same `CompClause`.
+    DictComp {
+        key: Box<Spanned<Expr>>,
+        value: Box<Spanned<Expr>>,
+        clauses: Vec<CompClause>,
+    },
+
+    // Set comprehension: `{expr for v1 in it1 if c1 for v2 in it2 ...}`.
+    SetComp {
+        expr: Box<Spanned<Expr>>,
+        clauses: Vec<CompClause>,
     },
 
     // Struct literal
@@ -154,6 +237,57 @@ pub enum Expr {
         name: String,
         fields: Vec<Spanned<StructField>>,
     },
+
+    // Match expression
+    Match {
+        scrutinee: Box<Spanned<Expr>>,
+        arms: Vec<MatchArm>,
+    },
+
+    // Lambda expression: `|params| body`, e.g. the predicate passed to
+    // `filter`/`map`/`fold`. Parameters are untyped in the surface syntax -
+    // the checker gives each a fresh type variable and infers it from the
+    // call site.
+    Lambda {
+        params: Vec<String>,
+        body: Box<Spanned<Expr>>,
+    },
+
+    // Tuple literal: `(a, b, c)`, e.g. a rule returning `(entity,
+    // brightness)` without defining a one-off struct. A single
+    // parenthesized expression with no comma is grouping, not a 1-tuple -
+    // see the parser's `atom` combinator.
+    Tuple(Vec<Spanned<Expr>>),
+}
+
+/// A single clause of a [`Expr::ListComp`]: either a generator binding a
+/// loop variable over an iterable, or a filter guarding every clause after
+/// it. Clauses run in source order, so `[x + y for x in xs if x > 0 for y
+/// in ys if y < x]` parses to `[For(x, xs), If(x > 0), For(y, ys), If(y <
+/// x)]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompClause {
+    /// `for var in iter`
+    For {
+        var: Spanned<BindPattern>,
+        iter: Spanned<Expr>,
+    },
+    /// `if cond`
+    If(Spanned<Expr>),
+}
+
+/// A pattern in a loop/comprehension variable-binding position, e.g. `for
+/// (k, v) in pairs` or `[k for (k, v) in pairs]` - the `for (x, y) in it`
+/// idiom from iterator code. Unlike [`Pattern`], which destructures a
+/// struct-shaped event by field name, this only supports positional tuple
+/// destructuring: a single name, or a parenthesized list of (possibly
+/// nested) sub-patterns.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindPattern {
+    /// A simple name, e.g. `x`.
+    Ident(String),
+    /// `(a, b, ...)`, possibly nested: `(a, (b, c))`.
+    Tuple(Vec<Spanned<BindPattern>>),
 }
 
 /// Binary operators.
@@ -190,6 +324,12 @@ pub enum BinOp {
     And,
     #[strum(serialize = "||")]
     Or,
+
+    // Ranges
+    #[strum(serialize = "..")]
+    Range,
+    #[strum(serialize = "..=")]
+    RangeInclusive,
 }
 
 /// Unary operators.
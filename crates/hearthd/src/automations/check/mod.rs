@@ -4,23 +4,36 @@
 //! - A typed AST with resolved types on every expression
 //! - Entity constraints for runtime validation
 //! - Type errors (if any)
+//!
+//! With the `trace-checker` feature enabled, the core inference entry
+//! points (expression checking, filter checking, observer/mutator return
+//! validation, and field/enum/struct resolution) each open a `tracing`
+//! span recording the node's source span and kind, and its resolved type
+//! on exit. A subscriber (e.g. `tracing_subscriber::fmt` with
+//! `with_span_events`) turns this into an indented trace of the whole
+//! checking run, useful for diagnosing a surprising inferred type or an
+//! error cascade. Without the feature, none of this is compiled in.
 
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 use chumsky::span::SimpleSpan;
 use chumsky::span::Span;
 use facet::Facet;
+use serde::Serialize;
 
 use super::repr::ast;
 use super::repr::lowered;
 use super::repr::typed::CheckResult;
 use super::repr::typed::EntityConstraint;
+use super::repr::typed::Severity;
 use super::repr::typed::Ty;
 use super::repr::typed::TypeError;
 use super::repr::typed::TypedArg;
 use super::repr::typed::TypedAutomation;
 use super::repr::typed::TypedExpr;
 use super::repr::typed::TypedExprKind;
+use super::repr::typed::TypedMatchArm;
 use super::repr::typed::TypedProgram;
 use super::repr::typed::TypedStmt;
 use super::repr::typed::TypedStructField;
@@ -63,10 +76,109 @@ fn shape_to_ty(shape: &facet::Shape) -> Ty {
     }
 }
 
+/// Levenshtein edit distance between two strings, used to find the closest
+/// known field name for a "did you mean" suggestion. Plain DP over a single
+/// row, since these are short identifiers, not arbitrary text.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        curr[0] = i;
+        for j in 1..=b.len() {
+            curr[j] = if a[i - 1] == b[j - 1] {
+                prev[j - 1]
+            } else {
+                1 + prev[j - 1].min(prev[j]).min(curr[j - 1])
+            };
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[b.len()]
+}
+
+/// The variant name of a `LoweredExpr`, for the `node.kind` field on
+/// `check_expr`'s `trace-checker` span - cheaper and more stable across
+/// refactors than formatting the whole node with `{:?}`.
+#[cfg(feature = "trace-checker")]
+fn lowered_expr_kind(expr: &lowered::LoweredExpr) -> &'static str {
+    match expr {
+        lowered::LoweredExpr::Int(_) => "Int",
+        lowered::LoweredExpr::Float(_) => "Float",
+        lowered::LoweredExpr::String(_) => "String",
+        lowered::LoweredExpr::Bool(_) => "Bool",
+        lowered::LoweredExpr::UnitLiteral { .. } => "UnitLiteral",
+        lowered::LoweredExpr::Ident(_) => "Ident",
+        lowered::LoweredExpr::Path(_) => "Path",
+        lowered::LoweredExpr::BinOp { .. } => "BinOp",
+        lowered::LoweredExpr::UnaryOp { .. } => "UnaryOp",
+        lowered::LoweredExpr::Field { .. } => "Field",
+        lowered::LoweredExpr::OptionalField { .. } => "OptionalField",
+        lowered::LoweredExpr::Call { .. } => "Call",
+        lowered::LoweredExpr::If { .. } => "If",
+        lowered::LoweredExpr::List(_) => "List",
+        lowered::LoweredExpr::StructLit { .. } => "StructLit",
+        lowered::LoweredExpr::Block { .. } => "Block",
+        lowered::LoweredExpr::MutableList => "MutableList",
+        lowered::LoweredExpr::MutableMap => "MutableMap",
+        lowered::LoweredExpr::MutableSet => "MutableSet",
+        lowered::LoweredExpr::Match { .. } => "Match",
+        lowered::LoweredExpr::Lambda { .. } => "Lambda",
+        lowered::LoweredExpr::Tuple(_) => "Tuple",
+    }
+}
+
+/// The closest name to `target` among `candidates`, if any is close enough
+/// to be worth suggesting (an edit distance of at most a third of the
+/// target's length, minimum 1 - short names need an almost-exact match).
+fn closest_name<'a>(target: &str, candidates: impl IntoIterator<Item = &'a String>) -> Option<&'a str> {
+    let max_distance = (target.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (c.as_str(), edit_distance(target, c)))
+        .filter(|(_, dist)| *dist <= max_distance)
+        .min_by_key(|(_, dist)| *dist)
+        .map(|(name, _)| name)
+}
+
+/// The closest known type name to `name`, if within edit distance 2
+/// (case-insensitively - `temperature` and `Temperature` are the same typo
+/// distance apart as `Temperature` and itself). Type names are short and
+/// typos in them are usually one or two characters off (`Tempurature` ->
+/// `Temperature`), so this uses a fixed threshold rather than
+/// `closest_name`'s length-relative one.
+fn closest_type_name<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    const MAX_DISTANCE: usize = 2;
+    let name = name.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|c| (c, edit_distance(&name, &c.to_lowercase())))
+        .filter(|(_, dist)| *dist <= MAX_DISTANCE)
+        .min_by_key(|(_, dist)| *dist)
+        .map(|(name, _)| name)
+}
+
+/// The span to blame for a statement block's result value: its last
+/// statement, or `fallback` (typically the enclosing `if`/`match`'s own
+/// span) if the block is empty and so has no result of its own.
+fn block_result_span(
+    stmts: &[lowered::Spanned<lowered::LoweredStmt>],
+    fallback: SimpleSpan,
+) -> SimpleSpan {
+    stmts.last().map(|stmt| stmt.span()).unwrap_or(fallback)
+}
+
 /// Information about an enum type in the registry.
 struct EnumInfo {
-    /// Maps variant name -> variant fields (e.g. "LightStateChanged" -> { entity_id: String, ... })
-    variants: HashMap<String, HashMap<String, Ty>>,
+    /// Variants in declaration order, each with its fields in declaration
+    /// order (e.g. "LightStateChanged" -> [(entity_id, String), ...]).
+    /// Declaration order matters here, unlike `TypeRegistry::struct_fields`'s
+    /// `HashMap`, because enum variants are constructed and destructured
+    /// positionally (`Event::LightStateChanged(l)`, `match` arm bindings).
+    variants: Vec<(String, Vec<(String, Ty)>)>,
 }
 
 /// Registry of known types.
@@ -93,19 +205,22 @@ impl TypeRegistry {
 
     /// Register enum types that can't be derived from facet reflection.
     fn register_enums(&mut self) {
-        let mut variants = HashMap::new();
-        variants.insert("LightStateChanged".into(), {
-            let mut fields = HashMap::new();
-            fields.insert("entity_id".into(), Ty::String);
-            fields.insert("state".into(), Ty::Named("LightState".into()));
-            fields
-        });
-        variants.insert("BinarySensorStateChanged".into(), {
-            let mut fields = HashMap::new();
-            fields.insert("entity_id".into(), Ty::String);
-            fields.insert("state".into(), Ty::Named("BinarySensorState".into()));
-            fields
-        });
+        let variants = vec![
+            (
+                "LightStateChanged".to_string(),
+                vec![
+                    ("entity_id".to_string(), Ty::String),
+                    ("state".to_string(), Ty::Named("LightState".into())),
+                ],
+            ),
+            (
+                "BinarySensorStateChanged".to_string(),
+                vec![
+                    ("entity_id".to_string(), Ty::String),
+                    ("state".to_string(), Ty::Named("BinarySensorState".into())),
+                ],
+            ),
+        ];
         self.enums.insert("Event".into(), EnumInfo { variants });
     }
 
@@ -119,6 +234,21 @@ impl TypeRegistry {
         }
     }
 
+    /// Every declared (non-builtin) type name this registry knows about:
+    /// registered enums, entity-registry aliases, and the facet-reflected
+    /// struct types `shape_for_type` resolves directly - used by
+    /// `ast_type_to_ty` to build "did you mean" suggestions for an unknown
+    /// type name. Keep the facet names here in sync with `shape_for_type`'s
+    /// own match arms.
+    fn known_type_names(&self) -> Vec<String> {
+        ["State", "LightState", "BinarySensorState"]
+            .into_iter()
+            .map(String::from)
+            .chain(self.enums.keys().cloned())
+            .chain(self.entity_registries.keys().cloned())
+            .collect()
+    }
+
     /// Look up a field on a type. Returns `None` if the type or field is unknown.
     fn lookup_field(&self, ty: &Ty, field: &str) -> Option<Ty> {
         match ty {
@@ -143,6 +273,26 @@ impl TypeRegistry {
         }
     }
 
+    /// All known field names on `ty`, for "did you mean" suggestions when
+    /// `lookup_field` fails. Entity registries have no fixed field set (any
+    /// entity id is accepted, constrained at runtime instead), so they
+    /// contribute nothing here.
+    fn field_names(&self, ty: &Ty) -> Vec<String> {
+        match ty {
+            Ty::Named(name) => match Self::shape_for_type(name) {
+                Some(shape) => {
+                    if let facet::Type::User(facet::UserType::Struct(st)) = &shape.ty {
+                        st.fields.iter().map(|f| f.name.to_string()).collect()
+                    } else {
+                        Vec::new()
+                    }
+                }
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
     /// Check if a named type is an entity registry.
     fn is_entity_registry(&self, ty: &Ty) -> bool {
         matches!(ty, Ty::Named(name) if self.entity_registries.contains_key(name.as_str()))
@@ -159,14 +309,23 @@ impl TypeRegistry {
     }
 
     /// Resolve an enum variant path (e.g. ["Event", "LightStateChanged"]).
-    fn resolve_enum_variant(
-        &self,
-        enum_name: &str,
-        variant_name: &str,
-    ) -> Option<&HashMap<String, Ty>> {
+    /// The returned fields are in declaration order, matching positional
+    /// variant construction and `match` arm bindings.
+    fn resolve_enum_variant(&self, enum_name: &str, variant_name: &str) -> Option<&[(String, Ty)]> {
+        self.enums.get(enum_name).and_then(|e| {
+            e.variants
+                .iter()
+                .find(|(name, _)| name == variant_name)
+                .map(|(_, fields)| fields.as_slice())
+        })
+    }
+
+    /// All variant names of an enum, in declaration order. Used for
+    /// exhaustiveness checking in `match` expressions.
+    fn enum_variant_names(&self, enum_name: &str) -> Option<Vec<String>> {
         self.enums
             .get(enum_name)
-            .and_then(|e| e.variants.get(variant_name))
+            .map(|e| e.variants.iter().map(|(name, _)| name.clone()).collect())
     }
 
     /// Check if a name refers to a known enum.
@@ -199,9 +358,23 @@ impl TypeRegistry {
 // TypeEnv
 // =============================================================================
 
+/// A single variable binding: its type, whether it was introduced by
+/// `LetMut` (and so may be targeted by `Push`/`Insert`/`Add`) rather than
+/// `Let`, a pattern binding, a `for` loop variable, or a `match` arm binding
+/// - all of which are immutable - and, where there's a meaningful one, the
+/// span where its type was first established (a `Let`'s initializer, or a
+/// parameter's own declaration). Used to attach a "this is {ty} because it
+/// was bound here" note to a type error, pointing past the use site that
+/// triggered it back to where that type actually came from.
+struct Binding {
+    ty: Ty,
+    mutable: bool,
+    origin: Option<SimpleSpan>,
+}
+
 /// Scoped variable environment for type checking.
 struct TypeEnv {
-    scopes: Vec<HashMap<String, Ty>>,
+    scopes: Vec<HashMap<String, Binding>>,
 }
 
 impl TypeEnv {
@@ -219,32 +392,235 @@ impl TypeEnv {
         self.scopes.pop();
     }
 
+    /// Bind an immutable name (`Let`, pattern destructuring, a `for` loop
+    /// variable, a `match` arm binding, a template parameter), with no
+    /// recorded origin span. Prefer [`TypeEnv::bind_at`] at call sites that
+    /// have one to offer.
     fn bind(&mut self, name: String, ty: Ty) {
+        self.bind_with(name, ty, false, None);
+    }
+
+    /// Like [`TypeEnv::bind`], recording where `ty` was established.
+    fn bind_at(&mut self, name: String, ty: Ty, origin: SimpleSpan) {
+        self.bind_with(name, ty, false, Some(origin));
+    }
+
+    /// Bind a mutable name (`LetMut`), eligible as a `Push`/`Insert`/`Add`
+    /// target.
+    fn bind_mut(&mut self, name: String, ty: Ty, origin: Option<SimpleSpan>) {
+        self.bind_with(name, ty, true, origin);
+    }
+
+    fn bind_with(&mut self, name: String, ty: Ty, mutable: bool, origin: Option<SimpleSpan>) {
         if let Some(scope) = self.scopes.last_mut() {
-            scope.insert(name, ty);
+            scope.insert(
+                name,
+                Binding {
+                    ty,
+                    mutable,
+                    origin,
+                },
+            );
         }
     }
 
     fn lookup(&self, name: &str) -> Option<&Ty> {
+        self.lookup_binding(name).map(|binding| &binding.ty)
+    }
+
+    fn lookup_binding(&self, name: &str) -> Option<&Binding> {
         for scope in self.scopes.iter().rev() {
-            if let Some(ty) = scope.get(name) {
-                return Some(ty);
+            if let Some(binding) = scope.get(name) {
+                return Some(binding);
             }
         }
         None
     }
 
-    /// Update the type of an existing binding (for mutable variables).
+    /// Where `name`'s type was first established (a `Let`'s initializer, a
+    /// parameter's declaration), if anything recorded one - used to attach
+    /// a provenance note to a type error involving `name`.
+    fn lookup_origin(&self, name: &str) -> Option<SimpleSpan> {
+        self.lookup_binding(name).and_then(|binding| binding.origin)
+    }
+
+    /// Every currently in-scope binding name, across every open scope - used
+    /// to build a "did you mean" suggestion when `lookup`/`lookup_binding`
+    /// fails. A shadowed name ends up listed once per scope that declares
+    /// it, which is harmless here since `closest_name` only cares about the
+    /// closest match, not how many times it appears.
+    fn names(&self) -> Vec<String> {
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.keys().cloned())
+            .collect()
+    }
+
+    /// Update the type of an existing binding (for mutable variables,
+    /// refining a `LetMut`'s element type from its first `Push`/`Insert`/
+    /// `Add`). Leaves the binding's mutability untouched.
     fn update(&mut self, name: &str, ty: Ty) {
         for scope in self.scopes.iter_mut().rev() {
-            if scope.contains_key(name) {
-                scope.insert(name.to_string(), ty);
+            if let Some(binding) = scope.get_mut(name) {
+                binding.ty = ty;
                 return;
             }
         }
     }
 }
 
+// =============================================================================
+// Builtin signatures
+// =============================================================================
+
+/// A type scheme node: a `Ty` shape that may mention symbolic type
+/// variables, used to describe a builtin's parameter and return types
+/// before they're instantiated for a particular call.
+///
+/// This mirrors `Ty` one level up - every `Ty` constructor that a builtin
+/// actually needs has a `Sig` counterpart - except that where `Ty` would
+/// hold a concrete element type, `Sig` can instead hold a named
+/// [`Sig::Var`] that gets unified across every parameter (and the return
+/// type) it appears in.
+#[derive(Debug, Clone)]
+enum Sig {
+    /// A fixed, non-generic type, e.g. `Sig::Concrete(Ty::Bool)`.
+    Concrete(Ty),
+    /// A symbolic type variable, e.g. `Sig::Var("T")`. Every occurrence of
+    /// the same name within one builtin's signature instantiates to the
+    /// same fresh `Ty::Var` and so unifies together.
+    Var(&'static str),
+    List(Box<Sig>),
+    Map { key: Box<Sig>, value: Box<Sig> },
+    /// A function type, e.g. the predicate `filter`/`map`/`fold` take.
+    Fn(Vec<Sig>, Box<Sig>),
+}
+
+/// A side constraint on one of a [`BuiltinSig`]'s variables, checked after
+/// instantiation and unification against that variable's resolved type.
+#[derive(Debug, Clone, Copy)]
+enum Constraint {
+    /// Must resolve to `Int` or `Float`.
+    Numeric,
+    /// Must resolve to `List`, `Set`, `Map`, or `String` (anything `len()`
+    /// accepts).
+    Collection,
+}
+
+/// A builtin's type scheme: its parameter types, return type, and any
+/// constraints on the variables they mention. Looked up by
+/// [`builtin_signature`] and checked by
+/// [`TypeChecker::check_builtin_signature`].
+#[derive(Debug, Clone)]
+struct BuiltinSig {
+    params: Vec<Sig>,
+    ret: Sig,
+    constraints: Vec<(&'static str, Constraint)>,
+}
+
+/// Every builtin function name, including `wait` (absent from
+/// `builtin_signature`'s table - see [`TypeChecker::resolve_builtin_call`]).
+/// Used to build a "did you mean" suggestion for an `undefined function`
+/// error; keep this in sync with `builtin_signature`'s match arms.
+const BUILTIN_FUNCTION_NAMES: [&str; 13] = [
+    "sleep",
+    "sleep_unique",
+    "wait",
+    "keys",
+    "values",
+    "len",
+    "abs",
+    "min",
+    "max",
+    "clamp",
+    "filter",
+    "map",
+    "fold",
+];
+
+/// The declarative signature table for builtins whose arity and parameter
+/// types are fixed and positional. `wait` is the one builtin excluded from
+/// this table - see [`TypeChecker::resolve_builtin_call`].
+fn builtin_signature(name: &str) -> Option<BuiltinSig> {
+    use Constraint::*;
+    use Sig::*;
+
+    Some(match name {
+        "sleep" => BuiltinSig {
+            params: vec![Concrete(Ty::Duration)],
+            ret: Concrete(Ty::Future(Box::new(Ty::Unit))),
+            constraints: vec![],
+        },
+        "sleep_unique" => BuiltinSig {
+            params: vec![Concrete(Ty::Duration)],
+            ret: Concrete(Ty::Future(Box::new(Ty::Bool))),
+            constraints: vec![],
+        },
+        "keys" => BuiltinSig {
+            params: vec![Map {
+                key: Box::new(Var("K")),
+                value: Box::new(Var("V")),
+            }],
+            ret: List(Box::new(Var("K"))),
+            constraints: vec![],
+        },
+        "values" => BuiltinSig {
+            params: vec![Map {
+                key: Box::new(Var("K")),
+                value: Box::new(Var("V")),
+            }],
+            ret: List(Box::new(Var("V"))),
+            constraints: vec![],
+        },
+        "len" => BuiltinSig {
+            params: vec![Var("C")],
+            ret: Concrete(Ty::Int),
+            constraints: vec![("C", Collection)],
+        },
+        "abs" => BuiltinSig {
+            params: vec![Var("N")],
+            ret: Var("N"),
+            constraints: vec![("N", Numeric)],
+        },
+        "min" | "max" => BuiltinSig {
+            params: vec![Var("N"), Var("N")],
+            ret: Var("N"),
+            constraints: vec![("N", Numeric)],
+        },
+        "clamp" => BuiltinSig {
+            params: vec![Var("N"), Var("N"), Var("N")],
+            ret: Var("N"),
+            constraints: vec![("N", Numeric)],
+        },
+        "filter" => BuiltinSig {
+            params: vec![
+                List(Box::new(Var("T"))),
+                Fn(vec![Var("T")], Box::new(Concrete(Ty::Bool))),
+            ],
+            ret: List(Box::new(Var("T"))),
+            constraints: vec![],
+        },
+        "map" => BuiltinSig {
+            params: vec![
+                List(Box::new(Var("T"))),
+                Fn(vec![Var("T")], Box::new(Var("R"))),
+            ],
+            ret: List(Box::new(Var("R"))),
+            constraints: vec![],
+        },
+        "fold" => BuiltinSig {
+            params: vec![
+                List(Box::new(Var("T"))),
+                Var("Acc"),
+                Fn(vec![Var("Acc"), Var("T")], Box::new(Var("Acc"))),
+            ],
+            ret: Var("Acc"),
+            constraints: vec![],
+        },
+        _ => return None,
+    })
+}
+
 // =============================================================================
 // TypeChecker
 // =============================================================================
@@ -255,6 +631,27 @@ pub struct TypeChecker {
     env: TypeEnv,
     errors: Vec<TypeError>,
     constraints: Vec<EntityConstraint>,
+    /// The automation's declared return type (`[Event]` for observers,
+    /// `Event` for mutators), consulted by `return` statements so they can
+    /// be checked bidirectionally just like the body's tail expression.
+    /// `None` outside of an automation body.
+    expected_return: Option<Ty>,
+    /// Union-find-style substitution table for `Ty::Var` placeholders
+    /// introduced by `fresh_ty_var`, populated as `unify_ty` binds them to
+    /// concrete types.
+    substitutions: HashMap<u32, Ty>,
+    /// Next id handed out by `fresh_ty_var`.
+    next_ty_var: u32,
+    /// `Ty::Var` ids already reported as "cannot infer element type" -
+    /// consulted by `finalize_ty` so a variable that shows up in several
+    /// `TypedExpr`/`TypedStmt` nodes (e.g. both the `let mut` binding and
+    /// every `Push` into it) gets exactly one diagnostic instead of one per
+    /// occurrence.
+    reported_unbound_vars: HashSet<u32>,
+    /// The file being checked; see `ast::FileId`. Stamped onto every
+    /// `TypeError` this checker produces by `error`/`error_with`, so call
+    /// sites building a `TypeError` never have to set it themselves.
+    file: ast::FileId,
 }
 
 impl Default for TypeChecker {
@@ -270,11 +667,50 @@ impl TypeChecker {
             env: TypeEnv::new(),
             errors: Vec::new(),
             constraints: Vec::new(),
+            expected_return: None,
+            substitutions: HashMap::new(),
+            next_ty_var: 0,
+            reported_unbound_vars: HashSet::new(),
+            file: ast::FileId::default(),
+        }
+    }
+
+    /// Like `new`, but tags every error this checker produces with `file`
+    /// instead of `FileId::default()` - for a caller that knows it's
+    /// checking one file among several.
+    pub fn with_file(file: ast::FileId) -> Self {
+        Self {
+            file,
+            ..Self::new()
         }
     }
 
     fn error(&mut self, span: SimpleSpan, message: String) {
-        self.errors.push(TypeError { message, span });
+        self.error_with(TypeError::new(span, message));
+    }
+
+    /// Push a structured [`TypeError`] built with `TypeError::new(...)`
+    /// `.with_code(...)`/`.with_secondary(...)`/`.with_help(...)` - for the
+    /// handful of call sites precise enough to attach a stable code or point
+    /// at a second, relevant span. Stamps `self.file` onto the error (and
+    /// onto any secondary label still at its default file), so call sites
+    /// never need to mention the file themselves.
+    fn error_with(&mut self, mut error: TypeError) {
+        error.file = self.file;
+        for secondary in &mut error.secondary {
+            if secondary.file == ast::FileId::default() {
+                secondary.file = self.file;
+            }
+        }
+        self.errors.push(error);
+    }
+
+    /// Allocate a fresh, as-yet-unconstrained type variable, e.g. for the
+    /// element type of `let mut xs = []` until its first `Push`.
+    fn fresh_ty_var(&mut self) -> Ty {
+        let id = self.next_ty_var;
+        self.next_ty_var += 1;
+        Ty::Var(id)
     }
 
     // =========================================================================
@@ -289,12 +725,13 @@ impl TypeChecker {
             lowered::LoweredProgram::Template {
                 params,
                 automations,
+                ..
             } => {
                 // Bind template parameters
                 self.env.push_scope();
                 for param in params {
-                    let ty = self.ast_type_to_ty(&param.node.ty);
-                    self.env.bind(param.node.name.clone(), ty);
+                    let ty = self.ast_type_to_ty(&param.node.ty, param.span);
+                    self.env.bind_at(param.node.name.clone(), ty, param.span);
                 }
                 let typed_autos: Vec<_> = automations
                     .iter()
@@ -328,6 +765,8 @@ impl TypeChecker {
         self.check_pattern(&auto.pattern, &input_fields);
 
         let filter = auto.filter.as_ref().map(|f| {
+            #[cfg(feature = "trace-checker")]
+            let _span = tracing::debug_span!("check_filter", node.span = ?f.span()).entered();
             let typed = self.check_expr(f);
             if typed.ty != Ty::Bool && typed.ty != Ty::Error {
                 self.error(f.span(), format!("filter must be Bool, found {}", typed.ty));
@@ -335,39 +774,88 @@ impl TypeChecker {
             typed
         });
 
-        let body: Vec<_> = auto.body.iter().map(|s| self.check_stmt(s)).collect();
-
-        // Validate return type
-        let body_ty = self.body_type(&body);
-        match auto.kind {
-            ast::AutomationKind::Observer => {
-                if !self.is_event_list(&body_ty) && body_ty != Ty::Error && body_ty != Ty::Unit {
-                    let span = auto
-                        .body
-                        .last()
-                        .map(|s| s.span())
-                        .unwrap_or(SimpleSpan::new((), 0..0));
-                    self.error(
-                        span,
-                        format!("observer body must return [Event], found {}", body_ty),
-                    );
+        // The declared return type is pushed down into the body's tail
+        // position (and into every `return`) so that underdetermined
+        // expressions like `[]` infer it instead of defaulting to
+        // `[<error>]`.
+        let declared_return = match auto.kind {
+            ast::AutomationKind::Observer => Ty::List(Box::new(Ty::Named("Event".into()))),
+            ast::AutomationKind::Mutator => Ty::Named("Event".into()),
+        };
+        self.expected_return = Some(declared_return.clone());
+        let mut body: Vec<_> = self.check_body(&auto.body, Some(&declared_return));
+        self.expected_return = None;
+
+        // Validate return type. Resolve first, so a `let mut xs = []` whose
+        // element type was only pinned down by a `Push` later in the body
+        // is compared against the declared return type by its final,
+        // unified form rather than the `Ty::Var` it started as.
+        let raw_body_ty = self.resolve_ty(&self.body_type(&body));
+        let span = auto
+            .body
+            .last()
+            .map(|s| s.span())
+            .unwrap_or(SimpleSpan::new((), 0..0));
+        let had_unresolved_body_ty = self.contains_unresolved_var(&raw_body_ty);
+        let body_ty = self.finalize_ty(&raw_body_ty, span);
+
+        // If the element type was never pinned down (never pushed to, or
+        // pushed to with nothing to constrain it), `finalize_ty` already
+        // reported and defaulted it above - skip the return-type check
+        // below so it doesn't also trip on the now-defaulted `Ty::Error`.
+        if !had_unresolved_body_ty {
+            #[cfg(feature = "trace-checker")]
+            let _span = tracing::debug_span!(
+                "check_return_type",
+                node.span = ?span,
+                node.kind = %auto.kind,
+                result.ty = %body_ty,
+            )
+            .entered();
+            match auto.kind {
+                ast::AutomationKind::Observer => {
+                    if !self.is_event_list(&body_ty) && body_ty != Ty::Error && body_ty != Ty::Unit
+                    {
+                        self.error_with(
+                            TypeError::new(
+                                span,
+                                format!("observer body must return [Event], found {}", body_ty),
+                            )
+                            .with_code("observer-return-type")
+                            .with_secondary(
+                                auto.kind_span,
+                                "expected because this is an `observer`",
+                            ),
+                        );
+                    }
                 }
-            }
-            ast::AutomationKind::Mutator => {
-                if !self.is_event_type(&body_ty) && body_ty != Ty::Error && body_ty != Ty::Unit {
-                    let span = auto
-                        .body
-                        .last()
-                        .map(|s| s.span())
-                        .unwrap_or(SimpleSpan::new((), 0..0));
-                    self.error(
-                        span,
-                        format!("mutator body must return Event, found {}", body_ty),
-                    );
+                ast::AutomationKind::Mutator => {
+                    if !self.is_event_type(&body_ty) && body_ty != Ty::Error && body_ty != Ty::Unit
+                    {
+                        self.error_with(
+                            TypeError::new(
+                                span,
+                                format!("mutator body must return Event, found {}", body_ty),
+                            )
+                            .with_code("mutator-return-type")
+                            .with_secondary(auto.kind_span, "expected because this is a `mutator`"),
+                        );
+                    }
                 }
             }
         }
 
+        // Now that every `Push`/`Insert`/`Add` in the body has had its say,
+        // walk the whole typed tree (not just the tail expression checked
+        // above) applying the final substitution, so a `let mut` collection
+        // read from a branch other than the one returned still shows its
+        // unified element type rather than the `Ty::Var` it was built with.
+        self.finalize_stmts(&mut body);
+        let mut filter = filter;
+        if let Some(filter) = &mut filter {
+            self.finalize_expr(filter);
+        }
+
         self.env.pop_scope();
 
         TypedAutomation {
@@ -387,6 +875,29 @@ impl TypeChecker {
             || matches!(ty, Ty::EnumVariant { enum_name, .. } if enum_name == "Event")
     }
 
+    /// Check a statement list, pushing `expected` down into the tail
+    /// expression (the last statement, if it's a bare `Expr`) so that its
+    /// type can be inferred bidirectionally. Every other statement -
+    /// including `return`s anywhere in the list - is checked normally via
+    /// `check_stmt`.
+    fn check_body(
+        &mut self,
+        stmts: &[lowered::Spanned<lowered::LoweredStmt>],
+        expected: Option<&Ty>,
+    ) -> Vec<TypedStmt> {
+        let mut typed = Vec::with_capacity(stmts.len());
+        for (i, stmt) in stmts.iter().enumerate() {
+            if i + 1 == stmts.len() {
+                if let lowered::LoweredStmt::Expr(expr) = &stmt.node {
+                    typed.push(TypedStmt::Expr(self.check_expr_expected(expr, expected)));
+                    continue;
+                }
+            }
+            typed.push(self.check_stmt(stmt));
+        }
+        typed
+    }
+
     fn body_type(&self, body: &[TypedStmt]) -> Ty {
         if let Some(last) = body.last() {
             match last {
@@ -411,7 +922,8 @@ impl TypeChecker {
         match &pattern.node {
             ast::Pattern::Ident(name) => {
                 // Bind the whole struct as a single variable -- use a generic named type
-                self.env.bind(name.clone(), Ty::Named("Input".into()));
+                self.env
+                    .bind_at(name.clone(), Ty::Named("Input".into()), pattern.span);
             }
             ast::Pattern::Struct { fields, .. } => {
                 for field in fields {
@@ -433,7 +945,7 @@ impl TypeChecker {
                         self.check_pattern(sub_pattern, &sub_fields);
                     } else {
                         // Simple binding: bind field name to its type
-                        self.env.bind(field_name.clone(), field_ty);
+                        self.env.bind_at(field_name.clone(), field_ty, field.span);
                     }
                 }
             }
@@ -456,7 +968,11 @@ impl TypeChecker {
         match &stmt.node {
             lowered::LoweredStmt::Let { name, value } => {
                 let typed_value = self.check_expr(value);
-                self.env.bind(name.clone(), typed_value.ty.clone());
+                self.env.bind_at(
+                    name.clone(),
+                    typed_value.ty.clone(),
+                    typed_value.origin.span(),
+                );
                 TypedStmt::Let {
                     name: name.clone(),
                     value: typed_value,
@@ -465,9 +981,11 @@ impl TypeChecker {
             }
             lowered::LoweredStmt::LetMut { name, value } => {
                 let typed_value = self.check_expr(value);
-                // Mutable list starts as List(Error), refined by Push
+                // Mutable list/map/set starts as e.g. List(Var(n)), its
+                // element type unified in by the first Push/Insert/Add.
                 let ty = typed_value.ty.clone();
-                self.env.bind(name.clone(), ty);
+                self.env
+                    .bind_mut(name.clone(), ty, Some(typed_value.origin.span()));
                 TypedStmt::LetMut {
                     name: name.clone(),
                     value: typed_value,
@@ -476,7 +994,8 @@ impl TypeChecker {
             }
             lowered::LoweredStmt::Expr(expr) => TypedStmt::Expr(self.check_expr(expr)),
             lowered::LoweredStmt::Return(expr) => {
-                let typed = self.check_expr(expr);
+                let expected = self.expected_return.clone();
+                let typed = self.check_expr_expected(expr, expected.as_ref());
                 TypedStmt::Return(typed, stmt.origin.clone())
             }
             lowered::LoweredStmt::For { var, iter, body } => {
@@ -493,7 +1012,7 @@ impl TypeChecker {
                 };
 
                 self.env.push_scope();
-                self.env.bind(var.clone(), elem_ty);
+                self.env.bind_at(var.clone(), elem_ty, iter.span());
                 let typed_body: Vec<_> = body.iter().map(|s| self.check_stmt(s)).collect();
                 self.env.pop_scope();
 
@@ -504,18 +1023,63 @@ impl TypeChecker {
                     origin: stmt.origin.clone(),
                 }
             }
+            lowered::LoweredStmt::While { cond, body } => {
+                let typed_cond = self.check_expr(cond);
+                if typed_cond.ty != Ty::Bool && typed_cond.ty != Ty::Error {
+                    self.error(
+                        cond.span(),
+                        format!("while condition must be Bool, found {}", typed_cond.ty),
+                    );
+                }
+
+                self.env.push_scope();
+                let typed_body: Vec<_> = body.iter().map(|s| self.check_stmt(s)).collect();
+                self.env.pop_scope();
+
+                TypedStmt::While {
+                    cond: typed_cond,
+                    body: typed_body,
+                    origin: stmt.origin.clone(),
+                }
+            }
             lowered::LoweredStmt::Push { list, value } => {
                 let typed_value = self.check_expr(value);
 
-                // Refine the mutable list's element type
-                if let Some(list_ty) = self.env.lookup(list).cloned() {
-                    match &list_ty {
-                        Ty::List(inner) if **inner == Ty::Error => {
-                            // First push: refine from List(Error) to List(value_ty)
-                            self.env
-                                .update(list, Ty::List(Box::new(typed_value.ty.clone())));
+                // A Push target must be a mutable (`LetMut`) local - pushing
+                // onto a plain `Let` binding, or a name that isn't bound at
+                // all, is rejected rather than silently doing nothing.
+                match self
+                    .env
+                    .lookup_binding(list)
+                    .map(|binding| (binding.mutable, binding.ty.clone()))
+                {
+                    Some((true, list_ty)) => {
+                        // Unify the list's element type with every pushed
+                        // value, rather than just refining it once from the
+                        // first push: a mismatched second push is then a
+                        // "conflicting element types" error instead of being
+                        // silently accepted under whatever type happened to
+                        // come first.
+                        if let Ty::List(inner) = &list_ty {
+                            let elem_ty = self.unify_ty(inner, &typed_value.ty, stmt.span());
+                            self.env.update(list, Ty::List(Box::new(elem_ty)));
+                        }
+                    }
+                    Some((false, _)) => {
+                        self.error(
+                            stmt.span(),
+                            format!("cannot push onto '{}': not declared with `let mut`", list),
+                        );
+                    }
+                    None => {
+                        let known_names = self.env.names();
+                        let mut error =
+                            TypeError::new(stmt.span(), format!("undefined variable '{}'", list))
+                                .with_code("undefined-variable");
+                        if let Some(suggestion) = closest_name(list, &known_names) {
+                            error = error.with_suggestion(suggestion);
                         }
-                        _ => {}
+                        self.error_with(error);
                     }
                 }
 
@@ -525,6 +1089,96 @@ impl TypeChecker {
                     origin: stmt.origin.clone(),
                 }
             }
+            lowered::LoweredStmt::CompoundAssign { name, op, value } => {
+                let typed_value = self.check_expr(value);
+                let result_ty =
+                    self.check_compound_assign(name, *op, &typed_value, stmt.span(), value.span());
+
+                TypedStmt::CompoundAssign {
+                    name: name.clone(),
+                    op: *op,
+                    value: typed_value,
+                    result_ty,
+                    origin: stmt.origin.clone(),
+                }
+            }
+        }
+    }
+
+    /// Check a compound assignment's target and operand, unify the binding's
+    /// type in place via `self.env.update` (mirroring `Push`'s
+    /// check-then-update-binding shape above), and return that new type.
+    ///
+    /// `List(T) += List(T)` is a list extend rather than a binary op - there
+    /// is no `List + List` arithmetic for `check_binop` to fall back on, so
+    /// it's special-cased here instead of every other compound operator,
+    /// which reuse `check_binop` directly.
+    fn check_compound_assign(
+        &mut self,
+        name: &str,
+        op: ast::BinOp,
+        typed_value: &TypedExpr,
+        span: SimpleSpan,
+        value_span: SimpleSpan,
+    ) -> Ty {
+        let binding = self
+            .env
+            .lookup_binding(name)
+            .map(|binding| (binding.mutable, binding.ty.clone()));
+
+        match binding {
+            Some((true, Ty::List(inner))) if op == ast::BinOp::Add => {
+                if let Ty::List(value_inner) = &typed_value.ty {
+                    let elem_ty = self.unify_ty(&inner, value_inner, span);
+                    let new_ty = Ty::List(Box::new(elem_ty));
+                    self.env.update(name, new_ty.clone());
+                    new_ty
+                } else {
+                    self.error(
+                        span,
+                        format!(
+                            "cannot extend list '{}' (of {}) with non-list value of {}",
+                            name,
+                            Ty::List(inner.clone()),
+                            typed_value.ty
+                        ),
+                    );
+                    Ty::List(inner)
+                }
+            }
+            Some((true, current_ty)) => {
+                let left_origin = self.env.lookup_origin(name);
+                let right_origin = self.provenance_span(typed_value);
+                let result_ty = self.check_binop(
+                    op,
+                    &current_ty,
+                    &typed_value.ty,
+                    span,
+                    span,
+                    left_origin,
+                    value_span,
+                    right_origin,
+                );
+                self.env.update(name, result_ty.clone());
+                result_ty
+            }
+            Some((false, _)) => {
+                self.error(
+                    span,
+                    format!("cannot assign to '{}': not declared with `let mut`", name),
+                );
+                Ty::Error
+            }
+            None => {
+                let known_names = self.env.names();
+                let mut error = TypeError::new(span, format!("undefined variable '{}'", name))
+                    .with_code("undefined-variable");
+                if let Some(suggestion) = closest_name(name, &known_names) {
+                    error = error.with_suggestion(suggestion);
+                }
+                self.error_with(error);
+                Ty::Error
+            }
         }
     }
 
@@ -532,7 +1186,36 @@ impl TypeChecker {
     // Expression checking
     // =========================================================================
 
+    /// Check one expression, recording its span, node kind, and resolved
+    /// type as a `tracing` span when built with the `trace-checker` feature
+    /// - otherwise a transparent call straight through to
+    /// [`TypeChecker::check_expr_uninstrumented`]. Kept as a thin wrapper
+    /// rather than instrumenting the body directly so normal builds don't
+    /// pay for the span setup/teardown or for `lowered_expr_kind`'s match.
     fn check_expr(&mut self, expr: &lowered::Spanned<lowered::LoweredExpr>) -> TypedExpr {
+        #[cfg(feature = "trace-checker")]
+        {
+            let span = tracing::debug_span!(
+                "check_expr",
+                node.span = ?expr.span(),
+                node.kind = lowered_expr_kind(&expr.node),
+                result.ty = tracing::field::Empty,
+            );
+            let _entered = span.enter();
+            let typed = self.check_expr_uninstrumented(expr);
+            span.record("result.ty", tracing::field::display(&typed.ty));
+            typed
+        }
+        #[cfg(not(feature = "trace-checker"))]
+        {
+            self.check_expr_uninstrumented(expr)
+        }
+    }
+
+    fn check_expr_uninstrumented(
+        &mut self,
+        expr: &lowered::Spanned<lowered::LoweredExpr>,
+    ) -> TypedExpr {
         let origin = expr.origin.clone();
         let span = expr.span();
 
@@ -592,7 +1275,13 @@ impl TypeChecker {
                 let ty = if let Some(ty) = self.env.lookup(name) {
                     ty.clone()
                 } else {
-                    self.error(span, format!("undefined variable '{}'", name));
+                    let known_names = self.env.names();
+                    let mut error = TypeError::new(span, format!("undefined variable '{}'", name))
+                        .with_code("undefined-variable");
+                    if let Some(suggestion) = closest_name(name, &known_names) {
+                        error = error.with_suggestion(suggestion);
+                    }
+                    self.error_with(error);
                     Ty::Error
                 };
                 TypedExpr {
@@ -609,7 +1298,18 @@ impl TypeChecker {
             lowered::LoweredExpr::BinOp { op, left, right } => {
                 let typed_left = self.check_expr(left);
                 let typed_right = self.check_expr(right);
-                let ty = self.check_binop(*op, &typed_left.ty, &typed_right.ty, span);
+                let left_origin = self.provenance_span(&typed_left);
+                let right_origin = self.provenance_span(&typed_right);
+                let ty = self.check_binop(
+                    *op,
+                    &typed_left.ty,
+                    &typed_right.ty,
+                    span,
+                    left.span(),
+                    left_origin,
+                    right.span(),
+                    right_origin,
+                );
                 TypedExpr {
                     kind: TypedExprKind::BinOp {
                         op: *op,
@@ -624,7 +1324,9 @@ impl TypeChecker {
             // Unary operations
             lowered::LoweredExpr::UnaryOp { op, expr: inner } => {
                 let typed_inner = self.check_expr(inner);
-                let ty = self.check_unaryop(*op, &typed_inner.ty, span);
+                let operand_origin = self.provenance_span(&typed_inner);
+                let ty =
+                    self.check_unaryop(*op, &typed_inner.ty, span, inner.span(), operand_origin);
                 TypedExpr {
                     kind: TypedExprKind::UnaryOp {
                         op: *op,
@@ -638,7 +1340,7 @@ impl TypeChecker {
             // Field access
             lowered::LoweredExpr::Field { expr: inner, field } => {
                 let typed_inner = self.check_expr(inner);
-                let ty = self.check_field_access(&typed_inner.ty, field, span);
+                let ty = self.check_field_access(&typed_inner.ty, field, span, inner.span());
                 TypedExpr {
                     kind: TypedExprKind::Field {
                         expr: Box::new(typed_inner),
@@ -656,7 +1358,7 @@ impl TypeChecker {
                     Ty::Option(inner) => *inner.clone(),
                     other => other.clone(),
                 };
-                let field_ty = self.check_field_access(&inner_ty, field, span);
+                let field_ty = self.check_field_access(&inner_ty, field, span, inner.span());
                 let ty = Ty::Option(Box::new(field_ty));
                 TypedExpr {
                     kind: TypedExprKind::OptionalField {
@@ -671,7 +1373,142 @@ impl TypeChecker {
             // Function calls
             lowered::LoweredExpr::Call { func, args } => self.check_call(func, args, span, origin),
 
-            // If expressions
+            // If expressions, list literals and blocks all participate in
+            // bidirectional checking (see `check_expr_expected`); with no
+            // expected type pushed down they behave exactly as before.
+            lowered::LoweredExpr::If { .. }
+            | lowered::LoweredExpr::List(_)
+            | lowered::LoweredExpr::Block { .. } => self.check_expr_expected(expr, None),
+
+            // Struct literals
+            lowered::LoweredExpr::StructLit { name, fields } => {
+                self.check_struct_lit(name, fields, span, origin)
+            }
+
+            // Mutable list (empty). Its element type starts out as a fresh
+            // type variable rather than `Ty::Error`, so the first `Push`
+            // pins it down via unification and a later mismatched `Push`
+            // is a real "conflicting element types" error rather than
+            // being silently accepted.
+            //
+            // There's deliberately no `LoweredExpr::ListComp` arm here: per
+            // `desugar`'s module doc, list/dict/set comprehensions are
+            // always expanded into this `Block`/`MutableList`/`For`/`Push`
+            // shape before the lowered tree reaches the checker, so a
+            // comprehension's element and iterable types are already fully
+            // checked by the arms above (`Block`, `For` isn't a
+            // `LoweredExpr` at all, and `Call`/`Path` cover `f(x)` and
+            // `keys(xs)`) - a second, undesugared `ListComp` node here would
+            // just be an alternate representation of the same thing that
+            // `desugar` never actually produces.
+            lowered::LoweredExpr::MutableList => {
+                let elem = self.fresh_ty_var();
+                TypedExpr {
+                    kind: TypedExprKind::MutableList,
+                    ty: Ty::List(Box::new(elem)),
+                    origin,
+                }
+            }
+
+            // Match expressions
+            lowered::LoweredExpr::Match { scrutinee, arms } => {
+                self.check_match(scrutinee, arms, span, origin)
+            }
+
+            // Lambda expressions: `|params| body`. Each parameter gets a
+            // fresh type variable, bound in its own scope - the call site
+            // (`filter`/`map`/`fold` in `resolve_builtin_call`) unifies
+            // those variables with the concrete argument types it expects.
+            lowered::LoweredExpr::Lambda { params, body } => {
+                self.env.push_scope();
+                let param_tys: Vec<Ty> = params.iter().map(|_| self.fresh_ty_var()).collect();
+                for (name, ty) in params.iter().zip(param_tys.iter()) {
+                    self.env.bind(name.clone(), ty.clone());
+                }
+                let typed_body = self.check_expr(body);
+                self.env.pop_scope();
+
+                let ret = Box::new(typed_body.ty.clone());
+                TypedExpr {
+                    kind: TypedExprKind::Lambda {
+                        params: params.clone(),
+                        body: Box::new(typed_body),
+                    },
+                    ty: Ty::Fn {
+                        params: param_tys,
+                        ret,
+                    },
+                    origin,
+                }
+            }
+
+            // Tuple literal: `(a, b, c)`. Unlike `List`, there's no shared
+            // element type to reconcile across entries, so this doesn't
+            // need `check_expr_expected`'s bidirectional handling - each
+            // element just checks independently and the tuple's type is
+            // their types in order.
+            lowered::LoweredExpr::Tuple(items) => {
+                let typed_items: Vec<_> = items.iter().map(|e| self.check_expr(e)).collect();
+                let ty = Ty::Tuple(typed_items.iter().map(|e| e.ty.clone()).collect());
+                TypedExpr {
+                    kind: TypedExprKind::Tuple(typed_items),
+                    ty,
+                    origin,
+                }
+            }
+        }
+    }
+
+    /// Check an expression with an expected type pushed down from the
+    /// enclosing context (the body's tail position, a `return`, or a
+    /// branch of an `if`). This is the bidirectional half of the checker:
+    /// `if`/`else` arms, blocks (their result expression) and list
+    /// literals consult `expected` to resolve otherwise-underdetermined
+    /// types (`[]` typing as `expected` instead of `[<error>]`); every
+    /// other expression just delegates to `check_expr` and, if it came
+    /// back underdetermined, adopts `expected` wholesale. A genuine
+    /// mismatch (e.g. a concretely-typed `[Int]` where `[Event]` is
+    /// expected) is never silently coerced, so `check_automation`'s
+    /// return-type validation still reports it.
+    fn check_expr_expected(
+        &mut self,
+        expr: &lowered::Spanned<lowered::LoweredExpr>,
+        expected: Option<&Ty>,
+    ) -> TypedExpr {
+        let origin = expr.origin.clone();
+
+        match &expr.node {
+            lowered::LoweredExpr::List(items) => {
+                let elem_expected = match expected {
+                    Some(Ty::List(inner)) => Some(inner.as_ref().clone()),
+                    _ => None,
+                };
+
+                if items.is_empty() {
+                    return TypedExpr {
+                        kind: TypedExprKind::List(vec![]),
+                        ty: Ty::List(Box::new(elem_expected.unwrap_or(Ty::Error))),
+                        origin,
+                    };
+                }
+
+                let typed_items: Vec<_> = items
+                    .iter()
+                    .map(|e| self.check_expr_expected(e, elem_expected.as_ref()))
+                    .collect();
+                let elem_ty = typed_items
+                    .iter()
+                    .map(|e| &e.ty)
+                    .find(|t| **t != Ty::Error)
+                    .cloned()
+                    .unwrap_or_else(|| elem_expected.unwrap_or(Ty::Error));
+                TypedExpr {
+                    kind: TypedExprKind::List(typed_items),
+                    ty: Ty::List(Box::new(elem_ty)),
+                    origin,
+                }
+            }
+
             lowered::LoweredExpr::If {
                 cond,
                 then_block,
@@ -686,12 +1523,12 @@ impl TypeChecker {
                 }
 
                 self.env.push_scope();
-                let typed_then: Vec<_> = then_block.iter().map(|s| self.check_stmt(s)).collect();
+                let typed_then = self.check_body(then_block, expected);
                 self.env.pop_scope();
 
                 let typed_else = else_block.as_ref().map(|stmts| {
                     self.env.push_scope();
-                    let typed: Vec<_> = stmts.iter().map(|s| self.check_stmt(s)).collect();
+                    let typed = self.check_body(stmts, expected);
                     self.env.pop_scope();
                     typed
                 });
@@ -699,7 +1536,9 @@ impl TypeChecker {
                 let then_ty = self.body_type(&typed_then);
                 let ty = if let Some(ref else_stmts) = typed_else {
                     let else_ty = self.body_type(else_stmts);
-                    self.unify(&then_ty, &else_ty)
+                    let then_span = block_result_span(then_block, span);
+                    let else_span = block_result_span(else_block.as_deref().unwrap_or(&[]), span);
+                    self.unify(&then_ty, then_span, &else_ty, else_span)
                 } else {
                     Ty::Unit
                 };
@@ -715,40 +1554,10 @@ impl TypeChecker {
                 }
             }
 
-            // List literals
-            lowered::LoweredExpr::List(items) => {
-                if items.is_empty() {
-                    TypedExpr {
-                        kind: TypedExprKind::List(vec![]),
-                        ty: Ty::List(Box::new(Ty::Error)),
-                        origin,
-                    }
-                } else {
-                    let typed_items: Vec<_> = items.iter().map(|e| self.check_expr(e)).collect();
-                    let elem_ty = typed_items
-                        .iter()
-                        .map(|e| &e.ty)
-                        .find(|t| **t != Ty::Error)
-                        .cloned()
-                        .unwrap_or(Ty::Error);
-                    TypedExpr {
-                        kind: TypedExprKind::List(typed_items),
-                        ty: Ty::List(Box::new(elem_ty)),
-                        origin,
-                    }
-                }
-            }
-
-            // Struct literals
-            lowered::LoweredExpr::StructLit { name, fields } => {
-                self.check_struct_lit(name, fields, span, origin)
-            }
-
-            // Block expressions
             lowered::LoweredExpr::Block { stmts, result } => {
                 self.env.push_scope();
                 let typed_stmts: Vec<_> = stmts.iter().map(|s| self.check_stmt(s)).collect();
-                let typed_result = self.check_expr(result);
+                let typed_result = self.check_expr_expected(result, expected);
                 let ty = typed_result.ty.clone();
                 self.env.pop_scope();
 
@@ -762,12 +1571,27 @@ impl TypeChecker {
                 }
             }
 
-            // Mutable list (empty)
-            lowered::LoweredExpr::MutableList => TypedExpr {
-                kind: TypedExprKind::MutableList,
-                ty: Ty::List(Box::new(Ty::Error)),
-                origin,
-            },
+            _ => {
+                let mut typed = self.check_expr(expr);
+                // A bare `Ty::Error` means the expression itself was invalid
+                // (undefined variable, unknown function, ...) and should
+                // keep reporting as `<error>` rather than silently becoming
+                // `expected`.
+                if let Some(exp @ Ty::List(_)) = expected {
+                    if matches!(&typed.ty, Ty::List(inner) if **inner == Ty::Error) {
+                        typed.ty = exp.clone();
+                    }
+                }
+                // An `Int` returned where `Float` is expected (e.g. `return
+                // 0` in a body declared to produce `Float`) widens, the same
+                // implicit coercion `check_binop` applies to mixed operands.
+                if let Some(exp) = expected {
+                    if let Some(widened) = self.coerce(&typed.ty, exp) {
+                        typed.ty = widened;
+                    }
+                }
+                typed
+            }
         }
     }
 
@@ -775,35 +1599,201 @@ impl TypeChecker {
     // Binary / Unary operators
     // =========================================================================
 
-    fn check_binop(&mut self, op: ast::BinOp, left: &Ty, right: &Ty, span: SimpleSpan) -> Ty {
-        // Error propagation
-        if *left == Ty::Error || *right == Ty::Error {
-            return Ty::Error;
+    /// If `expr` resolved its type from a variable binding, the span where
+    /// that binding's type was first established (a `Let`'s initializer, a
+    /// parameter's declaration, ...) - see `Binding`'s `origin` field.
+    /// `None` for anything that isn't a bare variable reference; a
+    /// literal's own span already *is* its origin, so there's nothing
+    /// further upstream to point at.
+    fn provenance_span(&self, expr: &TypedExpr) -> Option<SimpleSpan> {
+        match &expr.kind {
+            TypedExprKind::Ident(name) => self.env.lookup_origin(name),
+            _ => None,
         }
+    }
 
-        match op {
-            // Arithmetic
+    /// Report a binary-operator type error with both operands labeled at
+    /// their own spans ("this is `Duration`" / "this is `Angle`"), rather
+    /// than one message pointing at the whole expression - a caller reading
+    /// the diagnostic can see which side is which without re-deriving it
+    /// from the source. When an operand's type came from a variable binding
+    /// rather than an inline literal, `left_origin`/`right_origin` add a
+    /// further label at that binding's own span ("this is String because it
+    /// was bound here"), so a type error involving a variable several lines
+    /// removed from its `let` doesn't leave the reader to go find it.
+    #[allow(clippy::too_many_arguments)]
+    fn binop_error(
+        &mut self,
+        span: SimpleSpan,
+        code: &'static str,
+        message: String,
+        left_span: SimpleSpan,
+        left: &Ty,
+        left_origin: Option<SimpleSpan>,
+        right_span: SimpleSpan,
+        right: &Ty,
+        right_origin: Option<SimpleSpan>,
+    ) -> Ty {
+        let mut error = TypeError::new(span, message)
+            .with_code(code)
+            .with_secondary(left_span, format!("this is {}", left))
+            .with_secondary(right_span, format!("this is {}", right));
+
+        if let Some(origin) = left_origin {
+            error = error.with_secondary(
+                origin,
+                format!("this is {} because it was bound here", left),
+            );
+        }
+        if let Some(origin) = right_origin {
+            error = error.with_secondary(
+                origin,
+                format!("this is {} because it was bound here", right),
+            );
+        }
+
+        // "mismatched-dimensions" is the one code here where there's
+        // something more to say than the message itself - the two operands
+        // aren't just different types, they're different *dimensions*
+        // (e.g. a `Duration` and an `Angle`), so spell out that they need
+        // an explicit conversion rather than leaving the reader to guess
+        // why `+` won't just coerce one side like `Int + Float` does.
+        if code == "mismatched-dimensions" {
+            error = error.with_note(
+                "these are different physical dimensions; convert one side to the other's unit first",
+            );
+        }
+
+        self.error_with(error);
+        Ty::Error
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn check_binop(
+        &mut self,
+        op: ast::BinOp,
+        left: &Ty,
+        right: &Ty,
+        span: SimpleSpan,
+        left_span: SimpleSpan,
+        left_origin: Option<SimpleSpan>,
+        right_span: SimpleSpan,
+        right_origin: Option<SimpleSpan>,
+    ) -> Ty {
+        // Error propagation
+        if *left == Ty::Error || *right == Ty::Error {
+            return Ty::Error;
+        }
+
+        match op {
+            // Arithmetic
             ast::BinOp::Add
             | ast::BinOp::Sub
             | ast::BinOp::Mul
             | ast::BinOp::Div
             | ast::BinOp::Mod => {
+                let left_dim = self.physical_dimension(left);
+                let right_dim = self.physical_dimension(right);
+
                 if self.is_numeric(left) && self.is_numeric(right) {
-                    // Float contaminates
-                    if *left == Ty::Float || *right == Ty::Float {
-                        Ty::Float
+                    // Int coerces to Float, whichever side it's on.
+                    self.coerce(left, right)
+                        .or_else(|| self.coerce(right, left))
+                        .unwrap_or_else(|| left.clone())
+                } else if let (Some(ld), Some(rd)) = (left_dim, right_dim) {
+                    // Both operands carry the same kind of unit, e.g. `5min + 2h`.
+                    if ld != rd {
+                        self.binop_error(
+                            span,
+                            "mismatched-dimensions",
+                            format!(
+                                "arithmetic operator '{}' requires operands of the same dimension, found {} ({}) and {} ({})",
+                                op, left, ld, right, rd
+                            ),
+                            left_span,
+                            left,
+                            left_origin,
+                            right_span,
+                            right,
+                            right_origin,
+                        )
                     } else {
-                        Ty::Int
+                        match op {
+                            ast::BinOp::Add | ast::BinOp::Sub => left.clone(),
+                            // A ratio of two same-dimension quantities is a
+                            // plain number, e.g. `10min / 2min == 5`.
+                            ast::BinOp::Div => Ty::Float,
+                            _ => self.binop_error(
+                                span,
+                                "unsupported-dimension-op",
+                                format!(
+                                    "arithmetic operator '{}' is not supported between {} values, only '+', '-', and '/' are",
+                                    op, left
+                                ),
+                                left_span,
+                                left,
+                                left_origin,
+                                right_span,
+                                right,
+                                right_origin,
+                            ),
+                        }
+                    }
+                } else if left_dim.is_some() && self.is_numeric(right) {
+                    // Scaling a unit-bearing quantity by a plain number,
+                    // e.g. `5min * 2` or `5min / 2`, stays that quantity.
+                    match op {
+                        ast::BinOp::Mul | ast::BinOp::Div => left.clone(),
+                        _ => self.binop_error(
+                            span,
+                            "mismatched-dimensions",
+                            format!(
+                                "arithmetic operator '{}' requires operands of the same dimension, found {} and {}",
+                                op, left, right
+                            ),
+                            left_span,
+                            left,
+                            left_origin,
+                            right_span,
+                            right,
+                            right_origin,
+                        ),
+                    }
+                } else if right_dim.is_some() && self.is_numeric(left) {
+                    // `2 * 5min` (but not `2 / 5min`, which isn't a quantity
+                    // of either operand's dimension).
+                    match op {
+                        ast::BinOp::Mul => right.clone(),
+                        _ => self.binop_error(
+                            span,
+                            "mismatched-dimensions",
+                            format!(
+                                "arithmetic operator '{}' requires operands of the same dimension, found {} and {}",
+                                op, left, right
+                            ),
+                            left_span,
+                            left,
+                            left_origin,
+                            right_span,
+                            right,
+                            right_origin,
+                        ),
                     }
                 } else {
-                    self.error(
+                    self.binop_error(
                         span,
+                        "non-numeric-operands",
                         format!(
                             "arithmetic operator '{}' requires numeric operands, found {} and {}",
                             op, left, right
                         ),
-                    );
-                    Ty::Error
+                        left_span,
+                        left,
+                        left_origin,
+                        right_span,
+                        right,
+                        right_origin,
+                    )
                 }
             }
 
@@ -811,15 +1801,42 @@ impl TypeChecker {
             ast::BinOp::Lt | ast::BinOp::Le | ast::BinOp::Gt | ast::BinOp::Ge => {
                 if self.is_numeric(left) && self.is_numeric(right) {
                     Ty::Bool
+                } else if let (Some(ld), Some(rd)) =
+                    (self.physical_dimension(left), self.physical_dimension(right))
+                {
+                    if ld == rd {
+                        Ty::Bool
+                    } else {
+                        self.binop_error(
+                            span,
+                            "mismatched-dimensions",
+                            format!(
+                                "comparison operator '{}' requires operands of the same dimension, found {} ({}) and {} ({})",
+                                op, left, ld, right, rd
+                            ),
+                            left_span,
+                            left,
+                            left_origin,
+                            right_span,
+                            right,
+                            right_origin,
+                        )
+                    }
                 } else {
-                    self.error(
+                    self.binop_error(
                         span,
+                        "non-numeric-operands",
                         format!(
                             "comparison operator '{}' requires numeric operands, found {} and {}",
                             op, left, right
                         ),
-                    );
-                    Ty::Error
+                        left_span,
+                        left,
+                        left_origin,
+                        right_span,
+                        right,
+                        right_origin,
+                    )
                 }
             }
 
@@ -830,10 +1847,19 @@ impl TypeChecker {
             ast::BinOp::In => match right {
                 Ty::List(_) | Ty::Set(_) | Ty::Map { .. } => Ty::Bool,
                 _ => {
-                    self.error(
+                    let mut error = TypeError::new(
                         span,
                         format!("'in' requires collection on right side, found {}", right),
-                    );
+                    )
+                    .with_code("non-collection-operand")
+                    .with_secondary(right_span, format!("this is {}", right));
+                    if let Some(origin) = right_origin {
+                        error = error.with_secondary(
+                            origin,
+                            format!("this is {} because it was bound here", right),
+                        );
+                    }
+                    self.error_with(error);
                     Ty::Error
                 }
             },
@@ -843,20 +1869,33 @@ impl TypeChecker {
                 if *left == Ty::Bool && *right == Ty::Bool {
                     Ty::Bool
                 } else {
-                    self.error(
+                    self.binop_error(
                         span,
+                        "non-bool-operands",
                         format!(
                             "logical operator '{}' requires Bool operands, found {} and {}",
                             op, left, right
                         ),
-                    );
-                    Ty::Error
+                        left_span,
+                        left,
+                        left_origin,
+                        right_span,
+                        right,
+                        right_origin,
+                    )
                 }
             }
         }
     }
 
-    fn check_unaryop(&mut self, op: ast::UnaryOp, operand: &Ty, span: SimpleSpan) -> Ty {
+    fn check_unaryop(
+        &mut self,
+        op: ast::UnaryOp,
+        operand: &Ty,
+        span: SimpleSpan,
+        operand_span: SimpleSpan,
+        operand_origin: Option<SimpleSpan>,
+    ) -> Ty {
         if *operand == Ty::Error {
             return Ty::Error;
         }
@@ -866,10 +1905,19 @@ impl TypeChecker {
                 if self.is_numeric(operand) {
                     operand.clone()
                 } else {
-                    self.error(
+                    let mut error = TypeError::new(
                         span,
                         format!("negation requires numeric type, found {}", operand),
-                    );
+                    )
+                    .with_code("non-numeric-operand")
+                    .with_secondary(operand_span, format!("this is {}", operand));
+                    if let Some(origin) = operand_origin {
+                        error = error.with_secondary(
+                            origin,
+                            format!("this is {} because it was bound here", operand),
+                        );
+                    }
+                    self.error_with(error);
                     Ty::Error
                 }
             }
@@ -877,20 +1925,38 @@ impl TypeChecker {
                 if *operand == Ty::Bool {
                     Ty::Bool
                 } else {
-                    self.error(
+                    let mut error = TypeError::new(
                         span,
                         format!("logical not requires Bool, found {}", operand),
-                    );
+                    )
+                    .with_code("non-bool-operand")
+                    .with_secondary(operand_span, format!("this is {}", operand));
+                    if let Some(origin) = operand_origin {
+                        error = error.with_secondary(
+                            origin,
+                            format!("this is {} because it was bound here", operand),
+                        );
+                    }
+                    self.error_with(error);
                     Ty::Error
                 }
             }
             ast::UnaryOp::Await => match operand {
                 Ty::Future(inner) => *inner.clone(),
                 _ => {
-                    self.error(
+                    let mut error = TypeError::new(
                         span,
                         format!("await requires Future type, found {}", operand),
-                    );
+                    )
+                    .with_code("non-future-operand")
+                    .with_secondary(operand_span, format!("this is {}", operand));
+                    if let Some(origin) = operand_origin {
+                        error = error.with_secondary(
+                            origin,
+                            format!("this is {} because it was bound here", operand),
+                        );
+                    }
+                    self.error_with(error);
                     Ty::Error
                 }
             },
@@ -903,11 +1969,79 @@ impl TypeChecker {
         matches!(ty, Ty::Int | Ty::Float)
     }
 
+    /// Widen `from` to `to` if the language allows it implicitly, returning
+    /// the resulting type. The only implicit widening today is `Int` ->
+    /// `Float`; everything else (including same-type "coercion") is handled
+    /// by ordinary type equality. Shared by `check_binop`'s numeric operand
+    /// handling and `check_expr_expected`'s bidirectional coercion so the
+    /// rule lives in exactly one place.
+    fn coerce(&self, from: &Ty, to: &Ty) -> Option<Ty> {
+        if from == to {
+            return Some(to.clone());
+        }
+        if *from == Ty::Int && *to == Ty::Float {
+            return Some(Ty::Float);
+        }
+        None
+    }
+
+    /// The physical dimension a unit-bearing type belongs to, or `None` for
+    /// types with no associated dimension (e.g. `Int`, `String`).
+    fn physical_dimension(&self, ty: &Ty) -> Option<super::repr::Dimension> {
+        match ty {
+            Ty::Duration => Some(super::repr::Dimension::Time),
+            Ty::Angle => Some(super::repr::Dimension::Angle),
+            Ty::Temperature => Some(super::repr::Dimension::Temperature),
+            _ => None,
+        }
+    }
+
     // =========================================================================
     // Field access
     // =========================================================================
 
-    fn check_field_access(&mut self, ty: &Ty, field: &str, span: SimpleSpan) -> Ty {
+    /// Resolve a field on `ty`, e.g. `state.lights`. There's no assignment
+    /// syntax in the language - `Field`/`OptionalField` only ever appear in
+    /// read position - so registry-backed struct types like `State` and
+    /// `LightState` are read-only for free; this just resolves the field's
+    /// declared type.
+    /// Resolve a field access's type, tracing the field name and resolved
+    /// type under `trace-checker` - see [`TypeChecker::check_expr`]'s
+    /// doc comment for why this is a thin wrapper rather than an
+    /// instrumented body.
+    fn check_field_access(
+        &mut self,
+        ty: &Ty,
+        field: &str,
+        span: SimpleSpan,
+        object_span: SimpleSpan,
+    ) -> Ty {
+        #[cfg(feature = "trace-checker")]
+        {
+            let trace_span = tracing::debug_span!(
+                "check_field_access",
+                node.span = ?span,
+                node.kind = field,
+                result.ty = tracing::field::Empty,
+            );
+            let _entered = trace_span.enter();
+            let result_ty = self.check_field_access_uninstrumented(ty, field, span, object_span);
+            trace_span.record("result.ty", tracing::field::display(&result_ty));
+            result_ty
+        }
+        #[cfg(not(feature = "trace-checker"))]
+        {
+            self.check_field_access_uninstrumented(ty, field, span, object_span)
+        }
+    }
+
+    fn check_field_access_uninstrumented(
+        &mut self,
+        ty: &Ty,
+        field: &str,
+        span: SimpleSpan,
+        object_span: SimpleSpan,
+    ) -> Ty {
         if *ty == Ty::Error {
             return Ty::Error;
         }
@@ -917,6 +2051,35 @@ impl TypeChecker {
             return Ty::Error;
         }
 
+        // Constant-index tuple projection: `pair.0`, `pair.1`, ... The field
+        // string only reaches here as a tuple index when it parses cleanly
+        // as a `usize` - anything else (a non-digit field name on a tuple)
+        // falls through to the "no field" error below, same as any other
+        // type with no matching named field.
+        if let Ty::Tuple(elems) = ty {
+            if let Ok(index) = field.parse::<usize>() {
+                return match elems.get(index) {
+                    Some(elem_ty) => elem_ty.clone(),
+                    None => {
+                        self.error_with(
+                            TypeError::new(
+                                span,
+                                format!(
+                                    "tuple index {} out of bounds for {} (has {} element(s))",
+                                    index,
+                                    ty,
+                                    elems.len()
+                                ),
+                            )
+                            .with_code("tuple-index-out-of-bounds")
+                            .with_secondary(object_span, format!("this is of type {}", ty)),
+                        );
+                        Ty::Error
+                    }
+                };
+            }
+        }
+
         // Check entity registry
         if self.registry.is_entity_registry(ty) {
             if let Some(domain) = self.registry.entity_registry_domain(ty) {
@@ -931,7 +2094,14 @@ impl TypeChecker {
         if let Some(field_ty) = self.registry.lookup_field(ty, field) {
             field_ty
         } else {
-            self.error(span, format!("no field '{}' on type {}", field, ty));
+            let known_fields = self.registry.field_names(ty);
+            let mut error = TypeError::new(span, format!("no field '{}' on type {}", field, ty))
+                .with_code("unknown-field")
+                .with_secondary(object_span, format!("this is of type {}", ty));
+            if let Some(suggestion) = closest_name(field, &known_fields) {
+                error = error.with_suggestion(suggestion);
+            }
+            self.error_with(error);
             Ty::Error
         }
     }
@@ -940,11 +2110,40 @@ impl TypeChecker {
     // Path resolution
     // =========================================================================
 
+    /// Resolve a path expression (e.g. an enum variant `Event::LightOn`),
+    /// tracing the path and resolved type under `trace-checker` - see
+    /// [`TypeChecker::check_expr`]'s doc comment for why this is a thin
+    /// wrapper rather than an instrumented body.
     fn check_path(
         &mut self,
         segments: &[String],
         span: SimpleSpan,
         origin: lowered::Origin,
+    ) -> TypedExpr {
+        #[cfg(feature = "trace-checker")]
+        {
+            let trace_span = tracing::debug_span!(
+                "check_path",
+                node.span = ?span,
+                node.kind = segments.join("::"),
+                result.ty = tracing::field::Empty,
+            );
+            let _entered = trace_span.enter();
+            let typed = self.check_path_uninstrumented(segments, span, origin);
+            trace_span.record("result.ty", tracing::field::display(&typed.ty));
+            typed
+        }
+        #[cfg(not(feature = "trace-checker"))]
+        {
+            self.check_path_uninstrumented(segments, span, origin)
+        }
+    }
+
+    fn check_path_uninstrumented(
+        &mut self,
+        segments: &[String],
+        span: SimpleSpan,
+        origin: lowered::Origin,
     ) -> TypedExpr {
         if segments.len() == 2 {
             let enum_name = &segments[0];
@@ -965,10 +2164,19 @@ impl TypeChecker {
                         origin,
                     };
                 } else {
-                    self.error(
+                    let known_variants = self
+                        .registry
+                        .enum_variant_names(enum_name)
+                        .unwrap_or_default();
+                    let mut error = TypeError::new(
                         span,
                         format!("unknown variant '{}' on enum '{}'", variant_name, enum_name),
-                    );
+                    )
+                    .with_code("unknown-variant");
+                    if let Some(suggestion) = closest_name(variant_name, &known_variants) {
+                        error = error.with_suggestion(suggestion);
+                    }
+                    self.error_with(error);
                 }
             } else {
                 self.error(span, format!("unknown type '{}'", enum_name));
@@ -1003,10 +2211,10 @@ impl TypeChecker {
             if segments.len() == 2 {
                 let enum_name = &segments[0];
                 let variant_name = &segments[1];
-                if let Some(_variant_fields) = self
+                if self
                     .registry
                     .resolve_enum_variant(enum_name, variant_name)
-                    .cloned()
+                    .is_some()
                 {
                     let typed_func = self.check_path(segments, func.span(), func.origin.clone());
                     let typed_args = self.check_args(args);
@@ -1054,7 +2262,11 @@ impl TypeChecker {
                 };
             }
 
-            // Not a builtin - check if it's a variable that's callable
+            // Not a builtin - check if it's a variable that's callable. A
+            // `Ty::Fn` checks arity and unifies each argument against the
+            // corresponding parameter type, same as a builtin would; an
+            // undefined name or a non-function value still poisons the
+            // result to `Ty::Error` as before.
             let func_ty = self.env.lookup(name).cloned();
             let typed_func = TypedExpr {
                 kind: TypedExprKind::Ident(name.clone()),
@@ -1062,16 +2274,52 @@ impl TypeChecker {
                 origin: func.origin.clone(),
             };
 
-            if func_ty.is_none() {
-                self.error(span, format!("undefined function '{}'", name));
-            }
+            let ty = match &func_ty {
+                Some(Ty::Fn { params, ret }) => {
+                    if params.len() != arg_types.len() {
+                        self.error(
+                            span,
+                            format!(
+                                "'{}' expects {} argument(s), found {}",
+                                name,
+                                params.len(),
+                                arg_types.len()
+                            ),
+                        );
+                        Ty::Error
+                    } else {
+                        for (param_ty, arg_ty) in params.iter().zip(arg_types.iter()) {
+                            self.unify_ty(param_ty, arg_ty, span);
+                        }
+                        *ret.clone()
+                    }
+                }
+                Some(Ty::Error) => Ty::Error,
+                Some(other) => {
+                    self.error(span, format!("'{}' is not callable, found {}", name, other));
+                    Ty::Error
+                }
+                None => {
+                    let known_functions: Vec<String> = BUILTIN_FUNCTION_NAMES
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect();
+                    let mut error = TypeError::new(span, format!("undefined function '{}'", name))
+                        .with_code("undefined-function");
+                    if let Some(suggestion) = closest_name(name, &known_functions) {
+                        error = error.with_suggestion(suggestion);
+                    }
+                    self.error_with(error);
+                    Ty::Error
+                }
+            };
 
             return TypedExpr {
                 kind: TypedExprKind::Call {
                     func: Box::new(typed_func),
                     args: typed_args,
                 },
-                ty: Ty::Error,
+                ty,
                 origin,
             };
         }
@@ -1106,159 +2354,135 @@ impl TypeChecker {
 
     /// Resolve a call to a built-in function. Returns `Some(return_type)` if
     /// the name is a known builtin, `None` otherwise.
+    ///
+    /// Looks `name` up in [`builtin_signature`]'s table and, if found,
+    /// instantiates and checks it via [`TypeChecker::check_builtin_signature`]
+    /// - the one exception is `wait`, a `sleep` variant whose named-argument
+    /// calling convention (`wait(5 minutes, retry = cancel)`) doesn't fit the
+    /// table's positional-arity model, so it's special-cased here exactly as
+    /// before.
     fn resolve_builtin_call(
         &mut self,
         name: &str,
         arg_types: &[Ty],
         span: SimpleSpan,
     ) -> Option<Ty> {
-        match name {
-            "sleep" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "sleep() takes exactly 1 argument".into());
-                } else if arg_types[0] != Ty::Duration && arg_types[0] != Ty::Error {
-                    self.error(
-                        span,
-                        format!("sleep() requires Duration, found {}", arg_types[0]),
-                    );
-                }
-                Some(Ty::Future(Box::new(Ty::Unit)))
-            }
-            "sleep_unique" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "sleep_unique() takes exactly 1 argument".into());
-                } else if arg_types[0] != Ty::Duration && arg_types[0] != Ty::Error {
-                    self.error(
-                        span,
-                        format!("sleep_unique() requires Duration, found {}", arg_types[0]),
-                    );
-                }
-                Some(Ty::Future(Box::new(Ty::Bool)))
-            }
-            "keys" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "keys() takes exactly 1 argument".into());
-                    return Some(Ty::Error);
-                }
-                match &arg_types[0] {
-                    Ty::Map { key, .. } => Some(Ty::List(key.clone())),
-                    Ty::Error => Some(Ty::List(Box::new(Ty::Error))),
-                    other => {
-                        self.error(span, format!("keys() requires Map, found {}", other));
-                        Some(Ty::Error)
-                    }
-                }
+        if name == "wait" {
+            return Some(Ty::Future(Box::new(Ty::Unit)));
+        }
+
+        let sig = builtin_signature(name)?;
+        Some(self.check_builtin_signature(name, &sig, arg_types, span))
+    }
+
+    /// Instantiate `sig`'s type variables with fresh [`Ty::Var`]s, unify each
+    /// instantiated parameter against the corresponding entry in
+    /// `arg_types`, check `sig`'s constraints against the now-resolved
+    /// variables, and return the instantiated (and resolved) return type.
+    ///
+    /// This is the one generic resolver every table entry in
+    /// [`builtin_signature`] goes through, replacing what used to be a
+    /// hand-written arity check plus bespoke error message per builtin:
+    /// arity and most type mismatches now fall out of `unify_ty`'s own
+    /// diagnostics "for free", the same way a user-defined callable's call
+    /// site is checked in `check_call`.
+    fn check_builtin_signature(
+        &mut self,
+        name: &str,
+        sig: &BuiltinSig,
+        arg_types: &[Ty],
+        span: SimpleSpan,
+    ) -> Ty {
+        if arg_types.len() != sig.params.len() {
+            self.error(
+                span,
+                format!(
+                    "{}() takes exactly {} argument(s), found {}",
+                    name,
+                    sig.params.len(),
+                    arg_types.len()
+                ),
+            );
+            return Ty::Error;
+        }
+
+        let mut vars: HashMap<&'static str, Ty> = HashMap::new();
+        let instantiated_params: Vec<Ty> = sig
+            .params
+            .iter()
+            .map(|p| self.instantiate_sig(p, &mut vars))
+            .collect();
+        let instantiated_ret = self.instantiate_sig(&sig.ret, &mut vars);
+
+        for (param, arg) in instantiated_params.iter().zip(arg_types) {
+            if *arg == Ty::Error {
+                continue;
             }
-            "values" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "values() takes exactly 1 argument".into());
-                    return Some(Ty::Error);
-                }
-                match &arg_types[0] {
-                    Ty::Map { value, .. } => Some(Ty::List(value.clone())),
-                    Ty::Error => Some(Ty::List(Box::new(Ty::Error))),
-                    other => {
-                        self.error(span, format!("values() requires Map, found {}", other));
-                        Some(Ty::Error)
-                    }
-                }
+            let unified = self.unify_ty(param, arg, span);
+            // `unify_ty` widens e.g. `Int` against an already-`Int`-bound
+            // `N` to `Float` without rebinding `N` itself (both sides are
+            // concrete by the time it runs), so a bare scheme variable like
+            // `min`/`clamp`'s `N` needs its binding refreshed explicitly to
+            // pick up that widening for the next parameter and the return
+            // type.
+            if let Ty::Var(id) = param {
+                self.substitutions.insert(*id, unified);
             }
-            "len" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "len() takes exactly 1 argument".into());
-                } else {
-                    match &arg_types[0] {
-                        Ty::List(_) | Ty::Set(_) | Ty::Map { .. } | Ty::String | Ty::Error => {}
-                        other => {
-                            self.error(
-                                span,
-                                format!("len() requires collection or String, found {}", other),
-                            );
-                        }
-                    }
-                }
-                Some(Ty::Int)
+        }
+
+        for (var_name, constraint) in &sig.constraints {
+            let Some(var_ty) = vars.get(var_name) else {
+                continue;
+            };
+            let resolved = self.resolve_ty(var_ty);
+            if resolved == Ty::Error {
+                continue;
             }
-            "abs" => {
-                if arg_types.len() != 1 {
-                    self.error(span, "abs() takes exactly 1 argument".into());
-                    return Some(Ty::Error);
-                }
-                if self.is_numeric(&arg_types[0]) || arg_types[0] == Ty::Error {
-                    Some(arg_types[0].clone())
-                } else {
+            match constraint {
+                Constraint::Numeric if !self.is_numeric(&resolved) => {
                     self.error(
                         span,
-                        format!("abs() requires numeric type, found {}", arg_types[0]),
+                        format!("{}() requires numeric type, found {}", name, resolved),
                     );
-                    Some(Ty::Error)
                 }
-            }
-            "min" | "max" => {
-                if arg_types.len() != 2 {
-                    self.error(span, format!("{}() takes exactly 2 arguments", name));
-                    return Some(Ty::Error);
-                }
-                if (self.is_numeric(&arg_types[0]) || arg_types[0] == Ty::Error)
-                    && (self.is_numeric(&arg_types[1]) || arg_types[1] == Ty::Error)
+                Constraint::Collection
+                    if !matches!(
+                        resolved,
+                        Ty::List(_) | Ty::Set(_) | Ty::Map { .. } | Ty::String
+                    ) =>
                 {
-                    if arg_types[0] == Ty::Float || arg_types[1] == Ty::Float {
-                        Some(Ty::Float)
-                    } else {
-                        Some(Ty::Int)
-                    }
-                } else {
                     self.error(
                         span,
                         format!(
-                            "{}() requires numeric arguments, found {} and {}",
-                            name, arg_types[0], arg_types[1]
+                            "{}() requires collection or String, found {}",
+                            name, resolved
                         ),
                     );
-                    Some(Ty::Error)
                 }
+                Constraint::Numeric | Constraint::Collection => {}
             }
-            "clamp" => {
-                if arg_types.len() != 3 {
-                    self.error(span, "clamp() takes exactly 3 arguments".into());
-                    return Some(Ty::Error);
-                }
-                let all_numeric = arg_types
-                    .iter()
-                    .all(|t| self.is_numeric(t) || *t == Ty::Error);
-                if all_numeric {
-                    if arg_types.contains(&Ty::Float) {
-                        Some(Ty::Float)
-                    } else {
-                        Some(Ty::Int)
-                    }
-                } else {
-                    self.error(span, "clamp() requires numeric arguments".into());
-                    Some(Ty::Error)
-                }
-            }
-            "filter" => {
-                if arg_types.len() != 2 {
-                    self.error(span, "filter() takes exactly 2 arguments".into());
-                    return Some(Ty::Error);
-                }
-                match &arg_types[0] {
-                    Ty::List(_) => Some(arg_types[0].clone()),
-                    Ty::Error => Some(Ty::Error),
-                    other => {
-                        self.error(
-                            span,
-                            format!("filter() first argument must be a list, found {}", other),
-                        );
-                        Some(Ty::Error)
-                    }
-                }
-            }
-            "wait" => {
-                // wait is an alias / variant of sleep with named args
-                Some(Ty::Future(Box::new(Ty::Unit)))
-            }
-            _ => None,
+        }
+
+        self.resolve_ty(&instantiated_ret)
+    }
+
+    /// Substitute `sig`'s scheme variables with fresh [`Ty::Var`]s, caching
+    /// each variable's instantiation in `vars` so repeated occurrences of
+    /// the same variable name (e.g. `min`'s `Var("N")` appearing in both
+    /// parameters) resolve to the same `Ty::Var` id and so unify together.
+    fn instantiate_sig(&mut self, sig: &Sig, vars: &mut HashMap<&'static str, Ty>) -> Ty {
+        match sig {
+            Sig::Concrete(ty) => ty.clone(),
+            Sig::Var(name) => vars.entry(name).or_insert_with(|| self.fresh_ty_var()).clone(),
+            Sig::List(inner) => Ty::List(Box::new(self.instantiate_sig(inner, vars))),
+            Sig::Map { key, value } => Ty::Map {
+                key: Box::new(self.instantiate_sig(key, vars)),
+                value: Box::new(self.instantiate_sig(value, vars)),
+            },
+            Sig::Fn(params, ret) => Ty::Fn {
+                params: params.iter().map(|p| self.instantiate_sig(p, vars)).collect(),
+                ret: Box::new(self.instantiate_sig(ret, vars)),
+            },
         }
     }
 
@@ -1266,12 +2490,42 @@ impl TypeChecker {
     // Struct literals
     // =========================================================================
 
+    /// Resolve a struct literal's type, tracing the struct name and
+    /// resolved type under `trace-checker` - see [`TypeChecker::check_expr`]'s
+    /// doc comment for why this is a thin wrapper rather than an
+    /// instrumented body.
     fn check_struct_lit(
         &mut self,
         name: &str,
         fields: &[lowered::Spanned<lowered::LoweredStructField>],
         span: SimpleSpan,
         origin: lowered::Origin,
+    ) -> TypedExpr {
+        #[cfg(feature = "trace-checker")]
+        {
+            let trace_span = tracing::debug_span!(
+                "check_struct_lit",
+                node.span = ?span,
+                node.kind = name,
+                result.ty = tracing::field::Empty,
+            );
+            let _entered = trace_span.enter();
+            let typed = self.check_struct_lit_uninstrumented(name, fields, span, origin);
+            trace_span.record("result.ty", tracing::field::display(&typed.ty));
+            typed
+        }
+        #[cfg(not(feature = "trace-checker"))]
+        {
+            self.check_struct_lit_uninstrumented(name, fields, span, origin)
+        }
+    }
+
+    fn check_struct_lit_uninstrumented(
+        &mut self,
+        name: &str,
+        fields: &[lowered::Spanned<lowered::LoweredStructField>],
+        span: SimpleSpan,
+        origin: lowered::Origin,
     ) -> TypedExpr {
         let is_known = TypeRegistry::is_struct(name) || self.registry.is_enum(name);
 
@@ -1297,7 +2551,13 @@ impl TypeChecker {
         let ty = if is_known {
             Ty::Named(name.to_string())
         } else {
-            self.error(span, format!("unknown struct type '{}'", name));
+            let known = self.registry.known_type_names();
+            let mut error = TypeError::new(span, format!("unknown struct type '{}'", name))
+                .with_code("unknown-struct-type");
+            if let Some(suggestion) = closest_type_name(name, known.iter().map(|s| s.as_str())) {
+                error = error.with_suggestion(suggestion);
+            }
+            self.error_with(error);
             Ty::Error
         };
 
@@ -1311,11 +2571,561 @@ impl TypeChecker {
         }
     }
 
+    // =========================================================================
+    // Match expressions
+    // =========================================================================
+
+    /// Check a `match` expression: resolve the scrutinee's enum, verify each
+    /// arm's variant, bind positional payload fields, and report
+    /// unreachable arms (duplicate variants, or anything after a wildcard)
+    /// and non-exhaustive matches (missing variants with no wildcard arm).
+    fn check_match(
+        &mut self,
+        scrutinee: &lowered::Spanned<lowered::LoweredExpr>,
+        arms: &[lowered::LoweredMatchArm],
+        span: SimpleSpan,
+        origin: lowered::Origin,
+    ) -> TypedExpr {
+        let typed_scrutinee = self.check_expr(scrutinee);
+        let enum_name = match &typed_scrutinee.ty {
+            Ty::Named(n) if self.registry.is_enum(n) => Some(n.clone()),
+            Ty::EnumVariant { enum_name, .. } => Some(enum_name.clone()),
+            Ty::Error => None,
+            other => {
+                self.error(
+                    scrutinee.span(),
+                    format!("match requires an enum value, found {}", other),
+                );
+                None
+            }
+        };
+
+        // Usefulness/exhaustiveness via the remaining-constructors approach
+        // (rather than just counting what's been seen): start with every
+        // variant of the scrutinee's enum uncovered, and have each arm
+        // subtract the constructor(s) it covers. An arm that subtracts
+        // nothing - a variant already removed by an earlier arm, or a
+        // wildcard once nothing remains - covers no case its predecessors
+        // didn't already, so it's unreachable. Whatever's still in
+        // `remaining` after the last arm, with no wildcard to catch it, is
+        // the non-exhaustive-match report. Stays `None` until the
+        // scrutinee's enum (or, failing that, the first arm's own pattern
+        // enum - e.g. when the scrutinee itself didn't type-check) tells us
+        // which variants there are to cover.
+        let mut remaining: Option<std::collections::HashSet<String>> = enum_name
+            .as_ref()
+            .and_then(|en| self.registry.enum_variant_names(en))
+            .map(|variants| variants.into_iter().collect());
+        let mut seen_wildcard = false;
+        let mut result_ty = Ty::Error;
+        let mut result_span = span;
+        let mut typed_arms = Vec::with_capacity(arms.len());
+
+        for arm in arms {
+            let arm_span = arm.pattern.span;
+            if seen_wildcard {
+                self.error(
+                    arm_span,
+                    "unreachable match arm: a wildcard arm already matches all remaining cases"
+                        .into(),
+                );
+            }
+
+            self.env.push_scope();
+            let binding_types = match &arm.pattern.node {
+                ast::MatchPattern::Variant {
+                    enum_name: pat_enum,
+                    variant,
+                    bindings,
+                } => {
+                    if let Some(en) = &enum_name {
+                        if pat_enum != en {
+                            self.error(
+                                arm_span,
+                                format!(
+                                    "pattern enum '{}' does not match scrutinee type '{}'",
+                                    pat_enum, en
+                                ),
+                            );
+                        }
+                    }
+
+                    match self
+                        .registry
+                        .resolve_enum_variant(pat_enum, variant)
+                        .map(|fields| fields.to_vec())
+                    {
+                        Some(fields) => {
+                            let remaining = remaining.get_or_insert_with(|| {
+                                self.registry
+                                    .enum_variant_names(pat_enum)
+                                    .into_iter()
+                                    .flatten()
+                                    .collect()
+                            });
+                            if !remaining.remove(variant) {
+                                self.error(
+                                    arm_span,
+                                    format!(
+                                        "unreachable match arm: variant '{}::{}' already covered",
+                                        pat_enum, variant
+                                    ),
+                                );
+                            }
+                            if bindings.len() > fields.len() {
+                                self.error(
+                                    arm_span,
+                                    format!(
+                                        "variant '{}::{}' has {} field(s), found {} binding(s)",
+                                        pat_enum,
+                                        variant,
+                                        fields.len(),
+                                        bindings.len()
+                                    ),
+                                );
+                            }
+                            bindings
+                                .iter()
+                                .enumerate()
+                                .map(|(i, binding)| {
+                                    let field_ty = fields
+                                        .get(i)
+                                        .map(|(_, ty)| ty.clone())
+                                        .unwrap_or(Ty::Error);
+                                    if let ast::BindingPattern::Ident(name) = &binding.node {
+                                        self.env.bind_at(
+                                            name.clone(),
+                                            field_ty.clone(),
+                                            binding.span,
+                                        );
+                                    }
+                                    field_ty
+                                })
+                                .collect()
+                        }
+                        None => {
+                            let known_variants = self
+                                .registry
+                                .enum_variant_names(pat_enum)
+                                .unwrap_or_default();
+                            let mut error = TypeError::new(
+                                arm_span,
+                                format!("unknown variant '{}' on enum '{}'", variant, pat_enum),
+                            )
+                            .with_code("unknown-variant");
+                            if let Some(suggestion) = closest_name(variant, &known_variants) {
+                                error = error.with_suggestion(suggestion);
+                            }
+                            self.error_with(error);
+                            bindings.iter().map(|_| Ty::Error).collect()
+                        }
+                    }
+                }
+                ast::MatchPattern::Wildcard => {
+                    if matches!(&remaining, Some(r) if r.is_empty()) {
+                        self.error(
+                            arm_span,
+                            "unreachable match arm: every variant is already covered".into(),
+                        );
+                    }
+                    if let Some(r) = &mut remaining {
+                        r.clear();
+                    }
+                    seen_wildcard = true;
+                    Vec::new()
+                }
+            };
+
+            let typed_body: Vec<_> = arm.body.iter().map(|s| self.check_stmt(s)).collect();
+            self.env.pop_scope();
+
+            let arm_ty = self.body_type(&typed_body);
+            let arm_result_span = block_result_span(&arm.body, arm_span);
+            result_ty = self.unify(&result_ty, result_span, &arm_ty, arm_result_span);
+            result_span = arm_result_span;
+
+            typed_arms.push(TypedMatchArm {
+                pattern: arm.pattern.clone(),
+                binding_types,
+                body: typed_body,
+            });
+        }
+
+        if !seen_wildcard {
+            if let Some(remaining) = remaining {
+                if !remaining.is_empty() {
+                    let mut missing: Vec<_> = remaining.into_iter().collect();
+                    missing.sort();
+                    self.error(
+                        span,
+                        format!("non-exhaustive match: missing variant(s) {}", missing.join(", ")),
+                    );
+                }
+            }
+        }
+
+        TypedExpr {
+            kind: TypedExprKind::Match {
+                scrutinee: Box::new(typed_scrutinee),
+                arms: typed_arms,
+            },
+            ty: result_ty,
+            origin,
+        }
+    }
+
     // =========================================================================
     // Type unification
     // =========================================================================
 
-    fn unify(&self, a: &Ty, b: &Ty) -> Ty {
+    /// Follow `self.substitutions` to resolve `ty` to its most concrete
+    /// known form, recursing into nested collection types - so
+    /// `List(Var(0))` resolves to `List(Int)` once `0` has been bound,
+    /// even though the `TypedExpr`/`TypedStmt` that first produced the
+    /// `Var` was built before that binding existed.
+    ///
+    /// Path-compresses as it goes: a chain like `0 -> 1 -> Int` collapses
+    /// to `0 -> Int` directly in `substitutions`, so the next lookup of `0`
+    /// is O(1) instead of re-walking the chain.
+    fn resolve_ty(&mut self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(id) => match self.substitutions.get(id).cloned() {
+                Some(bound) => {
+                    let resolved = self.resolve_ty(&bound);
+                    self.substitutions.insert(*id, resolved.clone());
+                    resolved
+                }
+                None => ty.clone(),
+            },
+            Ty::List(inner) => Ty::List(Box::new(self.resolve_ty(inner))),
+            Ty::Set(inner) => Ty::Set(Box::new(self.resolve_ty(inner))),
+            Ty::Option(inner) => Ty::Option(Box::new(self.resolve_ty(inner))),
+            Ty::Future(inner) => Ty::Future(Box::new(self.resolve_ty(inner))),
+            Ty::Map { key, value } => Ty::Map {
+                key: Box::new(self.resolve_ty(key)),
+                value: Box::new(self.resolve_ty(value)),
+            },
+            Ty::Fn { params, ret } => Ty::Fn {
+                params: params.iter().map(|p| self.resolve_ty(p)).collect(),
+                ret: Box::new(self.resolve_ty(ret)),
+            },
+            Ty::Tuple(elems) => Ty::Tuple(elems.iter().map(|e| self.resolve_ty(e)).collect()),
+            other => other.clone(),
+        }
+    }
+
+    /// True if `ty` (after substitution) still has a free `Ty::Var` in it
+    /// somewhere - i.e. a `let mut` collection whose element type was never
+    /// pinned down by a `Push`/`Insert`/`Add`.
+    fn contains_unresolved_var(&mut self, ty: &Ty) -> bool {
+        match self.resolve_ty(ty) {
+            Ty::Var(_) => true,
+            Ty::List(inner) | Ty::Set(inner) | Ty::Option(inner) | Ty::Future(inner) => {
+                self.contains_unresolved_var(&inner)
+            }
+            Ty::Map { key, value } => {
+                self.contains_unresolved_var(&key) || self.contains_unresolved_var(&value)
+            }
+            Ty::Fn { params, ret } => {
+                params.iter().any(|p| self.contains_unresolved_var(p))
+                    || self.contains_unresolved_var(&ret)
+            }
+            Ty::Tuple(elems) => elems.iter().any(|e| self.contains_unresolved_var(e)),
+            _ => false,
+        }
+    }
+
+    /// True if the free variable `id` occurs anywhere inside `ty` (after
+    /// substitution) - checked before `unify_ty` binds `id`, so a cyclic
+    /// unification like `?0` with `[?0]` is rejected instead of producing a
+    /// type that resolves to itself and sends `resolve_ty` into infinite
+    /// recursion.
+    fn occurs_in(&mut self, id: u32, ty: &Ty) -> bool {
+        match self.resolve_ty(ty) {
+            Ty::Var(other) => other == id,
+            Ty::List(inner) | Ty::Set(inner) | Ty::Option(inner) | Ty::Future(inner) => {
+                self.occurs_in(id, &inner)
+            }
+            Ty::Map { key, value } => self.occurs_in(id, &key) || self.occurs_in(id, &value),
+            Ty::Fn { params, ret } => {
+                params.iter().any(|p| self.occurs_in(id, p)) || self.occurs_in(id, &ret)
+            }
+            Ty::Tuple(elems) => elems.iter().any(|e| self.occurs_in(id, e)),
+            _ => false,
+        }
+    }
+
+    /// Every free `Ty::Var` id left in `ty` after substitution.
+    fn collect_var_ids(&mut self, ty: &Ty) -> Vec<u32> {
+        match self.resolve_ty(ty) {
+            Ty::Var(id) => vec![id],
+            Ty::List(inner) | Ty::Set(inner) | Ty::Option(inner) | Ty::Future(inner) => {
+                self.collect_var_ids(&inner)
+            }
+            Ty::Map { key, value } => {
+                let mut ids = self.collect_var_ids(&key);
+                ids.extend(self.collect_var_ids(&value));
+                ids
+            }
+            Ty::Fn { params, ret } => {
+                let mut ids: Vec<u32> = params.iter().flat_map(|p| self.collect_var_ids(p)).collect();
+                ids.extend(self.collect_var_ids(&ret));
+                ids
+            }
+            Ty::Tuple(elems) => elems.iter().flat_map(|e| self.collect_var_ids(e)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Report "cannot infer element type" for `ty`, deduplicated against
+    /// `reported_unbound_vars` so the same never-pinned-down variable
+    /// showing up in more than one `TypedExpr`/`TypedStmt` (e.g. both the
+    /// automation's return value and an unrelated `let mut` elsewhere in
+    /// the body) only produces one diagnostic.
+    fn report_unbound_once(&mut self, ty: &Ty, span: SimpleSpan) {
+        // Don't short-circuit on the first already-reported id - every id
+        // in `ty` needs to be marked reported, not just the first one.
+        let mut any_new = false;
+        for id in self.collect_var_ids(ty) {
+            any_new |= self.reported_unbound_vars.insert(id);
+        }
+        if any_new {
+            self.error_with(
+                TypeError::new(span, format!("cannot infer element type: {}", ty))
+                    .with_code("cannot-infer-element-type")
+                    .with_help("push a value onto it so its element type can be inferred"),
+            );
+        }
+    }
+
+    /// Resolve `ty`, reporting (once) and defaulting to `Ty::Error` any
+    /// `Ty::Var` that's still free - the last step applied to every
+    /// `TypedExpr`/`TypedStmt` by `finalize_stmts` once a body is fully
+    /// checked, so nothing downstream of type checking ever sees a
+    /// `Ty::Var`.
+    fn finalize_ty(&mut self, ty: &Ty, span: SimpleSpan) -> Ty {
+        let resolved = self.resolve_ty(ty);
+        if self.contains_unresolved_var(&resolved) {
+            self.report_unbound_once(&resolved, span);
+        }
+        self.default_unbound(&resolved)
+    }
+
+    fn default_unbound(&self, ty: &Ty) -> Ty {
+        match ty {
+            Ty::Var(_) => Ty::Error,
+            Ty::List(inner) => Ty::List(Box::new(self.default_unbound(inner))),
+            Ty::Set(inner) => Ty::Set(Box::new(self.default_unbound(inner))),
+            Ty::Option(inner) => Ty::Option(Box::new(self.default_unbound(inner))),
+            Ty::Future(inner) => Ty::Future(Box::new(self.default_unbound(inner))),
+            Ty::Map { key, value } => Ty::Map {
+                key: Box::new(self.default_unbound(key)),
+                value: Box::new(self.default_unbound(value)),
+            },
+            Ty::Fn { params, ret } => Ty::Fn {
+                params: params.iter().map(|p| self.default_unbound(p)).collect(),
+                ret: Box::new(self.default_unbound(ret)),
+            },
+            Ty::Tuple(elems) => {
+                Ty::Tuple(elems.iter().map(|e| self.default_unbound(e)).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
+    /// Apply `finalize_ty` to every statement in `stmts` and everything
+    /// nested inside them, in place - the post-check substitution pass run
+    /// once per automation body in `check_automation`.
+    fn finalize_stmts(&mut self, stmts: &mut [TypedStmt]) {
+        for stmt in stmts {
+            self.finalize_stmt(stmt);
+        }
+    }
+
+    fn finalize_stmt(&mut self, stmt: &mut TypedStmt) {
+        match stmt {
+            TypedStmt::Let { value, .. } | TypedStmt::LetMut { value, .. } => {
+                self.finalize_expr(value);
+            }
+            TypedStmt::Expr(expr) => self.finalize_expr(expr),
+            TypedStmt::Return(expr, _) => self.finalize_expr(expr),
+            TypedStmt::For { iter, body, .. } => {
+                self.finalize_expr(iter);
+                self.finalize_stmts(body);
+            }
+            TypedStmt::Push { value, .. } => self.finalize_expr(value),
+            TypedStmt::While { cond, body, .. } => {
+                self.finalize_expr(cond);
+                self.finalize_stmts(body);
+            }
+        }
+    }
+
+    fn finalize_expr(&mut self, expr: &mut TypedExpr) {
+        let span = expr.origin.span();
+        expr.ty = self.finalize_ty(&expr.ty, span);
+
+        match &mut expr.kind {
+            TypedExprKind::BinOp { left, right, .. } => {
+                self.finalize_expr(left);
+                self.finalize_expr(right);
+            }
+            TypedExprKind::UnaryOp { expr, .. } => self.finalize_expr(expr),
+            TypedExprKind::Field { expr, .. } | TypedExprKind::OptionalField { expr, .. } => {
+                self.finalize_expr(expr);
+            }
+            TypedExprKind::Call { func, args } => {
+                self.finalize_expr(func);
+                for arg in args {
+                    match arg {
+                        TypedArg::Positional(value) => self.finalize_expr(value),
+                        TypedArg::Named { value, .. } => self.finalize_expr(value),
+                    }
+                }
+            }
+            TypedExprKind::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                self.finalize_expr(cond);
+                self.finalize_stmts(then_block);
+                if let Some(else_block) = else_block {
+                    self.finalize_stmts(else_block);
+                }
+            }
+            TypedExprKind::List(items) => {
+                for item in items {
+                    self.finalize_expr(item);
+                }
+            }
+            TypedExprKind::StructLit { fields, .. } => {
+                for field in fields {
+                    if let TypedStructField::Field { value, .. } = field {
+                        self.finalize_expr(value);
+                    }
+                }
+            }
+            TypedExprKind::Block { stmts, result } => {
+                self.finalize_stmts(stmts);
+                self.finalize_expr(result);
+            }
+            TypedExprKind::Match { scrutinee, arms } => {
+                self.finalize_expr(scrutinee);
+                let arm_span = scrutinee.origin.span();
+                for arm in arms {
+                    arm.binding_types = arm
+                        .binding_types
+                        .iter()
+                        .map(|ty| self.finalize_ty(ty, arm_span))
+                        .collect();
+                    self.finalize_stmts(&mut arm.body);
+                }
+            }
+            TypedExprKind::Lambda { body, .. } => self.finalize_expr(body),
+            TypedExprKind::Tuple(items) => {
+                for item in items {
+                    self.finalize_expr(item);
+                }
+            }
+            TypedExprKind::Int(_)
+            | TypedExprKind::Float(_)
+            | TypedExprKind::String(_)
+            | TypedExprKind::Bool(_)
+            | TypedExprKind::UnitLiteral { .. }
+            | TypedExprKind::Ident(_)
+            | TypedExprKind::Path(_)
+            | TypedExprKind::MutableList => {}
+        }
+    }
+
+    /// Unify two types that are expected to describe the same value,
+    /// following the rust-analyzer `infer/unify` model: a free `Ty::Var`
+    /// binds to whatever the other side resolves to, and the unifier
+    /// recurses structurally through `List`/`Set`/`Map`/`Option` so e.g.
+    /// unifying `List(Var(0))` with `List(Int)` binds `0 -> Int` instead of
+    /// just comparing the outer `List` shells. Two incompatible concrete
+    /// types are a genuine "conflicting element types" error - unlike the
+    /// permissive `unify` above (used for `if`/`match` arm results, which
+    /// predates type variables and silently falls back to the first type).
+    fn unify_ty(&mut self, a: &Ty, b: &Ty, span: SimpleSpan) -> Ty {
+        let a = self.resolve_ty(a);
+        let b = self.resolve_ty(b);
+
+        match (&a, &b) {
+            (Ty::Var(id), Ty::Var(other)) if id == other => a,
+            (Ty::Var(id), _) => {
+                if self.occurs_in(*id, &b) {
+                    self.error(
+                        span,
+                        format!("cannot construct infinite type: ?{} = {}", id, b),
+                    );
+                    return Ty::Error;
+                }
+                self.substitutions.insert(*id, b.clone());
+                b
+            }
+            (_, Ty::Var(id)) => {
+                if self.occurs_in(*id, &a) {
+                    self.error(
+                        span,
+                        format!("cannot construct infinite type: ?{} = {}", id, a),
+                    );
+                    return Ty::Error;
+                }
+                self.substitutions.insert(*id, a.clone());
+                a
+            }
+            (Ty::Error, _) => b,
+            (_, Ty::Error) => a,
+            _ if a == b => a,
+            (Ty::Int, Ty::Float) | (Ty::Float, Ty::Int) => Ty::Float,
+            _ if self.is_event_type(&a) && self.is_event_type(&b) => Ty::Named("Event".into()),
+            (Ty::List(inner_a), Ty::List(inner_b)) => {
+                Ty::List(Box::new(self.unify_ty(inner_a, inner_b, span)))
+            }
+            (Ty::Set(inner_a), Ty::Set(inner_b)) => {
+                Ty::Set(Box::new(self.unify_ty(inner_a, inner_b, span)))
+            }
+            (Ty::Option(inner_a), Ty::Option(inner_b)) => {
+                Ty::Option(Box::new(self.unify_ty(inner_a, inner_b, span)))
+            }
+            (Ty::Map { key: ka, value: va }, Ty::Map { key: kb, value: vb }) => Ty::Map {
+                key: Box::new(self.unify_ty(ka, kb, span)),
+                value: Box::new(self.unify_ty(va, vb, span)),
+            },
+            (Ty::Fn { params: pa, ret: ra }, Ty::Fn { params: pb, ret: rb }) => {
+                if pa.len() != pb.len() {
+                    self.error(span, format!("conflicting element types: {} vs {}", a, b));
+                    return Ty::Error;
+                }
+                let params = pa
+                    .iter()
+                    .zip(pb.iter())
+                    .map(|(x, y)| self.unify_ty(x, y, span))
+                    .collect();
+                let ret = self.unify_ty(ra, rb, span);
+                Ty::Fn {
+                    params,
+                    ret: Box::new(ret),
+                }
+            }
+            _ => {
+                self.error(span, format!("conflicting element types: {} vs {}", a, b));
+                Ty::Error
+            }
+        }
+    }
+
+    /// Unify two branch/arm result types that are expected to agree (e.g.
+    /// `if`/`else`, or successive `match` arms), given the span each one's
+    /// value came from. Unlike `unify_ty` (used for collection element
+    /// types, which already has `Ty::Var` to fall back on), there's no
+    /// variable to bind here - two genuinely incompatible types are a type
+    /// error with one red label at each contributing span, e.g. "this is
+    /// `Temperature`" / "but this is `Angle`", so the report points at both
+    /// sides instead of just the `if`/`match` keyword.
+    fn unify(&mut self, a: &Ty, a_span: SimpleSpan, b: &Ty, b_span: SimpleSpan) -> Ty {
         if *a == Ty::Error {
             return b.clone();
         }
@@ -1335,17 +3145,51 @@ impl TypeChecker {
         }
         // List unification
         if let (Ty::List(inner_a), Ty::List(inner_b)) = (a, b) {
-            return Ty::List(Box::new(self.unify(inner_a, inner_b)));
+            return Ty::List(Box::new(self.unify(inner_a, a_span, inner_b, b_span)));
         }
-        // Fall back to first type (could emit error, but for now be permissive)
-        a.clone()
+        // Tuple unification: equal arity unifies element-wise; a mismatched
+        // arity falls through to the mismatch report below, same as any
+        // other disagreement.
+        if let (Ty::Tuple(elems_a), Ty::Tuple(elems_b)) = (a, b) {
+            if elems_a.len() == elems_b.len() {
+                return Ty::Tuple(
+                    elems_a
+                        .iter()
+                        .zip(elems_b)
+                        .map(|(x, y)| self.unify(x, a_span, y, b_span))
+                        .collect(),
+                );
+            }
+        }
+        self.error_with(
+            TypeError::new(a_span, format!("mismatched types: {} and {}", a, b))
+                .with_code("type-mismatch")
+                .with_secondary(a_span, format!("this is {}", a))
+                .with_secondary(b_span, format!("but this is {}", b)),
+        );
+        Ty::Error
     }
 
     // =========================================================================
     // AST type -> Ty conversion
     // =========================================================================
 
-    fn ast_type_to_ty(&self, ty: &ast::Type) -> Ty {
+    /// Resolve a surface-syntax `ast::Type` to an internal `Ty`. `span`
+    /// blames the type annotation as a whole (e.g. a template parameter's
+    /// span) - there's no finer span per nested type, so a typo inside a
+    /// `[Tempurature]` list annotation and a bare `Tempurature` one both
+    /// point at the same place.
+    fn ast_type_to_ty(&mut self, ty: &ast::Type, span: SimpleSpan) -> Ty {
+        const BUILTIN_TYPE_NAMES: [&str; 7] = [
+            "Int",
+            "Float",
+            "Bool",
+            "String",
+            "Duration",
+            "Angle",
+            "Temperature",
+        ];
+
         match ty {
             ast::Type::Named(name) => match name.as_str() {
                 "Int" | "i64" => Ty::Int,
@@ -1355,50 +3199,590 @@ impl TypeChecker {
                 "Duration" => Ty::Duration,
                 "Angle" => Ty::Angle,
                 "Temperature" => Ty::Temperature,
-                _ => Ty::Named(name.clone()),
+                _ => {
+                    let known = self.registry.known_type_names();
+                    if known.iter().any(|k| k == name) {
+                        return Ty::Named(name.clone());
+                    }
+
+                    let candidates = BUILTIN_TYPE_NAMES
+                        .into_iter()
+                        .chain(known.iter().map(|s| s.as_str()));
+                    let mut error = TypeError::new(span, format!("unknown type '{}'", name))
+                        .with_code("unknown-type");
+                    if let Some(suggestion) = closest_type_name(name, candidates) {
+                        error = error.with_suggestion(suggestion);
+                    }
+                    self.error_with(error);
+                    Ty::Named(name.clone())
+                }
             },
-            ast::Type::List(inner) => Ty::List(Box::new(self.ast_type_to_ty(inner))),
-            ast::Type::Set(inner) => Ty::Set(Box::new(self.ast_type_to_ty(inner))),
+            ast::Type::List(inner) => Ty::List(Box::new(self.ast_type_to_ty(inner, span))),
+            ast::Type::Set(inner) => Ty::Set(Box::new(self.ast_type_to_ty(inner, span))),
             ast::Type::Map { key, value } => Ty::Map {
-                key: Box::new(self.ast_type_to_ty(key)),
-                value: Box::new(self.ast_type_to_ty(value)),
+                key: Box::new(self.ast_type_to_ty(key, span)),
+                value: Box::new(self.ast_type_to_ty(value, span)),
             },
-            ast::Type::Option(inner) => Ty::Option(Box::new(self.ast_type_to_ty(inner))),
+            ast::Type::Option(inner) => Ty::Option(Box::new(self.ast_type_to_ty(inner, span))),
         }
     }
 }
 
-/// Convenience function: parse, desugar, and type-check a program.
+/// Convenience function: parse, desugar, and type-check a program. Checks
+/// `program` in its own file (`program.file()`), so every error it
+/// produces carries the right `FileId` without the caller having to pass
+/// one in separately.
 pub fn check_program(program: &lowered::LoweredProgram) -> CheckResult {
-    TypeChecker::new().check_program(program)
+    TypeChecker::with_file(program.file()).check_program(program)
 }
 
-/// Render type errors as pretty diagnostics using ariadne.
+/// A `(name, contents)` pair per [`ast::FileId`], handed to
+/// `format_type_errors` so it can render a report that touches more than
+/// one file (e.g. a type error spanning an `import`ed definition and its
+/// use site) without assuming the whole program lives in one string.
+///
+/// Implements ariadne's own `Cache` trait directly, in the same spirit as
+/// the `(filename, Source)` tuple `format_type_errors` used to build fresh
+/// on every loop iteration - except keyed by `FileId` instead of assuming
+/// the filename itself is a good cache key, and built once up front so
+/// each file's `Source` (which does line-index bookkeeping) isn't
+/// recomputed per error.
+pub struct SourceCache {
+    files: HashMap<ast::FileId, (String, ariadne::Source<String>)>,
+    /// Byte-offset line index per file, built once alongside the
+    /// `ariadne::Source` above so [`format_type_errors_json`] doesn't
+    /// rescan the source for every error/label it converts to a (line,
+    /// column) position.
+    line_indexes: HashMap<ast::FileId, LineIndex>,
+}
+
+impl SourceCache {
+    /// Build a cache from `(file, name, contents)` triples.
+    pub fn new(files: impl IntoIterator<Item = (ast::FileId, String, String)>) -> Self {
+        let mut cache = Self {
+            files: HashMap::new(),
+            line_indexes: HashMap::new(),
+        };
+        for (id, name, contents) in files {
+            cache.line_indexes.insert(id, LineIndex::new(&contents));
+            cache
+                .files
+                .insert(id, (name, ariadne::Source::from(contents)));
+        }
+        cache
+    }
+
+    /// The common single-file case: one source under `FileId::default()`,
+    /// which is what every `TypeError` carries today since nothing in this
+    /// checker produces more than one file's worth of errors yet.
+    pub fn single(filename: impl Into<String>, contents: impl Into<String>) -> Self {
+        Self::new([(ast::FileId::default(), filename.into(), contents.into())])
+    }
+
+    /// 0-indexed LSP `Position` (line, UTF-16 column) for `offset` in
+    /// `file`, or `(0, 0)` if `file` is unknown to this cache - callers
+    /// building diagnostics for a file the cache was never told about have
+    /// no better fallback than pointing at the start of the document.
+    fn position(&self, file: ast::FileId, offset: usize) -> (usize, usize) {
+        self.line_indexes
+            .get(&file)
+            .map(|index| index.position(offset))
+            .unwrap_or((0, 0))
+    }
+
+    /// The name a file was registered under, for use as an LSP diagnostic's
+    /// `uri`. Falls back to a placeholder for a `FileId` the cache was
+    /// never told about rather than panicking.
+    fn name(&self, file: ast::FileId) -> &str {
+        self.files
+            .get(&file)
+            .map(|(name, _)| name.as_str())
+            .unwrap_or("<unknown>")
+    }
+}
+
+/// Maps byte offsets into a source string to 0-indexed (line, column)
+/// pairs, built once per file rather than re-scanning the source for every
+/// span [`format_type_errors_json`] needs to convert.
+struct LineIndex {
+    /// Byte offset of the start of each line, `line_starts[0] == 0`.
+    line_starts: Vec<usize>,
+    /// The source text itself, needed to count UTF-16 code units between a
+    /// line's start and a given offset (LSP columns are UTF-16, not bytes).
+    contents: String,
+}
+
+impl LineIndex {
+    fn new(contents: &str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(contents.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            line_starts,
+            contents: contents.to_string(),
+        }
+    }
+
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self
+            .line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1);
+        let line_start = self.line_starts[line];
+        let column = self.contents[line_start..offset.min(self.contents.len())]
+            .encode_utf16()
+            .count();
+        (line, column)
+    }
+}
+
+impl ariadne::Cache<ast::FileId> for SourceCache {
+    type Storage = String;
+
+    fn fetch(
+        &mut self,
+        id: &ast::FileId,
+    ) -> Result<&ariadne::Source<String>, Box<dyn std::fmt::Debug + '_>> {
+        self.files
+            .get(id)
+            .map(|(_, source)| source)
+            .ok_or_else(|| Box::new(format!("unknown file id {id}")) as Box<dyn std::fmt::Debug>)
+    }
+
+    fn display<'a>(&self, id: &'a ast::FileId) -> Option<Box<dyn std::fmt::Display + 'a>> {
+        self.files
+            .get(id)
+            .map(|(name, _)| Box::new(name.clone()) as Box<dyn std::fmt::Display>)
+    }
+}
+
+/// Rendering knobs for [`format_type_errors`], exposing ariadne's own
+/// configuration surface so callers aren't stuck with the hardcoded
+/// colored-Unicode output that breaks in CI logs piped to a file and in
+/// terminals without UTF-8 or ANSI support.
+#[derive(Debug, Clone)]
+pub struct RenderConfig {
+    /// Collapse each report onto a single line instead of a multi-line
+    /// source snippet - useful for dense CI logs.
+    pub compact: bool,
+    /// Draw box-drawing characters with plain ASCII instead of Unicode.
+    pub ascii: bool,
+    /// Emit ANSI color codes. Disable when piping to a file or a terminal
+    /// that doesn't support them.
+    pub color: bool,
+    /// How many columns a tab character should be treated as occupying
+    /// when computing caret alignment.
+    pub tab_width: usize,
+}
+
+impl Default for RenderConfig {
+    fn default() -> Self {
+        Self {
+            compact: false,
+            ascii: false,
+            color: true,
+            tab_width: 4,
+        }
+    }
+}
+
+impl RenderConfig {
+    fn to_ariadne(&self) -> ariadne::Config {
+        ariadne::Config::default()
+            .with_compact(self.compact)
+            .with_char_set(if self.ascii {
+                ariadne::CharSet::Ascii
+            } else {
+                ariadne::CharSet::Unicode
+            })
+            .with_color(self.color)
+            .with_tab_width(self.tab_width)
+    }
+}
+
+/// Render type errors as pretty diagnostics using ariadne, resolving each
+/// error's (and each of its secondary labels') [`ast::FileId`] against
+/// `cache` - so a report whose primary span and secondary labels land in
+/// different files renders both, instead of assuming one shared source.
 ///
 /// Each error becomes an ariadne `Report` with a labeled source span,
-/// producing output with line numbers, source context, and colored carets.
-pub fn format_type_errors(errors: &[TypeError], source: &str, filename: &str) -> String {
+/// producing output with line numbers, source context, and colored carets;
+/// `config` controls compactness, charset, and color per [`RenderConfig`].
+pub fn format_type_errors(
+    errors: &[TypeError],
+    cache: &mut SourceCache,
+    config: &RenderConfig,
+) -> String {
     use ariadne::Color;
     use ariadne::Label;
     use ariadne::Report;
     use ariadne::ReportKind;
-    use ariadne::Source;
 
     let mut output = Vec::new();
     for error in errors {
+        let (report_kind, primary_color) = match error.severity {
+            Severity::Error => (ReportKind::Error, Color::Red),
+            Severity::Warning => (ReportKind::Warning, Color::Yellow),
+        };
+
         let span = error.span.start..error.span.end;
-        let report = Report::build(ReportKind::Error, (filename, span.clone()))
+        let mut builder = Report::build(report_kind, (error.file, span.clone()))
+            .with_config(config.to_ariadne())
             .with_message(&error.message)
             .with_label(
-                Label::new((filename, span))
+                Label::new((error.file, span))
                     .with_message(&error.message)
-                    .with_color(Color::Red),
-            )
-            .finish();
+                    .with_color(primary_color),
+            );
+
+        // "generic" is `TypeError::new`'s default for the call sites that
+        // haven't been given a more specific code - leave those rendering
+        // exactly as before rather than printing a meaningless `[generic]`
+        // on most of the diagnostics this checker produces.
+        if error.code != "generic" {
+            builder = builder.with_code(error.code);
+        }
 
-        report
-            .write((filename, Source::from(source)), &mut output)
-            .ok();
+        for secondary in &error.secondary {
+            let secondary_span = secondary.span.start..secondary.span.end;
+            builder = builder.with_label(
+                Label::new((secondary.file, secondary_span))
+                    .with_message(&secondary.message)
+                    .with_color(Color::Blue),
+            );
+        }
+
+        if let Some(help) = &error.help {
+            builder = builder.with_help(help);
+        }
+
+        if let Some(note) = &error.note {
+            builder = builder.with_note(note);
+        }
+
+        let report = builder.finish();
+        report.write(&mut *cache, &mut output).ok();
     }
     String::from_utf8_lossy(&output).to_string()
 }
+
+/// A longer writeup for one of [`TypeError::code`]'s stable identifiers,
+/// for a `hearthd explain <code>` style command to print - the inline
+/// ariadne report built by [`format_type_errors`] only has room for the
+/// one-line `message`, which is specific to the types involved in one
+/// particular error.
+pub struct ErrorExplanation {
+    /// A one-line restatement of the problem, independent of any specific
+    /// error's interpolated types.
+    pub summary: &'static str,
+    /// A paragraph of background on why the check exists and how to fix it.
+    pub explanation: &'static str,
+    /// A minimal snippet that triggers the error, annotated with the fix.
+    pub example: &'static str,
+}
+
+/// `TypeError::code` -> [`ErrorExplanation`], covering every code this
+/// checker currently produces. A flat list rather than a `HashMap` since
+/// it's small, built once, and only ever read through [`explain`].
+static ERROR_EXPLANATIONS: &[(&str, ErrorExplanation)] = &[
+    (
+        "non-numeric-operand",
+        ErrorExplanation {
+            summary: "unary negation applied to a non-numeric value",
+            explanation: "Unary `-` is only defined for `Int` and `Float`. Anything else - \
+                `Bool`, `String`, a unit-bearing `Duration`/`Angle`/`Temperature`, etc. - has no \
+                sign to flip.",
+            example: "observer {} { -true }          // error\nobserver {} { -1 }             // ok",
+        },
+    ),
+    (
+        "non-bool-operand",
+        ErrorExplanation {
+            summary: "unary `!` applied to a non-`Bool` value",
+            explanation: "Logical negation only accepts `Bool`. Unlike some C-family languages, \
+                an `Int` is never implicitly truthy here, so `!42` is a type error rather than a \
+                numeric negation or a falsiness check.",
+            example: "observer {} { !42 }            // error\nobserver {} { !true }          // ok",
+        },
+    ),
+    (
+        "non-bool-operands",
+        ErrorExplanation {
+            summary: "`&&`/`||` applied to non-`Bool` operands",
+            explanation: "`&&` and `||` require both operands to already be `Bool`; there's no \
+                implicit truthiness conversion, so compare the operand to something first (e.g. \
+                `x != 0`) rather than using it directly.",
+            example: "observer {} { 1 && 2 }                // error\nobserver {} { 1 != 0 && 2 != 0 }     // ok",
+        },
+    ),
+    (
+        "non-future-operand",
+        ErrorExplanation {
+            summary: "`await` applied to a value that isn't a `Future`",
+            explanation: "`await` unwraps a `Future<T>` into a `T`, produced by things like \
+                `sleep(...)` or an integration call. Anything else hasn't been asynchronously \
+                produced, so there's nothing for `await` to wait on.",
+            example: "observer {} { await 42 }              // error\nobserver {} { await sleep(5s) }       // ok",
+        },
+    ),
+    (
+        "non-collection-operand",
+        ErrorExplanation {
+            summary: "`in` applied with a non-collection right-hand side",
+            explanation: "`in` tests membership in a `List`, `Set`, or `Map`, so its right \
+                operand must be one of those. A bare scalar has no elements to search.",
+            example: "observer {} { 1 in 2 }                 // error\nobserver {} { 1 in [1, 2, 3] }        // ok",
+        },
+    ),
+    (
+        "non-numeric-operands",
+        ErrorExplanation {
+            summary: "an arithmetic or comparison operator applied to non-numeric operands",
+            explanation: "`+ - * / < <= > >=` require both operands to be `Int`/`Float`, or both \
+                to carry the same physical dimension (see `mismatched-dimensions`). Anything else \
+                - strings, booleans, lists - can't be compared or combined this way.",
+            example: "observer {} { \"hello\" + 1 }          // error\nobserver {} { 1 + 1 }                // ok",
+        },
+    ),
+    (
+        "mismatched-dimensions",
+        ErrorExplanation {
+            summary: "arithmetic or comparison between two different physical dimensions",
+            explanation: "`Duration`, `Angle`, and `Temperature` each carry a dimension, and \
+                combining two values of *different* dimensions (e.g. a duration and an angle) is \
+                never meaningful, so it's rejected even though both sides look numeric. Convert \
+                one side explicitly, or check that both literals are the unit you intended.",
+            example: "observer {} { 5min + 1deg }            // error\nobserver {} { 5min + 1h }             // ok",
+        },
+    ),
+    (
+        "unsupported-dimension-op",
+        ErrorExplanation {
+            summary: "a dimensioned value used with an operator other than `+`, `-`, or `/`",
+            explanation: "Two same-dimension quantities can be added, subtracted, or divided \
+                (the last producing a plain ratio, e.g. `10min / 2min == 5`), but multiplying two \
+                durations together doesn't produce a meaningful unit, so `*` (and anything beyond \
+                `+ - /`) between two dimensioned operands is rejected.",
+            example: "observer {} { 5min * 2min }            // error\nobserver {} { 5min * 2 }              // ok",
+        },
+    ),
+    (
+        "undefined-variable",
+        ErrorExplanation {
+            summary: "reference to a name with no matching `let`/parameter in scope",
+            explanation: "Every identifier used as a value must be bound by an enclosing `let`, \
+                `let mut`, `for`, or automation/template parameter. This also fires for a typo'd \
+                name; check the suggested spelling first if one is attached.",
+            example: "observer {} { unknown }                // error\nobserver {} { let x = 1; x }          // ok",
+        },
+    ),
+    (
+        "undefined-function",
+        ErrorExplanation {
+            summary: "call to a name that isn't one of the builtin functions",
+            explanation: "Only the fixed set of builtins (`sleep`, `sleep_unique`, `wait`, \
+                `keys`, `values`, `len`, `abs`, `min`, `max`, `clamp`, `filter`, `map`, `fold`) \
+                can be called; there's no user-defined function declaration in this language yet.",
+            example: "observer {} { frobnicate(1) }          // error\nobserver {} { len([1, 2, 3]) }        // ok",
+        },
+    ),
+    (
+        "unknown-variant",
+        ErrorExplanation {
+            summary: "reference to an enum variant that doesn't exist on that enum",
+            explanation: "`Enum::Variant` paths and `Enum::Variant { .. }` match patterns are \
+                checked against the enum's actual declared variants; this fires for both a \
+                nonexistent variant name and a typo of a real one.",
+            example: "observer {} { Event::Nope }            // error\nobserver {} { Event::StateChanged }   // ok (if declared)",
+        },
+    ),
+    (
+        "unknown-struct-type",
+        ErrorExplanation {
+            summary: "struct literal naming a type this checker doesn't recognize",
+            explanation: "A `Name { field: value, ... }` literal's `Name` must be a known \
+                reflected struct type (e.g. `State`, `LightState`) or a registered entity alias - \
+                not an arbitrary identifier.",
+            example: "observer {} { Bogus { x: 1 } }          // error",
+        },
+    ),
+    (
+        "unknown-field",
+        ErrorExplanation {
+            summary: "field access naming a field that doesn't exist on that type",
+            explanation: "`expr.field` is checked against the accessed type's known fields \
+                (from the entity/struct registry); this fires for both a field that was never \
+                declared and a typo of a real one.",
+            example: "observer { state, ... } /true/ { state.nonexistent }  // error",
+        },
+    ),
+    (
+        "unknown-type",
+        ErrorExplanation {
+            summary: "a type annotation naming a type this checker doesn't recognize",
+            explanation: "Type annotations (e.g. a `let x: Foo = ...` or a parameter type) must \
+                name a builtin (`Int`, `String`, ...) or a declared enum/entity type, not an \
+                arbitrary identifier.",
+            example: "// in a type annotation\nlet x: Tempurature = 1  // error, did you mean `Temperature`?",
+        },
+    ),
+    (
+        "tuple-index-out-of-bounds",
+        ErrorExplanation {
+            summary: "`.N` field access past the end of a tuple's elements",
+            explanation: "A tuple's `.0`, `.1`, ... accessors are checked against its actual \
+                arity at compile time, since tuples (unlike lists) have a fixed, statically known \
+                element count.",
+            example: "observer {} { (1, 2).5 }               // error\nobserver {} { (1, 2).1 }              // ok",
+        },
+    ),
+    (
+        "cannot-infer-element-type",
+        ErrorExplanation {
+            summary: "a `let mut` list/set/map never had an element pushed into it",
+            explanation: "An empty `let mut xs = []` starts with an unresolved element type, \
+                normally pinned down by its first `Push`/`Insert`/`Add`. If the body never \
+                mutates it before it's used, there's nothing to infer from.",
+            example: "observer {} { let mut xs = []; xs }    // error\nobserver {} { let mut xs = []; xs.push(1); xs }  // ok",
+        },
+    ),
+    (
+        "type-mismatch",
+        ErrorExplanation {
+            summary: "two values expected to unify to the same type don't",
+            explanation: "This is the catch-all raised wherever two types are required to agree \
+                - both arms of an `if`/`match`, both sides of a ternary-like construct, an \
+                argument against a builtin's declared parameter type - and don't.",
+            example: "observer {} { if true { 1 } else { \"no\" } }  // error\nobserver {} { if true { 1 } else { 2 } }      // ok",
+        },
+    ),
+    (
+        "observer-return-type",
+        ErrorExplanation {
+            summary: "an `observer`'s body doesn't return `[Event]`",
+            explanation: "An observer reacts to a state change and emits zero or more events, so \
+                its body must evaluate to a `[Event]` (or `Unit`/already-errored, which are \
+                treated as satisfying any expected type).",
+            example: "observer {} { 42 }                     // error\nobserver {} { [] }                    // ok",
+        },
+    ),
+    (
+        "mutator-return-type",
+        ErrorExplanation {
+            summary: "a `mutator`'s body doesn't return `Event`",
+            explanation: "A mutator produces exactly one `Event` in response to a command, so \
+                its body must evaluate to `Event` (or `Unit`/already-errored).",
+            example: "mutator {} { [] }                      // error",
+        },
+    ),
+];
+
+/// Look up the extended explanation for one of [`TypeError::code`]'s stable
+/// identifiers (e.g. `"non-future-operand"`) - the entry point a `hearthd
+/// explain <code>` command would call. Returns `None` for `"generic"` and
+/// any code not yet given a writeup.
+pub fn explain(code: &str) -> Option<&'static ErrorExplanation> {
+    ERROR_EXPLANATIONS
+        .iter()
+        .find(|(known_code, _)| *known_code == code)
+        .map(|(_, explanation)| explanation)
+}
+
+/// A zero-indexed `(line, character)` position, matching the LSP
+/// `Position` type (`character` is a UTF-16 code unit offset, not a byte
+/// offset).
+#[derive(Debug, Clone, Serialize)]
+struct LspPosition {
+    line: usize,
+    character: usize,
+}
+
+/// A half-open `[start, end)` range over a single file, matching the LSP
+/// `Range` type.
+#[derive(Debug, Clone, Serialize)]
+struct LspRange {
+    start: LspPosition,
+    end: LspPosition,
+}
+
+/// A location in a specific file, matching the LSP `Location` type.
+#[derive(Debug, Clone, Serialize)]
+struct LspLocation {
+    uri: String,
+    range: LspRange,
+}
+
+/// One `relatedInformation` entry on an LSP `Diagnostic`, built from a
+/// [`SecondaryLabel`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LspRelatedInformation {
+    location: LspLocation,
+    message: std::string::String,
+}
+
+/// A single LSP-compatible `Diagnostic`, the JSON counterpart to the
+/// ariadne report [`format_type_errors`] renders for one [`TypeError`].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LspDiagnostic {
+    range: LspRange,
+    /// LSP's `DiagnosticSeverity`: 1 = Error, 2 = Warning. This checker
+    /// never produces Information (3) or Hint (4) severities today.
+    severity: u8,
+    code: &'static str,
+    message: std::string::String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    related_information: Option<Vec<LspRelatedInformation>>,
+}
+
+fn lsp_range(cache: &SourceCache, file: ast::FileId, span: SimpleSpan) -> LspRange {
+    let (start_line, start_character) = cache.position(file, span.start);
+    let (end_line, end_character) = cache.position(file, span.end);
+    LspRange {
+        start: LspPosition {
+            line: start_line,
+            character: start_character,
+        },
+        end: LspPosition {
+            line: end_line,
+            character: end_character,
+        },
+    }
+}
+
+/// Serialize `errors` into an LSP-compatible JSON diagnostics array -
+/// editors and CI consume structured output, while [`format_type_errors`]'
+/// ariadne report is for human eyes only.
+///
+/// Each secondary label becomes a `relatedInformation` entry; offsets are
+/// converted to `(line, column)` positions via `cache`'s line index, built
+/// once per file rather than rescanned per error.
+pub fn format_type_errors_json(errors: &[TypeError], cache: &SourceCache) -> String {
+    let diagnostics: Vec<LspDiagnostic> = errors
+        .iter()
+        .map(|error| LspDiagnostic {
+            range: lsp_range(cache, error.file, error.span),
+            severity: match error.severity {
+                Severity::Error => 1,
+                Severity::Warning => 2,
+            },
+            code: error.code,
+            message: error.message.clone(),
+            related_information: (!error.secondary.is_empty()).then(|| {
+                error
+                    .secondary
+                    .iter()
+                    .map(|secondary| LspRelatedInformation {
+                        location: LspLocation {
+                            uri: cache.name(secondary.file).to_string(),
+                            range: lsp_range(cache, secondary.file, secondary.span),
+                        },
+                        message: secondary.message.clone(),
+                    })
+                    .collect()
+            }),
+        })
+        .collect();
+
+    serde_json::to_string(&diagnostics).unwrap_or_else(|_| "[]".to_string())
+}
@@ -1,6 +1,14 @@
 use super::check_program;
+use super::explain;
 use super::format_type_errors;
+use super::format_type_errors_json;
+use super::RenderConfig;
+use super::SourceCache;
+use super::ERROR_EXPLANATIONS;
 use crate::automations::repr::pretty_print::PrettyPrint;
+use crate::automations::repr::typed::{
+    TypedArg, TypedAutomation, TypedExpr, TypedExprKind, TypedProgram, TypedStmt, TypedStructField,
+};
 
 fn check_and_pretty(input: &str) -> String {
     let program = crate::automations::parse(input).expect("parsing should succeed");
@@ -33,10 +41,145 @@ fn check_errors(input: &str) -> String {
     let program = crate::automations::parse(input).expect("parsing should succeed");
     let lowered = crate::automations::desugar_program(program);
     let result = check_program(&lowered);
-    let rendered = format_type_errors(&result.errors, input, "<test>");
+    let mut cache = SourceCache::single("<test>", input);
+    let rendered = format_type_errors(&result.errors, &mut cache, &RenderConfig::default());
     strip_ansi(&rendered)
 }
 
+/// Parse, desugar, and check `input`, then dump every sub-expression's
+/// source span, source text, and resolved type - one `start..end 'slice':
+/// Type` line per node, innermost first. Complements `check_errors`: that
+/// locks down *failed* checks, this locks down *successful* inference, so a
+/// silent type regression (one that produces no diagnostic) still shows up
+/// as a snapshot diff.
+fn check_inference(input: &str) -> String {
+    let program = crate::automations::parse(input).expect("parsing should succeed");
+    let lowered = crate::automations::desugar_program(program);
+    let result = check_program(&lowered);
+    let mut lines = Vec::new();
+    match &result.program {
+        TypedProgram::Automation(automation) => {
+            dump_automation(automation, input, &mut lines);
+        }
+        TypedProgram::Template { automations, .. } => {
+            for automation in automations {
+                dump_automation(automation, input, &mut lines);
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+fn dump_automation(automation: &TypedAutomation, source: &str, lines: &mut Vec<String>) {
+    if let Some(filter) = &automation.filter {
+        dump_expr(filter, source, lines);
+    }
+    dump_stmts(&automation.body, source, lines);
+}
+
+fn dump_stmts(stmts: &[TypedStmt], source: &str, lines: &mut Vec<String>) {
+    for stmt in stmts {
+        dump_stmt(stmt, source, lines);
+    }
+}
+
+fn dump_stmt(stmt: &TypedStmt, source: &str, lines: &mut Vec<String>) {
+    match stmt {
+        TypedStmt::Let { value, .. } | TypedStmt::LetMut { value, .. } => {
+            dump_expr(value, source, lines);
+        }
+        TypedStmt::Expr(expr) => dump_expr(expr, source, lines),
+        TypedStmt::Return(expr, _) => dump_expr(expr, source, lines),
+        TypedStmt::For { iter, body, .. } => {
+            dump_expr(iter, source, lines);
+            dump_stmts(body, source, lines);
+        }
+        TypedStmt::Push { value, .. } => dump_expr(value, source, lines),
+        TypedStmt::While { cond, body, .. } => {
+            dump_expr(cond, source, lines);
+            dump_stmts(body, source, lines);
+        }
+        TypedStmt::CompoundAssign { value, .. } => dump_expr(value, source, lines),
+    }
+}
+
+/// Visit `expr`'s children before `expr` itself, so a wrapping expression
+/// that starts at the same offset as its first child (e.g. `state.lights`
+/// and `state`) is listed after the child it contains, matching how a
+/// reader would want to confirm the innermost types before the ones built
+/// from them.
+fn dump_expr(expr: &TypedExpr, source: &str, lines: &mut Vec<String>) {
+    match &expr.kind {
+        TypedExprKind::Int(_)
+        | TypedExprKind::Float(_)
+        | TypedExprKind::String(_)
+        | TypedExprKind::Bool(_)
+        | TypedExprKind::UnitLiteral { .. }
+        | TypedExprKind::Ident(_)
+        | TypedExprKind::Path(_)
+        | TypedExprKind::MutableList => {}
+        TypedExprKind::BinOp { left, right, .. } => {
+            dump_expr(left, source, lines);
+            dump_expr(right, source, lines);
+        }
+        TypedExprKind::UnaryOp { expr: inner, .. } => dump_expr(inner, source, lines),
+        TypedExprKind::Field { expr: inner, .. }
+        | TypedExprKind::OptionalField { expr: inner, .. } => {
+            dump_expr(inner, source, lines);
+        }
+        TypedExprKind::Call { func, args } => {
+            dump_expr(func, source, lines);
+            for arg in args {
+                match arg {
+                    TypedArg::Positional(value) => dump_expr(value, source, lines),
+                    TypedArg::Named { value, .. } => dump_expr(value, source, lines),
+                }
+            }
+        }
+        TypedExprKind::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            dump_expr(cond, source, lines);
+            dump_stmts(then_block, source, lines);
+            if let Some(else_block) = else_block {
+                dump_stmts(else_block, source, lines);
+            }
+        }
+        TypedExprKind::List(items) | TypedExprKind::Tuple(items) => {
+            for item in items {
+                dump_expr(item, source, lines);
+            }
+        }
+        TypedExprKind::StructLit { fields, .. } => {
+            for field in fields {
+                if let TypedStructField::Field { value, .. } = field {
+                    dump_expr(value, source, lines);
+                }
+            }
+        }
+        TypedExprKind::Block { stmts, result } => {
+            dump_stmts(stmts, source, lines);
+            dump_expr(result, source, lines);
+        }
+        TypedExprKind::Match { scrutinee, arms } => {
+            dump_expr(scrutinee, source, lines);
+            for arm in arms {
+                dump_stmts(&arm.body, source, lines);
+            }
+        }
+        TypedExprKind::Lambda { body, .. } => dump_expr(body, source, lines),
+    }
+
+    let span = expr.origin.span();
+    let slice = source.get(span.start..span.end).unwrap_or("<error>");
+    lines.push(format!(
+        "{}..{} '{}': {}",
+        span.start, span.end, slice, expr.ty
+    ));
+}
+
 // =============================================================================
 // Literal type checking
 // =============================================================================
@@ -146,6 +289,57 @@ fn test_check_observer_unit_literal_temperature() {
     ");
 }
 
+#[test]
+fn test_check_same_dimension_unit_arithmetic() {
+    let result = check_and_pretty("observer {} { 5min + 2h }");
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+      Body:
+        ExprStmt:
+          BinOp: + [type: Duration]
+            UnitLiteral: 5min [type: Duration]
+            UnitLiteral: 2h [type: Duration]
+    Errors:
+      type error at 14..23: observer body must return [Event], found Duration
+    ");
+}
+
+#[test]
+fn test_check_unit_scaled_by_number() {
+    let result = check_and_pretty("observer {} { 5min * 2 }");
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+      Body:
+        ExprStmt:
+          BinOp: * [type: Duration]
+            UnitLiteral: 5min [type: Duration]
+            Int: 2 [type: Int]
+    Errors:
+      type error at 14..22: observer body must return [Event], found Duration
+    ");
+}
+
+#[test]
+fn test_check_same_dimension_ratio() {
+    let result = check_and_pretty("observer {} { 10min / 2min }");
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+      Body:
+        ExprStmt:
+          BinOp: / [type: Float]
+            UnitLiteral: 10min [type: Duration]
+            UnitLiteral: 2min [type: Duration]
+    Errors:
+      type error at 14..27: observer body must return [Event], found Float
+    ");
+}
+
 // =============================================================================
 // Variable binding and lookup
 // =============================================================================
@@ -422,6 +616,121 @@ fn test_check_unknown_enum_variant() {
     ");
 }
 
+// =============================================================================
+// Match expressions
+// =============================================================================
+
+#[test]
+fn test_check_match_exhaustive_bindings() {
+    let result = check_and_pretty(
+        "observer { event, ... } /true/ { match event { Event::LightStateChanged(id) => { id }, Event::BinarySensorStateChanged(id) => { id } } }",
+    );
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+          FieldPattern: event
+          Rest: ...
+      Filter:
+        Bool: true [type: Bool]
+      Body:
+        ExprStmt:
+          Match: [type: String]
+            Scrutinee:
+              Ident: event [type: Event]
+            Arm:
+              MatchPatternVariant: Event::LightStateChanged(
+                BindingIdent: id
+              )
+              Body:
+                ExprStmt:
+                  Ident: id [type: String]
+            Arm:
+              MatchPatternVariant: Event::BinarySensorStateChanged(
+                BindingIdent: id
+              )
+              Body:
+                ExprStmt:
+                  Ident: id [type: String]
+    Errors:
+      type error at 33..134: observer body must return [Event], found String
+    ");
+}
+
+#[test]
+fn test_check_match_non_exhaustive() {
+    let result = check_and_pretty(
+        "observer { event, ... } /true/ { match event { Event::LightStateChanged(id) => { id } } }",
+    );
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+          FieldPattern: event
+          Rest: ...
+      Filter:
+        Bool: true [type: Bool]
+      Body:
+        ExprStmt:
+          Match: [type: String]
+            Scrutinee:
+              Ident: event [type: Event]
+            Arm:
+              MatchPatternVariant: Event::LightStateChanged(
+                BindingIdent: id
+              )
+              Body:
+                ExprStmt:
+                  Ident: id [type: String]
+    Errors:
+      type error at 33..87: non-exhaustive match: missing variant(s) BinarySensorStateChanged
+      type error at 33..87: observer body must return [Event], found String
+    ");
+}
+
+#[test]
+fn test_check_match_unreachable_duplicate_variant() {
+    let result = check_and_pretty(
+        r#"observer { event, ... } /true/ { match event { Event::LightStateChanged(id) => { id }, Event::LightStateChanged(x) => { x }, _ => { "none" } } }"#,
+    );
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+          FieldPattern: event
+          Rest: ...
+      Filter:
+        Bool: true [type: Bool]
+      Body:
+        ExprStmt:
+          Match: [type: String]
+            Scrutinee:
+              Ident: event [type: Event]
+            Arm:
+              MatchPatternVariant: Event::LightStateChanged(
+                BindingIdent: id
+              )
+              Body:
+                ExprStmt:
+                  Ident: id [type: String]
+            Arm:
+              MatchPatternVariant: Event::LightStateChanged(
+                BindingIdent: x
+              )
+              Body:
+                ExprStmt:
+                  Ident: x [type: String]
+            Arm:
+              MatchPatternWildcard
+              Body:
+                ExprStmt:
+                  String: \"none\" [type: String]
+    Errors:
+      type error at 87..114: unreachable match arm: variant 'Event::LightStateChanged' already covered
+      type error at 33..142: observer body must return [Event], found String
+    ");
+}
+
 // =============================================================================
 // Built-in function calls
 // =============================================================================
@@ -578,7 +887,7 @@ fn test_check_list_comp() {
           Block: [type: [Event]]
             Stmts:
               LetMut: __result0
-                MutableList [type: [<error>]]
+                MutableList [type: [Event]]
               For:
                 Var: l
                 Iter:
@@ -599,6 +908,55 @@ fn test_check_list_comp() {
     ");
 }
 
+#[test]
+fn test_check_list_comp_resolved_outside_tail() {
+    // The comprehension lives in a `let` (not the tail `ExprStmt` the
+    // previous test covers), so this only passes if `finalize_stmts` walks
+    // every statement in the body, not just the one returned - the element
+    // type is unified deep inside `Push` but must show up resolved all the
+    // way back up at the `MutableList` node several statements earlier.
+    let result = check_and_pretty(
+        "observer { state = { lights, ... }, ... } /true/ { let evts = [Event::LightStateChanged(l) for l in keys(lights)]; evts }",
+    );
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Pattern:
+        PatternStruct:
+          FieldPattern: state
+            PatternStruct:
+              FieldPattern: lights
+              Rest: ...
+          Rest: ...
+      Filter:
+        Bool: true [type: Bool]
+      Body:
+        Let: evts
+          Block: [type: [Event]]
+            Stmts:
+              LetMut: __result0
+                MutableList [type: [Event]]
+              For:
+                Var: l
+                Iter:
+                  Call: [type: [String]]
+                    Ident: keys [type: <error>]
+                    Args:
+                      Ident: lights [type: Map<String, LightState>]
+                Body:
+                  Push: __result0
+                    Call: [type: Event]
+                      Path: [type: Event::LightStateChanged]
+                        Segment: Event
+                        Segment: LightStateChanged
+                      Args:
+                        Ident: l [type: String]
+            Result:
+              Ident: __result0 [type: [Event]]
+        ExprStmt:
+          Ident: evts [type: [Event]]
+    ");
+}
+
 // =============================================================================
 // Struct literals
 // =============================================================================
@@ -660,9 +1018,7 @@ fn test_check_filter_bool() {
         Bool: true [type: Bool]
       Body:
         ExprStmt:
-          List: (empty) [type: [<error>]]
-    Errors:
-      type error at 33..35: observer body must return [Event], found [<error>]
+          List: (empty) [type: [Event]]
     ");
 }
 
@@ -736,7 +1092,7 @@ fn test_check_lights_off_automation() {
           Block: [type: [Event]]
             Stmts:
               LetMut: __result0
-                MutableList [type: [<error>]]
+                MutableList [type: [Event]]
               For:
                 Var: l
                 Iter:
@@ -813,6 +1169,20 @@ fn test_error_arithmetic_on_strings() {
     "#);
 }
 
+#[test]
+fn test_error_cross_dimension_arithmetic() {
+    let result = check_errors("observer {} { 5min + 1deg }");
+    insta::assert_snapshot!(result, @r#"
+    Error: arithmetic operator '+' requires operands of the same dimension, found Duration (time) and Angle (angle)
+       ╭─[ <test>:1:15 ]
+       │
+     1 │ observer {} { 5min + 1deg }
+       │               ─────┬─────  
+       │                    ╰─────── arithmetic operator '+' requires operands of the same dimension, found Duration (time) and Angle (angle)
+    ───╯
+    "#);
+}
+
 #[test]
 fn test_error_comparison_on_strings() {
     let result = check_errors(r#"observer {} { "a" > "b" }"#);
@@ -964,13 +1334,6 @@ fn test_error_filter_not_bool() {
        │              ─┬  
        │               ╰── filter must be Bool, found Int
     ───╯
-    Error: observer body must return [Event], found [<error>]
-       ╭─[ <test>:1:20 ]
-       │
-     1 │ observer {} /42/ { [] }
-       │                    ─┬  
-       │                     ╰── observer body must return [Event], found [<error>]
-    ───╯
     ");
 }
 
@@ -978,11 +1341,13 @@ fn test_error_filter_not_bool() {
 fn test_error_observer_wrong_return() {
     let result = check_errors("observer {} { 42 }");
     insta::assert_snapshot!(result, @"
-    Error: observer body must return [Event], found Int
+    Error[observer-return-type]: observer body must return [Event], found Int
        ╭─[ <test>:1:15 ]
        │
      1 │ observer {} { 42 }
-       │               ─┬  
+       │ ────┬───      ─┬
+       │     ╰──────────── expected because this is an `observer`
+       │                │
        │                ╰── observer body must return [Event], found Int
     ───╯
     ");
@@ -992,11 +1357,13 @@ fn test_error_observer_wrong_return() {
 fn test_error_mutator_wrong_return() {
     let result = check_errors("mutator {} { [] }");
     insta::assert_snapshot!(result, @"
-    Error: mutator body must return Event, found [<error>]
+    Error[mutator-return-type]: mutator body must return Event, found [<error>]
        ╭─[ <test>:1:14 ]
        │
      1 │ mutator {} { [] }
-       │              ─┬  
+       │ ───┬───      ─┬
+       │    ╰──────────── expected because this is a `mutator`
+       │               │
        │               ╰── mutator body must return Event, found [<error>]
     ───╯
     ");
@@ -1006,16 +1373,34 @@ fn test_error_mutator_wrong_return() {
 fn test_error_unknown_field() {
     let result = check_errors("observer { state, ... } /true/ { state.nonexistent }");
     insta::assert_snapshot!(result, @"
-    Error: no field 'nonexistent' on type State
+    Error[unknown-field]: no field 'nonexistent' on type State
        ╭─[ <test>:1:34 ]
        │
      1 │ observer { state, ... } /true/ { state.nonexistent }
-       │                                  ────────┬────────  
+       │                                  ──┬──
+       │                                    ╰──────────────── this is of type State
+       │                                  ────────┬────────
        │                                          ╰────────── no field 'nonexistent' on type State
     ───╯
     ");
 }
 
+#[test]
+fn test_error_cannot_infer_element_type() {
+    let result = check_errors("observer {} { let mut xs = []; xs }");
+    insta::assert_snapshot!(result, @"
+    Error[cannot-infer-element-type]: cannot infer element type: [?0]
+       ╭─[ <test>:1:32 ]
+       │
+     1 │ observer {} { let mut xs = []; xs }
+       │                                ─┬
+       │                                 ╰── cannot infer element type: [?0]
+       │
+       │ Note: push a value onto it so its element type can be inferred
+    ───╯
+    ");
+}
+
 #[test]
 fn test_error_sleep_wrong_arg() {
     let result = check_errors("observer {} { sleep(42) }");
@@ -1090,13 +1475,6 @@ fn test_error_for_non_iterable() {
        │                           ─┬  
        │                            ╰── cannot iterate over Int
     ───╯
-    Error: observer body must return [Event], found [<error>]
-       ╭─[ <test>:1:15 ]
-       │
-     1 │ observer {} { [x for x in 42] }
-       │               ───────┬───────  
-       │                      ╰───────── observer body must return [Event], found [<error>]
-    ───╯
     ");
 }
 
@@ -1120,3 +1498,200 @@ fn test_error_multiple_errors() {
     ───╯
     "#);
 }
+
+#[test]
+fn test_json_diagnostics_range_and_severity() {
+    let input = "observer {} { unknown }";
+    let program = crate::automations::parse(input).expect("parsing should succeed");
+    let lowered = crate::automations::desugar_program(program);
+    let result = check_program(&lowered);
+    let cache = SourceCache::single("<test>", input);
+    let json = format_type_errors_json(&result.errors, &cache);
+    insta::assert_snapshot!(json, @r#"[{"range":{"start":{"line":0,"character":14},"end":{"line":0,"character":21}},"severity":1,"code":"generic","message":"undefined variable 'unknown'"}]"#);
+}
+
+// =============================================================================
+// Inference dump tests (check_inference)
+// =============================================================================
+
+#[test]
+fn test_inference_binop() {
+    let result = check_inference("observer {} { 1 + 2 }");
+    insta::assert_snapshot!(result, @r"
+    14..15 '1': Int
+    18..19 '2': Int
+    14..19 '1 + 2': Int
+    ");
+}
+
+#[test]
+fn test_inference_field_access() {
+    let result = check_inference("observer { state, ... } /true/ { state.lights }");
+    insta::assert_snapshot!(result, @"
+    25..29 'true': Bool
+    33..38 'state': State
+    33..45 'state.lights': Map<String, LightState>
+    ");
+}
+
+#[test]
+fn test_inference_enum_variant_construction() {
+    let result = check_inference(r#"observer {} { Event::LightStateChanged("kitchen") }"#);
+    insta::assert_snapshot!(result, @r#"
+    14..38 'Event::LightStateChanged': Event::LightStateChanged
+    39..48 '"kitchen"': String
+    14..49 'Event::LightStateChanged("kitchen")': Event
+    "#);
+}
+
+#[test]
+fn test_inference_struct_literal_construction() {
+    let result = check_inference("observer {} { Event { device: \"lamp\" } }");
+    insta::assert_snapshot!(result, @r#"
+    30..36 '"lamp"': String
+    14..38 'Event { device: "lamp" }': Event
+    "#);
+}
+
+#[test]
+fn test_inference_await() {
+    let result = check_inference("observer {} { await sleep(5min) }");
+    insta::assert_snapshot!(result, @"
+    20..25 'sleep': <error>
+    26..30 '5min': Duration
+    20..31 'sleep(5min)': Future<()>
+    14..31 'await sleep(5min)': ()
+    ");
+}
+
+#[test]
+fn test_inference_list_comprehension() {
+    let result = check_inference("observer {} { [x for x in [1, 2]] }");
+    // The comprehension desugars to a `Block` wrapping a `LetMut`/`For`/
+    // `Push`, with the loop's accumulator `Ident` as its result - every
+    // synthetic node (the `MutableList`, the result `Ident`, and the `Block`
+    // itself) shares the original comprehension's span, since none of them
+    // correspond to a distinct piece of the original source.
+    insta::assert_snapshot!(result, @"
+    14..33 '[x for x in [1, 2]]': [Int]
+    27..28 '1': Int
+    30..31 '2': Int
+    26..32 '[1, 2]': [Int]
+    15..16 'x': Int
+    14..33 '[x for x in [1, 2]]': [Int]
+    14..33 '[x for x in [1, 2]]': [Int]
+    ");
+}
+
+#[test]
+fn test_render_config_ascii_no_color() {
+    let input = "observer {} { unknown }";
+    let program = crate::automations::parse(input).expect("parsing should succeed");
+    let lowered = crate::automations::desugar_program(program);
+    let result = check_program(&lowered);
+    let mut cache = SourceCache::single("<test>", input);
+    let config = RenderConfig {
+        ascii: true,
+        color: false,
+        ..RenderConfig::default()
+    };
+    let rendered = format_type_errors(&result.errors, &mut cache, &config);
+    // No ANSI escapes and no Unicode box-drawing characters - just the
+    // plain ASCII/no-color rendering `RenderConfig` asked for.
+    assert!(!rendered.contains('\x1b'));
+    assert!(!rendered.contains('╭'));
+    assert!(rendered.contains("undefined variable 'unknown'"));
+}
+
+#[test]
+fn test_explain_known_code() {
+    let explanation = explain("non-future-operand").expect("should have a writeup");
+    assert_eq!(
+        explanation.summary,
+        "`await` applied to a value that isn't a `Future`"
+    );
+    assert!(explanation.example.contains("await 42"));
+}
+
+#[test]
+fn test_explain_unknown_code() {
+    assert!(explain("generic").is_none());
+    assert!(explain("not-a-real-code").is_none());
+}
+
+#[test]
+fn test_every_emitted_code_has_an_explanation() {
+    // A representative program per non-generic code this checker currently
+    // produces - catches a new `.with_code(...)` call site (or a removed
+    // one) that forgot to update `ERROR_EXPLANATIONS` to match.
+    let inputs = [
+        "observer {} { -true }",
+        "observer {} { !42 }",
+        "observer {} { 1 && 2 }",
+        "observer {} { await 42 }",
+        "observer {} { 1 in 2 }",
+        r#"observer {} { "hello" + 1 }"#,
+        "observer {} { 5min + 1deg }",
+        "observer {} { 5min * 2min }",
+        "observer {} { unknown }",
+        "observer {} { frobnicate(1) }",
+        "observer {} { Event::Nope }",
+        "observer {} { Bogus { x: 1 } }",
+        "observer { state, ... } /true/ { state.nonexistent }",
+        "observer {} { (1, 2).5 }",
+        "observer {} { let mut xs = []; xs }",
+        "observer {} { if true { 1 } else { \"no\" } }",
+        "observer {} { 42 }",
+        "mutator {} { [] }",
+        "template Foo(x: Tempurature) { observer {} { 1 } }",
+    ];
+
+    let mut seen_codes = std::collections::HashSet::new();
+    for input in inputs {
+        let program = crate::automations::parse(input).expect("parsing should succeed");
+        let lowered = crate::automations::desugar_program(program);
+        let result = check_program(&lowered);
+        for error in &result.errors {
+            if error.code != "generic" {
+                seen_codes.insert(error.code);
+                assert!(
+                    explain(error.code).is_some(),
+                    "missing ErrorExplanation for code {:?}",
+                    error.code
+                );
+            }
+        }
+    }
+    assert_eq!(seen_codes.len(), ERROR_EXPLANATIONS.len());
+}
+
+#[test]
+fn test_error_provenance_points_at_let_binding() {
+    // `a`'s type came from its `let` initializer, several characters before
+    // the `+` that rejects it - the diagnostic should point at both.
+    let result = check_errors(r#"observer {} { let a = "hi"; a + 1 }"#);
+    assert!(
+        result.contains("this is String because it was bound here"),
+        "missing provenance label: {result}"
+    );
+}
+
+#[test]
+fn test_error_provenance_absent_for_literal_operand() {
+    // A literal's own span already is its origin, so there's nothing
+    // further upstream to point at - no provenance label should appear.
+    let result = check_errors(r#"observer {} { "hello" + 1 }"#);
+    assert!(
+        !result.contains("because it was bound here"),
+        "unexpected provenance label: {result}"
+    );
+}
+
+#[test]
+fn test_error_provenance_on_unary_operand() {
+    let result = check_errors("observer {} { let flag = 1; !flag }");
+    assert!(
+        result.contains("this is Int because it was bound here"),
+        "missing provenance label: {result}"
+    );
+}
@@ -0,0 +1,535 @@
+//! Dead-binding elimination pass over the lowered AST.
+//!
+//! Runs after [`super::desugar`] (and typically after [`super::simplify`],
+//! though it doesn't require that ordering), removing `Let`/`LetMut`
+//! bindings whose value is never referenced - dead user-written `let`s, and
+//! the fresh `__resultN` accumulators [`super::desugar`] generates for list
+//! comprehensions once a later pass has inlined away whatever used to read
+//! them.
+//!
+//! Implemented as a classic backward liveness analysis: each statement list
+//! is walked in reverse maintaining a set of names live past that point. A
+//! `Let`/`LetMut` is dropped only if its bound name isn't live *and* its
+//! value is side-effect-free (no `Call` anywhere in it, and - since a list,
+//! dict, or set comprehension's desugared `for`/`push`/`insert`/`add` loop is
+//! exactly the kind of thing that can't be proven side-effect-free by this
+//! pass alone - no nested `Push`, `Insert`, `Add`, `for`, `Expr`, or `Return`
+//! statement either, and no bare `MutableList`/`MutableMap`/`MutableSet`).
+//! Every statement kind other than `Let`/`LetMut` is always retained, so a
+//! side effect is never dropped just because nothing reads the value it
+//! produces.
+//!
+//! `Block`/`If`/`For` bodies, and `match` arms, are each analyzed with their
+//! own scoped live-set - seeded from the block's trailing `result` for
+//! `Block`, empty otherwise - so a name they bind locally (a `let`, a `for`
+//! loop variable, a match binding) never leaks into the enclosing scope's
+//! liveness. Whatever in that scoped set remains free (not bound locally)
+//! is unioned back into the enclosing live-set before moving on, so an
+//! enclosing `let` referenced only from inside a nested block is still
+//! correctly kept alive.
+
+use std::collections::HashSet;
+
+use super::diagnostics::OriginDiagnostic;
+use super::repr::ast::BindingPattern;
+use super::repr::ast::MatchPattern;
+use super::repr::lowered::LoweredArg;
+use super::repr::lowered::LoweredAutomation;
+use super::repr::lowered::LoweredExpr;
+use super::repr::lowered::LoweredMatchArm;
+use super::repr::lowered::LoweredProgram;
+use super::repr::lowered::LoweredStmt;
+use super::repr::lowered::LoweredStructField;
+use super::repr::lowered::Spanned;
+
+#[cfg(test)]
+mod tests;
+
+/// Names live past the current point in a reverse statement walk.
+type Live = HashSet<String>;
+
+/// Eliminate dead `Let`/`LetMut` bindings from a complete lowered program
+/// (see module docs), returning the transformed program alongside one
+/// [`OriginDiagnostic`] per binding removed, so callers can surface them
+/// (e.g. as an "unused variable" note) instead of silently dropping code.
+pub fn eliminate_dead_bindings(program: LoweredProgram) -> (LoweredProgram, Vec<OriginDiagnostic>) {
+    let mut removed = Vec::new();
+    let program = match program {
+        LoweredProgram::Automation(automation) => {
+            LoweredProgram::Automation(eliminate_automation(automation, &mut removed))
+        }
+        LoweredProgram::Template {
+            params,
+            automations,
+            file,
+        } => LoweredProgram::Template {
+            params,
+            automations: automations
+                .into_iter()
+                .map(|automation| eliminate_automation(automation, &mut removed))
+                .collect(),
+            file,
+        },
+    };
+    (program, removed)
+}
+
+fn eliminate_automation(
+    automation: LoweredAutomation,
+    removed: &mut Vec<OriginDiagnostic>,
+) -> LoweredAutomation {
+    let (body, _) = eliminate_stmts(automation.body, Live::new(), removed);
+    LoweredAutomation {
+        kind: automation.kind,
+        kind_span: automation.kind_span,
+        pattern: automation.pattern,
+        filter: automation
+            .filter
+            .map(|filter| eliminate_expr(filter, removed).0),
+        body,
+        file: automation.file,
+    }
+}
+
+/// Eliminate dead bindings from a statement list walked in reverse, seeded
+/// with `live` (names already known live past the end of this list - e.g. a
+/// `Block`'s trailing `result`). Returns the transformed statements and the
+/// set of names this list references but doesn't bind itself, for the
+/// caller to union into its own enclosing live-set.
+fn eliminate_stmts(
+    stmts: Vec<Spanned<LoweredStmt>>,
+    mut live: Live,
+    removed: &mut Vec<OriginDiagnostic>,
+) -> (Vec<Spanned<LoweredStmt>>, Live) {
+    let mut out = Vec::with_capacity(stmts.len());
+
+    for stmt in stmts.into_iter().rev() {
+        let Spanned { node, origin } = stmt;
+        match node {
+            LoweredStmt::Let { name, value } => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                if !live.contains(&name) && is_side_effect_free(&value.node) {
+                    removed.push(OriginDiagnostic {
+                        message: format!("unused binding `{name}`"),
+                        origin,
+                    });
+                    continue;
+                }
+                live.extend(value_free);
+                live.remove(&name);
+                out.push(Spanned::new(LoweredStmt::Let { name, value }, origin));
+            }
+            LoweredStmt::LetMut { name, value } => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                if !live.contains(&name) && is_side_effect_free(&value.node) {
+                    removed.push(OriginDiagnostic {
+                        message: format!("unused binding `{name}`"),
+                        origin,
+                    });
+                    continue;
+                }
+                live.extend(value_free);
+                live.remove(&name);
+                out.push(Spanned::new(LoweredStmt::LetMut { name, value }, origin));
+            }
+            LoweredStmt::Expr(value) => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(value_free);
+                out.push(Spanned::new(LoweredStmt::Expr(value), origin));
+            }
+            LoweredStmt::Return(value) => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(value_free);
+                out.push(Spanned::new(LoweredStmt::Return(value), origin));
+            }
+            LoweredStmt::Push { list, value } => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(value_free);
+                live.insert(list.clone());
+                out.push(Spanned::new(LoweredStmt::Push { list, value }, origin));
+            }
+            LoweredStmt::Insert { map, key, value } => {
+                let (key, key_free) = eliminate_expr(key, removed);
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(key_free);
+                live.extend(value_free);
+                live.insert(map.clone());
+                out.push(Spanned::new(LoweredStmt::Insert { map, key, value }, origin));
+            }
+            LoweredStmt::Add { set, value } => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(value_free);
+                live.insert(set.clone());
+                out.push(Spanned::new(LoweredStmt::Add { set, value }, origin));
+            }
+            LoweredStmt::CompoundAssign { name, op, value } => {
+                let (value, value_free) = eliminate_expr(value, removed);
+                live.extend(value_free);
+                live.insert(name.clone());
+                out.push(Spanned::new(
+                    LoweredStmt::CompoundAssign { name, op, value },
+                    origin,
+                ));
+            }
+            LoweredStmt::For { var, iter, body } => {
+                let (body, mut body_live) = eliminate_stmts(body, Live::new(), removed);
+                body_live.remove(&var);
+                let (iter, iter_live) = eliminate_expr(iter, removed);
+                live.extend(body_live);
+                live.extend(iter_live);
+                out.push(Spanned::new(LoweredStmt::For { var, iter, body }, origin));
+            }
+            LoweredStmt::While { cond, body } => {
+                let (body, body_live) = eliminate_stmts(body, Live::new(), removed);
+                let (cond, cond_live) = eliminate_expr(cond, removed);
+                live.extend(body_live);
+                live.extend(cond_live);
+                out.push(Spanned::new(LoweredStmt::While { cond, body }, origin));
+            }
+        }
+    }
+
+    out.reverse();
+    (out, live)
+}
+
+/// Eliminate dead bindings from a single expression, returning the
+/// transformed expression and the set of free variable names it
+/// references - names used but not bound by a nested `Block`/`If`/`For`/
+/// match arm within it - for the caller to fold into its own live-set.
+fn eliminate_expr(expr: Spanned<LoweredExpr>, removed: &mut Vec<OriginDiagnostic>) -> (Spanned<LoweredExpr>, Live) {
+    let Spanned { node, origin } = expr;
+    match node {
+        LoweredExpr::Ident(name) => {
+            let mut free = Live::new();
+            free.insert(name.clone());
+            (Spanned::new(LoweredExpr::Ident(name), origin), free)
+        }
+        leaf @ (LoweredExpr::Int(_)
+        | LoweredExpr::Float(_)
+        | LoweredExpr::String(_)
+        | LoweredExpr::Bool(_)
+        | LoweredExpr::UnitLiteral { .. }
+        | LoweredExpr::Path(_)
+        | LoweredExpr::MutableList
+        | LoweredExpr::MutableMap
+        | LoweredExpr::MutableSet) => (Spanned::new(leaf, origin), Live::new()),
+        LoweredExpr::BinOp { op, left, right } => {
+            let (left, mut free) = eliminate_expr(*left, removed);
+            let (right, right_free) = eliminate_expr(*right, removed);
+            free.extend(right_free);
+            (
+                Spanned::new(
+                    LoweredExpr::BinOp {
+                        op,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::UnaryOp { op, expr } => {
+            let (expr, free) = eliminate_expr(*expr, removed);
+            (
+                Spanned::new(
+                    LoweredExpr::UnaryOp {
+                        op,
+                        expr: Box::new(expr),
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Field { expr, field } => {
+            let (expr, free) = eliminate_expr(*expr, removed);
+            (
+                Spanned::new(
+                    LoweredExpr::Field {
+                        expr: Box::new(expr),
+                        field,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::OptionalField { expr, field } => {
+            let (expr, free) = eliminate_expr(*expr, removed);
+            (
+                Spanned::new(
+                    LoweredExpr::OptionalField {
+                        expr: Box::new(expr),
+                        field,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Call { func, args } => {
+            let (func, mut free) = eliminate_expr(*func, removed);
+            let mut out_args = Vec::with_capacity(args.len());
+            for arg in args {
+                let (arg, arg_free) = eliminate_arg(arg, removed);
+                free.extend(arg_free);
+                out_args.push(arg);
+            }
+            (
+                Spanned::new(
+                    LoweredExpr::Call {
+                        func: Box::new(func),
+                        args: out_args,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            let (cond, mut free) = eliminate_expr(*cond, removed);
+            let (then_block, then_free) = eliminate_stmts(then_block, Live::new(), removed);
+            free.extend(then_free);
+            let else_block = match else_block {
+                Some(else_block) => {
+                    let (else_block, else_free) = eliminate_stmts(else_block, Live::new(), removed);
+                    free.extend(else_free);
+                    Some(else_block)
+                }
+                None => None,
+            };
+            (
+                Spanned::new(
+                    LoweredExpr::If {
+                        cond: Box::new(cond),
+                        then_block,
+                        else_block,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::List(items) => {
+            let mut free = Live::new();
+            let mut out_items = Vec::with_capacity(items.len());
+            for item in items {
+                let (item, item_free) = eliminate_expr(item, removed);
+                free.extend(item_free);
+                out_items.push(item);
+            }
+            (Spanned::new(LoweredExpr::List(out_items), origin), free)
+        }
+        LoweredExpr::StructLit { name, fields } => {
+            let mut free = Live::new();
+            let mut out_fields = Vec::with_capacity(fields.len());
+            for field in fields {
+                let (field, field_free) = eliminate_field(field, removed);
+                free.extend(field_free);
+                out_fields.push(field);
+            }
+            (
+                Spanned::new(
+                    LoweredExpr::StructLit {
+                        name,
+                        fields: out_fields,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Block { stmts, result } => {
+            let (result, result_free) = eliminate_expr(*result, removed);
+            let (stmts, free) = eliminate_stmts(stmts, result_free, removed);
+            (
+                Spanned::new(
+                    LoweredExpr::Block {
+                        stmts,
+                        result: Box::new(result),
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Match { scrutinee, arms } => {
+            let (scrutinee, mut free) = eliminate_expr(*scrutinee, removed);
+            let mut out_arms = Vec::with_capacity(arms.len());
+            for arm in arms {
+                let (body, mut body_live) = eliminate_stmts(arm.body, Live::new(), removed);
+                for name in pattern_bound_names(&arm.pattern.node) {
+                    body_live.remove(&name);
+                }
+                free.extend(body_live);
+                out_arms.push(LoweredMatchArm {
+                    pattern: arm.pattern,
+                    body,
+                });
+            }
+            (
+                Spanned::new(
+                    LoweredExpr::Match {
+                        scrutinee: Box::new(scrutinee),
+                        arms: out_arms,
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Lambda { params, body } => {
+            let (body, mut free) = eliminate_expr(*body, removed);
+            for param in &params {
+                free.remove(param);
+            }
+            (
+                Spanned::new(
+                    LoweredExpr::Lambda {
+                        params,
+                        body: Box::new(body),
+                    },
+                    origin,
+                ),
+                free,
+            )
+        }
+        LoweredExpr::Tuple(items) => {
+            let mut free = Live::new();
+            let mut out_items = Vec::with_capacity(items.len());
+            for item in items {
+                let (item, item_free) = eliminate_expr(item, removed);
+                free.extend(item_free);
+                out_items.push(item);
+            }
+            (Spanned::new(LoweredExpr::Tuple(out_items), origin), free)
+        }
+    }
+}
+
+fn eliminate_arg(arg: Spanned<LoweredArg>, removed: &mut Vec<OriginDiagnostic>) -> (Spanned<LoweredArg>, Live) {
+    let Spanned { node, origin } = arg;
+    match node {
+        LoweredArg::Positional(value) => {
+            let (value, free) = eliminate_expr(value, removed);
+            (Spanned::new(LoweredArg::Positional(value), origin), free)
+        }
+        LoweredArg::Named { name, value } => {
+            let (value, free) = eliminate_expr(value, removed);
+            (
+                Spanned::new(LoweredArg::Named { name, value }, origin),
+                free,
+            )
+        }
+    }
+}
+
+fn eliminate_field(
+    field: Spanned<LoweredStructField>,
+    removed: &mut Vec<OriginDiagnostic>,
+) -> (Spanned<LoweredStructField>, Live) {
+    let Spanned { node, origin } = field;
+    match node {
+        LoweredStructField::Field { name, value } => {
+            let (value, free) = eliminate_expr(value, removed);
+            (
+                Spanned::new(LoweredStructField::Field { name, value }, origin),
+                free,
+            )
+        }
+        other @ (LoweredStructField::Inherit(_) | LoweredStructField::Spread(_)) => {
+            (Spanned::new(other, origin), Live::new())
+        }
+    }
+}
+
+/// Names a `match` pattern binds within its arm body.
+fn pattern_bound_names(pattern: &MatchPattern) -> Vec<String> {
+    match pattern {
+        MatchPattern::Wildcard => Vec::new(),
+        MatchPattern::Variant { bindings, .. } => bindings
+            .iter()
+            .filter_map(|binding| match &binding.node {
+                BindingPattern::Ident(name) => Some(name.clone()),
+                BindingPattern::Wildcard => None,
+            })
+            .collect(),
+    }
+}
+
+/// Whether `expr` is safe to drop entirely along with its dead binding:
+/// contains no function call, no nested `Push`/`Insert`/`Add`, and no bare
+/// `MutableList`/`MutableMap`/`MutableSet` that could later escape via one.
+fn is_side_effect_free(expr: &LoweredExpr) -> bool {
+    match expr {
+        LoweredExpr::Int(_)
+        | LoweredExpr::Float(_)
+        | LoweredExpr::String(_)
+        | LoweredExpr::Bool(_)
+        | LoweredExpr::UnitLiteral { .. }
+        | LoweredExpr::Ident(_)
+        | LoweredExpr::Path(_) => true,
+        LoweredExpr::MutableList | LoweredExpr::MutableMap | LoweredExpr::MutableSet => false,
+        LoweredExpr::Call { .. } => false,
+        LoweredExpr::BinOp { left, right, .. } => {
+            is_side_effect_free(&left.node) && is_side_effect_free(&right.node)
+        }
+        LoweredExpr::UnaryOp { expr, .. } => is_side_effect_free(&expr.node),
+        LoweredExpr::Field { expr, .. } | LoweredExpr::OptionalField { expr, .. } => {
+            is_side_effect_free(&expr.node)
+        }
+        LoweredExpr::List(items) => items.iter().all(|item| is_side_effect_free(&item.node)),
+        LoweredExpr::StructLit { fields, .. } => fields.iter().all(|field| match &field.node {
+            LoweredStructField::Field { value, .. } => is_side_effect_free(&value.node),
+            LoweredStructField::Inherit(_) | LoweredStructField::Spread(_) => true,
+        }),
+        LoweredExpr::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            is_side_effect_free(&cond.node)
+                && stmts_side_effect_free(then_block)
+                && else_block
+                    .as_ref()
+                    .is_none_or(|block| stmts_side_effect_free(block))
+        }
+        LoweredExpr::Block { stmts, result } => {
+            stmts_side_effect_free(stmts) && is_side_effect_free(&result.node)
+        }
+        LoweredExpr::Match { scrutinee, arms } => {
+            is_side_effect_free(&scrutinee.node)
+                && arms.iter().all(|arm| stmts_side_effect_free(&arm.body))
+        }
+        // Defining a lambda has no side effects regardless of what its body
+        // does - only *calling* it (a `Call` node, handled above) can.
+        LoweredExpr::Lambda { .. } => true,
+        LoweredExpr::Tuple(items) => items.iter().all(|item| is_side_effect_free(&item.node)),
+    }
+}
+
+/// Whether every statement in `stmts` is itself a side-effect-free `Let`/
+/// `LetMut` - the presence of any `Expr`/`Return`/`Push`/`Insert`/`Add`/
+/// `CompoundAssign`/`For`/`While` statement means the list can't be dropped
+/// as a unit.
+fn stmts_side_effect_free(stmts: &[Spanned<LoweredStmt>]) -> bool {
+    stmts.iter().all(|stmt| match &stmt.node {
+        LoweredStmt::Let { value, .. } | LoweredStmt::LetMut { value, .. } => {
+            is_side_effect_free(&value.node)
+        }
+        LoweredStmt::Expr(_)
+        | LoweredStmt::Return(_)
+        | LoweredStmt::Push { .. }
+        | LoweredStmt::Insert { .. }
+        | LoweredStmt::Add { .. }
+        | LoweredStmt::CompoundAssign { .. }
+        | LoweredStmt::For { .. }
+        | LoweredStmt::While { .. } => false,
+    })
+}
@@ -0,0 +1,101 @@
+use super::eliminate_dead_bindings;
+use crate::automations::desugar::Desugarer;
+use crate::automations::parser::parse;
+use crate::automations::repr::lowered::LoweredProgram;
+use crate::automations::repr::lowered::LoweredStmt;
+
+fn dce_str(src: &str) -> (Vec<LoweredStmt>, usize) {
+    let program = parse(src).expect("parsing should succeed");
+    let lowered = Desugarer::new().desugar_program(program);
+    let (program, removed) = eliminate_dead_bindings(lowered);
+    let LoweredProgram::Automation(automation) = program else {
+        panic!("expected a single automation");
+    };
+    (
+        automation.body.into_iter().map(|s| s.node).collect(),
+        removed.len(),
+    )
+}
+
+#[test]
+fn drops_an_unused_let() {
+    let (body, removed) = dce_str("observer {} /true/ { let x = 1; 2; }");
+    assert_eq!(removed, 1);
+    assert_eq!(body.len(), 1);
+    assert!(matches!(body[0], LoweredStmt::Expr(_)));
+}
+
+#[test]
+fn keeps_a_let_referenced_later() {
+    let (body, removed) = dce_str("observer {} /true/ { let x = 1; x; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
+
+#[test]
+fn keeps_an_unused_let_whose_value_calls_a_function() {
+    let (body, removed) = dce_str("observer {} /true/ { let x = foo(); 2; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
+
+#[test]
+fn drops_a_chain_of_unused_lets() {
+    let (body, removed) = dce_str("observer {} /true/ { let x = 1; let y = x + 1; 2; }");
+    assert_eq!(removed, 2);
+    assert_eq!(body.len(), 1);
+}
+
+#[test]
+fn keeps_the_first_of_a_chain_when_the_second_is_used() {
+    let (body, removed) = dce_str("observer {} /true/ { let x = 1; let y = x + 1; y; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 3);
+}
+
+#[test]
+fn a_let_used_only_inside_a_nested_if_stays_live() {
+    let (body, removed) = dce_str(
+        "observer {} /true/ { let x = 1; if true { x; } else { 2; } }",
+    );
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
+
+#[test]
+fn list_comprehension_result_accumulator_survives_when_the_list_is_used() {
+    // Desugaring a list comprehension binds a mutable `__result0` list that
+    // this pass must not remove, since the comprehension's value is used.
+    let (body, removed) = dce_str("observer {} /true/ { let xs = [n * 2 for n in [1, 2, 3]]; xs; }");
+    assert_eq!(removed, 0);
+    assert!(body.iter().any(|stmt| matches!(stmt, LoweredStmt::For { .. })));
+}
+
+#[test]
+fn an_unused_list_comprehension_is_kept_since_its_push_cant_be_proven_pure() {
+    // `xs` itself is dead, but its desugared value is a `Block` whose
+    // stmts include a `for`/`push` loop - conservatively not side-effect
+    // free, so the binding (and the loop building it) survives this pass.
+    let (body, removed) = dce_str("observer {} /true/ { let xs = [n * 2 for n in [1, 2, 3]]; 2; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
+
+#[test]
+fn an_unused_dict_comprehension_is_kept_since_its_insert_cant_be_proven_pure() {
+    // Same reasoning as the list comprehension case above, but for the
+    // `for`/`insert` loop a dict comprehension desugars to.
+    let (body, removed) =
+        dce_str("observer {} /true/ { let xs = {n: n * 2 for n in [1, 2, 3]}; 2; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
+
+#[test]
+fn an_unused_set_comprehension_is_kept_since_its_add_cant_be_proven_pure() {
+    // Same reasoning as the list comprehension case above, but for the
+    // `for`/`add` loop a set comprehension desugars to.
+    let (body, removed) = dce_str("observer {} /true/ { let xs = {n * 2 for n in [1, 2, 3]}; 2; }");
+    assert_eq!(removed, 0);
+    assert_eq!(body.len(), 2);
+}
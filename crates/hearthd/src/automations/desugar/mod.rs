@@ -1,15 +1,18 @@
 //! Desugaring pass for the HearthD Automations language.
 //!
 //! Transforms the high-level AST into a lowered representation where
-//! list comprehensions are expanded into explicit loop constructs.
+//! list, dict, and set comprehensions are expanded into explicit loop
+//! constructs.
 
 use std::rc::Rc;
 
 use super::repr::ast;
 use super::repr::ast::Arg;
+use super::repr::ast::BinOp;
 use super::repr::ast::Expr;
 use super::repr::ast::Stmt;
 use super::repr::ast::StructField;
+use super::repr::ast::UnaryOp;
 use super::repr::lowered::LoweredArg;
 use super::repr::lowered::LoweredAutomation;
 use super::repr::lowered::LoweredExpr;
@@ -25,6 +28,8 @@ mod tests;
 /// State for generating unique variable names during desugaring.
 pub struct Desugarer {
     counter: usize,
+    lower_operators_to_calls: bool,
+    file: ast::FileId,
 }
 
 impl Default for Desugarer {
@@ -35,7 +40,31 @@ impl Default for Desugarer {
 
 impl Desugarer {
     pub fn new() -> Self {
-        Self { counter: 0 }
+        Self {
+            counter: 0,
+            lower_operators_to_calls: false,
+            file: ast::FileId::default(),
+        }
+    }
+
+    /// Lower `BinOp`/`UnaryOp` nodes to calls of fixed builtin functions
+    /// (`__op_add`, `__op_neg`, ...) instead of keeping them as `BinOp`/
+    /// `UnaryOp` lowered-AST nodes, so a backend only has to handle one kind
+    /// of operation node: `Call`. Off by default - existing consumers
+    /// (`interpreter`, `simplify`, `lowered_visit`) still expect to see
+    /// `BinOp`/`UnaryOp` directly, and error messages still want to show
+    /// operator syntax rather than a builtin's name.
+    pub fn with_operator_calls(mut self) -> Self {
+        self.lower_operators_to_calls = true;
+        self
+    }
+
+    /// Stamp every `LoweredAutomation`/`LoweredProgram` this desugarer
+    /// produces with `file` instead of `FileId::default()` - for a caller
+    /// that knows it's desugaring one file among several.
+    pub fn with_file(mut self, file: ast::FileId) -> Self {
+        self.file = file;
+        self
     }
 
     /// Generate a unique variable name for desugared constructs.
@@ -49,13 +78,15 @@ impl Desugarer {
     pub fn desugar_automation(&mut self, auto: ast::Automation) -> LoweredAutomation {
         LoweredAutomation {
             kind: auto.kind,
+            kind_span: auto.kind_span,
             pattern: auto.pattern,
-            filter: auto.filter.map(|f| self.desugar_expr(f)),
+            filter: Some(self.desugar_expr(auto.filter)),
             body: auto
                 .body
                 .into_iter()
                 .map(|s| self.desugar_stmt(s))
                 .collect(),
+            file: self.file,
         }
     }
 
@@ -72,6 +103,7 @@ impl Desugarer {
                     .into_iter()
                     .map(|a| self.desugar_automation(a.node))
                     .collect(),
+                file: self.file,
             },
         }
     }
@@ -115,39 +147,96 @@ impl Desugarer {
 
             // Recursive cases: clone children for origin, move originals to recursive calls
             Expr::BinOp { op, left, right } => {
-                let origin = Origin::Direct(ast::Spanned::new(
-                    Expr::BinOp {
-                        op,
-                        left: left.clone(),
-                        right: right.clone(),
-                    },
-                    span,
-                ));
-                Spanned::new(
-                    LoweredExpr::BinOp {
-                        op,
-                        left: Box::new(self.desugar_expr(*left)),
-                        right: Box::new(self.desugar_expr(*right)),
-                    },
-                    origin,
-                )
+                if self.lower_operators_to_calls {
+                    let original = Rc::new(ast::Spanned::new(
+                        Expr::BinOp {
+                            op,
+                            left: left.clone(),
+                            right: right.clone(),
+                        },
+                        span,
+                    ));
+                    let origin = Origin::Desugared(original);
+                    let func = Spanned::new(
+                        LoweredExpr::Ident(binop_builtin_name(op).to_string()),
+                        origin.clone(),
+                    );
+                    let left = Spanned::new(
+                        LoweredArg::Positional(self.desugar_expr(*left)),
+                        origin.clone(),
+                    );
+                    let right = Spanned::new(
+                        LoweredArg::Positional(self.desugar_expr(*right)),
+                        origin.clone(),
+                    );
+                    Spanned::new(
+                        LoweredExpr::Call {
+                            func: Box::new(func),
+                            args: vec![left, right],
+                        },
+                        origin,
+                    )
+                } else {
+                    let origin = Origin::Direct(ast::Spanned::new(
+                        Expr::BinOp {
+                            op,
+                            left: left.clone(),
+                            right: right.clone(),
+                        },
+                        span,
+                    ));
+                    Spanned::new(
+                        LoweredExpr::BinOp {
+                            op,
+                            left: Box::new(self.desugar_expr(*left)),
+                            right: Box::new(self.desugar_expr(*right)),
+                        },
+                        origin,
+                    )
+                }
             }
 
             Expr::UnaryOp { op, expr: inner } => {
-                let origin = Origin::Direct(ast::Spanned::new(
-                    Expr::UnaryOp {
-                        op,
-                        expr: inner.clone(),
-                    },
-                    span,
-                ));
-                Spanned::new(
-                    LoweredExpr::UnaryOp {
-                        op,
-                        expr: Box::new(self.desugar_expr(*inner)),
-                    },
-                    origin,
-                )
+                if self.lower_operators_to_calls {
+                    let original = Rc::new(ast::Spanned::new(
+                        Expr::UnaryOp {
+                            op,
+                            expr: inner.clone(),
+                        },
+                        span,
+                    ));
+                    let origin = Origin::Desugared(original);
+                    let func = Spanned::new(
+                        LoweredExpr::Ident(unaryop_builtin_name(op).to_string()),
+                        origin.clone(),
+                    );
+                    let arg = Spanned::new(
+                        LoweredArg::Positional(self.desugar_expr(*inner)),
+                        origin.clone(),
+                    );
+                    Spanned::new(
+                        LoweredExpr::Call {
+                            func: Box::new(func),
+                            args: vec![arg],
+                        },
+                        origin,
+                    )
+                } else {
+                    let origin = Origin::Direct(ast::Spanned::new(
+                        Expr::UnaryOp {
+                            op,
+                            expr: inner.clone(),
+                        },
+                        span,
+                    ));
+                    Spanned::new(
+                        LoweredExpr::UnaryOp {
+                            op,
+                            expr: Box::new(self.desugar_expr(*inner)),
+                        },
+                        origin,
+                    )
+                }
             }
 
             Expr::Field { expr: inner, field } => {
@@ -236,6 +325,14 @@ impl Desugarer {
                 )
             }
 
+            Expr::Tuple(items) => {
+                let origin = Origin::Direct(ast::Spanned::new(Expr::Tuple(items.clone()), span));
+                Spanned::new(
+                    LoweredExpr::Tuple(items.into_iter().map(|e| self.desugar_expr(e)).collect()),
+                    origin,
+                )
+            }
+
             Expr::StructLit { name, fields } => {
                 let origin = Origin::Direct(ast::Spanned::new(
                     Expr::StructLit {
@@ -256,27 +353,521 @@ impl Desugarer {
                 )
             }
 
+            Expr::Match { scrutinee, arms } => {
+                let origin = Origin::Direct(ast::Spanned::new(
+                    Expr::Match {
+                        scrutinee: scrutinee.clone(),
+                        arms: arms.clone(),
+                    },
+                    span,
+                ));
+                Spanned::new(
+                    LoweredExpr::Match {
+                        scrutinee: Box::new(self.desugar_expr(*scrutinee)),
+                        arms: arms
+                            .into_iter()
+                            .map(|arm| super::repr::lowered::LoweredMatchArm {
+                                pattern: arm.pattern,
+                                body: arm.body.into_iter().map(|s| self.desugar_stmt(s)).collect(),
+                            })
+                            .collect(),
+                    },
+                    origin,
+                )
+            }
+
+            Expr::Lambda { params, body } => {
+                let origin = Origin::Direct(ast::Spanned::new(
+                    Expr::Lambda {
+                        params: params.clone(),
+                        body: body.clone(),
+                    },
+                    span,
+                ));
+                Spanned::new(
+                    LoweredExpr::Lambda {
+                        params,
+                        body: Box::new(self.desugar_expr(*body)),
+                    },
+                    origin,
+                )
+            }
+
             // The main desugaring: ListComp - uses Rc for sharing
             Expr::ListComp {
                 expr: body_expr,
-                var,
-                iter,
-                filter,
+                clauses,
             } => {
                 let rc = Rc::new(ast::Spanned::new(
                     Expr::ListComp {
                         expr: body_expr.clone(),
-                        var: var.clone(),
-                        iter: iter.clone(),
-                        filter: filter.clone(),
+                        clauses: clauses.clone(),
+                    },
+                    span,
+                ));
+                self.desugar_list_comp(rc, *body_expr, clauses)
+            }
+
+            // Dict comprehension - uses Rc for sharing, mirroring ListComp
+            Expr::DictComp {
+                key,
+                value,
+                clauses,
+            } => {
+                let rc = Rc::new(ast::Spanned::new(
+                    Expr::DictComp {
+                        key: key.clone(),
+                        value: value.clone(),
+                        clauses: clauses.clone(),
+                    },
+                    span,
+                ));
+                self.desugar_dict_comp(rc, *key, *value, clauses)
+            }
+
+            // Set comprehension - uses Rc for sharing, mirroring ListComp
+            Expr::SetComp {
+                expr: body_expr,
+                clauses,
+            } => {
+                let rc = Rc::new(ast::Spanned::new(
+                    Expr::SetComp {
+                        expr: body_expr.clone(),
+                        clauses: clauses.clone(),
                     },
                     span,
                 ));
-                self.desugar_list_comp(rc, *body_expr, var, *iter, filter.map(|f| *f))
+                self.desugar_set_comp(rc, *body_expr, clauses)
             }
         }
     }
 
+    /// Resolve a `for`/comprehension binding pattern to the name that the
+    /// lowered `For` loop actually binds, plus any `Let` statements that
+    /// must run first in the loop body to project the pattern's names out
+    /// of it.
+    ///
+    /// A bare [`ast::BindPattern::Ident`] binds the loop variable directly -
+    /// no extra statements are needed. An [`ast::BindPattern::Tuple`]
+    /// instead binds a fresh temporary as the loop variable and prepends
+    /// `Let`s projecting each element out of it (see
+    /// [`Self::destructure_pattern`]).
+    fn bind_var_pattern(
+        &mut self,
+        origin: &Origin,
+        pattern: ast::Spanned<ast::BindPattern>,
+    ) -> (String, Vec<Spanned<LoweredStmt>>) {
+        match pattern.node {
+            ast::BindPattern::Ident(name) => (name, Vec::new()),
+            ast::BindPattern::Tuple(_) => {
+                let temp = self.fresh_name("pat");
+                let lets = self.destructure_pattern(
+                    origin,
+                    pattern,
+                    Spanned::new(LoweredExpr::Ident(temp.clone()), origin.clone()),
+                );
+                (temp, lets)
+            }
+        }
+    }
+
+    /// Recursively project `pattern`'s names out of `value` into `Let`
+    /// statements. Tuple elements are projected positionally via
+    /// [`LoweredExpr::Field`] with a stringified index (`"0"`, `"1"`, ...),
+    /// recursing for nested tuples - e.g. `(a, (b, c))` bound to `__pat0`
+    /// becomes `let a = __pat0.0; let b = __pat0.1.0; let c = __pat0.1.1;`.
+    fn destructure_pattern(
+        &mut self,
+        origin: &Origin,
+        pattern: ast::Spanned<ast::BindPattern>,
+        value: Spanned<LoweredExpr>,
+    ) -> Vec<Spanned<LoweredStmt>> {
+        let pattern_span = pattern.span;
+        match pattern.node {
+            ast::BindPattern::Ident(name) => {
+                // The `Let` binding a name to its projected value has a
+                // literal source counterpart (the identifier itself), so it
+                // gets a `Direct` origin at that identifier's own span
+                // rather than the synthetic `ListComp` origin - e.g. in
+                // `for (k, v) in pairs`, `let k = ...` points back at `k`.
+                let let_origin =
+                    Origin::Direct(ast::Spanned::new(Expr::Ident(name.clone()), pattern_span));
+                vec![Spanned::new(LoweredStmt::Let { name, value }, let_origin)]
+            }
+            ast::BindPattern::Tuple(elems) => {
+                let mut stmts = Vec::new();
+                for (i, elem) in elems.into_iter().enumerate() {
+                    let projected = Spanned::new(
+                        LoweredExpr::Field {
+                            expr: Box::new(value.clone()),
+                            field: i.to_string(),
+                        },
+                        origin.clone(),
+                    );
+                    stmts.extend(self.destructure_pattern(origin, elem, projected));
+                }
+                stmts
+            }
+        }
+    }
+
+    /// Nest `clauses` around `innermost` in source order: each generator
+    /// (`CompClause::For`) becomes a `For` wrapping the clauses after it,
+    /// and each filter (`CompClause::If`) wraps the clauses after it in an
+    /// `If` with no `else`. Processed back-to-front so the last clause ends
+    /// up innermost, right around `innermost` itself - e.g. `for x in xs if
+    /// x > 0 for y in ys` yields `For(x, xs, [If(x > 0, [For(y, ys,
+    /// innermost)])])`. Always returns exactly one statement, since nesting
+    /// at least one clause around any body collapses to a single outer
+    /// node.
+    fn desugar_comp_clauses(
+        &mut self,
+        origin: &Origin,
+        clauses: Vec<ast::CompClause>,
+        innermost: Vec<Spanned<LoweredStmt>>,
+    ) -> Vec<Spanned<LoweredStmt>> {
+        let mut body = innermost;
+        for clause in clauses.into_iter().rev() {
+            body = match clause {
+                ast::CompClause::For { var, iter } => match as_range(&iter.node) {
+                    Some((start, end, inclusive)) => {
+                        let start = start.clone();
+                        let end = end.clone();
+                        self.desugar_range_clause(origin, var, start, end, inclusive, body)
+                    }
+                    None => match as_builtin_call(&iter.node) {
+                        Some(("enumerate", [xs])) if positional_arg(xs).is_some() => {
+                            let xs = positional_arg(xs).unwrap().clone();
+                            self.desugar_enumerate_clause(origin, var, xs, body)
+                        }
+                        Some(("zip", [xs, ys]))
+                            if positional_arg(xs).is_some() && positional_arg(ys).is_some() =>
+                        {
+                            let xs = positional_arg(xs).unwrap().clone();
+                            let ys = positional_arg(ys).unwrap().clone();
+                            self.desugar_zip_clause(origin, var, xs, ys, body)
+                        }
+                        _ => {
+                            let lowered_iter = self.desugar_expr(iter);
+                            let (loop_var, pattern_lets) = self.bind_var_pattern(origin, var);
+                            let mut for_body = pattern_lets;
+                            for_body.extend(body);
+                            vec![Spanned::new(
+                                LoweredStmt::For {
+                                    var: loop_var,
+                                    iter: lowered_iter,
+                                    body: for_body,
+                                },
+                                origin.clone(),
+                            )]
+                        }
+                    },
+                },
+                ast::CompClause::If(cond) => {
+                    let lowered_cond = self.desugar_expr(cond);
+                    vec![Spanned::new(
+                        LoweredStmt::Expr(Spanned::new(
+                            LoweredExpr::If {
+                                cond: Box::new(lowered_cond),
+                                then_block: body,
+                                else_block: None,
+                            },
+                            origin.clone(),
+                        )),
+                        origin.clone(),
+                    )]
+                }
+            };
+        }
+        body
+    }
+
+    /// Desugar a `for pattern in enumerate(xs)` clause (recognized by
+    /// [`Self::desugar_comp_clauses`]) without requiring a real iterator
+    /// runtime: iterates `xs` directly, binding a fresh `__elemN` as the
+    /// loop variable, and tracks the index in a fresh `LetMut __idxN`
+    /// counter incremented at the end of every iteration. `pattern` is then
+    /// bound to the `(idx, elem)` pair through a fresh `__pairN` temporary
+    /// and [`Self::destructure_pattern`] - the same tuple-pattern machinery
+    /// [`Self::bind_var_pattern`] uses for `for (k, v) in pairs` - so `for
+    /// (i, x) in enumerate(xs)` binds `i`/`x` as expected, and a bare `for p
+    /// in enumerate(xs)` binds `p` to the whole `(idx, elem)` tuple.
+    fn desugar_enumerate_clause(
+        &mut self,
+        origin: &Origin,
+        pattern: ast::Spanned<ast::BindPattern>,
+        xs: ast::Spanned<Expr>,
+        body: Vec<Spanned<LoweredStmt>>,
+    ) -> Vec<Spanned<LoweredStmt>> {
+        let idx_name = self.fresh_name("idx");
+        let elem_name = self.fresh_name("elem");
+        let pair_name = self.fresh_name("pair");
+        let lowered_xs = self.desugar_expr(xs);
+
+        let let_pair_stmt = Spanned::new(
+            LoweredStmt::Let {
+                name: pair_name.clone(),
+                value: Spanned::new(
+                    LoweredExpr::Tuple(vec![
+                        Spanned::new(LoweredExpr::Ident(idx_name.clone()), origin.clone()),
+                        Spanned::new(LoweredExpr::Ident(elem_name.clone()), origin.clone()),
+                    ]),
+                    origin.clone(),
+                ),
+            },
+            origin.clone(),
+        );
+        let pattern_lets = self.destructure_pattern(
+            origin,
+            pattern,
+            Spanned::new(LoweredExpr::Ident(pair_name), origin.clone()),
+        );
+
+        let mut for_body = vec![let_pair_stmt];
+        for_body.extend(pattern_lets);
+        for_body.extend(body);
+        for_body.push(Spanned::new(
+            LoweredStmt::CompoundAssign {
+                name: idx_name.clone(),
+                op: BinOp::Add,
+                value: Spanned::new(LoweredExpr::Int(1), origin.clone()),
+            },
+            origin.clone(),
+        ));
+
+        vec![
+            Spanned::new(
+                LoweredStmt::LetMut {
+                    name: idx_name,
+                    value: Spanned::new(LoweredExpr::Int(0), origin.clone()),
+                },
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::For {
+                    var: elem_name,
+                    iter: lowered_xs,
+                    body: for_body,
+                },
+                origin.clone(),
+            ),
+        ]
+    }
+
+    /// Desugar a `for pattern in zip(xs, ys)` clause (recognized by
+    /// [`Self::desugar_comp_clauses`]) without requiring a real iterator
+    /// runtime: iterates `xs` directly via a plain `For`, binds `ys` to a
+    /// fresh `Let` once up front (so it's evaluated a single time rather
+    /// than once per lookup), and uses a fresh `LetMut __idxN` counter to
+    /// index into it with the `index` builtin. The counter is compared
+    /// against `len(ys)` with an `If` (the same no-`else` guard
+    /// `CompClause::If` filters use) so the body only runs - and `ys` is
+    /// only indexed - while both sides still have elements, i.e. the
+    /// shorter of `xs`/`ys` determines how many iterations actually
+    /// produce output. `pattern` is projected out of the `(x_elem,
+    /// y_elem)` pair the same way [`Self::desugar_enumerate_clause`]
+    /// projects `(idx, elem)`.
+    fn desugar_zip_clause(
+        &mut self,
+        origin: &Origin,
+        pattern: ast::Spanned<ast::BindPattern>,
+        xs: ast::Spanned<Expr>,
+        ys: ast::Spanned<Expr>,
+        body: Vec<Spanned<LoweredStmt>>,
+    ) -> Vec<Spanned<LoweredStmt>> {
+        let ys_name = self.fresh_name("zip");
+        let idx_name = self.fresh_name("idx");
+        let elem_name = self.fresh_name("elem");
+        let lowered_xs = self.desugar_expr(xs);
+        let lowered_ys = self.desugar_expr(ys);
+
+        let ys_ident = || Spanned::new(LoweredExpr::Ident(ys_name.clone()), origin.clone());
+        let idx_ident = || Spanned::new(LoweredExpr::Ident(idx_name.clone()), origin.clone());
+
+        let len_ys = Spanned::new(
+            LoweredExpr::Call {
+                func: Box::new(Spanned::new(
+                    LoweredExpr::Ident("len".to_string()),
+                    origin.clone(),
+                )),
+                args: vec![Spanned::new(
+                    LoweredArg::Positional(ys_ident()),
+                    origin.clone(),
+                )],
+            },
+            origin.clone(),
+        );
+        let indexed_ys = Spanned::new(
+            LoweredExpr::Call {
+                func: Box::new(Spanned::new(
+                    LoweredExpr::Ident("index".to_string()),
+                    origin.clone(),
+                )),
+                args: vec![
+                    Spanned::new(LoweredArg::Positional(ys_ident()), origin.clone()),
+                    Spanned::new(LoweredArg::Positional(idx_ident()), origin.clone()),
+                ],
+            },
+            origin.clone(),
+        );
+
+        // `index(ys, idx)` is only safe once the length guard below passes,
+        // so the pair (and the pattern lets projected from it) are built
+        // inside `guarded_body`, not hoisted above the `If`.
+        let pair_name = self.fresh_name("pair");
+        let let_pair_stmt = Spanned::new(
+            LoweredStmt::Let {
+                name: pair_name.clone(),
+                value: Spanned::new(
+                    LoweredExpr::Tuple(vec![
+                        Spanned::new(LoweredExpr::Ident(elem_name.clone()), origin.clone()),
+                        indexed_ys,
+                    ]),
+                    origin.clone(),
+                ),
+            },
+            origin.clone(),
+        );
+        let pattern_lets = self.destructure_pattern(
+            origin,
+            pattern,
+            Spanned::new(LoweredExpr::Ident(pair_name), origin.clone()),
+        );
+
+        let mut guarded_body = vec![let_pair_stmt];
+        guarded_body.extend(pattern_lets);
+        guarded_body.extend(body);
+
+        let for_body = vec![
+            Spanned::new(
+                LoweredStmt::Expr(Spanned::new(
+                    LoweredExpr::If {
+                        cond: Box::new(Spanned::new(
+                            LoweredExpr::BinOp {
+                                op: BinOp::Lt,
+                                left: Box::new(idx_ident()),
+                                right: Box::new(len_ys),
+                            },
+                            origin.clone(),
+                        )),
+                        then_block: guarded_body,
+                        else_block: None,
+                    },
+                    origin.clone(),
+                )),
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::CompoundAssign {
+                    name: idx_name.clone(),
+                    op: BinOp::Add,
+                    value: Spanned::new(LoweredExpr::Int(1), origin.clone()),
+                },
+                origin.clone(),
+            ),
+        ];
+
+        vec![
+            Spanned::new(
+                LoweredStmt::Let {
+                    name: ys_name,
+                    value: lowered_ys,
+                },
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::LetMut {
+                    name: idx_name,
+                    value: Spanned::new(LoweredExpr::Int(0), origin.clone()),
+                },
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::For {
+                    var: elem_name,
+                    iter: lowered_xs,
+                    body: for_body,
+                },
+                origin.clone(),
+            ),
+        ]
+    }
+
+    /// Desugar a `for pattern in start..end` (or `start..=end`) clause.
+    /// There's no lowered construct for iterating a range directly, so this
+    /// builds one out of a bounded [`LoweredStmt::While`]: `start`/`end` are
+    /// evaluated once into fresh temporaries, a fresh `LetMut __idxN`
+    /// counter is initialized to `start`, and the loop runs while the
+    /// counter is below `end` (or at-or-below it, for an inclusive range),
+    /// incrementing at the end of every iteration. `pattern` is bound
+    /// directly to the counter through [`Self::bind_var_pattern`] - the
+    /// same machinery a plain `for pattern in iter` clause uses - since each
+    /// iteration produces one `Int`, not a pair.
+    fn desugar_range_clause(
+        &mut self,
+        origin: &Origin,
+        pattern: ast::Spanned<ast::BindPattern>,
+        start: ast::Spanned<Expr>,
+        end: ast::Spanned<Expr>,
+        inclusive: bool,
+        body: Vec<Spanned<LoweredStmt>>,
+    ) -> Vec<Spanned<LoweredStmt>> {
+        let end_name = self.fresh_name("end");
+        let lowered_start = self.desugar_expr(start);
+        let lowered_end = self.desugar_expr(end);
+        let (counter, pattern_lets) = self.bind_var_pattern(origin, pattern);
+
+        let cond = Spanned::new(
+            LoweredExpr::BinOp {
+                op: if inclusive { BinOp::Le } else { BinOp::Lt },
+                left: Box::new(Spanned::new(
+                    LoweredExpr::Ident(counter.clone()),
+                    origin.clone(),
+                )),
+                right: Box::new(Spanned::new(
+                    LoweredExpr::Ident(end_name.clone()),
+                    origin.clone(),
+                )),
+            },
+            origin.clone(),
+        );
+
+        let mut while_body = pattern_lets;
+        while_body.extend(body);
+        while_body.push(Spanned::new(
+            LoweredStmt::CompoundAssign {
+                name: counter.clone(),
+                op: BinOp::Add,
+                value: Spanned::new(LoweredExpr::Int(1), origin.clone()),
+            },
+            origin.clone(),
+        ));
+
+        vec![
+            Spanned::new(
+                LoweredStmt::Let {
+                    name: end_name,
+                    value: lowered_end,
+                },
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::LetMut {
+                    name: counter,
+                    value: lowered_start,
+                },
+                origin.clone(),
+            ),
+            Spanned::new(
+                LoweredStmt::While {
+                    cond,
+                    body: while_body,
+                },
+                origin.clone(),
+            ),
+        ]
+    }
+
     /// Desugar a list comprehension into a block expression.
     ///
     /// `[expr for var in iter]` becomes:
@@ -290,38 +881,45 @@ impl Desugarer {
     /// }
     /// ```
     ///
-    /// `[expr for var in iter if filter]` becomes:
+    /// Additional clauses nest in source order (see
+    /// [`Self::desugar_comp_clauses`]), so `[expr for x in xs if x > 0 for y
+    /// in ys if y < x]` becomes:
     /// ```text
     /// {
     ///     let mut __result0 = MutableList;
-    ///     for var in iter {
-    ///         if filter {
-    ///             push(__result0, expr);
+    ///     for x in xs {
+    ///         if x > 0 {
+    ///             for y in ys {
+    ///                 if y < x {
+    ///                     push(__result0, expr);
+    ///                 }
+    ///             }
     ///         }
     ///     }
     ///     __result0
     /// }
     /// ```
+    ///
+    /// A generator's variable may also be a tuple pattern (see
+    /// [`Self::bind_var_pattern`]), e.g. `[k for (k, v) in pairs]` binds a
+    /// fresh temporary as the loop variable and projects `k`/`v` out of it
+    /// with `Let`s at the top of the loop body.
     fn desugar_list_comp(
         &mut self,
         list_comp_rc: Rc<ast::Spanned<Expr>>,
         body_expr: ast::Spanned<Expr>,
-        var: String,
-        iter: ast::Spanned<Expr>,
-        filter: Option<ast::Spanned<Expr>>,
+        clauses: Vec<ast::CompClause>,
     ) -> Spanned<LoweredExpr> {
         let origin = Origin::ListComp(list_comp_rc);
 
         // Generate unique result variable name
         let result_var = self.fresh_name("result");
 
-        // Desugar the iterator expression
-        let lowered_iter = self.desugar_expr(iter);
-
         // Desugar the body expression
         let lowered_body_expr = self.desugar_expr(body_expr);
 
-        // Create the push statement
+        // Create the push statement - the innermost body of the nested
+        // generators/filters
         let push_stmt = Spanned::new(
             LoweredStmt::Push {
                 list: result_var.clone(),
@@ -330,49 +928,157 @@ impl Desugarer {
             origin.clone(),
         );
 
-        // Build the for loop body
-        let for_body = if let Some(filter_expr) = filter {
-            // With filter: if cond { push(...) }
-            let lowered_filter = self.desugar_expr(filter_expr);
-            vec![Spanned::new(
-                LoweredStmt::Expr(Spanned::new(
-                    LoweredExpr::If {
-                        cond: Box::new(lowered_filter),
-                        then_block: vec![push_stmt],
-                        else_block: None,
-                    },
-                    origin.clone(),
-                )),
-                origin.clone(),
-            )]
-        } else {
-            // Without filter: just push
-            vec![push_stmt]
-        };
-
-        // Create the for statement
-        let for_stmt = Spanned::new(
-            LoweredStmt::For {
-                var,
-                iter: lowered_iter,
-                body: for_body,
+        // Nest the clauses around the push statement
+        let nested = self.desugar_comp_clauses(&origin, clauses, vec![push_stmt]);
+
+        // Create the let mut statement for result
+        let let_mut_stmt = Spanned::new(
+            LoweredStmt::LetMut {
+                name: result_var.clone(),
+                value: Spanned::new(LoweredExpr::MutableList, origin.clone()),
+            },
+            origin.clone(),
+        );
+
+        // Return the block expression
+        let mut stmts = vec![let_mut_stmt];
+        stmts.extend(nested);
+        Spanned::new(
+            LoweredExpr::Block {
+                stmts,
+                result: Box::new(Spanned::new(LoweredExpr::Ident(result_var), origin.clone())),
+            },
+            origin,
+        )
+    }
+
+    /// Desugar a dict comprehension into a block expression, exactly like
+    /// [`Self::desugar_list_comp`] but initializing a `MutableMap` and
+    /// emitting an `Insert` instead of a `Push`.
+    ///
+    /// `{key: value for var in iter}` becomes:
+    /// ```text
+    /// {
+    ///     let mut __result0 = MutableMap;
+    ///     for var in iter {
+    ///         insert(__result0, key, value);
+    ///     }
+    ///     __result0
+    /// }
+    /// ```
+    ///
+    /// Additional clauses nest in source order exactly like
+    /// [`Self::desugar_list_comp`]'s (see [`Self::desugar_comp_clauses`]).
+    fn desugar_dict_comp(
+        &mut self,
+        dict_comp_rc: Rc<ast::Spanned<Expr>>,
+        key_expr: ast::Spanned<Expr>,
+        value_expr: ast::Spanned<Expr>,
+        clauses: Vec<ast::CompClause>,
+    ) -> Spanned<LoweredExpr> {
+        let origin = Origin::ListComp(dict_comp_rc);
+
+        // Generate unique result variable name
+        let result_var = self.fresh_name("result");
+
+        // Desugar the key/value expressions
+        let lowered_key_expr = self.desugar_expr(key_expr);
+        let lowered_value_expr = self.desugar_expr(value_expr);
+
+        // Create the insert statement - the innermost body of the nested
+        // generators/filters
+        let insert_stmt = Spanned::new(
+            LoweredStmt::Insert {
+                map: result_var.clone(),
+                key: lowered_key_expr,
+                value: lowered_value_expr,
             },
             origin.clone(),
         );
 
+        // Nest the clauses around the insert statement
+        let nested = self.desugar_comp_clauses(&origin, clauses, vec![insert_stmt]);
+
         // Create the let mut statement for result
         let let_mut_stmt = Spanned::new(
             LoweredStmt::LetMut {
                 name: result_var.clone(),
-                value: Spanned::new(LoweredExpr::MutableList, origin.clone()),
+                value: Spanned::new(LoweredExpr::MutableMap, origin.clone()),
             },
             origin.clone(),
         );
 
         // Return the block expression
+        let mut stmts = vec![let_mut_stmt];
+        stmts.extend(nested);
         Spanned::new(
             LoweredExpr::Block {
-                stmts: vec![let_mut_stmt, for_stmt],
+                stmts,
+                result: Box::new(Spanned::new(LoweredExpr::Ident(result_var), origin.clone())),
+            },
+            origin,
+        )
+    }
+
+    /// Desugar a set comprehension into a block expression, exactly like
+    /// [`Self::desugar_list_comp`] but initializing a `MutableSet` and
+    /// emitting an `Add` instead of a `Push`.
+    ///
+    /// `{expr for var in iter}` becomes:
+    /// ```text
+    /// {
+    ///     let mut __result0 = MutableSet;
+    ///     for var in iter {
+    ///         add(__result0, expr);
+    ///     }
+    ///     __result0
+    /// }
+    /// ```
+    ///
+    /// Additional clauses nest in source order exactly like
+    /// [`Self::desugar_list_comp`]'s (see [`Self::desugar_comp_clauses`]).
+    fn desugar_set_comp(
+        &mut self,
+        set_comp_rc: Rc<ast::Spanned<Expr>>,
+        body_expr: ast::Spanned<Expr>,
+        clauses: Vec<ast::CompClause>,
+    ) -> Spanned<LoweredExpr> {
+        let origin = Origin::ListComp(set_comp_rc);
+
+        // Generate unique result variable name
+        let result_var = self.fresh_name("result");
+
+        // Desugar the body expression
+        let lowered_body_expr = self.desugar_expr(body_expr);
+
+        // Create the add statement - the innermost body of the nested
+        // generators/filters
+        let add_stmt = Spanned::new(
+            LoweredStmt::Add {
+                set: result_var.clone(),
+                value: lowered_body_expr,
+            },
+            origin.clone(),
+        );
+
+        // Nest the clauses around the add statement
+        let nested = self.desugar_comp_clauses(&origin, clauses, vec![add_stmt]);
+
+        // Create the let mut statement for result
+        let let_mut_stmt = Spanned::new(
+            LoweredStmt::LetMut {
+                name: result_var.clone(),
+                value: Spanned::new(LoweredExpr::MutableSet, origin.clone()),
+            },
+            origin.clone(),
+        );
+
+        // Return the block expression
+        let mut stmts = vec![let_mut_stmt];
+        stmts.extend(nested);
+        Spanned::new(
+            LoweredExpr::Block {
+                stmts,
                 result: Box::new(Spanned::new(LoweredExpr::Ident(result_var), origin.clone())),
             },
             origin,
@@ -395,6 +1101,29 @@ impl Desugarer {
                     origin,
                 )
             }
+            Stmt::LetMut { name, value } => {
+                let lowered_value = self.desugar_expr(value);
+                let origin = lowered_value.origin.clone();
+                Spanned::new(
+                    LoweredStmt::LetMut {
+                        name,
+                        value: lowered_value,
+                    },
+                    origin,
+                )
+            }
+            Stmt::CompoundAssign { name, op, value } => {
+                let lowered_value = self.desugar_expr(value);
+                let origin = lowered_value.origin.clone();
+                Spanned::new(
+                    LoweredStmt::CompoundAssign {
+                        name,
+                        op,
+                        value: lowered_value,
+                    },
+                    origin,
+                )
+            }
             Stmt::Expr(expr) => {
                 let lowered_expr = self.desugar_expr(expr);
                 let origin = lowered_expr.origin.clone();
@@ -473,3 +1202,79 @@ pub fn desugar(expr: ast::Spanned<Expr>) -> Spanned<LoweredExpr> {
 pub fn desugar_program(program: ast::Spanned<ast::Program>) -> LoweredProgram {
     Desugarer::new().desugar_program(program)
 }
+
+/// Fixed `BinOp` -> builtin-function-name table, used by
+/// [`Desugarer::with_operator_calls`].
+fn binop_builtin_name(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Add => "__op_add",
+        BinOp::Sub => "__op_sub",
+        BinOp::Mul => "__op_mul",
+        BinOp::Div => "__op_div",
+        BinOp::Mod => "__op_mod",
+        BinOp::Eq => "__op_eq",
+        BinOp::Ne => "__op_ne",
+        BinOp::Lt => "__op_lt",
+        BinOp::Le => "__op_le",
+        BinOp::Gt => "__op_gt",
+        BinOp::Ge => "__op_ge",
+        BinOp::And => "__op_and",
+        BinOp::Or => "__op_or",
+    }
+}
+
+/// Fixed `UnaryOp` -> builtin-function-name table, used by
+/// [`Desugarer::with_operator_calls`].
+fn unaryop_builtin_name(op: UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Neg => "__op_neg",
+        UnaryOp::Not => "__op_not",
+        UnaryOp::Deref => "__op_deref",
+        UnaryOp::Await => "__op_await",
+    }
+}
+
+/// If `expr` is a direct call to a bare identifier, e.g. `enumerate(xs)` or
+/// `zip(xs, ys)`, return its callee name and arguments. Used by
+/// [`Desugarer::desugar_comp_clauses`] to recognize the `enumerate`/`zip`
+/// iteration-source builtins in `for`/comprehension clauses; returns `None`
+/// for calls through a non-identifier callee and for anything that isn't a
+/// call at all.
+fn as_builtin_call(expr: &Expr) -> Option<(&str, &[ast::Spanned<Arg>])> {
+    match expr {
+        Expr::Call { func, args } => match &func.node {
+            Expr::Ident(name) => Some((name.as_str(), args.as_slice())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Extract an argument's expression if it's positional, e.g. from
+/// `enumerate`/`zip`'s arguments, which are never named.
+fn positional_arg(arg: &ast::Spanned<Arg>) -> Option<&ast::Spanned<Expr>> {
+    match &arg.node {
+        Arg::Positional(expr) => Some(expr),
+        Arg::Named { .. } => None,
+    }
+}
+
+/// If `expr` is a range expression (`a..b` or `a..=b`), return its bounds
+/// and whether it's inclusive. Used by [`Desugarer::desugar_comp_clauses`]
+/// to recognize ranges as an iteration source in `for`/comprehension
+/// clauses, the same way [`as_builtin_call`] recognizes `enumerate`/`zip`.
+fn as_range(expr: &Expr) -> Option<(&ast::Spanned<Expr>, &ast::Spanned<Expr>, bool)> {
+    match expr {
+        Expr::BinOp {
+            op: BinOp::Range,
+            left,
+            right,
+        } => Some((left, right, false)),
+        Expr::BinOp {
+            op: BinOp::RangeInclusive,
+            left,
+            right,
+        } => Some((left, right, true)),
+        _ => None,
+    }
+}
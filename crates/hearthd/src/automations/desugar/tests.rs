@@ -1,6 +1,7 @@
 use chumsky::prelude::*;
 
 use super::desugar;
+use super::Desugarer;
 use crate::automations::lexer::Token;
 use crate::automations::repr::ast::Expr;
 use crate::automations::repr::ast::Spanned;
@@ -35,6 +36,15 @@ fn parse_and_desugar(input: &str) -> (String, String) {
     (ast_pretty, lowered.to_pretty_string())
 }
 
+/// Like `parse_and_desugar`, but with `Desugarer::with_operator_calls` on.
+fn parse_and_desugar_with_operator_calls(input: &str) -> String {
+    let ast = parse_expr(input).expect("parsing should succeed");
+    Desugarer::new()
+        .with_operator_calls()
+        .desugar_expr(ast)
+        .to_pretty_string()
+}
+
 // =============================================================================
 // List Comprehension Tests
 // =============================================================================
@@ -46,8 +56,7 @@ fn test_desugar_simple_list_comp() {
     ListComp:
       Expr:
         Ident: x
-      Var: x
-      Iter:
+      For: x
         Ident: list
     ");
     insta::assert_snapshot!(lowered, @"
@@ -84,8 +93,7 @@ fn test_desugar_list_comp_with_expr() {
         BinOp: *
           Ident: x
           Int: 2
-      Var: x
-      Iter:
+      For: x
         Ident: items
     ");
     insta::assert_snapshot!(lowered, @"
@@ -124,10 +132,9 @@ fn test_desugar_list_comp_with_filter() {
     ListComp:
       Expr:
         Ident: x
-      Var: x
-      Iter:
+      For: x
         Ident: list
-      Filter:
+      If:
         BinOp: >
           Ident: x
           Int: 0
@@ -179,10 +186,9 @@ fn test_desugar_list_comp_complex() {
           Ident: f
           Args:
             Ident: x
-      Var: x
-      Iter:
+      For: x
         Ident: items
-      Filter:
+      If:
         Call:
           Ident: pred
           Args:
@@ -245,8 +251,7 @@ fn test_desugar_list_comp_with_path() {
             Segment: LightOff
           Args:
             Ident: l
-      Var: l
-      Iter:
+      For: l
         Call:
           Ident: keys
           Args:
@@ -291,6 +296,138 @@ fn test_desugar_list_comp_with_path() {
     ");
 }
 
+#[test]
+fn test_desugar_list_comp_multiple_generators() {
+    let (ast, lowered) = parse_and_desugar("[x + y for x in xs for y in ys]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        BinOp: +
+          Ident: x
+          Ident: y
+      For: x
+        Ident: xs
+      For: y
+        Ident: ys
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..31
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..31
+        LetMut: __result0
+          Origin: ListComp @ 0..31
+          MutableList
+        Origin: ListComp @ 0..31
+        For:
+          Var: x
+          Iter:
+            Origin: Direct @ 16..18
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..31
+            For:
+              Var: y
+              Iter:
+                Origin: Direct @ 28..30
+                Ident: ys
+              Body:
+                Origin: ListComp @ 0..31
+                Push: __result0
+                  Origin: Direct @ 1..6
+                  BinOp: +
+                    Origin: Direct @ 1..2
+                    Ident: x
+                    Origin: Direct @ 5..6
+                    Ident: y
+      Result:
+        Origin: ListComp @ 0..31
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_list_comp_interleaved_generators_and_filters() {
+    let (ast, lowered) = parse_and_desugar("[x + y for x in xs if x > 0 for y in ys if y < x]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        BinOp: +
+          Ident: x
+          Ident: y
+      For: x
+        Ident: xs
+      If:
+        BinOp: >
+          Ident: x
+          Int: 0
+      For: y
+        Ident: ys
+      If:
+        BinOp: <
+          Ident: y
+          Ident: x
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..49
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..49
+        LetMut: __result0
+          Origin: ListComp @ 0..49
+          MutableList
+        Origin: ListComp @ 0..49
+        For:
+          Var: x
+          Iter:
+            Origin: Direct @ 16..18
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..49
+            ExprStmt:
+              Origin: ListComp @ 0..49
+              If:
+                Cond:
+                  Origin: Direct @ 22..27
+                  BinOp: >
+                    Origin: Direct @ 22..23
+                    Ident: x
+                    Origin: Direct @ 26..27
+                    Int: 0
+                Then:
+                  Origin: ListComp @ 0..49
+                  For:
+                    Var: y
+                    Iter:
+                      Origin: Direct @ 37..39
+                      Ident: ys
+                    Body:
+                      Origin: ListComp @ 0..49
+                      ExprStmt:
+                        Origin: ListComp @ 0..49
+                        If:
+                          Cond:
+                            Origin: Direct @ 43..48
+                            BinOp: <
+                              Origin: Direct @ 43..44
+                              Ident: y
+                              Origin: Direct @ 47..48
+                              Ident: x
+                          Then:
+                            Origin: ListComp @ 0..49
+                            Push: __result0
+                              Origin: Direct @ 1..6
+                              BinOp: +
+                                Origin: Direct @ 1..2
+                                Ident: x
+                                Origin: Direct @ 5..6
+                                Ident: y
+      Result:
+        Origin: ListComp @ 0..49
+        Ident: __result0
+    ");
+}
+
 // =============================================================================
 // Pass-through Tests (non-ListComp expressions)
 // =============================================================================
@@ -385,8 +522,7 @@ fn test_desugar_nested_list_comp_in_if() {
           ListComp:
             Expr:
               Ident: x
-            Var: x
-            Iter:
+            For: x
               Ident: items
       Else:
         ExprStmt:
@@ -439,11 +575,9 @@ fn test_desugar_nested_list_comp() {
         ListComp:
           Expr:
             Ident: x
-          Var: x
-          Iter:
+          For: x
             Ident: row
-      Var: row
-      Iter:
+      For: row
         Ident: matrix
     ");
     insta::assert_snapshot!(lowered, @"
@@ -498,8 +632,7 @@ fn test_desugar_list_comp_with_field_access() {
       Expr:
         Field: .value
           Ident: item
-      Var: item
-      Iter:
+      For: item
         Ident: list
     ");
     insta::assert_snapshot!(lowered, @"
@@ -528,3 +661,670 @@ fn test_desugar_list_comp_with_field_access() {
         Ident: __result0
     ");
 }
+
+#[test]
+fn test_desugar_list_comp_tuple_pattern() {
+    let (ast, lowered) = parse_and_desugar("[k for (k, v) in pairs]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: k
+      For: (k, v)
+        Ident: pairs
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..23
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..23
+        LetMut: __result0
+          Origin: ListComp @ 0..23
+          MutableList
+        Origin: ListComp @ 0..23
+        For:
+          Var: __pat1
+          Iter:
+            Origin: Direct @ 17..22
+            Ident: pairs
+          Body:
+            Origin: Direct @ 8..9
+            Let: k
+              Origin: ListComp @ 0..23
+              Field: .0
+                Origin: ListComp @ 0..23
+                Ident: __pat1
+            Origin: Direct @ 11..12
+            Let: v
+              Origin: ListComp @ 0..23
+              Field: .1
+                Origin: ListComp @ 0..23
+                Ident: __pat1
+            Origin: ListComp @ 0..23
+            Push: __result0
+              Origin: Direct @ 1..2
+              Ident: k
+      Result:
+        Origin: ListComp @ 0..23
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_list_comp_nested_tuple_pattern() {
+    let (ast, lowered) = parse_and_desugar("[a for (a, (b, c)) in triples]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: a
+      For: (a, (b, c))
+        Ident: triples
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..30
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..30
+        LetMut: __result0
+          Origin: ListComp @ 0..30
+          MutableList
+        Origin: ListComp @ 0..30
+        For:
+          Var: __pat1
+          Iter:
+            Origin: Direct @ 22..29
+            Ident: triples
+          Body:
+            Origin: Direct @ 8..9
+            Let: a
+              Origin: ListComp @ 0..30
+              Field: .0
+                Origin: ListComp @ 0..30
+                Ident: __pat1
+            Origin: Direct @ 12..13
+            Let: b
+              Origin: ListComp @ 0..30
+              Field: .0
+                Origin: ListComp @ 0..30
+                Field: .1
+                  Origin: ListComp @ 0..30
+                  Ident: __pat1
+            Origin: Direct @ 15..16
+            Let: c
+              Origin: ListComp @ 0..30
+              Field: .1
+                Origin: ListComp @ 0..30
+                Field: .1
+                  Origin: ListComp @ 0..30
+                  Ident: __pat1
+            Origin: ListComp @ 0..30
+            Push: __result0
+              Origin: Direct @ 1..2
+              Ident: a
+      Result:
+        Origin: ListComp @ 0..30
+        Ident: __result0
+    ");
+}
+
+// =============================================================================
+// Enumerate/Zip Iteration Source Tests
+// =============================================================================
+
+#[test]
+fn test_desugar_list_comp_enumerate() {
+    let (ast, lowered) = parse_and_desugar("[x for (i, x) in enumerate(xs)]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: x
+      For: (i, x)
+        Call:
+          Ident: enumerate
+          Args:
+            Ident: xs
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..31
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..31
+        LetMut: __result0
+          Origin: ListComp @ 0..31
+          MutableList
+        Origin: ListComp @ 0..31
+        LetMut: __idx1
+          Origin: ListComp @ 0..31
+          Int: 0
+        Origin: ListComp @ 0..31
+        For:
+          Var: __elem2
+          Iter:
+            Origin: Direct @ 27..29
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..31
+            Let: __pair3
+              Origin: ListComp @ 0..31
+              Tuple:
+                Origin: ListComp @ 0..31
+                Ident: __idx1
+                Origin: ListComp @ 0..31
+                Ident: __elem2
+            Origin: Direct @ 8..9
+            Let: i
+              Origin: ListComp @ 0..31
+              Field: .0
+                Origin: ListComp @ 0..31
+                Ident: __pair3
+            Origin: Direct @ 11..12
+            Let: x
+              Origin: ListComp @ 0..31
+              Field: .1
+                Origin: ListComp @ 0..31
+                Ident: __pair3
+            Origin: ListComp @ 0..31
+            Push: __result0
+              Origin: Direct @ 1..2
+              Ident: x
+            Origin: ListComp @ 0..31
+            CompoundAssign: __idx1 +=
+              Origin: ListComp @ 0..31
+              Int: 1
+      Result:
+        Origin: ListComp @ 0..31
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_list_comp_zip() {
+    let (ast, lowered) = parse_and_desugar("[a for (a, b) in zip(xs, ys)]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: a
+      For: (a, b)
+        Call:
+          Ident: zip
+          Args:
+            Ident: xs
+            Ident: ys
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..29
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..29
+        LetMut: __result0
+          Origin: ListComp @ 0..29
+          MutableList
+        Origin: ListComp @ 0..29
+        Let: __zip1
+          Origin: Direct @ 25..27
+          Ident: ys
+        Origin: ListComp @ 0..29
+        LetMut: __idx2
+          Origin: ListComp @ 0..29
+          Int: 0
+        Origin: ListComp @ 0..29
+        For:
+          Var: __elem3
+          Iter:
+            Origin: Direct @ 21..23
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..29
+            ExprStmt:
+              Origin: ListComp @ 0..29
+              If:
+                Cond:
+                  Origin: ListComp @ 0..29
+                  BinOp: <
+                    Origin: ListComp @ 0..29
+                    Ident: __idx2
+                    Origin: ListComp @ 0..29
+                    Call:
+                      Origin: ListComp @ 0..29
+                      Ident: len
+                      Args:
+                        Origin: ListComp @ 0..29
+                        Origin: ListComp @ 0..29
+                        Ident: __zip1
+                Then:
+                  Origin: ListComp @ 0..29
+                  Let: __pair4
+                    Origin: ListComp @ 0..29
+                    Tuple:
+                      Origin: ListComp @ 0..29
+                      Ident: __elem3
+                      Origin: ListComp @ 0..29
+                      Call:
+                        Origin: ListComp @ 0..29
+                        Ident: index
+                        Args:
+                          Origin: ListComp @ 0..29
+                          Origin: ListComp @ 0..29
+                          Ident: __zip1
+                          Origin: ListComp @ 0..29
+                          Origin: ListComp @ 0..29
+                          Ident: __idx2
+                  Origin: Direct @ 8..9
+                  Let: a
+                    Origin: ListComp @ 0..29
+                    Field: .0
+                      Origin: ListComp @ 0..29
+                      Ident: __pair4
+                  Origin: Direct @ 11..12
+                  Let: b
+                    Origin: ListComp @ 0..29
+                    Field: .1
+                      Origin: ListComp @ 0..29
+                      Ident: __pair4
+                  Origin: ListComp @ 0..29
+                  Push: __result0
+                    Origin: Direct @ 1..2
+                    Ident: a
+            Origin: ListComp @ 0..29
+            CompoundAssign: __idx2 +=
+              Origin: ListComp @ 0..29
+              Int: 1
+      Result:
+        Origin: ListComp @ 0..29
+        Ident: __result0
+    ");
+}
+
+// =============================================================================
+// Range Iteration Source Tests
+// =============================================================================
+
+#[test]
+fn test_desugar_list_comp_range() {
+    let (ast, lowered) = parse_and_desugar("[x for x in 0..5]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: x
+      For: x
+        BinOp: ..
+          Int: 0
+          Int: 5
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..17
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..17
+        LetMut: __result0
+          Origin: ListComp @ 0..17
+          MutableList
+        Origin: ListComp @ 0..17
+        Let: __end1
+          Origin: Direct @ 15..16
+          Int: 5
+        Origin: ListComp @ 0..17
+        LetMut: x
+          Origin: Direct @ 12..13
+          Int: 0
+        Origin: ListComp @ 0..17
+        While:
+          Cond:
+            Origin: ListComp @ 0..17
+            BinOp: <
+              Origin: ListComp @ 0..17
+              Ident: x
+              Origin: ListComp @ 0..17
+              Ident: __end1
+          Body:
+            Origin: ListComp @ 0..17
+            Push: __result0
+              Origin: Direct @ 1..2
+              Ident: x
+            Origin: ListComp @ 0..17
+            CompoundAssign: x +=
+              Origin: ListComp @ 0..17
+              Int: 1
+      Result:
+        Origin: ListComp @ 0..17
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_list_comp_range_inclusive() {
+    let (ast, lowered) = parse_and_desugar("[x for x in 0..=5]");
+    insta::assert_snapshot!(ast, @"
+    ListComp:
+      Expr:
+        Ident: x
+      For: x
+        BinOp: ..=
+          Int: 0
+          Int: 5
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..18
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..18
+        LetMut: __result0
+          Origin: ListComp @ 0..18
+          MutableList
+        Origin: ListComp @ 0..18
+        Let: __end1
+          Origin: Direct @ 16..17
+          Int: 5
+        Origin: ListComp @ 0..18
+        LetMut: x
+          Origin: Direct @ 12..13
+          Int: 0
+        Origin: ListComp @ 0..18
+        While:
+          Cond:
+            Origin: ListComp @ 0..18
+            BinOp: <=
+              Origin: ListComp @ 0..18
+              Ident: x
+              Origin: ListComp @ 0..18
+              Ident: __end1
+          Body:
+            Origin: ListComp @ 0..18
+            Push: __result0
+              Origin: Direct @ 1..2
+              Ident: x
+            Origin: ListComp @ 0..18
+            CompoundAssign: x +=
+              Origin: ListComp @ 0..18
+              Int: 1
+      Result:
+        Origin: ListComp @ 0..18
+        Ident: __result0
+    ");
+}
+
+// =============================================================================
+// Dict/Set Comprehension Tests
+// =============================================================================
+
+#[test]
+fn test_desugar_simple_dict_comp() {
+    let (ast, lowered) = parse_and_desugar("{k: k * 2 for k in xs}");
+    insta::assert_snapshot!(ast, @"
+    DictComp:
+      Key:
+        Ident: k
+      Value:
+        BinOp: *
+          Ident: k
+          Int: 2
+      For: k
+        Ident: xs
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..22
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..22
+        LetMut: __result0
+          Origin: ListComp @ 0..22
+          MutableMap
+        Origin: ListComp @ 0..22
+        For:
+          Var: k
+          Iter:
+            Origin: Direct @ 19..21
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..22
+            Insert: __result0
+              Origin: Direct @ 1..2
+              Ident: k
+              Origin: Direct @ 4..9
+              BinOp: *
+                Origin: Direct @ 4..5
+                Ident: k
+                Origin: Direct @ 8..9
+                Int: 2
+      Result:
+        Origin: ListComp @ 0..22
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_dict_comp_with_filter() {
+    let (ast, lowered) = parse_and_desugar("{k: v for k in xs if k > 0}");
+    insta::assert_snapshot!(ast, @"
+    DictComp:
+      Key:
+        Ident: k
+      Value:
+        Ident: v
+      For: k
+        Ident: xs
+      If:
+        BinOp: >
+          Ident: k
+          Int: 0
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..27
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..27
+        LetMut: __result0
+          Origin: ListComp @ 0..27
+          MutableMap
+        Origin: ListComp @ 0..27
+        For:
+          Var: k
+          Iter:
+            Origin: Direct @ 15..17
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..27
+            ExprStmt:
+              Origin: ListComp @ 0..27
+              If:
+                Cond:
+                  Origin: Direct @ 21..26
+                  BinOp: >
+                    Origin: Direct @ 21..22
+                    Ident: k
+                    Origin: Direct @ 25..26
+                    Int: 0
+                Then:
+                  Origin: ListComp @ 0..27
+                  Insert: __result0
+                    Origin: Direct @ 1..2
+                    Ident: k
+                    Origin: Direct @ 4..5
+                    Ident: v
+      Result:
+        Origin: ListComp @ 0..27
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_simple_set_comp() {
+    let (ast, lowered) = parse_and_desugar("{x for x in xs}");
+    insta::assert_snapshot!(ast, @"
+    SetComp:
+      Expr:
+        Ident: x
+      For: x
+        Ident: xs
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..15
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..15
+        LetMut: __result0
+          Origin: ListComp @ 0..15
+          MutableSet
+        Origin: ListComp @ 0..15
+        For:
+          Var: x
+          Iter:
+            Origin: Direct @ 12..14
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..15
+            Add: __result0
+              Origin: Direct @ 1..2
+              Ident: x
+      Result:
+        Origin: ListComp @ 0..15
+        Ident: __result0
+    ");
+}
+
+#[test]
+fn test_desugar_set_comp_multiple_generators() {
+    // Demonstrates that `SetComp` reuses the same clause-nesting as
+    // `ListComp`, not just a single generator.
+    let (ast, lowered) = parse_and_desugar("{x + y for x in xs for y in ys}");
+    insta::assert_snapshot!(ast, @"
+    SetComp:
+      Expr:
+        BinOp: +
+          Ident: x
+          Ident: y
+      For: x
+        Ident: xs
+      For: y
+        Ident: ys
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: ListComp @ 0..31
+    Block:
+      Stmts:
+        Origin: ListComp @ 0..31
+        LetMut: __result0
+          Origin: ListComp @ 0..31
+          MutableSet
+        Origin: ListComp @ 0..31
+        For:
+          Var: x
+          Iter:
+            Origin: Direct @ 16..18
+            Ident: xs
+          Body:
+            Origin: ListComp @ 0..31
+            For:
+              Var: y
+              Iter:
+                Origin: Direct @ 28..30
+                Ident: ys
+              Body:
+                Origin: ListComp @ 0..31
+                Add: __result0
+                  Origin: Direct @ 1..6
+                  BinOp: +
+                    Origin: Direct @ 1..2
+                    Ident: x
+                    Origin: Direct @ 5..6
+                    Ident: y
+      Result:
+        Origin: ListComp @ 0..31
+        Ident: __result0
+    ");
+}
+
+// =============================================================================
+// Match Expression Tests
+// =============================================================================
+
+#[test]
+fn test_desugar_passthrough_match() {
+    let (ast, lowered) =
+        parse_and_desugar("match e { Event::LightStateChanged(l) => { l }, _ => { 0 } }");
+    insta::assert_snapshot!(ast, @"
+    Match:
+      Scrutinee:
+        Ident: e
+      Arm:
+        MatchPatternVariant: Event::LightStateChanged(
+          BindingIdent: l
+        )
+        Body:
+          ExprStmt:
+            Ident: l
+      Arm:
+        MatchPatternWildcard
+        Body:
+          ExprStmt:
+            Int: 0
+    ");
+    insta::assert_snapshot!(lowered, @"
+    Origin: Direct @ 0..60
+    Match:
+      Scrutinee:
+        Origin: Direct @ 6..7
+        Ident: e
+      Arm:
+        MatchPatternVariant: Event::LightStateChanged(
+          BindingIdent: l
+        )
+        Body:
+          Origin: Direct @ 43..44
+          ExprStmt:
+            Origin: Direct @ 43..44
+            Ident: l
+      Arm:
+        MatchPatternWildcard
+        Body:
+          Origin: Direct @ 55..56
+          ExprStmt:
+            Origin: Direct @ 55..56
+            Int: 0
+    ");
+}
+
+// =============================================================================
+// `Desugarer::with_operator_calls` Tests
+// =============================================================================
+
+#[test]
+fn test_desugar_binop_to_builtin_call() {
+    let lowered = parse_and_desugar_with_operator_calls("1 + 2");
+    insta::assert_snapshot!(lowered, @"
+    Origin: Desugared @ 0..5
+    Call:
+      Origin: Desugared @ 0..5
+      Ident: __op_add
+      Args:
+        Origin: Desugared @ 0..5
+        Origin: Direct @ 0..1
+        Int: 1
+        Origin: Desugared @ 0..5
+        Origin: Direct @ 4..5
+        Int: 2
+    ");
+}
+
+#[test]
+fn test_desugar_unaryop_to_builtin_call() {
+    let lowered = parse_and_desugar_with_operator_calls("-x");
+    insta::assert_snapshot!(lowered, @"
+    Origin: Desugared @ 0..2
+    Call:
+      Origin: Desugared @ 0..2
+      Ident: __op_neg
+      Args:
+        Origin: Desugared @ 0..2
+        Origin: Direct @ 1..2
+        Ident: x
+    ");
+}
+
+#[test]
+fn test_desugar_binop_without_flag_is_unchanged() {
+    // Sanity check: the default `desugar` free function (flag off) still
+    // produces a `BinOp` node, not a builtin call.
+    let (_, lowered) = parse_and_desugar("1 + 2");
+    assert!(lowered.contains("BinOp: +"));
+    assert!(!lowered.contains("__op_add"));
+}
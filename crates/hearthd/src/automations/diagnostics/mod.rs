@@ -0,0 +1,140 @@
+//! Rich, colorized diagnostic reports for parser and lowering errors.
+//!
+//! [`render_report`] turns the `Rich` errors `parser::parse`/
+//! `parser::parse_stmt` return into annotated source reports - a top-level
+//! message, the offending line, and an underline label at the exact
+//! `SimpleSpan` byte range - using the same `ariadne` renderer
+//! `hearthd_config::diagnostics` already uses for TOML config errors.
+//!
+//! [`to_diagnostics`] does the same conversion but into
+//! `hearthd_config::Diagnostic`s rather than a pre-rendered string, so a
+//! `.hearth` parse failure gets the identical Ariadne rendering, JSON
+//! output, and [`hearthd_config::Severity`] handling as a TOML config error.
+//!
+//! Passes that run after parsing (`check`, `interpreter`, any future
+//! evaluator) don't produce a `Rich` error of their own - they carry an
+//! [`Origin`], which may point at a synthetic node (e.g. a list
+//! comprehension's desugared `for` loop) rather than literal source text.
+//! [`render_origin_report`] renders those instead, resolving each one back
+//! to real source text via [`Origin::ast_node`]'s span.
+
+use std::path::PathBuf;
+
+use ariadne::Color;
+use ariadne::Label;
+use ariadne::Report;
+use ariadne::ReportKind;
+use ariadne::Source;
+use chumsky::error::Rich;
+use chumsky::error::RichReason;
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::ParseError;
+use hearthd_config::SourceInfo;
+
+use super::lexer::Token;
+use super::repr::lowered::Origin;
+
+#[cfg(test)]
+mod tests;
+
+/// Render `errs` (as returned by `parser::parse`/`parser::parse_stmt`)
+/// against `src`, one annotated report per error.
+pub fn render_report(src: &str, errs: &[Rich<Token>]) -> String {
+    let mut out = Vec::new();
+    for err in errs {
+        let span = *err.span();
+        let range = span.start..span.end;
+        let message = describe_reason(err.reason());
+
+        let report = Report::build(ReportKind::Error, ("input", range.clone()))
+            .with_message(&message)
+            .with_label(
+                Label::new(("input", range))
+                    .with_message(&message)
+                    .with_color(Color::Red),
+            )
+            .finish();
+        report.write(("input", Source::from(src)), &mut out).ok();
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// One diagnostic anchored to an [`Origin`] rather than a raw span -
+/// produced by a pass that runs after parsing, where the failing node's
+/// origin may point at a synthetic, desugared construct.
+#[derive(Debug, Clone)]
+pub struct OriginDiagnostic {
+    pub message: String,
+    pub origin: Origin,
+}
+
+/// Render a batch of post-parse diagnostics against `src`, resolving each
+/// [`Origin`] back to real source text via `Origin::ast_node().span`.
+pub fn render_origin_report(src: &str, diagnostics: &[OriginDiagnostic]) -> String {
+    let mut out = Vec::new();
+    for diag in diagnostics {
+        let span = diag.origin.span();
+        let range = span.start..span.end;
+
+        let report = Report::build(ReportKind::Error, ("input", range.clone()))
+            .with_message(&diag.message)
+            .with_label(
+                Label::new(("input", range))
+                    .with_message(&diag.message)
+                    .with_color(Color::Red),
+            )
+            .finish();
+        report.write(("input", Source::from(src)), &mut out).ok();
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Convert `errs` (as returned by `parser::parse`/`parser::parse_stmt`) into
+/// `hearthd_config::Diagnostic`s pointing at `file_path`, with `src` carried
+/// along as each one's [`SourceInfo`] so it renders with the exact byte span
+/// - the same shared representation `hearthd_config::format_diagnostics`/
+/// `format_diagnostics_json` already render TOML config errors with.
+pub fn to_diagnostics(
+    src: &str,
+    file_path: &std::path::Path,
+    errs: &[Rich<Token>],
+) -> Vec<Diagnostic> {
+    errs.iter()
+        .map(|err| {
+            let span = *err.span();
+            Diagnostic::Error(Error::Parse(ParseError {
+                message: describe_reason(err.reason()),
+                span: span.start..span.end,
+                source: SourceInfo {
+                    file_path: PathBuf::from(file_path),
+                    content: src.to_string(),
+                },
+            }))
+        })
+        .collect()
+}
+
+/// Render a `Rich` error's reason as an "expected X, found Y" message
+/// (or its custom message, if it was raised with one).
+fn describe_reason(reason: &RichReason<Token>) -> String {
+    match reason {
+        RichReason::ExpectedFound { expected, found } => {
+            let found = found
+                .as_ref()
+                .map(|tok| tok.to_string())
+                .unwrap_or_else(|| "end of input".to_string());
+            if expected.is_empty() {
+                format!("unexpected {found}")
+            } else {
+                let expected = expected
+                    .iter()
+                    .map(|pat| pat.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("expected {expected}, found {found}")
+            }
+        }
+        RichReason::Custom(message) => message.clone(),
+    }
+}
@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use chumsky::Parser;
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+
+use super::render_origin_report;
+use super::render_report;
+use super::to_diagnostics;
+use super::OriginDiagnostic;
+use crate::automations::repr::ast;
+use crate::automations::repr::lowered::Origin;
+
+// `ariadne`'s report includes ANSI color codes and box-drawing characters,
+// so these only check that the message and source snippet we asked for
+// made it into the rendered output, not the exact layout - there's no
+// snapshot test of `hearthd_config::diagnostics::format_diagnostics`'s
+// output either, for the same reason.
+
+#[test]
+fn render_report_includes_the_source_line_and_expected_found_message() {
+    let src = "let = 1;";
+    let errs = crate::automations::parser::parse_stmt(src)
+        .expect_err("missing a name after `let` should fail to parse");
+
+    let report = render_report(src, &errs);
+
+    assert!(!report.is_empty());
+    assert!(report.contains(src));
+    assert!(report.contains("expected") || report.contains("unexpected"));
+}
+
+#[test]
+fn render_origin_report_includes_the_message_and_source_line() {
+    let src = "1 + 2";
+    let origin = Origin::Direct(ast::Spanned::new(ast::Expr::Int(1), (0..1).into()));
+    let diagnostics = vec![OriginDiagnostic {
+        message: "division by a statically-zero divisor".to_string(),
+        origin,
+    }];
+
+    let report = render_origin_report(src, &diagnostics);
+
+    assert!(report.contains(src));
+    assert!(report.contains("division by a statically-zero divisor"));
+}
+
+#[test]
+fn to_diagnostics_reports_a_parse_error_with_the_exact_span_and_file() {
+    let src = "let = 1;";
+    let errs = crate::automations::parser::parse_stmt(src)
+        .expect_err("missing a name after `let` should fail to parse");
+
+    let diagnostics = to_diagnostics(src, Path::new("/tmp/trigger.hearth"), &errs);
+
+    assert_eq!(diagnostics.len(), errs.len());
+    let Diagnostic::Error(Error::Parse(parse_error)) = &diagnostics[0] else {
+        panic!("expected Error::Parse, got {:?}", diagnostics[0]);
+    };
+    assert_eq!(
+        parse_error.source.file_path,
+        Path::new("/tmp/trigger.hearth")
+    );
+    assert_eq!(parse_error.source.content, src);
+    let span = *errs[0].span();
+    assert_eq!(parse_error.span, span.start..span.end);
+}
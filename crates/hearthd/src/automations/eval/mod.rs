@@ -0,0 +1,816 @@
+//! Tree-walking interpreter for lowered HIR.
+//!
+//! Given bound top-level inputs (`event`, `state`), [`eval_automation`] walks
+//! the `HirAutomation`'s basic-block CFG starting at `bb0`, maintaining a map
+//! from `Tmp` to its runtime [`Value`], evaluating each instruction in order
+//! and following `Jump`/`Branch`/`Return`/`IterNext` terminators until a
+//! `Return` produces the automation's result — `[Event]` for observers, a
+//! single `Event` for mutators (both represented generically as [`Value`];
+//! callers that need the distinction use [`eval_observer`]/[`eval_mutator`]).
+//!
+//! Runs best against HIR that has already been through
+//! [`super::repr::optimize_program`], but doesn't require it — it only
+//! assumes every instruction it's asked to evaluate type-checked.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use super::int_ops::{checked_int_div, checked_int_mod};
+use super::repr::ast::UnitType;
+use super::repr::hir::*;
+use super::repr::units::{canonical_unit, dimension_of, to_base};
+
+#[cfg(test)]
+mod tests;
+
+/// A runtime value produced by evaluating HIR.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    /// A unit-bearing value normalized to its dimension's canonical base
+    /// unit (seconds, radians, or Kelvin), matching how `hir_fold` seeds
+    /// `Op::ConstUnit`.
+    Unit(f64, UnitType),
+    /// The `()` value.
+    Void,
+    /// An optional value, e.g. the result of an `OptionalField` access.
+    Option(Option<Box<Value>>),
+    List(Vec<Value>),
+    Set(Vec<Value>),
+    Map(Vec<(Value, Value)>),
+    Struct {
+        name: String,
+        fields: HashMap<String, Value>,
+    },
+    Variant {
+        enum_name: String,
+        variant: String,
+        args: Vec<Value>,
+    },
+}
+
+/// A runtime error raised while evaluating an automation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalError {
+    pub message: String,
+    pub span: Option<Range<usize>>,
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "error at {}..{}: {}", span.start, span.end, self.message),
+            None => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Safety cap on the number of terminators followed in a single evaluation,
+/// guarding against a loop that (due to a bug elsewhere) never terminates.
+const MAX_STEPS: usize = 1_000_000;
+
+/// Evaluate `automation` with `event` and `state` bound to its top-level
+/// `event`/`state` params (or, if the automation instead destructures the
+/// whole input as a single identifier, that identifier bound to a
+/// `{event, state}` struct), returning whatever `Value` its body returns.
+pub fn eval_automation(
+    automation: &HirAutomation,
+    event: Value,
+    state: Value,
+) -> Result<Value, EvalError> {
+    let mut interpreter = Interpreter::new(automation);
+    interpreter.bind_params(automation, event, state);
+    interpreter.run(automation)
+}
+
+/// Evaluate an observer automation, expecting its body to return `[Event]`.
+pub fn eval_observer(
+    automation: &HirAutomation,
+    event: Value,
+    state: Value,
+) -> Result<Vec<Value>, EvalError> {
+    match eval_automation(automation, event, state)? {
+        Value::List(events) => Ok(events),
+        other => Err(EvalError {
+            message: format!("observer body did not evaluate to a list of events: {other:?}"),
+            span: None,
+        }),
+    }
+}
+
+/// Evaluate a mutator automation, expecting its body to return a single
+/// `Event`.
+pub fn eval_mutator(
+    automation: &HirAutomation,
+    event: Value,
+    state: Value,
+) -> Result<Value, EvalError> {
+    eval_automation(automation, event, state)
+}
+
+struct Interpreter {
+    blocks: HashMap<BlockId, BasicBlock>,
+    values: HashMap<Tmp, Value>,
+    /// Cursor state for each live `Op::IterInit`-produced iterator, keyed by
+    /// its `Tmp`. Kept separate from `values` because `Terminator::IterNext`
+    /// mutates the same iterator across loop header re-entries.
+    iterators: HashMap<Tmp, VecDeque<Value>>,
+}
+
+impl Interpreter {
+    fn new(automation: &HirAutomation) -> Self {
+        let blocks = automation
+            .blocks
+            .iter()
+            .map(|b| (b.id, b.clone()))
+            .collect();
+        Self {
+            blocks,
+            values: HashMap::new(),
+            iterators: HashMap::new(),
+        }
+    }
+
+    fn bind_params(&mut self, automation: &HirAutomation, event: Value, state: Value) {
+        for param in &automation.params {
+            let value = match param.name.as_str() {
+                "event" => event.clone(),
+                "state" => state.clone(),
+                _ => Value::Struct {
+                    name: "Input".to_string(),
+                    fields: HashMap::from([
+                        ("event".to_string(), event.clone()),
+                        ("state".to_string(), state.clone()),
+                    ]),
+                },
+            };
+            self.values.insert(param.tmp, value);
+        }
+    }
+
+    /// Bind `target`'s block params to `args`, in order, ahead of jumping to
+    /// it — the runtime counterpart of the compile-time contract that every
+    /// `Jump`/`Branch` edge supplies one argument per target param.
+    fn bind_block_args(&mut self, target: BlockId, args: &[Tmp]) -> Result<(), EvalError> {
+        bind_block_args(&self.blocks, &mut self.values, target, args)
+    }
+
+    fn get(&self, tmp: Tmp) -> Result<Value, EvalError> {
+        get(&self.values, tmp)
+    }
+
+    fn run(&mut self, automation: &HirAutomation) -> Result<Value, EvalError> {
+        let mut current = automation
+            .blocks
+            .first()
+            .map(|b| b.id)
+            .unwrap_or(BlockId(0));
+
+        for _ in 0..MAX_STEPS {
+            let block = self.blocks.get(&current).cloned().ok_or_else(|| EvalError {
+                message: format!("jump to undefined block bb{}", current.0),
+                span: None,
+            })?;
+
+            for instr in &block.instructions {
+                // `IterInit`'s cursor must be keyed by this instruction's own
+                // `dst` (the `iter` operand `Terminator::IterNext` later
+                // reads), so it's handled here rather than in `eval_op`,
+                // which only ever computes a value, not a `(Tmp, Value)`
+                // pair.
+                if let Op::IterInit(tmp) = &instr.op {
+                    let items = match self.get(*tmp)? {
+                        Value::List(items) | Value::Set(items) => items,
+                        other => return Err(type_error("iterate over", &other, &instr.span)),
+                    };
+                    self.iterators.insert(instr.dst, items.into());
+                    self.values.insert(instr.dst, Value::Void);
+                    continue;
+                }
+
+                let value = eval_op(&mut self.values, &instr.op, &instr.span)?;
+                self.values.insert(instr.dst, value);
+            }
+
+            match &block.terminator {
+                Terminator::Jump(target, args) => {
+                    self.bind_block_args(*target, args)?;
+                    current = *target;
+                }
+                Terminator::Branch {
+                    cond,
+                    then_block,
+                    then_args,
+                    else_block,
+                    else_args,
+                } => {
+                    current = if as_bool(&self.get(*cond)?)? {
+                        self.bind_block_args(*then_block, then_args)?;
+                        *then_block
+                    } else {
+                        self.bind_block_args(*else_block, else_args)?;
+                        *else_block
+                    };
+                }
+                Terminator::Return(tmp) => return self.get(*tmp),
+                Terminator::IterNext {
+                    iter,
+                    value,
+                    body,
+                    exit,
+                } => match self.iterators.get_mut(iter).and_then(VecDeque::pop_front) {
+                    Some(next) => {
+                        self.values.insert(*value, next);
+                        current = *body;
+                    }
+                    None => current = *exit,
+                },
+                Terminator::Unreachable => {
+                    return Err(EvalError {
+                        message: "reached an unreachable match arm (non-exhaustive match)".into(),
+                        span: None,
+                    })
+                }
+                // Inserted by `hir_drop_elaborate`: `value` is dead along
+                // this path, so release it before continuing.
+                Terminator::Drop { value, target } => {
+                    self.values.remove(value);
+                    current = *target;
+                }
+            }
+        }
+
+        Err(EvalError {
+            message: format!("evaluation exceeded {MAX_STEPS} steps without returning"),
+            span: None,
+        })
+    }
+}
+
+/// Look up `tmp` in `values`, the shared read side [`eval_op`] and
+/// [`Stepper::step`] both use — a read of a `Tmp` nothing has written yet
+/// is always a bug somewhere upstream, not a recoverable runtime state.
+fn get(values: &HashMap<Tmp, Value>, tmp: Tmp) -> Result<Value, EvalError> {
+    values.get(&tmp).cloned().ok_or_else(|| EvalError {
+        message: format!("read of undefined temporary %{}", tmp.0),
+        span: None,
+    })
+}
+
+/// Bind `target`'s block params to `args`, in order, ahead of jumping to
+/// it — the runtime counterpart of the compile-time contract that every
+/// `Jump`/`Branch` edge supplies one argument per target param. Shared by
+/// [`Interpreter::bind_block_args`] and [`Stepper::step`].
+fn bind_block_args(
+    blocks: &HashMap<BlockId, BasicBlock>,
+    values: &mut HashMap<Tmp, Value>,
+    target: BlockId,
+    args: &[Tmp],
+) -> Result<(), EvalError> {
+    let params = blocks
+        .get(&target)
+        .map(|b| b.params.clone())
+        .unwrap_or_default();
+    for (param, arg) in params.iter().zip(args) {
+        let value = get(values, *arg)?;
+        values.insert(*param, value);
+    }
+    Ok(())
+}
+
+/// Evaluate `op` against `values`, writing through it directly for the two
+/// ops (`ListPush`/`ListExtend`) that mutate an existing `Tmp` in place
+/// instead of only producing a fresh one. Free rather than a method so
+/// both the big-step [`Interpreter::run`] and the small-step
+/// [`Stepper::step`] can share it.
+fn eval_op(
+    values: &mut HashMap<Tmp, Value>,
+    op: &Op,
+    span: &Option<Range<usize>>,
+) -> Result<Value, EvalError> {
+    match op {
+        Op::ConstInt(n) => Ok(Value::Int(*n)),
+        Op::ConstFloat(n) => Ok(Value::Float(*n)),
+        Op::ConstString(s) => Ok(Value::String(s.clone())),
+        Op::ConstBool(b) => Ok(Value::Bool(*b)),
+        Op::ConstUnit { value, unit } => {
+            let raw: f64 = value.parse().map_err(|_| EvalError {
+                message: format!("malformed unit literal value '{value}'"),
+                span: span.clone(),
+            })?;
+            Ok(Value::Unit(to_base(*unit, raw), canonical_unit(dimension_of(*unit))))
+        }
+        Op::Unit => Ok(Value::Void),
+
+        Op::BinOp { op, left, right } => {
+            eval_binop(*op, &get(values, *left)?, &get(values, *right)?, span)
+        }
+
+        Op::Neg(tmp) => match get(values, *tmp)? {
+            Value::Int(n) => Ok(Value::Int(-n)),
+            Value::Float(n) => Ok(Value::Float(-n)),
+            other => Err(type_error("negate", &other, span)),
+        },
+        Op::Not(tmp) => match get(values, *tmp)? {
+            Value::Bool(b) => Ok(Value::Bool(!b)),
+            other => Err(type_error("logical not", &other, span)),
+        },
+        Op::Deref(tmp) => get(values, *tmp),
+        Op::Await(tmp) => get(values, *tmp),
+
+        Op::Field { base, field } => eval_field(&get(values, *base)?, field, span),
+        Op::OptionalField { base, field } => eval_optional_field(&get(values, *base)?, field, span),
+
+        Op::Call { name, args } => {
+            let args = args
+                .iter()
+                .map(|a| get(values, *a))
+                .collect::<Result<Vec<_>, _>>()?;
+            eval_builtin(name, &args, span)
+        }
+
+        Op::Variant {
+            enum_name,
+            variant,
+            args,
+        } => {
+            let args = args
+                .iter()
+                .map(|a| get(values, *a))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Value::Variant {
+                enum_name: enum_name.clone(),
+                variant: variant.clone(),
+                args,
+            })
+        }
+
+        Op::VariantTest {
+            value,
+            enum_name,
+            variant,
+        } => match get(values, *value)? {
+            Value::Variant {
+                enum_name: en,
+                variant: va,
+                ..
+            } => Ok(Value::Bool(en == *enum_name && va == *variant)),
+            other => Err(type_error("test variant of", &other, span)),
+        },
+        Op::VariantField { base, index } => match get(values, *base)? {
+            Value::Variant { args, .. } => args.get(*index).cloned().ok_or_else(|| EvalError {
+                message: format!("variant has no positional field {index}"),
+                span: span.clone(),
+            }),
+            other => Err(type_error("extract variant field of", &other, span)),
+        },
+        Op::Discriminant(tmp) => match get(values, *tmp)? {
+            Value::Variant { variant, .. } => Ok(Value::String(variant)),
+            other => Err(type_error("take the discriminant of", &other, span)),
+        },
+
+        Op::EmptyList => Ok(Value::List(Vec::new())),
+        Op::List(items) => Ok(Value::List(
+            items.iter().map(|t| get(values, *t)).collect::<Result<_, _>>()?,
+        )),
+        // `list` is never rebound after a push (the `MutableList`
+        // accumulator desugaring keeps reading the same `Tmp`), so this
+        // must mutate the list in place at `list`'s own key rather than
+        // just producing a new value at `dst` — matching `hir_dce`,
+        // which always keeps a `ListPush` as side-effecting.
+        Op::ListPush { list, value } => match get(values, *list)? {
+            Value::List(mut items) => {
+                items.push(get(values, *value)?);
+                values.insert(*list, Value::List(items));
+                Ok(Value::Void)
+            }
+            other => Err(type_error("push onto", &other, span)),
+        },
+        // Same in-place-mutation rationale as `ListPush` above: `list`
+        // keeps referring to the same `Tmp` across every `+=`.
+        Op::ListExtend { list, value } => match (get(values, *list)?, get(values, *value)?) {
+            (Value::List(mut items), Value::List(extra)) => {
+                items.extend(extra);
+                values.insert(*list, Value::List(items));
+                Ok(Value::Void)
+            }
+            (other, _) => Err(type_error("extend", &other, span)),
+        },
+        // Handled by each driver's own loop, which needs this
+        // instruction's `dst` up front to key the iterator cursor.
+        Op::IterInit(_) => unreachable!("Op::IterInit is special-cased by its caller"),
+
+        Op::Struct { name, fields } => {
+            let mut built = HashMap::new();
+            for field in fields {
+                match field {
+                    HirStructField::Set { name, value } => {
+                        built.insert(name.clone(), get(values, *value)?);
+                    }
+                    HirStructField::Spread(tmp) => match get(values, *tmp)? {
+                        Value::Struct { fields, .. } => built.extend(fields),
+                        other => return Err(type_error("spread", &other, span)),
+                    },
+                }
+            }
+            Ok(Value::Struct {
+                name: name.clone(),
+                fields: built,
+            })
+        }
+
+        Op::Copy(tmp) => get(values, *tmp),
+    }
+}
+
+/// An automation's CFG, frozen for stepping: just the block map and an entry
+/// point, with none of [`Stepper`]'s per-run cursor state. Building this once
+/// and handing out many `Stepper`s (e.g. one per incoming event in a REPL
+/// session) avoids re-cloning `automation.blocks` on every run.
+pub struct ExecutionContext {
+    blocks: HashMap<BlockId, BasicBlock>,
+    entry: BlockId,
+}
+
+impl ExecutionContext {
+    pub fn new(automation: &HirAutomation) -> Self {
+        let blocks = automation
+            .blocks
+            .iter()
+            .map(|b| (b.id, b.clone()))
+            .collect();
+        let entry = automation
+            .blocks
+            .first()
+            .map(|b| b.id)
+            .unwrap_or(BlockId(0));
+        Self { blocks, entry }
+    }
+}
+
+/// What happened on a single [`Stepper::step`] call: either one instruction
+/// ran (its result is now in [`Stepper::values`] under the given `Tmp`), the
+/// current block's terminator was reached and dispatched, or the automation
+/// returned.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Assignment(Tmp),
+    Terminator(Terminator),
+    Done(Value),
+}
+
+/// A pull-style, single-step driver over an [`ExecutionContext`], for
+/// callers that need to inspect state *between* instructions - a debugger,
+/// a single-step REPL, or a test harness asserting on intermediate values -
+/// rather than running an automation to completion in one call the way
+/// [`eval_automation`] does. Shares its per-`Op` evaluation logic with
+/// [`Interpreter`] via the free functions above.
+pub struct Stepper<'a> {
+    context: &'a ExecutionContext,
+    current_block: BasicBlock,
+    index: usize,
+    values: HashMap<Tmp, Value>,
+    iterators: HashMap<Tmp, VecDeque<Value>>,
+}
+
+impl<'a> Stepper<'a> {
+    /// Start a `Stepper` at `context`'s entry block with `values` as the
+    /// initial environment (typically just the bound `event`/`state`
+    /// params - see [`Interpreter::bind_params`] for how those are built).
+    pub fn new(
+        context: &'a ExecutionContext,
+        values: HashMap<Tmp, Value>,
+    ) -> Result<Self, EvalError> {
+        let current_block =
+            context
+                .blocks
+                .get(&context.entry)
+                .cloned()
+                .ok_or_else(|| EvalError {
+                    message: format!("jump to undefined block bb{}", context.entry.0),
+                    span: None,
+                })?;
+        Ok(Self {
+            context,
+            current_block,
+            index: 0,
+            values,
+            iterators: HashMap::new(),
+        })
+    }
+
+    /// The environment as of the most recent `step()` call - what a debugger
+    /// or REPL would print between steps.
+    pub fn values(&self) -> &HashMap<Tmp, Value> {
+        &self.values
+    }
+
+    fn switch_to(&mut self, target: BlockId) -> Result<(), EvalError> {
+        self.current_block =
+            self.context
+                .blocks
+                .get(&target)
+                .cloned()
+                .ok_or_else(|| EvalError {
+                    message: format!("jump to undefined block bb{}", target.0),
+                    span: None,
+                })?;
+        self.index = 0;
+        Ok(())
+    }
+
+    /// Execute the current instruction and advance past it, or - once
+    /// `index` has reached the end of the block - dispatch the terminator
+    /// and switch to whichever block (if any) comes next.
+    pub fn step(&mut self) -> Result<Event, EvalError> {
+        if let Some(instr) = self.current_block.instructions.get(self.index).cloned() {
+            self.index += 1;
+
+            // Same rationale as `Interpreter::run`: `IterInit`'s cursor must
+            // be keyed by this instruction's own `dst`, so it's handled here
+            // rather than in `eval_op`.
+            if let Op::IterInit(tmp) = &instr.op {
+                let items = match get(&self.values, *tmp)? {
+                    Value::List(items) | Value::Set(items) => items,
+                    other => return Err(type_error("iterate over", &other, &instr.span)),
+                };
+                self.iterators.insert(instr.dst, items.into());
+                self.values.insert(instr.dst, Value::Void);
+                return Ok(Event::Assignment(instr.dst));
+            }
+
+            let value = eval_op(&mut self.values, &instr.op, &instr.span)?;
+            self.values.insert(instr.dst, value);
+            return Ok(Event::Assignment(instr.dst));
+        }
+
+        let terminator = self.current_block.terminator.clone();
+        match &terminator {
+            Terminator::Jump(target, args) => {
+                bind_block_args(&self.context.blocks, &mut self.values, *target, args)?;
+                self.switch_to(*target)?;
+            }
+            Terminator::Branch {
+                cond,
+                then_block,
+                then_args,
+                else_block,
+                else_args,
+            } => {
+                if as_bool(&get(&self.values, *cond)?)? {
+                    bind_block_args(
+                        &self.context.blocks,
+                        &mut self.values,
+                        *then_block,
+                        then_args,
+                    )?;
+                    self.switch_to(*then_block)?;
+                } else {
+                    bind_block_args(
+                        &self.context.blocks,
+                        &mut self.values,
+                        *else_block,
+                        else_args,
+                    )?;
+                    self.switch_to(*else_block)?;
+                }
+            }
+            Terminator::Return(tmp) => return Ok(Event::Done(get(&self.values, *tmp)?)),
+            Terminator::IterNext {
+                iter,
+                value,
+                body,
+                exit,
+            } => match self.iterators.get_mut(iter).and_then(VecDeque::pop_front) {
+                Some(next) => {
+                    self.values.insert(*value, next);
+                    self.switch_to(*body)?;
+                }
+                None => self.switch_to(*exit)?,
+            },
+            Terminator::Unreachable => {
+                return Err(EvalError {
+                    message: "reached an unreachable match arm (non-exhaustive match)".into(),
+                    span: None,
+                })
+            }
+            // Same rationale as `Interpreter::run`: release `value`, then
+            // continue to whatever this `Drop` was spliced in front of.
+            Terminator::Drop { value, target } => {
+                self.values.remove(value);
+                self.switch_to(*target)?;
+            }
+        }
+        Ok(Event::Terminator(terminator))
+    }
+
+    /// Drive `step()` to completion, discarding the intermediate `Event`s -
+    /// equivalent to [`Interpreter::run`], but built out of the same steps a
+    /// debugger would pause between. Uses the same [`MAX_STEPS`] cap, here
+    /// counting individual `step()` calls rather than terminators followed.
+    pub fn run(&mut self) -> Result<Value, EvalError> {
+        for _ in 0..MAX_STEPS {
+            if let Event::Done(value) = self.step()? {
+                return Ok(value);
+            }
+        }
+        Err(EvalError {
+            message: format!("evaluation exceeded {MAX_STEPS} steps without returning"),
+            span: None,
+        })
+    }
+}
+
+fn as_bool(value: &Value) -> Result<bool, EvalError> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        other => Err(type_error("branch on", other, &None)),
+    }
+}
+
+fn type_error(action: &str, value: &Value, span: &Option<Range<usize>>) -> EvalError {
+    EvalError {
+        message: format!("cannot {action} a value of this shape: {value:?}"),
+        span: span.clone(),
+    }
+}
+
+/// Look up `field` on a struct-shaped value (structs only — `Op::Field` is
+/// never emitted against a variant or collection by the lowering pass).
+fn eval_field(base: &Value, field: &str, span: &Option<Range<usize>>) -> Result<Value, EvalError> {
+    match base {
+        Value::Struct { fields, .. } => fields.get(field).cloned().ok_or_else(|| EvalError {
+            message: format!("no field '{field}' on struct value"),
+            span: span.clone(),
+        }),
+        other => Err(type_error(&format!("read field '{field}' of"), other, span)),
+    }
+}
+
+/// Like [`eval_field`], but tolerant of `base` already being an `Option`:
+/// `None` short-circuits to `Option(None)`, `Some(x)` looks up the field on
+/// `x` and re-wraps the result in `Option` if it isn't one already.
+fn eval_optional_field(
+    base: &Value,
+    field: &str,
+    span: &Option<Range<usize>>,
+) -> Result<Value, EvalError> {
+    match base {
+        Value::Option(None) => Ok(Value::Option(None)),
+        Value::Option(Some(inner)) => match eval_field(inner, field, span)? {
+            already_optional @ Value::Option(_) => Ok(already_optional),
+            other => Ok(Value::Option(Some(Box::new(other)))),
+        },
+        other => match eval_field(other, field, span)? {
+            already_optional @ Value::Option(_) => Ok(already_optional),
+            result => Ok(Value::Option(Some(Box::new(result)))),
+        },
+    }
+}
+
+fn eval_binop(
+    op: HirBinOp,
+    left: &Value,
+    right: &Value,
+    span: &Option<Range<usize>>,
+) -> Result<Value, EvalError> {
+    use HirBinOp::*;
+    use Value::*;
+
+    match (op, left, right) {
+        (_, Unit(a, ua), Unit(b, ub)) => {
+            let (da, db) = (dimension_of(*ua), dimension_of(*ub));
+            if da != db {
+                return Err(EvalError {
+                    message: format!(
+                        "cannot combine a {da} value with a {db} value: {op} requires both \
+                         sides to share a dimension"
+                    ),
+                    span: span.clone(),
+                });
+            }
+            match op {
+                Add => Ok(Unit(a + b, *ua)),
+                Sub => Ok(Unit(a - b, *ua)),
+                Eq => Ok(Bool(a == b)),
+                Ne => Ok(Bool(a != b)),
+                Lt => Ok(Bool(a < b)),
+                Le => Ok(Bool(a <= b)),
+                Gt => Ok(Bool(a > b)),
+                Ge => Ok(Bool(a >= b)),
+                _ => Err(binop_type_error(op, left, right, span)),
+            }
+        }
+        (Div, Int(_), Int(0)) | (Mod, Int(_), Int(0)) => Err(EvalError {
+            message: format!(
+                "{} by zero",
+                if op == Div { "division" } else { "modulo" }
+            ),
+            span: span.clone(),
+        }),
+        (Add, Int(a), Int(b)) => Ok(Int(a.wrapping_add(*b))),
+        (Sub, Int(a), Int(b)) => Ok(Int(a.wrapping_sub(*b))),
+        (Mul, Int(a), Int(b)) => Ok(Int(a.wrapping_mul(*b))),
+        // The b == 0 case is already handled by the guard arm above; this
+        // only needs to guard i64::MIN / -1, which plain / and % panic on.
+        (Div, Int(a), Int(b)) => Ok(Int(checked_int_div(*a, *b))),
+        (Mod, Int(a), Int(b)) => Ok(Int(checked_int_mod(*a, *b))),
+        (Add, Float(a), Float(b)) => Ok(Float(a + b)),
+        (Sub, Float(a), Float(b)) => Ok(Float(a - b)),
+        (Mul, Float(a), Float(b)) => Ok(Float(a * b)),
+        (Div, Float(a), Float(b)) => Ok(Float(a / b)),
+        (Add, String(a), String(b)) => Ok(String(format!("{a}{b}"))),
+        (In, needle, List(items)) | (In, needle, Set(items)) => Ok(Bool(items.contains(needle))),
+        (In, needle, Map(entries)) => Ok(Bool(entries.iter().any(|(k, _)| k == needle))),
+        (Eq, a, b) => Ok(Bool(a == b)),
+        (Ne, a, b) => Ok(Bool(a != b)),
+        (Lt, Int(a), Int(b)) => Ok(Bool(a < b)),
+        (Le, Int(a), Int(b)) => Ok(Bool(a <= b)),
+        (Gt, Int(a), Int(b)) => Ok(Bool(a > b)),
+        (Ge, Int(a), Int(b)) => Ok(Bool(a >= b)),
+        (Lt, Float(a), Float(b)) => Ok(Bool(a < b)),
+        (Le, Float(a), Float(b)) => Ok(Bool(a <= b)),
+        (Gt, Float(a), Float(b)) => Ok(Bool(a > b)),
+        (Ge, Float(a), Float(b)) => Ok(Bool(a >= b)),
+        _ => Err(binop_type_error(op, left, right, span)),
+    }
+}
+
+fn binop_type_error(
+    op: HirBinOp,
+    left: &Value,
+    right: &Value,
+    span: &Option<Range<usize>>,
+) -> EvalError {
+    EvalError {
+        message: format!("operator '{op}' is not defined for {left:?} and {right:?}"),
+        span: span.clone(),
+    }
+}
+
+/// Numeric builtins mirroring the subset typed by `check::check_call` that
+/// don't need closures or async scheduling to evaluate (`sleep`, `wait`, and
+/// `filter`'s callback argument aren't modeled yet).
+fn eval_builtin(name: &str, args: &[Value], span: &Option<Range<usize>>) -> Result<Value, EvalError> {
+    match name {
+        "len" => match args {
+            [Value::List(items)] | [Value::Set(items)] => Ok(Value::Int(items.len() as i64)),
+            [Value::Map(entries)] => Ok(Value::Int(entries.len() as i64)),
+            [Value::String(s)] => Ok(Value::Int(s.chars().count() as i64)),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "abs" => match args {
+            [Value::Int(n)] => Ok(Value::Int(n.abs())),
+            [Value::Float(n)] => Ok(Value::Float(n.abs())),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "min" => match args {
+            [Value::Int(a), Value::Int(b)] => Ok(Value::Int((*a).min(*b))),
+            [a, b] => Ok(Value::Float(as_f64(a, span)?.min(as_f64(b, span)?))),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "max" => match args {
+            [Value::Int(a), Value::Int(b)] => Ok(Value::Int((*a).max(*b))),
+            [a, b] => Ok(Value::Float(as_f64(a, span)?.max(as_f64(b, span)?))),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "clamp" => match args {
+            [Value::Int(v), Value::Int(lo), Value::Int(hi)] => Ok(Value::Int((*v).clamp(*lo, *hi))),
+            [v, lo, hi] => Ok(Value::Float(
+                as_f64(v, span)?.clamp(as_f64(lo, span)?, as_f64(hi, span)?),
+            )),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "keys" => match args {
+            [Value::Map(entries)] => Ok(Value::List(entries.iter().map(|(k, _)| k.clone()).collect())),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        "values" => match args {
+            [Value::Map(entries)] => Ok(Value::List(entries.iter().map(|(_, v)| v.clone()).collect())),
+            _ => Err(builtin_arity_error(name, args, span)),
+        },
+        other => Err(EvalError {
+            message: format!("builtin '{other}' is not supported by the interpreter"),
+            span: span.clone(),
+        }),
+    }
+}
+
+fn as_f64(value: &Value, span: &Option<Range<usize>>) -> Result<f64, EvalError> {
+    match value {
+        Value::Int(n) => Ok(*n as f64),
+        Value::Float(n) => Ok(*n),
+        other => Err(type_error("use as a number", other, span)),
+    }
+}
+
+fn builtin_arity_error(name: &str, args: &[Value], span: &Option<Range<usize>>) -> EvalError {
+    EvalError {
+        message: format!("builtin '{name}' cannot be called with arguments {args:?}"),
+        span: span.clone(),
+    }
+}
@@ -0,0 +1,629 @@
+use super::super::repr::ast::{AutomationKind, UnitType};
+use super::super::repr::hir::*;
+use super::super::repr::typed::Ty;
+use super::*;
+
+fn instr(dst: usize, op: Op, ty: Ty) -> Instruction {
+    Instruction {
+        dst: Tmp(dst),
+        op,
+        ty,
+        span: None,
+    }
+}
+
+fn automation(params: Vec<Param>, blocks: Vec<BasicBlock>) -> HirAutomation {
+    HirAutomation {
+        kind: AutomationKind::Observer,
+        params,
+        blocks,
+    }
+}
+
+fn no_event_state() -> (Value, Value) {
+    (Value::Void, Value::Void)
+}
+
+#[test]
+fn evaluates_constant_arithmetic() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(0, Op::ConstInt(2), Ty::Int),
+                instr(1, Op::ConstInt(3), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            terminator: Terminator::Return(Tmp(2)),
+        }],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Int(5));
+}
+
+#[test]
+fn reports_runtime_division_by_zero() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(0, Op::ConstInt(10), Ty::Int),
+                instr(1, Op::ConstInt(0), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Div,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            terminator: Terminator::Return(Tmp(2)),
+        }],
+    );
+
+    let (event, state) = no_event_state();
+    let err = eval_automation(&automation, event, state).unwrap_err();
+    assert!(err.message.contains("division"));
+}
+
+#[test]
+fn follows_branch_on_bound_bool() {
+    let param_tmp = Tmp(0);
+    let automation = automation(
+        vec![Param {
+            name: "event".to_string(),
+            tmp: param_tmp,
+            ty: Ty::Named("Event".to_string()),
+        }],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Branch {
+                    cond: param_tmp,
+                    then_block: BlockId(1),
+                    then_args: vec![],
+                    else_block: BlockId(2),
+                    else_args: vec![],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(1), Ty::Int)],
+                terminator: Terminator::Return(Tmp(1)),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(2, Op::ConstInt(2), Ty::Int)],
+                terminator: Terminator::Return(Tmp(2)),
+            },
+        ],
+    );
+
+    let result = eval_automation(&automation, Value::Bool(true), Value::Void).unwrap();
+    assert_eq!(result, Value::Int(1));
+
+    let result = eval_automation(&automation, Value::Bool(false), Value::Void).unwrap();
+    assert_eq!(result, Value::Int(2));
+}
+
+#[test]
+fn iterates_a_list_to_completion() {
+    // Mirrors the for-loop lowering shape (`lower_for`): `IterInit` in bb0,
+    // a header block that either enters the body or exits, and a body that
+    // jumps back to the header. Exercises the iterator cursor across
+    // multiple re-entries into the same header block.
+    let elem_tmp = Tmp(0);
+    let list_tmp = Tmp(1);
+    let iter_tmp = Tmp(2);
+    let value_tmp = Tmp(3);
+
+    let automation = automation(
+        vec![],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(elem_tmp.0, Op::ConstInt(7), Ty::Int),
+                    instr(
+                        list_tmp.0,
+                        Op::List(vec![elem_tmp, elem_tmp, elem_tmp]),
+                        Ty::List(Box::new(Ty::Int)),
+                    ),
+                    instr(iter_tmp.0, Op::IterInit(list_tmp), Ty::List(Box::new(Ty::Int))),
+                ],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::IterNext {
+                    iter: iter_tmp,
+                    value: value_tmp,
+                    body: BlockId(2),
+                    exit: BlockId(3),
+                },
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: Vec::new(),
+                instructions: vec![instr(4, Op::ConstInt(0), Ty::Int)],
+                terminator: Terminator::Return(Tmp(4)),
+            },
+        ],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Int(0));
+}
+
+#[test]
+fn struct_field_and_spread_construction() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(0, Op::ConstInt(1), Ty::Int),
+                instr(
+                    1,
+                    Op::Struct {
+                        name: "Point".to_string(),
+                        fields: vec![HirStructField::Set {
+                            name: "x".to_string(),
+                            value: Tmp(0),
+                        }],
+                    },
+                    Ty::Named("Point".to_string()),
+                ),
+                instr(
+                    2,
+                    Op::Struct {
+                        name: "Point".to_string(),
+                        fields: vec![
+                            HirStructField::Spread(Tmp(1)),
+                            HirStructField::Set {
+                                name: "y".to_string(),
+                                value: Tmp(0),
+                            },
+                        ],
+                    },
+                    Ty::Named("Point".to_string()),
+                ),
+                instr(3, Op::Field { base: Tmp(2), field: "x".to_string() }, Ty::Int),
+            ],
+            terminator: Terminator::Return(Tmp(3)),
+        }],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Int(1));
+}
+
+#[test]
+fn calls_clamp_and_keys_builtins() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(0, Op::ConstInt(42), Ty::Int),
+                instr(1, Op::ConstInt(0), Ty::Int),
+                instr(2, Op::ConstInt(10), Ty::Int),
+                instr(
+                    3,
+                    Op::Call {
+                        name: "clamp".to_string(),
+                        args: vec![Tmp(0), Tmp(1), Tmp(2)],
+                    },
+                    Ty::Int,
+                ),
+            ],
+            terminator: Terminator::Return(Tmp(3)),
+        }],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Int(10));
+}
+
+#[test]
+fn copy_propagated_unit_literal_round_trips_through_dimension_check() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(
+                    0,
+                    Op::ConstUnit {
+                        value: "5".to_string(),
+                        unit: UnitType::Minutes,
+                    },
+                    Ty::Duration,
+                ),
+                instr(
+                    1,
+                    Op::ConstUnit {
+                        value: "2.5".to_string(),
+                        unit: UnitType::Hours,
+                    },
+                    Ty::Duration,
+                ),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Duration,
+                ),
+            ],
+            terminator: Terminator::Return(Tmp(2)),
+        }],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Unit(9300.0, UnitType::Seconds));
+}
+
+#[test]
+fn merges_branch_arms_through_block_param() {
+    // Mirrors `lower_if`'s shape: bb0 branches, each arm jumps to bb3
+    // supplying its own value for bb3's sole param, which bb3 then returns.
+    let automation = automation(
+        vec![],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: vec![],
+                    else_block: BlockId(2),
+                    else_args: vec![],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(10), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(1)]),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(2, Op::ConstInt(20), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(2)]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: vec![Tmp(3)],
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(3)),
+            },
+        ],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::Int(10));
+}
+
+#[test]
+fn list_push_mutates_the_list_in_place_across_loop_iterations() {
+    // Mirrors desugared list comprehensions (`desugar_list_comp`): the
+    // accumulator `Tmp` is never rebound, so each `ListPush` must mutate it
+    // in place rather than produce a discarded new list at `dst`.
+    let list_tmp = Tmp(0);
+    let elem_tmp = Tmp(1);
+    let source_tmp = Tmp(2);
+    let iter_tmp = Tmp(3);
+    let value_tmp = Tmp(4);
+
+    let automation = automation(
+        vec![],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(list_tmp.0, Op::EmptyList, Ty::List(Box::new(Ty::Int))),
+                    instr(elem_tmp.0, Op::ConstInt(1), Ty::Int),
+                    instr(
+                        source_tmp.0,
+                        Op::List(vec![elem_tmp, elem_tmp]),
+                        Ty::List(Box::new(Ty::Int)),
+                    ),
+                    instr(iter_tmp.0, Op::IterInit(source_tmp), Ty::List(Box::new(Ty::Int))),
+                ],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::IterNext {
+                    iter: iter_tmp,
+                    value: value_tmp,
+                    body: BlockId(2),
+                    exit: BlockId(3),
+                },
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(
+                    5,
+                    Op::ListPush {
+                        list: list_tmp,
+                        value: value_tmp,
+                    },
+                    Ty::Unit,
+                )],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(list_tmp),
+            },
+        ],
+    );
+
+    let (event, state) = no_event_state();
+    let result = eval_automation(&automation, event, state).unwrap();
+    assert_eq!(result, Value::List(vec![Value::Int(1), Value::Int(1)]));
+}
+
+#[test]
+fn observer_and_mutator_entry_points_unwrap_expected_shapes() {
+    let observer = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![instr(0, Op::EmptyList, Ty::List(Box::new(Ty::Named("Event".to_string()))))],
+            terminator: Terminator::Return(Tmp(0)),
+        }],
+    );
+    let events = eval_observer(&observer, Value::Void, Value::Void).unwrap();
+    assert!(events.is_empty());
+
+    let mutator = automation(
+        vec![Param {
+            name: "event".to_string(),
+            tmp: Tmp(0),
+            ty: Ty::Named("Event".to_string()),
+        }],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![],
+            terminator: Terminator::Return(Tmp(0)),
+        }],
+    );
+    let event = Value::Variant {
+        enum_name: "Event".to_string(),
+        variant: "LightStateChanged".to_string(),
+        args: vec![],
+    };
+    let result = eval_mutator(&mutator, event.clone(), Value::Void).unwrap();
+    assert_eq!(result, event);
+}
+
+/// Mirrors `lower_match`'s shape for `match event { Event::LightStateChanged(e)
+/// => { e }, _ => { "none" } }`: a discriminant test branching to either a
+/// field extraction or a fallback constant, joined at a block-param merge.
+fn light_state_changed_or_none_automation() -> HirAutomation {
+    automation(
+        vec![Param {
+            name: "event".to_string(),
+            tmp: Tmp(0),
+            ty: Ty::Named("Event".to_string()),
+        }],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(
+                    1,
+                    Op::VariantTest {
+                        value: Tmp(0),
+                        enum_name: "Event".to_string(),
+                        variant: "LightStateChanged".to_string(),
+                    },
+                    Ty::Bool,
+                )],
+                terminator: Terminator::Branch {
+                    cond: Tmp(1),
+                    then_block: BlockId(1),
+                    then_args: vec![],
+                    else_block: BlockId(2),
+                    else_args: vec![],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(
+                    2,
+                    Op::VariantField {
+                        base: Tmp(0),
+                        index: 0,
+                    },
+                    Ty::String,
+                )],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(2)]),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(3, Op::ConstString("none".to_string()), Ty::String)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(3)]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: vec![Tmp(4)],
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(4)),
+            },
+        ],
+    )
+}
+
+#[test]
+fn variant_test_selects_matching_arm_and_extracts_field() {
+    let automation = light_state_changed_or_none_automation();
+    let event = Value::Variant {
+        enum_name: "Event".to_string(),
+        variant: "LightStateChanged".to_string(),
+        args: vec![Value::String("lamp".to_string())],
+    };
+    let result = eval_automation(&automation, event, Value::Void).unwrap();
+    assert_eq!(result, Value::String("lamp".to_string()));
+}
+
+#[test]
+fn variant_test_falls_through_to_else_on_mismatch() {
+    let automation = light_state_changed_or_none_automation();
+    let event = Value::Variant {
+        enum_name: "Event".to_string(),
+        variant: "BinarySensorStateChanged".to_string(),
+        args: vec![Value::String("motion".to_string())],
+    };
+    let result = eval_automation(&automation, event, Value::Void).unwrap();
+    assert_eq!(result, Value::String("none".to_string()));
+}
+
+#[test]
+fn stepper_yields_one_assignment_event_per_instruction() {
+    let automation = automation(
+        vec![],
+        vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![
+                instr(0, Op::ConstInt(2), Ty::Int),
+                instr(1, Op::ConstInt(3), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            terminator: Terminator::Return(Tmp(2)),
+        }],
+    );
+
+    let context = ExecutionContext::new(&automation);
+    let mut stepper = Stepper::new(&context, HashMap::new()).unwrap();
+
+    assert!(matches!(stepper.step().unwrap(), Event::Assignment(Tmp(0))));
+    assert_eq!(stepper.values().get(&Tmp(0)), Some(&Value::Int(2)));
+    assert!(matches!(stepper.step().unwrap(), Event::Assignment(Tmp(1))));
+    assert!(matches!(stepper.step().unwrap(), Event::Assignment(Tmp(2))));
+    assert_eq!(stepper.values().get(&Tmp(2)), Some(&Value::Int(5)));
+    assert!(matches!(
+        stepper.step().unwrap(),
+        Event::Done(Value::Int(5))
+    ));
+}
+
+#[test]
+fn stepper_emits_a_terminator_event_on_jump_and_run_matches_eval_automation() {
+    // Mirrors `merges_branch_arms_through_block_param` above, but driven one
+    // step at a time so the intermediate `Terminator` event at the branch is
+    // observable before the merge block's `Return`.
+    let automation = automation(
+        vec![],
+        vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: vec![],
+                    else_block: BlockId(2),
+                    else_args: vec![],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(10), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(1)]),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(2, Op::ConstInt(20), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(2)]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: vec![Tmp(3)],
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(3)),
+            },
+        ],
+    );
+
+    let context = ExecutionContext::new(&automation);
+    let mut stepper = Stepper::new(&context, HashMap::new()).unwrap();
+
+    assert!(matches!(stepper.step().unwrap(), Event::Assignment(Tmp(0))));
+    assert!(matches!(
+        stepper.step().unwrap(),
+        Event::Terminator(Terminator::Branch { .. })
+    ));
+
+    let (event, state) = no_event_state();
+    let expected = eval_automation(&automation, event, state).unwrap();
+    let mut stepper = Stepper::new(&context, HashMap::new()).unwrap();
+    assert_eq!(stepper.run().unwrap(), expected);
+}
@@ -0,0 +1,924 @@
+//! Source-faithful formatter: renders AST nodes back into clean, canonical
+//! DSL source with automatic inline-vs-multiline layout.
+//!
+//! [`PrettyPrint`](super::pretty_print::PrettyPrint) only produces the
+//! verbose, multi-line snapshot form used by parser tests; it never
+//! reproduces anything resembling the original syntax. [`SourceFormat`]
+//! fills that gap, following the classic Oppen two-pass pretty-printing
+//! algorithm (D. C. Oppen, "Pretty Printing", 1980) rather than deciding
+//! line wraps with ad-hoc per-construct width checks:
+//!
+//! 1. Each AST node is lowered into a flat stream of [`Token`]s: `Text` for
+//!    literal source text, `Break` for a point a line may wrap (printed as
+//!    `blank` spaces if not wrapped, or a newline plus indent otherwise),
+//!    and matched `Begin`/`End` pairs delimiting a group that is sized and
+//!    wrapped as a unit.
+//! 2. [`scan`] walks the stream once, computing the total flat width every
+//!    `Begin...End` group would occupy if printed on one line, plus (for
+//!    groups that turn out not to fit) the width of the segment following
+//!    each `Break` up to the next `Break`/`End` at that group's depth.
+//! 3. [`print_tokens`] walks the stream again carrying a `remaining`
+//!    columns budget. At each `Begin` it decides, from the group's
+//!    precomputed flat width, whether the group fits on the current line:
+//!    if so every `Break` inside it prints flat; if not, the group
+//!    "opens". A `consistent` group then wraps every contained `Break`; a
+//!    non-consistent ("fill") group only wraps the individual `Break`s
+//!    whose following segment would actually overflow, packing as much as
+//!    fits per line.
+//!
+//! This only formats nodes from [`super::ast`]; the `repr`-level typed and
+//! lowered representations exist for compilation and debugging, not as a
+//! round-trip source format.
+
+use super::ast::*;
+
+const INDENT_WIDTH: isize = 4;
+
+/// Right margin [`SourceFormat::to_source_string`] wraps at when the caller
+/// doesn't need a different one (e.g. a `hearthd fmt` CLI honoring a
+/// project-configured width would call [`SourceFormat::format_source`]
+/// directly instead).
+pub const DEFAULT_MAX_WIDTH: usize = 80;
+
+/// One element of the layout stream lowered from an AST node.
+#[derive(Debug, Clone)]
+enum Token {
+    /// Literal source text, printed verbatim.
+    Text(String),
+    /// A point the line may wrap: prints as `blank` spaces if the
+    /// enclosing group doesn't wrap this break, or a newline plus the
+    /// group's indent (extended by `offset`) if it does.
+    Break { blank: usize, offset: isize },
+    /// Opens a group sized and wrapped as a unit: either the whole group
+    /// fits on the current line, or every `Break` directly inside it (for
+    /// `consistent` groups) or only the overflowing ones (for "fill"
+    /// groups) wraps. `offset` is the indent added for wrapped breaks
+    /// inside this group.
+    Begin { offset: isize, consistent: bool },
+    /// Closes the most recently opened [`Token::Begin`].
+    End,
+}
+
+/// Accumulates the [`Token`] stream lowered from an AST node.
+#[derive(Default)]
+struct Builder {
+    tokens: Vec<Token>,
+}
+
+impl Builder {
+    fn text(&mut self, s: impl Into<String>) {
+        self.tokens.push(Token::Text(s.into()));
+    }
+
+    fn brk(&mut self, blank: usize, offset: isize) {
+        self.tokens.push(Token::Break { blank, offset });
+    }
+
+    fn begin(&mut self, offset: isize, consistent: bool) {
+        self.tokens.push(Token::Begin { offset, consistent });
+    }
+
+    fn end(&mut self) {
+        self.tokens.push(Token::End);
+    }
+}
+
+/// Renders a comma-separated, possibly-bracketed list as a sized group.
+///
+/// `padded` adds a space just inside `open`/`close` when the group prints
+/// flat (used for brace-delimited struct/pattern bodies, e.g. `{ x, y }`);
+/// bracket/paren bodies (calls, lists) print tight (`f(a, b)`) instead.
+fn delimited(
+    b: &mut Builder,
+    open: &str,
+    close: &str,
+    consistent: bool,
+    padded: bool,
+    count: usize,
+    mut item: impl FnMut(&mut Builder, usize),
+) {
+    b.text(open);
+    if count == 0 {
+        b.text(close);
+        return;
+    }
+    let pad = usize::from(padded);
+    b.begin(INDENT_WIDTH, consistent);
+    b.brk(pad, 0);
+    for i in 0..count {
+        item(b, i);
+        if i + 1 < count {
+            b.text(",");
+            b.brk(1, 0);
+        }
+    }
+    b.brk(pad, -INDENT_WIDTH);
+    b.end();
+    b.text(close);
+}
+
+fn block_tokens(b: &mut Builder, stmts: &[Spanned<Stmt>]) {
+    if stmts.is_empty() {
+        b.text("{}");
+        return;
+    }
+    b.text("{");
+    b.begin(INDENT_WIDTH, true);
+    b.brk(1, 0);
+    let last = stmts.len() - 1;
+    for (i, stmt) in stmts.iter().enumerate() {
+        stmt_tokens(b, &stmt.node);
+        if i != last {
+            b.text(";");
+            b.brk(1, 0);
+        } else if matches!(
+            stmt.node,
+            Stmt::Let { .. } | Stmt::LetMut { .. } | Stmt::CompoundAssign { .. }
+        ) {
+            b.text(";");
+        }
+    }
+    b.brk(1, -INDENT_WIDTH);
+    b.end();
+    b.text("}");
+}
+
+fn stmt_tokens(b: &mut Builder, stmt: &Stmt) {
+    match stmt {
+        Stmt::Let { name, value } => {
+            b.text(format!("let {} = ", name));
+            expr_tokens(b, &value.node);
+        }
+        Stmt::LetMut { name, value } => {
+            b.text(format!("let mut {} = ", name));
+            expr_tokens(b, &value.node);
+        }
+        Stmt::CompoundAssign { name, op, value } => {
+            b.text(format!("{} {}= ", name, op));
+            expr_tokens(b, &value.node);
+        }
+        Stmt::Expr(e) => expr_tokens(b, &e.node),
+    }
+}
+
+/// Precedence of a [`BinOp`], higher binds tighter. Matches the grammar in
+/// `parser.rs`: `..`/`..=` loosest, then `||`, then `&&`, then comparisons,
+/// then `+`/`-`, then `*`/`/`/`%`.
+fn bin_prec(op: BinOp) -> u8 {
+    match op {
+        BinOp::Range | BinOp::RangeInclusive => 0,
+        BinOp::Or => 1,
+        BinOp::And => 2,
+        BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+        BinOp::Add | BinOp::Sub => 4,
+        BinOp::Mul | BinOp::Div | BinOp::Mod => 5,
+    }
+}
+
+/// Whether `expr` needs parens when used as the operand of a unary op, a
+/// field access, or a call's callee - i.e. whether it's a looser-binding
+/// expression form than those positions allow unparenthesized.
+fn is_low_precedence(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::BinOp { .. } | Expr::If { .. } | Expr::Match { .. }
+    )
+}
+
+fn expr_tokens(b: &mut Builder, expr: &Expr) {
+    match expr {
+        Expr::Int(n) => b.text(n.to_string()),
+        Expr::Float(s) => b.text(s.clone()),
+        Expr::String(s) => b.text(format!("{:?}", s)),
+        Expr::Bool(v) => b.text(v.to_string()),
+        Expr::UnitLiteral { value, unit } => b.text(format!("{}{}", value, unit)),
+        Expr::Ident(s) => b.text(s.clone()),
+
+        Expr::BinOp { op, left, right } => {
+            let prec = bin_prec(*op);
+            b.begin(INDENT_WIDTH, true);
+            let left_parens =
+                matches!(&left.node, Expr::BinOp { op: lop, .. } if bin_prec(*lop) < prec);
+            parenthesized(b, left_parens, |b| expr_tokens(b, &left.node));
+            b.text(format!(" {}", op));
+            b.brk(1, 0);
+            let right_parens =
+                matches!(&right.node, Expr::BinOp { op: rop, .. } if bin_prec(*rop) <= prec);
+            parenthesized(b, right_parens, |b| expr_tokens(b, &right.node));
+            b.end();
+        }
+
+        Expr::UnaryOp { op, expr: operand } => {
+            let text = op.to_string();
+            if matches!(op, UnaryOp::Await) {
+                b.text(format!("{} ", text));
+            } else {
+                b.text(text);
+            }
+            parenthesized(b, is_low_precedence(&operand.node), |b| {
+                expr_tokens(b, &operand.node)
+            });
+        }
+
+        Expr::Field { expr: inner, field } => {
+            parenthesized(b, is_low_precedence(&inner.node), |b| {
+                expr_tokens(b, &inner.node)
+            });
+            b.text(format!(".{}", field));
+        }
+        Expr::OptionalField { expr: inner, field } => {
+            parenthesized(b, is_low_precedence(&inner.node), |b| {
+                expr_tokens(b, &inner.node)
+            });
+            b.text(format!("?.{}", field));
+        }
+
+        Expr::Call { func, args } => {
+            parenthesized(b, is_low_precedence(&func.node), |b| {
+                expr_tokens(b, &func.node)
+            });
+            delimited(b, "(", ")", true, false, args.len(), |b, i| {
+                arg_tokens(b, &args[i].node)
+            });
+        }
+
+        Expr::If {
+            cond,
+            then_block,
+            else_block,
+        } => {
+            b.text("if ");
+            expr_tokens(b, &cond.node);
+            b.text(" ");
+            block_tokens(b, then_block);
+            b.text(" else ");
+            block_tokens(b, else_block);
+        }
+
+        Expr::List(items) => {
+            // Inconsistent ("fill") so short scalar elements pack several
+            // per line instead of always going one-per-line.
+            delimited(b, "[", "]", false, false, items.len(), |b, i| {
+                expr_tokens(b, &items[i].node)
+            });
+        }
+
+        Expr::ListComp {
+            expr: body,
+            clauses,
+        } => {
+            b.text("[");
+            b.begin(INDENT_WIDTH, true);
+            expr_tokens(b, &body.node);
+            comp_clauses_tokens(b, clauses);
+            b.end();
+            b.text("]");
+        }
+
+        Expr::DictComp {
+            key,
+            value,
+            clauses,
+        } => {
+            b.text("{");
+            b.begin(INDENT_WIDTH, true);
+            expr_tokens(b, &key.node);
+            b.text(": ");
+            expr_tokens(b, &value.node);
+            comp_clauses_tokens(b, clauses);
+            b.end();
+            b.text("}");
+        }
+
+        Expr::SetComp {
+            expr: body,
+            clauses,
+        } => {
+            b.text("{");
+            b.begin(INDENT_WIDTH, true);
+            expr_tokens(b, &body.node);
+            comp_clauses_tokens(b, clauses);
+            b.end();
+            b.text("}");
+        }
+
+        Expr::StructLit { name, fields } => {
+            b.text(format!("{} ", name));
+            delimited(b, "{", "}", true, true, fields.len(), |b, i| {
+                struct_field_tokens(b, &fields[i].node)
+            });
+        }
+
+        Expr::Match { scrutinee, arms } => {
+            b.text("match ");
+            expr_tokens(b, &scrutinee.node);
+            b.text(" ");
+            delimited(b, "{", "}", true, true, arms.len(), |b, i| {
+                match_arm_tokens(b, &arms[i])
+            });
+        }
+
+        Expr::Lambda { params, body } => {
+            b.text(format!("|{}| ", params.join(", ")));
+            expr_tokens(b, &body.node);
+        }
+
+        Expr::Tuple(items) => {
+            delimited(b, "(", ")", false, false, items.len(), |b, i| {
+                expr_tokens(b, &items[i].node)
+            });
+        }
+    }
+}
+
+/// Wraps `f`'s output in parens when `needed`, without affecting layout
+/// otherwise.
+fn parenthesized(b: &mut Builder, needed: bool, f: impl FnOnce(&mut Builder)) {
+    if needed {
+        b.text("(");
+        f(b);
+        b.text(")");
+    } else {
+        f(b);
+    }
+}
+
+fn arg_tokens(b: &mut Builder, arg: &Arg) {
+    match arg {
+        Arg::Positional(e) => expr_tokens(b, &e.node),
+        Arg::Named { name, value } => {
+            b.text(format!("{} = ", name));
+            expr_tokens(b, &value.node);
+        }
+    }
+}
+
+fn struct_field_tokens(b: &mut Builder, field: &StructField) {
+    match field {
+        StructField::Field { name, value } => {
+            b.text(format!("{}: ", name));
+            expr_tokens(b, &value.node);
+        }
+        StructField::Inherit(name) => b.text(format!("inherit {}", name)),
+        StructField::Spread(name) => b.text(format!("...{}", name)),
+    }
+}
+
+fn pattern_tokens(b: &mut Builder, pattern: &Pattern) {
+    match pattern {
+        Pattern::Ident(name) => b.text(name.clone()),
+        Pattern::Struct { fields, has_rest } => {
+            let count = fields.len() + usize::from(*has_rest);
+            delimited(b, "{", "}", true, true, count, |b, i| {
+                if i < fields.len() {
+                    field_pattern_tokens(b, &fields[i].node);
+                } else {
+                    b.text("...");
+                }
+            });
+        }
+    }
+}
+
+/// Renders a comprehension's `for`/`if` clauses, shared by `ListComp`,
+/// `DictComp`, and `SetComp`. Assumes the caller already opened the group
+/// the clauses break inside of.
+fn comp_clauses_tokens(b: &mut Builder, clauses: &[CompClause]) {
+    for clause in clauses {
+        b.brk(1, 0);
+        match clause {
+            CompClause::For { var, iter } => {
+                b.text("for ");
+                bind_pattern_tokens(b, &var.node);
+                b.text(" in ");
+                expr_tokens(b, &iter.node);
+            }
+            CompClause::If(cond) => {
+                b.text("if ");
+                expr_tokens(b, &cond.node);
+            }
+        }
+    }
+}
+
+fn bind_pattern_tokens(b: &mut Builder, pattern: &BindPattern) {
+    match pattern {
+        BindPattern::Ident(name) => b.text(name.clone()),
+        BindPattern::Tuple(elems) => {
+            delimited(b, "(", ")", true, true, elems.len(), |b, i| {
+                bind_pattern_tokens(b, &elems[i].node)
+            });
+        }
+    }
+}
+
+fn field_pattern_tokens(b: &mut Builder, field: &FieldPattern) {
+    match &field.pattern {
+        Some(nested) => {
+            b.text(format!("{}: ", field.name));
+            pattern_tokens(b, &nested.node);
+        }
+        None => b.text(field.name.clone()),
+    }
+}
+
+fn match_pattern_tokens(b: &mut Builder, pattern: &MatchPattern) {
+    match pattern {
+        MatchPattern::Variant {
+            enum_name,
+            variant,
+            bindings,
+        } => {
+            b.text(format!("{}::{}", enum_name, variant));
+            if !bindings.is_empty() {
+                delimited(b, "(", ")", true, false, bindings.len(), |b, i| {
+                    binding_pattern_tokens(b, &bindings[i].node)
+                });
+            }
+        }
+        MatchPattern::Wildcard => b.text("_"),
+    }
+}
+
+fn binding_pattern_tokens(b: &mut Builder, binding: &BindingPattern) {
+    match binding {
+        BindingPattern::Ident(name) => b.text(name.clone()),
+        BindingPattern::Wildcard => b.text("_"),
+    }
+}
+
+fn match_arm_tokens(b: &mut Builder, arm: &MatchArm) {
+    match_pattern_tokens(b, &arm.pattern.node);
+    b.text(" => ");
+    block_tokens(b, &arm.body);
+}
+
+fn automation_tokens(b: &mut Builder, automation: &Automation) {
+    b.text(format!("{} ", automation.kind));
+    pattern_tokens(b, &automation.pattern.node);
+    b.text(" /");
+    expr_tokens(b, &automation.filter.node);
+    b.text("/ ");
+    block_tokens(b, &automation.body);
+}
+
+fn type_tokens(b: &mut Builder, ty: &Type) {
+    match ty {
+        Type::Named(name) => b.text(name.clone()),
+        Type::List(inner) => {
+            b.text("[");
+            type_tokens(b, inner);
+            b.text("]");
+        }
+        Type::Set(inner) => {
+            b.text("Set<");
+            type_tokens(b, inner);
+            b.text(">");
+        }
+        Type::Map { key, value } => {
+            b.text("Map<");
+            type_tokens(b, key);
+            b.text(", ");
+            type_tokens(b, value);
+            b.text(">");
+        }
+        Type::Option(inner) => {
+            b.text("Option<");
+            type_tokens(b, inner);
+            b.text(">");
+        }
+    }
+}
+
+fn template_param_tokens(b: &mut Builder, param: &TemplateParam) {
+    b.text(format!("{}: ", param.name));
+    type_tokens(b, &param.ty);
+}
+
+/// There's no concrete syntax for [`Template`] in `parser.rs` yet (it's
+/// only reachable by constructing the AST directly, e.g. in tests), so
+/// this renders the one literal surface form its fields imply:
+/// `template(params) { automations }`.
+fn template_tokens(b: &mut Builder, template: &Template) {
+    b.text("template");
+    delimited(b, "(", ")", true, false, template.params.len(), |b, i| {
+        template_param_tokens(b, &template.params[i].node)
+    });
+    b.text(" {");
+    b.begin(INDENT_WIDTH, true);
+    b.brk(1, 0);
+    let last = template.automations.len().saturating_sub(1);
+    for (i, automation) in template.automations.iter().enumerate() {
+        automation_tokens(b, &automation.node);
+        if i != last {
+            b.brk(1, 0);
+        }
+    }
+    b.brk(1, -INDENT_WIDTH);
+    b.end();
+    b.text("}");
+}
+
+fn program_tokens(b: &mut Builder, program: &Program) {
+    match program {
+        Program::Automation(a) => automation_tokens(b, a),
+        Program::Template(t) => template_tokens(b, t),
+    }
+}
+
+fn text_width(s: &str) -> usize {
+    s.chars().count()
+}
+
+/// Computes, for every `Begin` index, the flat width of its group; and for
+/// every `Break` index inside a group that turns out not to fit flat, the
+/// width of the segment that follows it up to the next `Break`/`End` at
+/// that same group's depth (used to decide individual wraps in a "fill"
+/// group).
+fn scan(tokens: &[Token]) -> (Vec<usize>, Vec<usize>) {
+    struct Frame {
+        begin_idx: usize,
+        total: usize,
+        since_last_break: usize,
+        last_break_idx: Option<usize>,
+    }
+
+    let mut group_width = vec![0usize; tokens.len()];
+    let mut seg_width = vec![0usize; tokens.len()];
+    let mut stack: Vec<Frame> = Vec::new();
+
+    for (i, tok) in tokens.iter().enumerate() {
+        match tok {
+            Token::Text(s) => {
+                let w = text_width(s);
+                if let Some(frame) = stack.last_mut() {
+                    frame.total += w;
+                    frame.since_last_break += w;
+                }
+            }
+            Token::Break { blank, .. } => {
+                if let Some(frame) = stack.last_mut() {
+                    if let Some(prev) = frame.last_break_idx {
+                        seg_width[prev] = frame.since_last_break;
+                    }
+                    frame.total += blank;
+                    frame.since_last_break = 0;
+                    frame.last_break_idx = Some(i);
+                }
+            }
+            Token::Begin { .. } => stack.push(Frame {
+                begin_idx: i,
+                total: 0,
+                since_last_break: 0,
+                last_break_idx: None,
+            }),
+            Token::End => {
+                let frame = stack.pop().expect("unmatched End in token stream");
+                if let Some(prev) = frame.last_break_idx {
+                    seg_width[prev] = frame.since_last_break;
+                }
+                group_width[frame.begin_idx] = frame.total;
+                if let Some(parent) = stack.last_mut() {
+                    parent.total += frame.total;
+                    parent.since_last_break += frame.total;
+                }
+            }
+        }
+    }
+
+    (group_width, seg_width)
+}
+
+/// Prints `tokens`, deciding inline-vs-multiline per `Begin...End` group
+/// from `group_width`/`seg_width` (see [`scan`]) and a budget of
+/// `max_width` columns per line.
+fn print_tokens(
+    tokens: &[Token],
+    group_width: &[usize],
+    seg_width: &[usize],
+    max_width: usize,
+) -> String {
+    struct Frame {
+        indent_before: isize,
+        content_indent: isize,
+        broken: bool,
+        consistent: bool,
+    }
+
+    let mut out = String::new();
+    let mut column: usize = 0;
+    let mut indent: isize = 0;
+    let mut stack: Vec<Frame> = Vec::new();
+
+    let newline = |out: &mut String, column: &mut usize, indent: isize| {
+        out.push('\n');
+        let indent = indent.max(0) as usize;
+        out.push_str(&" ".repeat(indent));
+        *column = indent;
+    };
+
+    for (i, tok) in tokens.iter().enumerate() {
+        match tok {
+            Token::Text(s) => {
+                out.push_str(s);
+                column += text_width(s);
+            }
+            Token::Begin { offset, consistent } => {
+                let remaining = max_width.saturating_sub(column);
+                let broken = group_width[i] > remaining;
+                let content_indent = indent + offset;
+                stack.push(Frame {
+                    indent_before: indent,
+                    content_indent,
+                    broken,
+                    consistent: *consistent,
+                });
+                if broken {
+                    indent = content_indent;
+                }
+            }
+            Token::End => {
+                let frame = stack.pop().expect("unmatched End in token stream");
+                indent = frame.indent_before;
+            }
+            Token::Break { blank, offset } => match stack.last() {
+                Some(frame) if frame.broken && frame.consistent => {
+                    newline(&mut out, &mut column, frame.content_indent + offset)
+                }
+                Some(frame) if frame.broken => {
+                    // Fill group: only wrap if the segment up to the next
+                    // break/end wouldn't fit on the current line.
+                    if column + blank + seg_width[i] > max_width {
+                        newline(&mut out, &mut column, frame.content_indent + offset);
+                    } else {
+                        out.push_str(&" ".repeat(*blank));
+                        column += blank;
+                    }
+                }
+                _ => {
+                    out.push_str(&" ".repeat(*blank));
+                    column += blank;
+                }
+            },
+        }
+    }
+
+    out
+}
+
+fn render(tokens: &[Token], max_width: usize) -> String {
+    let (group_width, seg_width) = scan(tokens);
+    print_tokens(tokens, &group_width, &seg_width, max_width)
+}
+
+/// Renders an AST node back into clean, canonical DSL source.
+pub trait SourceFormat {
+    #[doc(hidden)]
+    fn to_tokens(&self, b: &mut Builder);
+
+    /// Formats `self` as source, wrapping constructs that don't fit within
+    /// `max_width` columns.
+    fn format_source(&self, max_width: usize) -> String {
+        let mut b = Builder::default();
+        self.to_tokens(&mut b);
+        render(&b.tokens, max_width)
+    }
+
+    /// Like [`format_source`](SourceFormat::format_source), wrapping at
+    /// [`DEFAULT_MAX_WIDTH`] columns.
+    fn to_source_string(&self) -> String {
+        self.format_source(DEFAULT_MAX_WIDTH)
+    }
+}
+
+impl SourceFormat for Expr {
+    fn to_tokens(&self, b: &mut Builder) {
+        expr_tokens(b, self);
+    }
+}
+
+impl SourceFormat for Pattern {
+    fn to_tokens(&self, b: &mut Builder) {
+        pattern_tokens(b, self);
+    }
+}
+
+impl SourceFormat for Automation {
+    fn to_tokens(&self, b: &mut Builder) {
+        automation_tokens(b, self);
+    }
+}
+
+impl SourceFormat for Template {
+    fn to_tokens(&self, b: &mut Builder) {
+        template_tokens(b, self);
+    }
+}
+
+impl SourceFormat for Program {
+    fn to_tokens(&self, b: &mut Builder) {
+        program_tokens(b, self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chumsky::span::SimpleSpan;
+
+    fn span() -> SimpleSpan {
+        SimpleSpan::from(0..0)
+    }
+
+    fn spanned<T>(node: T) -> Spanned<T> {
+        Spanned::new(node, span())
+    }
+
+    #[test]
+    fn to_source_string_uses_default_max_width() {
+        let expr = Expr::Ident("foo".to_string());
+        assert_eq!(
+            expr.to_source_string(),
+            expr.format_source(DEFAULT_MAX_WIDTH)
+        );
+    }
+
+    #[test]
+    fn literals_and_idents_print_flat() {
+        assert_eq!(Expr::Int(42).format_source(80), "42");
+        assert_eq!(Expr::Bool(true).format_source(80), "true");
+        assert_eq!(Expr::Ident("foo".to_string()).format_source(80), "foo");
+        assert_eq!(Expr::String("hi".to_string()).format_source(80), "\"hi\"");
+        assert_eq!(
+            Expr::UnitLiteral {
+                value: "5".to_string(),
+                unit: UnitType::Minutes,
+            }
+            .format_source(80),
+            "5min"
+        );
+    }
+
+    #[test]
+    fn binop_preserves_precedence_with_parens() {
+        // (a + b) * c: the left operand is lower precedence than `*`, so it
+        // needs parens to round-trip correctly.
+        let expr = Expr::BinOp {
+            op: BinOp::Mul,
+            left: Box::new(spanned(Expr::BinOp {
+                op: BinOp::Add,
+                left: Box::new(spanned(Expr::Ident("a".to_string()))),
+                right: Box::new(spanned(Expr::Ident("b".to_string()))),
+            })),
+            right: Box::new(spanned(Expr::Ident("c".to_string()))),
+        };
+        assert_eq!(expr.format_source(80), "(a + b) * c");
+    }
+
+    #[test]
+    fn binop_without_parens_when_precedence_allows() {
+        // a + b * c: `*` binds tighter than `+`, so the right operand
+        // doesn't need parens.
+        let expr = Expr::BinOp {
+            op: BinOp::Add,
+            left: Box::new(spanned(Expr::Ident("a".to_string()))),
+            right: Box::new(spanned(Expr::BinOp {
+                op: BinOp::Mul,
+                left: Box::new(spanned(Expr::Ident("b".to_string()))),
+                right: Box::new(spanned(Expr::Ident("c".to_string()))),
+            })),
+        };
+        assert_eq!(expr.format_source(80), "a + b * c");
+    }
+
+    #[test]
+    fn short_struct_lit_stays_inline() {
+        let expr = Expr::StructLit {
+            name: "Point".to_string(),
+            fields: vec![
+                spanned(StructField::Field {
+                    name: "x".to_string(),
+                    value: spanned(Expr::Int(1)),
+                }),
+                spanned(StructField::Field {
+                    name: "y".to_string(),
+                    value: spanned(Expr::Int(2)),
+                }),
+            ],
+        };
+        assert_eq!(expr.format_source(80), "Point { x: 1, y: 2 }");
+    }
+
+    #[test]
+    fn long_struct_lit_wraps_one_field_per_line() {
+        let expr = Expr::StructLit {
+            name: "Config".to_string(),
+            fields: vec![
+                spanned(StructField::Field {
+                    name: "first_long_field_name".to_string(),
+                    value: spanned(Expr::Int(111_111)),
+                }),
+                spanned(StructField::Field {
+                    name: "second_long_field_name".to_string(),
+                    value: spanned(Expr::Int(222_222)),
+                }),
+            ],
+        };
+        assert_eq!(
+            expr.format_source(40),
+            "Config {\n    first_long_field_name: 111111,\n    second_long_field_name: 222222\n}"
+        );
+    }
+
+    #[test]
+    fn call_with_named_and_positional_args() {
+        let expr = Expr::Call {
+            func: Box::new(spanned(Expr::Ident("wait".to_string()))),
+            args: vec![
+                spanned(Arg::Positional(spanned(Expr::UnitLiteral {
+                    value: "5".to_string(),
+                    unit: UnitType::Minutes,
+                }))),
+                spanned(Arg::Named {
+                    name: "retry".to_string(),
+                    value: spanned(Expr::Ident("cancel".to_string())),
+                }),
+            ],
+        };
+        assert_eq!(expr.format_source(80), "wait(5min, retry = cancel)");
+    }
+
+    #[test]
+    fn fill_list_packs_multiple_items_per_line() {
+        let items: Vec<_> = (1..=8).map(|n| spanned(Expr::Int(n))).collect();
+        let expr = Expr::List(items);
+        // Narrow enough that not everything fits on one line, but wide
+        // enough that each line should hold more than one element.
+        let out = expr.format_source(16);
+        assert!(out.lines().count() > 1, "expected wrapping, got: {out}");
+        assert!(
+            out.lines().any(|l| l.split(", ").count() > 1),
+            "expected more than one element per line, got: {out}"
+        );
+    }
+
+    #[test]
+    fn automation_round_trips_simple_source() {
+        let automation = Automation {
+            kind: AutomationKind::Observer,
+            kind_span: span(),
+            pattern: spanned(Pattern::Struct {
+                fields: vec![],
+                has_rest: false,
+            }),
+            filter: spanned(Expr::Bool(true)),
+            body: vec![spanned(Stmt::Expr(spanned(Expr::Ident("x".to_string()))))],
+        };
+        assert_eq!(automation.format_source(80), "observer {} /true/ { x }");
+    }
+
+    #[test]
+    fn let_mut_and_compound_assign_round_trip() {
+        let automation = Automation {
+            kind: AutomationKind::Observer,
+            kind_span: span(),
+            pattern: spanned(Pattern::Struct {
+                fields: vec![],
+                has_rest: false,
+            }),
+            filter: spanned(Expr::Bool(true)),
+            body: vec![
+                spanned(Stmt::LetMut {
+                    name: "count".to_string(),
+                    value: spanned(Expr::Int(0)),
+                }),
+                spanned(Stmt::CompoundAssign {
+                    name: "count".to_string(),
+                    op: BinOp::Add,
+                    value: spanned(Expr::Int(1)),
+                }),
+            ],
+        };
+        assert_eq!(
+            automation.format_source(80),
+            "observer {} /true/ { let mut count = 0; count += 1; }"
+        );
+    }
+
+    #[test]
+    fn if_else_wraps_each_branch_when_too_wide() {
+        let expr = Expr::If {
+            cond: Box::new(spanned(Expr::Ident("some_long_condition_name".to_string()))),
+            then_block: vec![spanned(Stmt::Expr(spanned(Expr::Ident(
+                "do_something_long".to_string(),
+            ))))],
+            else_block: vec![spanned(Stmt::Expr(spanned(Expr::Ident(
+                "do_something_else_long".to_string(),
+            ))))],
+        };
+        let out = expr.format_source(20);
+        assert!(out.contains("if some_long_condition_name {\n"));
+        assert!(out.contains("do_something_long"));
+        assert!(out.contains("} else {\n"));
+    }
+}
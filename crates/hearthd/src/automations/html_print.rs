@@ -0,0 +1,574 @@
+//! HTML rendering of pretty-printed programs with hyperlinked identifiers.
+//!
+//! This is the HTML counterpart to [`PrettyPrint::to_pretty_string`]: it
+//! walks the same node structure, but instead of a plain indented tree it
+//! emits `<span class="...">` wrappers per token kind (keywords, idents,
+//! literals, operators) and `<a href="#def-...">` links from each `Ident`
+//! use back to whichever `Let`, `TemplateParam`, or pattern binding
+//! introduced it. The result is a self-contained, navigable snippet
+//! suitable for documenting or debugging a complex template in a browser.
+//!
+//! Binding resolution reuses the same scoping an automation or template
+//! already implies: a `Let` introduces a name for the rest of its block, a
+//! `TemplateParam` for the whole template, and a pattern binding (a struct
+//! field pattern, or a `match` arm's bindings) for the block it guards.
+//! [`Scope`] tracks this as a stack of name -> anchor-id maps, pushed and
+//! popped as the corresponding tree shape is entered and left.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::ast::*;
+use super::pretty_print::PrettyPrint;
+
+/// Tracks in-scope bindings (name -> anchor id) while walking the tree, so
+/// an `Ident` use can link back to wherever it was introduced.
+struct Scope {
+    frames: Vec<HashMap<String, String>>,
+    next_id: usize,
+}
+
+impl Scope {
+    fn new() -> Self {
+        Self {
+            frames: vec![HashMap::new()],
+            next_id: 0,
+        }
+    }
+
+    fn push(&mut self) {
+        self.frames.push(HashMap::new());
+    }
+
+    fn pop(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Introduces `name` as a binding in the current scope, returning the
+    /// anchor id its definition should be tagged with.
+    fn define(&mut self, name: &str) -> String {
+        let id = format!("def-{}-{}", sanitize_id(name), self.next_id);
+        self.next_id += 1;
+        self.frames
+            .last_mut()
+            .expect("Scope always has at least one frame")
+            .insert(name.to_string(), id.clone());
+        id
+    }
+
+    /// Looks up the anchor id `name` currently resolves to, innermost scope
+    /// first.
+    fn resolve(&self, name: &str) -> Option<&str> {
+        self.frames
+            .iter()
+            .rev()
+            .find_map(|frame| frame.get(name))
+            .map(String::as_str)
+    }
+}
+
+fn sanitize_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_indent(indent: usize, out: &mut String) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn span(out: &mut String, class: &str, text: &str) {
+    let _ = write!(
+        out,
+        "<span class=\"{}\">{}</span>",
+        class,
+        escape_html(text)
+    );
+}
+
+/// Emits an `Ident` use: a link to its definition if `name` resolves in the
+/// current scope, otherwise a plain, unresolved ident span.
+fn ident_use(out: &mut String, scope: &Scope, name: &str) {
+    match scope.resolve(name) {
+        Some(id) => {
+            let _ = write!(
+                out,
+                "<a class=\"ident\" href=\"#{}\">{}</a>",
+                id,
+                escape_html(name)
+            );
+        }
+        None => span(out, "ident", name),
+    }
+}
+
+/// Emits an `Ident` definition: the anchor other uses link to.
+fn ident_def(out: &mut String, scope: &mut Scope, name: &str) {
+    let id = scope.define(name);
+    let _ = write!(
+        out,
+        "<span class=\"ident def\" id=\"{}\">{}</span>",
+        id,
+        escape_html(name)
+    );
+}
+
+/// Renders an AST node as an HTML snippet, the counterpart to
+/// [`PrettyPrint`] for browser display.
+pub trait HtmlPrint: PrettyPrint {
+    #[doc(hidden)]
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String);
+
+    /// Renders `self` as a self-contained HTML snippet.
+    fn to_html_string(&self) -> String {
+        let mut out = String::new();
+        let mut scope = Scope::new();
+        self.html_print(0, &mut scope, &mut out);
+        out
+    }
+}
+
+impl<T: HtmlPrint> HtmlPrint for Spanned<T> {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        self.node.html_print(indent, scope, out);
+    }
+}
+
+impl HtmlPrint for Expr {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            Expr::Int(n) => span(out, "lit", &n.to_string()),
+            Expr::Float(n) => span(out, "lit", n),
+            Expr::String(s) => span(out, "lit", &format!("{:?}", s)),
+            Expr::Bool(b) => span(out, "lit", &b.to_string()),
+            Expr::UnitLiteral { value, unit } => span(out, "lit", &format!("{}{}", value, unit)),
+            Expr::Ident(name) => ident_use(out, scope, name),
+            Expr::BinOp { op, left, right } => {
+                out.push_str("<div class=\"binop\">");
+                left.html_print(0, scope, out);
+                span(out, "op", &format!(" {} ", op));
+                right.html_print(0, scope, out);
+                out.push_str("</div>");
+            }
+            Expr::UnaryOp { op, expr } => {
+                span(out, "op", &op.to_string());
+                if matches!(op, UnaryOp::Await) {
+                    out.push(' ');
+                }
+                expr.html_print(0, scope, out);
+            }
+            Expr::Field { expr, field } => {
+                expr.html_print(0, scope, out);
+                span(out, "punct", ".");
+                span(out, "field", field);
+            }
+            Expr::OptionalField { expr, field } => {
+                expr.html_print(0, scope, out);
+                span(out, "punct", "?.");
+                span(out, "field", field);
+            }
+            Expr::Call { func, args } => {
+                func.html_print(0, scope, out);
+                span(out, "punct", "(");
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    arg.html_print(0, scope, out);
+                }
+                span(out, "punct", ")");
+            }
+            Expr::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                out.push_str("<div class=\"if\">");
+                span(out, "kw", "if ");
+                cond.html_print(0, scope, out);
+                html_block(then_block, indent, scope, out);
+                span(out, "kw", " else ");
+                html_block(else_block, indent, scope, out);
+                out.push_str("</div>");
+            }
+            Expr::List(items) => {
+                span(out, "punct", "[");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    item.html_print(0, scope, out);
+                }
+                span(out, "punct", "]");
+            }
+            Expr::ListComp { expr, clauses } => {
+                span(out, "punct", "[");
+                expr.html_print(0, scope, out);
+                scope.push();
+                comp_clauses_html(out, scope, clauses);
+                scope.pop();
+                span(out, "punct", "]");
+            }
+            Expr::DictComp {
+                key,
+                value,
+                clauses,
+            } => {
+                span(out, "punct", "{");
+                key.html_print(0, scope, out);
+                span(out, "punct", ": ");
+                value.html_print(0, scope, out);
+                scope.push();
+                comp_clauses_html(out, scope, clauses);
+                scope.pop();
+                span(out, "punct", "}");
+            }
+            Expr::SetComp { expr, clauses } => {
+                span(out, "punct", "{");
+                expr.html_print(0, scope, out);
+                scope.push();
+                comp_clauses_html(out, scope, clauses);
+                scope.pop();
+                span(out, "punct", "}");
+            }
+            Expr::StructLit { name, fields } => {
+                span(out, "type", name);
+                out.push(' ');
+                span(out, "punct", "{ ");
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    field.html_print(0, scope, out);
+                }
+                span(out, "punct", " }");
+            }
+            Expr::Match { scrutinee, arms } => {
+                out.push_str("<div class=\"match\">");
+                span(out, "kw", "match ");
+                scrutinee.html_print(0, scope, out);
+                out.push_str(" {");
+                for arm in arms {
+                    out.push_str("<div class=\"arm\">");
+                    write_indent(indent + 1, out);
+                    arm.html_print(indent + 1, scope, out);
+                    out.push_str("</div>");
+                }
+                out.push('}');
+                out.push_str("</div>");
+            }
+            Expr::Lambda { params, body } => {
+                span(out, "punct", "|");
+                scope.push();
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    ident_def(out, scope, param);
+                }
+                span(out, "punct", "| ");
+                body.html_print(0, scope, out);
+                scope.pop();
+            }
+            Expr::Tuple(items) => {
+                span(out, "punct", "(");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    item.html_print(0, scope, out);
+                }
+                span(out, "punct", ")");
+            }
+        }
+    }
+}
+
+/// Renders `stmts` as a `{ ... }` block, each statement on its own line.
+fn html_block(stmts: &[Spanned<Stmt>], indent: usize, scope: &mut Scope, out: &mut String) {
+    span(out, "punct", "{");
+    if !stmts.is_empty() {
+        out.push_str("<div class=\"block\">");
+        for stmt in stmts {
+            out.push_str("<div class=\"stmt\">");
+            stmt.html_print(indent + 1, scope, out);
+            out.push_str("</div>");
+        }
+        out.push_str("</div>");
+    }
+    span(out, "punct", "}");
+}
+
+impl HtmlPrint for Stmt {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            Stmt::Let { name, value } => {
+                span(out, "kw", "let ");
+                ident_def(out, scope, name);
+                span(out, "punct", " = ");
+                value.html_print(0, scope, out);
+            }
+            Stmt::Expr(expr) => expr.html_print(0, scope, out),
+        }
+    }
+}
+
+impl HtmlPrint for Arg {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            Arg::Positional(expr) => expr.html_print(0, scope, out),
+            Arg::Named { name, value } => {
+                span(out, "field", name);
+                span(out, "punct", " = ");
+                value.html_print(0, scope, out);
+            }
+        }
+    }
+}
+
+impl HtmlPrint for StructField {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            StructField::Field { name, value } => {
+                span(out, "field", name);
+                span(out, "punct", ": ");
+                value.html_print(0, scope, out);
+            }
+            StructField::Inherit(name) => {
+                span(out, "kw", "inherit ");
+                ident_use(out, scope, name);
+            }
+            StructField::Spread(name) => {
+                span(out, "punct", "...");
+                ident_use(out, scope, name);
+            }
+        }
+    }
+}
+
+impl HtmlPrint for Pattern {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            Pattern::Ident(name) => ident_def(out, scope, name),
+            Pattern::Struct { fields, has_rest } => {
+                span(out, "punct", "{ ");
+                for (i, field) in fields.iter().enumerate() {
+                    if i > 0 {
+                        span(out, "punct", ", ");
+                    }
+                    field.html_print(0, scope, out);
+                }
+                if *has_rest {
+                    if !fields.is_empty() {
+                        span(out, "punct", ", ");
+                    }
+                    span(out, "punct", "...");
+                }
+                span(out, "punct", " }");
+            }
+        }
+    }
+}
+
+/// Emits a comprehension's `for`/`if` clauses, shared by `ListComp`,
+/// `DictComp`, and `SetComp`. Assumes the caller already pushed the scope
+/// frame the clauses' bindings live in.
+fn comp_clauses_html(out: &mut String, scope: &mut Scope, clauses: &[CompClause]) {
+    for clause in clauses {
+        match clause {
+            CompClause::For { var, iter } => {
+                span(out, "kw", " for ");
+                bind_pattern_html(out, scope, &var.node);
+                span(out, "kw", " in ");
+                iter.html_print(0, scope, out);
+            }
+            CompClause::If(cond) => {
+                span(out, "kw", " if ");
+                cond.html_print(0, scope, out);
+            }
+        }
+    }
+}
+
+/// Emits a `for`/comprehension binding pattern inline, registering every
+/// name it binds via [`ident_def`] - e.g. `x` or `(a, b)`.
+fn bind_pattern_html(out: &mut String, scope: &mut Scope, pattern: &BindPattern) {
+    match pattern {
+        BindPattern::Ident(name) => ident_def(out, scope, name),
+        BindPattern::Tuple(elems) => {
+            span(out, "punct", "(");
+            for (i, elem) in elems.iter().enumerate() {
+                if i > 0 {
+                    span(out, "punct", ", ");
+                }
+                bind_pattern_html(out, scope, &elem.node);
+            }
+            span(out, "punct", ")");
+        }
+    }
+}
+
+impl HtmlPrint for FieldPattern {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match &self.pattern {
+            Some(nested) => {
+                span(out, "field", &self.name);
+                span(out, "punct", ": ");
+                nested.html_print(0, scope, out);
+            }
+            None => ident_def(out, scope, &self.name),
+        }
+    }
+}
+
+impl HtmlPrint for MatchPattern {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            MatchPattern::Variant {
+                enum_name,
+                variant,
+                bindings,
+            } => {
+                span(out, "type", enum_name);
+                span(out, "punct", "::");
+                span(out, "type", variant);
+                if !bindings.is_empty() {
+                    span(out, "punct", "(");
+                    for (i, binding) in bindings.iter().enumerate() {
+                        if i > 0 {
+                            span(out, "punct", ", ");
+                        }
+                        binding.html_print(0, scope, out);
+                    }
+                    span(out, "punct", ")");
+                }
+            }
+            MatchPattern::Wildcard => span(out, "punct", "_"),
+        }
+    }
+}
+
+impl HtmlPrint for BindingPattern {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        match self {
+            BindingPattern::Ident(name) => ident_def(out, scope, name),
+            BindingPattern::Wildcard => span(out, "punct", "_"),
+        }
+    }
+}
+
+impl HtmlPrint for MatchArm {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        scope.push();
+        self.pattern.html_print(indent, scope, out);
+        span(out, "punct", " => ");
+        html_block(&self.body, indent, scope, out);
+        scope.pop();
+    }
+}
+
+impl HtmlPrint for Automation {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"automation\">");
+        span(out, "kw", &format!("{} ", self.kind));
+        scope.push();
+        self.pattern.html_print(0, scope, out);
+        span(out, "punct", " /");
+        self.filter.html_print(0, scope, out);
+        span(out, "punct", "/ ");
+        html_block(&self.body, indent, scope, out);
+        scope.pop();
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrint for Program {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        match self {
+            Program::Automation(auto) => auto.html_print(indent, scope, out),
+            Program::Template(tmpl) => tmpl.html_print(indent, scope, out),
+        }
+    }
+}
+
+impl HtmlPrint for Template {
+    fn html_print(&self, indent: usize, scope: &mut Scope, out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"template\">");
+        span(out, "kw", "template");
+        span(out, "punct", "(");
+        scope.push();
+        for (i, param) in self.params.iter().enumerate() {
+            if i > 0 {
+                span(out, "punct", ", ");
+            }
+            param.html_print(0, scope, out);
+        }
+        span(out, "punct", ") {");
+        for auto in &self.automations {
+            out.push_str("<div class=\"stmt\">");
+            auto.html_print(indent + 1, scope, out);
+            out.push_str("</div>");
+        }
+        scope.pop();
+        out.push('}');
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrint for TemplateParam {
+    fn html_print(&self, _indent: usize, scope: &mut Scope, out: &mut String) {
+        ident_def(out, scope, &self.name);
+        span(out, "punct", ": ");
+        html_type(&self.ty, out);
+    }
+}
+
+fn html_type(ty: &Type, out: &mut String) {
+    match ty {
+        Type::Named(s) => span(out, "type", s),
+        Type::List(t) => {
+            span(out, "punct", "[");
+            html_type(t, out);
+            span(out, "punct", "]");
+        }
+        Type::Set(t) => {
+            span(out, "type", "Set");
+            span(out, "punct", "<");
+            html_type(t, out);
+            span(out, "punct", ">");
+        }
+        Type::Map { key, value } => {
+            span(out, "type", "Map");
+            span(out, "punct", "<");
+            html_type(key, out);
+            span(out, "punct", ", ");
+            html_type(value, out);
+            span(out, "punct", ">");
+        }
+        Type::Option(t) => {
+            span(out, "type", "Option");
+            span(out, "punct", "<");
+            html_type(t, out);
+            span(out, "punct", ">");
+        }
+    }
+}
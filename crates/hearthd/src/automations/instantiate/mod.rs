@@ -0,0 +1,277 @@
+//! Template instantiation (monomorphization) pass over the lowered AST.
+//!
+//! Given a `LoweredProgram::Template` and the argument expressions supplied
+//! for each of its `TemplateParam`s, [`instantiate`] clones the template's
+//! automations with every reference to a parameter's name replaced by that
+//! argument's expression - turning one parameterized template into concrete,
+//! standalone automations ready for [`super::simplify`]/`interpreter` the
+//! same way a hand-written automation is.
+//!
+//! This needs a scope that shadows correctly through nested `Let`/`LetMut`/
+//! `For` bindings (a local binding that happens to reuse a parameter's name
+//! must stop that parameter from substituting from that point on), which
+//! doesn't fit [`super::repr::lowered_visit`]'s single stateless callback -
+//! so, like [`super::simplify`], this threads an explicit environment
+//! through its own copy of the structural walk instead of reusing `fold`.
+//!
+//! `Pattern` (an automation's destructuring match pattern) has no
+//! expression sites to substitute into - it's purely identifiers and nested
+//! field patterns - so it's carried through unchanged, the same as
+//! [`super::desugar`] and [`super::simplify`] already do.
+//!
+//! Substituted nodes are given a fresh [`Origin::Desugared`] wrapping the
+//! use site's own origin, rather than keeping the argument expression's
+//! origin, so a later error in an instantiated automation still points at
+//! the parameter reference the template author wrote, not at wherever the
+//! caller's argument expression came from.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::repr::lowered::LoweredArg;
+use super::repr::lowered::LoweredAutomation;
+use super::repr::lowered::LoweredExpr;
+use super::repr::lowered::LoweredMatchArm;
+use super::repr::lowered::LoweredProgram;
+use super::repr::lowered::LoweredStmt;
+use super::repr::lowered::LoweredStructField;
+use super::repr::lowered::Origin;
+use super::repr::lowered::Spanned;
+
+#[cfg(test)]
+mod tests;
+
+/// Argument expressions substituted so far in the current scope, keyed by
+/// parameter name.
+type Env = HashMap<String, Spanned<LoweredExpr>>;
+
+/// Instantiate `template`'s automations with `args` substituted for their
+/// matching `TemplateParam` names, returning one concrete automation per
+/// automation the template defines. `template` must be a
+/// `LoweredProgram::Template` - a plain `LoweredProgram::Automation` has no
+/// parameters to substitute, so this returns an empty `Vec` for it.
+pub fn instantiate(
+    template: &LoweredProgram,
+    args: &[(String, Spanned<LoweredExpr>)],
+) -> Vec<LoweredAutomation> {
+    let LoweredProgram::Template { automations, .. } = template else {
+        return Vec::new();
+    };
+
+    let env: Env = args.iter().cloned().collect();
+
+    automations
+        .iter()
+        .map(|automation| instantiate_automation(automation, &env))
+        .collect()
+}
+
+fn instantiate_automation(automation: &LoweredAutomation, env: &Env) -> LoweredAutomation {
+    LoweredAutomation {
+        kind: automation.kind,
+        kind_span: automation.kind_span,
+        pattern: automation.pattern.clone(),
+        filter: automation
+            .filter
+            .as_ref()
+            .map(|filter| instantiate_expr(filter, env)),
+        body: instantiate_stmts(&automation.body, env).0,
+        file: automation.file,
+    }
+}
+
+/// Substitute a statement list, returning the transformed statements and
+/// the environment with any shadowing `let`s removed - callers with a
+/// trailing result expression in the same scope (`Block`) need that
+/// environment to substitute it; callers that don't (`If`, `For`, match
+/// arms) just take the statements and let the environment go out of scope.
+fn instantiate_stmts(
+    stmts: &[Spanned<LoweredStmt>],
+    env: &Env,
+) -> (Vec<Spanned<LoweredStmt>>, Env) {
+    let mut env = env.clone();
+    let mut out = Vec::with_capacity(stmts.len());
+
+    for stmt in stmts {
+        let origin = stmt.origin.clone();
+        let node = match &stmt.node {
+            LoweredStmt::Let { name, value } => {
+                let value = instantiate_expr(value, &env);
+                env.remove(name);
+                LoweredStmt::Let {
+                    name: name.clone(),
+                    value,
+                }
+            }
+            LoweredStmt::LetMut { name, value } => {
+                let value = instantiate_expr(value, &env);
+                env.remove(name);
+                LoweredStmt::LetMut {
+                    name: name.clone(),
+                    value,
+                }
+            }
+            LoweredStmt::Expr(value) => LoweredStmt::Expr(instantiate_expr(value, &env)),
+            LoweredStmt::Return(value) => LoweredStmt::Return(instantiate_expr(value, &env)),
+            LoweredStmt::For { var, iter, body } => {
+                let iter = instantiate_expr(iter, &env);
+                let mut body_env = env.clone();
+                body_env.remove(var);
+                let (body, _) = instantiate_stmts(body, &body_env);
+                LoweredStmt::For {
+                    var: var.clone(),
+                    iter,
+                    body,
+                }
+            }
+            LoweredStmt::While { cond, body } => {
+                let cond = instantiate_expr(cond, &env);
+                let body_env = env.clone();
+                let (body, _) = instantiate_stmts(body, &body_env);
+                LoweredStmt::While { cond, body }
+            }
+            LoweredStmt::Push { list, value } => LoweredStmt::Push {
+                list: list.clone(),
+                value: instantiate_expr(value, &env),
+            },
+            LoweredStmt::CompoundAssign { name, op, value } => LoweredStmt::CompoundAssign {
+                name: name.clone(),
+                op: *op,
+                value: instantiate_expr(value, &env),
+            },
+            LoweredStmt::Insert { map, key, value } => LoweredStmt::Insert {
+                map: map.clone(),
+                key: instantiate_expr(key, &env),
+                value: instantiate_expr(value, &env),
+            },
+            LoweredStmt::Add { set, value } => LoweredStmt::Add {
+                set: set.clone(),
+                value: instantiate_expr(value, &env),
+            },
+        };
+        out.push(Spanned::new(node, origin));
+    }
+
+    (out, env)
+}
+
+fn instantiate_expr(expr: &Spanned<LoweredExpr>, env: &Env) -> Spanned<LoweredExpr> {
+    if let LoweredExpr::Ident(name) = &expr.node {
+        if let Some(value) = env.get(name) {
+            let origin = Origin::Desugared(Rc::new(expr.origin.ast_node().clone()));
+            return Spanned::new(value.node.clone(), origin);
+        }
+    }
+
+    let origin = expr.origin.clone();
+    let node = match &expr.node {
+        leaf @ (LoweredExpr::Int(_)
+        | LoweredExpr::Float(_)
+        | LoweredExpr::String(_)
+        | LoweredExpr::Bool(_)
+        | LoweredExpr::UnitLiteral { .. }
+        | LoweredExpr::Ident(_)
+        | LoweredExpr::Path(_)
+        | LoweredExpr::MutableList
+        | LoweredExpr::MutableMap
+        | LoweredExpr::MutableSet) => leaf.clone(),
+        LoweredExpr::BinOp { op, left, right } => LoweredExpr::BinOp {
+            op: *op,
+            left: Box::new(instantiate_expr(left, env)),
+            right: Box::new(instantiate_expr(right, env)),
+        },
+        LoweredExpr::UnaryOp { op, expr } => LoweredExpr::UnaryOp {
+            op: *op,
+            expr: Box::new(instantiate_expr(expr, env)),
+        },
+        LoweredExpr::Field { expr, field } => LoweredExpr::Field {
+            expr: Box::new(instantiate_expr(expr, env)),
+            field: field.clone(),
+        },
+        LoweredExpr::OptionalField { expr, field } => LoweredExpr::OptionalField {
+            expr: Box::new(instantiate_expr(expr, env)),
+            field: field.clone(),
+        },
+        LoweredExpr::Call { func, args } => LoweredExpr::Call {
+            func: Box::new(instantiate_expr(func, env)),
+            args: args.iter().map(|arg| instantiate_arg(arg, env)).collect(),
+        },
+        LoweredExpr::If {
+            cond,
+            then_block,
+            else_block,
+        } => LoweredExpr::If {
+            cond: Box::new(instantiate_expr(cond, env)),
+            then_block: instantiate_stmts(then_block, env).0,
+            else_block: else_block
+                .as_ref()
+                .map(|block| instantiate_stmts(block, env).0),
+        },
+        LoweredExpr::List(items) => {
+            LoweredExpr::List(items.iter().map(|item| instantiate_expr(item, env)).collect())
+        }
+        LoweredExpr::StructLit { name, fields } => LoweredExpr::StructLit {
+            name: name.clone(),
+            fields: fields
+                .iter()
+                .map(|field| instantiate_field(field, env))
+                .collect(),
+        },
+        LoweredExpr::Block { stmts, result } => {
+            let (stmts, block_env) = instantiate_stmts(stmts, env);
+            let result = Box::new(instantiate_expr(result, &block_env));
+            LoweredExpr::Block { stmts, result }
+        }
+        LoweredExpr::Match { scrutinee, arms } => LoweredExpr::Match {
+            scrutinee: Box::new(instantiate_expr(scrutinee, env)),
+            arms: arms
+                .iter()
+                .map(|arm| LoweredMatchArm {
+                    pattern: arm.pattern.clone(),
+                    body: instantiate_stmts(&arm.body, env).0,
+                })
+                .collect(),
+        },
+        LoweredExpr::Lambda { params, body } => {
+            let mut body_env = env.clone();
+            for param in params {
+                body_env.remove(param);
+            }
+            LoweredExpr::Lambda {
+                params: params.clone(),
+                body: Box::new(instantiate_expr(body, &body_env)),
+            }
+        }
+        LoweredExpr::Tuple(items) => {
+            LoweredExpr::Tuple(items.iter().map(|item| instantiate_expr(item, env)).collect())
+        }
+    };
+    Spanned::new(node, origin)
+}
+
+fn instantiate_arg(arg: &Spanned<LoweredArg>, env: &Env) -> Spanned<LoweredArg> {
+    let origin = arg.origin.clone();
+    let node = match &arg.node {
+        LoweredArg::Positional(value) => LoweredArg::Positional(instantiate_expr(value, env)),
+        LoweredArg::Named { name, value } => LoweredArg::Named {
+            name: name.clone(),
+            value: instantiate_expr(value, env),
+        },
+    };
+    Spanned::new(node, origin)
+}
+
+fn instantiate_field(
+    field: &Spanned<LoweredStructField>,
+    env: &Env,
+) -> Spanned<LoweredStructField> {
+    let origin = field.origin.clone();
+    let node = match &field.node {
+        LoweredStructField::Field { name, value } => LoweredStructField::Field {
+            name: name.clone(),
+            value: instantiate_expr(value, env),
+        },
+        other @ (LoweredStructField::Inherit(_) | LoweredStructField::Spread(_)) => other.clone(),
+    };
+    Spanned::new(node, origin)
+}
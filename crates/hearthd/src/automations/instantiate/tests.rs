@@ -0,0 +1,115 @@
+use chumsky::prelude::*;
+
+use super::instantiate;
+use crate::automations::ast;
+use crate::automations::desugar::Desugarer;
+use crate::automations::parser::expr_parser;
+use crate::automations::parser::parse;
+use crate::automations::repr::lowered::LoweredExpr;
+use crate::automations::repr::lowered::LoweredStmt;
+use crate::automations::repr::lowered::Spanned;
+
+fn parse_expr(input: &str) -> ast::Spanned<ast::Expr> {
+    let tokens = crate::automations::lexer::lexer()
+        .parse(input)
+        .into_result()
+        .expect("lexing should succeed");
+    let input_len = input.len();
+    expr_parser()
+        .parse(
+            tokens
+                .as_slice()
+                .map((input_len..input_len).into(), |(t, s)| (t, s)),
+        )
+        .into_result()
+        .expect("parsing should succeed")
+}
+
+/// Parse and desugar `src` (expected to be a `template ... { ... }`) and
+/// instantiate it with `args` (each an (param name, expression source) pair).
+fn instantiate_str(
+    src: &str,
+    args: &[(&str, &str)],
+) -> Vec<crate::automations::repr::lowered::LoweredAutomation> {
+    let program = parse(src).expect("parsing should succeed");
+    let lowered = Desugarer::new().desugar_program(program);
+
+    let args: Vec<(String, Spanned<LoweredExpr>)> = args
+        .iter()
+        .map(|(name, expr_src)| {
+            let expr = Desugarer::new().desugar_expr(parse_expr(expr_src));
+            (name.to_string(), expr)
+        })
+        .collect();
+
+    instantiate(&lowered, &args)
+}
+
+#[test]
+fn substitutes_param_into_filter() {
+    let automations = instantiate_str(
+        "template Foo(x: Int) { observer {} /x > 0/ { x; } }",
+        &[("x", "99")],
+    );
+
+    let filter = automations[0].filter.as_ref().expect("filter should be present");
+    match &filter.node {
+        LoweredExpr::BinOp { left, .. } => {
+            assert!(matches!(left.node, LoweredExpr::Int(99)));
+            // The substituted node is synthetic - it no longer literally
+            // matches the `x` source text at its origin's span.
+            assert!(left.origin.is_synthetic());
+        }
+        other => panic!("expected BinOp, got {other:?}"),
+    }
+}
+
+#[test]
+fn local_let_shadows_the_param_from_that_point_on() {
+    let automations = instantiate_str(
+        "template Foo(x: Int) { observer {} /true/ { let x = 1; x + 2; } }",
+        &[("x", "99")],
+    );
+
+    let body = &automations[0].body;
+    assert_eq!(body.len(), 2);
+
+    match &body[1].node {
+        LoweredStmt::Expr(value) => match &value.node {
+            LoweredExpr::BinOp { left, .. } => {
+                // Shadowed by the local `let x = 1;` above - left should
+                // still be the local `x`, not the substituted argument.
+                assert!(matches!(left.node, LoweredExpr::Ident(ref name) if name == "x"));
+            }
+            other => panic!("expected BinOp, got {other:?}"),
+        },
+        other => panic!("expected Expr statement, got {other:?}"),
+    }
+}
+
+#[test]
+fn substitutes_param_in_every_automation_of_a_multi_automation_template() {
+    let automations = instantiate_str(
+        "template Foo(x: Int) {
+            observer {} /x > 0/ { x; }
+            mutator {} /true/ { x; }
+        }",
+        &[("x", "7")],
+    );
+
+    assert_eq!(automations.len(), 2);
+    for automation in &automations {
+        match &automation.body[0].node {
+            LoweredStmt::Expr(value) => assert!(matches!(value.node, LoweredExpr::Int(7))),
+            other => panic!("expected Expr statement, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn a_plain_automation_has_nothing_to_instantiate() {
+    let program = parse("observer {} /true/ { 1; }").expect("parsing should succeed");
+    let lowered = Desugarer::new().desugar_program(program);
+
+    assert!(instantiate(&lowered, &[]).is_empty());
+}
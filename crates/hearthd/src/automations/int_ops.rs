@@ -0,0 +1,18 @@
+//! Checked-but-non-panicking integer division and modulo, shared by the
+//! HIR constant folder, the expression evaluator, the interpreter, and
+//! the simplifier.
+//!
+//! Plain `/` and `%` panic on `i64::MIN / -1` (and the equivalent `%`),
+//! not just on division by zero. Each caller already rejects a
+//! statically-known-zero divisor separately before reaching these, so
+//! these only need to guard the overflow case; `wrapping_div`/
+//! `wrapping_rem` wrap back around to `i64::MIN`/`0` instead of
+//! panicking, matching how the other arithmetic ops are folded.
+
+pub(crate) fn checked_int_div(a: i64, b: i64) -> i64 {
+    a.wrapping_div(b)
+}
+
+pub(crate) fn checked_int_mod(a: i64, b: i64) -> i64 {
+    a.wrapping_rem(b)
+}
@@ -0,0 +1,913 @@
+//! Tree-walking interpreter for the lowered AST.
+//!
+//! Complements [`super::eval`], which interprets type-checked HIR: this
+//! walks `LoweredProgram`/`LoweredExpr`/`LoweredStmt` directly, so a caller
+//! - e.g. the REPL in [`super::repl`] - can run an automation body before
+//! (or without) sending it through the checker and HIR lowering passes.
+//!
+//! Entity reads go through a snapshot of [`state::State`] taken when
+//! evaluation starts: `state.lights`/`state.binary_sensors` index by
+//! entity id into a [`Value::Entity`] handle, and further field access
+//! (`.on`, `.brightness`, ...) reads that entity's current `LightState`/
+//! `BinarySensorState`. Mutators don't write through a handle directly -
+//! like [`super::eval::eval_mutator`], the automation's returned value
+//! *is* the command a caller should dispatch (e.g. a `LightCommand`-shaped
+//! [`Value::Struct`]).
+
+use std::collections::HashMap;
+
+use super::int_ops::checked_int_div;
+use super::int_ops::checked_int_mod;
+use super::repr::ast::BinOp;
+use super::repr::ast::MatchPattern;
+use super::repr::ast::UnaryOp;
+use super::repr::ast::UnitType;
+use super::repr::lowered::LoweredArg;
+use super::repr::lowered::LoweredAutomation;
+use super::repr::lowered::LoweredExpr;
+use super::repr::lowered::LoweredMatchArm;
+use super::repr::lowered::LoweredProgram;
+use super::repr::lowered::LoweredStmt;
+use super::repr::lowered::LoweredStructField;
+use super::repr::lowered::Origin;
+use super::repr::units::canonical_unit;
+use super::repr::units::dimension_of;
+use super::repr::units::to_base;
+use super::repr::lowered::Spanned;
+use crate::engine::state;
+
+/// A runtime value produced by evaluating the lowered AST.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    /// A unit-bearing numeric literal, normalized to its dimension's
+    /// canonical base unit (seconds, radians, or Kelvin) by
+    /// [`super::repr::units`] as soon as it's evaluated, matching
+    /// `eval::Value::Unit`'s convention so arithmetic never has to
+    /// convert twice.
+    Unit(f64, UnitType),
+    /// The `()` value, e.g. the result of a `let` or `for` statement.
+    Void,
+    /// An optional value, e.g. the result of `OptionalField` access or an
+    /// absent `Option` entity field.
+    Option(Option<Box<Value>>),
+    List(Vec<Value>),
+    /// A set, built from a `SetComp` via `MutableSet`/`Add`. Kept as a
+    /// `Vec` rather than a hash-based set the same way `List` is - `Value`
+    /// holds `f64` members (`Float`/`Unit`) that aren't `Eq`/`Hash` - with
+    /// `Add` responsible for deduplicating on insert.
+    Set(Vec<Value>),
+    /// A map, built from a `DictComp` via `MutableMap`/`Insert`. Kept as an
+    /// association list of key/value pairs for the same reason `Set` is a
+    /// `Vec`: `Value` keys aren't `Eq`/`Hash`.
+    Map(Vec<(Value, Value)>),
+    /// A struct literal, or an enum variant constructed via `Path::Call`
+    /// (e.g. `Event::LightOff`), whose positional args are keyed `"0"`,
+    /// `"1"`, ... since this AST has no dedicated variant value.
+    Struct {
+        name: String,
+        fields: HashMap<String, Value>,
+    },
+    /// A handle into the entity snapshot this evaluation started with,
+    /// produced by indexing `state.lights`/`state.binary_sensors` by
+    /// entity id. Field access on one reads through to live state.
+    Entity(EntityHandle),
+}
+
+/// Identifies one entity within a [`state::State`] snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityHandle {
+    pub domain: EntityDomain,
+    pub entity_id: String,
+}
+
+/// Which `State` map an [`EntityHandle`] was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityDomain {
+    Light,
+    BinarySensor,
+}
+
+/// A runtime error raised while evaluating an automation, carrying the
+/// [`Origin`] of the lowered node that raised it for span-accurate
+/// diagnostics.
+#[derive(Debug, Clone)]
+pub struct EvalError {
+    pub message: String,
+    pub origin: Option<Origin>,
+}
+
+impl EvalError {
+    fn new(message: impl Into<String>, origin: &Origin) -> Self {
+        Self {
+            message: message.into(),
+            origin: Some(origin.clone()),
+        }
+    }
+
+    fn without_origin(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            origin: None,
+        }
+    }
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.origin {
+            Some(origin) => {
+                let span = origin.span();
+                write!(f, "error at {}..{}: {}", span.start, span.end, self.message)
+            }
+            None => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// Safety cap on the number of iterations a single `for` loop may run,
+/// guarding against an `iter` expression that (due to a bug elsewhere)
+/// never terminates.
+const MAX_LOOP_ITERATIONS: usize = 1_000_000;
+
+/// Evaluate an observer automation, expecting its body to return a list of
+/// events.
+pub fn eval_observer(
+    state: &state::State,
+    automation: &LoweredAutomation,
+    event: Value,
+) -> Result<Vec<Value>, EvalError> {
+    match eval_automation(state, automation, event)? {
+        Value::List(events) => Ok(events),
+        other => Err(EvalError::without_origin(format!(
+            "observer body did not evaluate to a list of events: {other:?}"
+        ))),
+    }
+}
+
+/// Evaluate a mutator automation, expecting its body to return the single
+/// command it wants applied.
+pub fn eval_mutator(
+    state: &state::State,
+    automation: &LoweredAutomation,
+    event: Value,
+) -> Result<Value, EvalError> {
+    eval_automation(state, automation, event)
+}
+
+/// Evaluate every automation in `program` against the same `event`,
+/// expanding a template to each of its automations in turn.
+pub fn eval_program(
+    state: &state::State,
+    program: &LoweredProgram,
+    event: Value,
+) -> Result<Vec<Value>, EvalError> {
+    match program {
+        LoweredProgram::Automation(auto) => Ok(vec![eval_automation(state, auto, event)?]),
+        LoweredProgram::Template { automations, .. } => automations
+            .iter()
+            .map(|auto| eval_automation(state, auto, event.clone()))
+            .collect(),
+    }
+}
+
+fn eval_automation(
+    state: &state::State,
+    automation: &LoweredAutomation,
+    event: Value,
+) -> Result<Value, EvalError> {
+    let mut interpreter = Interpreter::new(state);
+    interpreter.bind("event".to_string(), event);
+    let state_value = interpreter.state_value();
+    interpreter.bind("state".to_string(), state_value);
+
+    if let Some(filter) = &automation.filter {
+        if !as_bool(&interpreter.eval_expr(filter)?, &filter.origin)? {
+            return Ok(Value::Void);
+        }
+    }
+
+    match interpreter.eval_block(&automation.body)? {
+        Flow::Return(value) | Flow::Value(value) => Ok(value),
+    }
+}
+
+/// Whether a block finished by running off its last statement (`Value`,
+/// the trailing `Stmt::Expr`'s value or `Void`) or by hitting `return`
+/// (`Return`, which keeps unwinding through any enclosing `for`/`if`).
+enum Flow {
+    Value(Value),
+    Return(Value),
+}
+
+struct Interpreter<'a> {
+    state: &'a state::State,
+    scopes: Vec<HashMap<String, Value>>,
+}
+
+impl<'a> Interpreter<'a> {
+    fn new(state: &'a state::State) -> Self {
+        Self {
+            state,
+            scopes: vec![HashMap::new()],
+        }
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, name: String, value: Value) {
+        self.scopes.last_mut().expect("at least one scope").insert(name, value);
+    }
+
+    fn get(&self, name: &str) -> Option<Value> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(name).cloned())
+    }
+
+    /// Update an existing binding of `name`, searching outward from the
+    /// innermost scope - used by `Push`, whose target list was bound by an
+    /// enclosing `let mut` before the current `for` body's scope opened.
+    fn set(&mut self, name: &str, value: Value, origin: &Origin) -> Result<(), EvalError> {
+        for scope in self.scopes.iter_mut().rev() {
+            if scope.contains_key(name) {
+                scope.insert(name.to_string(), value);
+                return Ok(());
+            }
+        }
+        Err(EvalError::new(format!("assignment to undefined variable '{name}'"), origin))
+    }
+
+    /// Build a [`Value::Entity`] snapshot of `self.state`'s entity maps,
+    /// bound to the `state` identifier for the duration of one evaluation.
+    fn state_value(&self) -> Value {
+        let lights = self
+            .state
+            .lights
+            .keys()
+            .map(|id| {
+                (
+                    id.clone(),
+                    Value::Entity(EntityHandle {
+                        domain: EntityDomain::Light,
+                        entity_id: id.clone(),
+                    }),
+                )
+            })
+            .collect();
+        let binary_sensors = self
+            .state
+            .binary_sensors
+            .keys()
+            .map(|id| {
+                (
+                    id.clone(),
+                    Value::Entity(EntityHandle {
+                        domain: EntityDomain::BinarySensor,
+                        entity_id: id.clone(),
+                    }),
+                )
+            })
+            .collect();
+        Value::Struct {
+            name: "State".to_string(),
+            fields: HashMap::from([
+                (
+                    "lights".to_string(),
+                    Value::Struct {
+                        name: "Lights".to_string(),
+                        fields: lights,
+                    },
+                ),
+                (
+                    "binary_sensors".to_string(),
+                    Value::Struct {
+                        name: "BinarySensors".to_string(),
+                        fields: binary_sensors,
+                    },
+                ),
+            ]),
+        }
+    }
+
+    /// Evaluate statements in order, threading a single scope. The block's
+    /// value is its last `Stmt::Expr`'s value (`Void` if the block is
+    /// empty or ends on a `Let`/`For`/`Push`), unless a `return` is hit
+    /// first, which unwinds immediately as `Flow::Return`.
+    fn eval_block(&mut self, stmts: &[Spanned<LoweredStmt>]) -> Result<Flow, EvalError> {
+        let mut result = Value::Void;
+        for stmt in stmts {
+            match &stmt.node {
+                LoweredStmt::Let { name, value } | LoweredStmt::LetMut { name, value } => {
+                    let evaluated = self.eval_expr(value)?;
+                    self.bind(name.clone(), evaluated);
+                    result = Value::Void;
+                }
+                LoweredStmt::Expr(expr) => {
+                    result = self.eval_expr(expr)?;
+                }
+                LoweredStmt::Return(expr) => {
+                    return Ok(Flow::Return(self.eval_expr(expr)?));
+                }
+                LoweredStmt::For { var, iter, body } => {
+                    let items = self.eval_list(iter)?;
+                    for item in items.into_iter().take(MAX_LOOP_ITERATIONS) {
+                        self.push_scope();
+                        self.bind(var.clone(), item);
+                        let flow = self.eval_block(body);
+                        self.pop_scope();
+                        if let Flow::Return(value) = flow? {
+                            return Ok(Flow::Return(value));
+                        }
+                    }
+                    result = Value::Void;
+                }
+                LoweredStmt::While { cond, body } => {
+                    let mut iterations = 0;
+                    while as_bool(&self.eval_expr(cond)?, &cond.origin)? {
+                        if iterations >= MAX_LOOP_ITERATIONS {
+                            break;
+                        }
+                        iterations += 1;
+                        self.push_scope();
+                        let flow = self.eval_block(body);
+                        self.pop_scope();
+                        if let Flow::Return(value) = flow? {
+                            return Ok(Flow::Return(value));
+                        }
+                    }
+                    result = Value::Void;
+                }
+                LoweredStmt::Push { list, value } => {
+                    let evaluated = self.eval_expr(value)?;
+                    let mut items = match self.get(list) {
+                        Some(Value::List(items)) => items,
+                        Some(other) => return Err(type_error("push onto", &other, &stmt.origin)),
+                        None => {
+                            return Err(EvalError::new(
+                                format!("push onto undefined variable '{list}'"),
+                                &stmt.origin,
+                            ));
+                        }
+                    };
+                    items.push(evaluated);
+                    self.set(list, Value::List(items), &stmt.origin)?;
+                    result = Value::Void;
+                }
+                LoweredStmt::Insert { map, key, value } => {
+                    let evaluated_key = self.eval_expr(key)?;
+                    let evaluated_value = self.eval_expr(value)?;
+                    let mut entries = match self.get(map) {
+                        Some(Value::Map(entries)) => entries,
+                        Some(other) => return Err(type_error("insert into", &other, &stmt.origin)),
+                        None => {
+                            return Err(EvalError::new(
+                                format!("insert into undefined variable '{map}'"),
+                                &stmt.origin,
+                            ));
+                        }
+                    };
+                    match entries.iter_mut().find(|(k, _)| *k == evaluated_key) {
+                        Some((_, existing)) => *existing = evaluated_value,
+                        None => entries.push((evaluated_key, evaluated_value)),
+                    }
+                    self.set(map, Value::Map(entries), &stmt.origin)?;
+                    result = Value::Void;
+                }
+                LoweredStmt::Add { set, value } => {
+                    let evaluated = self.eval_expr(value)?;
+                    let mut items = match self.get(set) {
+                        Some(Value::Set(items)) => items,
+                        Some(other) => return Err(type_error("add to", &other, &stmt.origin)),
+                        None => {
+                            return Err(EvalError::new(
+                                format!("add to undefined variable '{set}'"),
+                                &stmt.origin,
+                            ));
+                        }
+                    };
+                    if !items.contains(&evaluated) {
+                        items.push(evaluated);
+                    }
+                    self.set(set, Value::Set(items), &stmt.origin)?;
+                    result = Value::Void;
+                }
+                LoweredStmt::CompoundAssign { name, op, value } => {
+                    let evaluated = self.eval_expr(value)?;
+                    let current = self.get(name).ok_or_else(|| {
+                        EvalError::new(
+                            format!("assign to undefined variable '{name}'"),
+                            &stmt.origin,
+                        )
+                    })?;
+                    let updated = match (op, &current, &evaluated) {
+                        (BinOp::Add, Value::List(items), Value::List(extra)) => {
+                            let mut items = items.clone();
+                            items.extend(extra.iter().cloned());
+                            Value::List(items)
+                        }
+                        _ => eval_binop(*op, &current, &evaluated, &stmt.origin)?,
+                    };
+                    self.set(name, updated, &stmt.origin)?;
+                    result = Value::Void;
+                }
+            }
+        }
+        Ok(Flow::Value(result))
+    }
+
+    /// Like [`Self::eval_block`], but for the synthetic `Block` expression
+    /// produced by desugaring a list comprehension: its value always comes
+    /// from the explicit trailing `result` expression, never from the
+    /// preceding statements, and a `return` inside it is rejected rather
+    /// than silently discarded - comprehensions aren't automation bodies.
+    fn eval_block_expr(
+        &mut self,
+        stmts: &[Spanned<LoweredStmt>],
+        result: &Spanned<LoweredExpr>,
+    ) -> Result<Value, EvalError> {
+        match self.eval_block(stmts)? {
+            Flow::Return(_) => Err(EvalError::new(
+                "'return' is not valid inside a list comprehension",
+                &result.origin,
+            )),
+            Flow::Value(_) => self.eval_expr(result),
+        }
+    }
+
+    fn eval_list(&mut self, expr: &Spanned<LoweredExpr>) -> Result<Vec<Value>, EvalError> {
+        match self.eval_expr(expr)? {
+            Value::List(items) => Ok(items),
+            Value::Set(items) => Ok(items),
+            Value::Map(entries) => Ok(entries.into_iter().map(|(key, _)| key).collect()),
+            other => Err(type_error("iterate over", &other, &expr.origin)),
+        }
+    }
+
+    fn eval_expr(&mut self, expr: &Spanned<LoweredExpr>) -> Result<Value, EvalError> {
+        match &expr.node {
+            LoweredExpr::Int(n) => Ok(Value::Int(*n)),
+            LoweredExpr::Float(s) => s.parse().map(Value::Float).map_err(|_| {
+                EvalError::new(format!("malformed float literal '{s}'"), &expr.origin)
+            }),
+            LoweredExpr::String(s) => Ok(Value::String(s.clone())),
+            LoweredExpr::Bool(b) => Ok(Value::Bool(*b)),
+            LoweredExpr::UnitLiteral { value, unit } => {
+                let raw: f64 = value.parse().map_err(|_| {
+                    EvalError::new(format!("malformed unit literal value '{value}'"), &expr.origin)
+                })?;
+                Ok(Value::Unit(to_base(*unit, raw), canonical_unit(dimension_of(*unit))))
+            }
+            LoweredExpr::Ident(name) => self.get(name).ok_or_else(|| {
+                EvalError::new(format!("undefined variable '{name}'"), &expr.origin)
+            }),
+            LoweredExpr::Path(segments) => Ok(Value::Struct {
+                name: segments.join("::"),
+                fields: HashMap::new(),
+            }),
+            LoweredExpr::BinOp { op, left, right } => {
+                let left = self.eval_expr(left)?;
+                let right = self.eval_expr(right)?;
+                eval_binop(*op, &left, &right, &expr.origin)
+            }
+            LoweredExpr::UnaryOp { op, expr: inner } => {
+                let value = self.eval_expr(inner)?;
+                eval_unop(*op, &value, &expr.origin)
+            }
+            LoweredExpr::Field { expr: base, field } => {
+                let base = self.eval_expr(base)?;
+                self.eval_field(&base, field, &expr.origin)
+            }
+            LoweredExpr::OptionalField { expr: base, field } => {
+                let base = self.eval_expr(base)?;
+                self.eval_optional_field(&base, field, &expr.origin)
+            }
+            LoweredExpr::Call { func, args } => self.eval_call(func, args, &expr.origin),
+            LoweredExpr::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                let branch = if as_bool(&self.eval_expr(cond)?, &cond.origin)? {
+                    then_block
+                } else {
+                    match else_block {
+                        Some(stmts) => stmts,
+                        None => return Ok(Value::Void),
+                    }
+                };
+                self.push_scope();
+                let flow = self.eval_block(branch);
+                self.pop_scope();
+                match flow? {
+                    Flow::Value(value) => Ok(value),
+                    // An early `return` inside an `if` used as an
+                    // expression has nowhere to go but back up through the
+                    // enclosing automation body, so surface it the same
+                    // way `eval_block` does for statement position.
+                    Flow::Return(value) => Ok(value),
+                }
+            }
+            LoweredExpr::List(items) => {
+                let values = items
+                    .iter()
+                    .map(|item| self.eval_expr(item))
+                    .collect::<Result<_, _>>()?;
+                Ok(Value::List(values))
+            }
+            LoweredExpr::StructLit { name, fields } => {
+                self.eval_struct_lit(name, fields, &expr.origin)
+            }
+            LoweredExpr::Block { stmts, result } => self.eval_block_expr(stmts, result),
+            LoweredExpr::MutableList => Ok(Value::List(Vec::new())),
+            LoweredExpr::MutableMap => Ok(Value::Map(Vec::new())),
+            LoweredExpr::MutableSet => Ok(Value::Set(Vec::new())),
+            LoweredExpr::Match { scrutinee, arms } => {
+                let scrutinee = self.eval_expr(scrutinee)?;
+                self.eval_match(&scrutinee, arms, &expr.origin)
+            }
+            // Closures aren't modeled as runtime values yet - like
+            // `eval::eval_builtin`, only the `filter`/`map`/`fold` call
+            // sites that consume a lambda directly understand it.
+            LoweredExpr::Lambda { .. } => Err(EvalError::new(
+                "lambda expressions cannot be evaluated standalone yet",
+                &expr.origin,
+            )),
+            // Like `Lambda`, there's no runtime `Value::Tuple` yet - tuples
+            // only exist as a checker-level type so far.
+            LoweredExpr::Tuple(_) => Err(EvalError::new(
+                "tuple expressions cannot be evaluated yet",
+                &expr.origin,
+            )),
+        }
+    }
+
+    fn eval_struct_lit(
+        &mut self,
+        name: &str,
+        fields: &[Spanned<LoweredStructField>],
+        origin: &Origin,
+    ) -> Result<Value, EvalError> {
+        let mut built = HashMap::new();
+        for field in fields {
+            match &field.node {
+                LoweredStructField::Field { name, value } => {
+                    built.insert(name.clone(), self.eval_expr(value)?);
+                }
+                LoweredStructField::Inherit(name) => {
+                    let value = self.get(name).ok_or_else(|| {
+                        EvalError::new(format!("undefined variable '{name}'"), origin)
+                    })?;
+                    built.insert(name.clone(), value);
+                }
+                LoweredStructField::Spread(name) => {
+                    let value = self.get(name).ok_or_else(|| {
+                        EvalError::new(format!("undefined variable '{name}'"), origin)
+                    })?;
+                    match value {
+                        Value::Struct { fields, .. } => built.extend(fields),
+                        other => return Err(type_error("spread", &other, origin)),
+                    }
+                }
+            }
+        }
+        Ok(Value::Struct {
+            name: name.to_string(),
+            fields: built,
+        })
+    }
+
+    /// `func` is either a builtin name (`len(xs)`) or an enum variant path
+    /// (`Event::LightOff(entity_id)`), constructing a [`Value::Struct`]
+    /// whose positional args are keyed `"0"`, `"1"`, ... - this AST has no
+    /// separate variant-construction node, unlike HIR's `Op::Variant`.
+    fn eval_call(
+        &mut self,
+        func: &Spanned<LoweredExpr>,
+        args: &[Spanned<LoweredArg>],
+        origin: &Origin,
+    ) -> Result<Value, EvalError> {
+        let values = args
+            .iter()
+            .map(|arg| match &arg.node {
+                LoweredArg::Positional(value) => self.eval_expr(value),
+                LoweredArg::Named { value, .. } => self.eval_expr(value),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match &func.node {
+            LoweredExpr::Path(segments) => {
+                let fields = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, v)| (i.to_string(), v))
+                    .collect();
+                Ok(Value::Struct {
+                    name: segments.join("::"),
+                    fields,
+                })
+            }
+            LoweredExpr::Ident(name) => eval_builtin(name, &values, origin),
+            _ => Err(EvalError::new("value is not callable", origin)),
+        }
+    }
+
+    fn eval_field(&self, base: &Value, field: &str, origin: &Origin) -> Result<Value, EvalError> {
+        match base {
+            Value::Struct { fields, .. } => fields.get(field).cloned().ok_or_else(|| {
+                EvalError::new(format!("no field '{field}' on struct value"), origin)
+            }),
+            Value::Entity(handle) => self.read_entity_field(handle, field, origin),
+            other => Err(type_error(&format!("read field '{field}' of"), other, origin)),
+        }
+    }
+
+    /// Like [`Self::eval_field`], but tolerant of `base` already being an
+    /// `Option`: absent short-circuits to `Option(None)` instead of
+    /// erroring, matching `?.`'s surface semantics.
+    fn eval_optional_field(
+        &self,
+        base: &Value,
+        field: &str,
+        origin: &Origin,
+    ) -> Result<Value, EvalError> {
+        match base {
+            Value::Option(None) => Ok(Value::Option(None)),
+            Value::Option(Some(inner)) => match self.eval_field(inner, field, origin)? {
+                already_optional @ Value::Option(_) => Ok(already_optional),
+                other => Ok(Value::Option(Some(Box::new(other)))),
+            },
+            other => match self.eval_field(other, field, origin)? {
+                already_optional @ Value::Option(_) => Ok(already_optional),
+                result => Ok(Value::Option(Some(Box::new(result)))),
+            },
+        }
+    }
+
+    fn read_entity_field(
+        &self,
+        handle: &EntityHandle,
+        field: &str,
+        origin: &Origin,
+    ) -> Result<Value, EvalError> {
+        match handle.domain {
+            EntityDomain::Light => {
+                let light = self.state.lights.get(&handle.entity_id).ok_or_else(|| {
+                    EvalError::new(
+                        format!("no light entity '{}' in state", handle.entity_id),
+                        origin,
+                    )
+                })?;
+                match field {
+                    "on" => Ok(Value::Bool(light.on)),
+                    "brightness" => {
+                        Ok(option_value(light.brightness.map(|b| Value::Int(b.into()))))
+                    }
+                    "color_temp" => {
+                        Ok(option_value(light.color_temp.map(|t| Value::Int(t.into()))))
+                    }
+                    "transition" => Ok(option_value(light.transition.map(Value::Float))),
+                    "color_xy" => Ok(option_value(light.color_xy.map(|c| Value::Struct {
+                        name: "ColorXy".to_string(),
+                        fields: HashMap::from([
+                            ("x".to_string(), Value::Float(c.x)),
+                            ("y".to_string(), Value::Float(c.y)),
+                        ]),
+                    }))),
+                    "color_rgb" => Ok(option_value(light.color_rgb.map(|c| Value::Struct {
+                        name: "ColorRgb".to_string(),
+                        fields: HashMap::from([
+                            ("r".to_string(), Value::Int(c.r.into())),
+                            ("g".to_string(), Value::Int(c.g.into())),
+                            ("b".to_string(), Value::Int(c.b.into())),
+                        ]),
+                    }))),
+                    other => Err(EvalError::new(
+                        format!("light entity has no field '{other}'"),
+                        origin,
+                    )),
+                }
+            }
+            EntityDomain::BinarySensor => {
+                let sensor = self.state.binary_sensors.get(&handle.entity_id).ok_or_else(|| {
+                    EvalError::new(
+                        format!("no binary sensor entity '{}' in state", handle.entity_id),
+                        origin,
+                    )
+                })?;
+                match field {
+                    "on" => Ok(Value::Bool(sensor.on)),
+                    other => Err(EvalError::new(
+                        format!("binary sensor entity has no field '{other}'"),
+                        origin,
+                    )),
+                }
+            }
+        }
+    }
+
+    fn eval_match(
+        &mut self,
+        scrutinee: &Value,
+        arms: &[LoweredMatchArm],
+        origin: &Origin,
+    ) -> Result<Value, EvalError> {
+        for arm in arms {
+            let bindings = match &arm.pattern.node {
+                MatchPattern::Wildcard => Some(Vec::new()),
+                MatchPattern::Variant {
+                    enum_name,
+                    variant,
+                    bindings,
+                } => match scrutinee {
+                    Value::Struct { name, fields }
+                        if *name == format!("{enum_name}::{variant}") =>
+                    {
+                        let mut bound = Vec::with_capacity(bindings.len());
+                        for (i, binding) in bindings.iter().enumerate() {
+                            bound.push((binding, fields.get(&i.to_string()).cloned()));
+                        }
+                        Some(bound.into_iter().map(|(b, v)| (b.clone(), v)).collect())
+                    }
+                    _ => None,
+                },
+            };
+
+            if let Some(bindings) = bindings {
+                self.push_scope();
+                for (binding, value) in bindings {
+                    if let (super::repr::ast::BindingPattern::Ident(name), Some(value)) =
+                        (&binding.node, value)
+                    {
+                        self.bind(name.clone(), value);
+                    }
+                }
+                let flow = self.eval_block(&arm.body);
+                self.pop_scope();
+                return match flow? {
+                    Flow::Value(value) | Flow::Return(value) => Ok(value),
+                };
+            }
+        }
+        Err(EvalError::new("no match arm matched the scrutinee", origin))
+    }
+}
+
+fn option_value(value: Option<Value>) -> Value {
+    Value::Option(value.map(Box::new))
+}
+
+fn as_bool(value: &Value, origin: &Origin) -> Result<bool, EvalError> {
+    match value {
+        Value::Bool(b) => Ok(*b),
+        other => Err(type_error("branch on", other, origin)),
+    }
+}
+
+fn type_error(action: &str, value: &Value, origin: &Origin) -> EvalError {
+    EvalError::new(format!("cannot {action} a value of this shape: {value:?}"), origin)
+}
+
+fn eval_unop(op: UnaryOp, value: &Value, origin: &Origin) -> Result<Value, EvalError> {
+    match (op, value) {
+        (UnaryOp::Neg, Value::Int(n)) => Ok(Value::Int(-n)),
+        (UnaryOp::Neg, Value::Float(n)) => Ok(Value::Float(-n)),
+        (UnaryOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        // `await`/`*` have no async scheduler or reference type to act on
+        // yet at this AST level, so both just pass their operand through,
+        // matching `eval::Interpreter::eval_op`'s `Deref`/`Await` handling.
+        (UnaryOp::Deref, v) | (UnaryOp::Await, v) => Ok(v.clone()),
+        (op, other) => Err(type_error(&format!("apply '{op}' to"), other, origin)),
+    }
+}
+
+fn eval_binop(op: BinOp, left: &Value, right: &Value, origin: &Origin) -> Result<Value, EvalError> {
+    use BinOp::*;
+    use Value::*;
+
+    match (op, left, right) {
+        (_, Unit(a, ua), Unit(b, ub)) => {
+            let (da, db) = (dimension_of(*ua), dimension_of(*ub));
+            if da != db {
+                return Err(EvalError::new(
+                    format!(
+                        "cannot combine a {da} value with a {db} value: {op} requires both \
+                         sides to share a dimension"
+                    ),
+                    origin,
+                ));
+            }
+            // Both sides are already normalized to the same canonical base
+            // unit by `eval_expr`'s `UnitLiteral` case.
+            match op {
+                Add => Ok(Unit(a + b, *ua)),
+                Sub => Ok(Unit(a - b, *ua)),
+                Eq => Ok(Bool(a == b)),
+                Ne => Ok(Bool(a != b)),
+                Lt => Ok(Bool(a < b)),
+                Le => Ok(Bool(a <= b)),
+                Gt => Ok(Bool(a > b)),
+                Ge => Ok(Bool(a >= b)),
+                _ => Err(binop_type_error(op, left, right, origin)),
+            }
+        }
+        (Div, Int(_), Int(0)) | (Mod, Int(_), Int(0)) => Err(EvalError::new(
+            format!("{} by zero", if op == Div { "division" } else { "modulo" }),
+            origin,
+        )),
+        (Add, Int(a), Int(b)) => Ok(Int(a.wrapping_add(*b))),
+        (Sub, Int(a), Int(b)) => Ok(Int(a.wrapping_sub(*b))),
+        (Mul, Int(a), Int(b)) => Ok(Int(a.wrapping_mul(*b))),
+        // The b == 0 case is already handled by the guard arm above; this
+        // only needs to guard i64::MIN / -1, which plain / and % panic on.
+        (Div, Int(a), Int(b)) => Ok(Int(checked_int_div(*a, *b))),
+        (Mod, Int(a), Int(b)) => Ok(Int(checked_int_mod(*a, *b))),
+        (Add, Float(a), Float(b)) => Ok(Float(a + b)),
+        (Sub, Float(a), Float(b)) => Ok(Float(a - b)),
+        (Mul, Float(a), Float(b)) => Ok(Float(a * b)),
+        (Div, Float(a), Float(b)) => Ok(Float(a / b)),
+        // Mixed int/float operands are promoted to float, matching the
+        // type checker's numeric-literal unification.
+        (Add, Int(a), Float(b)) | (Add, Float(b), Int(a)) => Ok(Float(*a as f64 + b)),
+        (Sub, Int(a), Float(b)) => Ok(Float(*a as f64 - b)),
+        (Sub, Float(a), Int(b)) => Ok(Float(a - *b as f64)),
+        (Mul, Int(a), Float(b)) | (Mul, Float(b), Int(a)) => Ok(Float(*a as f64 * b)),
+        (Div, Int(a), Float(b)) => Ok(Float(*a as f64 / b)),
+        (Div, Float(a), Int(b)) => Ok(Float(a / *b as f64)),
+        (Add, String(a), String(b)) => Ok(String(format!("{a}{b}"))),
+        (And, Bool(a), Bool(b)) => Ok(Bool(*a && *b)),
+        (Or, Bool(a), Bool(b)) => Ok(Bool(*a || *b)),
+        (Eq, a, b) => Ok(Bool(a == b)),
+        (Ne, a, b) => Ok(Bool(a != b)),
+        (Lt, Int(a), Int(b)) => Ok(Bool(a < b)),
+        (Le, Int(a), Int(b)) => Ok(Bool(a <= b)),
+        (Gt, Int(a), Int(b)) => Ok(Bool(a > b)),
+        (Ge, Int(a), Int(b)) => Ok(Bool(a >= b)),
+        (Lt, Float(a), Float(b)) => Ok(Bool(a < b)),
+        (Le, Float(a), Float(b)) => Ok(Bool(a <= b)),
+        (Gt, Float(a), Float(b)) => Ok(Bool(a > b)),
+        (Ge, Float(a), Float(b)) => Ok(Bool(a >= b)),
+        _ => Err(binop_type_error(op, left, right, origin)),
+    }
+}
+
+fn binop_type_error(op: BinOp, left: &Value, right: &Value, origin: &Origin) -> EvalError {
+    EvalError::new(format!("operator '{op}' is not defined for {left:?} and {right:?}"), origin)
+}
+
+/// Numeric/collection builtins mirroring the non-async subset of
+/// `eval::eval_builtin`.
+fn eval_builtin(name: &str, args: &[Value], origin: &Origin) -> Result<Value, EvalError> {
+    match name {
+        "len" => match args {
+            [Value::List(items)] => Ok(Value::Int(items.len() as i64)),
+            [Value::String(s)] => Ok(Value::Int(s.chars().count() as i64)),
+            _ => Err(builtin_arity_error(name, args, origin)),
+        },
+        "abs" => match args {
+            [Value::Int(n)] => Ok(Value::Int(n.abs())),
+            [Value::Float(n)] => Ok(Value::Float(n.abs())),
+            _ => Err(builtin_arity_error(name, args, origin)),
+        },
+        "min" => match args {
+            [Value::Int(a), Value::Int(b)] => Ok(Value::Int((*a).min(*b))),
+            [a, b] => Ok(Value::Float(as_f64(a, origin)?.min(as_f64(b, origin)?))),
+            _ => Err(builtin_arity_error(name, args, origin)),
+        },
+        "max" => match args {
+            [Value::Int(a), Value::Int(b)] => Ok(Value::Int((*a).max(*b))),
+            [a, b] => Ok(Value::Float(as_f64(a, origin)?.max(as_f64(b, origin)?))),
+            _ => Err(builtin_arity_error(name, args, origin)),
+        },
+        "clamp" => match args {
+            [Value::Int(v), Value::Int(lo), Value::Int(hi)] => Ok(Value::Int((*v).clamp(*lo, *hi))),
+            [v, lo, hi] => Ok(Value::Float(
+                as_f64(v, origin)?.clamp(as_f64(lo, origin)?, as_f64(hi, origin)?),
+            )),
+            _ => Err(builtin_arity_error(name, args, origin)),
+        },
+        other => Err(EvalError::new(
+            format!("builtin '{other}' is not supported by the interpreter"),
+            origin,
+        )),
+    }
+}
+
+fn as_f64(value: &Value, origin: &Origin) -> Result<f64, EvalError> {
+    match value {
+        Value::Int(n) => Ok(*n as f64),
+        Value::Float(n) => Ok(*n),
+        other => Err(type_error("use as a number", other, origin)),
+    }
+}
+
+fn builtin_arity_error(name: &str, args: &[Value], origin: &Origin) -> EvalError {
+    EvalError::new(format!("builtin '{name}' cannot be called with arguments {args:?}"), origin)
+}
@@ -1,4 +1,12 @@
 //! Lexer and token definitions for the HearthD Automations language.
+//!
+//! `/` is contextually ambiguous - division (`a / b`) and a filter
+//! literal's delimiters (`/a.on/`) use the same character. [`lexer`] first
+//! tokenizes everything as if `/` only ever meant division, then
+//! [`disambiguate_filters`] walks that token list once, reclassifying
+//! bare `Slash` tokens into `FilterStart`/`FilterEnd` based on the
+//! preceding token - mirroring how a JavaScript lexer tells a regex
+//! literal from division.
 
 use chumsky::input::MapExtra;
 use chumsky::prelude::*;
@@ -24,7 +32,9 @@ pub enum Token {
     // Keywords
     Observer,
     Mutator,
+    Template,
     Let,
+    Mut,
     If,
     Else,
     For,
@@ -51,8 +61,17 @@ pub enum Token {
     Not,       // !
     Question,  // ?
     Dot,       // .
+    DotDot,    // ..
+    DotDotEq,  // ..=
     DotDotDot, // ...
     Assign,    // =
+    FatArrow,  // =>
+    Pipe,      // | (lambda parameter delimiter)
+    PlusEq,    // +=
+    MinusEq,   // -=
+    StarEq,    // *=
+    SlashEq,   // /=
+    PercentEq, // %=
 
     // Delimiters
     LParen,      // (
@@ -80,7 +99,9 @@ impl std::fmt::Display for Token {
             Token::Ident(s) => write!(f, "{}", s),
             Token::Observer => write!(f, "observer"),
             Token::Mutator => write!(f, "mutator"),
+            Token::Template => write!(f, "template"),
             Token::Let => write!(f, "let"),
+            Token::Mut => write!(f, "mut"),
             Token::If => write!(f, "if"),
             Token::Else => write!(f, "else"),
             Token::For => write!(f, "for"),
@@ -105,8 +126,17 @@ impl std::fmt::Display for Token {
             Token::Not => write!(f, "!"),
             Token::Question => write!(f, "?"),
             Token::Dot => write!(f, "."),
+            Token::DotDot => write!(f, ".."),
+            Token::DotDotEq => write!(f, "..="),
             Token::DotDotDot => write!(f, "..."),
             Token::Assign => write!(f, "="),
+            Token::FatArrow => write!(f, "=>"),
+            Token::Pipe => write!(f, "|"),
+            Token::PlusEq => write!(f, "+="),
+            Token::MinusEq => write!(f, "-="),
+            Token::StarEq => write!(f, "*="),
+            Token::SlashEq => write!(f, "/="),
+            Token::PercentEq => write!(f, "%="),
             Token::LParen => write!(f, "("),
             Token::RParen => write!(f, ")"),
             Token::LBrace => write!(f, "{{"),
@@ -123,6 +153,74 @@ impl std::fmt::Display for Token {
     }
 }
 
+/// Whether a `/` immediately following `prev` (the last token emitted, or
+/// `None` at the very start of input) must be division rather than the
+/// start of a filter literal.
+///
+/// Division follows something that already yields a value: an identifier,
+/// a literal, or a closing `)`/`]`. Everything else - an opening `(`, a
+/// `,`, `in`, `await`, a binary/unary operator, a keyword, `{`/`}`, or the
+/// start of input - means a value is expected next, so a `/` there opens
+/// a filter literal instead.
+fn is_division_context(prev: Option<&Token>) -> bool {
+    matches!(
+        prev,
+        Some(Token::Ident(_))
+            | Some(Token::Int(_))
+            | Some(Token::Float(_))
+            | Some(Token::UnitLiteral { .. })
+            | Some(Token::String(_))
+            | Some(Token::Bool(_))
+            | Some(Token::RParen)
+            | Some(Token::RBracket)
+    )
+}
+
+/// Reclassify bare `Token::Slash` tokens into `FilterStart`/`FilterEnd`
+/// pairs, using [`is_division_context`] to decide whether each one opens a
+/// filter or is division. Once a filter is open, the next unescaped slash
+/// always closes it - a `\/` lexes to a two-character-wide `Slash` (see
+/// `op`'s `just("\\/")` alternative) and is left alone either way, so a
+/// filter's own expression can contain a division without prematurely
+/// ending the literal.
+fn disambiguate_filters(
+    tokens: Vec<(Token, SimpleSpan)>,
+) -> Result<Vec<(Token, SimpleSpan)>, (String, SimpleSpan)> {
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut in_filter = false;
+    let mut filter_start_span = None;
+    let mut prev: Option<Token> = None;
+
+    for (token, span) in tokens {
+        let is_escaped_slash = token == Token::Slash && span.end - span.start > 1;
+
+        let reclassified = if token == Token::Slash && !is_escaped_slash {
+            if in_filter {
+                in_filter = false;
+                Token::FilterEnd
+            } else if is_division_context(prev.as_ref()) {
+                Token::Slash
+            } else {
+                in_filter = true;
+                filter_start_span = Some(span);
+                Token::FilterStart
+            }
+        } else {
+            token
+        };
+
+        prev = Some(reclassified.clone());
+        out.push((reclassified, span));
+    }
+
+    if in_filter {
+        let span = filter_start_span.expect("in_filter is only set alongside filter_start_span");
+        return Err(("unterminated filter literal: expected a closing '/'".to_string(), span));
+    }
+
+    Ok(out)
+}
+
 /// Parse a unit suffix and return the corresponding unit type.
 fn parse_unit_suffix(suffix: &str) -> Option<UnitType> {
     match suffix {
@@ -196,7 +294,9 @@ pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token, SimpleSpan)>, extra::
     let ident = text::ident().map(|s: &str| match s {
         "observer" => Token::Observer,
         "mutator" => Token::Mutator,
+        "template" => Token::Template,
         "let" => Token::Let,
+        "mut" => Token::Mut,
         "if" => Token::If,
         "else" => Token::Else,
         "for" => Token::For,
@@ -210,26 +310,48 @@ pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token, SimpleSpan)>, extra::
         _ => Token::Ident(s.to_string()),
     });
 
-    // Operators (order matters for multi-char ops)
+    // Operators (order matters for multi-char ops). Split into two `choice`
+    // tuples since chumsky's `choice` macro only supports tuples up to a
+    // fixed arity - `...`/`..=`/`..` are pulled out to the front so they're
+    // tried before the bare `.` buried inside the inner tuple.
     let op = choice((
         just("...").to(Token::DotDotDot),
-        just("==").to(Token::Eq),
-        just("!=").to(Token::Ne),
-        just("<=").to(Token::Le),
-        just(">=").to(Token::Ge),
-        just("&&").to(Token::And),
-        just("||").to(Token::Or),
-        just("<").to(Token::Lt),
-        just(">").to(Token::Gt),
-        just("!").to(Token::Not),
-        just("+").to(Token::Plus),
-        just("-").to(Token::Minus),
-        just("*").to(Token::Star),
-        just("/").to(Token::Slash),
-        just("%").to(Token::Percent),
-        just("?").to(Token::Question),
-        just(".").to(Token::Dot),
-        just("=").to(Token::Assign),
+        just("..=").to(Token::DotDotEq),
+        just("..").to(Token::DotDot),
+        choice((
+            just("==").to(Token::Eq),
+            just("!=").to(Token::Ne),
+            just("<=").to(Token::Le),
+            just(">=").to(Token::Ge),
+            just("&&").to(Token::And),
+            just("||").to(Token::Or),
+            just("|").to(Token::Pipe),
+            just("<").to(Token::Lt),
+            just(">").to(Token::Gt),
+            just("!").to(Token::Not),
+            just("+=").to(Token::PlusEq),
+            just("+").to(Token::Plus),
+            just("-=").to(Token::MinusEq),
+            just("-").to(Token::Minus),
+            just("*=").to(Token::StarEq),
+            just("*").to(Token::Star),
+            // `\/` always means a literal slash, never a filter delimiter -
+            // write it inside a filter literal to embed a division that would
+            // otherwise be read as the filter's closing `/`.
+            just("\\/").to(Token::Slash),
+            // `/=` is only ever meaningful as compound assignment (a filter
+            // literal's contents never start with `=`), so it's pulled out of
+            // the division-vs-filter ambiguity entirely: matched as its own
+            // token here, it never reaches `disambiguate_filters` at all.
+            just("/=").to(Token::SlashEq),
+            just("/").to(Token::Slash),
+            just("%=").to(Token::PercentEq),
+            just("%").to(Token::Percent),
+            just("?").to(Token::Question),
+            just(".").to(Token::Dot),
+            just("=>").to(Token::FatArrow),
+            just("=").to(Token::Assign),
+        )),
     ));
 
     // Delimiters (order matters: :: before :)
@@ -271,4 +393,7 @@ pub fn lexer<'a>() -> impl Parser<'a, &'a str, Vec<(Token, SimpleSpan)>, extra::
         .padded_by(ws)
         .repeated()
         .collect()
+        .try_map(|tokens, _span| {
+            disambiguate_filters(tokens).map_err(|(msg, span)| Rich::custom(span, msg))
+        })
 }
@@ -65,20 +65,21 @@ fn test_lex_bools() {
 
 #[test]
 fn test_lex_keywords() {
-    let input = "observer mutator let if else for in await inherit";
+    let input = "observer mutator template let if else for in await inherit";
     let result = lexer().parse(input).into_result().unwrap();
     assert_eq!(
         result,
         vec![
             (Token::Observer, (0..8).into()),
             (Token::Mutator, (9..16).into()),
-            (Token::Let, (17..20).into()),
-            (Token::If, (21..23).into()),
-            (Token::Else, (24..28).into()),
-            (Token::For, (29..32).into()),
-            (Token::In, (33..35).into()),
-            (Token::Await, (36..41).into()),
-            (Token::Inherit, (42..49).into()),
+            (Token::Template, (17..25).into()),
+            (Token::Let, (26..29).into()),
+            (Token::If, (30..32).into()),
+            (Token::Else, (33..37).into()),
+            (Token::For, (38..41).into()),
+            (Token::In, (42..44).into()),
+            (Token::Await, (45..50).into()),
+            (Token::Inherit, (51..58).into()),
         ]
     );
 }
@@ -97,9 +98,12 @@ fn test_lex_identifiers() {
     );
 }
 
+// `/` is covered separately by `test_lex_division` and
+// `test_lex_filter_literal` below - which token it lexes to depends on
+// the preceding token, so it doesn't fit in a flat list of operators.
 #[test]
 fn test_lex_operators() {
-    let input = "+ - * / % == != < <= > >= && || !";
+    let input = "+ - * % == != < <= > >= && || ! =>";
     let result = lexer().parse(input).into_result().unwrap();
     assert_eq!(
         result,
@@ -107,21 +111,95 @@ fn test_lex_operators() {
             (Token::Plus, (0..1).into()),
             (Token::Minus, (2..3).into()),
             (Token::Star, (4..5).into()),
+            (Token::Percent, (6..7).into()),
+            (Token::Eq, (8..10).into()),
+            (Token::Ne, (11..13).into()),
+            (Token::Lt, (14..15).into()),
+            (Token::Le, (16..18).into()),
+            (Token::Gt, (19..20).into()),
+            (Token::Ge, (21..23).into()),
+            (Token::And, (24..26).into()),
+            (Token::Or, (27..29).into()),
+            (Token::Not, (30..31).into()),
+            (Token::FatArrow, (32..34).into()),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_division() {
+    // `/` after an identifier, a literal, or a closing `)`/`]` is division.
+    let input = "a / 2 / (b) / [c]";
+    let result = lexer().parse(input).into_result().unwrap();
+    assert_eq!(
+        result,
+        vec![
+            (Token::Ident("a".to_string()), (0..1).into()),
+            (Token::Slash, (2..3).into()),
+            (Token::Int(2), (4..5).into()),
             (Token::Slash, (6..7).into()),
-            (Token::Percent, (8..9).into()),
-            (Token::Eq, (10..12).into()),
-            (Token::Ne, (13..15).into()),
-            (Token::Lt, (16..17).into()),
-            (Token::Le, (18..20).into()),
-            (Token::Gt, (21..22).into()),
-            (Token::Ge, (23..25).into()),
-            (Token::And, (26..28).into()),
-            (Token::Or, (29..31).into()),
-            (Token::Not, (32..33).into()),
+            (Token::LParen, (8..9).into()),
+            (Token::Ident("b".to_string()), (9..10).into()),
+            (Token::RParen, (10..11).into()),
+            (Token::Slash, (12..13).into()),
+            (Token::LBracket, (14..15).into()),
+            (Token::Ident("c".to_string()), (15..16).into()),
+            (Token::RBracket, (16..17).into()),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_filter_literal() {
+    // `/` after `{` (here, the automation's empty pattern) isn't division,
+    // so it opens a filter literal that closes on the next `/`.
+    let input = "{} /a.on/ {}";
+    let result = lexer().parse(input).into_result().unwrap();
+    assert_eq!(
+        result,
+        vec![
+            (Token::LBrace, (0..1).into()),
+            (Token::RBrace, (1..2).into()),
+            (Token::FilterStart, (3..4).into()),
+            (Token::Ident("a".to_string()), (4..5).into()),
+            (Token::Dot, (5..6).into()),
+            (Token::Ident("on".to_string()), (6..8).into()),
+            (Token::FilterEnd, (8..9).into()),
+            (Token::LBrace, (10..11).into()),
+            (Token::RBrace, (11..12).into()),
         ]
     );
 }
 
+#[test]
+fn test_lex_filter_literal_with_escaped_division() {
+    // Inside a filter, `\/` always lexes to a literal `Slash` rather than
+    // closing the filter, so a division can appear in the filter's
+    // expression without being misread as `FilterEnd`.
+    let input = "/a.value \\/ 2 > 1/";
+    let result = lexer().parse(input).into_result().unwrap();
+    assert_eq!(
+        result,
+        vec![
+            (Token::FilterStart, (0..1).into()),
+            (Token::Ident("a".to_string()), (1..2).into()),
+            (Token::Dot, (2..3).into()),
+            (Token::Ident("value".to_string()), (3..8).into()),
+            (Token::Slash, (9..11).into()),
+            (Token::Int(2), (12..13).into()),
+            (Token::Gt, (14..15).into()),
+            (Token::Int(1), (16..17).into()),
+            (Token::FilterEnd, (17..18).into()),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_unterminated_filter_literal() {
+    let input = "(a.on, /a.on";
+    assert!(lexer().parse(input).into_result().is_err());
+}
+
 #[test]
 fn test_lex_unit_literals() {
     let input = "5min 2.5h 90deg 20c";
@@ -78,6 +78,74 @@ fn lower_binop(op: ast::BinOp) -> HirBinOp {
     }
 }
 
+/// Every variable name reassigned by a `CompoundAssign` anywhere within
+/// `stmts`, including inside nested `if`/`match`/block bodies, in
+/// first-occurrence order with duplicates removed. `lower_if` and
+/// `lower_for` use this to know which outer variables need a merge slot at
+/// their join point - without it, a write visible in only one `if` arm, or
+/// made partway through a loop body, would be silently lost once control
+/// flow rejoins.
+fn reassigned_names(stmts: &[TypedStmt]) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_reassigned_names(stmts, &mut names);
+    names
+}
+
+fn collect_reassigned_names(stmts: &[TypedStmt], names: &mut Vec<String>) {
+    for stmt in stmts {
+        match stmt {
+            TypedStmt::CompoundAssign { name, value, .. } => {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+                collect_reassigned_names_in_expr(value, names);
+            }
+            TypedStmt::Let { value, .. } | TypedStmt::LetMut { value, .. } => {
+                collect_reassigned_names_in_expr(value, names);
+            }
+            TypedStmt::Expr(expr) | TypedStmt::Return(expr, _) => {
+                collect_reassigned_names_in_expr(expr, names);
+            }
+            TypedStmt::For { iter, body, .. } => {
+                collect_reassigned_names_in_expr(iter, names);
+                collect_reassigned_names(body, names);
+            }
+            TypedStmt::Push { value, .. } => {
+                collect_reassigned_names_in_expr(value, names);
+            }
+            TypedStmt::While { cond, body, .. } => {
+                collect_reassigned_names_in_expr(cond, names);
+                collect_reassigned_names(body, names);
+            }
+        }
+    }
+}
+
+/// The only places a statement list (and therefore a further
+/// `CompoundAssign`) can hide inside an expression: an `if`'s arms, a
+/// `match`'s arms, and a desugared comprehension's block body.
+fn collect_reassigned_names_in_expr(expr: &TypedExpr, names: &mut Vec<String>) {
+    match &expr.kind {
+        TypedExprKind::If {
+            then_block,
+            else_block,
+            ..
+        } => {
+            collect_reassigned_names(then_block, names);
+            if let Some(else_block) = else_block {
+                collect_reassigned_names(else_block, names);
+            }
+        }
+        TypedExprKind::Block { stmts, .. } => collect_reassigned_names(stmts, names),
+        TypedExprKind::Match { arms, .. } => {
+            for arm in arms {
+                collect_reassigned_names(&arm.body, names);
+            }
+        }
+        _ => {}
+    }
+}
+
 // ============================================================================
 // Public API
 // ============================================================================
@@ -111,7 +179,9 @@ fn lower_automation(auto: &TypedAutomation) -> HirAutomation {
         lowerer.set_terminator(Terminator::Branch {
             cond,
             then_block: body_entry,
+            then_args: vec![],
             else_block: exit_block,
+            else_args: vec![],
         });
 
         // Exit block: return default value for automation kind.
@@ -146,25 +216,49 @@ fn lower_automation(auto: &TypedAutomation) -> HirAutomation {
 // Lowerer
 // ============================================================================
 
+/// The blocks a `break`/`continue` inside a loop body would jump to.
+/// `lower_for` pushes one of these before lowering its body and pops it
+/// after, so nested loops unwind correctly.
+struct LoopFrame {
+    continue_target: BlockId,
+    break_target: BlockId,
+    /// The block parameter of `break_target` that a `break <expr>` jumps its
+    /// value into, the same mechanism [`Lowerer::lower_if`]'s `bb_merge`
+    /// uses for its then/else result. `None` for `for`/`while` - their
+    /// `break_target` takes no arguments, since falling off the end of the
+    /// loop reaches it too (via `IterNext`'s `exit` edge or the header's
+    /// `Branch`, neither of which carries a value) and every edge into a
+    /// block must agree on its parameter count. Only [`Lowerer::lower_loop`]
+    /// sets this, since a bare `loop { ... }` exits *only* through `break`.
+    result: Option<Tmp>,
+}
+
 struct Lowerer {
     blocks: Vec<BasicBlock>,
     current_block: BlockId,
     tmp_counter: usize,
     scopes: Vec<HashMap<String, Tmp>>,
+    /// The stack of loops currently being lowered, innermost last. Kept so
+    /// that a `break`/`continue` statement nested arbitrarily deep in a loop
+    /// body can find its target without threading it through every
+    /// intermediate call. Always empty outside of `lower_for`.
+    loop_stack: Vec<LoopFrame>,
 }
 
 impl Lowerer {
     fn new() -> Self {
         let entry = BasicBlock {
             id: BlockId(0),
+            params: Vec::new(),
             instructions: Vec::new(),
-            terminator: Terminator::Jump(BlockId(0)), // placeholder
+            terminator: Terminator::Jump(BlockId(0), Vec::new()), // placeholder
         };
         Self {
             blocks: vec![entry],
             current_block: BlockId(0),
             tmp_counter: 0,
             scopes: vec![HashMap::new()],
+            loop_stack: Vec::new(),
         }
     }
 
@@ -178,25 +272,34 @@ impl Lowerer {
         let id = BlockId(self.blocks.len());
         self.blocks.push(BasicBlock {
             id,
+            params: Vec::new(),
             instructions: Vec::new(),
-            terminator: Terminator::Jump(BlockId(0)), // placeholder
+            terminator: Terminator::Jump(BlockId(0), Vec::new()), // placeholder
         });
         id
     }
 
+    /// Declare `tmp` as a block parameter of `block`, supplied by every
+    /// `Jump`/`Branch` edge that targets it.
+    fn add_block_param(&mut self, block: BlockId, tmp: Tmp) {
+        self.blocks[block.0].params.push(tmp);
+    }
+
     fn emit(&mut self, op: Op, ty: Ty) -> Tmp {
-        let dst = self.fresh_tmp();
-        self.blocks[self.current_block.0]
-            .instructions
-            .push(Instruction { dst, op, ty });
-        dst
+        self.emit_spanned(op, ty, None)
     }
 
-    /// Emit an instruction with a pre-allocated destination (non-SSA merge).
-    fn emit_into(&mut self, dst: Tmp, op: Op, ty: Ty) {
-        self.blocks[self.current_block.0]
-            .instructions
-            .push(Instruction { dst, op, ty });
+    /// Emit an instruction, recording the source span it was lowered from so
+    /// later passes (e.g. constant folding) can point diagnostics at it.
+    fn emit_spanned(&mut self, op: Op, ty: Ty, span: Option<std::ops::Range<usize>>) -> Tmp {
+        let dst = self.fresh_tmp();
+        self.blocks[self.current_block.0].instructions.push(Instruction {
+            dst,
+            op,
+            ty,
+            span,
+        });
+        dst
     }
 
     fn set_terminator(&mut self, term: Terminator) {
@@ -317,17 +420,26 @@ impl Lowerer {
     // ========================================================================
 
     fn lower_expr(&mut self, expr: &TypedExpr) -> Tmp {
+        let span = {
+            let s = expr.origin.span();
+            Some(s.start..s.end)
+        };
         match &expr.kind {
-            TypedExprKind::Int(n) => self.emit(Op::ConstInt(*n), expr.ty.clone()),
-            TypedExprKind::Float(n) => self.emit(Op::ConstFloat(*n), expr.ty.clone()),
-            TypedExprKind::String(s) => self.emit(Op::ConstString(s.clone()), expr.ty.clone()),
-            TypedExprKind::Bool(b) => self.emit(Op::ConstBool(*b), expr.ty.clone()),
-            TypedExprKind::UnitLiteral { value, unit } => self.emit(
+            TypedExprKind::Int(n) => self.emit_spanned(Op::ConstInt(*n), expr.ty.clone(), span),
+            TypedExprKind::Float(n) => {
+                self.emit_spanned(Op::ConstFloat(*n), expr.ty.clone(), span)
+            }
+            TypedExprKind::String(s) => {
+                self.emit_spanned(Op::ConstString(s.clone()), expr.ty.clone(), span)
+            }
+            TypedExprKind::Bool(b) => self.emit_spanned(Op::ConstBool(*b), expr.ty.clone(), span),
+            TypedExprKind::UnitLiteral { value, unit } => self.emit_spanned(
                 Op::ConstUnit {
                     value: value.clone(),
                     unit: *unit,
                 },
                 expr.ty.clone(),
+                span,
             ),
 
             TypedExprKind::Ident(name) => self.lookup(name),
@@ -335,13 +447,14 @@ impl Lowerer {
             TypedExprKind::Path(segments) => {
                 // Standalone enum variant reference (not called).
                 if segments.len() == 2 {
-                    self.emit(
+                    self.emit_spanned(
                         Op::Variant {
                             enum_name: segments[0].clone(),
                             variant: segments[1].clone(),
                             args: vec![],
                         },
                         expr.ty.clone(),
+                        span,
                     )
                 } else {
                     self.emit(Op::Unit, Ty::Error)
@@ -354,13 +467,14 @@ impl Lowerer {
                 _ => {
                     let left_tmp = self.lower_expr(left);
                     let right_tmp = self.lower_expr(right);
-                    self.emit(
+                    self.emit_spanned(
                         Op::BinOp {
                             op: lower_binop(*op),
                             left: left_tmp,
                             right: right_tmp,
                         },
                         expr.ty.clone(),
+                        span,
                     )
                 }
             },
@@ -373,28 +487,30 @@ impl Lowerer {
                     ast::UnaryOp::Deref => Op::Deref(tmp),
                     ast::UnaryOp::Await => Op::Await(tmp),
                 };
-                self.emit(hir_op, expr.ty.clone())
+                self.emit_spanned(hir_op, expr.ty.clone(), span)
             }
 
             TypedExprKind::Field { expr: inner, field } => {
                 let base = self.lower_expr(inner);
-                self.emit(
+                self.emit_spanned(
                     Op::Field {
                         base,
                         field: field.clone(),
                     },
                     expr.ty.clone(),
+                    span,
                 )
             }
 
             TypedExprKind::OptionalField { expr: inner, field } => {
                 let base = self.lower_expr(inner);
-                self.emit(
+                self.emit_spanned(
                     Op::OptionalField {
                         base,
                         field: field.clone(),
                     },
                     expr.ty.clone(),
+                    span,
                 )
             }
 
@@ -408,10 +524,10 @@ impl Lowerer {
 
             TypedExprKind::List(items) => {
                 if items.is_empty() {
-                    self.emit(Op::EmptyList, expr.ty.clone())
+                    self.emit_spanned(Op::EmptyList, expr.ty.clone(), span)
                 } else {
                     let tmps: Vec<Tmp> = items.iter().map(|item| self.lower_expr(item)).collect();
-                    self.emit(Op::List(tmps), expr.ty.clone())
+                    self.emit_spanned(Op::List(tmps), expr.ty.clone(), span)
                 }
             }
 
@@ -428,17 +544,145 @@ impl Lowerer {
             }
 
             TypedExprKind::MutableList => self.emit(Op::EmptyList, expr.ty.clone()),
+
+            TypedExprKind::Match { scrutinee, arms } => self.lower_match(scrutinee, arms),
+
+            // HIR's `Op` has no closure representation, so a lambda can
+            // only ever reach here if it escapes the builtin call site
+            // (`filter`/`map`/`fold` in `check::resolve_builtin_call`) that
+            // was supposed to consume it directly - e.g. `let f = |x| x;`.
+            // `eval::eval_builtin` doesn't model closures either (see its
+            // doc comment), so there's nothing useful to lower to yet.
+            TypedExprKind::Lambda { .. } => {
+                panic!("lambda expressions are not yet lowered to HIR")
+            }
+
+            // `Op` has no tuple representation yet either - tuples are
+            // currently only a checker-level convenience for typing
+            // multi-value returns/destructuring, not a runtime value.
+            TypedExprKind::Tuple(_) => {
+                panic!("tuple expressions are not yet lowered to HIR")
+            }
         }
     }
 
+    // ========================================================================
+    // Match lowering
+    // ========================================================================
+
+    /// Lower a `match` expression as a decision tree: the scrutinee's
+    /// discriminant is computed once via `Op::Discriminant`, then each
+    /// variant arm in turn tests it for equality against its own variant
+    /// name, branching to its own body block or falling through to the next
+    /// test. A wildcard arm (always last, per `check_match`) runs
+    /// unconditionally once reached; if there isn't one, the final
+    /// fallthrough block is a `Terminator::Unreachable` rather than a dummy
+    /// value, so later passes can tell a non-exhaustive match's dead path
+    /// from a real one. Every arm's result joins at a single merge block via
+    /// a block parameter, the same pattern [`Self::lower_if`] uses for its
+    /// then/else join.
+    fn lower_match(&mut self, scrutinee: &TypedExpr, arms: &[TypedMatchArm]) -> Tmp {
+        let scrutinee_tmp = self.lower_expr(scrutinee);
+        let discriminant = self.emit(Op::Discriminant(scrutinee_tmp), Ty::String);
+        let bb_merge = self.fresh_block();
+
+        let mut default = None;
+        for arm in arms {
+            match &arm.pattern.node {
+                ast::MatchPattern::Variant { variant, bindings, .. } => {
+                    let bb_body = self.fresh_block();
+                    let bb_next = self.fresh_block();
+
+                    let expected = self.emit(Op::ConstString(variant.clone()), Ty::String);
+                    let test = self.emit(
+                        Op::BinOp {
+                            op: HirBinOp::Eq,
+                            left: discriminant,
+                            right: expected,
+                        },
+                        Ty::Bool,
+                    );
+                    self.set_terminator(Terminator::Branch {
+                        cond: test,
+                        then_block: bb_body,
+                        then_args: vec![],
+                        else_block: bb_next,
+                        else_args: vec![],
+                    });
+
+                    self.switch_to(bb_body);
+                    self.lower_match_arm(scrutinee_tmp, bindings, &arm.binding_types, &arm.body, bb_merge);
+
+                    self.switch_to(bb_next);
+                }
+                ast::MatchPattern::Wildcard => {
+                    // Always the last arm per `check_match`'s exhaustiveness
+                    // checking - any arm after it is already a reported
+                    // error, so there's nothing further to lower.
+                    default = Some(arm);
+                    break;
+                }
+            }
+        }
+
+        match default {
+            Some(arm) => {
+                self.lower_match_arm(scrutinee_tmp, &[], &arm.binding_types, &arm.body, bb_merge);
+            }
+            None => {
+                // Non-exhaustive match with no wildcard arm: the checker
+                // already reported an error, but the chain still needs a
+                // terminator for the final fallthrough block to stay
+                // well-formed.
+                self.set_terminator(Terminator::Unreachable);
+            }
+        }
+
+        self.switch_to(bb_merge);
+        let result = self.fresh_tmp();
+        self.add_block_param(bb_merge, result);
+        result
+    }
+
+    /// Lower one `match` arm's body into the current block: bind its
+    /// positional bindings (if any) via `Op::VariantField`, lower the body,
+    /// and jump to `bb_merge` with the result.
+    fn lower_match_arm(
+        &mut self,
+        scrutinee_tmp: Tmp,
+        bindings: &[ast::Spanned<ast::BindingPattern>],
+        binding_types: &[Ty],
+        body: &[TypedStmt],
+        bb_merge: BlockId,
+    ) {
+        self.push_scope();
+        for (index, binding) in bindings.iter().enumerate() {
+            if let ast::BindingPattern::Ident(name) = &binding.node {
+                let field_ty = binding_types.get(index).cloned().unwrap_or(Ty::Error);
+                let tmp = self.emit(
+                    Op::VariantField {
+                        base: scrutinee_tmp,
+                        index,
+                    },
+                    field_ty,
+                );
+                self.bind(name, tmp);
+            }
+        }
+        let arm_result = self.lower_stmts_result(body);
+        self.pop_scope();
+        self.set_terminator(Terminator::Jump(bb_merge, vec![arm_result]));
+    }
+
     // ========================================================================
     // Short-circuit lowering
     // ========================================================================
 
     /// Lower `a && b` to branches: eval a, if true eval b, else short-circuit.
+    /// The result is a block parameter of the merge block, supplied by
+    /// whichever edge is taken — not a `Tmp` pre-allocated and copied into
+    /// from both arms.
     fn lower_and(&mut self, left: &TypedExpr, right: &TypedExpr) -> Tmp {
-        let result = self.fresh_tmp();
-
         let left_tmp = self.lower_expr(left);
         let bb_rhs = self.fresh_block();
         let bb_false = self.fresh_block();
@@ -447,28 +691,30 @@ impl Lowerer {
         self.set_terminator(Terminator::Branch {
             cond: left_tmp,
             then_block: bb_rhs,
+            then_args: vec![],
             else_block: bb_false,
+            else_args: vec![],
         });
 
         // False branch: short-circuit.
         self.switch_to(bb_false);
-        self.emit_into(result, Op::ConstBool(false), Ty::Bool);
-        self.set_terminator(Terminator::Jump(bb_merge));
+        let false_tmp = self.emit(Op::ConstBool(false), Ty::Bool);
+        self.set_terminator(Terminator::Jump(bb_merge, vec![false_tmp]));
 
         // RHS branch: evaluate right operand.
         self.switch_to(bb_rhs);
         let right_tmp = self.lower_expr(right);
-        self.emit_into(result, Op::Copy(right_tmp), Ty::Bool);
-        self.set_terminator(Terminator::Jump(bb_merge));
+        self.set_terminator(Terminator::Jump(bb_merge, vec![right_tmp]));
 
         self.switch_to(bb_merge);
+        let result = self.fresh_tmp();
+        self.add_block_param(bb_merge, result);
         result
     }
 
     /// Lower `a || b` to branches: eval a, if true short-circuit, else eval b.
+    /// See [`Self::lower_and`] for how the merge result is threaded.
     fn lower_or(&mut self, left: &TypedExpr, right: &TypedExpr) -> Tmp {
-        let result = self.fresh_tmp();
-
         let left_tmp = self.lower_expr(left);
         let bb_true = self.fresh_block();
         let bb_rhs = self.fresh_block();
@@ -477,21 +723,24 @@ impl Lowerer {
         self.set_terminator(Terminator::Branch {
             cond: left_tmp,
             then_block: bb_true,
+            then_args: vec![],
             else_block: bb_rhs,
+            else_args: vec![],
         });
 
         // True branch: short-circuit.
         self.switch_to(bb_true);
-        self.emit_into(result, Op::ConstBool(true), Ty::Bool);
-        self.set_terminator(Terminator::Jump(bb_merge));
+        let true_tmp = self.emit(Op::ConstBool(true), Ty::Bool);
+        self.set_terminator(Terminator::Jump(bb_merge, vec![true_tmp]));
 
         // RHS branch: evaluate right operand.
         self.switch_to(bb_rhs);
         let right_tmp = self.lower_expr(right);
-        self.emit_into(result, Op::Copy(right_tmp), Ty::Bool);
-        self.set_terminator(Terminator::Jump(bb_merge));
+        self.set_terminator(Terminator::Jump(bb_merge, vec![right_tmp]));
 
         self.switch_to(bb_merge);
+        let result = self.fresh_tmp();
+        self.add_block_param(bb_merge, result);
         result
     }
 
@@ -499,16 +748,29 @@ impl Lowerer {
     // If/else lowering
     // ========================================================================
 
+    /// Lower `if`/`else` to branches. The result is a block parameter of the
+    /// merge block, supplied by whichever edge is taken.
     fn lower_if(
         &mut self,
         cond: &TypedExpr,
         then_stmts: &[TypedStmt],
         else_stmts: Option<&[TypedStmt]>,
-        result_ty: &Ty,
+        _result_ty: &Ty,
     ) -> Tmp {
-        let result = self.fresh_tmp();
         let cond_tmp = self.lower_expr(cond);
 
+        // Variables reassigned by either arm need a merge slot alongside the
+        // if's own value, so a write visible in only one arm (or nested
+        // deeper, inside that arm's own `if`/`match`) is still visible after
+        // the merge. See `reassigned_names`.
+        let mut names = reassigned_names(then_stmts);
+        for name in reassigned_names(else_stmts.unwrap_or(&[])) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        let pre_if_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
+
         let bb_then = self.fresh_block();
         let bb_else = self.fresh_block();
         let bb_merge = self.fresh_block();
@@ -516,30 +778,44 @@ impl Lowerer {
         self.set_terminator(Terminator::Branch {
             cond: cond_tmp,
             then_block: bb_then,
+            then_args: vec![],
             else_block: bb_else,
+            else_args: vec![],
         });
 
         // Then branch.
         self.switch_to(bb_then);
         self.push_scope();
         let then_result = self.lower_stmts_result(then_stmts);
+        let then_name_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
         self.pop_scope();
-        self.emit_into(result, Op::Copy(then_result), result_ty.clone());
-        self.set_terminator(Terminator::Jump(bb_merge));
+        let mut then_args = vec![then_result];
+        then_args.extend(then_name_tmps);
+        self.set_terminator(Terminator::Jump(bb_merge, then_args));
 
         // Else branch.
         self.switch_to(bb_else);
-        if let Some(stmts) = else_stmts {
+        let (else_result, else_name_tmps) = if let Some(stmts) = else_stmts {
             self.push_scope();
-            let else_result = self.lower_stmts_result(stmts);
+            let r = self.lower_stmts_result(stmts);
+            let tmps = names.iter().map(|n| self.lookup(n)).collect();
             self.pop_scope();
-            self.emit_into(result, Op::Copy(else_result), result_ty.clone());
+            (r, tmps)
         } else {
-            self.emit_into(result, Op::Unit, Ty::Unit);
-        }
-        self.set_terminator(Terminator::Jump(bb_merge));
+            (self.emit(Op::Unit, Ty::Unit), pre_if_tmps)
+        };
+        let mut else_args = vec![else_result];
+        else_args.extend(else_name_tmps);
+        self.set_terminator(Terminator::Jump(bb_merge, else_args));
 
         self.switch_to(bb_merge);
+        let result = self.fresh_tmp();
+        self.add_block_param(bb_merge, result);
+        for name in &names {
+            let merge_tmp = self.fresh_tmp();
+            self.add_block_param(bb_merge, merge_tmp);
+            self.bind(name, merge_tmp);
+        }
         result
     }
 
@@ -596,14 +872,33 @@ impl Lowerer {
         let iter_tmp = self.lower_expr(iter);
         let iter_state = self.emit(Op::IterInit(iter_tmp), iter.ty.clone());
 
+        // Variables reassigned anywhere in the body (including inside a
+        // nested `if`/`match`) need to be threaded through the loop header
+        // as block params - the same merge-on-join approach `lower_if` uses
+        // for its then/else value - otherwise each iteration's update is
+        // lost the moment the header block reruns. See `reassigned_names`.
+        let names = reassigned_names(body);
+        let initial_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
+
         let bb_header = self.fresh_block();
         let bb_body = self.fresh_block();
         let bb_exit = self.fresh_block();
 
-        self.set_terminator(Terminator::Jump(bb_header));
+        self.set_terminator(Terminator::Jump(bb_header, initial_tmps));
 
-        // Header: advance iterator or exit.
+        // Header: advance iterator or exit. Its block params hold this
+        // iteration's value of each reassigned variable, bound here (rather
+        // than inside the body's own scope) so that once the iterator is
+        // exhausted, `bb_exit` - which `bb_header` dominates - can still see
+        // the final values through the same, already-bound names.
         self.switch_to(bb_header);
+        let header_tmps: Vec<Tmp> = names.iter().map(|_| self.fresh_tmp()).collect();
+        for tmp in &header_tmps {
+            self.add_block_param(bb_header, *tmp);
+        }
+        for (name, tmp) in names.iter().zip(&header_tmps) {
+            self.bind(name, *tmp);
+        }
         let value_tmp = self.fresh_tmp();
         self.set_terminator(Terminator::IterNext {
             iter: iter_state,
@@ -616,14 +911,160 @@ impl Lowerer {
         self.switch_to(bb_body);
         self.push_scope();
         self.bind(var, value_tmp);
+        self.loop_stack.push(LoopFrame {
+            continue_target: bb_header,
+            break_target: bb_exit,
+            result: None,
+        });
+        self.lower_stmts(body);
+        self.loop_stack.pop();
+        let back_edge_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
+        self.pop_scope();
+        self.set_terminator(Terminator::Jump(bb_header, back_edge_tmps));
+
+        // Continue in exit block.
+        self.switch_to(bb_exit);
+    }
+
+    // ========================================================================
+    // While loop lowering
+    // ========================================================================
+
+    /// Unlike `lower_for`'s `IterNext`, there's no statically known exit
+    /// edge here - `cond` is re-evaluated in `bb_header` on every pass
+    /// through the loop, so it has to be lowered *inside* that block rather
+    /// than once before it, and the header's terminator is an ordinary
+    /// `Branch` rather than an iterator advance.
+    fn lower_while(&mut self, cond: &TypedExpr, body: &[TypedStmt]) {
+        let names = reassigned_names(body);
+        let initial_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
+
+        let bb_header = self.fresh_block();
+        let bb_body = self.fresh_block();
+        let bb_exit = self.fresh_block();
+
+        self.set_terminator(Terminator::Jump(bb_header, initial_tmps));
+
+        // Header: re-evaluate `cond` against this iteration's values of the
+        // reassigned variables, then branch into the body or out of the
+        // loop. Block params bound here the same way `lower_for` binds its
+        // header params, so `bb_exit` sees the final values too.
+        self.switch_to(bb_header);
+        let header_tmps: Vec<Tmp> = names.iter().map(|_| self.fresh_tmp()).collect();
+        for tmp in &header_tmps {
+            self.add_block_param(bb_header, *tmp);
+        }
+        for (name, tmp) in names.iter().zip(&header_tmps) {
+            self.bind(name, *tmp);
+        }
+        let cond_tmp = self.lower_expr(cond);
+        self.set_terminator(Terminator::Branch {
+            cond: cond_tmp,
+            then_block: bb_body,
+            then_args: vec![],
+            else_block: bb_exit,
+            else_args: vec![],
+        });
+
+        // Body.
+        self.switch_to(bb_body);
+        self.push_scope();
+        self.loop_stack.push(LoopFrame {
+            continue_target: bb_header,
+            break_target: bb_exit,
+            result: None,
+        });
         self.lower_stmts(body);
+        self.loop_stack.pop();
+        let back_edge_tmps: Vec<Tmp> = names.iter().map(|n| self.lookup(n)).collect();
         self.pop_scope();
-        self.set_terminator(Terminator::Jump(bb_header));
+        self.set_terminator(Terminator::Jump(bb_header, back_edge_tmps));
 
         // Continue in exit block.
         self.switch_to(bb_exit);
     }
 
+    // ========================================================================
+    // Bare loop lowering (`loop { ... break <expr>; }`)
+    // ========================================================================
+
+    /// Lower a `loop { ... }` block - the one loop form usable as an
+    /// expression, since the only way out is `break <expr>` (a bare `break`
+    /// breaks with `Op::Unit`). Unlike `lower_for`/`lower_while` there's no
+    /// condition to re-check and no "falls off the end" edge into the join
+    /// block: every edge reaching it comes from a `break`, carrying its
+    /// value as a block parameter exactly like `lower_if`'s `bb_merge` does
+    /// for its then/else result.
+    ///
+    /// `lower_stmts_result` needs no change to return this as a trailing
+    /// expression's value: it already does `self.lower_expr(expr)` for any
+    /// `TypedStmt::Expr`, so once `TypedExprKind` gains a `Loop` arm that
+    /// calls this, the result flows through unchanged.
+    ///
+    /// NOTE: there is no surface `loop`/`break` syntax yet to drive this
+    /// from - `TypedStmt`/`TypedExprKind` have no `Loop`/`Break` variants
+    /// (the lexer, parser, desugarer and checker don't know about either
+    /// keyword). This lowers the typed-AST shape the request describes;
+    /// wiring an actual `loop`/`break` keyword through the rest of the
+    /// pipeline is follow-up work, same as the `loop_stack` plumbing above
+    /// that's already in place for `for`/`while` ahead of their own
+    /// `break`/`continue` support.
+    #[allow(dead_code)]
+    fn lower_loop(&mut self, body: &[TypedStmt]) -> Tmp {
+        let bb_header = self.fresh_block();
+        let bb_join = self.fresh_block();
+
+        let result = self.fresh_tmp();
+        self.add_block_param(bb_join, result);
+
+        self.set_terminator(Terminator::Jump(bb_header, Vec::new()));
+
+        self.switch_to(bb_header);
+        self.push_scope();
+        self.loop_stack.push(LoopFrame {
+            continue_target: bb_header,
+            break_target: bb_join,
+            result: Some(result),
+        });
+        self.lower_stmts(body);
+        self.loop_stack.pop();
+        self.pop_scope();
+        self.set_terminator(Terminator::Jump(bb_header, Vec::new()));
+
+        self.switch_to(bb_join);
+        result
+    }
+
+    /// Lower `break <expr>` (or a bare `break`, treated as `break ()`),
+    /// resolving to the innermost loop frame - the same "find the nearest
+    /// enclosing loop" rule `continue` would use. Handled exactly like
+    /// `TypedStmt::Return`: set the terminator, then switch to a fresh block
+    /// for the unreachable tail so any statements lowered after the `break`
+    /// land somewhere well-formed instead of appending to an already-closed
+    /// block.
+    #[allow(dead_code)]
+    fn lower_break(&mut self, value: Option<&TypedExpr>) {
+        let frame = self
+            .loop_stack
+            .last()
+            .expect("break outside of a loop (should be caught by the type checker)");
+        let break_target = frame.break_target;
+        let args = match frame.result {
+            Some(_) => {
+                let value_tmp = match value {
+                    Some(expr) => self.lower_expr(expr),
+                    None => self.emit(Op::Unit, Ty::Unit),
+                };
+                vec![value_tmp]
+            }
+            None => Vec::new(),
+        };
+
+        self.set_terminator(Terminator::Jump(break_target, args));
+        let dead = self.fresh_block();
+        self.switch_to(dead);
+    }
+
     // ========================================================================
     // Struct literal lowering
     // ========================================================================
@@ -690,6 +1131,15 @@ impl Lowerer {
             TypedStmt::For {
                 var, iter, body, ..
             } => {
+                // `TypedStmt::For` bodies are only ever produced by
+                // desugaring a comprehension (`ListComp`/`DictComp`/
+                // `SetComp`), which never contains a surface-level `break`
+                // or `continue` - the language has no statement form that
+                // could lower to one yet. `loop_stack` above is therefore
+                // unused for now; it exists so that once `break`/`continue`
+                // are added to the surface grammar, this lowering only needs
+                // new `TypedStmt` arms rather than new loop-tracking
+                // plumbing.
                 self.lower_for(var, iter, body);
             }
             TypedStmt::Push { list, value, .. } => {
@@ -703,6 +1153,44 @@ impl Lowerer {
                     Ty::Unit,
                 );
             }
+            TypedStmt::CompoundAssign {
+                name,
+                op,
+                value,
+                result_ty,
+                ..
+            } => {
+                let val_tmp = self.lower_expr(value);
+                if *op == ast::BinOp::Add && matches!(result_ty, Ty::List(_)) {
+                    let list_tmp = self.lookup(name);
+                    self.emit(
+                        Op::ListExtend {
+                            list: list_tmp,
+                            value: val_tmp,
+                        },
+                        Ty::Unit,
+                    );
+                } else {
+                    let current_tmp = self.lookup(name);
+                    let new_tmp = self.emit(
+                        Op::BinOp {
+                            op: lower_binop(*op),
+                            left: current_tmp,
+                            right: val_tmp,
+                        },
+                        result_ty.clone(),
+                    );
+                    self.bind(name, new_tmp);
+                }
+            }
+            TypedStmt::While { cond, body, .. } => {
+                // Same `loop_stack` caveat as `TypedStmt::For` above:
+                // `TypedStmt::While` is only ever produced by desugaring a
+                // range-based comprehension clause, which has no surface
+                // `break`/`continue`, so the loop frame pushed by
+                // `lower_while` goes unused for now.
+                self.lower_while(cond, body);
+            }
         }
     }
 
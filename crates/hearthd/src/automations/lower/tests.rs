@@ -23,7 +23,7 @@ fn test_lower_empty_list_observer() {
         %0 = const_bool true [Bool]
         branch %0 -> bb1, bb2
       bb1:
-        %2 = empty_list [[<error>]]
+        %2 = empty_list [[Event]]
         return %2
       bb2:
         %1 = empty_list [[Event]]
@@ -41,7 +41,7 @@ fn test_lower_let_binding() {
         branch %0 -> bb1, bb2
       bb1:
         %2 = const_int 42 [Int]
-        %3 = empty_list [[<error>]]
+        %3 = empty_list [[Event]]
         return %3
       bb2:
         %1 = empty_list [[Event]]
@@ -63,7 +63,7 @@ fn test_lower_binary_arithmetic() {
         %4 = const_int 3 [Int]
         %5 = mul %3, %4 [Int]
         %6 = add %2, %5 [Int]
-        %7 = empty_list [[<error>]]
+        %7 = empty_list [[Event]]
         return %7
       bb2:
         %1 = empty_list [[Event]]
@@ -84,21 +84,19 @@ fn test_lower_if_else() {
         %0 = const_bool true [Bool]
         branch %0 -> bb1, bb2
       bb1:
-        %3 = const_bool true [Bool]
-        branch %3 -> bb3, bb4
+        %2 = const_bool true [Bool]
+        branch %2 -> bb3, bb4
       bb2:
         %1 = empty_list [[Event]]
         return %1
       bb3:
-        %4 = empty_list [[<error>]]
-        %2 = copy %4 [[<error>]]
-        jump -> bb5
+        %3 = empty_list [[Event]]
+        jump -> bb5(%3)
       bb4:
-        %5 = empty_list [[<error>]]
-        %2 = copy %5 [[<error>]]
-        jump -> bb5
-      bb5:
-        return %2
+        %4 = empty_list [[Event]]
+        jump -> bb5(%4)
+      bb5(%5):
+        return %5
     ");
 }
 
@@ -111,21 +109,52 @@ fn test_lower_if_no_else() {
         %0 = const_bool true [Bool]
         branch %0 -> bb1, bb2
       bb1:
+        %2 = const_bool true [Bool]
+        branch %2 -> bb3, bb4
+      bb2:
+        %1 = empty_list [[Event]]
+        return %1
+      bb3:
+        %3 = const_int 42 [Int]
+        jump -> bb5(%3)
+      bb4:
+        %4 = unit [()]
+        jump -> bb5(%4)
+      bb5(%5):
+        %6 = empty_list [[Event]]
+        return %6
+    ");
+}
+
+#[test]
+fn test_lower_if_reassigns_outer_variable() {
+    // `total` is only reassigned in the `then` arm, so the merge block
+    // needs an extra param for it alongside the if's own (unit) value -
+    // without one, the trailing `total` would still read the pre-if %2.
+    let result =
+        lower_and_pretty("observer {} /true/ { let mut total = 0; if true { total += 1 }; total }");
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      bb0:
+        %0 = const_bool true [Bool]
+        branch %0 -> bb1, bb2
+      bb1:
+        %2 = const_int 0 [Int]
         %3 = const_bool true [Bool]
         branch %3 -> bb3, bb4
       bb2:
         %1 = empty_list [[Event]]
         return %1
       bb3:
-        %4 = const_int 42 [Int]
-        %2 = copy %4 [()]
-        jump -> bb5
+        %4 = const_int 1 [Int]
+        %5 = add %2, %4 [Int]
+        %6 = unit [()]
+        jump -> bb5(%6, %5)
       bb4:
-        %2 = unit [()]
-        jump -> bb5
-      bb5:
-        %5 = empty_list [[<error>]]
-        return %5
+        %7 = unit [()]
+        jump -> bb5(%7, %2)
+      bb5(%8, %9):
+        return %9
     ");
 }
 
@@ -135,23 +164,22 @@ fn test_lower_short_circuit_and() {
     insta::assert_snapshot!(result, @"
     Automation: observer
       bb0:
-        %1 = const_bool true [Bool]
-        branch %1 -> bb3, bb4
+        %0 = const_bool true [Bool]
+        branch %0 -> bb3, bb4
       bb1:
-        %4 = empty_list [[<error>]]
-        return %4
+        %5 = empty_list [[Event]]
+        return %5
       bb2:
-        %3 = empty_list [[Event]]
-        return %3
+        %4 = empty_list [[Event]]
+        return %4
       bb3:
         %2 = const_bool false [Bool]
-        %0 = copy %2 [Bool]
-        jump -> bb5
+        jump -> bb5(%2)
       bb4:
-        %0 = const_bool false [Bool]
-        jump -> bb5
-      bb5:
-        branch %0 -> bb1, bb2
+        %1 = const_bool false [Bool]
+        jump -> bb5(%1)
+      bb5(%3):
+        branch %3 -> bb1, bb2
     ");
 }
 
@@ -161,23 +189,22 @@ fn test_lower_short_circuit_or() {
     insta::assert_snapshot!(result, @"
     Automation: observer
       bb0:
-        %1 = const_bool true [Bool]
-        branch %1 -> bb3, bb4
+        %0 = const_bool true [Bool]
+        branch %0 -> bb3, bb4
       bb1:
-        %4 = empty_list [[<error>]]
-        return %4
+        %5 = empty_list [[Event]]
+        return %5
       bb2:
-        %3 = empty_list [[Event]]
-        return %3
+        %4 = empty_list [[Event]]
+        return %4
       bb3:
-        %0 = const_bool true [Bool]
-        jump -> bb5
+        %1 = const_bool true [Bool]
+        jump -> bb5(%1)
       bb4:
         %2 = const_bool false [Bool]
-        %0 = copy %2 [Bool]
-        jump -> bb5
-      bb5:
-        branch %0 -> bb1, bb2
+        jump -> bb5(%2)
+      bb5(%3):
+        branch %3 -> bb1, bb2
     ");
 }
 
@@ -221,6 +248,35 @@ fn test_lower_list_comprehension() {
     ");
 }
 
+#[test]
+fn test_lower_range_comprehension() {
+    let result = lower_and_pretty("observer {} /true/ { [x for x in 0..3] }");
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      bb0:
+        %0 = const_bool true [Bool]
+        branch %0 -> bb1, bb2
+      bb1:
+        %2 = empty_list [[Int]]
+        %3 = const_int 3 [Int]
+        %4 = const_int 0 [Int]
+        jump -> bb3(%4)
+      bb2:
+        %1 = empty_list [[Event]]
+        return %1
+      bb3(%5):
+        %6 = lt %5, %3 [Bool]
+        branch %6 -> bb4, bb5
+      bb4:
+        %7 = list_push %2, %5 [()]
+        %8 = const_int 1 [Int]
+        %9 = add %5, %8 [Int]
+        jump -> bb3(%9)
+      bb5:
+        return %2
+    ");
+}
+
 // =============================================================================
 // Function calls
 // =============================================================================
@@ -238,7 +294,7 @@ fn test_lower_builtin_clamp() {
         %3 = const_int 0 [Int]
         %4 = const_int 255 [Int]
         %5 = call clamp(%2, %3, %4) [Int]
-        %6 = empty_list [[<error>]]
+        %6 = empty_list [[Event]]
         return %6
       bb2:
         %1 = empty_list [[Event]]
@@ -295,7 +351,7 @@ fn test_lower_subtraction() {
         %2 = const_int 10 [Int]
         %3 = const_int 3 [Int]
         %4 = sub %2, %3 [Int]
-        %5 = empty_list [[<error>]]
+        %5 = empty_list [[Event]]
         return %5
       bb2:
         %1 = empty_list [[Event]]
@@ -319,7 +375,7 @@ fn test_lower_division_and_modulo() {
         %6 = const_int 5 [Int]
         %7 = mod %5, %6 [Int]
         %8 = add %4, %7 [Int]
-        %9 = empty_list [[<error>]]
+        %9 = empty_list [[Event]]
         return %9
       bb2:
         %1 = empty_list [[Event]]
@@ -354,7 +410,7 @@ fn test_lower_comparison_operators() {
         %17 = const_int 1 [Int]
         %18 = const_int 1 [Int]
         %19 = eq %17, %18 [Bool]
-        %20 = empty_list [[<error>]]
+        %20 = empty_list [[Event]]
         return %20
       bb2:
         %1 = empty_list [[Event]]
@@ -377,7 +433,7 @@ fn test_lower_negation() {
       bb1:
         %2 = const_int 10 [Int]
         %3 = neg %2 [Int]
-        %4 = empty_list [[<error>]]
+        %4 = empty_list [[Event]]
         return %4
       bb2:
         %1 = empty_list [[Event]]
@@ -396,7 +452,7 @@ fn test_lower_not() {
       bb1:
         %2 = const_bool true [Bool]
         %3 = not %2 [Bool]
-        %4 = empty_list [[<error>]]
+        %4 = empty_list [[Event]]
         return %4
       bb2:
         %1 = empty_list [[Event]]
@@ -418,7 +474,7 @@ fn test_lower_string_literal() {
         branch %0 -> bb1, bb2
       bb1:
         %2 = const_string "hello" [String]
-        %3 = empty_list [[<error>]]
+        %3 = empty_list [[Event]]
         return %3
       bb2:
         %1 = empty_list [[Event]]
@@ -438,7 +494,7 @@ fn test_lower_float_literal() {
         %2 = const_float 1.5 [Float]
         %3 = const_float 2.5 [Float]
         %4 = add %2, %3 [Float]
-        %5 = empty_list [[<error>]]
+        %5 = empty_list [[Event]]
         return %5
       bb2:
         %1 = empty_list [[Event]]
@@ -459,7 +515,7 @@ fn test_lower_unit_literals() {
         %3 = const_unit 30min [Duration]
         %4 = const_unit 25c [Temperature]
         %5 = const_unit 90deg [Angle]
-        %6 = empty_list [[<error>]]
+        %6 = empty_list [[Event]]
         return %6
       bb2:
         %1 = empty_list [[Event]]
@@ -490,7 +546,7 @@ fn test_lower_field_access() {
         %2 = const_bool true [Bool]
         branch %2 -> bb1, bb2
       bb1:
-        %4 = empty_list [[<error>]]
+        %4 = empty_list [[Event]]
         return %4
       bb2:
         %3 = empty_list [[Event]]
@@ -515,7 +571,7 @@ fn test_lower_list_literal() {
         %3 = const_int 2 [Int]
         %4 = const_int 3 [Int]
         %5 = list [%2, %3, %4] [[Int]]
-        %6 = empty_list [[<error>]]
+        %6 = empty_list [[Event]]
         return %6
       bb2:
         %1 = empty_list [[Event]]
@@ -537,7 +593,7 @@ fn test_lower_variable_reference() {
         branch %0 -> bb1, bb2
       bb1:
         %2 = const_int 42 [Int]
-        %3 = empty_list [[<error>]]
+        %3 = empty_list [[Event]]
         return %3
       bb2:
         %1 = empty_list [[Event]]
@@ -558,32 +614,28 @@ fn test_lower_nested_if() {
         %0 = const_bool true [Bool]
         branch %0 -> bb1, bb2
       bb1:
-        %3 = const_bool true [Bool]
-        branch %3 -> bb3, bb4
+        %2 = const_bool true [Bool]
+        branch %2 -> bb3, bb4
       bb2:
         %1 = empty_list [[Event]]
         return %1
       bb3:
-        %5 = const_bool false [Bool]
-        branch %5 -> bb6, bb7
+        %3 = const_bool false [Bool]
+        branch %3 -> bb6, bb7
       bb4:
-        %8 = const_int 3 [Int]
-        %2 = copy %8 [Int]
-        jump -> bb5
-      bb5:
-        %9 = empty_list [[<error>]]
+        %7 = const_int 3 [Int]
+        jump -> bb5(%7)
+      bb5(%8):
+        %9 = empty_list [[Event]]
         return %9
       bb6:
-        %6 = const_int 1 [Int]
-        %4 = copy %6 [Int]
-        jump -> bb8
+        %4 = const_int 1 [Int]
+        jump -> bb8(%4)
       bb7:
-        %7 = const_int 2 [Int]
-        %4 = copy %7 [Int]
-        jump -> bb8
-      bb8:
-        %2 = copy %4 [Int]
-        jump -> bb5
+        %5 = const_int 2 [Int]
+        jump -> bb8(%5)
+      bb8(%6):
+        jump -> bb5(%6)
     ");
 }
 
@@ -625,32 +677,30 @@ fn test_lower_nested_short_circuit() {
     insta::assert_snapshot!(result, @"
     Automation: observer
       bb0:
-        %2 = const_bool true [Bool]
-        branch %2 -> bb3, bb4
+        %0 = const_bool true [Bool]
+        branch %0 -> bb3, bb4
       bb1:
-        %6 = empty_list [[<error>]]
-        return %6
+        %8 = empty_list [[Event]]
+        return %8
       bb2:
-        %5 = empty_list [[Event]]
-        return %5
+        %7 = empty_list [[Event]]
+        return %7
       bb3:
-        %3 = const_bool false [Bool]
-        %1 = copy %3 [Bool]
-        jump -> bb5
+        %2 = const_bool false [Bool]
+        jump -> bb5(%2)
       bb4:
         %1 = const_bool false [Bool]
-        jump -> bb5
-      bb5:
-        branch %1 -> bb6, bb7
+        jump -> bb5(%1)
+      bb5(%3):
+        branch %3 -> bb6, bb7
       bb6:
-        %0 = const_bool true [Bool]
-        jump -> bb8
-      bb7:
         %4 = const_bool true [Bool]
-        %0 = copy %4 [Bool]
-        jump -> bb8
-      bb8:
-        branch %0 -> bb1, bb2
+        jump -> bb8(%4)
+      bb7:
+        %5 = const_bool true [Bool]
+        jump -> bb8(%5)
+      bb8(%6):
+        branch %6 -> bb1, bb2
     ");
 }
 
@@ -667,7 +717,7 @@ fn test_lower_early_return() {
         %0 = const_bool true [Bool]
         branch %0 -> bb1, bb2
       bb1:
-        %2 = empty_list [[<error>]]
+        %2 = empty_list [[Event]]
         return %2
       bb2:
         %1 = empty_list [[Event]]
@@ -693,7 +743,7 @@ fn test_lower_multiple_lets_and_arithmetic() {
         %2 = const_int 1 [Int]
         %3 = const_int 2 [Int]
         %4 = add %2, %3 [Int]
-        %5 = empty_list [[<error>]]
+        %5 = empty_list [[Event]]
         return %5
       bb2:
         %1 = empty_list [[Event]]
@@ -733,20 +783,19 @@ fn test_lower_list_comprehension_with_filter() {
       bb3:
         iter_next %6 -> %7, bb4, bb5
       bb4:
-        %9 = const_bool true [Bool]
-        branch %9 -> bb6, bb7
+        %8 = const_bool true [Bool]
+        branch %8 -> bb6, bb7
       bb5:
         return %4
       bb6:
-        %10 = variant Event::LightStateChanged(%7) [Event]
-        %11 = list_push %4, %10 [()]
-        %12 = unit [()]
-        %8 = copy %12 [()]
-        jump -> bb8
+        %9 = variant Event::LightStateChanged(%7) [Event]
+        %10 = list_push %4, %9 [()]
+        %11 = unit [()]
+        jump -> bb8(%11)
       bb7:
-        %8 = unit [()]
-        jump -> bb8
-      bb8:
+        %12 = unit [()]
+        jump -> bb8(%12)
+      bb8(%13):
         jump -> bb3
     ");
 }
@@ -782,7 +831,7 @@ fn test_lower_nested_pattern() {
         %4 = const_bool true [Bool]
         branch %4 -> bb1, bb2
       bb1:
-        %6 = empty_list [[<error>]]
+        %6 = empty_list [[Event]]
         return %6
       bb2:
         %5 = empty_list [[Event]]
@@ -875,7 +924,7 @@ fn test_lower_no_filter() {
     insta::assert_snapshot!(result, @"
     Automation: observer
       bb0:
-        %0 = empty_list [[<error>]]
+        %0 = empty_list [[Event]]
         return %0
     ");
 }
@@ -904,30 +953,114 @@ fn test_lower_observer_if_else_with_events() {
         %3 = const_bool true [Bool]
         branch %3 -> bb1, bb2
       bb1:
-        %6 = const_bool true [Bool]
-        branch %6 -> bb3, bb4
+        %5 = const_bool true [Bool]
+        branch %5 -> bb3, bb4
       bb2:
         %4 = empty_list [[Event]]
         return %4
       bb3:
-        %7 = empty_list [[<error>]]
-        %8 = call keys(%2) [[String]]
-        %9 = iter_init %8 [[String]]
+        %6 = empty_list [[<error>]]
+        %7 = call keys(%2) [[String]]
+        %8 = iter_init %7 [[String]]
         jump -> bb6
       bb4:
-        %13 = empty_list [[<error>]]
-        %5 = copy %13 [[Event]]
-        jump -> bb5
-      bb5:
-        return %5
+        %12 = empty_list [[Event]]
+        jump -> bb5(%12)
+      bb5(%13):
+        return %13
       bb6:
-        iter_next %9 -> %10, bb7, bb8
+        iter_next %8 -> %9, bb7, bb8
       bb7:
-        %11 = variant Event::LightStateChanged(%10) [Event]
-        %12 = list_push %7, %11 [()]
+        %10 = variant Event::LightStateChanged(%9) [Event]
+        %11 = list_push %6, %10 [()]
         jump -> bb6
       bb8:
-        %5 = copy %7 [[Event]]
-        jump -> bb5
+        jump -> bb5(%6)
+    ");
+}
+
+// =============================================================================
+// Match expressions
+// =============================================================================
+
+#[test]
+fn test_lower_match_variant_and_wildcard() {
+    let src = r#"observer {
+  event,
+  ...
+} /true/ {
+  match event {
+    Event::LightStateChanged(l) => { l },
+    _ => { 0 }
+  }
+}"#;
+    let result = lower_and_pretty(src);
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Params:
+        %0: event [Event]
+      bb0:
+        %1 = const_bool true [Bool]
+        branch %1 -> bb1, bb2
+      bb1:
+        %3 = discriminant %0 [String]
+        %4 = const_string "LightStateChanged" [String]
+        %5 = eq %3, %4 [Bool]
+        branch %5 -> bb4, bb5
+      bb2:
+        %2 = empty_list [[Event]]
+        return %2
+      bb3(%8):
+        return %8
+      bb4:
+        %6 = variant_field %0[0] [String]
+        jump -> bb3(%6)
+      bb5:
+        %7 = const_int 0 [Int]
+        jump -> bb3(%7)
+    ");
+}
+
+#[test]
+fn test_lower_match_multiple_variants() {
+    let src = r#"observer {
+  event,
+  ...
+} /true/ {
+  match event {
+    Event::LightStateChanged(l) => { l },
+    Event::BinarySensorStateChanged(b) => { b }
+  }
+}"#;
+    let result = lower_and_pretty(src);
+    insta::assert_snapshot!(result, @"
+    Automation: observer
+      Params:
+        %0: event [Event]
+      bb0:
+        %1 = const_bool true [Bool]
+        branch %1 -> bb1, bb2
+      bb1:
+        %3 = discriminant %0 [String]
+        %4 = const_string "LightStateChanged" [String]
+        %5 = eq %3, %4 [Bool]
+        branch %5 -> bb4, bb5
+      bb2:
+        %2 = empty_list [[Event]]
+        return %2
+      bb3(%10):
+        return %10
+      bb4:
+        %6 = variant_field %0[0] [String]
+        jump -> bb3(%6)
+      bb5:
+        %7 = const_string "BinarySensorStateChanged" [String]
+        %8 = eq %3, %7 [Bool]
+        branch %8 -> bb6, bb7
+      bb6:
+        %9 = variant_field %0[0] [String]
+        jump -> bb3(%9)
+      bb7:
+        unreachable
     ");
 }
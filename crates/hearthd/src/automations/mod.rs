@@ -3,14 +3,38 @@
 //! This module provides parsing and type checking for `.hda` automation files.
 
 pub mod ast;
+pub mod dce;
 pub mod desugar;
+pub mod diagnostics;
+pub mod format;
+pub mod html_print;
+pub mod instantiate;
+pub(crate) mod int_ops;
+pub mod interpreter;
 pub mod lexer;
 pub mod lowered_ast;
 pub mod lowered_pretty_print;
 pub mod parser;
 pub mod pretty_print;
+pub mod repl;
+pub mod simplify;
+pub mod spanless_eq;
 
 pub use ast::*;
+pub use dce::eliminate_dead_bindings;
 pub use desugar::desugar;
+pub use diagnostics::render_origin_report;
+pub use diagnostics::render_report;
+pub use diagnostics::to_diagnostics;
+pub use diagnostics::OriginDiagnostic;
+pub use format::DEFAULT_MAX_WIDTH;
+pub use format::SourceFormat;
+pub use html_print::HtmlPrint;
+pub use instantiate::instantiate;
 pub use parser::parse;
+pub use parser::parse_diagnostics;
 pub use pretty_print::PrettyPrint;
+pub use repl::Repl;
+pub use repl::ReplOutput;
+pub use simplify::simplify;
+pub use spanless_eq::SpanlessEq;
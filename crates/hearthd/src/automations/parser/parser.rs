@@ -1,12 +1,16 @@
 //! Parser for the HearthD Automations language.
 
+use std::path::Path;
+
 use chumsky::prelude::*;
 use chumsky::span::SimpleSpan;
+use hearthd_config::Diagnostic;
 
 use crate::automations::ast::*;
+use crate::automations::diagnostics::to_diagnostics;
 use crate::automations::lexer::Token;
 
-/// Parse a complete automation program.
+/// Parse a complete top-level item: either a bare automation or a template.
 pub fn parse(input: &str) -> Result<Spanned<Program>, Vec<Rich<'static, Token>>> {
     let tokens = crate::automations::lexer::lexer()
         .parse(input)
@@ -17,21 +21,78 @@ pub fn parse(input: &str) -> Result<Spanned<Program>, Vec<Rich<'static, Token>>>
                 .collect::<Vec<_>>()
         })?;
     let input_len = input.len();
-    let result = automation_parser()
+    program_parser()
+        .parse(
+            tokens
+                .as_slice()
+                .map((input_len..input_len).into(), |(t, s)| (t, s)),
+        )
+        .into_result()
+        .map_err(|errs| errs.into_iter().map(|e| e.into_owned()).collect())
+}
+
+/// Parse a complete top-level item like [`parse`], but report lexer/parser
+/// failures as `hearthd_config::Diagnostic`s rather than raw `Rich` errors -
+/// so a `.hearth` file gets the identical Ariadne rendering, JSON output,
+/// and severity/code handling as a TOML config error.
+///
+/// `file_path` is attached to every diagnostic's [`hearthd_config::SourceInfo`]
+/// so multi-file tooling (the daemon, an editor) can tell which file a
+/// reported span belongs to. Chumsky's own alternation/backtracking may
+/// already surface more than one error from a single failed parse, but this
+/// does not yet retry past a syntax error to parse later top-level items in
+/// the same file - see [`parse_file`] for that.
+pub fn parse_diagnostics(
+    input: &str,
+    file_path: &Path,
+) -> (Option<Spanned<Program>>, Vec<Diagnostic>) {
+    match parse(input) {
+        Ok(program) => (Some(program), Vec::new()),
+        Err(errs) => (None, to_diagnostics(input, file_path, &errs)),
+    }
+}
+
+/// Parse a file containing one or more top-level items (automations and/or
+/// templates) back to back, e.g. a library of several related templates.
+/// [`parse`] only ever parses and returns the first.
+pub fn parse_file(input: &str) -> Result<Vec<Spanned<Program>>, Vec<Rich<'static, Token>>> {
+    let tokens = crate::automations::lexer::lexer()
+        .parse(input)
+        .into_result()
+        .map_err(|errs| {
+            errs.into_iter()
+                .map(|err| Rich::<Token>::custom(*err.span(), format!("Lexer error: {}", err)))
+                .collect::<Vec<_>>()
+        })?;
+    let input_len = input.len();
+    program_parser()
+        .repeated()
+        .at_least(1)
+        .collect::<Vec<_>>()
         .parse(
             tokens
                 .as_slice()
                 .map((input_len..input_len).into(), |(t, s)| (t, s)),
         )
         .into_result()
-        .map(|auto| Spanned::new(Program::Automation(auto.node), auto.span))
-        .map_err(|errs| errs.into_iter().map(|e| e.into_owned()).collect());
-    result
+        .map_err(|errs| errs.into_iter().map(|e| e.into_owned()).collect())
+}
+
+/// Parser for a single top-level item.
+fn program_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Spanned<Program>, extra::Err<Rich<'tokens, Token>>> + Clone
+where
+    I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
+{
+    choice((
+        template_parser().map(|t| Spanned::new(Program::Template(t.node), t.span)),
+        automation_parser().map(|a| Spanned::new(Program::Automation(a.node), a.span)),
+    ))
 }
 
 /// Parser for expressions.
-pub(crate) fn expr_parser<'tokens, 'src: 'tokens, I>()
--> impl Parser<'tokens, I, Spanned<Expr>, extra::Err<Rich<'tokens, Token>>> + Clone
+pub(crate) fn expr_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Spanned<Expr>, extra::Err<Rich<'tokens, Token>>> + Clone
 where
     I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
 {
@@ -87,6 +148,83 @@ where
             )
             .map(|(name, fields)| Expr::StructLit { name, fields });
 
+        // A pattern in a `for`/comprehension binding position: a bare name,
+        // or a parenthesized (possibly nested) tuple of them, e.g. `for (k,
+        // v) in pairs` - the `for (x, y) in it` idiom from iterator code.
+        let bind_pattern = recursive(|bind_pattern| {
+            choice((
+                select! { Token::Ident(s) => s }
+                    .map_with(|s, e| Spanned::new(BindPattern::Ident(s), e.span())),
+                bind_pattern
+                    .separated_by(just(Token::Comma))
+                    .allow_trailing()
+                    .collect::<Vec<_>>()
+                    .delimited_by(just(Token::LParen), just(Token::RParen))
+                    .map_with(|elems, e| Spanned::new(BindPattern::Tuple(elems), e.span())),
+            ))
+        });
+
+        // A generator clause: `for v in iter` or `for (a, b) in iter`.
+        let for_clause = just(Token::For)
+            .ignore_then(bind_pattern)
+            .then_ignore(just(Token::In))
+            .then(expr.clone())
+            .map(|(var, iter)| CompClause::For { var, iter });
+
+        // A single comprehension clause after the first: another generator,
+        // or a filter (`if cond`) guarding everything after it.
+        let comp_clause = choice((
+            for_clause.clone(),
+            just(Token::If)
+                .ignore_then(expr.clone())
+                .map(CompClause::If),
+        ));
+
+        // `[expr for v1 in it1 if c1 for v2 in it2 ...]` - at least one
+        // generator clause (always first, Python-style) followed by any
+        // number of further generators/filters in source order.
+        let list_comp = expr
+            .clone()
+            .then(for_clause.clone())
+            .then(comp_clause.clone().repeated().collect::<Vec<_>>())
+            .delimited_by(just(Token::LBracket), just(Token::RBracket))
+            .map(|((body, first), rest)| {
+                let mut clauses = vec![first];
+                clauses.extend(rest);
+                Expr::ListComp {
+                    expr: Box::new(body),
+                    clauses,
+                }
+            })
+            .labelled("list comprehension");
+
+        // `{key: value for v1 in it1 if c1 ...}` or `{expr for v1 in it1 if
+        // c1 ...}` - a dict or set comprehension, disambiguated by whether a
+        // `:` follows the first element, exactly mirroring `list_comp`'s
+        // at-least-one-generator structure.
+        let dict_or_set_comp = expr
+            .clone()
+            .then(just(Token::Colon).ignore_then(expr.clone()).or_not())
+            .then(for_clause)
+            .then(comp_clause.repeated().collect::<Vec<_>>())
+            .delimited_by(just(Token::LBrace), just(Token::RBrace))
+            .map(|(((first, value), first_clause), rest)| {
+                let mut clauses = vec![first_clause];
+                clauses.extend(rest);
+                match value {
+                    Some(value) => Expr::DictComp {
+                        key: Box::new(first),
+                        value: Box::new(value),
+                        clauses,
+                    },
+                    None => Expr::SetComp {
+                        expr: Box::new(first),
+                        clauses,
+                    },
+                }
+            })
+            .labelled("dict/set comprehension");
+
         let list = expr
             .clone()
             .separated_by(just(Token::Comma))
@@ -96,10 +234,28 @@ where
             .map(Expr::List)
             .labelled("list");
 
+        // Parenthesized expression or tuple literal: `(a)` is grouping (no
+        // trailing comma, one element), `(a,)`/`(a, b)` is a tuple - same
+        // disambiguation Rust uses.
         let paren_expr = expr
             .clone()
+            .then(
+                just(Token::Comma)
+                    .ignore_then(expr.clone())
+                    .repeated()
+                    .collect::<Vec<_>>(),
+            )
+            .then(just(Token::Comma).or_not())
             .delimited_by(just(Token::LParen), just(Token::RParen))
-            .map(|e| e.node);
+            .map(|((first, rest), trailing_comma)| {
+                if rest.is_empty() && trailing_comma.is_none() {
+                    first.node
+                } else {
+                    let mut elems = vec![first];
+                    elems.extend(rest);
+                    Expr::Tuple(elems)
+                }
+            });
 
         // Block of statements (reusable for if branches)
         // Uses stmt_parser_with to pass the recursive expr reference
@@ -120,9 +276,93 @@ where
                 else_block,
             });
 
-        let atom = choice((literal, struct_lit, ident, list, if_expr, paren_expr))
-            .map_with(|node, e| Spanned::new(node, e.span()))
-            .boxed();
+        // Match expression: `match scrutinee { Enum::Variant(a, b) => { ... }, _ => { ... } }`
+        //
+        // Variant patterns destructure positionally (matching how variants
+        // are constructed, e.g. `Event::LightStateChanged(l)`), so each
+        // binding is just an identifier or a `_` wildcard, not a nested
+        // field pattern.
+        let wildcard_ident = just(Token::Ident("_".to_string()));
+
+        let binding = choice((
+            wildcard_ident
+                .clone()
+                .map_with(|_, e| Spanned::new(BindingPattern::Wildcard, e.span())),
+            select! { Token::Ident(s) => s }
+                .map_with(|s, e| Spanned::new(BindingPattern::Ident(s), e.span())),
+        ));
+
+        let variant_pattern = select! { Token::Ident(s) => s }
+            .then_ignore(just(Token::ColonColon))
+            .then(select! { Token::Ident(s) => s })
+            .then(
+                binding
+                    .separated_by(just(Token::Comma))
+                    .allow_trailing()
+                    .collect::<Vec<_>>()
+                    .delimited_by(just(Token::LParen), just(Token::RParen))
+                    .or_not(),
+            )
+            .map_with(|((enum_name, variant), bindings), e| {
+                Spanned::new(
+                    MatchPattern::Variant {
+                        enum_name,
+                        variant,
+                        bindings: bindings.unwrap_or_default(),
+                    },
+                    e.span(),
+                )
+            });
+
+        let wildcard_pattern =
+            wildcard_ident.map_with(|_, e| Spanned::new(MatchPattern::Wildcard, e.span()));
+
+        let match_arm = choice((variant_pattern, wildcard_pattern))
+            .then_ignore(just(Token::FatArrow))
+            .then(block.clone())
+            .map(|(pattern, body)| MatchArm { pattern, body });
+
+        let match_expr = just(Token::Match)
+            .ignore_then(expr.clone())
+            .then(
+                match_arm
+                    .separated_by(just(Token::Comma))
+                    .allow_trailing()
+                    .collect::<Vec<_>>()
+                    .delimited_by(just(Token::LBrace), just(Token::RBrace)),
+            )
+            .map(|(scrutinee, arms)| Expr::Match {
+                scrutinee: Box::new(scrutinee),
+                arms,
+            });
+
+        // Lambda expression: `|params| body`, e.g. `|l| l.brightness > 50`
+        // passed to `filter`/`map`/`fold`.
+        let lambda = select! { Token::Ident(s) => s }
+            .separated_by(just(Token::Comma))
+            .allow_trailing()
+            .collect::<Vec<_>>()
+            .delimited_by(just(Token::Pipe), just(Token::Pipe))
+            .then(expr.clone())
+            .map(|(params, body)| Expr::Lambda {
+                params,
+                body: Box::new(body),
+            });
+
+        let atom = choice((
+            literal,
+            struct_lit,
+            ident,
+            list_comp,
+            dict_or_set_comp,
+            list,
+            if_expr,
+            match_expr,
+            lambda,
+            paren_expr,
+        ))
+        .map_with(|node, e| Spanned::new(node, e.span()))
+        .boxed();
 
         // Function argument: either `name = expr` (named) or `expr` (positional)
         let arg = choice((
@@ -293,7 +533,8 @@ where
         // Logical OR: ||
         let or_op = select! { Token::Or => BinOp::Or };
 
-        and.clone()
+        let or = and
+            .clone()
             .foldl_with(or_op.then(and).repeated(), |left, (op, right), e| {
                 Spanned::new(
                     Expr::BinOp {
@@ -303,10 +544,55 @@ where
                     },
                     e.span(),
                 )
+            });
+
+        // Range: `a..b` (exclusive), `a..=b` (inclusive). Binds looser than
+        // every other binary operator, so `x..y + 1` is `x..(y + 1)`, and
+        // doesn't associate with itself - `a..b..c` is rejected rather than
+        // silently nesting.
+        let range_op = select! {
+            Token::DotDot => BinOp::Range,
+            Token::DotDotEq => BinOp::RangeInclusive,
+        };
+
+        or.clone()
+            .then(range_op.then(or).or_not())
+            .map_with(|(left, rest), e| match rest {
+                Some((op, right)) => Spanned::new(
+                    Expr::BinOp {
+                        op,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                    },
+                    e.span(),
+                ),
+                None => left,
             })
     })
 }
 
+/// Parse a single statement (`let` binding or bare expression), e.g. one
+/// entry typed into a REPL rather than a whole `observer`/`mutator` file.
+pub fn parse_stmt(input: &str) -> Result<Spanned<Stmt>, Vec<Rich<'static, Token>>> {
+    let tokens = crate::automations::lexer::lexer()
+        .parse(input)
+        .into_result()
+        .map_err(|errs| {
+            errs.into_iter()
+                .map(|err| Rich::<Token>::custom(*err.span(), format!("Lexer error: {}", err)))
+                .collect::<Vec<_>>()
+        })?;
+    let input_len = input.len();
+    stmt_parser()
+        .parse(
+            tokens
+                .as_slice()
+                .map((input_len..input_len).into(), |(t, s)| (t, s)),
+        )
+        .into_result()
+        .map_err(|errs| errs.into_iter().map(|e| e.into_owned()).collect())
+}
+
 /// Parser for statements, parameterized by an expression parser.
 ///
 /// This allows breaking mutual recursion between expr_parser and stmt_parser
@@ -319,22 +605,46 @@ where
     E: Parser<'tokens, I, Spanned<Expr>, extra::Err<Rich<'tokens, Token>>> + Clone,
 {
     let let_stmt = just(Token::Let)
-        .ignore_then(select! { Token::Ident(s) => s })
+        .ignore_then(just(Token::Mut).or_not())
+        .then(select! { Token::Ident(s) => s })
         .then_ignore(just(Token::Assign))
         .then(expr.clone())
         .then_ignore(just(Token::Semicolon))
-        .map_with(|(name, value), e| Spanned::new(Stmt::Let { name, value }, e.span()));
+        .map_with(|((is_mut, name), value), e| {
+            let stmt = if is_mut.is_some() {
+                Stmt::LetMut { name, value }
+            } else {
+                Stmt::Let { name, value }
+            };
+            Spanned::new(stmt, e.span())
+        });
+
+    let compound_op = select! {
+        Token::PlusEq => BinOp::Add,
+        Token::MinusEq => BinOp::Sub,
+        Token::StarEq => BinOp::Mul,
+        Token::SlashEq => BinOp::Div,
+        Token::PercentEq => BinOp::Mod,
+    };
+
+    let compound_assign_stmt = select! { Token::Ident(s) => s }
+        .then(compound_op)
+        .then(expr.clone())
+        .then_ignore(just(Token::Semicolon))
+        .map_with(|((name, op), value), e| {
+            Spanned::new(Stmt::CompoundAssign { name, op, value }, e.span())
+        });
 
     let expr_stmt = expr
         .then(just(Token::Semicolon).or_not())
         .map_with(|(expr, _), e| Spanned::new(Stmt::Expr(expr), e.span()));
 
-    choice((let_stmt, expr_stmt))
+    choice((let_stmt, compound_assign_stmt, expr_stmt))
 }
 
 /// Parser for statements using the top-level expression parser.
-fn stmt_parser<'tokens, 'src: 'tokens, I>()
--> impl Parser<'tokens, I, Spanned<Stmt>, extra::Err<Rich<'tokens, Token>>> + Clone
+fn stmt_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Spanned<Stmt>, extra::Err<Rich<'tokens, Token>>> + Clone
 where
     I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
 {
@@ -344,15 +654,16 @@ where
 /// Automation parser - parses `observer {} /filter/ { stmts }`
 ///
 /// Pattern is currently stubbed to empty braces; filter and body are fully parsed.
-fn automation_parser<'tokens, 'src: 'tokens, I>()
--> impl Parser<'tokens, I, Spanned<Automation>, extra::Err<Rich<'tokens, Token>>>
+fn automation_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Spanned<Automation>, extra::Err<Rich<'tokens, Token>>> + Clone
 where
     I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
 {
     let kind = select! {
         Token::Observer => AutomationKind::Observer,
         Token::Mutator => AutomationKind::Mutator,
-    };
+    }
+    .map_with(|kind, e| (kind, e.span()));
 
     // Pattern parser for struct destructuring (recursive for nested patterns)
     let pattern = recursive(|pattern| {
@@ -390,10 +701,13 @@ where
             })
     });
 
-    // Filter uses expr_parser
-    let filter = just(Token::Slash)
+    // Filter uses expr_parser. The lexer has already disambiguated the
+    // delimiting slashes from division inside the filter expression itself
+    // (see `lexer::disambiguate_filters`), so this just matches the
+    // `FilterStart`/`FilterEnd` pair it produced.
+    let filter = just(Token::FilterStart)
         .ignore_then(expr_parser())
-        .then_ignore(just(Token::Slash));
+        .then_ignore(just(Token::FilterEnd));
 
     // Body - list of statements
     let body = stmt_parser()
@@ -405,9 +719,11 @@ where
         .then(filter)
         .then(body)
         .map_with(|(((kind, pattern), filter), body), e| {
+            let (kind, kind_span) = kind;
             Spanned::new(
                 Automation {
                     kind,
+                    kind_span,
                     pattern,
                     filter,
                     body,
@@ -416,3 +732,87 @@ where
             )
         })
 }
+
+/// Parser for `TemplateParam` type annotations: a bare name (`Light`), or
+/// one of the built-in generics applied to another type (`List<Light>`,
+/// `Set<String>`, `Map<String, Light>`, `Option<Light>`).
+fn type_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Type, extra::Err<Rich<'tokens, Token>>> + Clone
+where
+    I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
+{
+    recursive(|ty| {
+        let generic_arg = ty.clone().delimited_by(just(Token::Lt), just(Token::Gt));
+
+        let list = just(Token::Ident("List".to_string()))
+            .ignore_then(generic_arg.clone())
+            .map(|inner| Type::List(Box::new(inner)));
+
+        let set = just(Token::Ident("Set".to_string()))
+            .ignore_then(generic_arg.clone())
+            .map(|inner| Type::Set(Box::new(inner)));
+
+        let option = just(Token::Ident("Option".to_string()))
+            .ignore_then(generic_arg)
+            .map(|inner| Type::Option(Box::new(inner)));
+
+        let map = just(Token::Ident("Map".to_string()))
+            .ignore_then(
+                ty.clone()
+                    .then_ignore(just(Token::Comma))
+                    .then(ty)
+                    .delimited_by(just(Token::Lt), just(Token::Gt)),
+            )
+            .map(|(key, value)| Type::Map {
+                key: Box::new(key),
+                value: Box::new(value),
+            });
+
+        let named = select! { Token::Ident(s) => s }.map(Type::Named);
+
+        choice((list, set, option, map, named))
+    })
+}
+
+/// Template parser - parses `template Name(param: Type, ...) { automation+ }`.
+///
+/// `ast::Template` has no field for the template's own name - it's only
+/// used here to distinguish one template from another in source text, the
+/// same way a `fn`'s name isn't part of the expression it compiles to - so
+/// it's parsed and then discarded.
+fn template_parser<'tokens, 'src: 'tokens, I>(
+) -> impl Parser<'tokens, I, Spanned<Template>, extra::Err<Rich<'tokens, Token>>>
+where
+    I: chumsky::input::ValueInput<'tokens, Token = Token, Span = SimpleSpan>,
+{
+    let param = select! { Token::Ident(s) => s }
+        .then_ignore(just(Token::Colon))
+        .then(type_parser())
+        .map_with(|(name, ty), e| Spanned::new(TemplateParam { name, ty }, e.span()));
+
+    let params = param
+        .separated_by(just(Token::Comma))
+        .allow_trailing()
+        .collect::<Vec<_>>()
+        .delimited_by(just(Token::LParen), just(Token::RParen));
+
+    let automations = automation_parser()
+        .repeated()
+        .at_least(1)
+        .collect::<Vec<_>>()
+        .delimited_by(just(Token::LBrace), just(Token::RBrace));
+
+    just(Token::Template)
+        .ignore_then(select! { Token::Ident(s) => s })
+        .ignore_then(params)
+        .then(automations)
+        .map_with(|(params, automations), e| {
+            Spanned::new(
+                Template {
+                    params,
+                    automations,
+                },
+                e.span(),
+            )
+        })
+}
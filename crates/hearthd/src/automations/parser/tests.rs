@@ -1,6 +1,11 @@
+use std::path::Path;
+
 use chumsky::prelude::*;
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
 
 use super::expr_parser;
+use super::parse_diagnostics;
 use crate::automations::ast::*;
 use crate::automations::lexer::Token;
 use crate::automations::pretty_print::PrettyPrint;
@@ -389,6 +394,48 @@ fn test_parse_nested_if() {
     ");
 }
 
+#[test]
+fn test_parse_match_with_bindings_and_wildcard() {
+    insta::assert_snapshot!(parse_expr("match e { Event::LightStateChanged(l) => { l }, Event::BinarySensorStateChanged(_) => { 0 }, _ => { 1 } }").unwrap().to_pretty_string(), @r"
+    Match:
+      Scrutinee:
+        Ident: e
+      Arm:
+        MatchPatternVariant: Event::LightStateChanged(
+          BindingIdent: l
+        )
+        Body:
+          ExprStmt:
+            Ident: l
+      Arm:
+        MatchPatternVariant: Event::BinarySensorStateChanged(
+          BindingWildcard
+        )
+        Body:
+          ExprStmt:
+            Int: 0
+      Arm:
+        MatchPatternWildcard
+        Body:
+          ExprStmt:
+            Int: 1
+    ");
+}
+
+#[test]
+fn test_parse_match_no_bindings() {
+    insta::assert_snapshot!(parse_expr("match e { Event::LightStateChanged => { 1 } }").unwrap().to_pretty_string(), @r"
+    Match:
+      Scrutinee:
+        Ident: e
+      Arm:
+        MatchPatternVariant: Event::LightStateChanged
+        Body:
+          ExprStmt:
+            Int: 1
+    ");
+}
+
 #[test]
 fn test_parse_automation() {
     insta::assert_snapshot!(crate::automations::parse("observer {} /true/ { let x = 42; }").unwrap().to_pretty_string(), @r"
@@ -647,3 +694,23 @@ fn test_parse_struct_lit_nested() {
             Int: 1
     ");
 }
+
+#[test]
+fn test_parse_diagnostics_returns_none_and_a_diagnostic_on_failure() {
+    let (program, diagnostics) = parse_diagnostics("let = 1;", Path::new("/tmp/trigger.hearth"));
+
+    assert!(program.is_none());
+    assert!(!diagnostics.is_empty());
+    assert!(diagnostics
+        .iter()
+        .all(|d| matches!(d, Diagnostic::Error(Error::Parse(_)))));
+}
+
+#[test]
+fn test_parse_diagnostics_returns_the_program_and_no_diagnostics_on_success() {
+    let (program, diagnostics) =
+        parse_diagnostics("observer {} /true/ { 1 }", Path::new("/tmp/ok.hearth"));
+
+    assert!(program.is_some());
+    assert!(diagnostics.is_empty());
+}
@@ -4,21 +4,103 @@
 
 use super::ast::*;
 
+/// A node passed to a [`PpAnn`] callback, borrowing whichever AST value is
+/// about to be (or was just) printed. Not every printable type has a
+/// variant here - only the ones annotators plausibly care to hook, e.g. to
+/// append an inferred type after an `Expr` or a diagnostic after a `Stmt`.
+pub enum AnnNode<'a> {
+    Expr(&'a Expr),
+    Stmt(&'a Stmt),
+    Automation(&'a Automation),
+    Pattern(&'a Pattern),
+    Program(&'a Program),
+    Template(&'a Template),
+    MatchArm(&'a MatchArm),
+}
+
+/// Extension point for interleaving extra information into a
+/// [`PrettyPrint`] tree - inferred types, source spans, evaluation
+/// diagnostics - without forking the printer for every use case. `pre`
+/// runs just before a node's own output, `post` just after. Both default
+/// to a no-op, so implementing only one is enough for most annotators.
+pub trait PpAnn {
+    fn pre(&self, _node: AnnNode<'_>, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+
+    fn post(&self, _node: AnnNode<'_>, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+}
+
+/// The default annotator: no hooks fire, so output is unchanged from
+/// before `PpAnn` existed.
+pub struct NoAnn;
+
+impl PpAnn for NoAnn {}
+
 /// Trait for verbose, multi-line AST pretty-printing.
 pub trait PrettyPrint {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result;
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result;
 
     fn to_pretty_string(&self) -> String {
-        struct Wrapper<'a, T: PrettyPrint + ?Sized>(&'a T);
+        self.to_annotated_pretty_string(&NoAnn)
+    }
+
+    /// Like [`to_pretty_string`](PrettyPrint::to_pretty_string), but runs
+    /// `ann`'s hooks around every annotatable node.
+    fn to_annotated_pretty_string(&self, ann: &dyn PpAnn) -> String {
+        struct Wrapper<'a, T: PrettyPrint + ?Sized>(&'a T, &'a dyn PpAnn);
         impl<T: PrettyPrint + ?Sized> std::fmt::Display for Wrapper<'_, T> {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                self.0.pretty_print(0, f)
+                self.0.pretty_print(0, self.1, f)
             }
         }
-        Wrapper(self).to_string()
+        Wrapper(self, ann).to_string()
     }
 }
 
+/// Renders a `for`/comprehension binding pattern inline, e.g. `x` or
+/// `(a, (b, c))`, for use in a single-line `Var: ...` label.
+fn bind_pattern_inline(pattern: &BindPattern) -> String {
+    match pattern {
+        BindPattern::Ident(name) => name.clone(),
+        BindPattern::Tuple(elems) => {
+            let parts: Vec<String> = elems.iter().map(|e| bind_pattern_inline(&e.node)).collect();
+            format!("({})", parts.join(", "))
+        }
+    }
+}
+
+/// Prints a comprehension's `for`/`if` clauses, shared by `ListComp`,
+/// `DictComp`, and `SetComp`.
+fn print_comp_clauses(
+    clauses: &[CompClause],
+    indent: usize,
+    ann: &dyn PpAnn,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    for clause in clauses {
+        write_indent(indent, f)?;
+        match clause {
+            CompClause::For { var, iter } => {
+                writeln!(f, "For: {}", bind_pattern_inline(&var.node))?;
+                iter.pretty_print(indent + 1, ann, f)?;
+            }
+            CompClause::If(cond) => {
+                writeln!(f, "If:")?;
+                cond.pretty_print(indent + 1, ann, f)?;
+            }
+        }
+    }
+    Ok(())
+}
+
 fn write_indent(indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     for _ in 0..indent {
         write!(f, "  ")?;
@@ -27,58 +109,60 @@ fn write_indent(indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Res
 }
 
 impl<T: PrettyPrint> PrettyPrint for Spanned<T> {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.node.pretty_print(indent, f)
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        self.node.pretty_print(indent, ann, f)
     }
 }
 
 impl PrettyPrint for Expr {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Expr(self), f)?;
         write_indent(indent, f)?;
         match self {
-            Expr::Int(n) => writeln!(f, "Int: {}", n),
-            Expr::Float(n) => writeln!(f, "Float: {}", n),
-            Expr::String(s) => writeln!(f, "String: \"{}\"", s),
-            Expr::Bool(b) => writeln!(f, "Bool: {}", b),
-            Expr::UnitLiteral { value, unit } => writeln!(f, "UnitLiteral: {}{}", value, unit),
-            Expr::Ident(s) => writeln!(f, "Ident: {}", s),
-            Expr::Path(segments) => {
-                writeln!(f, "Path:")?;
-                for seg in segments {
-                    write_indent(indent + 1, f)?;
-                    writeln!(f, "Segment: {}", seg)?;
-                }
-                Ok(())
-            }
+            Expr::Int(n) => writeln!(f, "Int: {}", n)?,
+            Expr::Float(n) => writeln!(f, "Float: {}", n)?,
+            Expr::String(s) => writeln!(f, "String: \"{}\"", s)?,
+            Expr::Bool(b) => writeln!(f, "Bool: {}", b)?,
+            Expr::UnitLiteral { value, unit } => writeln!(f, "UnitLiteral: {}{}", value, unit)?,
+            Expr::Ident(s) => writeln!(f, "Ident: {}", s)?,
             Expr::BinOp { op, left, right } => {
                 writeln!(f, "BinOp: {}", op)?;
-                left.pretty_print(indent + 1, f)?;
-                right.pretty_print(indent + 1, f)
+                left.pretty_print(indent + 1, ann, f)?;
+                right.pretty_print(indent + 1, ann, f)?;
             }
             Expr::UnaryOp { op, expr } => {
                 writeln!(f, "UnaryOp: {}", op)?;
-                expr.pretty_print(indent + 1, f)
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             Expr::Field { expr, field } => {
                 writeln!(f, "Field: .{}", field)?;
-                expr.pretty_print(indent + 1, f)
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             Expr::OptionalField { expr, field } => {
                 writeln!(f, "OptionalField: ?.{}", field)?;
-                expr.pretty_print(indent + 1, f)
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             Expr::Call { func, args } => {
                 writeln!(f, "Call:")?;
-                func.pretty_print(indent + 1, f)?;
+                func.pretty_print(indent + 1, ann, f)?;
                 write_indent(indent + 1, f)?;
                 if args.is_empty() {
-                    writeln!(f, "Args: (none)")
+                    writeln!(f, "Args: (none)")?;
                 } else {
                     writeln!(f, "Args:")?;
                     for arg in args {
-                        arg.pretty_print(indent + 2, f)?;
+                        arg.pretty_print(indent + 2, ann, f)?;
                     }
-                    Ok(())
                 }
             }
             Expr::If {
@@ -89,105 +173,139 @@ impl PrettyPrint for Expr {
                 writeln!(f, "If:")?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Cond:")?;
-                cond.pretty_print(indent + 2, f)?;
+                cond.pretty_print(indent + 2, ann, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Then:")?;
                 for stmt in then_block {
-                    stmt.pretty_print(indent + 2, f)?;
+                    stmt.pretty_print(indent + 2, ann, f)?;
                 }
-                if let Some(else_stmts) = else_block {
-                    write_indent(indent + 1, f)?;
-                    writeln!(f, "Else:")?;
-                    for stmt in else_stmts {
-                        stmt.pretty_print(indent + 2, f)?;
-                    }
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Else:")?;
+                for stmt in else_block {
+                    stmt.pretty_print(indent + 2, ann, f)?;
                 }
-                Ok(())
             }
             Expr::List(items) => {
                 if items.is_empty() {
-                    writeln!(f, "List: (empty)")
+                    writeln!(f, "List: (empty)")?;
                 } else {
                     writeln!(f, "List:")?;
                     for item in items {
-                        item.pretty_print(indent + 1, f)?;
+                        item.pretty_print(indent + 1, ann, f)?;
                     }
-                    Ok(())
                 }
             }
-            Expr::ListComp {
-                expr,
-                var,
-                iter,
-                filter,
-            } => {
+            Expr::ListComp { expr, clauses } => {
                 writeln!(f, "ListComp:")?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Expr:")?;
-                expr.pretty_print(indent + 2, f)?;
+                expr.pretty_print(indent + 2, ann, f)?;
+                print_comp_clauses(clauses, indent + 1, ann, f)?;
+            }
+            Expr::DictComp {
+                key,
+                value,
+                clauses,
+            } => {
+                writeln!(f, "DictComp:")?;
                 write_indent(indent + 1, f)?;
-                writeln!(f, "Var: {}", var)?;
+                writeln!(f, "Key:")?;
+                key.pretty_print(indent + 2, ann, f)?;
                 write_indent(indent + 1, f)?;
-                writeln!(f, "Iter:")?;
-                iter.pretty_print(indent + 2, f)?;
-                if let Some(filt) = filter {
-                    write_indent(indent + 1, f)?;
-                    writeln!(f, "Filter:")?;
-                    filt.pretty_print(indent + 2, f)?;
-                }
-                Ok(())
+                writeln!(f, "Value:")?;
+                value.pretty_print(indent + 2, ann, f)?;
+                print_comp_clauses(clauses, indent + 1, ann, f)?;
+            }
+            Expr::SetComp { expr, clauses } => {
+                writeln!(f, "SetComp:")?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Expr:")?;
+                expr.pretty_print(indent + 2, ann, f)?;
+                print_comp_clauses(clauses, indent + 1, ann, f)?;
             }
             Expr::StructLit { name, fields } => {
                 writeln!(f, "StructLit: {}", name)?;
                 for field in fields {
-                    field.pretty_print(indent + 1, f)?;
+                    field.pretty_print(indent + 1, ann, f)?;
+                }
+            }
+            Expr::Match { scrutinee, arms } => {
+                writeln!(f, "Match:")?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Scrutinee:")?;
+                scrutinee.pretty_print(indent + 2, ann, f)?;
+                for arm in arms {
+                    arm.pretty_print(indent + 1, ann, f)?;
+                }
+            }
+            Expr::Lambda { params, body } => {
+                writeln!(f, "Lambda: |{}|", params.join(", "))?;
+                body.pretty_print(indent + 1, ann, f)?;
+            }
+            Expr::Tuple(items) => {
+                writeln!(f, "Tuple:")?;
+                for item in items {
+                    item.pretty_print(indent + 1, ann, f)?;
                 }
-                Ok(())
             }
         }
+        ann.post(AnnNode::Expr(self), f)
     }
 }
 
 impl PrettyPrint for Stmt {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Stmt(self), f)?;
         write_indent(indent, f)?;
         match self {
             Stmt::Let { name, value } => {
                 writeln!(f, "Let: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                value.pretty_print(indent + 1, ann, f)?;
             }
             Stmt::Expr(expr) => {
                 writeln!(f, "ExprStmt:")?;
-                expr.pretty_print(indent + 1, f)
-            }
-            Stmt::Return(expr) => {
-                writeln!(f, "Return:")?;
-                expr.pretty_print(indent + 1, f)
+                expr.pretty_print(indent + 1, ann, f)?;
             }
         }
+        ann.post(AnnNode::Stmt(self), f)
     }
 }
 
 impl PrettyPrint for Arg {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         match self {
-            Arg::Positional(expr) => expr.pretty_print(indent, f),
+            Arg::Positional(expr) => expr.pretty_print(indent, ann, f),
             Arg::Named { name, value } => {
                 write_indent(indent, f)?;
                 writeln!(f, "Named: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                value.pretty_print(indent + 1, ann, f)
             }
         }
     }
 }
 
 impl PrettyPrint for StructField {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         match self {
             StructField::Field { name, value } => {
                 writeln!(f, "Field: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                value.pretty_print(indent + 1, ann, f)
             }
             StructField::Inherit(name) => writeln!(f, "Inherit: {}", name),
             StructField::Spread(name) => writeln!(f, "Spread: {}", name),
@@ -196,91 +314,190 @@ impl PrettyPrint for StructField {
 }
 
 impl PrettyPrint for Pattern {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Pattern(self), f)?;
         write_indent(indent, f)?;
         match self {
-            Pattern::Ident(s) => writeln!(f, "PatternIdent: {}", s),
+            Pattern::Ident(s) => writeln!(f, "PatternIdent: {}", s)?,
             Pattern::Struct { fields, has_rest } => {
                 writeln!(f, "PatternStruct:")?;
                 for field in fields {
-                    field.pretty_print(indent + 1, f)?;
+                    field.pretty_print(indent + 1, ann, f)?;
                 }
                 if *has_rest {
                     write_indent(indent + 1, f)?;
                     writeln!(f, "Rest: ...")?;
                 }
-                Ok(())
             }
         }
+        ann.post(AnnNode::Pattern(self), f)
     }
 }
 
 impl PrettyPrint for FieldPattern {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         if let Some(pattern) = &self.pattern {
             writeln!(f, "FieldPattern: {}", self.name)?;
-            pattern.pretty_print(indent + 1, f)
+            pattern.pretty_print(indent + 1, ann, f)
         } else {
             writeln!(f, "FieldPattern: {}", self.name)
         }
     }
 }
 
+impl PrettyPrint for MatchPattern {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write_indent(indent, f)?;
+        match self {
+            MatchPattern::Variant {
+                enum_name,
+                variant,
+                bindings,
+            } => {
+                write!(f, "MatchPatternVariant: {}::{}", enum_name, variant)?;
+                if bindings.is_empty() {
+                    writeln!(f)
+                } else {
+                    writeln!(f, "(")?;
+                    for binding in bindings {
+                        binding.pretty_print(indent + 1, ann, f)?;
+                    }
+                    write_indent(indent, f)?;
+                    writeln!(f, ")")
+                }
+            }
+            MatchPattern::Wildcard => writeln!(f, "MatchPatternWildcard"),
+        }
+    }
+}
+
+impl PrettyPrint for BindingPattern {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        _ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write_indent(indent, f)?;
+        match self {
+            BindingPattern::Ident(s) => writeln!(f, "BindingIdent: {}", s),
+            BindingPattern::Wildcard => writeln!(f, "BindingWildcard"),
+        }
+    }
+}
+
+impl PrettyPrint for MatchArm {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::MatchArm(self), f)?;
+        write_indent(indent, f)?;
+        writeln!(f, "Arm:")?;
+        self.pattern.pretty_print(indent + 1, ann, f)?;
+        write_indent(indent + 1, f)?;
+        writeln!(f, "Body:")?;
+        for stmt in &self.body {
+            stmt.pretty_print(indent + 2, ann, f)?;
+        }
+        ann.post(AnnNode::MatchArm(self), f)
+    }
+}
+
 impl PrettyPrint for Automation {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Automation(self), f)?;
         write_indent(indent, f)?;
         writeln!(f, "Automation: {}", self.kind)?;
         write_indent(indent + 1, f)?;
         writeln!(f, "Pattern:")?;
-        self.pattern.pretty_print(indent + 2, f)?;
-        if let Some(filter) = &self.filter {
-            write_indent(indent + 1, f)?;
-            writeln!(f, "Filter:")?;
-            filter.pretty_print(indent + 2, f)?;
-        }
+        self.pattern.pretty_print(indent + 2, ann, f)?;
+        write_indent(indent + 1, f)?;
+        writeln!(f, "Filter:")?;
+        self.filter.pretty_print(indent + 2, ann, f)?;
         write_indent(indent + 1, f)?;
         if self.body.is_empty() {
-            writeln!(f, "Body: (empty)")
+            writeln!(f, "Body: (empty)")?;
         } else {
             writeln!(f, "Body:")?;
             for stmt in &self.body {
-                stmt.pretty_print(indent + 2, f)?;
+                stmt.pretty_print(indent + 2, ann, f)?;
             }
-            Ok(())
         }
+        ann.post(AnnNode::Automation(self), f)
     }
 }
 
 impl PrettyPrint for Program {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Program(self), f)?;
         match self {
-            Program::Automation(auto) => auto.pretty_print(indent, f),
-            Program::Template(tmpl) => tmpl.pretty_print(indent, f),
+            Program::Automation(auto) => auto.pretty_print(indent, ann, f)?,
+            Program::Template(tmpl) => tmpl.pretty_print(indent, ann, f)?,
         }
+        ann.post(AnnNode::Program(self), f)
     }
 }
 
 impl PrettyPrint for Template {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::Template(self), f)?;
         write_indent(indent, f)?;
         writeln!(f, "Template:")?;
         write_indent(indent + 1, f)?;
         writeln!(f, "Params:")?;
         for param in &self.params {
-            param.pretty_print(indent + 2, f)?;
+            param.pretty_print(indent + 2, ann, f)?;
         }
         write_indent(indent + 1, f)?;
         writeln!(f, "Automations:")?;
         for auto in &self.automations {
-            auto.pretty_print(indent + 2, f)?;
+            auto.pretty_print(indent + 2, ann, f)?;
         }
-        Ok(())
+        ann.post(AnnNode::Template(self), f)
     }
 }
 
 impl PrettyPrint for TemplateParam {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        _ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         write!(f, "Param: {}: ", self.name)?;
         write_type_inline(&self.ty, f)?;
@@ -0,0 +1,162 @@
+//! Interactive REPL for the Automations language.
+//!
+//! Wraps the lexer and [`parse_stmt`] in a read-eval loop that can span
+//! multiple lines: [`Repl::feed_line`] buffers raw input until
+//! [`is_complete`] decides the buffered token stream could plausibly be a
+//! whole statement (balanced delimiters, not trailing on a token that
+//! demands more input), then parses and echoes it. Accepted statements
+//! accumulate in [`Repl::history`], so a `let` from one entry stays visible
+//! to later ones without the caller re-typing it.
+//!
+//! This only drives the lexer/parser stage that already feeds `.hda` files
+//! (`automations::ast`, `automations::parser`). The desugar/check/lower
+//! pipeline under [`super::repr`] builds its typed and HIR ASTs from a
+//! separate `repr::ast` that isn't wired up to this AST yet, so there's no
+//! typed result to echo alongside the parsed one - [`Repl::feed_line`]
+//! echoes the parsed statement's [`PrettyPrint`] rendering instead.
+
+use chumsky::Parser;
+
+use super::ast::Spanned;
+use super::ast::Stmt;
+use super::lexer::Token;
+use super::lexer::lexer;
+use super::parser::parse_stmt;
+use super::pretty_print::PrettyPrint;
+
+/// One REPL session. Owns the statement history that gives later entries
+/// access to earlier `let` bindings.
+#[derive(Default)]
+pub struct Repl {
+    history: Vec<Spanned<Stmt>>,
+    pending: String,
+}
+
+/// The outcome of feeding a complete entry to the REPL.
+pub enum ReplOutput {
+    /// The entry parsed; this is its pretty-printed form.
+    Parsed(String),
+    /// Lexing or parsing failed; the entry is discarded, not added to
+    /// [`Repl::history`].
+    Error(String),
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed one line of input. Returns `None` while the buffered entry is
+    /// still incomplete - the caller should print a continuation prompt
+    /// (e.g. `... `) and feed the next line back in. Returns `Some` once a
+    /// complete entry has been lexed and parsed (or failed to).
+    pub fn feed_line(&mut self, line: &str) -> Option<ReplOutput> {
+        if !self.pending.is_empty() {
+            self.pending.push('\n');
+        }
+        self.pending.push_str(line);
+
+        if !is_complete(&self.pending) {
+            return None;
+        }
+
+        let source = std::mem::take(&mut self.pending);
+        Some(self.eval_entry(&source))
+    }
+
+    fn eval_entry(&mut self, source: &str) -> ReplOutput {
+        match parse_stmt(source) {
+            Ok(stmt) => {
+                let rendered = stmt.node.to_pretty_string();
+                self.history.push(stmt);
+                ReplOutput::Parsed(rendered)
+            }
+            Err(errs) => ReplOutput::Error(
+                errs.iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        }
+    }
+
+    /// Statements accepted so far, in entry order.
+    pub fn history(&self) -> &[Spanned<Stmt>] {
+        &self.history
+    }
+}
+
+/// Whether `source`'s token stream looks like a complete statement: every
+/// opened delimiter is closed, and the last token isn't one that demands
+/// more input (a binary operator, `=`, `,`, a keyword that always starts a
+/// construct, etc.).
+///
+/// A lex error is treated as complete - more input won't fix an
+/// unterminated string or bad unit suffix, so the caller is better off
+/// dispatching to `parse_stmt` and showing the real error immediately
+/// rather than waiting forever for a line that closes it.
+fn is_complete(source: &str) -> bool {
+    let tokens = match lexer().parse(source).into_result() {
+        Ok(tokens) => tokens,
+        Err(_) => return true,
+    };
+
+    let mut depth: i32 = 0;
+    for (token, _) in &tokens {
+        match token {
+            Token::LParen | Token::LBrace | Token::LBracket => depth += 1,
+            Token::RParen | Token::RBrace | Token::RBracket => depth -= 1,
+            _ => {}
+        }
+    }
+    if depth > 0 {
+        return false;
+    }
+
+    match tokens.last() {
+        // Whitespace-only (or empty) input: keep waiting for a real entry.
+        None => false,
+        Some((token, _)) => is_terminal(token),
+    }
+}
+
+/// Whether `token` can legally be the last token of a complete statement
+/// or expression. Binary/assignment operators, field access, separators,
+/// and keywords that always introduce a following construct all demand
+/// more input.
+fn is_terminal(token: &Token) -> bool {
+    !matches!(
+        token,
+        Token::Plus
+            | Token::Minus
+            | Token::Star
+            | Token::Slash
+            | Token::Percent
+            | Token::Eq
+            | Token::Ne
+            | Token::Lt
+            | Token::Le
+            | Token::Gt
+            | Token::Ge
+            | Token::And
+            | Token::Or
+            | Token::Not
+            | Token::Question
+            | Token::Dot
+            | Token::DotDotDot
+            | Token::Assign
+            | Token::FatArrow
+            | Token::Comma
+            | Token::Colon
+            | Token::ColonColon
+            | Token::Let
+            | Token::If
+            | Token::Else
+            | Token::For
+            | Token::In
+            | Token::Await
+            | Token::Inherit
+            | Token::Match
+            | Token::Return
+    )
+}
@@ -0,0 +1,149 @@
+//! A two-phase, width-aware document layout engine for pretty-printing.
+//!
+//! Mirrors the classic Wadler/Prettier "doc" IR: a node first lowers itself
+//! into a [`Doc`] tree of `Text`, `Concat`, `Group`, `Indent`, and `Line`
+//! nodes, then [`Doc::render`] walks that tree with a target column budget,
+//! measuring each `Group`'s flattened width and emitting it inline if it
+//! fits in the remaining columns, or breaking it at its `Line` points
+//! otherwise. This lets leaf-heavy trees (a two-element `List` of `Int`s, a
+//! three-field `StructLit`) collapse onto one line while bigger ones still
+//! wrap legibly - see [`super::pretty_print::CompactPrint`] for the trait
+//! that builds a `Doc` from a node.
+
+use std::rc::Rc;
+
+/// An intermediate layout document.
+#[derive(Debug, Clone)]
+pub enum Doc {
+    /// Literal text with no embedded newlines.
+    Text(Rc<str>),
+    /// Zero or more docs rendered back to back.
+    Concat(Vec<Doc>),
+    /// A unit rendered flat (its `Line`s become spaces) if its flattened
+    /// width fits in the remaining columns, otherwise broken (its `Line`s
+    /// become newlines).
+    Group(Rc<Doc>),
+    /// Increase the indent level used by any `Line` broken inside `self`.
+    Indent(Rc<Doc>),
+    /// A space when flattened, a newline plus the current indent when
+    /// broken.
+    Line,
+}
+
+impl Doc {
+    pub fn text(s: impl Into<String>) -> Doc {
+        Doc::Text(Rc::from(s.into()))
+    }
+
+    pub fn concat(docs: impl IntoIterator<Item = Doc>) -> Doc {
+        Doc::Concat(docs.into_iter().collect())
+    }
+
+    pub fn group(doc: Doc) -> Doc {
+        Doc::Group(Rc::new(doc))
+    }
+
+    pub fn indent(doc: Doc) -> Doc {
+        Doc::Indent(Rc::new(doc))
+    }
+
+    pub fn line() -> Doc {
+        Doc::Line
+    }
+
+    /// Join `docs` with a literal `sep` (e.g. `","`) followed by a `Line`,
+    /// the shape used for `Call` args, `List`/`Tuple` items, `StructLit`
+    /// fields, and `Block` stmts - each collapses to `a, b, c` when flat and
+    /// breaks to one item per line, trailing `sep`, when it doesn't fit.
+    pub fn join(docs: impl IntoIterator<Item = Doc>, sep: &str) -> Doc {
+        let mut items = docs.into_iter();
+        let mut out = Vec::new();
+        if let Some(first) = items.next() {
+            out.push(first);
+            for doc in items {
+                out.push(Doc::text(sep));
+                out.push(Doc::Line);
+                out.push(doc);
+            }
+        }
+        Doc::Concat(out)
+    }
+
+    /// `prefix` followed by `open`, the (comma-joined) `items` indented on
+    /// their own lines if they don't fit flat, then `close` - e.g.
+    /// `List: [type: Int] [1, 2]` or, broken, `List: [type: Int] [\n  1,\n  2\n]`.
+    /// Renders as `{prefix}{open}{close}` with no interior `Line` when
+    /// `items` is empty, so an empty list/struct/block never breaks.
+    pub fn bracketed(prefix: impl Into<String>, open: &str, items: Vec<Doc>, close: &str) -> Doc {
+        let prefix = prefix.into();
+        if items.is_empty() {
+            return Doc::text(format!("{prefix}{open}{close}"));
+        }
+        Doc::group(Doc::concat([
+            Doc::text(format!("{prefix}{open}")),
+            Doc::indent(Doc::concat([Doc::line(), Doc::join(items, ",")])),
+            Doc::line(),
+            Doc::text(close.to_string()),
+        ]))
+    }
+
+    fn flat_width(&self) -> usize {
+        match self {
+            Doc::Text(s) => s.chars().count(),
+            Doc::Concat(docs) => docs.iter().map(Doc::flat_width).sum(),
+            Doc::Group(doc) | Doc::Indent(doc) => doc.flat_width(),
+            Doc::Line => 1,
+        }
+    }
+
+    /// Render with a target column budget (e.g. 100).
+    pub fn render(&self, width: usize) -> String {
+        let mut out = String::new();
+        let mut column = 0;
+        render_node(self, width, 0, false, &mut column, &mut out);
+        out
+    }
+}
+
+fn render_node(
+    doc: &Doc,
+    width: usize,
+    indent: usize,
+    flat: bool,
+    column: &mut usize,
+    out: &mut String,
+) {
+    match doc {
+        Doc::Text(s) => {
+            out.push_str(s);
+            *column += s.chars().count();
+        }
+        Doc::Concat(docs) => {
+            for d in docs {
+                render_node(d, width, indent, flat, column, out);
+            }
+        }
+        Doc::Group(inner) => {
+            let fits = flat || column.saturating_add(inner.flat_width()) <= width;
+            render_node(inner, width, indent, fits, column, out);
+        }
+        Doc::Indent(inner) => {
+            render_node(inner, width, indent + 1, flat, column, out);
+        }
+        Doc::Line => {
+            if flat {
+                out.push(' ');
+                *column += 1;
+            } else {
+                out.push('\n');
+                for _ in 0..indent {
+                    out.push_str("  ");
+                }
+                *column = indent * 2;
+            }
+        }
+    }
+}
+
+/// The default column budget used by [`super::pretty_print::CompactPrint::to_compact_string`].
+pub const DEFAULT_WIDTH: usize = 100;
@@ -43,9 +43,27 @@ pub enum HirProgram {
 }
 
 /// A basic block: a linear sequence of instructions followed by a terminator.
+///
+/// `params` are this block's SSA block parameters (the phi-equivalent):
+/// every `Terminator::Jump`/`Terminator::Branch` edge that targets this block
+/// supplies one argument per parameter, in order. A value produced along
+/// more than one control-flow path (an `if`/`else` result, a short-circuit
+/// `&&`/`||` result) is modeled as a block parameter of the merge block
+/// rather than as several instructions writing the same `Tmp`.
+///
+/// This is deliberately block parameters rather than a dedicated
+/// `Op::Phi { incomings: Vec<(BlockId, Tmp)> }` instruction: a phi still
+/// needs the predecessor's id to know which incoming `Tmp` to pick at
+/// runtime, which in a block-parameter form falls out of "which edge was
+/// taken to get here" for free, instead of every phi needing its own
+/// `BlockId`-keyed lookup. Passes that need "is this param constant"
+/// (folding) or "what does this param read" (liveness) already get that by
+/// treating `params` as ordinary per-edge `Tmp` arguments - see
+/// [`super::hir_visit::terminator_operands`] for the read side.
 #[derive(Debug, Clone)]
 pub struct BasicBlock {
     pub id: BlockId,
+    pub params: Vec<Tmp>,
     pub instructions: Vec<Instruction>,
     pub terminator: Terminator,
 }
@@ -56,6 +74,11 @@ pub struct Instruction {
     pub dst: Tmp,
     pub op: Op,
     pub ty: Ty,
+    /// Source span this instruction was lowered from, used to point
+    /// compile-time diagnostics (e.g. constant-folded divide-by-zero) at the
+    /// original `.hda` source. `None` for instructions with no direct source
+    /// origin (e.g. those synthesized by earlier passes).
+    pub span: Option<std::ops::Range<usize>>,
 }
 
 /// Operations that compute values.
@@ -110,6 +133,27 @@ pub enum Op {
         args: Vec<Tmp>,
     },
 
+    /// Discriminant test for a `match` arm: whether `value` is the given
+    /// variant of `enum_name`. Produces a `Bool`.
+    VariantTest {
+        value: Tmp,
+        enum_name: String,
+        variant: String,
+    },
+    /// Positional payload extraction for a `match` arm binding. Unlike
+    /// `Op::Field`, which is only ever emitted against `Value::Struct`, this
+    /// reads the `index`'th element of a `Value::Variant`'s `args`.
+    VariantField {
+        base: Tmp,
+        index: usize,
+    },
+    /// The variant name of a `Value::Variant` (e.g. `"LightStateChanged"`),
+    /// produced as a `String`. Decision-tree `match` lowering computes this
+    /// once per scrutinee, then dispatches to each arm via a `BinOp::Eq`
+    /// against a `Op::ConstString` rather than re-deriving `VariantTest`'s
+    /// shape check per arm.
+    Discriminant(Tmp),
+
     // === Collections ===
     /// Empty list (from MutableList desugaring).
     EmptyList,
@@ -120,6 +164,11 @@ pub enum Op {
         list: Tmp,
         value: Tmp,
     },
+    /// Append every element of `value` onto `list` (list `+=` list).
+    ListExtend {
+        list: Tmp,
+        value: Tmp,
+    },
     /// Create an iterator from a collection.
     IterInit(Tmp),
 
@@ -183,13 +232,16 @@ pub enum HirStructField {
 /// Block terminator — exactly one per basic block.
 #[derive(Debug, Clone)]
 pub enum Terminator {
-    /// Unconditional jump.
-    Jump(BlockId),
-    /// Conditional branch.
+    /// Unconditional jump, supplying one argument per target block param.
+    Jump(BlockId, Vec<Tmp>),
+    /// Conditional branch. `then_args`/`else_args` supply the target block's
+    /// params along whichever edge is taken.
     Branch {
         cond: Tmp,
         then_block: BlockId,
+        then_args: Vec<Tmp>,
         else_block: BlockId,
+        else_args: Vec<Tmp>,
     },
     /// Return from automation.
     Return(Tmp),
@@ -202,4 +254,16 @@ pub enum Terminator {
         body: BlockId,
         exit: BlockId,
     },
+    /// Marks a control-flow path that can never execute - e.g. the
+    /// fallthrough of a non-exhaustive `match` with no wildcard arm, which
+    /// the checker already reports as an error. Lets later passes detect
+    /// dead paths explicitly instead of inferring them from a dummy value.
+    Unreachable,
+    /// Drop an owned value before continuing to `target`, inserted by
+    /// [`super::hir_drop_elaborate`] once a `Tmp`'s liveness analysis shows
+    /// it's no longer needed along this path. Always precedes whatever
+    /// terminator `target`'s block starts with - several `Drop`s can chain
+    /// before the original `Jump`/`Branch`/`Return`/`IterNext` they were
+    /// spliced in front of.
+    Drop { value: Tmp, target: BlockId },
 }
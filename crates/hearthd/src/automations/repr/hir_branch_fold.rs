@@ -0,0 +1,181 @@
+//! Branch-folding pass over HIR.
+//!
+//! Complements [`super::hir_fold`]: once [`super::hir_fold::fold_program`]
+//! has collapsed a condition to `Op::ConstBool`, a `Terminator::Branch` on
+//! that value can be resolved at compile time. This pass walks each block's
+//! `Branch` terminator and, if its `cond` traces to a `const_bool` producer
+//! within the same block, rewrites the terminator to an unconditional
+//! `Jump` to the taken block, dropping the other edge so a later DCE pass
+//! can prune it once it becomes unreachable.
+
+use super::hir::*;
+
+/// Resolve `Branch` terminators whose condition is a compile-time constant
+/// into unconditional `Jump`s.
+pub fn branch_fold_program(program: &mut HirProgram) {
+    match program {
+        HirProgram::Automation(automation) => branch_fold_automation(automation),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                branch_fold_automation(automation);
+            }
+        }
+    }
+}
+
+fn branch_fold_automation(automation: &mut HirAutomation) {
+    for block in &mut automation.blocks {
+        branch_fold_block(block);
+    }
+}
+
+fn branch_fold_block(block: &mut BasicBlock) {
+    let Terminator::Branch {
+        cond,
+        then_block,
+        then_args,
+        else_block,
+        else_args,
+    } = &block.terminator
+    else {
+        return;
+    };
+
+    let Some(value) = const_bool_of(&block.instructions, *cond) else {
+        return;
+    };
+
+    block.terminator = if value {
+        Terminator::Jump(*then_block, then_args.clone())
+    } else {
+        Terminator::Jump(*else_block, else_args.clone())
+    };
+}
+
+/// Whether `tmp` is produced, within this block's own instructions, by an
+/// `Op::ConstBool`.
+fn const_bool_of(instructions: &[Instruction], tmp: Tmp) -> Option<bool> {
+    instructions
+        .iter()
+        .find(|instr| instr.dst == tmp)
+        .and_then(|instr| match instr.op {
+            Op::ConstBool(b) => Some(b),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty: Ty::Bool,
+            span: None,
+        }
+    }
+
+    fn automation(blocks: Vec<BasicBlock>) -> HirAutomation {
+        HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks,
+        }
+    }
+
+    #[test]
+    fn folds_branch_on_true_to_jump_then() {
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true))],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: vec![Tmp(0)],
+                    else_block: BlockId(2),
+                    else_args: Vec::new(),
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(0)),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(0)),
+            },
+        ]));
+
+        branch_fold_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            &automation.blocks[0].terminator,
+            Terminator::Jump(BlockId(1), args) if *args == vec![Tmp(0)]
+        ));
+    }
+
+    #[test]
+    fn folds_branch_on_false_to_jump_else() {
+        let mut program = HirProgram::Automation(automation(vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![instr(0, Op::ConstBool(false))],
+            terminator: Terminator::Branch {
+                cond: Tmp(0),
+                then_block: BlockId(1),
+                then_args: Vec::new(),
+                else_block: BlockId(2),
+                else_args: vec![Tmp(0)],
+            },
+        }]));
+
+        branch_fold_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            &automation.blocks[0].terminator,
+            Terminator::Jump(BlockId(2), args) if *args == vec![Tmp(0)]
+        ));
+    }
+
+    #[test]
+    fn leaves_non_constant_branch_untouched() {
+        let mut program = HirProgram::Automation(automation(vec![BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions: vec![instr(0, Op::Field { base: Tmp(9), field: "x".into() })],
+            terminator: Terminator::Branch {
+                cond: Tmp(0),
+                then_block: BlockId(1),
+                then_args: Vec::new(),
+                else_block: BlockId(2),
+                else_args: Vec::new(),
+            },
+        }]));
+
+        branch_fold_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Branch { .. }
+        ));
+    }
+}
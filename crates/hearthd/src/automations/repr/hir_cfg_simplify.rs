@@ -0,0 +1,357 @@
+//! CFG cleanup pass: prune unreachable blocks and collapse goto chains.
+//!
+//! [`super::lower`] leaves blocks behind that never execute - notably the
+//! `fresh_block()` every `Return`/`break` switches to afterward, so
+//! statements lowered after one (dead in practice, but still legal to write)
+//! land somewhere well-formed rather than reopening an already-terminated
+//! block. Those blocks, plus whatever [`super::hir_branch_fold`] strands
+//! once a `Branch` resolves to a constant, accumulate as pure overhead for
+//! the interpreter and noise for anything inspecting the IR (`to_dot`,
+//! snapshot tests).
+//!
+//! This pass does two things, to a fixpoint:
+//!
+//! 1. Prunes blocks unreachable from the entry block (`bb0`) - the same walk
+//!    [`super::hir_dce`] already does internally, duplicated here (rather
+//!    than exposed from there) since it's a natural companion to goto-chain
+//!    collapsing and this pass is meant to be usable on its own.
+//! 2. Collapses a `Jump(A -> B)` edge where `B` has exactly one incoming
+//!    edge in the whole CFG: splices `B`'s instructions and terminator
+//!    directly into `A` (substituting `B`'s block parameters for the
+//!    `Jump`'s arguments) and removes `B` entirely.
+//!
+//! Either step can expose more opportunities for the other - collapsing a
+//! chain can drop a block's only remaining predecessor, and pruning can
+//! leave a former two-predecessor block down to one - so this re-runs both
+//! to a fixpoint rather than a single pass of each.
+//!
+//! Predecessor counts are tracked as a plain `HashMap<BlockId, usize>`
+//! rather than a `SmallVec`-backed predecessor list per block: real
+//! automations' blocks rarely have more than one or two predecessors, but
+//! this pass only ever needs the *count*, not the list of who they are, so
+//! there's nothing to store per predecessor in the first place.
+
+use std::collections::HashMap;
+
+use super::hir::*;
+use super::hir_visit::{rewrite_op_operands, rewrite_terminator_operands};
+
+/// Run CFG simplification over every automation in `program`.
+pub fn cfg_simplify_program(program: &mut HirProgram) {
+    match program {
+        HirProgram::Automation(automation) => cfg_simplify_automation(automation),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                cfg_simplify_automation(automation);
+            }
+        }
+    }
+}
+
+fn cfg_simplify_automation(automation: &mut HirAutomation) {
+    loop {
+        let pruned = prune_unreachable_blocks(automation);
+        let collapsed = collapse_goto_chains(automation);
+        if !pruned && !collapsed {
+            break;
+        }
+    }
+}
+
+fn successors(terminator: &Terminator) -> Vec<BlockId> {
+    match terminator {
+        Terminator::Jump(target, _) => vec![*target],
+        Terminator::Branch {
+            then_block,
+            else_block,
+            ..
+        } => vec![*then_block, *else_block],
+        Terminator::Return(_) => vec![],
+        Terminator::IterNext { body, exit, .. } => vec![*body, *exit],
+        Terminator::Unreachable => vec![],
+        Terminator::Drop { target, .. } => vec![*target],
+    }
+}
+
+/// Remove blocks not reachable from `bb0`. Returns whether anything was
+/// removed.
+fn prune_unreachable_blocks(automation: &mut HirAutomation) -> bool {
+    let mut reachable = std::collections::HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    reachable.insert(BlockId(0));
+    queue.push_back(BlockId(0));
+
+    while let Some(id) = queue.pop_front() {
+        let Some(block) = automation.blocks.iter().find(|b| b.id == id) else {
+            continue;
+        };
+        for succ in successors(&block.terminator) {
+            if reachable.insert(succ) {
+                queue.push_back(succ);
+            }
+        }
+    }
+
+    let before = automation.blocks.len();
+    automation.blocks.retain(|b| reachable.contains(&b.id));
+    automation.blocks.len() != before
+}
+
+/// The number of incoming edges for every `BlockId` targeted by some
+/// terminator in `automation` (an edge, not a predecessor block - a
+/// `Branch` whose `then_block` and `else_block` are the same counts twice,
+/// since splicing that target into either predecessor would drop the
+/// other).
+fn incoming_edge_counts(automation: &HirAutomation) -> HashMap<BlockId, usize> {
+    let mut counts = HashMap::new();
+    for block in &automation.blocks {
+        for succ in successors(&block.terminator) {
+            *counts.entry(succ).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+/// Splice every `Jump(A -> B)` where `B` has exactly one incoming edge
+/// directly into `A`, removing `B`. Returns whether anything was collapsed.
+/// One collapse invalidates the edge counts the rest rely on, so this
+/// restarts the scan after each successful splice rather than trying to
+/// patch them up incrementally.
+fn collapse_goto_chains(automation: &mut HirAutomation) -> bool {
+    let mut changed = false;
+
+    loop {
+        let counts = incoming_edge_counts(automation);
+        let mut collapsed_this_round = false;
+
+        for index in 0..automation.blocks.len() {
+            let Terminator::Jump(target, args) = &automation.blocks[index].terminator else {
+                continue;
+            };
+            let target = *target;
+            let args = args.clone();
+
+            // Never fold the entry block away - it has to stay `bb0`.
+            if target == BlockId(0) || target == automation.blocks[index].id {
+                continue;
+            }
+            if counts.get(&target).copied().unwrap_or(0) != 1 {
+                continue;
+            }
+            let Some(target_index) = automation.blocks.iter().position(|b| b.id == target) else {
+                continue;
+            };
+
+            let target_block = automation.blocks.remove(target_index);
+            let index = if target_index < index {
+                index - 1
+            } else {
+                index
+            };
+
+            let substitution: HashMap<Tmp, Tmp> = target_block
+                .params
+                .iter()
+                .copied()
+                .zip(args.iter().copied())
+                .collect();
+
+            let mut instructions = target_block.instructions;
+            for instr in &mut instructions {
+                rewrite_op_operands(&mut instr.op, |tmp| {
+                    substitution.get(&tmp).copied().unwrap_or(tmp)
+                });
+            }
+            let mut terminator = target_block.terminator;
+            rewrite_terminator_operands(&mut terminator, |tmp| {
+                substitution.get(&tmp).copied().unwrap_or(tmp)
+            });
+
+            automation.blocks[index].instructions.extend(instructions);
+            automation.blocks[index].terminator = terminator;
+
+            collapsed_this_round = true;
+            changed = true;
+            break;
+        }
+
+        if !collapsed_this_round {
+            break;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty: Ty::Int,
+            span: None,
+        }
+    }
+
+    fn automation(blocks: Vec<BasicBlock>) -> HirAutomation {
+        HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks,
+        }
+    }
+
+    #[test]
+    fn prunes_block_unreachable_from_entry() {
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1))],
+                terminator: Terminator::Return(Tmp(0)),
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(0)),
+            },
+        ]));
+
+        cfg_simplify_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert_eq!(automation.blocks.len(), 1);
+        assert_eq!(automation.blocks[0].id, BlockId(0));
+    }
+
+    #[test]
+    fn collapses_single_predecessor_goto_chain() {
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1))],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(2))],
+                terminator: Terminator::Return(Tmp(1)),
+            },
+        ]));
+
+        cfg_simplify_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert_eq!(automation.blocks.len(), 1);
+        assert_eq!(automation.blocks[0].id, BlockId(0));
+        assert_eq!(automation.blocks[0].instructions.len(), 2);
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(1))
+        ));
+    }
+
+    #[test]
+    fn substitutes_block_params_with_jump_args_when_collapsing() {
+        // bb0 jumps to bb1(%0), whose only param %1 is returned directly -
+        // after collapsing, the return should read %0, not the stale %1.
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1))],
+                terminator: Terminator::Jump(BlockId(1), vec![Tmp(0)]),
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: vec![Tmp(1)],
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(1)),
+            },
+        ]));
+
+        cfg_simplify_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert_eq!(automation.blocks.len(), 1);
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+
+    #[test]
+    fn does_not_collapse_a_block_with_two_predecessors() {
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true))],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: vec![],
+                    else_block: BlockId(1),
+                    else_args: vec![],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(0)),
+            },
+        ]));
+
+        cfg_simplify_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        // Both edges target bb1, so it has two incoming edges and can't be
+        // folded into either predecessor alone.
+        assert_eq!(automation.blocks.len(), 2);
+    }
+
+    #[test]
+    fn never_collapses_the_entry_block_away() {
+        // bb1 is scanned first (vec order) and would otherwise fold bb0
+        // into itself since bb0's only predecessor is bb1 - but bb0 is the
+        // entry block, so it has to survive under its own id regardless.
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1))],
+                terminator: Terminator::Jump(BlockId(0), vec![]),
+            },
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Jump(BlockId(1), vec![]),
+            },
+        ]));
+
+        cfg_simplify_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(automation.blocks.iter().any(|b| b.id == BlockId(0)));
+    }
+}
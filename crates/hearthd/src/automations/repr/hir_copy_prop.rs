@@ -0,0 +1,212 @@
+//! Copy-propagation pass over HIR.
+//!
+//! HIR values merged across control-flow paths (`if`/`else` results,
+//! short-circuit `&&`/`||` results, loop-carried iteration values) are block
+//! parameters rather than `Op::Copy`, so a `Tmp` can have more than one
+//! *reaching* value without having more than one *definition site* — true
+//! SSA. Where a plain `Op::Copy` does appear (e.g. hand-written or
+//! future-desugared HIR) and it is a temporary's only definition anywhere in
+//! the automation, every later use of it can be rewritten to read the copy's
+//! source directly. The copy instruction itself is left in place — it
+//! becomes dead and is removed by a subsequent [`super::hir_dce`] pass.
+//!
+//! The sole-definition check still guards against the one remaining way a
+//! `Tmp` can be written from more than one place: hand-built or
+//! not-yet-SSA-converted HIR that reuses a destination across blocks.
+
+use std::collections::HashMap;
+
+use super::hir::*;
+use super::hir_visit::{rewrite_op_operands, rewrite_terminator_operands, transform_instructions};
+
+/// Rewrite uses of copy-only temporaries to their ultimate source.
+pub fn copy_prop_program(program: &mut HirProgram) {
+    match program {
+        HirProgram::Automation(automation) => copy_prop_automation(automation),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                copy_prop_automation(automation);
+            }
+        }
+    }
+}
+
+fn copy_prop_automation(automation: &mut HirAutomation) {
+    let mut def_count: HashMap<Tmp, usize> = HashMap::new();
+    for block in &automation.blocks {
+        for instr in &block.instructions {
+            *def_count.entry(instr.dst).or_insert(0) += 1;
+        }
+    }
+
+    let mut sources: HashMap<Tmp, Tmp> = HashMap::new();
+    for block in &automation.blocks {
+        for instr in &block.instructions {
+            if let Op::Copy(src) = instr.op {
+                if def_count.get(&instr.dst) == Some(&1) {
+                    sources.insert(instr.dst, src);
+                }
+            }
+        }
+    }
+    if sources.is_empty() {
+        return;
+    }
+
+    // Resolve chains of copies (a copy of a copy) to their ultimate source.
+    let resolve = |mut tmp: Tmp| {
+        let mut hops = 0;
+        while let Some(&src) = sources.get(&tmp) {
+            tmp = src;
+            hops += 1;
+            if hops > sources.len() {
+                break; // guard against a cycle, which should never occur
+            }
+        }
+        tmp
+    };
+
+    transform_instructions(
+        automation,
+        |mut instr| {
+            rewrite_op_operands(&mut instr.op, &resolve);
+            Some(instr)
+        },
+        |mut terminator| {
+            rewrite_terminator_operands(&mut terminator, &resolve);
+            terminator
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty: Ty::Int,
+            span: None,
+        }
+    }
+
+    fn automation(blocks: Vec<BasicBlock>) -> HirAutomation {
+        HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks,
+        }
+    }
+
+    #[test]
+    fn propagates_sole_copy_to_later_use() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(42)),
+                instr(1, Op::Copy(Tmp(0))),
+                instr(2, Op::Neg(Tmp(1))),
+            ],
+            Terminator::Return(Tmp(1)),
+        )]));
+
+        copy_prop_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[0].instructions[2].op,
+            Op::Neg(Tmp(0))
+        ));
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+
+    #[test]
+    fn follows_chain_of_copies() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(1)),
+                instr(1, Op::Copy(Tmp(0))),
+                instr(2, Op::Copy(Tmp(1))),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        copy_prop_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+
+    #[test]
+    fn leaves_merge_point_copy_untouched() {
+        // `%2` is written by a `Copy` in two different blocks — not a sole
+        // definition, so it must not be rewritten. Real lowering now threads
+        // merge values as block params instead of reusing a destination like
+        // this, but hand-built (or not-yet-SSA-converted) HIR can still hit
+        // this shape, and the pass must stay sound against it.
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1))],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: Vec::new(),
+                    else_block: BlockId(2),
+                    else_args: Vec::new(),
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(2, Op::Copy(Tmp(0)))],
+                terminator: Terminator::Jump(BlockId(3), Vec::new()),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(2, Op::Copy(Tmp(0)))],
+                terminator: Terminator::Jump(BlockId(3), Vec::new()),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: Vec::new(),
+                instructions: vec![],
+                terminator: Terminator::Return(Tmp(2)),
+            },
+        ]));
+
+        copy_prop_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[3].terminator,
+            Terminator::Return(Tmp(2))
+        ));
+    }
+
+    fn block(instructions: Vec<Instruction>, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions,
+            terminator,
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Dead-code elimination and unreachable-block pruning for HIR.
+//!
+//! Runs as a separate pass from [`super::hir_fold`] so it can be toggled
+//! independently (e.g. disabled to keep unoptimized HIR for debugging).
+//! First prunes `BasicBlock`s unreachable from the entry block (`bb0`) by a
+//! BFS over `Terminator::Jump`/`Branch`/`IterNext` edges. Then computes the
+//! live `Tmp` set by a backward fixpoint seeded from terminator operands and
+//! from instructions with observable side effects, and deletes every other
+//! dead instruction via [`super::hir_visit::transform_instructions`].
+//!
+//! This liveness fixpoint is simpler than the classic per-block `use`/`def`/
+//! `live_in`/`live_out` dataflow: because every `Tmp` is a globally unique
+//! SSA definition, "is this `Tmp` live" doesn't depend on which block asks -
+//! a single global `live` set, repeatedly closed over operands of
+//! already-live instructions regardless of block order, converges to the
+//! same answer. Block-local `live_in`/`live_out` sets would only earn their
+//! keep if two different `Tmp`s could share a destination across blocks,
+//! which SSA here rules out.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use super::hir::*;
+use super::hir_visit::{has_side_effects, op_operands, terminator_operands, transform_instructions};
+
+/// Whether to run the DCE pass. Exposed so callers (and tests) can compare
+/// optimized vs. unoptimized HIR.
+pub fn dce_program(program: &mut HirProgram, enabled: bool) {
+    if !enabled {
+        return;
+    }
+    match program {
+        HirProgram::Automation(automation) => dce_automation(automation),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                dce_automation(automation);
+            }
+        }
+    }
+}
+
+fn dce_automation(automation: &mut HirAutomation) {
+    prune_unreachable_blocks(automation);
+    eliminate_dead_instructions(automation);
+}
+
+/// Remove blocks not reachable from `bb0` via jumps, branches, or loop edges.
+fn prune_unreachable_blocks(automation: &mut HirAutomation) {
+    let mut reachable = HashSet::new();
+    let mut queue = VecDeque::new();
+    reachable.insert(BlockId(0));
+    queue.push_back(BlockId(0));
+
+    while let Some(id) = queue.pop_front() {
+        let Some(block) = automation.blocks.iter().find(|b| b.id == id) else {
+            continue;
+        };
+        for succ in successors(&block.terminator) {
+            if reachable.insert(succ) {
+                queue.push_back(succ);
+            }
+        }
+    }
+
+    automation.blocks.retain(|b| reachable.contains(&b.id));
+}
+
+fn successors(terminator: &Terminator) -> Vec<BlockId> {
+    match terminator {
+        Terminator::Jump(target, _) => vec![*target],
+        Terminator::Branch {
+            then_block,
+            else_block,
+            ..
+        } => vec![*then_block, *else_block],
+        Terminator::Return(_) => vec![],
+        Terminator::IterNext { body, exit, .. } => vec![*body, *exit],
+        Terminator::Unreachable => vec![],
+        Terminator::Drop { target, .. } => vec![*target],
+    }
+}
+
+fn eliminate_dead_instructions(automation: &mut HirAutomation) {
+    // Seed liveness from terminators and side-effecting instructions, then
+    // propagate backward to a fixpoint.
+    let mut live: HashSet<Tmp> = HashSet::new();
+    for block in &automation.blocks {
+        live.extend(terminator_operands(&block.terminator));
+        for instr in &block.instructions {
+            if has_side_effects(&instr.op) {
+                live.insert(instr.dst);
+            }
+        }
+    }
+
+    loop {
+        let mut changed = false;
+        for block in &automation.blocks {
+            for instr in &block.instructions {
+                if live.contains(&instr.dst) {
+                    for operand in op_operands(&instr.op) {
+                        changed |= live.insert(operand);
+                    }
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    transform_instructions(
+        automation,
+        |instr| (live.contains(&instr.dst) || has_side_effects(&instr.op)).then_some(instr),
+        |terminator| terminator,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty: Ty::Int,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn removes_unused_pure_instruction() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::ConstInt(1)),
+                    instr(1, Op::ConstInt(2)), // dead: never read
+                ],
+                terminator: Terminator::Return(Tmp(0)),
+            }],
+        };
+
+        dce_automation(&mut automation);
+
+        assert_eq!(automation.blocks[0].instructions.len(), 1);
+        assert_eq!(automation.blocks[0].instructions[0].dst, Tmp(0));
+    }
+
+    #[test]
+    fn keeps_side_effecting_instruction_even_if_dead() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::ConstInt(1)),
+                    instr(
+                        1,
+                        Op::Call {
+                            name: "log".into(),
+                            args: vec![Tmp(0)],
+                        },
+                    ),
+                ],
+                terminator: Terminator::Return(Tmp(0)),
+            }],
+        };
+
+        dce_automation(&mut automation);
+
+        assert_eq!(automation.blocks[0].instructions.len(), 2);
+    }
+
+    #[test]
+    fn prunes_unreachable_block() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![instr(0, Op::ConstInt(1))],
+                    terminator: Terminator::Return(Tmp(0)),
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: Vec::new(),
+                    instructions: vec![],
+                    terminator: Terminator::Return(Tmp(0)),
+                },
+            ],
+        };
+
+        prune_unreachable_blocks(&mut automation);
+
+        assert_eq!(automation.blocks.len(), 1);
+        assert_eq!(automation.blocks[0].id, BlockId(0));
+    }
+
+    #[test]
+    fn disabled_flag_is_a_no_op() {
+        let mut program = HirProgram::Automation(HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1)), instr(1, Op::ConstInt(2))],
+                terminator: Terminator::Return(Tmp(0)),
+            }],
+        });
+
+        dce_program(&mut program, false);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert_eq!(automation.blocks[0].instructions.len(), 2);
+    }
+}
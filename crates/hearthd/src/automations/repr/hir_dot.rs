@@ -0,0 +1,254 @@
+//! GraphViz/DOT rendering of the HIR control-flow graph.
+//!
+//! Renders an [`HirAutomation`]'s basic blocks as a `digraph`: each block
+//! becomes a node labeled with its instructions (`dst = op [ty]`, reusing
+//! the same rendering as [`super::hir_pretty_print`]) and a summary of its
+//! terminator. Edges come from the terminator: `Jump` emits one unlabeled
+//! edge, `Branch` emits two edges labeled `then`/`else`, `IterNext` emits
+//! two edges labeled `body`/`exit`, and `Return` emits none. Intended for ad
+//! hoc debugging (pipe into `dot -Tsvg`) and test snapshots - nothing here
+//! is consumed by the interpreter.
+
+use std::fmt::Write as _;
+
+use super::hir::*;
+
+impl HirProgram {
+    /// Render every automation in `self` as its own GraphViz `digraph`,
+    /// concatenated with a blank line between them.
+    pub fn to_dot(&self) -> String {
+        match self {
+            HirProgram::Automation(automation) => automation.to_dot(),
+            HirProgram::Template { automations, .. } => automations
+                .iter()
+                .map(HirAutomation::to_dot)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+impl HirAutomation {
+    /// Render this automation's basic blocks as a single GraphViz `digraph`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "digraph {} {{", self.kind).unwrap();
+        writeln!(out, "  node [shape=box, fontname=monospace];").unwrap();
+        for block in &self.blocks {
+            writeln!(
+                out,
+                "  {} [label=\"{}\"];",
+                block.id,
+                escape(&node_label(block))
+            )
+            .unwrap();
+        }
+        for block in &self.blocks {
+            write_edges(block, &mut out);
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+/// The label text for a block's node: its id, its instructions (one per
+/// line, `dst = op [ty]`), and its terminator's own summary line.
+fn node_label(block: &BasicBlock) -> String {
+    let mut lines = vec![format!("{}:", block.id)];
+    for instr in &block.instructions {
+        lines.push(format!("{} = {} [{}]", instr.dst, op_summary(&instr.op), instr.ty));
+    }
+    lines.push(terminator_summary(&block.terminator));
+    lines.join("\\l") + "\\l"
+}
+
+/// A one-line rendering of `op`, independent of [`super::hir_pretty_print`]
+/// (which writes straight to a `Formatter` rather than returning a `String`).
+fn op_summary(op: &Op) -> String {
+    match op {
+        Op::ConstInt(n) => format!("const_int {n}"),
+        Op::ConstFloat(n) => format!("const_float {n}"),
+        Op::ConstString(s) => format!("const_string \"{s}\""),
+        Op::ConstBool(b) => format!("const_bool {b}"),
+        Op::ConstUnit { value, unit } => format!("const_unit {value}{unit}"),
+        Op::Unit => "unit".to_string(),
+        Op::BinOp { op, left, right } => format!("{op} {left}, {right}"),
+        Op::Neg(tmp) => format!("neg {tmp}"),
+        Op::Not(tmp) => format!("not {tmp}"),
+        Op::Deref(tmp) => format!("deref {tmp}"),
+        Op::Await(tmp) => format!("await {tmp}"),
+        Op::Field { base, field } => format!("field {base}.{field}"),
+        Op::OptionalField { base, field } => format!("optional_field {base}?.{field}"),
+        Op::Call { name, args } => format!("call {name}({})", tmp_list(args)),
+        Op::Variant {
+            enum_name,
+            variant,
+            args,
+        } => format!("variant {enum_name}::{variant}({})", tmp_list(args)),
+        Op::VariantTest {
+            value,
+            enum_name,
+            variant,
+        } => format!("variant_test {value} is {enum_name}::{variant}"),
+        Op::VariantField { base, index } => format!("variant_field {base}[{index}]"),
+        Op::Discriminant(tmp) => format!("discriminant {tmp}"),
+        Op::EmptyList => "empty_list".to_string(),
+        Op::List(items) => format!("list [{}]", tmp_list(items)),
+        Op::ListPush { list, value } => format!("list_push {list}, {value}"),
+        Op::ListExtend { list, value } => format!("list_extend {list}, {value}"),
+        Op::IterInit(tmp) => format!("iter_init {tmp}"),
+        Op::Struct { name, fields } => {
+            let fields = fields
+                .iter()
+                .map(|field| match field {
+                    HirStructField::Set { name, value } => format!("{name}: {value}"),
+                    HirStructField::Spread(tmp) => format!("...{tmp}"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("struct {name} {{ {fields} }}")
+        }
+        Op::Copy(tmp) => format!("copy {tmp}"),
+    }
+}
+
+fn tmp_list(tmps: &[Tmp]) -> String {
+    tmps.iter()
+        .map(|t| t.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn terminator_summary(terminator: &Terminator) -> String {
+    match terminator {
+        Terminator::Jump(target, args) => format!("jump -> {}", target_label(*target, args)),
+        Terminator::Branch {
+            cond,
+            then_block,
+            then_args,
+            else_block,
+            else_args,
+        } => format!(
+            "branch {cond} -> {}, {}",
+            target_label(*then_block, then_args),
+            target_label(*else_block, else_args)
+        ),
+        Terminator::Return(tmp) => format!("return {tmp}"),
+        Terminator::IterNext {
+            iter,
+            value,
+            body,
+            exit,
+        } => format!("iter_next {iter} -> {value}, {body}, {exit}"),
+        Terminator::Unreachable => "unreachable".to_string(),
+        Terminator::Drop { value, target } => format!("drop {value} -> {target}"),
+    }
+}
+
+fn target_label(target: BlockId, args: &[Tmp]) -> String {
+    if args.is_empty() {
+        target.to_string()
+    } else {
+        format!("{target}({})", tmp_list(args))
+    }
+}
+
+/// Emit `block`'s outgoing edges, labeled per the terminator kind.
+fn write_edges(block: &BasicBlock, out: &mut String) {
+    match &block.terminator {
+        Terminator::Jump(target, _) => {
+            writeln!(out, "  {} -> {};", block.id, target).unwrap();
+        }
+        Terminator::Branch {
+            then_block,
+            else_block,
+            ..
+        } => {
+            writeln!(out, "  {} -> {} [label=\"then\"];", block.id, then_block).unwrap();
+            writeln!(out, "  {} -> {} [label=\"else\"];", block.id, else_block).unwrap();
+        }
+        Terminator::Return(_) => {}
+        Terminator::IterNext { body, exit, .. } => {
+            writeln!(out, "  {} -> {} [label=\"body\"];", block.id, body).unwrap();
+            writeln!(out, "  {} -> {} [label=\"exit\"];", block.id, exit).unwrap();
+        }
+        Terminator::Unreachable => {}
+        Terminator::Drop { target, .. } => {
+            writeln!(out, "  {} -> {};", block.id, target).unwrap();
+        }
+    }
+}
+
+/// Escape a node label's quotes and literal newlines for GraphViz's `"..."`
+/// string syntax (`\l` is already a GraphViz escape, not a real newline, so
+/// it passes through untouched).
+fn escape(label: &str) -> String {
+    label.replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op, ty: Ty) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn renders_jump_edge_and_instruction_label() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![instr(0, Op::ConstInt(1), Ty::Int)],
+                    terminator: Terminator::Jump(BlockId(1), vec![Tmp(0)]),
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: vec![Tmp(0)],
+                    instructions: vec![],
+                    terminator: Terminator::Return(Tmp(0)),
+                },
+            ],
+        };
+
+        let dot = automation.to_dot();
+        assert!(dot.starts_with("digraph observer {\n"));
+        assert!(dot.contains("bb0 -> bb1;"));
+        assert!(dot.contains("%0 = const_int 1 [Int]"));
+    }
+
+    #[test]
+    fn renders_labeled_branch_edges() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: Vec::new(),
+                    else_block: BlockId(2),
+                    else_args: Vec::new(),
+                },
+            }],
+        };
+
+        let dot = automation.to_dot();
+        assert!(dot.contains("bb0 -> bb1 [label=\"then\"];"));
+        assert!(dot.contains("bb0 -> bb2 [label=\"else\"];"));
+    }
+}
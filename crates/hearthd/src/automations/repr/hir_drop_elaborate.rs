@@ -0,0 +1,738 @@
+//! Liveness-based drop insertion for owned HIR values.
+//!
+//! Lowering produces `Tmp`s for heap-backed values (lists, sets, maps,
+//! strings, named/struct types) but never frees them - the interpreters just
+//! let them accumulate in their `values: HashMap<Tmp, Value>` for the rest of
+//! the evaluation. This pass inserts [`Terminator::Drop`] at the point each
+//! such `Tmp` becomes dead, so [`super::super::eval`]'s interpreters can
+//! actually remove it from their runtime environment instead of holding it
+//! for the remainder of the run.
+//!
+//! This is modeled on MIR's `elaborate_drops`, with one deliberate scope
+//! reduction MIR's version can't take: **no conditional drop flags.** MIR
+//! needs a boolean flag per maybe-initialized local because a raw stack slot
+//! can be live on one predecessor path and uninitialized on another. That
+//! situation can't arise here: every value reassigned along only one branch
+//! of an `if`/`for`/`while` is already re-exported through a block parameter
+//! at the merge point by [`super::hir`]'s lowering (see `BasicBlock::params`'
+//! doc comment) - a `Tmp` is a single global SSA definition, so if it's live
+//! at a use, it was defined on every path reaching that use. The classic
+//! "maybe-initialized" problem drop flags solve doesn't occur in this IR.
+//!
+//! Placement is still per-*edge*, not just per-block: a value that's live
+//! down one successor of a `Branch`/`IterNext` but dead down the other is
+//! dropped only on the dead edge, by comparing against that specific
+//! successor's `live_in` rather than the block's aggregate `live_out`. This
+//! is what makes a loop-carried accumulator drop only once, on the edge
+//! leaving the loop, rather than never (if compared against the aggregate,
+//! which stays live down the loop-body edge) or on every iteration (if
+//! dropped unconditionally inside the loop).
+//!
+//! Like [`super::hir_dce`], this needs real per-block `live_in`/`live_out`
+//! sets rather than a single global fixpoint - unlike DCE's question ("is
+//! this `Tmp` used by anything reachable"), drop placement is inherently
+//! about *where along the CFG* a value stops being needed, which a global
+//! `live` set can't answer.
+//!
+//! Run once, after [`super::hir_optimize::optimize_program`] has converged -
+//! not folded into that pass's fixpoint. Drop insertion only adds dead-end
+//! blocks that the later passes have no reason to revisit, and running it
+//! mid-fixpoint would mean DCE/fold/branch-fold/copy-prop all have to cope
+//! with `Drop` terminators for no benefit.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use super::hir::*;
+use super::hir_visit::{op_operands, terminator_operands};
+use super::typed::Ty;
+
+/// Run drop elaboration over every automation in `program`.
+pub fn elaborate_drops_program(program: &mut HirProgram) {
+    match program {
+        HirProgram::Automation(automation) => elaborate_drops_automation(automation),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                elaborate_drops_automation(automation);
+            }
+        }
+    }
+}
+
+fn elaborate_drops_automation(automation: &mut HirAutomation) {
+    let (defs, uses) = def_use_sets(automation);
+    let (live_in, _live_out) = liveness(automation, &defs, &uses);
+    insert_drops(automation, &defs, &live_in);
+}
+
+/// Whether a value of this type is heap-backed and worth dropping. Scalars
+/// (`Int`/`Float`/`Bool`/unit-bearing values) are plain Rust `Copy` data once
+/// represented as [`super::super::eval::Value`], so there's nothing to free.
+fn is_owned(ty: &Ty) -> bool {
+    matches!(
+        ty,
+        Ty::List(_) | Ty::Set(_) | Ty::Map { .. } | Ty::String | Ty::Named(_)
+    )
+}
+
+/// Every `Tmp`'s type, including block parameters. Instruction destinations
+/// and the automation's own params carry their type directly; a block
+/// parameter doesn't (see `BasicBlock::params`' doc comment), so its type is
+/// propagated from whichever incoming `Jump`/`Branch` edge's argument type is
+/// already known - iterating to a fixpoint, since that argument may itself
+/// be another block's parameter. Well-typed HIR guarantees every edge into a
+/// given parameter agrees on its type, so it doesn't matter which edge
+/// resolves it first.
+fn resolve_tmp_types(automation: &HirAutomation) -> HashMap<Tmp, Ty> {
+    let mut tmp_ty: HashMap<Tmp, Ty> = HashMap::new();
+    for param in &automation.params {
+        tmp_ty.insert(param.tmp, param.ty.clone());
+    }
+    for block in &automation.blocks {
+        for instr in &block.instructions {
+            if !matches!(instr.op, Op::IterInit(_)) {
+                tmp_ty.insert(instr.dst, instr.ty.clone());
+            }
+        }
+    }
+
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for block in &automation.blocks {
+            for (target, args) in jump_edges(&block.terminator) {
+                let Some(target_block) = automation.blocks.iter().find(|b| b.id == target) else {
+                    continue;
+                };
+                for (&param, &arg) in target_block.params.iter().zip(args) {
+                    if tmp_ty.contains_key(&param) {
+                        continue;
+                    }
+                    if let Some(ty) = tmp_ty.get(&arg).cloned() {
+                        tmp_ty.insert(param, ty);
+                        changed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    tmp_ty
+}
+
+/// A block's outgoing `Jump`/`Branch` edges as `(target, args)` pairs - the
+/// only terminators that feed a target block's params. `IterNext`'s `body`
+/// edge binds its `value` directly rather than through `params`, so it's not
+/// an edge in this sense.
+fn jump_edges(terminator: &Terminator) -> Vec<(BlockId, &[Tmp])> {
+    match terminator {
+        Terminator::Jump(target, args) => vec![(*target, args.as_slice())],
+        Terminator::Branch {
+            then_block,
+            then_args,
+            else_block,
+            else_args,
+            ..
+        } => vec![
+            (*then_block, then_args.as_slice()),
+            (*else_block, else_args.as_slice()),
+        ],
+        Terminator::Return(_)
+        | Terminator::IterNext { .. }
+        | Terminator::Unreachable
+        | Terminator::Drop { .. } => vec![],
+    }
+}
+
+/// Per-block owned `defs` (this block's own owned-typed instruction
+/// destinations, plus any owned-typed block params it introduces at entry)
+/// and upward-exposed `uses` (owned `Tmp`s read before being locally
+/// redefined - the only uses liveness needs per SSA, since a `Tmp` has
+/// exactly one definition site in the whole automation).
+fn def_use_sets(
+    automation: &HirAutomation,
+) -> (
+    HashMap<BlockId, HashSet<Tmp>>,
+    HashMap<BlockId, HashSet<Tmp>>,
+) {
+    let tmp_ty = resolve_tmp_types(automation);
+
+    let mut owned = HashSet::new();
+    let mut defs: HashMap<BlockId, HashSet<Tmp>> = HashMap::new();
+    for block in &automation.blocks {
+        let mut block_defs = HashSet::new();
+        for instr in &block.instructions {
+            // `Op::IterInit`'s `dst` mirrors the source collection's element
+            // type in `instr.ty`, but both interpreters bind `Value::Void`
+            // there at runtime - the real cursor lives in their separate
+            // `iterators` map. Treating it as an owned def would be
+            // harmless (dropping `Void` is a no-op) but semantically wrong.
+            if matches!(instr.op, Op::IterInit(_)) {
+                continue;
+            }
+            if is_owned(&instr.ty) {
+                block_defs.insert(instr.dst);
+            }
+        }
+        // A block param is itself a definition, at block entry - the
+        // merge-point equivalent of an instruction dst (see
+        // `BasicBlock::params`' doc comment). Without this, a value only
+        // produced along one branch arm and re-exported through a join's
+        // param never gets tracked as owned, and is never dropped.
+        for &param in &block.params {
+            if tmp_ty.get(&param).is_some_and(is_owned) {
+                block_defs.insert(param);
+            }
+        }
+        owned.extend(&block_defs);
+        defs.insert(block.id, block_defs);
+    }
+
+    let mut uses: HashMap<BlockId, HashSet<Tmp>> = HashMap::new();
+    for block in &automation.blocks {
+        let block_defs = &defs[&block.id];
+        let mut block_uses = HashSet::new();
+        for instr in &block.instructions {
+            for operand in op_operands(&instr.op) {
+                if owned.contains(&operand) && !block_defs.contains(&operand) {
+                    block_uses.insert(operand);
+                }
+            }
+        }
+        for operand in terminator_operands(&block.terminator) {
+            if owned.contains(&operand) && !block_defs.contains(&operand) {
+                block_uses.insert(operand);
+            }
+        }
+        uses.insert(block.id, block_uses);
+    }
+
+    (defs, uses)
+}
+
+/// A block's successors, including the new [`Terminator::Drop`] variant -
+/// unlike [`super::hir_dce`]'s copy of this helper, this one is written with
+/// `Drop` in mind from the start, since drop insertion never needs to walk a
+/// CFG that already contains one.
+fn successors(terminator: &Terminator) -> Vec<BlockId> {
+    match terminator {
+        Terminator::Jump(target, _) => vec![*target],
+        Terminator::Branch {
+            then_block,
+            else_block,
+            ..
+        } => vec![*then_block, *else_block],
+        Terminator::Return(_) => vec![],
+        Terminator::IterNext { body, exit, .. } => vec![*body, *exit],
+        Terminator::Unreachable => vec![],
+        Terminator::Drop { target, .. } => vec![*target],
+    }
+}
+
+/// Standard backward liveness dataflow to a fixpoint:
+/// `live_out[B] = U live_in[S] for S in succ(B)`,
+/// `live_in[B] = use[B] U (live_out[B] \ def[B])`.
+fn liveness(
+    automation: &HirAutomation,
+    defs: &HashMap<BlockId, HashSet<Tmp>>,
+    uses: &HashMap<BlockId, HashSet<Tmp>>,
+) -> (
+    HashMap<BlockId, HashSet<Tmp>>,
+    HashMap<BlockId, HashSet<Tmp>>,
+) {
+    let mut live_in: HashMap<BlockId, HashSet<Tmp>> = automation
+        .blocks
+        .iter()
+        .map(|b| (b.id, HashSet::new()))
+        .collect();
+    let mut live_out: HashMap<BlockId, HashSet<Tmp>> = automation
+        .blocks
+        .iter()
+        .map(|b| (b.id, HashSet::new()))
+        .collect();
+
+    loop {
+        let mut changed = false;
+        for block in &automation.blocks {
+            let mut out = HashSet::new();
+            for succ in successors(&block.terminator) {
+                if let Some(succ_in) = live_in.get(&succ) {
+                    out.extend(succ_in);
+                }
+            }
+
+            let mut new_in = uses[&block.id].clone();
+            new_in.extend(out.difference(&defs[&block.id]));
+
+            if out != live_out[&block.id] {
+                live_out.insert(block.id, out);
+                changed = true;
+            }
+            if new_in != live_in[&block.id] {
+                live_in.insert(block.id, new_in);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    (live_in, live_out)
+}
+
+/// The owned `Tmp`s that die crossing a specific edge: defined-or-live at the
+/// start of the edge's block, not live at the start of the edge's target,
+/// and not itself an argument this edge forwards to the target (those move
+/// into the target block's params under a different `Tmp` number, rather
+/// than dying here).
+fn edge_dying(
+    candidates: &HashSet<Tmp>,
+    target_live_in: &HashSet<Tmp>,
+    edge_args: &[Tmp],
+) -> Vec<Tmp> {
+    let edge_args: HashSet<Tmp> = edge_args.iter().copied().collect();
+    let mut dying: Vec<Tmp> = candidates
+        .iter()
+        .filter(|tmp| !target_live_in.contains(tmp) && !edge_args.contains(tmp))
+        .copied()
+        .collect();
+    dying.sort_by_key(|tmp| tmp.0);
+    dying
+}
+
+/// Build a chain of fresh [`Terminator::Drop`] blocks for `dying` (dropped in
+/// order), whose tail is `continuation` - the terminator that should run once
+/// every value in `dying` has been released. Returns `continuation`
+/// unchanged if `dying` is empty. `next_id`/`new_blocks` are shared across
+/// every edge in the automation so chain block ids never collide.
+fn build_drop_chain(
+    dying: Vec<Tmp>,
+    continuation: Terminator,
+    next_id: &mut usize,
+    new_blocks: &mut Vec<BasicBlock>,
+) -> Terminator {
+    let mut tail = continuation;
+    for tmp in dying.into_iter().rev() {
+        let drop_block_id = BlockId(*next_id);
+        *next_id += 1;
+        new_blocks.push(BasicBlock {
+            id: drop_block_id,
+            params: Vec::new(),
+            instructions: Vec::new(),
+            terminator: tail,
+        });
+        tail = Terminator::Drop {
+            value: tmp,
+            target: drop_block_id,
+        };
+    }
+    tail
+}
+
+/// Redirect a `Branch`/`IterNext` edge through a drop chain for the `Tmp`s
+/// that die crossing it, returning the `(target, args)` that edge should use
+/// instead. If nothing dies, returns `(original_target, original_args)`
+/// unchanged - no new block is allocated. Otherwise allocates one more block
+/// (beyond `build_drop_chain`'s own) to hold the chain's entry terminator, so
+/// the edge has a single fresh `BlockId` to point at; that block takes no
+/// params, so the edge's own args become empty (the original `args` are
+/// preserved inside the chain's final `Jump` instead).
+fn splice_edge(
+    dying: Vec<Tmp>,
+    original_target: BlockId,
+    original_args: Vec<Tmp>,
+    next_id: &mut usize,
+    new_blocks: &mut Vec<BasicBlock>,
+) -> (BlockId, Vec<Tmp>) {
+    if dying.is_empty() {
+        return (original_target, original_args);
+    }
+    let continuation = Terminator::Jump(original_target, original_args);
+    let entry_terminator = build_drop_chain(dying, continuation, next_id, new_blocks);
+    let entry_id = BlockId(*next_id);
+    *next_id += 1;
+    new_blocks.push(BasicBlock {
+        id: entry_id,
+        params: Vec::new(),
+        instructions: Vec::new(),
+        terminator: entry_terminator,
+    });
+    (entry_id, Vec::new())
+}
+
+/// For each block, redirect every outgoing edge through a drop chain for the
+/// owned `Tmp`s that die on that specific edge. A value live down one
+/// successor but not another (a loop's accumulator down the body edge vs.
+/// the exit edge, or an `if`/`else` where only one arm still needs a value)
+/// is dropped only on the edge(s) where it's actually dead.
+fn insert_drops(
+    automation: &mut HirAutomation,
+    defs: &HashMap<BlockId, HashSet<Tmp>>,
+    live_in: &HashMap<BlockId, HashSet<Tmp>>,
+) {
+    let mut next_id = automation.blocks.iter().map(|b| b.id.0).max().unwrap_or(0) + 1;
+    let mut new_blocks = Vec::new();
+    let empty = HashSet::new();
+
+    for block in &mut automation.blocks {
+        let candidates: HashSet<Tmp> = defs[&block.id]
+            .union(&live_in[&block.id])
+            .copied()
+            .collect();
+
+        block.terminator = match std::mem::replace(&mut block.terminator, Terminator::Unreachable) {
+            // `Jump`/`Return`/`Unreachable` have a single (or no) successor,
+            // so the chain can simply become the block's own terminator -
+            // no separate entry `BlockId` is needed the way `Branch`/
+            // `IterNext`'s two independent edges require.
+            Terminator::Jump(target, args) => {
+                let target_live_in = live_in.get(&target).unwrap_or(&empty);
+                let dying = edge_dying(&candidates, target_live_in, &args);
+                let continuation = Terminator::Jump(target, args);
+                build_drop_chain(dying, continuation, &mut next_id, &mut new_blocks)
+            }
+            Terminator::Branch {
+                cond,
+                then_block,
+                then_args,
+                else_block,
+                else_args,
+            } => {
+                let then_live_in = live_in.get(&then_block).unwrap_or(&empty);
+                let then_dying = edge_dying(&candidates, then_live_in, &then_args);
+                let (then_block, then_args) = splice_edge(
+                    then_dying,
+                    then_block,
+                    then_args,
+                    &mut next_id,
+                    &mut new_blocks,
+                );
+
+                let else_live_in = live_in.get(&else_block).unwrap_or(&empty);
+                let else_dying = edge_dying(&candidates, else_live_in, &else_args);
+                let (else_block, else_args) = splice_edge(
+                    else_dying,
+                    else_block,
+                    else_args,
+                    &mut next_id,
+                    &mut new_blocks,
+                );
+
+                Terminator::Branch {
+                    cond,
+                    then_block,
+                    then_args,
+                    else_block,
+                    else_args,
+                }
+            }
+            Terminator::Return(tmp) => {
+                let dying = edge_dying(&candidates, &empty, &[tmp]);
+                let continuation = Terminator::Return(tmp);
+                build_drop_chain(dying, continuation, &mut next_id, &mut new_blocks)
+            }
+            Terminator::IterNext {
+                iter,
+                value,
+                body,
+                exit,
+            } => {
+                let body_live_in = live_in.get(&body).unwrap_or(&empty);
+                let body_dying = edge_dying(&candidates, body_live_in, &[]);
+                let (body, _) =
+                    splice_edge(body_dying, body, Vec::new(), &mut next_id, &mut new_blocks);
+
+                let exit_live_in = live_in.get(&exit).unwrap_or(&empty);
+                let exit_dying = edge_dying(&candidates, exit_live_in, &[]);
+                let (exit, _) =
+                    splice_edge(exit_dying, exit, Vec::new(), &mut next_id, &mut new_blocks);
+
+                Terminator::IterNext {
+                    iter,
+                    value,
+                    body,
+                    exit,
+                }
+            }
+            Terminator::Unreachable => {
+                let dying = edge_dying(&candidates, &empty, &[]);
+                build_drop_chain(
+                    dying,
+                    Terminator::Unreachable,
+                    &mut next_id,
+                    &mut new_blocks,
+                )
+            }
+            // Not expected - this pass runs once, before any `Drop`
+            // terminators exist. Left unchanged rather than panicking so a
+            // second accidental run is a no-op instead of a crash.
+            already_dropped @ Terminator::Drop { .. } => already_dropped,
+        };
+    }
+
+    automation.blocks.extend(new_blocks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::*;
+
+    fn instr(dst: usize, op: Op, ty: Ty) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn drops_unused_owned_local_before_return() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::EmptyList, Ty::List(Box::new(Ty::Int))),
+                    instr(1, Op::ConstInt(1), Ty::Int),
+                ],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        };
+
+        elaborate_drops_automation(&mut automation);
+
+        assert_eq!(automation.blocks.len(), 2);
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Drop { value: Tmp(0), .. }
+        ));
+    }
+
+    #[test]
+    fn returned_owned_value_is_not_dropped() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::EmptyList, Ty::List(Box::new(Ty::Int)))],
+                terminator: Terminator::Return(Tmp(0)),
+            }],
+        };
+
+        elaborate_drops_automation(&mut automation);
+
+        // Nothing dies here: the list is moved out as the return value.
+        assert_eq!(automation.blocks.len(), 1);
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+
+    #[test]
+    fn drops_value_produced_on_only_one_branch_arm_at_the_join() {
+        // bb0 branches; only the `then` arm (bb1) produces an owned local
+        // that isn't forwarded to the join (bb3), so it should be dropped in
+        // bb1 before the jump - bb2 (the `else` arm) never defines it, so it
+        // has nothing to drop.
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                    terminator: Terminator::Branch {
+                        cond: Tmp(0),
+                        then_block: BlockId(1),
+                        then_args: Vec::new(),
+                        else_block: BlockId(2),
+                        else_args: Vec::new(),
+                    },
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: Vec::new(),
+                    instructions: vec![instr(1, Op::EmptyList, Ty::List(Box::new(Ty::Int)))],
+                    terminator: Terminator::Jump(BlockId(3), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(2),
+                    params: Vec::new(),
+                    instructions: vec![],
+                    terminator: Terminator::Jump(BlockId(3), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(3),
+                    params: Vec::new(),
+                    instructions: vec![instr(2, Op::ConstInt(1), Ty::Int)],
+                    terminator: Terminator::Return(Tmp(2)),
+                },
+            ],
+        };
+
+        elaborate_drops_automation(&mut automation);
+
+        let bb1 = automation
+            .blocks
+            .iter()
+            .find(|b| b.id == BlockId(1))
+            .unwrap();
+        assert!(matches!(
+            bb1.terminator,
+            Terminator::Drop { value: Tmp(1), .. }
+        ));
+        let bb2 = automation
+            .blocks
+            .iter()
+            .find(|b| b.id == BlockId(2))
+            .unwrap();
+        assert!(matches!(bb2.terminator, Terminator::Jump(BlockId(3), _)));
+    }
+
+    #[test]
+    fn value_merged_through_a_block_param_is_dropped_once_unused() {
+        // bb0 branches; both arms produce their own owned list and forward
+        // it to bb3's block param (Tmp(3)) - the `lower_if` merge pattern.
+        // bb3 never uses Tmp(3), so it must be dropped before bb3's return.
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                    terminator: Terminator::Branch {
+                        cond: Tmp(0),
+                        then_block: BlockId(1),
+                        then_args: Vec::new(),
+                        else_block: BlockId(2),
+                        else_args: Vec::new(),
+                    },
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: Vec::new(),
+                    instructions: vec![instr(1, Op::EmptyList, Ty::List(Box::new(Ty::Int)))],
+                    terminator: Terminator::Jump(BlockId(3), vec![Tmp(1)]),
+                },
+                BasicBlock {
+                    id: BlockId(2),
+                    params: Vec::new(),
+                    instructions: vec![instr(2, Op::EmptyList, Ty::List(Box::new(Ty::Int)))],
+                    terminator: Terminator::Jump(BlockId(3), vec![Tmp(2)]),
+                },
+                BasicBlock {
+                    id: BlockId(3),
+                    params: vec![Tmp(3)],
+                    instructions: vec![instr(4, Op::ConstInt(1), Ty::Int)],
+                    terminator: Terminator::Return(Tmp(4)),
+                },
+            ],
+        };
+
+        elaborate_drops_automation(&mut automation);
+
+        let bb3 = automation
+            .blocks
+            .iter()
+            .find(|b| b.id == BlockId(3))
+            .unwrap();
+        assert!(matches!(
+            bb3.terminator,
+            Terminator::Drop { value: Tmp(3), .. }
+        ));
+    }
+
+    #[test]
+    fn loop_carried_accumulator_is_dropped_only_after_the_loop_exits() {
+        // bb0 initializes an accumulator list and an iterator, then loops:
+        // bb1 (header) advances the iterator into bb2 (body) or bb3 (exit).
+        // bb2 pushes onto the accumulator and jumps back to bb1. The
+        // accumulator is live down the body edge (it feeds the next
+        // iteration) but dead down the exit edge, so it must be dropped only
+        // on bb1's exit edge - never inside the bb1/bb2 loop body.
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![
+                        instr(0, Op::EmptyList, Ty::List(Box::new(Ty::Int))),
+                        instr(1, Op::EmptyList, Ty::List(Box::new(Ty::Int))),
+                        instr(2, Op::IterInit(Tmp(1)), Ty::Int),
+                    ],
+                    terminator: Terminator::Jump(BlockId(1), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: Vec::new(),
+                    instructions: vec![],
+                    terminator: Terminator::IterNext {
+                        iter: Tmp(2),
+                        value: Tmp(3),
+                        body: BlockId(2),
+                        exit: BlockId(3),
+                    },
+                },
+                BasicBlock {
+                    id: BlockId(2),
+                    params: Vec::new(),
+                    instructions: vec![instr(
+                        4,
+                        Op::ListPush {
+                            list: Tmp(0),
+                            value: Tmp(3),
+                        },
+                        Ty::Unit,
+                    )],
+                    terminator: Terminator::Jump(BlockId(1), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(3),
+                    params: Vec::new(),
+                    instructions: vec![instr(5, Op::ConstInt(1), Ty::Int)],
+                    terminator: Terminator::Return(Tmp(5)),
+                },
+            ],
+        };
+
+        elaborate_drops_automation(&mut automation);
+
+        let bb1 = automation
+            .blocks
+            .iter()
+            .find(|b| b.id == BlockId(1))
+            .unwrap();
+        let Terminator::IterNext { body, exit, .. } = bb1.terminator else {
+            unreachable!("bb1's terminator must stay an IterNext")
+        };
+        // The body edge is unchanged: the accumulator is still needed there.
+        assert_eq!(body, BlockId(2));
+        // The exit edge now runs through a fresh chain that drops it.
+        assert_ne!(exit, BlockId(3));
+        let exit_chain = automation.blocks.iter().find(|b| b.id == exit).unwrap();
+        assert!(matches!(
+            exit_chain.terminator,
+            Terminator::Drop { value: Tmp(0), .. }
+        ));
+
+        let bb2 = automation
+            .blocks
+            .iter()
+            .find(|b| b.id == BlockId(2))
+            .unwrap();
+        assert!(matches!(bb2.terminator, Terminator::Jump(BlockId(1), _)));
+    }
+}
@@ -0,0 +1,717 @@
+//! Constant-folding and compile-time error-detection pass over HIR.
+//!
+//! Runs after lowering, before the HIR is handed to later optimization
+//! passes or the interpreter. Walks each `BasicBlock`'s instructions in
+//! order, maintaining a map from `Tmp` to its folded constant value (if
+//! any) seeded from `Op::Const*`/`Op::EmptyList`. Whenever a `BinOp`,
+//! `Neg`, `Not`, or `Copy` has all of its operands already folded, the
+//! instruction is evaluated and rewritten in place to the matching
+//! `Op::Const*`, and the result is folded forward to later instructions.
+//!
+//! Resolving `Terminator::Branch`es whose folded `cond` is a compile-time
+//! `ConstBool` into unconditional `Jump`s is a separate pass,
+//! [`super::hir_branch_fold`], run immediately after this one by
+//! [`super::hir_optimize`] - see that module's doc comment for why the two
+//! are kept apart.
+//!
+//! `Op::ConstUnit` operands are seeded by converting them to their
+//! dimension's canonical base unit (see [`super::units`]), so `BinOp`s
+//! between two unit literals fold in that normalized representation and
+//! `5min + 2.5h` collapses to a single `ConstUnit` in seconds.
+//!
+//! Along the way this reports compile-time errors as [`FoldDiagnostic`]s:
+//! integer division/modulo by a statically-zero divisor, list literals
+//! that mix element kinds (e.g. `[1, true]`), and arithmetic/comparisons
+//! between unit literals of different dimensions (e.g. a duration and an
+//! angle). List indexing isn't part of the Automations language yet, so
+//! there is no out-of-bounds check here.
+//!
+//! A block parameter (see the doc comment on `BasicBlock::params`) merges
+//! values from more than one predecessor edge; after the first per-block
+//! pass, [`constant_block_params`] checks whether every edge into a
+//! parameter supplied the same constant and, if so, re-folds seeded with
+//! that knowledge - the same "is this phi constant" question the request
+//! for a dedicated `Op::Phi` was really after, answered without needing one.
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use super::ast::UnitType;
+use super::hir::*;
+use super::units::{canonical_unit, dimension_of, to_base};
+use crate::automations::int_ops::{checked_int_div, checked_int_mod};
+
+/// A compile-time error surfaced by the folding pass.
+#[derive(Debug, Clone)]
+pub struct FoldDiagnostic {
+    pub message: String,
+    pub span: Option<Range<usize>>,
+}
+
+impl std::fmt::Display for FoldDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "error at {}..{}: {}", span.start, span.end, self.message),
+            None => write!(f, "error: {}", self.message),
+        }
+    }
+}
+
+/// A constant value folded from HIR instructions.
+#[derive(Debug, Clone, PartialEq)]
+enum ConstValue {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+    List(Vec<ConstValue>),
+    /// A unit literal, normalized to its dimension's canonical base unit
+    /// (seconds, radians or Kelvin) as soon as it's seeded.
+    Unit(f64, UnitType),
+}
+
+impl ConstValue {
+    /// A short name used in "mixed element kinds" diagnostics.
+    fn kind_name(&self) -> &'static str {
+        match self {
+            ConstValue::Int(_) => "Int",
+            ConstValue::Float(_) => "Float",
+            ConstValue::Bool(_) => "Bool",
+            ConstValue::String(_) => "String",
+            ConstValue::List(_) => "List",
+            ConstValue::Unit(..) => "Unit",
+        }
+    }
+}
+
+/// Fold constants through every basic block of `program`, rewriting
+/// fully-constant instructions in place and returning any compile-time
+/// errors found.
+pub fn fold_program(program: &mut HirProgram) -> Vec<FoldDiagnostic> {
+    let mut diagnostics = Vec::new();
+    match program {
+        HirProgram::Automation(automation) => fold_automation(automation, &mut diagnostics),
+        HirProgram::Template { automations, .. } => {
+            for automation in automations {
+                fold_automation(automation, &mut diagnostics);
+            }
+        }
+    }
+    diagnostics
+}
+
+fn fold_automation(automation: &mut HirAutomation, diagnostics: &mut Vec<FoldDiagnostic>) {
+    for block in &mut automation.blocks {
+        fold_block(block, diagnostics, &HashMap::new());
+    }
+
+    // A block parameter merges values from more than one predecessor edge
+    // (see the doc comment on `BasicBlock::params`); if every edge supplies
+    // the exact same constant, the parameter is constant too, just like a
+    // classic phi node whose incoming values all agree. Re-fold seeded with
+    // that knowledge so downstream instructions using the parameter fold as
+    // well.
+    let param_consts = constant_block_params(automation);
+    if !param_consts.is_empty() {
+        for block in &mut automation.blocks {
+            fold_block(block, diagnostics, &param_consts);
+        }
+    }
+}
+
+/// Block parameters whose every incoming edge supplies the same compile-time
+/// constant.
+fn constant_block_params(automation: &HirAutomation) -> HashMap<Tmp, ConstValue> {
+    // The constant value (if any) each instruction's `dst` was folded to.
+    let mut folded: HashMap<Tmp, ConstValue> = HashMap::new();
+    for block in &automation.blocks {
+        for instr in &block.instructions {
+            if let Some(value) = seed(&instr.op) {
+                folded.insert(instr.dst, value);
+            }
+        }
+    }
+
+    // For every (target block, param index), the constant argument (if any)
+    // supplied by each edge that targets it.
+    let mut incoming: HashMap<(BlockId, usize), Vec<Option<ConstValue>>> = HashMap::new();
+    let mut record = |target: BlockId, args: &[Tmp]| {
+        for (i, arg) in args.iter().enumerate() {
+            incoming
+                .entry((target, i))
+                .or_default()
+                .push(folded.get(arg).cloned());
+        }
+    };
+    for block in &automation.blocks {
+        match &block.terminator {
+            Terminator::Jump(target, args) => record(*target, args),
+            Terminator::Branch {
+                then_block,
+                then_args,
+                else_block,
+                else_args,
+                ..
+            } => {
+                record(*then_block, then_args);
+                record(*else_block, else_args);
+            }
+            Terminator::Return(_) | Terminator::IterNext { .. } => {}
+        }
+    }
+
+    let mut result = HashMap::new();
+    for block in &automation.blocks {
+        for (i, param) in block.params.iter().enumerate() {
+            let Some(values) = incoming.get(&(block.id, i)) else {
+                continue;
+            };
+            let Some(first) = values.first().cloned().flatten() else {
+                continue;
+            };
+            if values.iter().all(|v| v.as_ref() == Some(&first)) {
+                result.insert(*param, first);
+            }
+        }
+    }
+    result
+}
+
+fn fold_block(
+    block: &mut BasicBlock,
+    diagnostics: &mut Vec<FoldDiagnostic>,
+    param_consts: &HashMap<Tmp, ConstValue>,
+) {
+    let mut consts: HashMap<Tmp, ConstValue> = param_consts.clone();
+
+    for instr in &mut block.instructions {
+        if let Some(value) = seed(&instr.op) {
+            consts.insert(instr.dst, value);
+            continue;
+        }
+
+        let folded = match &instr.op {
+            Op::BinOp { op, left, right } => match (consts.get(left), consts.get(right)) {
+                (Some(l), Some(r)) => eval_binop(*op, l, r, &instr.span, diagnostics),
+                _ => None,
+            },
+            Op::Copy(tmp) => consts.get(tmp).cloned(),
+            Op::Neg(tmp) => consts.get(tmp).and_then(eval_neg),
+            Op::Not(tmp) => match consts.get(tmp) {
+                Some(ConstValue::Bool(b)) => Some(ConstValue::Bool(!b)),
+                _ => None,
+            },
+            Op::List(items) => {
+                if items.iter().all(|t| consts.contains_key(t)) {
+                    let values: Vec<ConstValue> =
+                        items.iter().map(|t| consts[t].clone()).collect();
+                    check_list_literal(&values, &instr.span, diagnostics);
+                    Some(ConstValue::List(values))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(value) = folded {
+            instr.op = to_op(&value);
+            consts.insert(instr.dst, value);
+        }
+    }
+}
+
+/// Seed the constant map from a literal-producing op.
+fn seed(op: &Op) -> Option<ConstValue> {
+    match op {
+        Op::ConstInt(n) => Some(ConstValue::Int(*n)),
+        Op::ConstFloat(n) => Some(ConstValue::Float(*n)),
+        Op::ConstBool(b) => Some(ConstValue::Bool(*b)),
+        Op::ConstString(s) => Some(ConstValue::String(s.clone())),
+        Op::EmptyList => Some(ConstValue::List(Vec::new())),
+        Op::ConstUnit { value, unit } => {
+            let base = to_base(*unit, value.parse().ok()?);
+            Some(ConstValue::Unit(base, canonical_unit(dimension_of(*unit))))
+        }
+        _ => None,
+    }
+}
+
+fn to_op(value: &ConstValue) -> Op {
+    match value {
+        ConstValue::Int(n) => Op::ConstInt(*n),
+        ConstValue::Float(n) => Op::ConstFloat(*n),
+        ConstValue::Bool(b) => Op::ConstBool(*b),
+        ConstValue::String(s) => Op::ConstString(s.clone()),
+        ConstValue::List(_) => Op::EmptyList, // only ever constructed from EmptyList
+        ConstValue::Unit(value, unit) => Op::ConstUnit {
+            value: value.to_string(),
+            unit: *unit,
+        },
+    }
+}
+
+fn eval_neg(value: &ConstValue) -> Option<ConstValue> {
+    match value {
+        ConstValue::Int(n) => Some(ConstValue::Int(-n)),
+        ConstValue::Float(n) => Some(ConstValue::Float(-n)),
+        _ => None,
+    }
+}
+
+fn eval_binop(
+    op: HirBinOp,
+    left: &ConstValue,
+    right: &ConstValue,
+    span: &Option<Range<usize>>,
+    diagnostics: &mut Vec<FoldDiagnostic>,
+) -> Option<ConstValue> {
+    use ConstValue::*;
+    use HirBinOp::*;
+
+    match (op, left, right) {
+        (_, Unit(a, ua), Unit(b, ub)) => {
+            let (da, db) = (dimension_of(*ua), dimension_of(*ub));
+            if da != db {
+                diagnostics.push(FoldDiagnostic {
+                    message: format!(
+                        "cannot combine a {da} value with a {db} value: {op} requires both \
+                         sides to share a dimension"
+                    ),
+                    span: span.clone(),
+                });
+                return None;
+            }
+            // Both sides already normalized to the same canonical base unit by `seed`.
+            match op {
+                Add => Some(Unit(a + b, *ua)),
+                Sub => Some(Unit(a - b, *ua)),
+                Eq => Some(Bool(a == b)),
+                Ne => Some(Bool(a != b)),
+                Lt => Some(Bool(a < b)),
+                Le => Some(Bool(a <= b)),
+                Gt => Some(Bool(a > b)),
+                Ge => Some(Bool(a >= b)),
+                _ => None,
+            }
+        }
+        (Div, Int(_), Int(0)) | (Mod, Int(_), Int(0)) => {
+            diagnostics.push(FoldDiagnostic {
+                message: format!(
+                    "{} by zero: divisor is a compile-time constant 0",
+                    if op == Div { "division" } else { "modulo" }
+                ),
+                span: span.clone(),
+            });
+            None
+        }
+        (Add, Int(a), Int(b)) => Some(Int(a.wrapping_add(*b))),
+        (Sub, Int(a), Int(b)) => Some(Int(a.wrapping_sub(*b))),
+        (Mul, Int(a), Int(b)) => Some(Int(a.wrapping_mul(*b))),
+        // The `b == 0` case is already handled separately above; this only
+        // needs to guard `i64::MIN / -1`, which plain `/`/`%` panic on.
+        (Div, Int(a), Int(b)) => Some(Int(checked_int_div(*a, *b))),
+        (Mod, Int(a), Int(b)) => Some(Int(checked_int_mod(*a, *b))),
+        (Add, Float(a), Float(b)) => Some(Float(a + b)),
+        (Sub, Float(a), Float(b)) => Some(Float(a - b)),
+        (Mul, Float(a), Float(b)) => Some(Float(a * b)),
+        (Div, Float(a), Float(b)) => Some(Float(a / b)),
+        (Add, String(a), String(b)) => Some(String(format!("{a}{b}"))),
+        (In, needle, List(items)) => Some(Bool(items.contains(needle))),
+        (Eq, a, b) => Some(Bool(a == b)),
+        (Ne, a, b) => Some(Bool(a != b)),
+        (Lt, Int(a), Int(b)) => Some(Bool(a < b)),
+        (Le, Int(a), Int(b)) => Some(Bool(a <= b)),
+        (Gt, Int(a), Int(b)) => Some(Bool(a > b)),
+        (Ge, Int(a), Int(b)) => Some(Bool(a >= b)),
+        (Lt, Float(a), Float(b)) => Some(Bool(a < b)),
+        (Le, Float(a), Float(b)) => Some(Bool(a <= b)),
+        (Gt, Float(a), Float(b)) => Some(Bool(a > b)),
+        (Ge, Float(a), Float(b)) => Some(Bool(a >= b)),
+        _ => None,
+    }
+}
+
+/// Report list literals that mix incompatible constant kinds, e.g. `[1, true]`.
+fn check_list_literal(
+    values: &[ConstValue],
+    span: &Option<Range<usize>>,
+    diagnostics: &mut Vec<FoldDiagnostic>,
+) {
+    let mut kinds = values.iter().map(ConstValue::kind_name);
+    let Some(first) = kinds.next() else {
+        return;
+    };
+    if let Some(mismatched) = kinds.find(|k| *k != first) {
+        diagnostics.push(FoldDiagnostic {
+            message: format!(
+                "list literal mixes `{first}` and `{mismatched}` elements, which have \
+                 incompatible types"
+            ),
+            span: span.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn block(instructions: Vec<Instruction>, terminator: Terminator) -> BasicBlock {
+        BasicBlock {
+            id: BlockId(0),
+            params: Vec::new(),
+            instructions,
+            terminator,
+        }
+    }
+
+    fn instr(dst: usize, op: Op, ty: Ty) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty,
+            span: Some(0..1),
+        }
+    }
+
+    fn automation(blocks: Vec<BasicBlock>) -> HirAutomation {
+        HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks,
+        }
+    }
+
+    #[test]
+    fn folds_constant_addition() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(2), Ty::Int),
+                instr(1, Op::ConstInt(3), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        match automation.blocks[0].instructions[2].op {
+            Op::ConstInt(5) => {}
+            ref other => panic!("expected folded const_int 5, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn folds_copy_of_a_constant() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(7), Ty::Int),
+                instr(1, Op::Copy(Tmp(0)), Ty::Int),
+            ],
+            Terminator::Return(Tmp(1)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        match automation.blocks[0].instructions[1].op {
+            Op::ConstInt(7) => {}
+            ref other => panic!("expected folded const_int 7, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn folds_block_param_when_every_incoming_edge_agrees() {
+        // bb0 branches to bb1 or bb2, both of which jump into bb3 passing
+        // `5` as the merged param - bb3's `%2` is constant even though no
+        // single block produces it directly.
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: Vec::new(),
+                    else_block: BlockId(2),
+                    else_args: Vec::new(),
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(5), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(1)]),
+            },
+            BasicBlock {
+                id: BlockId(2),
+                params: Vec::new(),
+                instructions: vec![instr(1, Op::ConstInt(5), Ty::Int)],
+                terminator: Terminator::Jump(BlockId(3), vec![Tmp(1)]),
+            },
+            BasicBlock {
+                id: BlockId(3),
+                params: vec![Tmp(2)],
+                instructions: vec![instr(
+                    3,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(2),
+                        right: Tmp(2),
+                    },
+                    Ty::Int,
+                )],
+                terminator: Terminator::Return(Tmp(3)),
+            },
+        ]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        match automation.blocks[3].instructions[0].op {
+            Op::ConstInt(10) => {}
+            ref other => panic!("expected folded const_int 10, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn leaves_block_param_unfolded_when_edges_disagree() {
+        let mut program = HirProgram::Automation(automation(vec![
+            BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::ConstInt(5), Ty::Int),
+                    instr(1, Op::ConstInt(6), Ty::Int),
+                ],
+                terminator: Terminator::Branch {
+                    cond: Tmp(0),
+                    then_block: BlockId(1),
+                    then_args: vec![Tmp(0)],
+                    else_block: BlockId(1),
+                    else_args: vec![Tmp(1)],
+                },
+            },
+            BasicBlock {
+                id: BlockId(1),
+                params: vec![Tmp(2)],
+                instructions: vec![instr(3, Op::Copy(Tmp(2)), Ty::Int)],
+                terminator: Terminator::Return(Tmp(3)),
+            },
+        ]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[1].instructions[0].op,
+            Op::Copy(Tmp(2))
+        ));
+    }
+
+    #[test]
+    fn reports_division_by_zero() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(10), Ty::Int),
+                instr(1, Op::ConstInt(0), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Div,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("division"));
+    }
+
+    #[test]
+    fn folds_membership_test_against_constant_list() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstString("off".into()), Ty::String),
+                instr(1, Op::ConstString("on".into()), Ty::String),
+                instr(2, Op::ConstString("off".into()), Ty::String),
+                instr(
+                    3,
+                    Op::List(vec![Tmp(0), Tmp(1)]),
+                    Ty::List(Box::new(Ty::String)),
+                ),
+                instr(
+                    4,
+                    Op::BinOp {
+                        op: HirBinOp::In,
+                        left: Tmp(2),
+                        right: Tmp(3),
+                    },
+                    Ty::Bool,
+                ),
+            ],
+            Terminator::Return(Tmp(4)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        match automation.blocks[0].instructions[4].op {
+            Op::ConstBool(true) => {}
+            ref other => panic!("expected folded const_bool true, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_mixed_list_literal() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::ConstInt(1), Ty::Int),
+                instr(1, Op::ConstBool(true), Ty::Bool),
+                instr(2, Op::List(vec![Tmp(0), Tmp(1)]), Ty::List(Box::new(Ty::Int))),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("mixes"));
+    }
+
+    #[test]
+    fn folds_mixed_unit_literals_to_canonical_base_unit() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(
+                    0,
+                    Op::ConstUnit { value: "5".into(), unit: UnitType::Minutes },
+                    Ty::Duration,
+                ),
+                instr(
+                    1,
+                    Op::ConstUnit { value: "2.5".into(), unit: UnitType::Hours },
+                    Ty::Duration,
+                ),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Duration,
+                ),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        match &automation.blocks[0].instructions[2].op {
+            Op::ConstUnit { value, unit: UnitType::Seconds } => {
+                assert_eq!(value.parse::<f64>().unwrap(), 9300.0);
+            }
+            other => panic!("expected folded const_unit in seconds, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reports_cross_dimension_unit_arithmetic() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(
+                    0,
+                    Op::ConstUnit { value: "5".into(), unit: UnitType::Minutes },
+                    Ty::Duration,
+                ),
+                instr(
+                    1,
+                    Op::ConstUnit { value: "90".into(), unit: UnitType::Degrees },
+                    Ty::Angle,
+                ),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Error,
+                ),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("dimension"));
+    }
+
+    #[test]
+    fn leaves_non_constant_binop_untouched() {
+        let mut program = HirProgram::Automation(automation(vec![block(
+            vec![
+                instr(0, Op::Field { base: Tmp(99), field: "x".into() }, Ty::Int),
+                instr(1, Op::ConstInt(1), Ty::Int),
+                instr(
+                    2,
+                    Op::BinOp {
+                        op: HirBinOp::Add,
+                        left: Tmp(0),
+                        right: Tmp(1),
+                    },
+                    Ty::Int,
+                ),
+            ],
+            Terminator::Return(Tmp(2)),
+        )]));
+
+        let diagnostics = fold_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        assert!(matches!(
+            automation.blocks[0].instructions[2].op,
+            Op::BinOp { .. }
+        ));
+    }
+}
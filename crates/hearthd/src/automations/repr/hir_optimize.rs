@@ -0,0 +1,206 @@
+//! Fixpoint orchestration for the HIR optimization passes.
+//!
+//! Runs [`fold_program`], [`branch_fold_program`], [`dce_program`],
+//! [`copy_prop_program`], and [`cfg_simplify_program`] in sequence, repeating
+//! until a full round leaves the HIR unchanged. Folding can expose a
+//! branch-foldable condition, branch-folding can make a block unreachable,
+//! DCE can make a copy's destination the sole remaining definition, and so
+//! on — so a single pass over each isn't enough in general, even though real
+//! automations tend to converge in two or three rounds.
+//!
+//! Diagnostics accumulate across rounds and are deduplicated by message and
+//! span, since a value that never folds (e.g. a persistent divide-by-zero)
+//! would otherwise be reported once per round.
+
+use std::collections::HashSet;
+
+use super::hir::HirProgram;
+use super::hir_branch_fold::branch_fold_program;
+use super::hir_cfg_simplify::cfg_simplify_program;
+use super::hir_copy_prop::copy_prop_program;
+use super::hir_dce::dce_program;
+use super::hir_fold::{FoldDiagnostic, fold_program};
+
+/// Safety cap on fixpoint rounds. Real automations converge in a handful of
+/// iterations; this just bounds the pathological case.
+const MAX_ROUNDS: usize = 16;
+
+/// Run the constant-folding, branch-folding, dead-code-elimination, and
+/// copy-propagation passes over `program` to a fixpoint, returning any
+/// compile-time errors found along the way.
+pub fn optimize_program(program: &mut HirProgram) -> Vec<FoldDiagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut previous = snapshot(program);
+
+    for _ in 0..MAX_ROUNDS {
+        diagnostics.extend(fold_program(program));
+        branch_fold_program(program);
+        dce_program(program, true);
+        copy_prop_program(program);
+        cfg_simplify_program(program);
+
+        let current = snapshot(program);
+        if current == previous {
+            break;
+        }
+        previous = current;
+    }
+
+    dedup_diagnostics(diagnostics)
+}
+
+/// A cheap way to detect "no change this round": the `Debug` rendering of
+/// every basic block, compared by value.
+fn snapshot(program: &HirProgram) -> String {
+    match program {
+        HirProgram::Automation(automation) => format!("{:?}", automation.blocks),
+        HirProgram::Template { automations, .. } => format!(
+            "{:?}",
+            automations.iter().map(|a| &a.blocks).collect::<Vec<_>>()
+        ),
+    }
+}
+
+fn dedup_diagnostics(diagnostics: Vec<FoldDiagnostic>) -> Vec<FoldDiagnostic> {
+    let mut seen = HashSet::new();
+    diagnostics
+        .into_iter()
+        .filter(|d| seen.insert((d.message.clone(), d.span.as_ref().map(|s| (s.start, s.end)))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::{AutomationKind, UnitType};
+    use super::super::hir::*;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op, ty: Ty) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty,
+            span: Some(0..1),
+        }
+    }
+
+    #[test]
+    fn folds_condition_then_branch_then_prunes_dead_block() {
+        // `true && false` lowered via short-circuit branching: bb0 computes
+        // the left operand, branches on it; the `false` arm of `&&` is
+        // unreachable once the condition folds to a constant.
+        let mut program = HirProgram::Automation(HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![
+                BasicBlock {
+                    id: BlockId(0),
+                    params: Vec::new(),
+                    instructions: vec![instr(0, Op::ConstBool(true), Ty::Bool)],
+                    terminator: Terminator::Branch {
+                        cond: Tmp(0),
+                        then_block: BlockId(1),
+                        then_args: Vec::new(),
+                        else_block: BlockId(2),
+                        else_args: Vec::new(),
+                    },
+                },
+                BasicBlock {
+                    id: BlockId(1),
+                    params: Vec::new(),
+                    instructions: vec![instr(1, Op::ConstBool(false), Ty::Bool)],
+                    terminator: Terminator::Jump(BlockId(3), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(2),
+                    params: Vec::new(),
+                    instructions: vec![instr(2, Op::ConstBool(false), Ty::Bool)],
+                    terminator: Terminator::Jump(BlockId(3), Vec::new()),
+                },
+                BasicBlock {
+                    id: BlockId(3),
+                    params: Vec::new(),
+                    instructions: vec![instr(3, Op::Copy(Tmp(1)))],
+                    terminator: Terminator::Return(Tmp(3)),
+                },
+            ],
+        });
+
+        let diagnostics = optimize_program(&mut program);
+        assert!(diagnostics.is_empty());
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        // bb2 (the untaken `else` arm) was pruned as unreachable.
+        assert_eq!(automation.blocks.len(), 3);
+        assert!(automation.blocks.iter().all(|b| b.id != BlockId(2)));
+    }
+
+    #[test]
+    fn deduplicates_repeated_fold_diagnostics_across_rounds() {
+        let mut program = HirProgram::Automation(HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::ConstInt(10), Ty::Int),
+                    instr(1, Op::ConstInt(0), Ty::Int),
+                    instr(
+                        2,
+                        Op::BinOp {
+                            op: HirBinOp::Div,
+                            left: Tmp(0),
+                            right: Tmp(1),
+                        },
+                        Ty::Int,
+                    ),
+                ],
+                terminator: Terminator::Return(Tmp(2)),
+            }],
+        });
+
+        let diagnostics = optimize_program(&mut program);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("division"));
+    }
+
+    #[test]
+    fn propagates_copy_then_removes_it_as_dead() {
+        let mut program = HirProgram::Automation(HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(
+                        0,
+                        Op::ConstUnit {
+                            value: "5".into(),
+                            unit: UnitType::Minutes,
+                        },
+                        Ty::Duration,
+                    ),
+                    instr(1, Op::Copy(Tmp(0)), Ty::Duration),
+                ],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        });
+
+        optimize_program(&mut program);
+
+        let HirProgram::Automation(automation) = &program else {
+            unreachable!()
+        };
+        // The copy is dead once its use is rewritten to `%0` directly.
+        assert_eq!(automation.blocks[0].instructions.len(), 1);
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+}
@@ -3,6 +3,8 @@
 //! Used by lowering tests to produce readable snapshot output.
 
 use super::hir::*;
+use super::hir_visit::for_each_instruction;
+use super::pretty_print::PpAnn;
 use super::pretty_print::PrettyPrint;
 use super::pretty_print::write_indent;
 
@@ -19,9 +21,14 @@ impl std::fmt::Display for BlockId {
 }
 
 impl PrettyPrint for HirProgram {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         match self {
-            HirProgram::Automation(auto) => auto.pretty_print(indent, f),
+            HirProgram::Automation(auto) => auto.pretty_print(indent, ann, f),
             HirProgram::Template {
                 params,
                 automations,
@@ -31,12 +38,12 @@ impl PrettyPrint for HirProgram {
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Params:")?;
                 for param in params {
-                    param.pretty_print(indent + 2, f)?;
+                    param.pretty_print(indent + 2, ann, f)?;
                 }
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Automations:")?;
                 for auto in automations {
-                    auto.pretty_print(indent + 2, f)?;
+                    auto.pretty_print(indent + 2, ann, f)?;
                 }
                 Ok(())
             }
@@ -45,7 +52,12 @@ impl PrettyPrint for HirProgram {
 }
 
 impl PrettyPrint for HirAutomation {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         writeln!(f, "Automation: {}", self.kind)?;
         if !self.params.is_empty() {
@@ -56,32 +68,47 @@ impl PrettyPrint for HirAutomation {
                 writeln!(f, "{}: {} [{}]", param.tmp, param.name, param.ty)?;
             }
         }
-        self.blocks.pretty_print(indent + 1, f)
+        self.blocks.pretty_print(indent + 1, ann, f)
     }
 }
 
 impl PrettyPrint for Vec<BasicBlock> {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         for block in self {
-            block.pretty_print(indent, f)?;
+            block.pretty_print(indent, ann, f)?;
         }
         Ok(())
     }
 }
 
 impl PrettyPrint for BasicBlock {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
-        writeln!(f, "{}:", self.id)?;
-        for instr in &self.instructions {
-            instr.pretty_print(indent + 1, f)?;
-        }
-        self.terminator.pretty_print(indent + 1, f)
+        write!(f, "{}", self.id)?;
+        write_param_list(&self.params, f)?;
+        writeln!(f, ":")?;
+        for_each_instruction(self, |instr| instr.pretty_print(indent + 1, ann, f))?;
+        self.terminator.pretty_print(indent + 1, ann, f)
     }
 }
 
 impl PrettyPrint for Instruction {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        _ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         write!(f, "{} = ", self.dst)?;
         write_op(&self.op, f)?;
@@ -118,6 +145,13 @@ fn write_op(op: &Op, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             write_tmp_list(args, f)?;
             write!(f, ")")
         }
+        Op::VariantTest {
+            value,
+            enum_name,
+            variant,
+        } => write!(f, "variant_test {} is {}::{}", value, enum_name, variant),
+        Op::VariantField { base, index } => write!(f, "variant_field {}[{}]", base, index),
+        Op::Discriminant(tmp) => write!(f, "discriminant {}", tmp),
         Op::EmptyList => write!(f, "empty_list"),
         Op::List(elems) => {
             write!(f, "list [")?;
@@ -125,6 +159,7 @@ fn write_op(op: &Op, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             write!(f, "]")
         }
         Op::ListPush { list, value } => write!(f, "list_push {}, {}", list, value),
+        Op::ListExtend { list, value } => write!(f, "list_extend {}, {}", list, value),
         Op::IterInit(tmp) => write!(f, "iter_init {}", tmp),
         Op::Struct { name, fields } => {
             write!(f, "struct {} {{ ", name)?;
@@ -153,16 +188,51 @@ fn write_tmp_list(tmps: &[Tmp], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Re
     Ok(())
 }
 
+/// Render a block's parameter list, e.g. `(%2, %3)`. Omitted entirely when
+/// the block takes no parameters, so unchanged blocks print unchanged.
+fn write_param_list(params: &[Tmp], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    if params.is_empty() {
+        return Ok(());
+    }
+    write!(f, "(")?;
+    write_tmp_list(params, f)?;
+    write!(f, ")")
+}
+
+/// Render a jump/branch target together with the arguments it supplies,
+/// e.g. `bb5` or `bb5(%3)`.
+fn write_target(target: BlockId, args: &[Tmp], f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", target)?;
+    write_param_list(args, f)
+}
+
 impl PrettyPrint for Terminator {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        _ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         match self {
-            Terminator::Jump(target) => writeln!(f, "jump -> {}", target),
+            Terminator::Jump(target, args) => {
+                write!(f, "jump -> ")?;
+                write_target(*target, args, f)?;
+                writeln!(f)
+            }
             Terminator::Branch {
                 cond,
                 then_block,
+                then_args,
                 else_block,
-            } => writeln!(f, "branch {} -> {}, {}", cond, then_block, else_block),
+                else_args,
+            } => {
+                write!(f, "branch {} -> ", cond)?;
+                write_target(*then_block, then_args, f)?;
+                write!(f, ", ")?;
+                write_target(*else_block, else_args, f)?;
+                writeln!(f)
+            }
             Terminator::Return(tmp) => writeln!(f, "return {}", tmp),
             Terminator::IterNext {
                 iter,
@@ -170,6 +240,8 @@ impl PrettyPrint for Terminator {
                 body,
                 exit,
             } => writeln!(f, "iter_next {} -> {}, {}, {}", iter, value, body, exit),
+            Terminator::Unreachable => writeln!(f, "unreachable"),
+            Terminator::Drop { value, target } => writeln!(f, "drop {} -> {}", value, target),
         }
     }
 }
@@ -0,0 +1,189 @@
+//! Unused-value analysis over lowered HIR.
+//!
+//! `lower_stmts_result` (see [`super::super::lower`]) lowers every
+//! non-trailing `TypedStmt::Expr` the same way it lowers any other
+//! expression: the statement's `Tmp` is computed, and then silently
+//! overwritten the moment the next statement lowers (`last_tmp =
+//! Some(...)`). If that `Tmp` is never read again - no later instruction or
+//! terminator operand names it - and its `Op` had no side effect worth
+//! keeping it around for anyway, the value the user wrote was thrown away
+//! for no reason, most likely a mistake (e.g. `a == b;` where an `if`/
+//! `assert` was meant, or a forgotten `return`).
+//!
+//! This has to run on the freshly lowered HIR, before
+//! [`super::hir_optimize::optimize_program`]: by the time its `dce_program`
+//! pass converges, every dead-pure instruction this flags has already been
+//! deleted, and there'd be nothing left to diagnose.
+//!
+//! Unlike [`super::hir_dce`]'s liveness fixpoint, "is this `Tmp` ever read"
+//! is answerable in one pass over [`super::hir_visit::used_tmps`]: because
+//! every `Tmp` is a globally unique SSA definition, one not in that set is
+//! read by nothing anywhere in the automation, full stop - no backward
+//! propagation needed, since nothing can make an otherwise-unread `Tmp`
+//! retroactively read.
+
+use std::ops::Range;
+
+use super::hir::*;
+use super::hir_visit::{has_side_effects, used_tmps};
+use super::typed::Ty;
+
+/// A statement whose computed value was never used, and whose `Op` had no
+/// side effect to justify keeping it anyway.
+#[derive(Debug, Clone)]
+pub struct UnusedValueDiagnostic {
+    pub message: String,
+    pub span: Option<Range<usize>>,
+}
+
+impl std::fmt::Display for UnusedValueDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.span {
+            Some(span) => write!(
+                f,
+                "warning at {}..{}: {}",
+                span.start, span.end, self.message
+            ),
+            None => write!(f, "warning: {}", self.message),
+        }
+    }
+}
+
+/// Find every discarded, pure, non-`Unit` value in `program`.
+pub fn find_unused_values(program: &HirProgram) -> Vec<UnusedValueDiagnostic> {
+    match program {
+        HirProgram::Automation(automation) => find_unused_values_automation(automation),
+        HirProgram::Template { automations, .. } => automations
+            .iter()
+            .flat_map(find_unused_values_automation)
+            .collect(),
+    }
+}
+
+fn find_unused_values_automation(automation: &HirAutomation) -> Vec<UnusedValueDiagnostic> {
+    let used = used_tmps(automation);
+
+    automation
+        .blocks
+        .iter()
+        .flat_map(|block| &block.instructions)
+        .filter(|instr| {
+            !used.contains(&instr.dst) && instr.ty != Ty::Unit && !has_side_effects(&instr.op)
+        })
+        .map(|instr| UnusedValueDiagnostic {
+            message: format!(
+                "unused value of type {} - did you mean to use it, e.g. as the block's result or in a `return`?",
+                instr.ty
+            ),
+            span: instr.span.clone(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::*;
+
+    fn instr(dst: usize, op: Op, ty: Ty, span: Option<Range<usize>>) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty,
+            span,
+        }
+    }
+
+    #[test]
+    fn flags_discarded_pure_non_unit_value() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(
+                        0,
+                        Op::BinOp {
+                            op: HirBinOp::Eq,
+                            left: Tmp(1),
+                            right: Tmp(2),
+                        },
+                        Ty::Bool,
+                        Some(10..20),
+                    ),
+                    instr(3, Op::ConstInt(1), Ty::Int, None),
+                ],
+                terminator: Terminator::Return(Tmp(3)),
+            }],
+        };
+
+        let diagnostics = find_unused_values_automation(&automation);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Some(10..20));
+    }
+
+    #[test]
+    fn does_not_flag_the_returned_value() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1), Ty::Int, Some(0..1))],
+                terminator: Terminator::Return(Tmp(0)),
+            }],
+        };
+
+        assert!(find_unused_values_automation(&automation).is_empty());
+    }
+
+    #[test]
+    fn does_not_flag_unit_typed_statements() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::Unit, Ty::Unit, Some(0..1)),
+                    instr(1, Op::ConstInt(1), Ty::Int, None),
+                ],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        };
+
+        assert!(find_unused_values_automation(&automation).is_empty());
+    }
+
+    #[test]
+    fn does_not_flag_a_discarded_but_effectful_call() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(
+                        0,
+                        Op::Call {
+                            name: "log".into(),
+                            args: vec![],
+                        },
+                        Ty::String,
+                        Some(0..10),
+                    ),
+                    instr(1, Op::ConstInt(1), Ty::Int, None),
+                ],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        };
+
+        assert!(find_unused_values_automation(&automation).is_empty());
+    }
+}
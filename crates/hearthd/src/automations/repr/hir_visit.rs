@@ -0,0 +1,386 @@
+//! Generic traversal layer for the HIR.
+//!
+//! Every pass that walks `Op`/`Terminator` needs the same two things: the
+//! list of `Tmp`s an instruction reads (used by analyses like
+//! [`super::hir_dce`]'s liveness fixpoint), and a way to rewrite those reads
+//! in place (used by rewrites like [`super::hir_copy_prop`]). Before this
+//! module, each pass hand-rolled its own match over every `Op` variant to
+//! get there. This module provides both as a pair, modeled on a monoidal
+//! reducer + reconstructing director split:
+//!
+//! - The reducer half (`op_operands`, `terminator_operands`,
+//!   `reduce_instructions`, [`Monoid`]) only reads the HIR, folding a
+//!   combinable accumulator over every operand or instruction - e.g.
+//!   collecting every `call` target, every free `Tmp`, or every used `%N`.
+//! - The director half (`rewrite_op_operands`, `rewrite_terminator_operands`,
+//!   `transform_instructions`) reconstructs instructions/terminators from a
+//!   caller-supplied callback, reusing everything the callback doesn't touch.
+//!
+//! New passes should reach for these instead of matching on `Op`/`Terminator`
+//! directly wherever they only care about operands, not op-specific shape.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use super::hir::*;
+
+/// A combinable accumulator for the monoidal reducer. `empty()` is the
+/// identity and `combine` must be associative, so a caller's result doesn't
+/// depend on traversal order.
+pub trait Monoid {
+    fn empty() -> Self;
+    fn combine(self, other: Self) -> Self;
+}
+
+impl<T> Monoid for Vec<T> {
+    fn empty() -> Self {
+        Vec::new()
+    }
+
+    fn combine(mut self, mut other: Self) -> Self {
+        self.append(&mut other);
+        self
+    }
+}
+
+impl<T: Eq + Hash> Monoid for HashSet<T> {
+    fn empty() -> Self {
+        HashSet::new()
+    }
+
+    fn combine(mut self, other: Self) -> Self {
+        self.extend(other);
+        self
+    }
+}
+
+/// Operands read by an instruction's `Op`.
+pub fn op_operands(op: &Op) -> Vec<Tmp> {
+    match op {
+        Op::ConstInt(_)
+        | Op::ConstFloat(_)
+        | Op::ConstString(_)
+        | Op::ConstBool(_)
+        | Op::ConstUnit { .. }
+        | Op::Unit
+        | Op::EmptyList => vec![],
+        Op::BinOp { left, right, .. } => vec![*left, *right],
+        Op::Neg(tmp) | Op::Not(tmp) | Op::Deref(tmp) | Op::Await(tmp) | Op::IterInit(tmp) => {
+            vec![*tmp]
+        }
+        Op::Field { base, .. } | Op::OptionalField { base, .. } => vec![*base],
+        Op::VariantTest { value, .. } => vec![*value],
+        Op::VariantField { base, .. } => vec![*base],
+        Op::Discriminant(tmp) => vec![*tmp],
+        Op::Call { args, .. } | Op::Variant { args, .. } => args.clone(),
+        Op::List(items) => items.clone(),
+        Op::ListPush { list, value } | Op::ListExtend { list, value } => vec![*list, *value],
+        Op::Struct { fields, .. } => fields
+            .iter()
+            .map(|field| match field {
+                HirStructField::Set { value, .. } => *value,
+                HirStructField::Spread(tmp) => *tmp,
+            })
+            .collect(),
+        Op::Copy(tmp) => vec![*tmp],
+    }
+}
+
+/// Operands read by a terminator (jump/branch arguments count as reads of
+/// the values they forward to the target block's params).
+pub fn terminator_operands(terminator: &Terminator) -> Vec<Tmp> {
+    match terminator {
+        Terminator::Jump(_, args) => args.clone(),
+        Terminator::Branch {
+            cond,
+            then_args,
+            else_args,
+            ..
+        } => {
+            let mut operands = vec![*cond];
+            operands.extend(then_args);
+            operands.extend(else_args);
+            operands
+        }
+        Terminator::Return(tmp) => vec![*tmp],
+        Terminator::IterNext { iter, .. } => vec![*iter],
+        Terminator::Unreachable => vec![],
+        Terminator::Drop { value, .. } => vec![*value],
+    }
+}
+
+/// Rewrite every `Tmp` operand read by `op` through `rewrite`, in place.
+pub fn rewrite_op_operands(op: &mut Op, mut rewrite: impl FnMut(Tmp) -> Tmp) {
+    match op {
+        Op::ConstInt(_)
+        | Op::ConstFloat(_)
+        | Op::ConstString(_)
+        | Op::ConstBool(_)
+        | Op::ConstUnit { .. }
+        | Op::Unit
+        | Op::EmptyList => {}
+        Op::BinOp { left, right, .. } => {
+            *left = rewrite(*left);
+            *right = rewrite(*right);
+        }
+        Op::Neg(tmp) | Op::Not(tmp) | Op::Deref(tmp) | Op::Await(tmp) | Op::IterInit(tmp) => {
+            *tmp = rewrite(*tmp);
+        }
+        Op::Field { base, .. } | Op::OptionalField { base, .. } => {
+            *base = rewrite(*base);
+        }
+        Op::VariantTest { value, .. } => {
+            *value = rewrite(*value);
+        }
+        Op::VariantField { base, .. } => {
+            *base = rewrite(*base);
+        }
+        Op::Discriminant(tmp) => {
+            *tmp = rewrite(*tmp);
+        }
+        Op::Call { args, .. } | Op::Variant { args, .. } => {
+            for arg in args {
+                *arg = rewrite(*arg);
+            }
+        }
+        Op::List(items) => {
+            for item in items {
+                *item = rewrite(*item);
+            }
+        }
+        Op::ListPush { list, value } | Op::ListExtend { list, value } => {
+            *list = rewrite(*list);
+            *value = rewrite(*value);
+        }
+        Op::Struct { fields, .. } => {
+            for field in fields {
+                match field {
+                    HirStructField::Set { value, .. } => *value = rewrite(*value),
+                    HirStructField::Spread(tmp) => *tmp = rewrite(*tmp),
+                }
+            }
+        }
+        Op::Copy(tmp) => *tmp = rewrite(*tmp),
+    }
+}
+
+/// Rewrite every `Tmp` operand read by `terminator` through `rewrite`, in place.
+pub fn rewrite_terminator_operands(terminator: &mut Terminator, mut rewrite: impl FnMut(Tmp) -> Tmp) {
+    match terminator {
+        Terminator::Jump(_, args) => {
+            for arg in args {
+                *arg = rewrite(*arg);
+            }
+        }
+        Terminator::Branch {
+            cond,
+            then_args,
+            else_args,
+            ..
+        } => {
+            *cond = rewrite(*cond);
+            for arg in then_args {
+                *arg = rewrite(*arg);
+            }
+            for arg in else_args {
+                *arg = rewrite(*arg);
+            }
+        }
+        Terminator::Return(tmp) => *tmp = rewrite(*tmp),
+        Terminator::IterNext { iter, .. } => *iter = rewrite(*iter),
+        Terminator::Unreachable => {}
+        Terminator::Drop { value, .. } => *value = rewrite(*value),
+    }
+}
+
+/// Fold a combinable accumulator over every instruction in `automation`, in
+/// block order. The monoidal reducer: implement `f` once to collect
+/// something across the whole CFG (every `call` target, every free `Tmp`,
+/// every used `%N`) instead of hand-rolling the block/instruction walk.
+pub fn reduce_instructions<M: Monoid>(
+    automation: &HirAutomation,
+    mut f: impl FnMut(&Instruction) -> M,
+) -> M {
+    automation
+        .blocks
+        .iter()
+        .flat_map(|block| &block.instructions)
+        .map(&mut f)
+        .fold(M::empty(), Monoid::combine)
+}
+
+/// Call `f` with each instruction of `block` in order, short-circuiting on
+/// the first error. Lets callers that need to thread a fallible side effect
+/// (e.g. [`super::pretty_print`]) reuse the block/instruction walk instead
+/// of hand-rolling `for instr in &block.instructions`.
+pub fn for_each_instruction<E>(
+    block: &BasicBlock,
+    mut f: impl FnMut(&Instruction) -> Result<(), E>,
+) -> Result<(), E> {
+    for instr in &block.instructions {
+        f(instr)?;
+    }
+    Ok(())
+}
+
+/// Every `Tmp` read anywhere in `automation`: by an instruction's `Op`, or by
+/// a terminator (jump/branch arguments, the returned value, ...).
+pub fn used_tmps(automation: &HirAutomation) -> HashSet<Tmp> {
+    let mut used: HashSet<Tmp> =
+        reduce_instructions(automation, |instr| op_operands(&instr.op).into_iter().collect());
+    for block in &automation.blocks {
+        used.extend(terminator_operands(&block.terminator));
+    }
+    used
+}
+
+/// Every `name` targeted by an `Op::Call` anywhere in `automation`, in block
+/// order (duplicates included - callers that want a set can collect into
+/// one).
+pub fn call_targets(automation: &HirAutomation) -> Vec<String> {
+    reduce_instructions(automation, |instr| match &instr.op {
+        Op::Call { name, .. } => vec![name.clone()],
+        _ => vec![],
+    })
+}
+
+/// Whether `op` has observable effects beyond producing its own destination
+/// value, and so must be kept even if that destination turns out to be
+/// unread - shared by [`super::hir_dce`] (which deletes unread-and-pure
+/// instructions) and [`super::hir_unused_value`] (which instead reports
+/// them as likely mistakes) so the two can't disagree about what counts as
+/// "has a side effect".
+pub fn has_side_effects(op: &Op) -> bool {
+    matches!(
+        op,
+        Op::Call { .. } | Op::Await(_) | Op::ListPush { .. } | Op::ListExtend { .. } | Op::IterInit(_)
+    )
+}
+
+/// Rebuild `automation`'s blocks via two callbacks: `rewrite` is given each
+/// instruction in turn and may replace it or return `None` to drop it (later
+/// instructions still see the original `Tmp` numbering, so dropping one
+/// never needs renumbering); `rewrite_terminator` is given each block's
+/// terminator. The reconstructing director: passes that only need to
+/// inspect-and-possibly-drop/replace instructions one at a time can use this
+/// instead of hand-rolling the block walk.
+pub fn transform_instructions(
+    automation: &mut HirAutomation,
+    mut rewrite: impl FnMut(Instruction) -> Option<Instruction>,
+    mut rewrite_terminator: impl FnMut(Terminator) -> Terminator,
+) {
+    for block in &mut automation.blocks {
+        block.instructions = std::mem::take(&mut block.instructions)
+            .into_iter()
+            .filter_map(&mut rewrite)
+            .collect();
+        let terminator = std::mem::replace(&mut block.terminator, Terminator::Return(Tmp(0)));
+        block.terminator = rewrite_terminator(terminator);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::AutomationKind;
+    use super::super::typed::Ty;
+    use super::*;
+
+    fn instr(dst: usize, op: Op) -> Instruction {
+        Instruction {
+            dst: Tmp(dst),
+            op,
+            ty: Ty::Int,
+            span: None,
+        }
+    }
+
+    #[test]
+    fn used_tmps_collects_instruction_and_terminator_operands() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(0, Op::ConstInt(1)),
+                    instr(
+                        1,
+                        Op::BinOp {
+                            op: HirBinOp::Add,
+                            left: Tmp(0),
+                            right: Tmp(0),
+                        },
+                    ),
+                ],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        };
+
+        let mut used: Vec<_> = used_tmps(&automation).into_iter().collect();
+        used.sort_by_key(|t| t.0);
+        assert_eq!(used, vec![Tmp(0), Tmp(1)]);
+    }
+
+    #[test]
+    fn call_targets_collects_every_call_name() {
+        let automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![
+                    instr(
+                        0,
+                        Op::Call {
+                            name: "foo".into(),
+                            args: vec![],
+                        },
+                    ),
+                    instr(1, Op::ConstInt(1)),
+                    instr(
+                        2,
+                        Op::Call {
+                            name: "bar".into(),
+                            args: vec![Tmp(1)],
+                        },
+                    ),
+                ],
+                terminator: Terminator::Return(Tmp(2)),
+            }],
+        };
+
+        assert_eq!(call_targets(&automation), vec!["foo", "bar"]);
+    }
+
+    #[test]
+    fn transform_instructions_drops_and_rewrites() {
+        let mut automation = HirAutomation {
+            kind: AutomationKind::Observer,
+            params: Vec::new(),
+            blocks: vec![BasicBlock {
+                id: BlockId(0),
+                params: Vec::new(),
+                instructions: vec![instr(0, Op::ConstInt(1)), instr(1, Op::ConstInt(2))],
+                terminator: Terminator::Return(Tmp(1)),
+            }],
+        };
+
+        transform_instructions(
+            &mut automation,
+            |instr| if instr.dst == Tmp(1) { None } else { Some(instr) },
+            |mut terminator| {
+                rewrite_terminator_operands(&mut terminator, |_| Tmp(0));
+                terminator
+            },
+        );
+
+        assert_eq!(automation.blocks[0].instructions.len(), 1);
+        assert_eq!(automation.blocks[0].instructions[0].dst, Tmp(0));
+        assert!(matches!(
+            automation.blocks[0].terminator,
+            Terminator::Return(Tmp(0))
+        ));
+    }
+}
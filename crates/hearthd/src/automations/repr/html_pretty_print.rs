@@ -0,0 +1,561 @@
+//! HTML rendering of the typed, verbose pretty-printer for `TypedProgram`,
+//! `TypedExpr`, and `CheckResult`.
+//!
+//! This is the typed counterpart to [`super::super::html_print::HtmlPrint`]:
+//! where that module renders source-level AST with ident-to-binding links,
+//! this one renders the post-type-checking tree from [`typed_pretty_print`]
+//! (`super::typed_pretty_print`) with every `[type: X]` annotation turned
+//! into a `<span class="type-ann ...">` - classed by the type's shape
+//! (literal/list/struct/unit/...) - and, when the annotated expression is
+//! the site of an `EntityConstraint`, an `<a href="#...">` straight into
+//! the `EntityConstraints` block rendered alongside it. `Errors` get their
+//! own `error` class so a failed check stands out in a browser at a
+//! glance rather than needing to be read line by line.
+
+use super::typed::*;
+
+/// A self-contained HTML rendering produced by
+/// [`HtmlPrettyPrint::render_to_html`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlSnippet(pub String);
+
+impl std::fmt::Display for HtmlSnippet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn write_indent(indent: usize, out: &mut String) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn span(out: &mut String, class: &str, text: &str) {
+    use std::fmt::Write as _;
+    let _ = write!(
+        out,
+        "<span class=\"{}\">{}</span>",
+        class,
+        escape_html(text)
+    );
+}
+
+fn sanitize_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Classifies `ty`'s CSS class: `literal` for scalars and unit-literal
+/// types, the matching name for each collection (`list`/`set`/`map`/
+/// `option`/`future`), `struct` for a `Ty::Named`/`EnumVariant` type (the
+/// typed AST has no separate nominal-vs-structural distinction, so any
+/// named type is treated as a potential jump target), `unit`/`error`/`var`
+/// for the three types with no surface syntax, and `fn`/`tuple` for the
+/// two produced only by checking a `Lambda`/`Tuple` literal.
+fn ty_class(ty: &Ty) -> &'static str {
+    match ty {
+        Ty::Int
+        | Ty::Float
+        | Ty::Bool
+        | Ty::String
+        | Ty::Duration
+        | Ty::Angle
+        | Ty::Temperature => "literal",
+        Ty::List(_) => "list",
+        Ty::Set(_) => "set",
+        Ty::Map { .. } => "map",
+        Ty::Option(_) => "option",
+        Ty::Future(_) => "future",
+        Ty::Named(_) | Ty::EnumVariant { .. } => "struct",
+        Ty::Unit => "unit",
+        Ty::Error => "error",
+        Ty::Var(_) => "var",
+        Ty::Fn { .. } => "fn",
+        Ty::Tuple(_) => "tuple",
+    }
+}
+
+/// The generic per-type-name anchor a `[type: X]` annotation links to when
+/// it isn't the site of an `EntityConstraint` - e.g. `Ty::Named("Light")`
+/// becomes `#type-Light`, landing on that struct's own definition if one
+/// is rendered into the same page. Returns `None` for types with no
+/// useful jump target (primitives, `Error`, `Var`, ...).
+fn ty_anchor(ty: &Ty) -> Option<String> {
+    match ty {
+        Ty::Named(name) => Some(format!("type-{}", sanitize_id(name))),
+        Ty::EnumVariant { enum_name, .. } => Some(format!("type-{}", sanitize_id(enum_name))),
+        _ => None,
+    }
+}
+
+/// The anchor id an `EntityConstraint`'s own list entry is tagged with, so
+/// a `[type: X]` annotation at the constraint's span can link straight to
+/// it instead of the generic `ty_anchor`.
+fn constraint_anchor(constraint: &EntityConstraint) -> String {
+    format!(
+        "entity-{}-{}",
+        sanitize_id(&constraint.domain),
+        sanitize_id(&constraint.entity)
+    )
+}
+
+/// Renders a `[type: X]` annotation as a classed, linked span. `origin`
+/// is the annotated expression's source span, if any; when it matches an
+/// `EntityConstraint` in `constraints`, the annotation links to that
+/// constraint's anchor instead of the generic `ty_anchor`, so clicking a
+/// `person_tracker.jake` call site's type reaches the exact constraint
+/// that access produced.
+fn html_type_annotation(
+    ty: &Ty,
+    origin: Option<chumsky::span::SimpleSpan>,
+    constraints: &[EntityConstraint],
+    out: &mut String,
+) {
+    use std::fmt::Write as _;
+
+    let class = format!("type-ann {}", ty_class(ty));
+    let text = escape_html(&ty.to_string());
+    let matching_constraint = origin.and_then(|span| constraints.iter().find(|c| c.span == span));
+
+    if let Some(constraint) = matching_constraint {
+        let _ = write!(
+            out,
+            "<a class=\"{}\" href=\"#{}\">{}</a>",
+            class,
+            constraint_anchor(constraint),
+            text
+        );
+    } else if let Some(anchor) = ty_anchor(ty) {
+        let _ = write!(
+            out,
+            "<a class=\"{}\" href=\"#{}\">{}</a>",
+            class, anchor, text
+        );
+    } else {
+        span(out, &class, &ty.to_string());
+    }
+}
+
+/// Renders a typed AST node (or `CheckResult`) as an HTML snippet, the
+/// typed counterpart to [`super::super::html_print::HtmlPrint`].
+/// `constraints` is threaded through every node so a `[type: X]`
+/// annotation can link to the `EntityConstraint` it's the site of, if
+/// any; call [`render_to_html`](HtmlPrettyPrint::render_to_html) rather
+/// than implementing this directly - it's only `pub` so every typed node
+/// type can call into its children's impls.
+pub trait HtmlPrettyPrint {
+    #[doc(hidden)]
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String);
+
+    /// Renders `self` as a self-contained HTML snippet. A bare
+    /// `TypedProgram`/`TypedExpr` has no `EntityConstraint`s to link
+    /// `[type: X]` annotations against; `CheckResult` overrides
+    /// `html_pretty_print` to thread its own `constraints` down
+    /// regardless of what's passed in here, so rendering a whole
+    /// `CheckResult` still gets fully linked annotations and its
+    /// `EntityConstraints`/`Errors` blocks.
+    fn render_to_html(&self) -> HtmlSnippet {
+        let mut out = String::new();
+        self.html_pretty_print(0, &[], &mut out);
+        HtmlSnippet(out)
+    }
+}
+
+impl HtmlPrettyPrint for TypedExpr {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"node typed-expr\">");
+
+        macro_rules! ty_ann {
+            () => {
+                html_type_annotation(&self.ty, Some(self.origin.span()), constraints, out)
+            };
+        }
+
+        match &self.kind {
+            TypedExprKind::Int(n) => {
+                span(out, "kind", "Int: ");
+                span(out, "lit", &n.to_string());
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::Float(n) => {
+                span(out, "kind", "Float: ");
+                span(out, "lit", n);
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::String(s) => {
+                span(out, "kind", "String: ");
+                span(out, "lit", &format!("{:?}", s));
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::Bool(b) => {
+                span(out, "kind", "Bool: ");
+                span(out, "lit", &b.to_string());
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::UnitLiteral { value, unit } => {
+                span(out, "kind", "UnitLiteral: ");
+                span(out, "lit", &format!("{}{}", value, unit));
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::Ident(s) => {
+                span(out, "kind", "Ident: ");
+                span(out, "ident", s);
+                out.push(' ');
+                ty_ann!();
+            }
+            TypedExprKind::Path(segments) => {
+                span(out, "kind", "Path: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                for seg in segments {
+                    write_indent(indent + 1, out);
+                    span(out, "field", seg);
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::BinOp { op, left, right } => {
+                span(out, "kind", "BinOp: ");
+                span(out, "op", &op.to_string());
+                out.push(' ');
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                left.html_pretty_print(indent + 1, constraints, out);
+                right.html_pretty_print(indent + 1, constraints, out);
+                out.push_str("</div>");
+            }
+            TypedExprKind::UnaryOp { op, expr } => {
+                span(out, "kind", "UnaryOp: ");
+                span(out, "op", &op.to_string());
+                out.push(' ');
+                ty_ann!();
+                expr.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedExprKind::Field { expr, field } => {
+                span(out, "kind", &format!("Field: .{}", field));
+                out.push(' ');
+                ty_ann!();
+                expr.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedExprKind::OptionalField { expr, field } => {
+                span(out, "kind", &format!("OptionalField: ?.{}", field));
+                out.push(' ');
+                ty_ann!();
+                expr.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedExprKind::Call { func, args } => {
+                span(out, "kind", "Call: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                func.html_pretty_print(indent + 1, constraints, out);
+                for arg in args {
+                    arg.html_pretty_print(indent + 1, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                span(out, "kind", "If: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                write_indent(indent + 1, out);
+                span(out, "label", "Cond:");
+                cond.html_pretty_print(indent + 2, constraints, out);
+                write_indent(indent + 1, out);
+                span(out, "label", "Then:");
+                for stmt in then_block {
+                    stmt.html_pretty_print(indent + 2, constraints, out);
+                }
+                if let Some(else_stmts) = else_block {
+                    write_indent(indent + 1, out);
+                    span(out, "label", "Else:");
+                    for stmt in else_stmts {
+                        stmt.html_pretty_print(indent + 2, constraints, out);
+                    }
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::List(items) => {
+                span(out, "kind", "List: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                for item in items {
+                    item.html_pretty_print(indent + 1, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::StructLit { name, fields } => {
+                span(out, "kind", "StructLit: ");
+                span(out, "type", name);
+                out.push(' ');
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                for field in fields {
+                    field.html_pretty_print(indent + 1, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::Block { stmts, result } => {
+                span(out, "kind", "Block: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                write_indent(indent + 1, out);
+                span(out, "label", "Stmts:");
+                for stmt in stmts {
+                    stmt.html_pretty_print(indent + 2, constraints, out);
+                }
+                write_indent(indent + 1, out);
+                span(out, "label", "Result:");
+                result.html_pretty_print(indent + 2, constraints, out);
+                out.push_str("</div>");
+            }
+            TypedExprKind::MutableList => {
+                span(out, "kind", "MutableList ");
+                ty_ann!();
+            }
+            TypedExprKind::Match { scrutinee, arms } => {
+                span(out, "kind", "Match: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                write_indent(indent + 1, out);
+                span(out, "label", "Scrutinee:");
+                scrutinee.html_pretty_print(indent + 2, constraints, out);
+                for arm in arms {
+                    arm.html_pretty_print(indent + 1, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+            TypedExprKind::Lambda { params, body } => {
+                span(out, "kind", &format!("Lambda: |{}|", params.join(", ")));
+                out.push(' ');
+                ty_ann!();
+                body.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedExprKind::Tuple(items) => {
+                span(out, "kind", "Tuple: ");
+                ty_ann!();
+                out.push_str("<div class=\"children\">");
+                for item in items {
+                    item.html_pretty_print(indent + 1, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+        }
+
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrettyPrint for TypedMatchArm {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"node arm\">");
+        span(out, "label", "Arm:");
+        write_indent(indent + 1, out);
+        span(out, "pattern", &format!("{:?}", self.pattern.node));
+        write_indent(indent + 1, out);
+        span(out, "label", "Body:");
+        for stmt in &self.body {
+            stmt.html_pretty_print(indent + 2, constraints, out);
+        }
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrettyPrint for TypedStmt {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"node stmt\">");
+        match self {
+            TypedStmt::Let { name, value, .. } => {
+                span(out, "kind", &format!("Let: {}", name));
+                value.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStmt::LetMut { name, value, .. } => {
+                span(out, "kind", &format!("LetMut: {}", name));
+                value.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStmt::Expr(expr) => {
+                span(out, "kind", "ExprStmt:");
+                expr.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStmt::Return(expr, _) => {
+                span(out, "kind", "Return:");
+                expr.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStmt::For {
+                var, iter, body, ..
+            } => {
+                span(out, "kind", &format!("For: {}", var));
+                iter.html_pretty_print(indent + 1, constraints, out);
+                for stmt in body {
+                    stmt.html_pretty_print(indent + 1, constraints, out);
+                }
+            }
+            TypedStmt::Push { list, value, .. } => {
+                span(out, "kind", &format!("Push: {}", list));
+                value.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStmt::While { cond, body, .. } => {
+                span(out, "kind", "While:");
+                cond.html_pretty_print(indent + 1, constraints, out);
+                for stmt in body {
+                    stmt.html_pretty_print(indent + 1, constraints, out);
+                }
+            }
+            TypedStmt::CompoundAssign {
+                name, op, value, ..
+            } => {
+                span(out, "kind", &format!("CompoundAssign: {} {}=", name, op));
+                value.html_pretty_print(indent + 1, constraints, out);
+            }
+        }
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrettyPrint for TypedArg {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        match self {
+            TypedArg::Positional(expr) => expr.html_pretty_print(indent, constraints, out),
+            TypedArg::Named { name, value } => {
+                write_indent(indent, out);
+                out.push_str("<div class=\"node named-arg\">");
+                span(out, "kind", &format!("Named: {}", name));
+                value.html_pretty_print(indent + 1, constraints, out);
+                out.push_str("</div>");
+            }
+        }
+    }
+}
+
+impl HtmlPrettyPrint for TypedStructField {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"node field\">");
+        match self {
+            TypedStructField::Field { name, value } => {
+                span(out, "kind", &format!("Field: {}", name));
+                value.html_pretty_print(indent + 1, constraints, out);
+            }
+            TypedStructField::Inherit(name) => span(out, "kind", &format!("Inherit: {}", name)),
+            TypedStructField::Spread(name) => span(out, "kind", &format!("Spread: {}", name)),
+        }
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrettyPrint for TypedAutomation {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        write_indent(indent, out);
+        out.push_str("<div class=\"node automation\">");
+        span(out, "kind", &format!("Automation: {}", self.kind));
+        write_indent(indent + 1, out);
+        span(out, "label", "Pattern:");
+        write_indent(indent + 2, out);
+        span(out, "pattern", &format!("{:?}", self.pattern.node));
+        if let Some(filter) = &self.filter {
+            write_indent(indent + 1, out);
+            span(out, "label", "Filter:");
+            filter.html_pretty_print(indent + 2, constraints, out);
+        }
+        write_indent(indent + 1, out);
+        span(out, "label", "Body:");
+        for stmt in &self.body {
+            stmt.html_pretty_print(indent + 2, constraints, out);
+        }
+        out.push_str("</div>");
+    }
+}
+
+impl HtmlPrettyPrint for TypedProgram {
+    fn html_pretty_print(&self, indent: usize, constraints: &[EntityConstraint], out: &mut String) {
+        match self {
+            TypedProgram::Automation(auto) => auto.html_pretty_print(indent, constraints, out),
+            TypedProgram::Template {
+                params,
+                automations,
+            } => {
+                write_indent(indent, out);
+                out.push_str("<div class=\"node template\">");
+                span(out, "kind", "Template:");
+                write_indent(indent + 1, out);
+                span(out, "label", "Params:");
+                for param in params {
+                    write_indent(indent + 2, out);
+                    span(out, "pattern", &format!("{:?}", param.node));
+                }
+                write_indent(indent + 1, out);
+                span(out, "label", "Automations:");
+                for auto in automations {
+                    auto.html_pretty_print(indent + 2, constraints, out);
+                }
+                out.push_str("</div>");
+            }
+        }
+    }
+}
+
+impl HtmlPrettyPrint for CheckResult {
+    fn html_pretty_print(
+        &self,
+        indent: usize,
+        _constraints: &[EntityConstraint],
+        out: &mut String,
+    ) {
+        self.program
+            .html_pretty_print(indent, &self.constraints, out);
+
+        if !self.constraints.is_empty() {
+            write_indent(indent, out);
+            out.push_str("<div class=\"node entity-constraints\">");
+            span(out, "label", "EntityConstraints:");
+            for c in &self.constraints {
+                write_indent(indent + 1, out);
+                let _ = std::fmt::Write::write_fmt(
+                    out,
+                    format_args!(
+                        "<div class=\"constraint\" id=\"{}\">{}.{} @ {}..{}</div>",
+                        constraint_anchor(c),
+                        escape_html(&c.domain),
+                        escape_html(&c.entity),
+                        c.span.start,
+                        c.span.end
+                    ),
+                );
+            }
+            out.push_str("</div>");
+        }
+
+        if !self.errors.is_empty() {
+            write_indent(indent, out);
+            out.push_str("<div class=\"node errors\">");
+            span(out, "label", "Errors:");
+            for e in &self.errors {
+                write_indent(indent + 1, out);
+                let _ = std::fmt::Write::write_fmt(
+                    out,
+                    format_args!("<div class=\"error\">{}</div>", escape_html(&e.to_string())),
+                );
+            }
+            out.push_str("</div>");
+        }
+    }
+}
@@ -22,6 +22,12 @@ pub enum Origin {
     /// Synthetic node generated from desugaring a ListComp.
     /// Uses Rc because multiple synthetic nodes share the same original ListComp.
     ListComp(Rc<ast::Spanned<ast::Expr>>),
+    /// Synthetic node generated from desugaring something other than a
+    /// ListComp, e.g. a `BinOp`/`UnaryOp` lowered to a builtin `Call` (see
+    /// `Desugarer::with_operator_calls`). Uses Rc for the same reason as
+    /// `ListComp`: several synthetic nodes (the call, its callee `Ident`,
+    /// its args) share the one original expression.
+    Desugared(Rc<ast::Spanned<ast::Expr>>),
 }
 
 impl Origin {
@@ -30,6 +36,7 @@ impl Origin {
         match self {
             Origin::Direct(expr) => expr,
             Origin::ListComp(rc) => rc,
+            Origin::Desugared(rc) => rc,
         }
     }
 
@@ -40,7 +47,7 @@ impl Origin {
 
     /// Returns true if this is a synthetic node from desugaring.
     pub fn is_synthetic(&self) -> bool {
-        matches!(self, Origin::ListComp(_))
+        matches!(self, Origin::ListComp(_) | Origin::Desugared(_))
     }
 }
 
@@ -138,6 +145,38 @@ pub enum LoweredExpr {
 
     // Create empty mutable list (synthetic, from ListComp desugaring)
     MutableList,
+
+    // Create empty mutable map (synthetic, from DictComp desugaring)
+    MutableMap,
+
+    // Create empty mutable set (synthetic, from SetComp desugaring)
+    MutableSet,
+
+    // Match expression
+    Match {
+        scrutinee: Box<Spanned<LoweredExpr>>,
+        arms: Vec<LoweredMatchArm>,
+    },
+
+    // Lambda expression: `|params| body`, e.g. the predicate passed to
+    // `filter`/`map`/`fold`.
+    Lambda {
+        params: Vec<String>,
+        body: Box<Spanned<LoweredExpr>>,
+    },
+
+    // Tuple literal: `(a, b, c)`.
+    Tuple(Vec<Spanned<LoweredExpr>>),
+}
+
+/// A single arm of a lowered `match` expression. The pattern is carried
+/// through unchanged from the surface AST (matching how [`LoweredAutomation`]
+/// carries its `pattern` field) since patterns contain no sub-expressions
+/// that desugaring would need to rewrite.
+#[derive(Debug, Clone)]
+pub struct LoweredMatchArm {
+    pub pattern: ast::Spanned<ast::MatchPattern>,
+    pub body: Vec<Spanned<LoweredStmt>>,
 }
 
 /// A lowered statement.
@@ -150,12 +189,22 @@ pub enum LoweredStmt {
     },
 
     /// Mutable let binding: `let mut x = expr;`
-    /// Generated during desugaring of list comprehensions.
+    /// Written directly by users, and also synthesized during desugaring of
+    /// list/dict/set comprehensions.
     LetMut {
         name: String,
         value: Spanned<LoweredExpr>,
     },
 
+    /// Compound assignment to a mutable binding: `name += value;` (and
+    /// `-=`/`*=`/`/=`/`%=`). `op` reuses [`BinOp`] the same way
+    /// `ast::Stmt::CompoundAssign` does.
+    CompoundAssign {
+        name: String,
+        op: BinOp,
+        value: Spanned<LoweredExpr>,
+    },
+
     /// Expression statement
     Expr(Spanned<LoweredExpr>),
 
@@ -176,15 +225,47 @@ pub enum LoweredStmt {
         list: String,
         value: Spanned<LoweredExpr>,
     },
+
+    /// Insert a key/value pair into a mutable map variable (synthetic, from
+    /// DictComp desugaring). References the map by variable name, not by
+    /// expression, the same as `Push` does for lists.
+    Insert {
+        map: String,
+        key: Spanned<LoweredExpr>,
+        value: Spanned<LoweredExpr>,
+    },
+
+    /// Add a value to a mutable set variable (synthetic, from SetComp
+    /// desugaring). References the set by variable name, not by expression,
+    /// the same as `Push` does for lists.
+    Add {
+        set: String,
+        value: Spanned<LoweredExpr>,
+    },
+
+    /// Condition-guarded loop: `while cond { body }` (synthetic, from
+    /// desugaring a comprehension `for` clause over a range expression -
+    /// there's no surface-syntax `while` to lower from). Unlike `For`,
+    /// `cond` is re-evaluated before every iteration rather than fixed to
+    /// one iterable.
+    While {
+        cond: Spanned<LoweredExpr>,
+        body: Vec<Spanned<LoweredStmt>>,
+    },
 }
 
 /// A lowered automation definition.
 #[derive(Debug, Clone)]
 pub struct LoweredAutomation {
     pub kind: ast::AutomationKind,
+    /// Span of just the `observer`/`mutator` keyword; see
+    /// `ast::Automation::kind_span`.
+    pub kind_span: chumsky::span::SimpleSpan,
     pub pattern: ast::Spanned<ast::Pattern>,
     pub filter: Option<Spanned<LoweredExpr>>,
     pub body: Vec<Spanned<LoweredStmt>>,
+    /// The file this automation was parsed from; see [`ast::FileId`].
+    pub file: ast::FileId,
 }
 
 /// A lowered top-level program.
@@ -194,9 +275,25 @@ pub enum LoweredProgram {
     Template {
         params: Vec<ast::Spanned<ast::TemplateParam>>,
         automations: Vec<LoweredAutomation>,
+        /// The file this template was parsed from; see [`ast::FileId`].
+        /// Each of `automations` also carries its own `file` (currently
+        /// always the same one, since a template is one parse), so callers
+        /// that only have a `LoweredAutomation` in hand don't need to go
+        /// looking for the enclosing `Template`.
+        file: ast::FileId,
     },
 }
 
+impl LoweredProgram {
+    /// The file this program was parsed from; see [`ast::FileId`].
+    pub fn file(&self) -> ast::FileId {
+        match self {
+            LoweredProgram::Automation(automation) => automation.file,
+            LoweredProgram::Template { file, .. } => *file,
+        }
+    }
+}
+
 /// Lowered function argument.
 #[derive(Debug, Clone)]
 pub enum LoweredArg {
@@ -3,6 +3,7 @@
 //! Used by desugar tests to produce unambiguous snapshot output.
 
 use super::lowered::*;
+use super::pretty_print::PrettyPrint as AstPrettyPrint;
 
 /// Trait for verbose, multi-line AST pretty-printing.
 pub trait PrettyPrint {
@@ -38,6 +39,10 @@ impl PrettyPrint for Origin {
                 let span = rc.span;
                 writeln!(f, "Origin: ListComp @ {}..{}", span.start, span.end)
             }
+            Origin::Desugared(rc) => {
+                let span = rc.span;
+                writeln!(f, "Origin: Desugared @ {}..{}", span.start, span.end)
+            }
         }
     }
 }
@@ -153,7 +158,44 @@ impl PrettyPrint for LoweredExpr {
                 result.pretty_print(indent + 2, f)
             }
             LoweredExpr::MutableList => writeln!(f, "MutableList"),
+            LoweredExpr::MutableMap => writeln!(f, "MutableMap"),
+            LoweredExpr::MutableSet => writeln!(f, "MutableSet"),
+            LoweredExpr::Match { scrutinee, arms } => {
+                writeln!(f, "Match:")?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Scrutinee:")?;
+                scrutinee.pretty_print(indent + 2, f)?;
+                for arm in arms {
+                    arm.pretty_print(indent + 1, f)?;
+                }
+                Ok(())
+            }
+            LoweredExpr::Lambda { params, body } => {
+                writeln!(f, "Lambda: |{}|", params.join(", "))?;
+                body.pretty_print(indent + 1, f)
+            }
+            LoweredExpr::Tuple(items) => {
+                writeln!(f, "Tuple:")?;
+                for item in items {
+                    item.pretty_print(indent + 1, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl PrettyPrint for LoweredMatchArm {
+    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(f, "Arm:")?;
+        self.pattern.pretty_print(indent + 1, f)?;
+        write_indent(indent + 1, f)?;
+        writeln!(f, "Body:")?;
+        for stmt in &self.body {
+            stmt.pretty_print(indent + 2, f)?;
         }
+        Ok(())
     }
 }
 
@@ -191,10 +233,35 @@ impl PrettyPrint for LoweredStmt {
                 }
                 Ok(())
             }
+            LoweredStmt::While { cond, body } => {
+                writeln!(f, "While:")?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Cond:")?;
+                cond.pretty_print(indent + 2, f)?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Body:")?;
+                for stmt in body {
+                    stmt.pretty_print(indent + 2, f)?;
+                }
+                Ok(())
+            }
             LoweredStmt::Push { list, value } => {
                 writeln!(f, "Push: {}", list)?;
                 value.pretty_print(indent + 1, f)
             }
+            LoweredStmt::CompoundAssign { name, op, value } => {
+                writeln!(f, "CompoundAssign: {} {}=", name, op)?;
+                value.pretty_print(indent + 1, f)
+            }
+            LoweredStmt::Insert { map, key, value } => {
+                writeln!(f, "Insert: {}", map)?;
+                key.pretty_print(indent + 1, f)?;
+                value.pretty_print(indent + 1, f)
+            }
+            LoweredStmt::Add { set, value } => {
+                writeln!(f, "Add: {}", set)?;
+                value.pretty_print(indent + 1, f)
+            }
         }
     }
 }
@@ -0,0 +1,437 @@
+//! Generic traversal layer for the lowered AST.
+//!
+//! Optimization, validation, and substitution passes over [`super::lowered`]
+//! otherwise each have to hand-roll the same full match over every
+//! `LoweredExpr`/`LoweredStmt` variant just to reach the handful of nodes
+//! they actually care about. [`Spanned<LoweredExpr>::map_children`] and
+//! [`Spanned<LoweredExpr>::fold`] (with parallel methods on
+//! `Spanned<LoweredStmt>`) do that walk once, the same way
+//! [`super::hir_visit`] does it for the HIR: `map_children` recurses
+//! read-only over every expression reachable from a node (including through
+//! nested statement blocks - `If`/`Block`/`Match` arm bodies, `For.body`,
+//! and so on), and `fold` rebuilds the tree bottom-up, handing each
+//! reconstructed node to the caller's callback and preserving its `Origin`
+//! unless the callback swaps the node out for a different one. This mirrors
+//! the `traverse_ref`/`map_ref` pattern used in Dhall's core.
+
+use super::lowered::{
+    LoweredArg, LoweredAutomation, LoweredExpr, LoweredMatchArm, LoweredProgram, LoweredStmt,
+    LoweredStructField, Spanned,
+};
+
+impl Spanned<LoweredExpr> {
+    /// Call `f` with every expression reachable from this node, not just its
+    /// direct children - `self` itself is not passed to `f`, so callers that
+    /// want the whole subtree (including the root) should call `f` on it
+    /// directly first.
+    pub fn map_children(&self, f: &mut impl FnMut(&Spanned<LoweredExpr>)) {
+        match &self.node {
+            LoweredExpr::Int(_)
+            | LoweredExpr::Float(_)
+            | LoweredExpr::String(_)
+            | LoweredExpr::Bool(_)
+            | LoweredExpr::UnitLiteral { .. }
+            | LoweredExpr::Ident(_)
+            | LoweredExpr::Path(_)
+            | LoweredExpr::MutableList
+            | LoweredExpr::MutableMap
+            | LoweredExpr::MutableSet => {}
+            LoweredExpr::BinOp { left, right, .. } => {
+                f(left);
+                left.map_children(f);
+                f(right);
+                right.map_children(f);
+            }
+            LoweredExpr::UnaryOp { expr, .. }
+            | LoweredExpr::Field { expr, .. }
+            | LoweredExpr::OptionalField { expr, .. } => {
+                f(expr);
+                expr.map_children(f);
+            }
+            LoweredExpr::Call { func, args } => {
+                f(func);
+                func.map_children(f);
+                for arg in args {
+                    let value = arg_value(&arg.node);
+                    f(value);
+                    value.map_children(f);
+                }
+            }
+            LoweredExpr::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                f(cond);
+                cond.map_children(f);
+                for stmt in then_block {
+                    stmt.map_children(f);
+                }
+                if let Some(else_block) = else_block {
+                    for stmt in else_block {
+                        stmt.map_children(f);
+                    }
+                }
+            }
+            LoweredExpr::List(items) => {
+                for item in items {
+                    f(item);
+                    item.map_children(f);
+                }
+            }
+            LoweredExpr::StructLit { fields, .. } => {
+                for field in fields {
+                    if let LoweredStructField::Field { value, .. } = &field.node {
+                        f(value);
+                        value.map_children(f);
+                    }
+                }
+            }
+            LoweredExpr::Block { stmts, result } => {
+                for stmt in stmts {
+                    stmt.map_children(f);
+                }
+                f(result);
+                result.map_children(f);
+            }
+            LoweredExpr::Match { scrutinee, arms } => {
+                f(scrutinee);
+                scrutinee.map_children(f);
+                for arm in arms {
+                    for stmt in &arm.body {
+                        stmt.map_children(f);
+                    }
+                }
+            }
+            LoweredExpr::Lambda { body, .. } => {
+                f(body);
+                body.map_children(f);
+            }
+            LoweredExpr::Tuple(items) => {
+                for item in items {
+                    f(item);
+                    item.map_children(f);
+                }
+            }
+        }
+    }
+
+    /// Rebuild this node bottom-up, folding every descendant first and then
+    /// handing the reconstructed node to `f`. `f` may return it unchanged,
+    /// in which case the node's `Origin` is untouched, or replace it outright
+    /// with a different node (and `Origin`).
+    pub fn fold(
+        self,
+        f: &mut impl FnMut(Spanned<LoweredExpr>) -> Spanned<LoweredExpr>,
+    ) -> Spanned<LoweredExpr> {
+        let Spanned { node, origin } = self;
+        let node = match node {
+            leaf @ (LoweredExpr::Int(_)
+            | LoweredExpr::Float(_)
+            | LoweredExpr::String(_)
+            | LoweredExpr::Bool(_)
+            | LoweredExpr::UnitLiteral { .. }
+            | LoweredExpr::Ident(_)
+            | LoweredExpr::Path(_)
+            | LoweredExpr::MutableList
+            | LoweredExpr::MutableMap
+            | LoweredExpr::MutableSet) => leaf,
+            LoweredExpr::BinOp { op, left, right } => LoweredExpr::BinOp {
+                op,
+                left: Box::new(left.fold(f)),
+                right: Box::new(right.fold(f)),
+            },
+            LoweredExpr::UnaryOp { op, expr } => LoweredExpr::UnaryOp {
+                op,
+                expr: Box::new(expr.fold(f)),
+            },
+            LoweredExpr::Field { expr, field } => LoweredExpr::Field {
+                expr: Box::new(expr.fold(f)),
+                field,
+            },
+            LoweredExpr::OptionalField { expr, field } => LoweredExpr::OptionalField {
+                expr: Box::new(expr.fold(f)),
+                field,
+            },
+            LoweredExpr::Call { func, args } => LoweredExpr::Call {
+                func: Box::new(func.fold(f)),
+                args: args.into_iter().map(|arg| fold_arg(arg, f)).collect(),
+            },
+            LoweredExpr::If {
+                cond,
+                then_block,
+                else_block,
+            } => LoweredExpr::If {
+                cond: Box::new(cond.fold(f)),
+                then_block: fold_stmts(then_block, f),
+                else_block: else_block.map(|block| fold_stmts(block, f)),
+            },
+            LoweredExpr::List(items) => {
+                LoweredExpr::List(items.into_iter().map(|item| item.fold(f)).collect())
+            }
+            LoweredExpr::StructLit { name, fields } => LoweredExpr::StructLit {
+                name,
+                fields: fields.into_iter().map(|field| fold_field(field, f)).collect(),
+            },
+            LoweredExpr::Block { stmts, result } => LoweredExpr::Block {
+                stmts: fold_stmts(stmts, f),
+                result: Box::new(result.fold(f)),
+            },
+            LoweredExpr::Match { scrutinee, arms } => LoweredExpr::Match {
+                scrutinee: Box::new(scrutinee.fold(f)),
+                arms: arms
+                    .into_iter()
+                    .map(|arm| LoweredMatchArm {
+                        pattern: arm.pattern,
+                        body: fold_stmts(arm.body, f),
+                    })
+                    .collect(),
+            },
+            LoweredExpr::Lambda { params, body } => LoweredExpr::Lambda {
+                params,
+                body: Box::new(body.fold(f)),
+            },
+            LoweredExpr::Tuple(items) => {
+                LoweredExpr::Tuple(items.into_iter().map(|item| item.fold(f)).collect())
+            }
+        };
+        f(Spanned::new(node, origin))
+    }
+}
+
+impl Spanned<LoweredStmt> {
+    /// Call `f` with every expression reachable from this statement,
+    /// recursing into nested statement blocks (`For.body`, `While.body`) the
+    /// same way [`Spanned<LoweredExpr>::map_children`] does for expressions.
+    pub fn map_children(&self, f: &mut impl FnMut(&Spanned<LoweredExpr>)) {
+        match &self.node {
+            LoweredStmt::Let { value, .. }
+            | LoweredStmt::LetMut { value, .. }
+            | LoweredStmt::Expr(value)
+            | LoweredStmt::Return(value)
+            | LoweredStmt::Push { value, .. }
+            | LoweredStmt::CompoundAssign { value, .. }
+            | LoweredStmt::Add { value, .. } => {
+                f(value);
+                value.map_children(f);
+            }
+            LoweredStmt::Insert { key, value, .. } => {
+                f(key);
+                key.map_children(f);
+                f(value);
+                value.map_children(f);
+            }
+            LoweredStmt::For { iter, body, .. } => {
+                f(iter);
+                iter.map_children(f);
+                for stmt in body {
+                    stmt.map_children(f);
+                }
+            }
+            LoweredStmt::While { cond, body } => {
+                f(cond);
+                cond.map_children(f);
+                for stmt in body {
+                    stmt.map_children(f);
+                }
+            }
+        }
+    }
+
+    /// Rebuild this statement bottom-up, folding every expression it
+    /// contains through [`Spanned<LoweredExpr>::fold`].
+    pub fn fold(
+        self,
+        f: &mut impl FnMut(Spanned<LoweredExpr>) -> Spanned<LoweredExpr>,
+    ) -> Spanned<LoweredStmt> {
+        let Spanned { node, origin } = self;
+        let node = match node {
+            LoweredStmt::Let { name, value } => LoweredStmt::Let {
+                name,
+                value: value.fold(f),
+            },
+            LoweredStmt::LetMut { name, value } => LoweredStmt::LetMut {
+                name,
+                value: value.fold(f),
+            },
+            LoweredStmt::Expr(value) => LoweredStmt::Expr(value.fold(f)),
+            LoweredStmt::Return(value) => LoweredStmt::Return(value.fold(f)),
+            LoweredStmt::For { var, iter, body } => LoweredStmt::For {
+                var,
+                iter: iter.fold(f),
+                body: fold_stmts(body, f),
+            },
+            LoweredStmt::Push { list, value } => LoweredStmt::Push {
+                list,
+                value: value.fold(f),
+            },
+            LoweredStmt::CompoundAssign { name, op, value } => LoweredStmt::CompoundAssign {
+                name,
+                op,
+                value: value.fold(f),
+            },
+            LoweredStmt::Insert { map, key, value } => LoweredStmt::Insert {
+                map,
+                key: key.fold(f),
+                value: value.fold(f),
+            },
+            LoweredStmt::Add { set, value } => LoweredStmt::Add {
+                set,
+                value: value.fold(f),
+            },
+            LoweredStmt::While { cond, body } => LoweredStmt::While {
+                cond: cond.fold(f),
+                body: fold_stmts(body, f),
+            },
+        };
+        Spanned::new(node, origin)
+    }
+}
+
+fn fold_stmts(
+    stmts: Vec<Spanned<LoweredStmt>>,
+    f: &mut impl FnMut(Spanned<LoweredExpr>) -> Spanned<LoweredExpr>,
+) -> Vec<Spanned<LoweredStmt>> {
+    stmts.into_iter().map(|stmt| stmt.fold(f)).collect()
+}
+
+fn fold_arg(
+    arg: Spanned<LoweredArg>,
+    f: &mut impl FnMut(Spanned<LoweredExpr>) -> Spanned<LoweredExpr>,
+) -> Spanned<LoweredArg> {
+    let Spanned { node, origin } = arg;
+    let node = match node {
+        LoweredArg::Positional(value) => LoweredArg::Positional(value.fold(f)),
+        LoweredArg::Named { name, value } => LoweredArg::Named {
+            name,
+            value: value.fold(f),
+        },
+    };
+    Spanned::new(node, origin)
+}
+
+fn fold_field(
+    field: Spanned<LoweredStructField>,
+    f: &mut impl FnMut(Spanned<LoweredExpr>) -> Spanned<LoweredExpr>,
+) -> Spanned<LoweredStructField> {
+    let Spanned { node, origin } = field;
+    let node = match node {
+        LoweredStructField::Field { name, value } => LoweredStructField::Field {
+            name,
+            value: value.fold(f),
+        },
+        LoweredStructField::Inherit(name) => LoweredStructField::Inherit(name),
+        LoweredStructField::Spread(name) => LoweredStructField::Spread(name),
+    };
+    Spanned::new(node, origin)
+}
+
+fn arg_value(arg: &LoweredArg) -> &Spanned<LoweredExpr> {
+    match arg {
+        LoweredArg::Positional(value) => value,
+        LoweredArg::Named { value, .. } => value,
+    }
+}
+
+/// Call `f` with every expression reachable from `automation`'s `filter`
+/// and `body`, the top-level entry point analogous to walking every block
+/// of a [`super::hir::HirAutomation`] with `hir_visit`.
+pub fn automation_map_children(
+    automation: &LoweredAutomation,
+    f: &mut impl FnMut(&Spanned<LoweredExpr>),
+) {
+    if let Some(filter) = &automation.filter {
+        f(filter);
+        filter.map_children(f);
+    }
+    for stmt in &automation.body {
+        stmt.map_children(f);
+    }
+}
+
+/// Call `f` with every expression reachable from every automation in
+/// `program`, whether it's a single top-level automation or a template's
+/// automations.
+pub fn program_map_children(program: &LoweredProgram, f: &mut impl FnMut(&Spanned<LoweredExpr>)) {
+    match program {
+        LoweredProgram::Automation(automation) => automation_map_children(automation, f),
+        LoweredProgram::Template { automations, .. } => {
+            for automation in automations {
+                automation_map_children(automation, f);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::automations::ast;
+    use crate::automations::repr::lowered::{BinOp, Origin, UnaryOp};
+
+    fn origin() -> Origin {
+        Origin::Direct(ast::Spanned::new(ast::Expr::Int(0), (0..0).into()))
+    }
+
+    fn spanned(node: LoweredExpr) -> Spanned<LoweredExpr> {
+        Spanned::new(node, origin())
+    }
+
+    #[test]
+    fn map_children_visits_every_nested_expression() {
+        let expr = spanned(LoweredExpr::BinOp {
+            op: BinOp::Add,
+            left: Box::new(spanned(LoweredExpr::Int(1))),
+            right: Box::new(spanned(LoweredExpr::UnaryOp {
+                op: UnaryOp::Neg,
+                expr: Box::new(spanned(LoweredExpr::Int(2))),
+            })),
+        });
+
+        let mut visited = Vec::new();
+        expr.map_children(&mut |child| visited.push(format!("{:?}", child.node)));
+
+        assert_eq!(visited.len(), 3);
+    }
+
+    #[test]
+    fn map_children_descends_into_if_branches() {
+        let expr = spanned(LoweredExpr::If {
+            cond: Box::new(spanned(LoweredExpr::Bool(true))),
+            then_block: vec![Spanned::new(
+                LoweredStmt::Expr(spanned(LoweredExpr::Int(1))),
+                origin(),
+            )],
+            else_block: Some(vec![Spanned::new(
+                LoweredStmt::Expr(spanned(LoweredExpr::Int(2))),
+                origin(),
+            )]),
+        });
+
+        let mut count = 0;
+        expr.map_children(&mut |_| count += 1);
+
+        // cond, then_block's Int(1), else_block's Int(2)
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn fold_rewrites_a_child_and_preserves_parent_origin() {
+        let expr = spanned(LoweredExpr::UnaryOp {
+            op: UnaryOp::Neg,
+            expr: Box::new(spanned(LoweredExpr::Int(1))),
+        });
+
+        let folded = expr.fold(&mut |node| match node.node {
+            LoweredExpr::Int(1) => Spanned::new(LoweredExpr::Int(42), node.origin),
+            _ => node,
+        });
+
+        match folded.node {
+            LoweredExpr::UnaryOp { expr, .. } => {
+                assert!(matches!(expr.node, LoweredExpr::Int(42)));
+            }
+            other => panic!("expected UnaryOp, got {other:?}"),
+        }
+    }
+}
@@ -4,16 +4,41 @@
 //! pretty-printing utilities for debugging and testing.
 
 pub mod ast;
+pub mod doc;
 pub mod hir;
+pub mod hir_branch_fold;
+pub mod hir_cfg_simplify;
+pub mod hir_copy_prop;
+pub mod hir_dce;
+pub mod hir_drop_elaborate;
+pub mod hir_fold;
+pub mod hir_optimize;
+pub mod hir_unused_value;
+pub mod hir_visit;
 pub mod lowered;
+pub mod lowered_visit;
 pub mod pretty_print;
 pub mod typed;
+pub mod units;
 
 // Pretty print impls (use the same PrettyPrint trait)
 mod hir_pretty_print;
 mod lowered_pretty_print;
 mod typed_pretty_print;
 
+// Width-aware compact rendering (use the same Doc layout engine)
+mod typed_compact_print;
+
+// Serde-based structured (JSON) export, parallel to the pretty-printers
+pub mod typed_export;
+
+// HTML rendering of the typed pretty-printer, for browser-viewable snapshots
+mod html_pretty_print;
+
+// GraphViz/DOT rendering of the HIR (inherent `to_dot` methods on
+// `HirAutomation`/`HirProgram`, so no re-export is needed)
+mod hir_dot;
+
 // Re-export AST types at the repr level
 pub use ast::*;
 // Re-export HIR types
@@ -21,15 +46,45 @@ pub use hir::{
     BasicBlock, BlockId, HirAutomation, HirBinOp, HirProgram, HirStructField, Instruction, Op,
     Param, Terminator, Tmp,
 };
+// Re-export the HIR constant-folding pass
+pub use hir_fold::{FoldDiagnostic, fold_program};
+// Re-export the HIR branch-folding pass
+pub use hir_branch_fold::branch_fold_program;
+// Re-export the HIR CFG cleanup pass (unreachable-block pruning + goto-chain collapsing)
+pub use hir_cfg_simplify::cfg_simplify_program;
+// Re-export the HIR dead-code elimination pass
+pub use hir_dce::dce_program;
+// Re-export the HIR drop-elaboration pass
+pub use hir_drop_elaborate::elaborate_drops_program;
+// Re-export the HIR copy-propagation pass
+pub use hir_copy_prop::copy_prop_program;
+// Re-export the fixpoint orchestration of the above passes
+pub use hir_optimize::optimize_program;
+// Re-export the unused-value analysis (run before optimize_program, which would otherwise delete its evidence)
+pub use hir_unused_value::{UnusedValueDiagnostic, find_unused_values};
+// Re-export the generic HIR traversal layer (monoidal reducer + reconstructing transformer)
+pub use hir_visit::{
+    Monoid, call_targets, for_each_instruction, op_operands, reduce_instructions,
+    rewrite_op_operands, rewrite_terminator_operands, terminator_operands, transform_instructions,
+    used_tmps,
+};
+// Re-export unit dimensional-analysis helpers
+pub use units::{Dimension, canonical_unit, dimension_of, to_base};
 // Re-export lowered AST types with a Lowered prefix already in their names
 pub use lowered::{
     LoweredArg, LoweredAutomation, LoweredExpr, LoweredProgram, LoweredStmt, LoweredStructField,
     Origin, Spanned as LoweredSpanned,
 };
+// Re-export the generic lowered-AST traversal layer (map_children/fold)
+pub use lowered_visit::{automation_map_children, program_map_children};
 // Re-export pretty printing
-pub use pretty_print::PrettyPrint;
+pub use pretty_print::{CompactPrint, PrettyPrint};
+// Re-export the Doc layout engine
+pub use doc::Doc;
 // Re-export typed AST types
 pub use typed::{
     CheckResult, EntityConstraint, Ty, TypedArg, TypedAutomation, TypedExpr, TypedExprKind,
     TypedProgram, TypedStmt, TypedStructField,
 };
+// Re-export HTML rendering of the typed pretty-printer
+pub use html_pretty_print::{HtmlPrettyPrint, HtmlSnippet};
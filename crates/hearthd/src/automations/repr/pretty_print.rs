@@ -0,0 +1,107 @@
+//! Base `PrettyPrint` trait shared by the lowered, typed, and HIR
+//! pretty-printers in this module.
+//!
+//! Mirrors [`super::super::pretty_print`]'s `PpAnn` hook design: a
+//! `pretty_print` call threads an `&dyn PpAnn` that is invoked immediately
+//! before and after each visited node, so a caller can interleave extra
+//! information (e.g. a [`super::typed::TypedExpr`]'s resolved `Ty`) into the
+//! rendering without forking the traversal per concern. `NoAnn` is the
+//! default and adds nothing.
+
+/// A node reference passed to [`PpAnn::pre`]/[`PpAnn::post`].
+pub enum AnnNode<'a> {
+    TypedExpr(&'a super::typed::TypedExpr),
+    TypedStmt(&'a super::typed::TypedStmt),
+    TypedArg(&'a super::typed::TypedArg),
+}
+
+/// Hooks called immediately before/after a node is printed, plus an
+/// optional suffix appended to the node's own header line. Default to
+/// no-ops, so implementing only the hook an annotator needs is enough.
+pub trait PpAnn {
+    fn pre(&self, _node: AnnNode<'_>, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+
+    fn post(&self, _node: AnnNode<'_>, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Ok(())
+    }
+
+    /// Text appended, space-separated, to the end of `node`'s header line -
+    /// e.g. `@ 12..19` for [`super::typed_pretty_print::SpanAnnotator`].
+    /// Unlike `pre`/`post`, which bracket the node's entire (possibly
+    /// multi-line) subtree, this lands inline on the one line a node's own
+    /// label is printed on. Returns `None` by default, so opting in to a
+    /// new per-node annotation (spans, entity IDs, ...) needs no changes to
+    /// existing golden output - only a caller passing a non-default
+    /// `PpAnn` sees it.
+    fn header_suffix(&self, _node: AnnNode<'_>) -> Option<String> {
+        None
+    }
+}
+
+/// The default [`PpAnn`]: prints nothing extra.
+pub struct NoAnn;
+
+impl PpAnn for NoAnn {}
+
+pub trait PrettyPrint {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result;
+
+    fn to_pretty_string(&self) -> String {
+        self.to_annotated_pretty_string(&NoAnn)
+    }
+
+    /// Like [`to_pretty_string`](PrettyPrint::to_pretty_string), but runs
+    /// `ann`'s hooks around every visited node.
+    fn to_annotated_pretty_string(&self, ann: &dyn PpAnn) -> String {
+        struct Wrapper<'a, T: PrettyPrint + ?Sized>(&'a T, &'a dyn PpAnn);
+        impl<T: PrettyPrint + ?Sized> std::fmt::Display for Wrapper<'_, T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.pretty_print(0, self.1, f)
+            }
+        }
+        Wrapper(self, ann).to_string()
+    }
+}
+
+pub fn write_indent(indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    for _ in 0..indent {
+        write!(f, "  ")?;
+    }
+    Ok(())
+}
+
+/// Append `ann`'s [`PpAnn::header_suffix`] for `node` (if any) to the header
+/// line currently being written, then terminate it. Call this in place of a
+/// bare `writeln!` at the end of a node's header.
+pub fn write_header_end(
+    ann: &dyn PpAnn,
+    node: AnnNode<'_>,
+    f: &mut std::fmt::Formatter<'_>,
+) -> std::fmt::Result {
+    if let Some(suffix) = ann.header_suffix(node) {
+        write!(f, " {suffix}")?;
+    }
+    writeln!(f)
+}
+
+/// A node's compact, width-aware rendering, as opposed to [`PrettyPrint`]'s
+/// always-multi-line one. A node lowers itself to a [`super::doc::Doc`] once;
+/// [`to_compact_string`](CompactPrint::to_compact_string) then lays that doc
+/// out against [`super::doc::DEFAULT_WIDTH`], collapsing onto one line
+/// whatever fits and breaking the rest. Intended for REPL/diagnostic output
+/// that wants to show a value's shape without type-checker snapshot tests'
+/// need for a stable, fully-expanded tree.
+pub trait CompactPrint {
+    fn to_doc(&self) -> super::doc::Doc;
+
+    fn to_compact_string(&self) -> String {
+        self.to_doc().render(super::doc::DEFAULT_WIDTH)
+    }
+}
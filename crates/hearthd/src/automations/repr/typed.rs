@@ -47,6 +47,28 @@ pub enum Ty {
 
     // Poison type to prevent cascading errors
     Error,
+
+    // Unification variable, e.g. the unconstrained element type of an empty
+    // `let mut xs = []` until its first `Push` pins it down. Never appears
+    // in a fully-checked program's final types - `TypeChecker::resolve_ty`
+    // substitutes these away, defaulting (and reporting) any that are still
+    // free once the automation body has been fully checked.
+    Var(u32),
+
+    // Function type, e.g. the predicate passed to `filter`/`map`/`fold`.
+    // Only produced by checking a `Lambda` expression - there is no surface
+    // syntax for a function *type* annotation, so this never appears as the
+    // target of `ast_type_to_ty`.
+    Fn {
+        params: Vec<Ty>,
+        ret: Box<Ty>,
+    },
+
+    // Tuple type, e.g. `(entity, brightness)`. Like `Ty::Fn`, only produced
+    // by checking a `Tuple` literal - there is no surface tuple-type
+    // annotation syntax, so this never appears as the target of
+    // `ast_type_to_ty` either.
+    Tuple(Vec<Ty>),
 }
 
 impl std::fmt::Display for Ty {
@@ -71,6 +93,27 @@ impl std::fmt::Display for Ty {
             } => write!(f, "{}::{}", enum_name, variant_name),
             Ty::Unit => write!(f, "()"),
             Ty::Error => write!(f, "<error>"),
+            Ty::Var(id) => write!(f, "?{}", id),
+            Ty::Fn { params, ret } => {
+                write!(f, "Fn(")?;
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", param)?;
+                }
+                write!(f, ") -> {}", ret)
+            }
+            Ty::Tuple(elems) => {
+                write!(f, "(")?;
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", elem)?;
+                }
+                write!(f, ")")
+            }
         }
     }
 }
@@ -158,6 +201,35 @@ pub enum TypedExprKind {
 
     // Empty mutable list (from desugared list comprehensions)
     MutableList,
+
+    // Match expression
+    Match {
+        scrutinee: Box<TypedExpr>,
+        arms: Vec<TypedMatchArm>,
+    },
+
+    // Lambda expression: `|params| body`, e.g. the predicate passed to
+    // `filter`/`map`/`fold`. `ty` on the enclosing `TypedExpr` carries the
+    // inferred `Ty::Fn` for the whole lambda.
+    Lambda {
+        params: Vec<std::string::String>,
+        body: Box<TypedExpr>,
+    },
+
+    // Tuple literal: `(a, b, c)`. `ty` on the enclosing `TypedExpr` carries
+    // the inferred `Ty::Tuple`.
+    Tuple(Vec<TypedExpr>),
+}
+
+/// A single arm of a typed `match` expression. `binding_types` gives the
+/// resolved type of each positional binding in `pattern`, in the same order
+/// as [`ast::MatchPattern::Variant`]'s `bindings` (empty for
+/// [`ast::MatchPattern::Wildcard`]).
+#[derive(Debug, Clone)]
+pub struct TypedMatchArm {
+    pub pattern: ast::Spanned<ast::MatchPattern>,
+    pub binding_types: Vec<Ty>,
+    pub body: Vec<TypedStmt>,
 }
 
 /// A typed statement.
@@ -186,6 +258,25 @@ pub enum TypedStmt {
         value: TypedExpr,
         origin: Origin,
     },
+    /// Condition-guarded loop (synthetic - see
+    /// [`super::lowered::LoweredStmt::While`], the sole source this is
+    /// checked from).
+    While {
+        cond: TypedExpr,
+        body: Vec<TypedStmt>,
+        origin: Origin,
+    },
+    CompoundAssign {
+        name: std::string::String,
+        op: ast::BinOp,
+        value: TypedExpr,
+        /// The binding's new type after the assignment (e.g. `Int += Float`
+        /// widens to `Float`) - recorded here, rather than recomputed during
+        /// lowering, since `lower` has no access to the checker's
+        /// unification/coercion rules.
+        result_ty: Ty,
+        origin: Origin,
+    },
 }
 
 /// A typed function argument.
@@ -240,11 +331,143 @@ pub struct EntityConstraint {
     pub span: chumsky::span::SimpleSpan,
 }
 
+/// How seriously a [`TypeError`] should be taken. Every diagnostic this
+/// checker produces today is `Error` - `Warning` exists so a future check
+/// (e.g. a discouraged-but-legal coercion) can be reported without making
+/// the program fail to compile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// An additional labeled span attached to a [`TypeError`], e.g. pointing at
+/// the declaration that makes the primary span wrong (the automation's
+/// `observer`/`mutator` keyword, the type of the object a bad field access
+/// was performed on).
+#[derive(Debug, Clone)]
+pub struct SecondaryLabel {
+    pub span: chumsky::span::SimpleSpan,
+    pub message: std::string::String,
+    /// Which file `span` is relative to. Defaults to `FileId::default()` at
+    /// construction and gets backfilled to the owning [`TypeError`]'s own
+    /// `file` by `TypeChecker::error_with` unless a call site used
+    /// [`TypeError::with_secondary_in`] to point it at a different file on
+    /// purpose (e.g. a future `import`ed definition's own file).
+    pub file: ast::FileId,
+}
+
 /// A type error produced during type checking.
+///
+/// `code` is a stable identifier (e.g. `"return-type-mismatch"`) that
+/// doesn't change if `message`'s wording is tweaked - tests and tooling can
+/// match on it instead of the human-readable text. `severity`, `secondary`,
+/// `help`, and `note` are all optional and default to `Error`/empty/`None`
+/// for most errors; `Display` only ever renders `span`/`message` (the plain
+/// one-line form used by [`super::check::format_type_errors`]'s
+/// ariadne-backed caller, and throughout `check/tests.rs`), so adding them
+/// never changes an error's short text form.
+///
+/// `help` and `note` both end up as extra guidance below the rendered
+/// report, but via ariadne's two distinct calls: `help` is a fix-it
+/// ("did you mean `x`?") surfaced through `Report::with_help`, while `note`
+/// is background explanation ("durations and angles are different
+/// dimensions; convert one before combining them") surfaced through
+/// `Report::with_note`. Keep using whichever of the two reads right for a
+/// given call site rather than defaulting to one.
 #[derive(Debug, Clone)]
 pub struct TypeError {
     pub message: std::string::String,
     pub span: chumsky::span::SimpleSpan,
+    pub code: &'static str,
+    pub severity: Severity,
+    pub secondary: Vec<SecondaryLabel>,
+    pub help: Option<std::string::String>,
+    pub note: Option<std::string::String>,
+    /// Which file `span` is relative to; see [`ast::FileId`]. Defaults to
+    /// `FileId::default()` here and gets set to the producing
+    /// `TypeChecker`'s own file by `TypeChecker::error`/`error_with` - call
+    /// sites building a `TypeError` never need to set this themselves.
+    pub file: ast::FileId,
+}
+
+impl TypeError {
+    /// A plain error with just a primary span and message, tagged with a
+    /// generic code - the common case, and what `TypeChecker::error` still
+    /// produces for the large majority of call sites that don't (yet) have
+    /// anything more specific to say.
+    pub fn new(span: chumsky::span::SimpleSpan, message: std::string::String) -> Self {
+        Self {
+            message,
+            span,
+            code: "generic",
+            severity: Severity::Error,
+            secondary: Vec::new(),
+            help: None,
+            note: None,
+            file: ast::FileId::default(),
+        }
+    }
+
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = code;
+        self
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_secondary(
+        mut self,
+        span: chumsky::span::SimpleSpan,
+        message: impl Into<std::string::String>,
+    ) -> Self {
+        self.secondary.push(SecondaryLabel {
+            span,
+            message: message.into(),
+            file: ast::FileId::default(),
+        });
+        self
+    }
+
+    /// Like `with_secondary`, but for a label that points into a different
+    /// file than the error's own primary span (e.g. a future `import`ed
+    /// definition's declaration site). No call site needs this yet - every
+    /// error and its secondary labels live in the one file being checked -
+    /// but it's here so `format_type_errors`'s multi-file rendering has
+    /// somewhere to plug in once one does.
+    pub fn with_secondary_in(
+        mut self,
+        file: ast::FileId,
+        span: chumsky::span::SimpleSpan,
+        message: impl Into<std::string::String>,
+    ) -> Self {
+        self.secondary.push(SecondaryLabel {
+            span,
+            message: message.into(),
+            file,
+        });
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<std::string::String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<std::string::String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+
+    /// A "did you mean `x`?" fix-it, rendered as the diagnostic's `help`.
+    /// A dedicated constructor over a bare `with_help(format!(...))` call so
+    /// every suggestion in the checker reads identically.
+    pub fn with_suggestion(self, candidate: impl std::fmt::Display) -> Self {
+        self.with_help(format!("did you mean `{}`?", candidate))
+    }
 }
 
 impl std::fmt::Display for TypeError {
@@ -271,7 +494,16 @@ impl CheckResult {
     }
 
     /// Render all type errors as pretty diagnostics with source context.
+    ///
+    /// Convenience wrapper over `format_type_errors` for the common
+    /// single-file case - every error here is expected to carry
+    /// `FileId::default()`, so one `(filename, source)` pair is enough to
+    /// resolve every span. A caller juggling more than one file should
+    /// build its own `SourceCache` and call `format_type_errors` directly.
     pub fn format_errors(&self, source: &str, filename: &str) -> String {
-        crate::automations::check::format_type_errors(&self.errors, source, filename)
+        let mut cache =
+            crate::automations::check::SourceCache::single(filename.to_string(), source);
+        let config = crate::automations::check::RenderConfig::default();
+        crate::automations::check::format_type_errors(&self.errors, &mut cache, &config)
     }
 }
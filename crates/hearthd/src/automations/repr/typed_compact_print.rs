@@ -0,0 +1,205 @@
+//! Compact, width-aware rendering for typed AST nodes.
+//!
+//! The [`CompactPrint`] counterpart to [`typed_pretty_print`](super::typed_pretty_print)'s
+//! always-expanded [`PrettyPrint`]: builds the same `Label: ... [type: X]`
+//! text as the verbose printer, but as a [`Doc`] so leaf-heavy subtrees
+//! (a short `List`, a `Call` with a couple of args) collapse onto one line
+//! instead of each occupying its own indented row.
+
+use super::doc::Doc;
+use super::pretty_print::CompactPrint;
+use super::typed::*;
+
+impl CompactPrint for TypedExpr {
+    fn to_doc(&self) -> Doc {
+        let ty = &self.ty;
+        match &self.kind {
+            TypedExprKind::Int(n) => Doc::text(format!("Int: {n} [type: {ty}]")),
+            TypedExprKind::Float(n) => Doc::text(format!("Float: {n} [type: {ty}]")),
+            TypedExprKind::String(s) => Doc::text(format!("String: \"{s}\" [type: {ty}]")),
+            TypedExprKind::Bool(b) => Doc::text(format!("Bool: {b} [type: {ty}]")),
+            TypedExprKind::UnitLiteral { value, unit } => {
+                Doc::text(format!("UnitLiteral: {value}{unit} [type: {ty}]"))
+            }
+            TypedExprKind::Ident(s) => Doc::text(format!("Ident: {s} [type: {ty}]")),
+            TypedExprKind::Path(segments) => {
+                Doc::text(format!("Path: {} [type: {ty}]", segments.join(".")))
+            }
+            TypedExprKind::BinOp { op, left, right } => Doc::bracketed(
+                format!("BinOp: {op} [type: {ty}] "),
+                "(",
+                vec![left.to_doc(), right.to_doc()],
+                ")",
+            ),
+            TypedExprKind::UnaryOp { op, expr } => Doc::bracketed(
+                format!("UnaryOp: {op} [type: {ty}] "),
+                "(",
+                vec![expr.to_doc()],
+                ")",
+            ),
+            TypedExprKind::Field { expr, field } => {
+                Doc::concat([expr.to_doc(), Doc::text(format!(".{field} [type: {ty}]"))])
+            }
+            TypedExprKind::OptionalField { expr, field } => {
+                Doc::concat([expr.to_doc(), Doc::text(format!("?.{field} [type: {ty}]"))])
+            }
+            TypedExprKind::Call { func, args } => Doc::concat([
+                func.to_doc(),
+                Doc::bracketed(
+                    format!(" [type: {ty}] "),
+                    "(",
+                    args.iter().map(CompactPrint::to_doc).collect(),
+                    ")",
+                ),
+            ]),
+            TypedExprKind::If {
+                cond,
+                then_block,
+                else_block,
+            } => {
+                let mut doc = vec![
+                    Doc::text(format!("If: [type: {ty}] (")),
+                    cond.to_doc(),
+                    Doc::text(") "),
+                    Doc::bracketed(
+                        "Then: ",
+                        "{",
+                        then_block.iter().map(CompactPrint::to_doc).collect(),
+                        "}",
+                    ),
+                ];
+                if let Some(else_stmts) = else_block {
+                    doc.push(Doc::text(" "));
+                    doc.push(Doc::bracketed(
+                        "Else: ",
+                        "{",
+                        else_stmts.iter().map(CompactPrint::to_doc).collect(),
+                        "}",
+                    ));
+                }
+                Doc::concat(doc)
+            }
+            TypedExprKind::List(items) => Doc::bracketed(
+                format!("List: [type: {ty}] "),
+                "[",
+                items.iter().map(CompactPrint::to_doc).collect(),
+                "]",
+            ),
+            TypedExprKind::StructLit { name, fields } => Doc::bracketed(
+                format!("StructLit: {name} [type: {ty}] "),
+                "{",
+                fields.iter().map(CompactPrint::to_doc).collect(),
+                "}",
+            ),
+            TypedExprKind::Block { stmts, result } => {
+                let mut items: Vec<Doc> = stmts.iter().map(CompactPrint::to_doc).collect();
+                items.push(result.to_doc());
+                Doc::bracketed(format!("Block: [type: {ty}] "), "{", items, "}")
+            }
+            TypedExprKind::MutableList => Doc::text(format!("MutableList [type: {ty}]")),
+            TypedExprKind::Match { scrutinee, arms } => Doc::concat([
+                Doc::text(format!("Match: [type: {ty}] (")),
+                scrutinee.to_doc(),
+                Doc::text(") "),
+                Doc::bracketed(
+                    "",
+                    "{",
+                    arms.iter().map(CompactPrint::to_doc).collect(),
+                    "}",
+                ),
+            ]),
+            TypedExprKind::Lambda { params, body } => Doc::concat([
+                Doc::text(format!("Lambda: |{}| [type: {ty}] ", params.join(", "))),
+                body.to_doc(),
+            ]),
+            TypedExprKind::Tuple(items) => Doc::bracketed(
+                format!("Tuple: [type: {ty}] "),
+                "(",
+                items.iter().map(CompactPrint::to_doc).collect(),
+                ")",
+            ),
+        }
+    }
+}
+
+impl CompactPrint for TypedMatchArm {
+    fn to_doc(&self) -> Doc {
+        Doc::concat([
+            Doc::text(format!("Arm: {:?} => ", self.pattern.node)),
+            Doc::bracketed(
+                "",
+                "{",
+                self.body.iter().map(CompactPrint::to_doc).collect(),
+                "}",
+            ),
+        ])
+    }
+}
+
+impl CompactPrint for TypedStmt {
+    fn to_doc(&self) -> Doc {
+        match self {
+            TypedStmt::Let { name, value, .. } => {
+                Doc::concat([Doc::text(format!("Let {name} = ")), value.to_doc()])
+            }
+            TypedStmt::LetMut { name, value, .. } => {
+                Doc::concat([Doc::text(format!("LetMut {name} = ")), value.to_doc()])
+            }
+            TypedStmt::Expr(expr) => expr.to_doc(),
+            TypedStmt::Return(expr, _) => Doc::concat([Doc::text("Return "), expr.to_doc()]),
+            TypedStmt::For {
+                var, iter, body, ..
+            } => Doc::concat([
+                Doc::text(format!("For {var} in (")),
+                iter.to_doc(),
+                Doc::text(") "),
+                Doc::bracketed(
+                    "",
+                    "{",
+                    body.iter().map(CompactPrint::to_doc).collect(),
+                    "}",
+                ),
+            ]),
+            TypedStmt::Push { list, value, .. } => {
+                Doc::concat([Doc::text(format!("Push {list} <- ")), value.to_doc()])
+            }
+            TypedStmt::While { cond, body, .. } => Doc::concat([
+                Doc::text("While ("),
+                cond.to_doc(),
+                Doc::text(") "),
+                Doc::bracketed(
+                    "",
+                    "{",
+                    body.iter().map(CompactPrint::to_doc).collect(),
+                    "}",
+                ),
+            ]),
+            TypedStmt::CompoundAssign {
+                name, op, value, ..
+            } => Doc::concat([Doc::text(format!("{name} {op}= ")), value.to_doc()]),
+        }
+    }
+}
+
+impl CompactPrint for TypedArg {
+    fn to_doc(&self) -> Doc {
+        match self {
+            TypedArg::Positional(expr) => expr.to_doc(),
+            TypedArg::Named { name, value } => {
+                Doc::concat([Doc::text(format!("{name}: ")), value.to_doc()])
+            }
+        }
+    }
+}
+
+impl CompactPrint for TypedStructField {
+    fn to_doc(&self) -> Doc {
+        match self {
+            TypedStructField::Field { name, value } => {
+                Doc::concat([Doc::text(format!("{name}: ")), value.to_doc()])
+            }
+            TypedStructField::Inherit(name) => Doc::text(format!("Inherit: {name}")),
+            TypedStructField::Spread(name) => Doc::text(format!("Spread: {name}")),
+        }
+    }
+}
@@ -0,0 +1,561 @@
+//! Serde-based structured (JSON) export of the typed AST and [`CheckResult`].
+//!
+//! Parallel to [`super::typed_pretty_print`]'s human-readable dump: each
+//! typed node converts into one of the `Exported*` DTOs here, which derive
+//! `Serialize` directly rather than deriving it on the domain types in
+//! `typed.rs` - those carry an [`super::lowered::Origin`] (an `Rc`-shared
+//! reference into the original AST) that's an implementation detail, not
+//! part of the stable export shape. Editor plugins, LSP-style consumers,
+//! and diff tools can depend on `{"kind": "BinOp", "ty": "Int", ...}`
+//! instead of scraping indented text, and integration tests can assert on
+//! this structured form rather than brittle whitespace.
+
+use serde::Serialize;
+
+use super::typed::*;
+
+/// A half-open `[start, end)` byte-offset span, the JSON counterpart to
+/// [`chumsky::span::SimpleSpan`].
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct ExportedSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<chumsky::span::SimpleSpan> for ExportedSpan {
+    fn from(span: chumsky::span::SimpleSpan) -> Self {
+        ExportedSpan {
+            start: span.start,
+            end: span.end,
+        }
+    }
+}
+
+/// A typed expression, tagged by `kind` (its [`TypedExprKind`] variant
+/// name) with `ty` giving the resolved [`Ty`]'s [`std::fmt::Display`] form
+/// and `span` its originating source range.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum ExportedExpr {
+    Int {
+        value: i64,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Float {
+        value: f64,
+        ty: String,
+        span: ExportedSpan,
+    },
+    String {
+        value: std::string::String,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Bool {
+        value: bool,
+        ty: String,
+        span: ExportedSpan,
+    },
+    UnitLiteral {
+        value: std::string::String,
+        unit: String,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Ident {
+        name: std::string::String,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Path {
+        segments: Vec<std::string::String>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    BinOp {
+        op: String,
+        left: Box<ExportedExpr>,
+        right: Box<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    UnaryOp {
+        op: String,
+        expr: Box<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Field {
+        expr: Box<ExportedExpr>,
+        field: std::string::String,
+        ty: String,
+        span: ExportedSpan,
+    },
+    OptionalField {
+        expr: Box<ExportedExpr>,
+        field: std::string::String,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Call {
+        func: Box<ExportedExpr>,
+        args: Vec<ExportedArg>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    If {
+        cond: Box<ExportedExpr>,
+        then_block: Vec<ExportedStmt>,
+        else_block: Option<Vec<ExportedStmt>>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    List {
+        items: Vec<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    StructLit {
+        name: std::string::String,
+        fields: Vec<ExportedStructField>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Block {
+        stmts: Vec<ExportedStmt>,
+        result: Box<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    MutableList {
+        ty: String,
+        span: ExportedSpan,
+    },
+    Match {
+        scrutinee: Box<ExportedExpr>,
+        arms: Vec<ExportedMatchArm>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Lambda {
+        params: Vec<std::string::String>,
+        body: Box<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+    Tuple {
+        items: Vec<ExportedExpr>,
+        ty: String,
+        span: ExportedSpan,
+    },
+}
+
+impl From<&TypedExpr> for ExportedExpr {
+    fn from(expr: &TypedExpr) -> Self {
+        let ty = expr.ty.to_string();
+        let span = expr.origin.span().into();
+        match &expr.kind {
+            TypedExprKind::Int(value) => ExportedExpr::Int {
+                value: *value,
+                ty,
+                span,
+            },
+            TypedExprKind::Float(value) => ExportedExpr::Float {
+                value: *value,
+                ty,
+                span,
+            },
+            TypedExprKind::String(value) => ExportedExpr::String {
+                value: value.clone(),
+                ty,
+                span,
+            },
+            TypedExprKind::Bool(value) => ExportedExpr::Bool {
+                value: *value,
+                ty,
+                span,
+            },
+            TypedExprKind::UnitLiteral { value, unit } => ExportedExpr::UnitLiteral {
+                value: value.clone(),
+                unit: unit.to_string(),
+                ty,
+                span,
+            },
+            TypedExprKind::Ident(name) => ExportedExpr::Ident {
+                name: name.clone(),
+                ty,
+                span,
+            },
+            TypedExprKind::Path(segments) => ExportedExpr::Path {
+                segments: segments.clone(),
+                ty,
+                span,
+            },
+            TypedExprKind::BinOp { op, left, right } => ExportedExpr::BinOp {
+                op: op.to_string(),
+                left: Box::new((&**left).into()),
+                right: Box::new((&**right).into()),
+                ty,
+                span,
+            },
+            TypedExprKind::UnaryOp { op, expr } => ExportedExpr::UnaryOp {
+                op: op.to_string(),
+                expr: Box::new((&**expr).into()),
+                ty,
+                span,
+            },
+            TypedExprKind::Field { expr, field } => ExportedExpr::Field {
+                expr: Box::new((&**expr).into()),
+                field: field.clone(),
+                ty,
+                span,
+            },
+            TypedExprKind::OptionalField { expr, field } => ExportedExpr::OptionalField {
+                expr: Box::new((&**expr).into()),
+                field: field.clone(),
+                ty,
+                span,
+            },
+            TypedExprKind::Call { func, args } => ExportedExpr::Call {
+                func: Box::new((&**func).into()),
+                args: args.iter().map(ExportedArg::from).collect(),
+                ty,
+                span,
+            },
+            TypedExprKind::If {
+                cond,
+                then_block,
+                else_block,
+            } => ExportedExpr::If {
+                cond: Box::new((&**cond).into()),
+                then_block: then_block.iter().map(ExportedStmt::from).collect(),
+                else_block: else_block
+                    .as_ref()
+                    .map(|stmts| stmts.iter().map(ExportedStmt::from).collect()),
+                ty,
+                span,
+            },
+            TypedExprKind::List(items) => ExportedExpr::List {
+                items: items.iter().map(ExportedExpr::from).collect(),
+                ty,
+                span,
+            },
+            TypedExprKind::StructLit { name, fields } => ExportedExpr::StructLit {
+                name: name.clone(),
+                fields: fields.iter().map(ExportedStructField::from).collect(),
+                ty,
+                span,
+            },
+            TypedExprKind::Block { stmts, result } => ExportedExpr::Block {
+                stmts: stmts.iter().map(ExportedStmt::from).collect(),
+                result: Box::new((&**result).into()),
+                ty,
+                span,
+            },
+            TypedExprKind::MutableList => ExportedExpr::MutableList { ty, span },
+            TypedExprKind::Match { scrutinee, arms } => ExportedExpr::Match {
+                scrutinee: Box::new((&**scrutinee).into()),
+                arms: arms.iter().map(ExportedMatchArm::from).collect(),
+                ty,
+                span,
+            },
+            TypedExprKind::Lambda { params, body } => ExportedExpr::Lambda {
+                params: params.clone(),
+                body: Box::new((&**body).into()),
+                ty,
+                span,
+            },
+            TypedExprKind::Tuple(items) => ExportedExpr::Tuple {
+                items: items.iter().map(ExportedExpr::from).collect(),
+                ty,
+                span,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum ExportedArg {
+    Positional {
+        value: ExportedExpr,
+    },
+    Named {
+        name: std::string::String,
+        value: ExportedExpr,
+    },
+}
+
+impl From<&TypedArg> for ExportedArg {
+    fn from(arg: &TypedArg) -> Self {
+        match arg {
+            TypedArg::Positional(expr) => ExportedArg::Positional { value: expr.into() },
+            TypedArg::Named { name, value } => ExportedArg::Named {
+                name: name.clone(),
+                value: value.into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum ExportedStructField {
+    Field {
+        name: std::string::String,
+        value: ExportedExpr,
+    },
+    Inherit {
+        name: std::string::String,
+    },
+    Spread {
+        name: std::string::String,
+    },
+}
+
+impl From<&TypedStructField> for ExportedStructField {
+    fn from(field: &TypedStructField) -> Self {
+        match field {
+            TypedStructField::Field { name, value } => ExportedStructField::Field {
+                name: name.clone(),
+                value: value.into(),
+            },
+            TypedStructField::Inherit(name) => ExportedStructField::Inherit { name: name.clone() },
+            TypedStructField::Spread(name) => ExportedStructField::Spread { name: name.clone() },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedMatchArm {
+    /// [`std::fmt::Debug`] form of the pattern - there is no JSON-friendly
+    /// typed-pattern shape distinct from the untyped `ast::MatchPattern`.
+    pub pattern: std::string::String,
+    pub binding_types: Vec<String>,
+    pub body: Vec<ExportedStmt>,
+}
+
+impl From<&TypedMatchArm> for ExportedMatchArm {
+    fn from(arm: &TypedMatchArm) -> Self {
+        ExportedMatchArm {
+            pattern: format!("{:?}", arm.pattern.node),
+            binding_types: arm.binding_types.iter().map(Ty::to_string).collect(),
+            body: arm.body.iter().map(ExportedStmt::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum ExportedStmt {
+    Let {
+        name: std::string::String,
+        value: ExportedExpr,
+        span: ExportedSpan,
+    },
+    LetMut {
+        name: std::string::String,
+        value: ExportedExpr,
+        span: ExportedSpan,
+    },
+    Expr {
+        value: ExportedExpr,
+    },
+    Return {
+        value: ExportedExpr,
+        span: ExportedSpan,
+    },
+    For {
+        var: std::string::String,
+        iter: ExportedExpr,
+        body: Vec<ExportedStmt>,
+        span: ExportedSpan,
+    },
+    Push {
+        list: std::string::String,
+        value: ExportedExpr,
+        span: ExportedSpan,
+    },
+    While {
+        cond: ExportedExpr,
+        body: Vec<ExportedStmt>,
+        span: ExportedSpan,
+    },
+    CompoundAssign {
+        name: std::string::String,
+        op: String,
+        value: ExportedExpr,
+        result_ty: String,
+        span: ExportedSpan,
+    },
+}
+
+impl From<&TypedStmt> for ExportedStmt {
+    fn from(stmt: &TypedStmt) -> Self {
+        match stmt {
+            TypedStmt::Let {
+                name,
+                value,
+                origin,
+            } => ExportedStmt::Let {
+                name: name.clone(),
+                value: value.into(),
+                span: origin.span().into(),
+            },
+            TypedStmt::LetMut {
+                name,
+                value,
+                origin,
+            } => ExportedStmt::LetMut {
+                name: name.clone(),
+                value: value.into(),
+                span: origin.span().into(),
+            },
+            TypedStmt::Expr(expr) => ExportedStmt::Expr { value: expr.into() },
+            TypedStmt::Return(expr, origin) => ExportedStmt::Return {
+                value: expr.into(),
+                span: origin.span().into(),
+            },
+            TypedStmt::For {
+                var,
+                iter,
+                body,
+                origin,
+            } => ExportedStmt::For {
+                var: var.clone(),
+                iter: iter.into(),
+                body: body.iter().map(ExportedStmt::from).collect(),
+                span: origin.span().into(),
+            },
+            TypedStmt::Push {
+                list,
+                value,
+                origin,
+            } => ExportedStmt::Push {
+                list: list.clone(),
+                value: value.into(),
+                span: origin.span().into(),
+            },
+            TypedStmt::While { cond, body, origin } => ExportedStmt::While {
+                cond: cond.into(),
+                body: body.iter().map(ExportedStmt::from).collect(),
+                span: origin.span().into(),
+            },
+            TypedStmt::CompoundAssign {
+                name,
+                op,
+                value,
+                result_ty,
+                origin,
+            } => ExportedStmt::CompoundAssign {
+                name: name.clone(),
+                op: op.to_string(),
+                value: value.into(),
+                result_ty: result_ty.to_string(),
+                span: origin.span().into(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedAutomation {
+    pub kind: String,
+    /// [`std::fmt::Debug`] form of the pattern, see [`ExportedMatchArm::pattern`].
+    pub pattern: std::string::String,
+    pub filter: Option<ExportedExpr>,
+    pub body: Vec<ExportedStmt>,
+}
+
+impl From<&TypedAutomation> for ExportedAutomation {
+    fn from(automation: &TypedAutomation) -> Self {
+        ExportedAutomation {
+            kind: automation.kind.to_string(),
+            pattern: format!("{:?}", automation.pattern.node),
+            filter: automation.filter.as_ref().map(ExportedExpr::from),
+            body: automation.body.iter().map(ExportedStmt::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind")]
+pub enum ExportedProgram {
+    Automation {
+        automation: ExportedAutomation,
+    },
+    Template {
+        params: Vec<ExportedStructField>,
+        automations: Vec<ExportedAutomation>,
+    },
+}
+
+impl From<&TypedProgram> for ExportedProgram {
+    fn from(program: &TypedProgram) -> Self {
+        match program {
+            TypedProgram::Automation(automation) => ExportedProgram::Automation {
+                automation: (&**automation).into(),
+            },
+            TypedProgram::Template {
+                params,
+                automations,
+            } => ExportedProgram::Template {
+                params: params.iter().map(ExportedStructField::from).collect(),
+                automations: automations.iter().map(ExportedAutomation::from).collect(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedConstraint {
+    pub domain: std::string::String,
+    pub entity: std::string::String,
+    pub span: ExportedSpan,
+}
+
+impl From<&EntityConstraint> for ExportedConstraint {
+    fn from(constraint: &EntityConstraint) -> Self {
+        ExportedConstraint {
+            domain: constraint.domain.clone(),
+            entity: constraint.entity.clone(),
+            span: constraint.span.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedCheckResult {
+    pub program: ExportedProgram,
+    pub constraints: Vec<ExportedConstraint>,
+    /// Each [`TypeError`]'s [`std::fmt::Display`] form - see
+    /// `super::super::check::format_type_errors_json` for a richer,
+    /// LSP-shaped export of the same errors.
+    pub errors: Vec<std::string::String>,
+}
+
+impl From<&CheckResult> for ExportedCheckResult {
+    fn from(result: &CheckResult) -> Self {
+        ExportedCheckResult {
+            program: (&result.program).into(),
+            constraints: result
+                .constraints
+                .iter()
+                .map(ExportedConstraint::from)
+                .collect(),
+            errors: result
+                .errors
+                .iter()
+                .map(std::string::ToString::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl CheckResult {
+    /// Serialize this check result to a JSON string via [`ExportedCheckResult`].
+    pub fn to_json(&self) -> serde_json::Result<std::string::String> {
+        serde_json::to_string(&ExportedCheckResult::from(self))
+    }
+}
@@ -1,61 +1,96 @@
 //! Verbose, multi-line pretty-printing for typed AST nodes.
 //!
 //! Used by type checker tests to produce unambiguous snapshot output.
-//! Shows `[type: X]` annotations on every expression.
+//! Shows `[type: X]` annotations on every expression by default; a caller
+//! wanting something else (spans, entity constraints, a future
+//! `--explain-types` CLI) can supply its own [`PpAnn`] instead of forking
+//! this traversal - see [`TypeAnnotator`] for an example that appends each
+//! expression's `Ty` as a trailing comment rather than baking it into the
+//! node's own text.
 
-use super::pretty_print::PrettyPrint;
+use super::pretty_print::write_header_end;
 use super::pretty_print::write_indent;
+use super::pretty_print::AnnNode;
+use super::pretty_print::PpAnn;
+use super::pretty_print::PrettyPrint;
 use super::typed::*;
 
 impl PrettyPrint for TypedExpr {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::TypedExpr(self), f)?;
         write_indent(indent, f)?;
+        let node = AnnNode::TypedExpr(self);
         match &self.kind {
-            TypedExprKind::Int(n) => writeln!(f, "Int: {} [type: {}]", n, self.ty),
-            TypedExprKind::Float(n) => writeln!(f, "Float: {} [type: {}]", n, self.ty),
-            TypedExprKind::String(s) => writeln!(f, "String: \"{}\" [type: {}]", s, self.ty),
-            TypedExprKind::Bool(b) => writeln!(f, "Bool: {} [type: {}]", b, self.ty),
+            TypedExprKind::Int(n) => {
+                write!(f, "Int: {} [type: {}]", n, self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
+            TypedExprKind::Float(n) => {
+                write!(f, "Float: {} [type: {}]", n, self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
+            TypedExprKind::String(s) => {
+                write!(f, "String: \"{}\" [type: {}]", s, self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
+            TypedExprKind::Bool(b) => {
+                write!(f, "Bool: {} [type: {}]", b, self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
             TypedExprKind::UnitLiteral { value, unit } => {
-                writeln!(f, "UnitLiteral: {}{} [type: {}]", value, unit, self.ty)
+                write!(f, "UnitLiteral: {}{} [type: {}]", value, unit, self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
+            TypedExprKind::Ident(s) => {
+                write!(f, "Ident: {} [type: {}]", s, self.ty)?;
+                write_header_end(ann, node, f)?;
             }
-            TypedExprKind::Ident(s) => writeln!(f, "Ident: {} [type: {}]", s, self.ty),
             TypedExprKind::Path(segments) => {
-                writeln!(f, "Path: [type: {}]", self.ty)?;
+                write!(f, "Path: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
                 for seg in segments {
                     write_indent(indent + 1, f)?;
                     writeln!(f, "Segment: {}", seg)?;
                 }
-                Ok(())
             }
             TypedExprKind::BinOp { op, left, right } => {
-                writeln!(f, "BinOp: {} [type: {}]", op, self.ty)?;
-                left.pretty_print(indent + 1, f)?;
-                right.pretty_print(indent + 1, f)
+                write!(f, "BinOp: {} [type: {}]", op, self.ty)?;
+                write_header_end(ann, node, f)?;
+                left.pretty_print(indent + 1, ann, f)?;
+                right.pretty_print(indent + 1, ann, f)?;
             }
             TypedExprKind::UnaryOp { op, expr } => {
-                writeln!(f, "UnaryOp: {} [type: {}]", op, self.ty)?;
-                expr.pretty_print(indent + 1, f)
+                write!(f, "UnaryOp: {} [type: {}]", op, self.ty)?;
+                write_header_end(ann, node, f)?;
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             TypedExprKind::Field { expr, field } => {
-                writeln!(f, "Field: .{} [type: {}]", field, self.ty)?;
-                expr.pretty_print(indent + 1, f)
+                write!(f, "Field: .{} [type: {}]", field, self.ty)?;
+                write_header_end(ann, node, f)?;
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             TypedExprKind::OptionalField { expr, field } => {
-                writeln!(f, "OptionalField: ?.{} [type: {}]", field, self.ty)?;
-                expr.pretty_print(indent + 1, f)
+                write!(f, "OptionalField: ?.{} [type: {}]", field, self.ty)?;
+                write_header_end(ann, node, f)?;
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             TypedExprKind::Call { func, args } => {
-                writeln!(f, "Call: [type: {}]", self.ty)?;
-                func.pretty_print(indent + 1, f)?;
+                write!(f, "Call: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
+                func.pretty_print(indent + 1, ann, f)?;
                 write_indent(indent + 1, f)?;
                 if args.is_empty() {
-                    writeln!(f, "Args: (none)")
+                    writeln!(f, "Args: (none)")?;
                 } else {
                     writeln!(f, "Args:")?;
                     for arg in args {
-                        arg.pretty_print(indent + 2, f)?;
+                        arg.pretty_print(indent + 2, ann, f)?;
                     }
-                    Ok(())
                 }
             }
             TypedExprKind::If {
@@ -63,122 +98,213 @@ impl PrettyPrint for TypedExpr {
                 then_block,
                 else_block,
             } => {
-                writeln!(f, "If: [type: {}]", self.ty)?;
+                write!(f, "If: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Cond:")?;
-                cond.pretty_print(indent + 2, f)?;
+                cond.pretty_print(indent + 2, ann, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Then:")?;
                 for stmt in then_block {
-                    stmt.pretty_print(indent + 2, f)?;
+                    stmt.pretty_print(indent + 2, ann, f)?;
                 }
                 if let Some(else_stmts) = else_block {
                     write_indent(indent + 1, f)?;
                     writeln!(f, "Else:")?;
                     for stmt in else_stmts {
-                        stmt.pretty_print(indent + 2, f)?;
+                        stmt.pretty_print(indent + 2, ann, f)?;
                     }
                 }
-                Ok(())
             }
             TypedExprKind::List(items) => {
                 if items.is_empty() {
-                    writeln!(f, "List: (empty) [type: {}]", self.ty)
+                    write!(f, "List: (empty) [type: {}]", self.ty)?;
+                    write_header_end(ann, node, f)?;
                 } else {
-                    writeln!(f, "List: [type: {}]", self.ty)?;
+                    write!(f, "List: [type: {}]", self.ty)?;
+                    write_header_end(ann, node, f)?;
                     for item in items {
-                        item.pretty_print(indent + 1, f)?;
+                        item.pretty_print(indent + 1, ann, f)?;
                     }
-                    Ok(())
                 }
             }
             TypedExprKind::StructLit { name, fields } => {
-                writeln!(f, "StructLit: {} [type: {}]", name, self.ty)?;
+                write!(f, "StructLit: {} [type: {}]", name, self.ty)?;
+                write_header_end(ann, node, f)?;
                 for field in fields {
-                    field.pretty_print(indent + 1, f)?;
+                    field.pretty_print(indent + 1, ann, f)?;
                 }
-                Ok(())
             }
             TypedExprKind::Block { stmts, result } => {
-                writeln!(f, "Block: [type: {}]", self.ty)?;
+                write!(f, "Block: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Stmts:")?;
                 for stmt in stmts {
-                    stmt.pretty_print(indent + 2, f)?;
+                    stmt.pretty_print(indent + 2, ann, f)?;
                 }
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Result:")?;
-                result.pretty_print(indent + 2, f)
+                result.pretty_print(indent + 2, ann, f)?;
+            }
+            TypedExprKind::MutableList => {
+                write!(f, "MutableList [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
+            }
+            TypedExprKind::Match { scrutinee, arms } => {
+                write!(f, "Match: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Scrutinee:")?;
+                scrutinee.pretty_print(indent + 2, ann, f)?;
+                for arm in arms {
+                    arm.pretty_print(indent + 1, ann, f)?;
+                }
+            }
+            TypedExprKind::Lambda { params, body } => {
+                write!(f, "Lambda: |{}| [type: {}]", params.join(", "), self.ty)?;
+                write_header_end(ann, node, f)?;
+                body.pretty_print(indent + 1, ann, f)?;
             }
-            TypedExprKind::MutableList => writeln!(f, "MutableList [type: {}]", self.ty),
+            TypedExprKind::Tuple(items) => {
+                write!(f, "Tuple: [type: {}]", self.ty)?;
+                write_header_end(ann, node, f)?;
+                for item in items {
+                    item.pretty_print(indent + 1, ann, f)?;
+                }
+            }
+        }
+        ann.post(AnnNode::TypedExpr(self), f)
+    }
+}
+
+impl PrettyPrint for TypedMatchArm {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(f, "Arm:")?;
+        self.pattern.pretty_print(indent + 1, ann, f)?;
+        write_indent(indent + 1, f)?;
+        writeln!(f, "Body:")?;
+        for stmt in &self.body {
+            stmt.pretty_print(indent + 2, ann, f)?;
         }
+        Ok(())
     }
 }
 
 impl PrettyPrint for TypedStmt {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::TypedStmt(self), f)?;
         write_indent(indent, f)?;
+        let node = AnnNode::TypedStmt(self);
         match self {
             TypedStmt::Let { name, value, .. } => {
-                writeln!(f, "Let: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                write!(f, "Let: {}", name)?;
+                write_header_end(ann, node, f)?;
+                value.pretty_print(indent + 1, ann, f)?;
             }
             TypedStmt::LetMut { name, value, .. } => {
-                writeln!(f, "LetMut: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                write!(f, "LetMut: {}", name)?;
+                write_header_end(ann, node, f)?;
+                value.pretty_print(indent + 1, ann, f)?;
             }
             TypedStmt::Expr(expr) => {
-                writeln!(f, "ExprStmt:")?;
-                expr.pretty_print(indent + 1, f)
+                write!(f, "ExprStmt:")?;
+                write_header_end(ann, node, f)?;
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             TypedStmt::Return(expr, _) => {
-                writeln!(f, "Return:")?;
-                expr.pretty_print(indent + 1, f)
+                write!(f, "Return:")?;
+                write_header_end(ann, node, f)?;
+                expr.pretty_print(indent + 1, ann, f)?;
             }
             TypedStmt::For {
                 var, iter, body, ..
             } => {
-                writeln!(f, "For:")?;
+                write!(f, "For:")?;
+                write_header_end(ann, node, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Var: {}", var)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Iter:")?;
-                iter.pretty_print(indent + 2, f)?;
+                iter.pretty_print(indent + 2, ann, f)?;
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Body:")?;
                 for stmt in body {
-                    stmt.pretty_print(indent + 2, f)?;
+                    stmt.pretty_print(indent + 2, ann, f)?;
                 }
-                Ok(())
             }
             TypedStmt::Push { list, value, .. } => {
-                writeln!(f, "Push: {}", list)?;
-                value.pretty_print(indent + 1, f)
+                write!(f, "Push: {}", list)?;
+                write_header_end(ann, node, f)?;
+                value.pretty_print(indent + 1, ann, f)?;
+            }
+            TypedStmt::While { cond, body, .. } => {
+                write!(f, "While:")?;
+                write_header_end(ann, node, f)?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Cond:")?;
+                cond.pretty_print(indent + 2, ann, f)?;
+                write_indent(indent + 1, f)?;
+                writeln!(f, "Body:")?;
+                for stmt in body {
+                    stmt.pretty_print(indent + 2, ann, f)?;
+                }
+            }
+            TypedStmt::CompoundAssign {
+                name, op, value, ..
+            } => {
+                write!(f, "CompoundAssign: {} {}=", name, op)?;
+                write_header_end(ann, node, f)?;
+                value.pretty_print(indent + 1, ann, f)?;
             }
         }
+        ann.post(AnnNode::TypedStmt(self), f)
     }
 }
 
 impl PrettyPrint for TypedArg {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        ann.pre(AnnNode::TypedArg(self), f)?;
         match self {
-            TypedArg::Positional(expr) => expr.pretty_print(indent, f),
+            TypedArg::Positional(expr) => expr.pretty_print(indent, ann, f)?,
             TypedArg::Named { name, value } => {
                 write_indent(indent, f)?;
                 writeln!(f, "Named: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                value.pretty_print(indent + 1, ann, f)?;
             }
         }
+        ann.post(AnnNode::TypedArg(self), f)
     }
 }
 
 impl PrettyPrint for TypedStructField {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         match self {
             TypedStructField::Field { name, value } => {
                 writeln!(f, "Field: {}", name)?;
-                value.pretty_print(indent + 1, f)
+                value.pretty_print(indent + 1, ann, f)
             }
             TypedStructField::Inherit(name) => writeln!(f, "Inherit: {}", name),
             TypedStructField::Spread(name) => writeln!(f, "Spread: {}", name),
@@ -187,16 +313,21 @@ impl PrettyPrint for TypedStructField {
 }
 
 impl PrettyPrint for TypedAutomation {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         write_indent(indent, f)?;
         writeln!(f, "Automation: {}", self.kind)?;
         write_indent(indent + 1, f)?;
         writeln!(f, "Pattern:")?;
-        self.pattern.pretty_print(indent + 2, f)?;
+        self.pattern.pretty_print(indent + 2, ann, f)?;
         if let Some(filter) = &self.filter {
             write_indent(indent + 1, f)?;
             writeln!(f, "Filter:")?;
-            filter.pretty_print(indent + 2, f)?;
+            filter.pretty_print(indent + 2, ann, f)?;
         }
         write_indent(indent + 1, f)?;
         if self.body.is_empty() {
@@ -204,7 +335,7 @@ impl PrettyPrint for TypedAutomation {
         } else {
             writeln!(f, "Body:")?;
             for stmt in &self.body {
-                stmt.pretty_print(indent + 2, f)?;
+                stmt.pretty_print(indent + 2, ann, f)?;
             }
             Ok(())
         }
@@ -212,9 +343,14 @@ impl PrettyPrint for TypedAutomation {
 }
 
 impl PrettyPrint for TypedProgram {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
         match self {
-            TypedProgram::Automation(auto) => auto.pretty_print(indent, f),
+            TypedProgram::Automation(auto) => auto.pretty_print(indent, ann, f),
             TypedProgram::Template {
                 params,
                 automations,
@@ -224,12 +360,12 @@ impl PrettyPrint for TypedProgram {
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Params:")?;
                 for param in params {
-                    param.pretty_print(indent + 2, f)?;
+                    param.pretty_print(indent + 2, ann, f)?;
                 }
                 write_indent(indent + 1, f)?;
                 writeln!(f, "Automations:")?;
                 for auto in automations {
-                    auto.pretty_print(indent + 2, f)?;
+                    auto.pretty_print(indent + 2, ann, f)?;
                 }
                 Ok(())
             }
@@ -238,8 +374,13 @@ impl PrettyPrint for TypedProgram {
 }
 
 impl PrettyPrint for CheckResult {
-    fn pretty_print(&self, indent: usize, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.program.pretty_print(indent, f)?;
+    fn pretty_print(
+        &self,
+        indent: usize,
+        ann: &dyn PpAnn,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        self.program.pretty_print(indent, ann, f)?;
         if !self.constraints.is_empty() {
             write_indent(indent, f)?;
             writeln!(f, "EntityConstraints:")?;
@@ -263,3 +404,58 @@ impl PrettyPrint for CheckResult {
         Ok(())
     }
 }
+
+/// A [`PpAnn`] that appends each typed expression's resolved [`Ty`] (and,
+/// for a [`TypedExprKind::Call`] whose callee resolves to an entity-registry
+/// field access, nothing further - entity constraints are collected
+/// separately on [`CheckResult`], not per-node) as a trailing comment line,
+/// e.g. `Int: 5 [type: Int]` followed by `  /* : Int */`. Demonstrates that
+/// the `[type: X]` baked into [`TypedExpr`]'s own rendering could instead be
+/// sourced entirely from an annotator like this one.
+pub struct TypeAnnotator;
+
+impl PpAnn for TypeAnnotator {
+    fn post(&self, node: AnnNode<'_>, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let AnnNode::TypedExpr(expr) = node {
+            writeln!(f, "  /* : {} */", expr.ty)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`PpAnn`] that appends each node's originating source span as
+/// `@ start..end` to the end of its header line, e.g.
+/// `BinOp: + [type: Int] @ 12..19`. Opt in with
+/// `to_annotated_pretty_string(&SpanAnnotator)`; existing golden files that
+/// call `to_pretty_string` (equivalent to `&NoAnn`) are unaffected, so this
+/// is incremental per call site rather than a breaking change to the
+/// default output.
+pub struct SpanAnnotator;
+
+impl PpAnn for SpanAnnotator {
+    fn header_suffix(&self, node: AnnNode<'_>) -> Option<String> {
+        let span = match node {
+            AnnNode::TypedExpr(expr) => expr.origin.span(),
+            AnnNode::TypedStmt(stmt) => stmt_origin(stmt)?.span(),
+            AnnNode::TypedArg(_) => return None,
+        };
+        Some(format!("@ {}..{}", span.start, span.end))
+    }
+}
+
+/// The [`Origin`](super::lowered::Origin) for a [`TypedStmt`], or `None` for
+/// [`TypedStmt::Expr`] - which has no origin of its own and instead defers
+/// to its wrapped [`TypedExpr`]'s, printed on that expression's own header
+/// line.
+fn stmt_origin(stmt: &TypedStmt) -> Option<&super::lowered::Origin> {
+    match stmt {
+        TypedStmt::Let { origin, .. }
+        | TypedStmt::LetMut { origin, .. }
+        | TypedStmt::For { origin, .. }
+        | TypedStmt::Push { origin, .. }
+        | TypedStmt::While { origin, .. }
+        | TypedStmt::CompoundAssign { origin, .. } => Some(origin),
+        TypedStmt::Return(_, origin) => Some(origin),
+        TypedStmt::Expr(_) => None,
+    }
+}
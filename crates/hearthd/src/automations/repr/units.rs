@@ -0,0 +1,83 @@
+//! Dimensional analysis for unit-literal arithmetic (`5min + 2.5h`, etc.).
+//!
+//! Each `ast::UnitType` belongs to exactly one physical [`Dimension`]. Values
+//! within a dimension convert to a canonical base unit (seconds, radians, or
+//! Kelvin) so they can be combined; values from different dimensions cannot.
+
+use super::ast::UnitType;
+
+/// A physical dimension that a unit literal can belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Time,
+    Angle,
+    Temperature,
+}
+
+impl std::fmt::Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Dimension::Time => write!(f, "time"),
+            Dimension::Angle => write!(f, "angle"),
+            Dimension::Temperature => write!(f, "temperature"),
+        }
+    }
+}
+
+/// The dimension a unit belongs to.
+pub fn dimension_of(unit: UnitType) -> Dimension {
+    match unit {
+        UnitType::Seconds | UnitType::Minutes | UnitType::Hours | UnitType::Days => {
+            Dimension::Time
+        }
+        UnitType::Degrees | UnitType::Radians => Dimension::Angle,
+        UnitType::Celsius | UnitType::Fahrenheit | UnitType::Kelvin => Dimension::Temperature,
+    }
+}
+
+/// The canonical base unit a dimension's values are normalized to.
+pub fn canonical_unit(dimension: Dimension) -> UnitType {
+    match dimension {
+        Dimension::Time => UnitType::Seconds,
+        Dimension::Angle => UnitType::Radians,
+        Dimension::Temperature => UnitType::Kelvin,
+    }
+}
+
+/// Convert `value` (in `unit`) to its dimension's canonical base unit.
+pub fn to_base(unit: UnitType, value: f64) -> f64 {
+    match unit {
+        UnitType::Seconds => value,
+        UnitType::Minutes => value * 60.0,
+        UnitType::Hours => value * 3600.0,
+        UnitType::Days => value * 86400.0,
+        UnitType::Radians => value,
+        UnitType::Degrees => value.to_radians(),
+        UnitType::Kelvin => value,
+        UnitType::Celsius => value + 273.15,
+        UnitType::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_dimension_units_share_dimension() {
+        assert_eq!(dimension_of(UnitType::Minutes), dimension_of(UnitType::Hours));
+        assert_ne!(dimension_of(UnitType::Minutes), dimension_of(UnitType::Degrees));
+    }
+
+    #[test]
+    fn converts_minutes_and_hours_to_seconds() {
+        assert_eq!(to_base(UnitType::Minutes, 5.0), 300.0);
+        assert_eq!(to_base(UnitType::Hours, 2.5), 9000.0);
+    }
+
+    #[test]
+    fn converts_celsius_and_fahrenheit_to_kelvin() {
+        assert!((to_base(UnitType::Celsius, 0.0) - 273.15).abs() < 1e-9);
+        assert!((to_base(UnitType::Fahrenheit, 32.0) - 273.15).abs() < 1e-9);
+    }
+}
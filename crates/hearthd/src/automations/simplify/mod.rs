@@ -0,0 +1,481 @@
+//! Compile-time simplification pass over the lowered AST.
+//!
+//! Runs after [`super::desugar`], before the lowered tree is handed to
+//! `lower` (HIR construction) or walked directly by `interpreter`. Does two
+//! independent things in one bottom-up walk of each automation's `filter`
+//! and `body`:
+//!
+//! - Folds `BinOp`/`UnaryOp` nodes whose operands are already literals into
+//!   a single literal, short-circuiting `&&`/`||` as soon as the left side
+//!   is a constant `Bool` (the right side doesn't need to be constant, or
+//!   even get walked further, for the result to be known).
+//! - Inlines immutable `let x = <literal-or-ident>;` bindings, substituting
+//!   `x`'s value at every reference in scope and dropping the now-dead
+//!   `let`. `LetMut` bindings (the mutable list accumulators
+//!   [`super::desugar`] generates for list comprehensions) are never
+//!   inlined, since `Push` can change their value after the binding.
+//!
+//! Constant folding reuses [`super::repr::lowered_visit`]'s traversal shape
+//! directly, since it needs no state beyond the node being folded. Inlining
+//! needs a scope that shadows correctly through nested `Block`/`If`/`For`
+//! bodies, which doesn't fit `fold`'s single stateless callback, so
+//! `simplify_expr`/`simplify_stmts` below thread an explicit environment
+//! through the same structural walk instead.
+//!
+//! Folded and substituted nodes keep the `Origin` of the expression they
+//! replace, not the value's own origin, so error spans still point at the
+//! code the user wrote.
+
+use std::collections::HashMap;
+
+use super::int_ops::checked_int_div;
+use super::int_ops::checked_int_mod;
+use super::repr::lowered::BinOp;
+use super::repr::lowered::LoweredArg;
+use super::repr::lowered::LoweredAutomation;
+use super::repr::lowered::LoweredExpr;
+use super::repr::lowered::LoweredMatchArm;
+use super::repr::lowered::LoweredProgram;
+use super::repr::lowered::LoweredStmt;
+use super::repr::lowered::LoweredStructField;
+use super::repr::lowered::Origin;
+use super::repr::lowered::Spanned;
+use super::repr::lowered::UnaryOp;
+use super::repr::lowered::UnitType;
+use super::repr::units::canonical_unit;
+use super::repr::units::dimension_of;
+use super::repr::units::to_base;
+
+#[cfg(test)]
+mod tests;
+
+/// Bindings inlined so far in the current scope, keyed by variable name.
+type Env = HashMap<String, Spanned<LoweredExpr>>;
+
+/// Simplify a complete lowered program.
+pub fn simplify(program: LoweredProgram) -> LoweredProgram {
+    match program {
+        LoweredProgram::Automation(automation) => {
+            LoweredProgram::Automation(simplify_automation(automation))
+        }
+        LoweredProgram::Template {
+            params,
+            automations,
+            file,
+        } => LoweredProgram::Template {
+            params,
+            automations: automations.into_iter().map(simplify_automation).collect(),
+            file,
+        },
+    }
+}
+
+fn simplify_automation(automation: LoweredAutomation) -> LoweredAutomation {
+    let env = Env::new();
+    LoweredAutomation {
+        kind: automation.kind,
+        kind_span: automation.kind_span,
+        pattern: automation.pattern,
+        filter: automation.filter.map(|filter| simplify_expr(filter, &env)),
+        body: simplify_stmts(automation.body, &env).0,
+        file: automation.file,
+    }
+}
+
+/// Simplify a statement list, returning the transformed statements and the
+/// environment accumulated by any `let`s inlined along the way - callers
+/// with a trailing result expression in the same scope (`Block`) need that
+/// environment to simplify it; callers that don't (`If`, `For`, match arms)
+/// just take the statements and let the environment go out of scope.
+fn simplify_stmts(stmts: Vec<Spanned<LoweredStmt>>, env: &Env) -> (Vec<Spanned<LoweredStmt>>, Env) {
+    let mut env = env.clone();
+    let mut out = Vec::with_capacity(stmts.len());
+
+    for stmt in stmts {
+        let Spanned { node, origin } = stmt;
+        match node {
+            LoweredStmt::Let { name, value } => {
+                let value = simplify_expr(value, &env);
+                if is_inlineable(&value.node) {
+                    env.insert(name, value);
+                    // Inlined away - don't emit the now-dead `let`.
+                } else {
+                    env.remove(&name);
+                    out.push(Spanned::new(LoweredStmt::Let { name, value }, origin));
+                }
+            }
+            LoweredStmt::LetMut { name, value } => {
+                let value = simplify_expr(value, &env);
+                // Never inlined - `Push` can change it after this point.
+                env.remove(&name);
+                out.push(Spanned::new(LoweredStmt::LetMut { name, value }, origin));
+            }
+            LoweredStmt::Expr(value) => {
+                out.push(Spanned::new(LoweredStmt::Expr(simplify_expr(value, &env)), origin));
+            }
+            LoweredStmt::Return(value) => {
+                out.push(Spanned::new(LoweredStmt::Return(simplify_expr(value, &env)), origin));
+            }
+            LoweredStmt::For { var, iter, body } => {
+                let iter = simplify_expr(iter, &env);
+                let mut body_env = env.clone();
+                body_env.remove(&var);
+                let (body, _) = simplify_stmts(body, &body_env);
+                out.push(Spanned::new(LoweredStmt::For { var, iter, body }, origin));
+            }
+            LoweredStmt::While { cond, body } => {
+                let cond = simplify_expr(cond, &env);
+                let (body, _) = simplify_stmts(body, &env);
+                out.push(Spanned::new(LoweredStmt::While { cond, body }, origin));
+            }
+            LoweredStmt::Push { list, value } => {
+                let value = simplify_expr(value, &env);
+                out.push(Spanned::new(LoweredStmt::Push { list, value }, origin));
+            }
+            LoweredStmt::CompoundAssign { name, op, value } => {
+                let value = simplify_expr(value, &env);
+                // Same rationale as `Push` above: the target's value changes
+                // here, so any prior inlined binding for it is stale.
+                env.remove(&name);
+                out.push(Spanned::new(
+                    LoweredStmt::CompoundAssign { name, op, value },
+                    origin,
+                ));
+            }
+            LoweredStmt::Insert { map, key, value } => {
+                let key = simplify_expr(key, &env);
+                let value = simplify_expr(value, &env);
+                out.push(Spanned::new(LoweredStmt::Insert { map, key, value }, origin));
+            }
+            LoweredStmt::Add { set, value } => {
+                let value = simplify_expr(value, &env);
+                out.push(Spanned::new(LoweredStmt::Add { set, value }, origin));
+            }
+        }
+    }
+
+    (out, env)
+}
+
+/// A value simple enough to substitute at every reference site instead of
+/// binding a `let` for it.
+fn is_inlineable(node: &LoweredExpr) -> bool {
+    matches!(
+        node,
+        LoweredExpr::Int(_)
+            | LoweredExpr::Float(_)
+            | LoweredExpr::String(_)
+            | LoweredExpr::Bool(_)
+            | LoweredExpr::UnitLiteral { .. }
+            | LoweredExpr::Ident(_)
+    )
+}
+
+fn simplify_expr(expr: Spanned<LoweredExpr>, env: &Env) -> Spanned<LoweredExpr> {
+    let Spanned { node, origin } = expr;
+    match node {
+        LoweredExpr::Ident(name) => match env.get(&name) {
+            Some(value) => Spanned::new(value.node.clone(), origin),
+            None => Spanned::new(LoweredExpr::Ident(name), origin),
+        },
+        leaf @ (LoweredExpr::Int(_)
+        | LoweredExpr::Float(_)
+        | LoweredExpr::String(_)
+        | LoweredExpr::Bool(_)
+        | LoweredExpr::UnitLiteral { .. }
+        | LoweredExpr::Path(_)
+        | LoweredExpr::MutableList
+        | LoweredExpr::MutableMap
+        | LoweredExpr::MutableSet) => Spanned::new(leaf, origin),
+        LoweredExpr::BinOp { op, left, right } => {
+            let left = simplify_expr(*left, env);
+            let right = simplify_expr(*right, env);
+            fold_binop(origin, op, left, right)
+        }
+        LoweredExpr::UnaryOp { op, expr } => {
+            let expr = simplify_expr(*expr, env);
+            fold_unaryop(origin, op, expr)
+        }
+        LoweredExpr::Field { expr, field } => Spanned::new(
+            LoweredExpr::Field {
+                expr: Box::new(simplify_expr(*expr, env)),
+                field,
+            },
+            origin,
+        ),
+        LoweredExpr::OptionalField { expr, field } => Spanned::new(
+            LoweredExpr::OptionalField {
+                expr: Box::new(simplify_expr(*expr, env)),
+                field,
+            },
+            origin,
+        ),
+        LoweredExpr::Call { func, args } => Spanned::new(
+            LoweredExpr::Call {
+                func: Box::new(simplify_expr(*func, env)),
+                args: args
+                    .into_iter()
+                    .map(|arg| simplify_arg(arg, env))
+                    .collect(),
+            },
+            origin,
+        ),
+        LoweredExpr::If {
+            cond,
+            then_block,
+            else_block,
+        } => Spanned::new(
+            LoweredExpr::If {
+                cond: Box::new(simplify_expr(*cond, env)),
+                then_block: simplify_stmts(then_block, env).0,
+                else_block: else_block.map(|block| simplify_stmts(block, env).0),
+            },
+            origin,
+        ),
+        LoweredExpr::List(items) => Spanned::new(
+            LoweredExpr::List(
+                items
+                    .into_iter()
+                    .map(|item| simplify_expr(item, env))
+                    .collect(),
+            ),
+            origin,
+        ),
+        LoweredExpr::StructLit { name, fields } => Spanned::new(
+            LoweredExpr::StructLit {
+                name,
+                fields: fields
+                    .into_iter()
+                    .map(|field| simplify_field(field, env))
+                    .collect(),
+            },
+            origin,
+        ),
+        LoweredExpr::Block { stmts, result } => {
+            let (stmts, block_env) = simplify_stmts(stmts, env);
+            let result = Box::new(simplify_expr(*result, &block_env));
+            Spanned::new(LoweredExpr::Block { stmts, result }, origin)
+        }
+        LoweredExpr::Match { scrutinee, arms } => Spanned::new(
+            LoweredExpr::Match {
+                scrutinee: Box::new(simplify_expr(*scrutinee, env)),
+                arms: arms
+                    .into_iter()
+                    .map(|arm| LoweredMatchArm {
+                        pattern: arm.pattern,
+                        body: simplify_stmts(arm.body, env).0,
+                    })
+                    .collect(),
+            },
+            origin,
+        ),
+        LoweredExpr::Lambda { params, body } => {
+            let mut body_env = env.clone();
+            for param in &params {
+                body_env.remove(param);
+            }
+            Spanned::new(
+                LoweredExpr::Lambda {
+                    params,
+                    body: Box::new(simplify_expr(*body, &body_env)),
+                },
+                origin,
+            )
+        }
+        LoweredExpr::Tuple(items) => Spanned::new(
+            LoweredExpr::Tuple(
+                items
+                    .into_iter()
+                    .map(|item| simplify_expr(item, env))
+                    .collect(),
+            ),
+            origin,
+        ),
+    }
+}
+
+fn simplify_arg(arg: Spanned<LoweredArg>, env: &Env) -> Spanned<LoweredArg> {
+    let Spanned { node, origin } = arg;
+    let node = match node {
+        LoweredArg::Positional(value) => LoweredArg::Positional(simplify_expr(value, env)),
+        LoweredArg::Named { name, value } => LoweredArg::Named {
+            name,
+            value: simplify_expr(value, env),
+        },
+    };
+    Spanned::new(node, origin)
+}
+
+fn simplify_field(field: Spanned<LoweredStructField>, env: &Env) -> Spanned<LoweredStructField> {
+    let Spanned { node, origin } = field;
+    let node = match node {
+        LoweredStructField::Field { name, value } => LoweredStructField::Field {
+            name,
+            value: simplify_expr(value, env),
+        },
+        other @ (LoweredStructField::Inherit(_) | LoweredStructField::Spread(_)) => other,
+    };
+    Spanned::new(node, origin)
+}
+
+fn fold_binop(
+    origin: Origin,
+    op: BinOp,
+    left: Spanned<LoweredExpr>,
+    right: Spanned<LoweredExpr>,
+) -> Spanned<LoweredExpr> {
+    use LoweredExpr::*;
+
+    // Short-circuit as soon as the left side is a constant `Bool` - the
+    // right side doesn't need to be constant for the result to be known.
+    match (op, &left.node) {
+        (BinOp::And, Bool(false)) => return Spanned::new(Bool(false), origin),
+        (BinOp::And, Bool(true)) => return right,
+        (BinOp::Or, Bool(true)) => return Spanned::new(Bool(true), origin),
+        (BinOp::Or, Bool(false)) => return right,
+        _ => {}
+    }
+
+    match (&left.node, &right.node) {
+        (Int(a), Int(b)) => match op {
+            BinOp::Add => Spanned::new(Int(a.wrapping_add(*b)), origin),
+            BinOp::Sub => Spanned::new(Int(a.wrapping_sub(*b)), origin),
+            BinOp::Mul => Spanned::new(Int(a.wrapping_mul(*b)), origin),
+            // Division/modulo by a statically-zero divisor is a
+            // compile-time error, not something this pass folds away -
+            // leave it untouched for `check` to report. `checked_int_div`/
+            // `checked_int_mod` cover the remaining i64::MIN / -1 overflow
+            // case, which plain `/`/`%` would panic on.
+            BinOp::Div if *b != 0 => Spanned::new(Int(checked_int_div(*a, *b)), origin),
+            BinOp::Mod if *b != 0 => Spanned::new(Int(checked_int_mod(*a, *b)), origin),
+            BinOp::Eq => Spanned::new(Bool(a == b), origin),
+            BinOp::Ne => Spanned::new(Bool(a != b), origin),
+            BinOp::Lt => Spanned::new(Bool(a < b), origin),
+            BinOp::Le => Spanned::new(Bool(a <= b), origin),
+            BinOp::Gt => Spanned::new(Bool(a > b), origin),
+            BinOp::Ge => Spanned::new(Bool(a >= b), origin),
+            _ => rebuild(origin, op, left, right),
+        },
+        (Float(a), Float(b)) => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(a), Ok(b)) => match op {
+                BinOp::Add => Spanned::new(Float((a + b).to_string()), origin),
+                BinOp::Sub => Spanned::new(Float((a - b).to_string()), origin),
+                BinOp::Mul => Spanned::new(Float((a * b).to_string()), origin),
+                BinOp::Div => Spanned::new(Float((a / b).to_string()), origin),
+                BinOp::Eq => Spanned::new(Bool(a == b), origin),
+                BinOp::Ne => Spanned::new(Bool(a != b), origin),
+                BinOp::Lt => Spanned::new(Bool(a < b), origin),
+                BinOp::Le => Spanned::new(Bool(a <= b), origin),
+                BinOp::Gt => Spanned::new(Bool(a > b), origin),
+                BinOp::Ge => Spanned::new(Bool(a >= b), origin),
+                _ => rebuild(origin, op, left, right),
+            },
+            _ => rebuild(origin, op, left, right),
+        },
+        (Bool(a), Bool(b)) => match op {
+            BinOp::Eq => Spanned::new(Bool(a == b), origin),
+            BinOp::Ne => Spanned::new(Bool(a != b), origin),
+            _ => rebuild(origin, op, left, right),
+        },
+        (String(a), String(b)) => match op {
+            BinOp::Add => Spanned::new(String(format!("{a}{b}")), origin),
+            BinOp::Eq => Spanned::new(Bool(a == b), origin),
+            BinOp::Ne => Spanned::new(Bool(a != b), origin),
+            _ => rebuild(origin, op, left, right),
+        },
+        (
+            UnitLiteral {
+                value: va,
+                unit: ua,
+            },
+            UnitLiteral {
+                value: vb,
+                unit: ub,
+            },
+        ) => {
+            let (va, ua, vb, ub) = (va.clone(), *ua, vb.clone(), *ub);
+            fold_unit_binop(origin, op, &va, ua, &vb, ub, left, right)
+        }
+        _ => rebuild(origin, op, left, right),
+    }
+}
+
+/// Fold two unit-literal operands, refusing (leaving the node untouched) if
+/// their dimensions aren't commensurable (e.g. a duration and an angle) -
+/// that's a type error for `check` to report, not something to silently
+/// fold away.
+fn fold_unit_binop(
+    origin: Origin,
+    op: BinOp,
+    va: &str,
+    ua: UnitType,
+    vb: &str,
+    ub: UnitType,
+    left: Spanned<LoweredExpr>,
+    right: Spanned<LoweredExpr>,
+) -> Spanned<LoweredExpr> {
+    let (da, db) = (dimension_of(ua), dimension_of(ub));
+    if da != db {
+        return rebuild(origin, op, left, right);
+    }
+
+    let (Ok(a), Ok(b)) = (va.parse::<f64>(), vb.parse::<f64>()) else {
+        return rebuild(origin, op, left, right);
+    };
+    let (a, b) = (to_base(ua, a), to_base(ub, b));
+    let unit = canonical_unit(da);
+
+    match op {
+        BinOp::Add => Spanned::new(
+            LoweredExpr::UnitLiteral {
+                value: (a + b).to_string(),
+                unit,
+            },
+            origin,
+        ),
+        BinOp::Sub => Spanned::new(
+            LoweredExpr::UnitLiteral {
+                value: (a - b).to_string(),
+                unit,
+            },
+            origin,
+        ),
+        BinOp::Eq => Spanned::new(LoweredExpr::Bool(a == b), origin),
+        BinOp::Ne => Spanned::new(LoweredExpr::Bool(a != b), origin),
+        BinOp::Lt => Spanned::new(LoweredExpr::Bool(a < b), origin),
+        BinOp::Le => Spanned::new(LoweredExpr::Bool(a <= b), origin),
+        BinOp::Gt => Spanned::new(LoweredExpr::Bool(a > b), origin),
+        BinOp::Ge => Spanned::new(LoweredExpr::Bool(a >= b), origin),
+        _ => rebuild(origin, op, left, right),
+    }
+}
+
+fn rebuild(
+    origin: Origin,
+    op: BinOp,
+    left: Spanned<LoweredExpr>,
+    right: Spanned<LoweredExpr>,
+) -> Spanned<LoweredExpr> {
+    Spanned::new(
+        LoweredExpr::BinOp {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+        },
+        origin,
+    )
+}
+
+fn fold_unaryop(origin: Origin, op: UnaryOp, expr: Spanned<LoweredExpr>) -> Spanned<LoweredExpr> {
+    use LoweredExpr::*;
+
+    match (op, &expr.node) {
+        (UnaryOp::Neg, Int(n)) => Spanned::new(Int(-n), origin),
+        (UnaryOp::Neg, Float(s)) => match s.parse::<f64>() {
+            Ok(v) => Spanned::new(Float((-v).to_string()), origin),
+            Err(_) => Spanned::new(UnaryOp { op, expr: Box::new(expr) }, origin),
+        },
+        (UnaryOp::Not, Bool(b)) => Spanned::new(Bool(!b), origin),
+        _ => Spanned::new(UnaryOp { op, expr: Box::new(expr) }, origin),
+    }
+}
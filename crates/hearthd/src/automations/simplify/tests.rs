@@ -0,0 +1,222 @@
+use chumsky::prelude::*;
+
+use super::simplify_expr;
+use super::simplify_stmts;
+use super::Env;
+use crate::automations::ast;
+use crate::automations::desugar::Desugarer;
+use crate::automations::parser::expr_parser;
+use crate::automations::parser::parse_stmt;
+use crate::automations::repr::lowered::LoweredExpr;
+use crate::automations::repr::lowered::LoweredStmt;
+use crate::automations::repr::lowered::Origin;
+use crate::automations::repr::lowered::Spanned;
+
+fn parse_expr(input: &str) -> ast::Spanned<ast::Expr> {
+    let tokens = crate::automations::lexer::lexer()
+        .parse(input)
+        .into_result()
+        .expect("lexing should succeed");
+    let input_len = input.len();
+    expr_parser()
+        .parse(
+            tokens
+                .as_slice()
+                .map((input_len..input_len).into(), |(t, s)| (t, s)),
+        )
+        .into_result()
+        .expect("parsing should succeed")
+}
+
+/// Parse, desugar, and simplify a standalone expression.
+fn simplify_expr_str(input: &str) -> LoweredExpr {
+    let ast = parse_expr(input);
+    let lowered = Desugarer::new().desugar_expr(ast);
+    simplify_expr(lowered, &Env::new()).node
+}
+
+/// Parse, desugar, and simplify a sequence of statements (each on its own
+/// line, `let`/bare-expression only - the surface grammar doesn't have
+/// syntax for `For`/`LetMut`/`Push`, which only `desugar` itself produces
+/// from list comprehensions, so those are exercised with hand-built
+/// fixtures below instead).
+fn simplify_stmts_str(inputs: &[&str]) -> Vec<LoweredStmt> {
+    let mut desugarer = Desugarer::new();
+    let stmts = inputs
+        .iter()
+        .map(|input| {
+            let stmt = parse_stmt(input).expect("parsing should succeed");
+            desugarer.desugar_stmt(stmt)
+        })
+        .collect();
+    simplify_stmts(stmts, &Env::new())
+        .0
+        .into_iter()
+        .map(|s| s.node)
+        .collect()
+}
+
+fn dummy_origin() -> Origin {
+    Origin::Direct(ast::Spanned::new(ast::Expr::Int(0), (0..0).into()))
+}
+
+fn spanned(node: LoweredExpr) -> Spanned<LoweredExpr> {
+    Spanned::new(node, dummy_origin())
+}
+
+#[test]
+fn folds_int_arithmetic() {
+    assert!(matches!(simplify_expr_str("2 + 3 * 4"), LoweredExpr::Int(14)));
+}
+
+#[test]
+fn folds_comparisons_to_bool() {
+    assert!(matches!(simplify_expr_str("3 < 5"), LoweredExpr::Bool(true)));
+}
+
+#[test]
+fn short_circuits_and_without_evaluating_a_non_literal_right_side() {
+    // `false && a.on` - `a.on` is a field access, never constant, but the
+    // whole expression still folds because `false` already decides it.
+    assert!(matches!(
+        simplify_expr_str("false && a.on"),
+        LoweredExpr::Bool(false)
+    ));
+}
+
+#[test]
+fn or_with_true_left_short_circuits_to_true() {
+    assert!(matches!(
+        simplify_expr_str("true || a.on"),
+        LoweredExpr::Bool(true)
+    ));
+}
+
+#[test]
+fn and_with_true_left_reduces_to_the_right_side_unevaluated() {
+    // `true && a.on` isn't a compile-time constant overall, but it should
+    // still drop the redundant `true &&` down to just `a.on`.
+    assert!(matches!(
+        simplify_expr_str("true && a.on"),
+        LoweredExpr::Field { .. }
+    ));
+}
+
+#[test]
+fn leaves_unit_arithmetic_across_incompatible_dimensions_untouched() {
+    // Seconds and degrees don't share a dimension - this should stay a
+    // `BinOp`, not be folded (or silently miscomputed).
+    assert!(matches!(
+        simplify_expr_str("5s + 90deg"),
+        LoweredExpr::BinOp { .. }
+    ));
+}
+
+#[test]
+fn folds_compatible_unit_arithmetic_to_the_canonical_base_unit() {
+    // 1min + 30s -> 90s, in the canonical base unit (seconds).
+    match simplify_expr_str("1min + 30s") {
+        LoweredExpr::UnitLiteral { value, .. } => {
+            assert_eq!(value.parse::<f64>().unwrap(), 90.0);
+        }
+        other => panic!("expected a folded UnitLiteral, got {other:?}"),
+    }
+}
+
+#[test]
+fn inlines_a_literal_let_and_drops_the_binding() {
+    let stmts = simplify_stmts_str(&["let x = 2;", "x + 1"]);
+    assert_eq!(stmts.len(), 1);
+    assert!(matches!(
+        stmts[0],
+        LoweredStmt::Expr(Spanned {
+            node: LoweredExpr::Int(3),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn non_literal_let_is_kept_and_not_inlined() {
+    let stmts = simplify_stmts_str(&["let x = a.on;", "x"]);
+    assert_eq!(stmts.len(), 2);
+    assert!(matches!(stmts[0], LoweredStmt::Let { .. }));
+    assert!(matches!(
+        stmts[1],
+        LoweredStmt::Expr(Spanned {
+            node: LoweredExpr::Ident(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn let_mut_binding_is_never_inlined() {
+    // Hand-built: `let mut acc = 0; acc` - the surface grammar has no
+    // `LetMut` syntax, since `desugar` only ever produces it itself.
+    let stmts = vec![
+        Spanned::new(
+            LoweredStmt::LetMut {
+                name: "acc".to_string(),
+                value: spanned(LoweredExpr::Int(0)),
+            },
+            dummy_origin(),
+        ),
+        Spanned::new(
+            LoweredStmt::Expr(spanned(LoweredExpr::Ident("acc".to_string()))),
+            dummy_origin(),
+        ),
+    ];
+
+    let (simplified, _) = simplify_stmts(stmts, &Env::new());
+
+    assert_eq!(simplified.len(), 2);
+    assert!(matches!(
+        simplified[1].node,
+        LoweredStmt::Expr(Spanned {
+            node: LoweredExpr::Ident(_),
+            ..
+        })
+    ));
+}
+
+#[test]
+fn for_loop_variable_shadows_an_outer_binding_of_the_same_name() {
+    // `let x = 1; for x in list { x }` - the loop's own `x` must not be
+    // replaced by the outer binding's `1` inside the loop body.
+    let stmts = vec![
+        Spanned::new(
+            LoweredStmt::Let {
+                name: "x".to_string(),
+                value: spanned(LoweredExpr::Int(1)),
+            },
+            dummy_origin(),
+        ),
+        Spanned::new(
+            LoweredStmt::For {
+                var: "x".to_string(),
+                iter: spanned(LoweredExpr::Ident("list".to_string())),
+                body: vec![Spanned::new(
+                    LoweredStmt::Expr(spanned(LoweredExpr::Ident("x".to_string()))),
+                    dummy_origin(),
+                )],
+            },
+            dummy_origin(),
+        ),
+    ];
+
+    let (simplified, _) = simplify_stmts(stmts, &Env::new());
+
+    // The outer `let x = 1;` is inlined away (dead), leaving only the `For`.
+    assert_eq!(simplified.len(), 1);
+    let LoweredStmt::For { body, .. } = &simplified[0].node else {
+        panic!("expected a For statement");
+    };
+    assert!(matches!(
+        body[0].node,
+        LoweredStmt::Expr(Spanned {
+            node: LoweredExpr::Ident(_),
+            ..
+        })
+    ));
+}
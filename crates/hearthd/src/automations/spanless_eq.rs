@@ -0,0 +1,390 @@
+//! Span-insensitive AST equality.
+//!
+//! `Spanned<T>` derives `PartialEq`, which compares `span` along with
+//! `node` - so two trees parsed from different source text never compare
+//! equal via `==`, even when they represent the same program. That's the
+//! wrong notion of equality for round-trip tests (parse -> format ->
+//! re-parse), which only care whether the *meaning* survived. `SpanlessEq`
+//! compares structure and values only, ignoring every `span` field.
+
+use super::ast::*;
+
+pub trait SpanlessEq {
+    fn spanless_eq(&self, other: &Self) -> bool;
+}
+
+impl<T: SpanlessEq> SpanlessEq for Spanned<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.node.spanless_eq(&other.node)
+    }
+}
+
+impl<T: SpanlessEq> SpanlessEq for Box<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        (**self).spanless_eq(other)
+    }
+}
+
+impl<T: SpanlessEq> SpanlessEq for Option<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Some(a), Some(b)) => a.spanless_eq(b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+impl<T: SpanlessEq> SpanlessEq for Vec<T> {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().zip(other).all(|(a, b)| a.spanless_eq(b))
+    }
+}
+
+/// Implements [`SpanlessEq`] for a leaf type by delegating straight to its
+/// own `PartialEq`.
+macro_rules! leaf_spanless_eq {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl SpanlessEq for $ty {
+                fn spanless_eq(&self, other: &Self) -> bool {
+                    self == other
+                }
+            }
+        )*
+    };
+}
+
+leaf_spanless_eq!(
+    String,
+    bool,
+    i64,
+    BinOp,
+    UnaryOp,
+    UnitType,
+    AutomationKind,
+    Type
+);
+
+impl SpanlessEq for Program {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Program::Automation(a), Program::Automation(b)) => a.spanless_eq(b),
+            (Program::Template(a), Program::Template(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Template {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.params.spanless_eq(&other.params) && self.automations.spanless_eq(&other.automations)
+    }
+}
+
+impl SpanlessEq for TemplateParam {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.ty == other.ty
+    }
+}
+
+impl SpanlessEq for Automation {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.pattern.spanless_eq(&other.pattern)
+            && self.filter.spanless_eq(&other.filter)
+            && self.body.spanless_eq(&other.body)
+    }
+}
+
+impl SpanlessEq for Pattern {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Pattern::Ident(a), Pattern::Ident(b)) => a == b,
+            (
+                Pattern::Struct {
+                    fields: fa,
+                    has_rest: ra,
+                },
+                Pattern::Struct {
+                    fields: fb,
+                    has_rest: rb,
+                },
+            ) => ra == rb && fa.spanless_eq(fb),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for CompClause {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                CompClause::For {
+                    var: vara,
+                    iter: ia,
+                },
+                CompClause::For {
+                    var: varb,
+                    iter: ib,
+                },
+            ) => vara.spanless_eq(varb) && ia.spanless_eq(ib),
+            (CompClause::If(a), CompClause::If(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for BindPattern {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (BindPattern::Ident(a), BindPattern::Ident(b)) => a == b,
+            (BindPattern::Tuple(a), BindPattern::Tuple(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for FieldPattern {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.pattern.spanless_eq(&other.pattern)
+    }
+}
+
+impl SpanlessEq for MatchPattern {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                MatchPattern::Variant {
+                    enum_name: ea,
+                    variant: va,
+                    bindings: ba,
+                },
+                MatchPattern::Variant {
+                    enum_name: eb,
+                    variant: vb,
+                    bindings: bb,
+                },
+            ) => ea == eb && va == vb && ba.spanless_eq(bb),
+            (MatchPattern::Wildcard, MatchPattern::Wildcard) => true,
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for BindingPattern {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (BindingPattern::Wildcard, BindingPattern::Wildcard)
+        ) || matches!((self, other), (BindingPattern::Ident(a), BindingPattern::Ident(b)) if a == b)
+    }
+}
+
+impl SpanlessEq for MatchArm {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        self.pattern.spanless_eq(&other.pattern) && self.body.spanless_eq(&other.body)
+    }
+}
+
+impl SpanlessEq for Stmt {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Stmt::Let {
+                    name: na,
+                    value: va,
+                },
+                Stmt::Let {
+                    name: nb,
+                    value: vb,
+                },
+            ) => na == nb && va.spanless_eq(vb),
+            (Stmt::Expr(a), Stmt::Expr(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Arg {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Arg::Positional(a), Arg::Positional(b)) => a.spanless_eq(b),
+            (
+                Arg::Named {
+                    name: na,
+                    value: va,
+                },
+                Arg::Named {
+                    name: nb,
+                    value: vb,
+                },
+            ) => na == nb && va.spanless_eq(vb),
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for StructField {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                StructField::Field {
+                    name: na,
+                    value: va,
+                },
+                StructField::Field {
+                    name: nb,
+                    value: vb,
+                },
+            ) => na == nb && va.spanless_eq(vb),
+            (StructField::Inherit(a), StructField::Inherit(b)) => a == b,
+            (StructField::Spread(a), StructField::Spread(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl SpanlessEq for Expr {
+    fn spanless_eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Expr::Int(a), Expr::Int(b)) => a == b,
+            (Expr::Float(a), Expr::Float(b)) => a == b,
+            (Expr::String(a), Expr::String(b)) => a == b,
+            (Expr::Bool(a), Expr::Bool(b)) => a == b,
+            (
+                Expr::UnitLiteral {
+                    value: va,
+                    unit: ua,
+                },
+                Expr::UnitLiteral {
+                    value: vb,
+                    unit: ub,
+                },
+            ) => va == vb && ua == ub,
+            (Expr::Ident(a), Expr::Ident(b)) => a == b,
+            (
+                Expr::BinOp {
+                    op: oa,
+                    left: la,
+                    right: ra,
+                },
+                Expr::BinOp {
+                    op: ob,
+                    left: lb,
+                    right: rb,
+                },
+            ) => oa == ob && la.spanless_eq(lb) && ra.spanless_eq(rb),
+            (Expr::UnaryOp { op: oa, expr: ea }, Expr::UnaryOp { op: ob, expr: eb }) => {
+                oa == ob && ea.spanless_eq(eb)
+            }
+            (
+                Expr::Field {
+                    expr: ea,
+                    field: fa,
+                },
+                Expr::Field {
+                    expr: eb,
+                    field: fb,
+                },
+            ) => fa == fb && ea.spanless_eq(eb),
+            (
+                Expr::OptionalField {
+                    expr: ea,
+                    field: fa,
+                },
+                Expr::OptionalField {
+                    expr: eb,
+                    field: fb,
+                },
+            ) => fa == fb && ea.spanless_eq(eb),
+            (
+                Expr::Call {
+                    func: fa,
+                    args: aa,
+                },
+                Expr::Call {
+                    func: fb,
+                    args: ab,
+                },
+            ) => fa.spanless_eq(fb) && aa.spanless_eq(ab),
+            (
+                Expr::If {
+                    cond: ca,
+                    then_block: ta,
+                    else_block: ea,
+                },
+                Expr::If {
+                    cond: cb,
+                    then_block: tb,
+                    else_block: eb,
+                },
+            ) => ca.spanless_eq(cb) && ta.spanless_eq(tb) && ea.spanless_eq(eb),
+            (Expr::List(a), Expr::List(b)) => a.spanless_eq(b),
+            (
+                Expr::ListComp {
+                    expr: expa,
+                    clauses: ca,
+                },
+                Expr::ListComp {
+                    expr: expb,
+                    clauses: cb,
+                },
+            ) => expa.spanless_eq(expb) && ca.spanless_eq(cb),
+            (
+                Expr::DictComp {
+                    key: ka,
+                    value: vaa,
+                    clauses: ca,
+                },
+                Expr::DictComp {
+                    key: kb,
+                    value: vab,
+                    clauses: cb,
+                },
+            ) => ka.spanless_eq(kb) && vaa.spanless_eq(vab) && ca.spanless_eq(cb),
+            (
+                Expr::SetComp {
+                    expr: expa,
+                    clauses: ca,
+                },
+                Expr::SetComp {
+                    expr: expb,
+                    clauses: cb,
+                },
+            ) => expa.spanless_eq(expb) && ca.spanless_eq(cb),
+            (
+                Expr::StructLit {
+                    name: na,
+                    fields: fa,
+                },
+                Expr::StructLit {
+                    name: nb,
+                    fields: fb,
+                },
+            ) => na == nb && fa.spanless_eq(fb),
+            (
+                Expr::Match {
+                    scrutinee: sa,
+                    arms: aa,
+                },
+                Expr::Match {
+                    scrutinee: sb,
+                    arms: ab,
+                },
+            ) => sa.spanless_eq(sb) && aa.spanless_eq(ab),
+            (
+                Expr::Lambda {
+                    params: pa,
+                    body: ba,
+                },
+                Expr::Lambda {
+                    params: pb,
+                    body: bb,
+                },
+            ) => pa == pb && ba.spanless_eq(bb),
+            (Expr::Tuple(a), Expr::Tuple(b)) => a.spanless_eq(b),
+            _ => false,
+        }
+    }
+}
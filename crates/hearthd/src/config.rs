@@ -156,11 +156,33 @@ impl Config {
 
 impl HaIntegrationConfig {
     /// Convert the opaque TOML config to JSON for transmission to Python
-    pub fn config_to_json(&self) -> Result<serde_json::Value, ConfigError> {
-        // Convert toml::Value -> serde_json::Value
-        let json_str = serde_json::to_string(&self.config).map_err(ConfigError::JsonConversion)?;
+    pub fn config_to_json(&self) -> serde_json::Value {
+        toml_to_json(&self.config)
+    }
+}
 
-        serde_json::from_str(&json_str).map_err(ConfigError::JsonConversion)
+/// Convert a `toml::Value` to the `serde_json::Value` it represents, field by
+/// field, rather than round-tripping through `serde_json::to_string` /
+/// `from_str`. The round-trip mishandles `toml::Value::Datetime`: serde_json
+/// has no native datetime type, so it serializes one as a tagged object like
+/// `{"$__toml_private_datetime": "2024-01-01T00:00:00Z"}` instead of the
+/// plain string the Python integration code expects.
+fn toml_to_json(value: &toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(s) => serde_json::Value::String(s.clone()),
+        toml::Value::Integer(i) => serde_json::Value::Number((*i).into()),
+        toml::Value::Float(f) => serde_json::Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        toml::Value::Boolean(b) => serde_json::Value::Bool(*b),
+        toml::Value::Datetime(dt) => serde_json::Value::String(dt.to_string()),
+        toml::Value::Array(arr) => serde_json::Value::Array(arr.iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => serde_json::Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.clone(), toml_to_json(v)))
+                .collect(),
+        ),
     }
 }
 
@@ -171,9 +193,6 @@ pub enum ConfigError {
 
     #[error("Failed to parse TOML: {0}")]
     Parse(#[from] toml::de::Error),
-
-    #[error("Failed to convert config to JSON: {0}")]
-    JsonConversion(#[source] serde_json::Error),
 }
 
 #[cfg(test)]
@@ -235,10 +254,38 @@ mod tests {
         assert_eq!(met_config.domain, "met");
         assert!(met_config.enabled);
 
-        let json = met_config.config_to_json().unwrap();
+        let json = met_config.config_to_json();
         assert_eq!(json["latitude"], 59.9139);
     }
 
+    #[test]
+    fn test_ha_integration_config_to_json_renders_datetimes_as_plain_strings() {
+        let toml = r#"
+            [system]
+            python_path = "/usr/bin/python3"
+            ha_source_path = "/tmp/ha"
+
+            [location]
+            latitude = 59.9139
+            longitude = 10.7522
+            elevation = 10
+            timezone = "Europe/Oslo"
+
+            [integrations]
+
+            [integrations.ha.met_oslo]
+            domain = "met"
+            enabled = true
+            config.forecast_until = 2024-01-01T00:00:00Z
+        "#;
+
+        let config: Config = toml::from_str(toml).unwrap();
+        let met_config = config.integrations.ha.get("met_oslo").unwrap();
+
+        let json = met_config.config_to_json();
+        assert_eq!(json["forecast_until"], "2024-01-01T00:00:00Z");
+    }
+
     #[test]
     fn test_parse_native_integration() {
         let toml = r#"
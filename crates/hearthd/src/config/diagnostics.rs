@@ -8,19 +8,44 @@ pub struct SourceInfo {
     pub content: String,
 }
 
-/// A diagnostic message that can be either a warning or an error
+/// A diagnostic message that can be informational, a warning, or an error
 #[derive(Debug, Clone)]
 pub enum Diagnostic {
+    Info(Info),
     Warning(Warning),
     Error(Error),
 }
 
+/// Informational messages that record something that happened but isn't a
+/// problem - e.g. an environment variable overriding a file-set value.
+#[derive(Debug, Clone)]
+pub enum Info {
+    EnvOverride {
+        var_name: String,
+        field_path: String,
+    },
+}
+
 /// Warning messages that don't prevent config loading
 #[derive(Debug, Clone)]
 pub enum Warning {
     EmptyConfig {
         file_path: PathBuf,
     },
+    /// A field was defined in more than one config file and
+    /// `MergeStrategy::LastWins` silently picked the later one, rather than
+    /// `MergeStrategy::FirstWins`'s `Error::Merge`.
+    FieldOverridden {
+        field_path: String,
+        overridden: MergeConflictLocation,
+        winner: MergeConflictLocation,
+    },
+    /// A glob pattern or drop-in directory in `imports` matched no files.
+    /// Not an error - an empty `conf.d`-style directory is normal - but
+    /// surfaced in case it was meant to contain something.
+    EmptyImport {
+        pattern: PathBuf,
+    },
 }
 
 /// Error messages that indicate problems with the config
@@ -45,23 +70,88 @@ pub struct MergeConflictLocation {
     pub content: String,
 }
 
+/// Where a single field's winning value came from: the file that set it,
+/// plus its byte span within that file - analogous to Cargo's
+/// `Definition`. Only a real file produces one today; `merge` runs before
+/// the env/CLI layers (`PartialConfig::apply_env_layer`,
+/// `apply_override_layer`) are applied, so fields those layers set aren't
+/// reflected here yet.
+#[derive(Debug, Clone)]
+pub struct FieldProvenance {
+    pub file_path: PathBuf,
+    pub span: Range<usize>,
+}
+
+/// Per-field provenance for an entire merged config, keyed by the same
+/// dotted field path used elsewhere (`"logging.level"`,
+/// `"locations.home.latitude"`, `"logging.overrides.my_module"`, ...).
+/// Built by [`PartialConfig::merge_with_provenance`] from whichever file's
+/// value actually won each field, so callers can answer "where did this
+/// value come from?" without re-deriving it from the raw files themselves.
+#[derive(Debug, Clone, Default)]
+pub struct Provenance(pub std::collections::HashMap<String, FieldProvenance>);
+
+impl Provenance {
+    /// Look up where `field_path` came from, if any file set it.
+    pub fn get(&self, field_path: &str) -> Option<&FieldProvenance> {
+        self.0.get(field_path)
+    }
+}
+
 /// Error type for validation failures
 #[derive(Debug, Clone)]
 pub struct ValidationError {
     pub field_path: String,
     pub message: String,
-    #[allow(dead_code)] // May be used in future for better error reporting
     pub span: Option<Range<usize>>,
-    #[allow(dead_code)] // May be used in future for better error reporting
     pub source: Option<SourceInfo>,
 }
 
 /// Error type for config loading failures (parse errors, IO errors, etc.)
 #[derive(Debug)]
 pub enum LoadError {
-    Io { path: PathBuf, error: std::io::Error },
-    Parse { path: PathBuf, error: toml::de::Error },
-    ImportCycle { path: PathBuf, cycle: Vec<PathBuf> },
+    Io {
+        path: PathBuf,
+        error: String,
+    },
+    Parse {
+        path: PathBuf,
+        /// The format the parser was dispatched to based on the file's
+        /// extension (`"TOML"`, `"JSON"`, `"YAML"`, `"RON"`), so the error
+        /// message disambiguates which parser rejected the file.
+        format: &'static str,
+        error: String,
+    },
+    ImportCycle {
+        path: PathBuf,
+        cycle: Vec<PathBuf>,
+    },
+    /// A single file, or the aggregate of a file and everything it
+    /// transitively imports, exceeded the configured size limit. This is a
+    /// sanity guard against accidentally pointing the daemon at a log file
+    /// or a dump rather than a config - raise or disable `limit` (see
+    /// `PartialConfig::from_file_with_limit`) if a legitimately large config
+    /// is expected.
+    TooLarge {
+        path: PathBuf,
+        size: u64,
+        limit: u64,
+    },
+    /// The extension named a non-TOML format (`.json`, `.yaml`/`.yml`,
+    /// `.ron`) whose parser isn't compiled in - see the `json`/`yaml`/`ron`
+    /// features on the `hearthd` crate.
+    UnsupportedFormat {
+        path: PathBuf,
+        format: &'static str,
+    },
+    /// The extension doesn't match any format `ConfigFormat` recognizes
+    /// (`.toml`, `.json`, `.yaml`/`.yml`, `.ron`) - unlike `UnsupportedFormat`,
+    /// no parser would help here even in a build with every format feature
+    /// enabled, so this is reported regardless of features.
+    UnknownExtension {
+        path: PathBuf,
+        extension: String,
+    },
 }
 
 impl std::fmt::Display for LoadError {
@@ -70,8 +160,18 @@ impl std::fmt::Display for LoadError {
             LoadError::Io { path, error } => {
                 write!(f, "Failed to read '{}': {}", path.display(), error)
             }
-            LoadError::Parse { path, error } => {
-                write!(f, "Failed to parse '{}': {}", path.display(), error)
+            LoadError::Parse {
+                path,
+                format,
+                error,
+            } => {
+                write!(
+                    f,
+                    "Failed to parse '{}' as {}: {}",
+                    path.display(),
+                    format,
+                    error
+                )
             }
             LoadError::ImportCycle { path, cycle } => {
                 write!(
@@ -81,6 +181,31 @@ impl std::fmt::Display for LoadError {
                     cycle.len()
                 )
             }
+            LoadError::TooLarge { path, size, limit } => {
+                write!(
+                    f,
+                    "'{}' is {} bytes, exceeding the {} byte config size limit",
+                    path.display(),
+                    size,
+                    limit
+                )
+            }
+            LoadError::UnsupportedFormat { path, format } => {
+                write!(
+                    f,
+                    "'{}' looks like {}, but this build of hearthd doesn't support it",
+                    path.display(),
+                    format
+                )
+            }
+            LoadError::UnknownExtension { path, extension } => {
+                write!(
+                    f,
+                    "'{}' has an unrecognized extension '.{}' - expected one of toml, json, yaml, yml, ron",
+                    path.display(),
+                    extension
+                )
+            }
         }
     }
 }
@@ -97,6 +222,11 @@ impl Diagnostic {
     pub fn is_warning(&self) -> bool {
         matches!(self, Diagnostic::Warning(_))
     }
+
+    /// Returns true if this diagnostic is purely informational
+    pub fn is_info(&self) -> bool {
+        matches!(self, Diagnostic::Info(_))
+    }
 }
 
 /// Format all diagnostics for display using Ariadne
@@ -107,6 +237,18 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
 
     for diagnostic in diagnostics {
         match diagnostic {
+            Diagnostic::Info(info) => match info {
+                Info::EnvOverride {
+                    var_name,
+                    field_path,
+                } => {
+                    let info_msg = format!(
+                        "Info: '{}' set '{}' from the environment, overriding any file value\n",
+                        var_name, field_path
+                    );
+                    output.extend_from_slice(info_msg.as_bytes());
+                }
+            },
             Diagnostic::Warning(warning) => match warning {
                 Warning::EmptyConfig { file_path } => {
                     let warning_msg = format!(
@@ -115,6 +257,24 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
                     );
                     output.extend_from_slice(warning_msg.as_bytes());
                 }
+                Warning::FieldOverridden {
+                    field_path,
+                    overridden,
+                    winner,
+                } => {
+                    let warning_msg = format!(
+                        "Warning: '{}' set in '{}' was overridden by '{}'\n",
+                        field_path,
+                        overridden.file_path.display(),
+                        winner.file_path.display()
+                    );
+                    output.extend_from_slice(warning_msg.as_bytes());
+                }
+                Warning::EmptyImport { pattern } => {
+                    let warning_msg =
+                        format!("Warning: import '{}' matched no files\n", pattern.display());
+                    output.extend_from_slice(warning_msg.as_bytes());
+                }
             },
             Diagnostic::Error(error) => match error {
                 Error::Merge(merge_error) => {
@@ -127,7 +287,10 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
                             first_conflict.span.clone(),
                         ),
                     )
-                    .with_message(format!("Merge conflict in field '{}'", merge_error.field_path))
+                    .with_message(format!(
+                        "Merge conflict in field '{}'",
+                        merge_error.field_path
+                    ))
                     .with_note(&merge_error.message);
 
                     // Add labels for each conflict location
@@ -144,7 +307,11 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
                                 conflict.span.clone(),
                             ))
                             .with_message(label_msg)
-                            .with_color(if idx == 0 { Color::Red } else { Color::Yellow }),
+                            .with_color(if idx == 0 {
+                                Color::Red
+                            } else {
+                                Color::Yellow
+                            }),
                         );
                     }
 
@@ -157,19 +324,47 @@ pub fn format_diagnostics(diagnostics: &[Diagnostic]) -> String {
                         let file_id = conflict.file_path.to_string_lossy().to_string();
                         if written_files.insert(file_id.clone()) {
                             let source = Source::from(&conflict.content);
-                            finished_report
-                                .write((file_id, source), &mut output)
-                                .ok();
+                            finished_report.write((file_id, source), &mut output).ok();
                         }
                     }
                 }
                 Error::Validation(validation_error) => {
-                    // For validation errors, format them simply
-                    let error_msg = format!(
-                        "Validation error in '{}': {}\n",
-                        validation_error.field_path, validation_error.message
-                    );
-                    output.extend_from_slice(error_msg.as_bytes());
+                    match (&validation_error.span, &validation_error.source) {
+                        (Some(span), Some(source_info)) => {
+                            let file_id = source_info.file_path.to_string_lossy().to_string();
+
+                            let finished_report =
+                                Report::build(ReportKind::Error, (file_id.clone(), span.clone()))
+                                    .with_message(format!(
+                                        "Validation error in '{}'",
+                                        validation_error.field_path
+                                    ))
+                                    .with_label(
+                                        Label::new((file_id.clone(), span.clone()))
+                                            .with_message(&validation_error.message)
+                                            .with_color(Color::Red),
+                                    )
+                                    .with_note(format!(
+                                        "fix '{}' and reload the config",
+                                        validation_error.field_path
+                                    ))
+                                    .finish();
+
+                            finished_report
+                                .write((file_id, Source::from(&source_info.content)), &mut output)
+                                .ok();
+                        }
+                        _ => {
+                            // No span/source to point at (e.g. a cross-field
+                            // validation error with no single origin) - fall
+                            // back to the plain-text message.
+                            let error_msg = format!(
+                                "Validation error in '{}': {}\n",
+                                validation_error.field_path, validation_error.message
+                            );
+                            output.extend_from_slice(error_msg.as_bytes());
+                        }
+                    }
                 }
             },
         }
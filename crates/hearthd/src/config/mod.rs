@@ -1,7 +1,15 @@
 mod config;
+mod diagnostics;
+mod partial;
+#[cfg(feature = "watch")]
+mod watch;
 
 // Re-export specific types for clarity
 pub use config::LogLevel;
 pub use config::*;
+#[cfg(feature = "watch")]
+pub use watch::WatchHandle;
 // Re-export diagnostics from hearthd_config (the proc-macro based implementation)
-pub use hearthd_config::{Diagnostic, Diagnostics, format_diagnostics};
+pub use hearthd_config::{
+    format_diagnostics, format_diagnostics_json, Diagnostic, Diagnostics, OutputFormat,
+};
@@ -3,10 +3,20 @@ use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use super::diagnostics::{
-    Diagnostic, Error, LoadError, MergeConflictLocation, MergeError, SourceInfo, ValidationError,
-    Warning,
+    Diagnostic, Error, FieldProvenance, Info, LoadError, MergeConflictLocation, MergeError,
+    Provenance, SourceInfo, ValidationError, Warning,
 };
-use super::{HttpConfig, Location, LocationsConfig, LogLevel, LoggingConfig};
+use super::{
+    HttpConfig, Location, LocationsConfig, LogLevel, LoggingConfig, DEFAULT_MAX_LOG_FILES,
+};
+
+/// Default ceiling on a single config file, and on the aggregate of a file
+/// plus everything it transitively imports. Guards against accidentally
+/// pointing the daemon at a runaway or wrong file (a log, a dump) as
+/// config - `merge` stores full file contents in every diagnostic location,
+/// so an unbounded file would also blow up memory well before that point.
+/// Pass a larger `Some(limit)` or `None` to disable the check entirely.
+pub const DEFAULT_MAX_CONFIG_SIZE: u64 = 100 * 1024 * 1024;
 
 #[derive(Debug, Default, Deserialize)]
 pub struct PartialConfig {
@@ -27,6 +37,9 @@ pub struct PartialConfig {
 pub struct PartialLoggingConfig {
     pub level: Option<toml::Spanned<LogLevel>>,
     pub overrides: Option<HashMap<String, toml::Spanned<LogLevel>>>,
+    pub file: Option<toml::Spanned<PathBuf>>,
+    pub max_size_bytes: Option<toml::Spanned<u64>>,
+    pub max_files: Option<toml::Spanned<u32>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -38,10 +51,10 @@ pub struct PartialLocationsConfig {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct PartialLocation {
-    pub latitude: Option<f64>,
-    pub longitude: Option<f64>,
-    pub elevation_m: Option<f64>,
-    pub timezone: Option<String>,
+    pub latitude: Option<toml::Spanned<f64>>,
+    pub longitude: Option<toml::Spanned<f64>>,
+    pub elevation_m: Option<toml::Spanned<f64>>,
+    pub timezone: Option<toml::Spanned<String>>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -83,7 +96,7 @@ impl TryFrom<(PartialLocation, LocationConversionContext)> for Location {
 
         // Latitude is required
         let latitude = if let Some(lat) = partial.latitude {
-            lat
+            lat.into_inner()
         } else {
             diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
                 field_path: format!("locations.{}.latitude", ctx.name),
@@ -96,7 +109,7 @@ impl TryFrom<(PartialLocation, LocationConversionContext)> for Location {
 
         // Longitude is required
         let longitude = if let Some(lon) = partial.longitude {
-            lon
+            lon.into_inner()
         } else {
             diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
                 field_path: format!("locations.{}.longitude", ctx.name),
@@ -107,8 +120,8 @@ impl TryFrom<(PartialLocation, LocationConversionContext)> for Location {
             0.0 // Default for error recovery
         };
 
-        let elevation_m = partial.elevation_m;
-        let timezone = partial.timezone;
+        let elevation_m = partial.elevation_m.map(|v| v.into_inner());
+        let timezone = partial.timezone.map(|v| v.into_inner());
 
         if diagnostics.is_empty() {
             Ok(Location {
@@ -133,6 +146,12 @@ impl TryFrom<PartialLoggingConfig> for LoggingConfig {
                 .overrides
                 .map(|hm| hm.into_iter().map(|(k, v)| (k, *v.get_ref())).collect())
                 .unwrap_or_default(),
+            file: partial.file.map(|s| s.into_inner()),
+            max_size_bytes: partial.max_size_bytes.map(|s| *s.get_ref()),
+            max_files: partial
+                .max_files
+                .map(|s| *s.get_ref())
+                .unwrap_or(DEFAULT_MAX_LOG_FILES),
         })
     }
 }
@@ -173,17 +192,419 @@ impl TryFrom<(PartialLocationsConfig, Option<SourceInfo>)> for LocationsConfig {
     }
 }
 
+/// Whether a field defined in more than one config file keeps the first
+/// definition (reporting later ones as an `Error::Merge` conflict) or lets
+/// the last definition silently win (reporting a `Warning::FieldOverridden`
+/// instead).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    FirstWins,
+    LastWins,
+}
+
+/// Merge a single scalar field (one value per config, e.g. `http.port`)
+/// according to `strategy`. `field_loc` tracks the location of whichever
+/// definition is currently in `result_field`, so a later conflict can always
+/// point at the right "first definition here" / "overridden by" span.
+#[allow(clippy::too_many_arguments)]
+fn merge_scalar_field<T: Clone>(
+    field_path: &str,
+    message: &str,
+    new_value: Option<toml::Spanned<T>>,
+    result_field: &mut Option<T>,
+    field_loc: &mut Option<MergeConflictLocation>,
+    source_info: &SourceInfo,
+    strategy: MergeStrategy,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(new_value) = new_value else {
+        return;
+    };
+
+    let conflict_loc = MergeConflictLocation {
+        file_path: source_info.file_path.clone(),
+        span: new_value.span(),
+        content: source_info.content.clone(),
+    };
+
+    match field_loc.clone() {
+        Some(prev_loc) => match strategy {
+            MergeStrategy::FirstWins => {
+                diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
+                    field_path: field_path.to_string(),
+                    message: message.to_string(),
+                    conflicts: vec![prev_loc, conflict_loc],
+                })));
+            }
+            MergeStrategy::LastWins => {
+                diagnostics.push(Diagnostic::Warning(Warning::FieldOverridden {
+                    field_path: field_path.to_string(),
+                    overridden: prev_loc,
+                    winner: conflict_loc.clone(),
+                }));
+                *result_field = Some(new_value.into_inner());
+                *field_loc = Some(conflict_loc);
+            }
+        },
+        None => {
+            *result_field = Some(new_value.into_inner());
+            *field_loc = Some(conflict_loc);
+        }
+    }
+}
+
+/// Merge a field that's keyed by a secondary string - a named location's
+/// field, or a logging override for a named target - according to
+/// `strategy`. `field_locs` tracks the location of whichever definition is
+/// currently in `result_field` for `key`, keyed the same way.
+#[allow(clippy::too_many_arguments)]
+fn merge_keyed_field<T: Clone>(
+    field_path: String,
+    message: String,
+    new_value: Option<toml::Spanned<T>>,
+    result_field: &mut Option<T>,
+    field_locs: &mut HashMap<String, MergeConflictLocation>,
+    key: &str,
+    source_info: &SourceInfo,
+    strategy: MergeStrategy,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let Some(new_value) = new_value else {
+        return;
+    };
+
+    let conflict_loc = MergeConflictLocation {
+        file_path: source_info.file_path.clone(),
+        span: new_value.span(),
+        content: source_info.content.clone(),
+    };
+
+    match field_locs.get(key).cloned() {
+        Some(prev_loc) => match strategy {
+            MergeStrategy::FirstWins => {
+                diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
+                    field_path,
+                    message,
+                    conflicts: vec![prev_loc, conflict_loc],
+                })));
+            }
+            MergeStrategy::LastWins => {
+                diagnostics.push(Diagnostic::Warning(Warning::FieldOverridden {
+                    field_path,
+                    overridden: prev_loc,
+                    winner: conflict_loc.clone(),
+                }));
+                *result_field = Some(new_value.into_inner());
+                field_locs.insert(key.to_string(), conflict_loc);
+            }
+        },
+        None => {
+            *result_field = Some(new_value.into_inner());
+            field_locs.insert(key.to_string(), conflict_loc);
+        }
+    }
+}
+
+/// Merge one field of one named location: see [`merge_keyed_field`] for the
+/// `strategy` semantics. `field_name`'s real parsed span - from
+/// `toml::Spanned`, not a source-text search - is what conflicts point at.
+#[allow(clippy::too_many_arguments)]
+fn merge_location_field<T: Clone>(
+    location_key: &str,
+    field_name: &str,
+    new_value: Option<toml::Spanned<T>>,
+    result_field: &mut Option<T>,
+    field_locs: &mut HashMap<String, MergeConflictLocation>,
+    source_info: &SourceInfo,
+    strategy: MergeStrategy,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    merge_keyed_field(
+        format!("locations.{}.{}", location_key, field_name),
+        format!(
+            "Field '{}' for location '{}' defined in multiple config files",
+            field_name, location_key
+        ),
+        new_value,
+        result_field,
+        field_locs,
+        field_name,
+        source_info,
+        strategy,
+        diagnostics,
+    )
+}
+
+/// A JSON/YAML/RON mirror of `PartialConfig` with plain, unwrapped field
+/// types instead of `toml::Spanned<T>`. None of `serde_json`, `serde_yaml`,
+/// or `ron` understands the span sentinels `toml::Spanned`'s `Deserialize`
+/// impl relies on, so non-TOML files are deserialized into this shape first
+/// and then converted, with every value getting a span-less `0..0` span.
+///
+/// Only compiled in when at least one of the `json`/`yaml`/`ron` features is
+/// enabled, since it exists purely to feed their parsers.
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+#[derive(Debug, Deserialize)]
+struct RawPartialConfig {
+    #[serde(default)]
+    imports: Vec<String>,
+    logging: Option<RawPartialLoggingConfig>,
+    locations: Option<RawPartialLocationsConfig>,
+    http: Option<RawPartialHttpConfig>,
+    integrations: Option<PartialIntegrationsConfig>,
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+#[derive(Debug, Deserialize)]
+struct RawPartialLoggingConfig {
+    level: Option<LogLevel>,
+    overrides: Option<HashMap<String, LogLevel>>,
+    file: Option<PathBuf>,
+    max_size_bytes: Option<u64>,
+    max_files: Option<u32>,
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+#[derive(Debug, Deserialize)]
+struct RawPartialLocationsConfig {
+    default: Option<String>,
+    #[serde(flatten)]
+    locations: HashMap<String, RawPartialLocation>,
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+#[derive(Debug, Deserialize)]
+struct RawPartialLocation {
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    elevation_m: Option<f64>,
+    timezone: Option<String>,
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+#[derive(Debug, Deserialize)]
+struct RawPartialHttpConfig {
+    listen: Option<String>,
+    port: Option<u16>,
+}
+
+#[cfg(any(feature = "json", feature = "yaml", feature = "ron"))]
+impl From<RawPartialConfig> for PartialConfig {
+    fn from(raw: RawPartialConfig) -> Self {
+        PartialConfig {
+            imports: raw.imports,
+            logging: raw.logging.map(|l| PartialLoggingConfig {
+                level: l.level.map(|v| toml::Spanned::new(0..0, v)),
+                overrides: l.overrides.map(|hm| {
+                    hm.into_iter()
+                        .map(|(k, v)| (k, toml::Spanned::new(0..0, v)))
+                        .collect()
+                }),
+                file: l.file.map(|v| toml::Spanned::new(0..0, v)),
+                max_size_bytes: l.max_size_bytes.map(|v| toml::Spanned::new(0..0, v)),
+                max_files: l.max_files.map(|v| toml::Spanned::new(0..0, v)),
+            }),
+            locations: raw.locations.map(|locs| PartialLocationsConfig {
+                default: locs.default.map(|v| toml::Spanned::new(0..0, v)),
+                locations: locs
+                    .locations
+                    .into_iter()
+                    .map(|(key, loc)| {
+                        (
+                            key,
+                            PartialLocation {
+                                latitude: loc.latitude.map(|v| toml::Spanned::new(0..0, v)),
+                                longitude: loc.longitude.map(|v| toml::Spanned::new(0..0, v)),
+                                elevation_m: loc.elevation_m.map(|v| toml::Spanned::new(0..0, v)),
+                                timezone: loc.timezone.map(|v| toml::Spanned::new(0..0, v)),
+                            },
+                        )
+                    })
+                    .collect(),
+            }),
+            http: raw.http.map(|h| PartialHttpConfig {
+                listen: h.listen.map(|v| toml::Spanned::new(0..0, v)),
+                port: h.port.map(|v| toml::Spanned::new(0..0, v)),
+            }),
+            integrations: raw.integrations,
+            source: None,
+        }
+    }
+}
+
+/// Which parser a config file's extension selects - dispatch happens on
+/// extension the way the `config` crate does it: `.toml`, `.json`,
+/// `.yaml`/`.yml`, `.ron`. A file with no extension defaults to `Toml`,
+/// hearthd's original and most common format; any other extension matches
+/// no variant, which [`PartialConfig::from_file_with_limit`] reports as
+/// [`LoadError::UnknownExtension`] rather than silently falling back to TOML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+    Ron,
+}
+
+impl ConfigFormat {
+    fn from_extension(ext: Option<&str>) -> Option<Self> {
+        match ext {
+            None | Some("toml") => Some(ConfigFormat::Toml),
+            Some("json") => Some(ConfigFormat::Json),
+            Some("yaml") | Some("yml") => Some(ConfigFormat::Yaml),
+            Some("ron") => Some(ConfigFormat::Ron),
+            Some(_) => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Ron => "RON",
+        }
+    }
+}
+
+/// Parses a config file's raw text into a [`PartialConfig`]. One
+/// implementation per [`ConfigFormat`] - TOML is always compiled in;
+/// JSON/YAML/RON are gated behind their matching cargo feature - so
+/// `from_file_with_limit`'s format dispatch just picks the right impl
+/// instead of inlining each parser's call and error mapping.
+trait FormatParser {
+    fn parse(content: &str) -> Result<PartialConfig, String>;
+}
+
+struct TomlParser;
+impl FormatParser for TomlParser {
+    fn parse(content: &str) -> Result<PartialConfig, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "json")]
+struct JsonParser;
+#[cfg(feature = "json")]
+impl FormatParser for JsonParser {
+    fn parse(content: &str) -> Result<PartialConfig, String> {
+        serde_json::from_str::<RawPartialConfig>(content)
+            .map(PartialConfig::from)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "yaml")]
+struct YamlParser;
+#[cfg(feature = "yaml")]
+impl FormatParser for YamlParser {
+    fn parse(content: &str) -> Result<PartialConfig, String> {
+        serde_yaml::from_str::<RawPartialConfig>(content)
+            .map(PartialConfig::from)
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "ron")]
+struct RonParser;
+#[cfg(feature = "ron")]
+impl FormatParser for RonParser {
+    fn parse(content: &str) -> Result<PartialConfig, String> {
+        ron::from_str::<RawPartialConfig>(content)
+            .map(PartialConfig::from)
+            .map_err(|e| e.to_string())
+    }
+}
+
 impl PartialConfig {
-    /// Load a single config file without processing imports
+    /// Load a single config file without processing imports.
+    ///
+    /// The parser is chosen from `path`'s extension via [`ConfigFormat`]:
+    /// `.toml` (the default for no extension too) parses with real
+    /// `toml::Spanned` byte spans; `.json`, `.yaml`/`.yml`, and `.ron` parse
+    /// via [`RawPartialConfig`] instead, since none of their Serde crates
+    /// understand toml's span sentinels, so every value loaded from them
+    /// gets a span-less `0..0` span. The non-TOML parsers are gated behind
+    /// their matching `json`/`yaml`/`ron` cargo features - a file with one
+    /// of those extensions in a build without the matching feature fails
+    /// with [`LoadError::UnsupportedFormat`], and a file whose extension
+    /// doesn't match any [`ConfigFormat`] at all fails with
+    /// [`LoadError::UnknownExtension`] - neither falls back to TOML.
+    /// Imported files (`imports = [...]`) go through this same dispatch, so
+    /// a TOML file can import a JSON, YAML, or RON one and vice versa.
     pub fn from_file(path: &Path) -> Result<Self, LoadError> {
+        Self::from_file_with_limit(path, Some(DEFAULT_MAX_CONFIG_SIZE))
+    }
+
+    /// Like [`from_file`](Self::from_file), but with an explicit size limit.
+    ///
+    /// `limit` is the maximum allowed file size in bytes; `None` disables
+    /// the check entirely (the opt-out escape hatch for a legitimately
+    /// large config).
+    pub fn from_file_with_limit(path: &Path, limit: Option<u64>) -> Result<Self, LoadError> {
+        if let Some(limit) = limit {
+            let size = std::fs::metadata(path)
+                .map_err(|e| LoadError::Io {
+                    path: path.to_path_buf(),
+                    error: e.to_string(),
+                })?
+                .len();
+            if size > limit {
+                return Err(LoadError::TooLarge {
+                    path: path.to_path_buf(),
+                    size,
+                    limit,
+                });
+            }
+        }
+
         let content = std::fs::read_to_string(path).map_err(|e| LoadError::Io {
             path: path.to_path_buf(),
             error: e.to_string(),
         })?;
 
-        let mut config: PartialConfig = toml::from_str(&content).map_err(|e| LoadError::Parse {
+        let extension = path.extension().and_then(|ext| ext.to_str());
+        let format =
+            ConfigFormat::from_extension(extension).ok_or_else(|| LoadError::UnknownExtension {
+                path: path.to_path_buf(),
+                extension: extension.unwrap_or_default().to_string(),
+            })?;
+
+        let parsed = match format {
+            ConfigFormat::Toml => TomlParser::parse(&content),
+            #[cfg(feature = "json")]
+            ConfigFormat::Json => JsonParser::parse(&content),
+            #[cfg(not(feature = "json"))]
+            ConfigFormat::Json => {
+                return Err(LoadError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                    format: "JSON",
+                });
+            }
+            #[cfg(feature = "yaml")]
+            ConfigFormat::Yaml => YamlParser::parse(&content),
+            #[cfg(not(feature = "yaml"))]
+            ConfigFormat::Yaml => {
+                return Err(LoadError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                    format: "YAML",
+                });
+            }
+            #[cfg(feature = "ron")]
+            ConfigFormat::Ron => RonParser::parse(&content),
+            #[cfg(not(feature = "ron"))]
+            ConfigFormat::Ron => {
+                return Err(LoadError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                    format: "RON",
+                });
+            }
+        };
+
+        let mut config: PartialConfig = parsed.map_err(|error| LoadError::Parse {
             path: path.to_path_buf(),
-            error: e.to_string(),
+            format: format.name(),
+            error,
         })?;
 
         config.source = Some(SourceInfo {
@@ -194,28 +615,191 @@ impl PartialConfig {
         Ok(config)
     }
 
+    /// Parse one `--config` command-line argument into a partial config.
+    ///
+    /// `arg` is taken as a literal TOML fragment, not split into a path and
+    /// value itself - so both a dotted-key assignment like
+    /// `logging.level="debug"` (valid TOML on its own, via dotted keys) and
+    /// a multi-line `[table]` fragment work with the same parser used for a
+    /// whole file. `index` (1-based, matching how arguments are numbered
+    /// when reported to a user) names the synthetic source as
+    /// `--config argument {index}`, so a parse or validation error in the
+    /// fragment points at which argument caused it instead of a file path.
+    pub fn from_arg(index: usize, arg: &str) -> Result<Self, LoadError> {
+        let path = PathBuf::from(format!("--config argument {}", index));
+
+        let mut config: PartialConfig = toml::from_str(arg).map_err(|e| LoadError::Parse {
+            path: path.clone(),
+            format: "TOML",
+            error: e.to_string(),
+        })?;
+
+        config.source = Some(SourceInfo {
+            file_path: path,
+            content: arg.to_string(),
+        });
+
+        Ok(config)
+    }
+
+    /// Apply `layer` on top of `self` as an override: every field `layer`
+    /// sets overwrites the corresponding field in `self`, with no
+    /// diagnostic either way, regardless of whether `self` already had a
+    /// value there. Used to fold `--config` arguments in above the merged
+    /// files - unlike `merge_with_strategy`'s `LastWins`, a `--config`
+    /// value is explicit user intent, not a second file that happens to
+    /// collide, so it wins silently instead of producing a
+    /// `Warning::FieldOverridden`.
+    pub fn apply_override_layer(&mut self, layer: Self) {
+        if let Some(layer_logging) = layer.logging {
+            let result_logging = self.logging.get_or_insert_with(|| PartialLoggingConfig {
+                level: None,
+                overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            });
+            if let Some(level) = layer_logging.level {
+                result_logging.level = Some(level);
+            }
+            if let Some(overrides) = layer_logging.overrides {
+                result_logging
+                    .overrides
+                    .get_or_insert_with(HashMap::new)
+                    .extend(overrides);
+            }
+        }
+
+        if let Some(layer_http) = layer.http {
+            let result_http = self.http.get_or_insert_with(|| PartialHttpConfig {
+                listen: None,
+                port: None,
+            });
+            if let Some(listen) = layer_http.listen {
+                result_http.listen = Some(listen);
+            }
+            if let Some(port) = layer_http.port {
+                result_http.port = Some(port);
+            }
+        }
+
+        if let Some(layer_locations) = layer.locations {
+            let result_locations = self
+                .locations
+                .get_or_insert_with(|| PartialLocationsConfig {
+                    default: None,
+                    locations: HashMap::new(),
+                });
+            if let Some(default) = layer_locations.default {
+                result_locations.default = Some(default);
+            }
+            for (name, layer_location) in layer_locations.locations {
+                let result_location =
+                    result_locations
+                        .locations
+                        .entry(name)
+                        .or_insert(PartialLocation {
+                            latitude: None,
+                            longitude: None,
+                            elevation_m: None,
+                            timezone: None,
+                        });
+                if let Some(latitude) = layer_location.latitude {
+                    result_location.latitude = Some(latitude);
+                }
+                if let Some(longitude) = layer_location.longitude {
+                    result_location.longitude = Some(longitude);
+                }
+                if let Some(elevation_m) = layer_location.elevation_m {
+                    result_location.elevation_m = Some(elevation_m);
+                }
+                if let Some(timezone) = layer_location.timezone {
+                    result_location.timezone = Some(timezone);
+                }
+            }
+        }
+
+        if layer.integrations.is_some() {
+            self.integrations = layer.integrations;
+        }
+    }
+
     /// Load config files with import resolution
     ///
     /// Each config file is loaded, then its imports are recursively processed.
     /// Cycle detection prevents infinite loops.
     ///
-    /// Returns a Vec of all loaded configs in order (imports first, then parent)
-    pub fn load_with_imports(paths: &[PathBuf]) -> Result<Vec<Self>, LoadError> {
+    /// Returns the loaded configs in order (imports first, then parent) plus
+    /// any non-fatal diagnostics accumulated while expanding imports (e.g. a
+    /// glob or drop-in directory that matched no files).
+    pub fn load_with_imports(paths: &[PathBuf]) -> Result<(Vec<Self>, Vec<Diagnostic>), LoadError> {
+        Self::load_with_imports_with_limit(paths, Some(DEFAULT_MAX_CONFIG_SIZE))
+    }
+
+    /// Like [`load_with_imports`](Self::load_with_imports), but with an
+    /// explicit size limit applied both per-file and to the aggregate of
+    /// `paths` plus everything they transitively import.
+    ///
+    /// `limit` is the maximum allowed size in bytes; `None` disables the
+    /// check entirely.
+    pub fn load_with_imports_with_limit(
+        paths: &[PathBuf],
+        limit: Option<u64>,
+    ) -> Result<(Vec<Self>, Vec<Diagnostic>), LoadError> {
+        let (configs, diagnostics, _paths) =
+            Self::load_with_imports_with_limit_and_paths(paths, limit)?;
+        Ok((configs, diagnostics))
+    }
+
+    /// Like [`load_with_imports`](Self::load_with_imports), but also
+    /// returns every file actually read - `paths` plus everything they
+    /// transitively import - so a caller like [`Config::watch`] knows the
+    /// full set of files to watch instead of just the ones it was handed
+    /// directly.
+    ///
+    /// [`Config::watch`]: super::config::Config::watch
+    pub fn load_with_imports_with_paths(
+        paths: &[PathBuf],
+    ) -> Result<(Vec<Self>, Vec<Diagnostic>, Vec<PathBuf>), LoadError> {
+        Self::load_with_imports_with_limit_and_paths(paths, Some(DEFAULT_MAX_CONFIG_SIZE))
+    }
+
+    /// [`load_with_imports_with_paths`](Self::load_with_imports_with_paths)
+    /// with an explicit size limit, the same way
+    /// [`load_with_imports_with_limit`](Self::load_with_imports_with_limit)
+    /// relates to [`load_with_imports`](Self::load_with_imports).
+    pub fn load_with_imports_with_limit_and_paths(
+        paths: &[PathBuf],
+        limit: Option<u64>,
+    ) -> Result<(Vec<Self>, Vec<Diagnostic>, Vec<PathBuf>), LoadError> {
         let mut visited = HashSet::new();
         let mut all_configs = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut total_size = 0u64;
 
         for path in paths {
-            Self::load_recursive(path, &mut visited, &mut all_configs)?;
+            Self::load_recursive(
+                path,
+                &mut visited,
+                &mut all_configs,
+                &mut diagnostics,
+                &mut total_size,
+                limit,
+            )?;
         }
 
-        Ok(all_configs)
+        Ok((all_configs, diagnostics, visited.into_iter().collect()))
     }
 
     /// Recursively load a config file and its imports
+    #[allow(clippy::too_many_arguments)]
     fn load_recursive(
         path: &Path,
         visited: &mut HashSet<PathBuf>,
         configs: &mut Vec<Self>,
+        diagnostics: &mut Vec<Diagnostic>,
+        total_size: &mut u64,
+        limit: Option<u64>,
     ) -> Result<(), LoadError> {
         // Canonicalize the path to detect cycles reliably
         let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
@@ -230,22 +814,38 @@ impl PartialConfig {
 
         visited.insert(canonical_path.clone());
 
-        // Load the config file
-        let config = Self::from_file(path)?;
+        // Load the config file. The per-file limit is enforced inside
+        // `from_file_with_limit`; the aggregate limit across everything
+        // loaded so far is enforced separately below, since a pile of
+        // individually-small files can still add up to a runaway total.
+        let config = Self::from_file_with_limit(path, limit)?;
+        if let Some(limit) = limit {
+            *total_size += std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            if *total_size > limit {
+                return Err(LoadError::TooLarge {
+                    path: canonical_path,
+                    size: *total_size,
+                    limit,
+                });
+            }
+        }
 
-        // Process imports first (depth-first)
+        // Process imports first (depth-first). Each entry is expanded to
+        // concrete file paths - a glob or directory may expand to several -
+        // before cycle detection, so the usual `ImportCycle` guard applies
+        // to each resolved file individually.
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
         for import_path in &config.imports {
-            let import_path_buf = PathBuf::from(import_path);
-
-            // Resolve relative imports from the parent file's directory
-            let resolved_path = if import_path_buf.is_absolute() {
-                import_path_buf
-            } else {
-                let parent_dir = path.parent().unwrap_or_else(|| Path::new("."));
-                parent_dir.join(import_path_buf)
-            };
-
-            Self::load_recursive(&resolved_path, visited, configs)?;
+            for resolved_path in expand_import(import_path, base_dir, diagnostics) {
+                Self::load_recursive(
+                    &resolved_path,
+                    visited,
+                    configs,
+                    diagnostics,
+                    total_size,
+                    limit,
+                )?;
+            }
         }
 
         // Add this config after its imports
@@ -257,14 +857,79 @@ impl PartialConfig {
         Ok(())
     }
 
-    /// Merge multiple partial configs together
+    /// Merge multiple partial configs together using first-wins semantics
+    /// (the default and the only behavior before [`MergeStrategy`] existed).
     ///
-    /// Uses first-wins semantics: the first occurrence of a field is kept.
     /// Conflicts (same field defined in multiple configs) are collected as errors
     /// but merging continues to find all conflicts at once (compiler-style error collection).
     ///
     /// Returns (merged, diagnostics) where diagnostics may contain warnings and errors
     pub fn merge<I>(configs: I) -> (Self, Vec<Diagnostic>)
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        Self::merge_with_strategy(configs, MergeStrategy::FirstWins)
+    }
+
+    /// Like [`merge`](Self::merge), but also returns a [`Provenance`]
+    /// recording which file supplied each field's winning value - see
+    /// [`merge_with_strategy_and_provenance`](Self::merge_with_strategy_and_provenance).
+    pub fn merge_with_provenance<I>(configs: I) -> (Self, Vec<Diagnostic>, Provenance)
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        Self::merge_with_strategy_and_provenance(configs, MergeStrategy::FirstWins)
+    }
+
+    /// Merge layered configs - `configs` ordered from lowest to highest
+    /// precedence, e.g. a shipped default file followed by a user override
+    /// file - under [`MergeStrategy::LastWins`].
+    ///
+    /// This is [`merge_with_strategy`](Self::merge_with_strategy) with the
+    /// strategy fixed to `LastWins`, for callers that just want "later files
+    /// override earlier ones" without spelling out the strategy themselves.
+    pub fn merge_layered<I>(configs: I) -> (Self, Vec<Diagnostic>)
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        Self::merge_with_strategy(configs, MergeStrategy::LastWins)
+    }
+
+    /// Merge multiple partial configs together under an explicit
+    /// [`MergeStrategy`].
+    ///
+    /// Under `MergeStrategy::FirstWins`, a field defined in more than one
+    /// config is an `Error::Merge` conflict and the first definition is
+    /// kept. Under `MergeStrategy::LastWins`, the last definition silently
+    /// wins and a `Warning::FieldOverridden` is reported instead.
+    ///
+    /// `logging.overrides` and `locations` are maps, and are merged key by
+    /// key rather than as whole sections: a key present in only one config
+    /// is unioned in untouched, and only a key defined in more than one
+    /// config applies the strategy above - so a global `logging.level` in
+    /// one file and a module-specific `logging.overrides` entry in another
+    /// compose cleanly instead of one clobbering the other.
+    ///
+    /// Returns (merged, diagnostics) where diagnostics may contain warnings and errors
+    pub fn merge_with_strategy<I>(configs: I, strategy: MergeStrategy) -> (Self, Vec<Diagnostic>)
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let (result, diagnostics, _provenance) =
+            Self::merge_with_strategy_and_provenance(configs, strategy);
+        (result, diagnostics)
+    }
+
+    /// Like [`merge_with_strategy`](Self::merge_with_strategy), but also
+    /// returns a [`Provenance`] mapping each dotted field path to the file
+    /// (and byte span within it) that supplied its winning value - built
+    /// from the same per-field location tracking `merge_with_strategy`
+    /// already does internally to report conflicts, just surfaced to the
+    /// caller instead of being discarded at the end of the merge.
+    pub fn merge_with_strategy_and_provenance<I>(
+        configs: I,
+        strategy: MergeStrategy,
+    ) -> (Self, Vec<Diagnostic>, Provenance)
     where
         I: IntoIterator<Item = Self>,
     {
@@ -275,10 +940,15 @@ impl PartialConfig {
         // Track which file set each field with span information (for first-wins)
         let mut logging_level_loc: Option<MergeConflictLocation> = None;
         let mut logging_overrides_locs: HashMap<String, MergeConflictLocation> = HashMap::new();
+        let mut logging_file_loc: Option<MergeConflictLocation> = None;
+        let mut logging_max_size_bytes_loc: Option<MergeConflictLocation> = None;
+        let mut logging_max_files_loc: Option<MergeConflictLocation> = None;
         let mut locations_default_loc: Option<MergeConflictLocation> = None;
         // Track field-level conflicts: location_key -> field_name -> conflict location
         let mut location_field_locs: HashMap<String, HashMap<String, MergeConflictLocation>> =
             HashMap::new();
+        let mut http_listen_loc: Option<MergeConflictLocation> = None;
+        let mut http_port_loc: Option<MergeConflictLocation> = None;
 
         for config in configs {
             // Collect all imports
@@ -312,34 +982,58 @@ impl PartialConfig {
                     result.logging = Some(PartialLoggingConfig {
                         level: None,
                         overrides: None,
+                        file: None,
+                        max_size_bytes: None,
+                        max_files: None,
                     });
                 }
 
                 let result_logging = result.logging.as_mut().unwrap();
 
-                // Check logging level conflict (first-wins)
-                if let Some(level_spanned) = logging.level {
-                    let conflict_loc = MergeConflictLocation {
-                        file_path: source_info.file_path.clone(),
-                        span: level_spanned.span(),
-                        content: source_info.content.clone(),
-                    };
-
-                    if let Some(prev_loc) = logging_level_loc.as_ref() {
-                        // Conflict: keep first value, record error
-                        diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                            field_path: "logging.level".to_string(),
-                            message: "Logging level defined in multiple config files".to_string(),
-                            conflicts: vec![prev_loc.clone(), conflict_loc],
-                        })));
-                    } else {
-                        // First occurrence: keep it
-                        result_logging.level = Some(level_spanned);
-                        logging_level_loc = Some(conflict_loc);
-                    }
-                }
+                merge_scalar_field(
+                    "logging.level",
+                    "Logging level defined in multiple config files",
+                    logging.level,
+                    &mut result_logging.level,
+                    &mut logging_level_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
+
+                merge_scalar_field(
+                    "logging.file",
+                    "Log file path defined in multiple config files",
+                    logging.file,
+                    &mut result_logging.file,
+                    &mut logging_file_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
+
+                merge_scalar_field(
+                    "logging.max_size_bytes",
+                    "Log file rotation size defined in multiple config files",
+                    logging.max_size_bytes,
+                    &mut result_logging.max_size_bytes,
+                    &mut logging_max_size_bytes_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
+
+                merge_scalar_field(
+                    "logging.max_files",
+                    "Log file rotation count defined in multiple config files",
+                    logging.max_files,
+                    &mut result_logging.max_files,
+                    &mut logging_max_files_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
 
-                // Check logging overrides conflicts (first-wins per key)
                 if let Some(overrides) = logging.overrides {
                     if result_logging.overrides.is_none() {
                         result_logging.overrides = Some(HashMap::new());
@@ -353,20 +1047,34 @@ impl PartialConfig {
                             content: source_info.content.clone(),
                         };
 
-                        if let Some(prev_loc) = logging_overrides_locs.get(&key) {
-                            // Conflict: keep first value, record error
-                            diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                                field_path: format!("logging.overrides.{}", key),
-                                message: format!(
-                                    "Logging override for '{}' defined in multiple config files",
-                                    key
-                                ),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
-                        } else {
-                            // First occurrence: keep it
-                            result_overrides.insert(key.clone(), value_spanned);
-                            logging_overrides_locs.insert(key, conflict_loc);
+                        match logging_overrides_locs.get(&key).cloned() {
+                            Some(prev_loc) => match strategy {
+                                MergeStrategy::FirstWins => {
+                                    diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
+                                        field_path: format!("logging.overrides.{}", key),
+                                        message: format!(
+                                            "Logging override for '{}' defined in multiple config files",
+                                            key
+                                        ),
+                                        conflicts: vec![prev_loc, conflict_loc],
+                                    })));
+                                }
+                                MergeStrategy::LastWins => {
+                                    diagnostics.push(Diagnostic::Warning(
+                                        Warning::FieldOverridden {
+                                            field_path: format!("logging.overrides.{}", key),
+                                            overridden: prev_loc,
+                                            winner: conflict_loc.clone(),
+                                        },
+                                    ));
+                                    result_overrides.insert(key.clone(), value_spanned);
+                                    logging_overrides_locs.insert(key, conflict_loc);
+                                }
+                            },
+                            None => {
+                                result_overrides.insert(key.clone(), value_spanned);
+                                logging_overrides_locs.insert(key, conflict_loc);
+                            }
                         }
                     }
                 }
@@ -383,28 +1091,16 @@ impl PartialConfig {
 
                 let result_locations = result.locations.as_mut().unwrap();
 
-                // Check default location conflict (first-wins)
-                if let Some(default_spanned) = locations.default {
-                    let conflict_loc = MergeConflictLocation {
-                        file_path: source_info.file_path.clone(),
-                        span: default_spanned.span(),
-                        content: source_info.content.clone(),
-                    };
-
-                    if let Some(prev_loc) = locations_default_loc.as_ref() {
-                        // Conflict: keep first value, record error
-                        diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                            field_path: "locations.default".to_string(),
-                            message: "Default location defined in multiple config files"
-                                .to_string(),
-                            conflicts: vec![prev_loc.clone(), conflict_loc],
-                        })));
-                    } else {
-                        // First occurrence: keep it
-                        result_locations.default = Some(default_spanned);
-                        locations_default_loc = Some(conflict_loc);
-                    }
-                }
+                merge_scalar_field(
+                    "locations.default",
+                    "Default location defined in multiple config files",
+                    locations.default,
+                    &mut result_locations.default,
+                    &mut locations_default_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
 
                 // Check location definitions conflicts (first-wins per field)
                 for (key, value) in locations.locations {
@@ -425,162 +1121,46 @@ impl PartialConfig {
                     let field_locs = location_field_locs.entry(key.clone()).or_default();
                     let result_location = result_locations.locations.get_mut(&key).unwrap();
 
-                    // Helper function to find field span in source
-                    let find_field_span =
-                        |field_name: &str, _field_value: &str| -> std::ops::Range<usize> {
-                            // Look for the field assignment line, e.g., "latitude = 59.9139"
-                            let search_pattern = format!("{} =", field_name);
-                            if let Some(start) = source_info.content.find(&search_pattern) {
-                                // Find the end of the line
-                                let line_end = source_info.content[start..]
-                                    .find('\n')
-                                    .map(|offset| start + offset)
-                                    .unwrap_or(source_info.content.len());
-                                start..line_end
-                            } else {
-                                0..0
-                            }
-                        };
-
-                    // Check and merge latitude
-                    if let Some(new_lat) = value.latitude {
-                        let field_name = "latitude";
-                        if let Some(prev_loc) = field_locs.get(field_name) {
-                            // Conflict: field already defined (first-wins)
-                            let span = find_field_span(field_name, &new_lat.to_string());
-                            let conflict_loc = MergeConflictLocation {
-                                file_path: source_info.file_path.clone(),
-                                span,
-                                content: source_info.content.clone(),
-                            };
-
-                            diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                                field_path: format!("locations.{}.{}", key, field_name),
-                                message: format!(
-                                    "Field '{}' for location '{}' defined in multiple config files",
-                                    field_name, key
-                                ),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
-                        } else {
-                            // First occurrence: keep it
-                            result_location.latitude = Some(new_lat);
-                            let span = find_field_span(field_name, &new_lat.to_string());
-                            field_locs.insert(
-                                field_name.to_string(),
-                                MergeConflictLocation {
-                                    file_path: source_info.file_path.clone(),
-                                    span,
-                                    content: source_info.content.clone(),
-                                },
-                            );
-                        }
-                    }
-
-                    // Check and merge longitude
-                    if let Some(new_lon) = value.longitude {
-                        let field_name = "longitude";
-                        if let Some(prev_loc) = field_locs.get(field_name) {
-                            // Conflict: field already defined (first-wins)
-                            let span = find_field_span(field_name, &new_lon.to_string());
-                            let conflict_loc = MergeConflictLocation {
-                                file_path: source_info.file_path.clone(),
-                                span,
-                                content: source_info.content.clone(),
-                            };
-
-                            diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                                field_path: format!("locations.{}.{}", key, field_name),
-                                message: format!(
-                                    "Field '{}' for location '{}' defined in multiple config files",
-                                    field_name, key
-                                ),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
-                        } else {
-                            // First occurrence: keep it
-                            result_location.longitude = Some(new_lon);
-                            let span = find_field_span(field_name, &new_lon.to_string());
-                            field_locs.insert(
-                                field_name.to_string(),
-                                MergeConflictLocation {
-                                    file_path: source_info.file_path.clone(),
-                                    span,
-                                    content: source_info.content.clone(),
-                                },
-                            );
-                        }
-                    }
-
-                    // Check and merge elevation_m
-                    if let Some(new_elev) = value.elevation_m {
-                        let field_name = "elevation_m";
-                        if let Some(prev_loc) = field_locs.get(field_name) {
-                            // Conflict: field already defined (first-wins)
-                            let span = find_field_span(field_name, &new_elev.to_string());
-                            let conflict_loc = MergeConflictLocation {
-                                file_path: source_info.file_path.clone(),
-                                span,
-                                content: source_info.content.clone(),
-                            };
-
-                            diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                                field_path: format!("locations.{}.{}", key, field_name),
-                                message: format!(
-                                    "Field '{}' for location '{}' defined in multiple config files",
-                                    field_name, key
-                                ),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
-                        } else {
-                            // First occurrence: keep it
-                            result_location.elevation_m = Some(new_elev);
-                            let span = find_field_span(field_name, &new_elev.to_string());
-                            field_locs.insert(
-                                field_name.to_string(),
-                                MergeConflictLocation {
-                                    file_path: source_info.file_path.clone(),
-                                    span,
-                                    content: source_info.content.clone(),
-                                },
-                            );
-                        }
-                    }
-
-                    // Check and merge timezone
-                    if let Some(ref new_tz) = value.timezone {
-                        let field_name = "timezone";
-                        if let Some(prev_loc) = field_locs.get(field_name) {
-                            // Conflict: field already defined (first-wins)
-                            let span = find_field_span(field_name, new_tz);
-                            let conflict_loc = MergeConflictLocation {
-                                file_path: source_info.file_path.clone(),
-                                span,
-                                content: source_info.content.clone(),
-                            };
-
-                            diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                                field_path: format!("locations.{}.{}", key, field_name),
-                                message: format!(
-                                    "Field '{}' for location '{}' defined in multiple config files",
-                                    field_name, key
-                                ),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
-                        } else {
-                            // First occurrence: keep it
-                            result_location.timezone = Some(new_tz.clone());
-                            let span = find_field_span(field_name, new_tz);
-                            field_locs.insert(
-                                field_name.to_string(),
-                                MergeConflictLocation {
-                                    file_path: source_info.file_path.clone(),
-                                    span,
-                                    content: source_info.content.clone(),
-                                },
-                            );
-                        }
-                    }
+                    merge_location_field(
+                        &key,
+                        "latitude",
+                        value.latitude,
+                        &mut result_location.latitude,
+                        field_locs,
+                        &source_info,
+                        strategy,
+                        &mut diagnostics,
+                    );
+                    merge_location_field(
+                        &key,
+                        "longitude",
+                        value.longitude,
+                        &mut result_location.longitude,
+                        field_locs,
+                        &source_info,
+                        strategy,
+                        &mut diagnostics,
+                    );
+                    merge_location_field(
+                        &key,
+                        "elevation_m",
+                        value.elevation_m,
+                        &mut result_location.elevation_m,
+                        field_locs,
+                        &source_info,
+                        strategy,
+                        &mut diagnostics,
+                    );
+                    merge_location_field(
+                        &key,
+                        "timezone",
+                        value.timezone,
+                        &mut result_location.timezone,
+                        field_locs,
+                        &source_info,
+                        strategy,
+                        &mut diagnostics,
+                    );
                 }
             }
 
@@ -595,51 +1175,27 @@ impl PartialConfig {
 
                 let result_http = result.http.as_mut().unwrap();
 
-                // Check http.listen conflict (first-wins)
-                if let Some(listen_spanned) = http.listen {
-                    let conflict_loc = MergeConflictLocation {
-                        file_path: source_info.file_path.clone(),
-                        span: listen_spanned.span(),
-                        content: source_info.content.clone(),
-                    };
-
-                    if let Some(prev_loc) =
-                        result_http.listen.as_ref().map(|_| conflict_loc.clone())
-                    {
-                        // Note: we need to track the first location separately
-                        // For now, just report conflict
-                        diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                            field_path: "http.listen".to_string(),
-                            message: "HTTP listen address defined in multiple config files"
-                                .to_string(),
-                            conflicts: vec![prev_loc, conflict_loc],
-                        })));
-                    } else {
-                        // First occurrence: keep it
-                        result_http.listen = Some(listen_spanned);
-                    }
-                }
-
-                // Check http.port conflict (first-wins)
-                if let Some(port_spanned) = http.port {
-                    let conflict_loc = MergeConflictLocation {
-                        file_path: source_info.file_path.clone(),
-                        span: port_spanned.span(),
-                        content: source_info.content.clone(),
-                    };
-
-                    if let Some(prev_loc) = result_http.port.as_ref().map(|_| conflict_loc.clone())
-                    {
-                        diagnostics.push(Diagnostic::Error(Error::Merge(MergeError {
-                            field_path: "http.port".to_string(),
-                            message: "HTTP port defined in multiple config files".to_string(),
-                            conflicts: vec![prev_loc, conflict_loc],
-                        })));
-                    } else {
-                        // First occurrence: keep it
-                        result_http.port = Some(port_spanned);
-                    }
-                }
+                merge_scalar_field(
+                    "http.listen",
+                    "HTTP listen address defined in multiple config files",
+                    http.listen,
+                    &mut result_http.listen,
+                    &mut http_listen_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
+
+                merge_scalar_field(
+                    "http.port",
+                    "HTTP port defined in multiple config files",
+                    http.port,
+                    &mut result_http.port,
+                    &mut http_port_loc,
+                    &source_info,
+                    strategy,
+                    &mut diagnostics,
+                );
             }
 
             // Merge integrations config (currently empty, but set up for future)
@@ -650,63 +1206,768 @@ impl PartialConfig {
 
         result.imports = imports;
 
-        (result, diagnostics)
-    }
-}
+        let mut provenance = HashMap::new();
+        let mut record = |field_path: String, loc: Option<MergeConflictLocation>| {
+            if let Some(loc) = loc {
+                provenance.insert(
+                    field_path,
+                    FieldProvenance {
+                        file_path: loc.file_path,
+                        span: loc.span,
+                    },
+                );
+            }
+        };
+        record("logging.level".to_string(), logging_level_loc);
+        record("logging.file".to_string(), logging_file_loc);
+        record(
+            "logging.max_size_bytes".to_string(),
+            logging_max_size_bytes_loc,
+        );
+        record("logging.max_files".to_string(), logging_max_files_loc);
+        for (key, loc) in logging_overrides_locs {
+            record(format!("logging.overrides.{}", key), Some(loc));
+        }
+        record("locations.default".to_string(), locations_default_loc);
+        for (location_key, field_locs) in location_field_locs {
+            for (field_name, loc) in field_locs {
+                record(
+                    format!("locations.{}.{}", location_key, field_name),
+                    Some(loc),
+                );
+            }
+        }
+        record("http.listen".to_string(), http_listen_loc);
+        record("http.port".to_string(), http_port_loc);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use std::io::Write;
+        (result, diagnostics, Provenance(provenance))
+    }
 
-    #[test]
-    fn test_partial_config_from_file() {
-        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file");
-        fs::create_dir_all(&temp_dir).unwrap();
+    /// Apply environment-variable overrides on top of an already-merged
+    /// config, following cargo's `CARGO_*` model: unlike `merge`'s
+    /// first-wins file semantics, an env var always wins over whatever the
+    /// files set. Each applied override pushes an informational
+    /// `Diagnostic::Info` naming the variable and the field path it set, so
+    /// users can see why a value differs from their files. A malformed
+    /// value (e.g. a non-numeric port) is a `ValidationError` naming the
+    /// variable as its source instead of a file span.
+    pub fn apply_env_overrides(&mut self, diagnostics: &mut Vec<Diagnostic>) {
+        if let Ok(raw) = std::env::var("HEARTHD_LOGGING_LEVEL") {
+            self.apply_env_logging_level(&raw, diagnostics);
+        }
 
-        let config_path = temp_dir.join("test.toml");
-        let mut config_file = fs::File::create(&config_path).unwrap();
-        write!(
-            config_file,
-            r#"
-[logging]
-level = "debug"
+        if let Ok(raw) = std::env::var("HEARTHD_HTTP_LISTEN") {
+            if self.http.is_none() {
+                self.http = Some(PartialHttpConfig {
+                    listen: None,
+                    port: None,
+                });
+            }
+            self.http.as_mut().unwrap().listen = Some(toml::Spanned::new(0..0, raw));
+            diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                var_name: "HEARTHD_HTTP_LISTEN".to_string(),
+                field_path: "http.listen".to_string(),
+            }));
+        }
 
-[locations.home]
-latitude = 59.9139
-longitude = 10.7522
-"#
-        )
-        .unwrap();
+        if let Ok(raw) = std::env::var("HEARTHD_HTTP_PORT") {
+            self.apply_env_http_port(&raw, diagnostics);
+        }
 
-        let partial = PartialConfig::from_file(&config_path).unwrap();
-        assert!(partial.logging.is_some());
-        assert!(partial.locations.is_some());
-        assert!(partial.source.is_some());
+        if let Ok(raw) = std::env::var("HEARTHD_LOCATIONS_DEFAULT") {
+            if self.locations.is_none() {
+                self.locations = Some(PartialLocationsConfig {
+                    default: None,
+                    locations: HashMap::new(),
+                });
+            }
+            self.locations.as_mut().unwrap().default = Some(toml::Spanned::new(0..0, raw));
+            diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                var_name: "HEARTHD_LOCATIONS_DEFAULT".to_string(),
+                field_path: "locations.default".to_string(),
+            }));
+        }
+    }
 
-        let source = partial.source.unwrap();
-        assert_eq!(source.file_path, config_path);
-        assert!(source.content.contains("debug"));
+    fn apply_env_logging_level(&mut self, raw: &str, diagnostics: &mut Vec<Diagnostic>) {
+        let level = match parse_log_level(raw) {
+            Ok(level) => level,
+            Err(()) => {
+                diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
+                    field_path: "logging.level".to_string(),
+                    message: format!(
+                        "HEARTHD_LOGGING_LEVEL value '{}' is not one of trace, debug, info, warn, error",
+                        raw
+                    ),
+                    span: None,
+                    source: Some(env_source("HEARTHD_LOGGING_LEVEL")),
+                })));
+                return;
+            }
+        };
 
-        fs::remove_dir_all(&temp_dir).ok();
+        if self.logging.is_none() {
+            self.logging = Some(PartialLoggingConfig {
+                level: None,
+                overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            });
+        }
+        self.logging.as_mut().unwrap().level = Some(toml::Spanned::new(0..0, level));
+        diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+            var_name: "HEARTHD_LOGGING_LEVEL".to_string(),
+            field_path: "logging.level".to_string(),
+        }));
     }
 
-    #[test]
-    fn test_partial_config_from_file_not_found() {
-        let result = PartialConfig::from_file(Path::new("/nonexistent/config.toml"));
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            LoadError::Io { .. } => {}
-            _ => panic!("Expected Io error"),
+    fn apply_env_http_port(&mut self, raw: &str, diagnostics: &mut Vec<Diagnostic>) {
+        let port: u16 = match raw.parse() {
+            Ok(port) => port,
+            Err(e) => {
+                diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
+                    field_path: "http.port".to_string(),
+                    message: format!(
+                        "HEARTHD_HTTP_PORT value '{}' is not a valid port: {}",
+                        raw, e
+                    ),
+                    span: None,
+                    source: Some(env_source("HEARTHD_HTTP_PORT")),
+                })));
+                return;
+            }
+        };
+
+        if self.http.is_none() {
+            self.http = Some(PartialHttpConfig {
+                listen: None,
+                port: None,
+            });
         }
+        self.http.as_mut().unwrap().port = Some(toml::Spanned::new(0..0, port));
+        diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+            var_name: "HEARTHD_HTTP_PORT".to_string(),
+            field_path: "http.port".to_string(),
+        }));
     }
 
-    #[test]
-    fn test_partial_config_from_file_parse_error() {
-        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_parse_error");
-        fs::create_dir_all(&temp_dir).unwrap();
-
+    /// Apply `env` (built by [`Self::from_env`]) on top of `self` as the
+    /// highest-precedence layer: every field `env` sets overwrites the
+    /// corresponding field in `self`, regardless of whether a file already
+    /// set it, and pushes an informational `Diagnostic::Info` naming the
+    /// `{prefix}_*` variable and the field path it set - so a field an env
+    /// var overrides is reported the same way as one no file touched at
+    /// all, rather than as a `merge`-style same-field conflict.
+    pub fn apply_env_layer(&mut self, env: Self, prefix: &str, diagnostics: &mut Vec<Diagnostic>) {
+        if let Some(env_logging) = env.logging {
+            if let Some(level) = env_logging.level {
+                self.logging
+                    .get_or_insert_with(|| PartialLoggingConfig {
+                        level: None,
+                        overrides: None,
+                        file: None,
+                        max_size_bytes: None,
+                        max_files: None,
+                    })
+                    .level = Some(level);
+                diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                    var_name: format!("{}_LOGGING_LEVEL", prefix),
+                    field_path: "logging.level".to_string(),
+                }));
+            }
+
+            if let Some(env_overrides) = env_logging.overrides {
+                let result_logging = self.logging.get_or_insert_with(|| PartialLoggingConfig {
+                    level: None,
+                    overrides: None,
+                    file: None,
+                    max_size_bytes: None,
+                    max_files: None,
+                });
+                let result_overrides = result_logging.overrides.get_or_insert_with(HashMap::new);
+
+                for (target, level) in env_overrides {
+                    let var_name = format!(
+                        "{}_LOGGING_OVERRIDES_{}",
+                        prefix,
+                        target.replace("::", "__")
+                    );
+                    result_overrides.insert(target.clone(), level);
+                    diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                        var_name,
+                        field_path: format!("logging.overrides.{}", target),
+                    }));
+                }
+            }
+        }
+
+        if let Some(env_http) = env.http {
+            let result_http = self.http.get_or_insert_with(|| PartialHttpConfig {
+                listen: None,
+                port: None,
+            });
+
+            if let Some(listen) = env_http.listen {
+                result_http.listen = Some(listen);
+                diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                    var_name: format!("{}_HTTP_LISTEN", prefix),
+                    field_path: "http.listen".to_string(),
+                }));
+            }
+            if let Some(port) = env_http.port {
+                result_http.port = Some(port);
+                diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                    var_name: format!("{}_HTTP_PORT", prefix),
+                    field_path: "http.port".to_string(),
+                }));
+            }
+        }
+
+        if let Some(env_locations) = env.locations {
+            let result_locations = self
+                .locations
+                .get_or_insert_with(|| PartialLocationsConfig {
+                    default: None,
+                    locations: HashMap::new(),
+                });
+
+            if let Some(default) = env_locations.default {
+                result_locations.default = Some(default);
+                diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                    var_name: format!("{}_LOCATIONS_DEFAULT", prefix),
+                    field_path: "locations.default".to_string(),
+                }));
+            }
+
+            for (name, env_location) in env_locations.locations {
+                let result_location =
+                    result_locations
+                        .locations
+                        .entry(name.clone())
+                        .or_insert(PartialLocation {
+                            latitude: None,
+                            longitude: None,
+                            elevation_m: None,
+                            timezone: None,
+                        });
+                let name_upper = name.to_uppercase();
+
+                if let Some(latitude) = env_location.latitude {
+                    result_location.latitude = Some(latitude);
+                    diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                        var_name: format!("{}_LOCATIONS_{}_LATITUDE", prefix, name_upper),
+                        field_path: format!("locations.{}.latitude", name),
+                    }));
+                }
+                if let Some(longitude) = env_location.longitude {
+                    result_location.longitude = Some(longitude);
+                    diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                        var_name: format!("{}_LOCATIONS_{}_LONGITUDE", prefix, name_upper),
+                        field_path: format!("locations.{}.longitude", name),
+                    }));
+                }
+                if let Some(elevation_m) = env_location.elevation_m {
+                    result_location.elevation_m = Some(elevation_m);
+                    diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                        var_name: format!("{}_LOCATIONS_{}_ELEVATION_M", prefix, name_upper),
+                        field_path: format!("locations.{}.elevation_m", name),
+                    }));
+                }
+                if let Some(timezone) = env_location.timezone {
+                    result_location.timezone = Some(timezone);
+                    diagnostics.push(Diagnostic::Info(Info::EnvOverride {
+                        var_name: format!("{}_LOCATIONS_{}_TIMEZONE", prefix, name_upper),
+                        field_path: format!("locations.{}.timezone", name),
+                    }));
+                }
+            }
+        }
+    }
+
+    /// Build a standalone `PartialConfig` from `{prefix}_*` environment
+    /// variables - `{prefix}_LOGGING_LEVEL`, `{prefix}_LOGGING_OVERRIDES_<target>`
+    /// (`<target>` has its `::` written as `__`, e.g.
+    /// `HEARTHD_LOGGING_OVERRIDES_hearthd__api=trace` sets the
+    /// `hearthd::api` override), `{prefix}_HTTP_LISTEN`, `{prefix}_HTTP_PORT`,
+    /// `{prefix}_LOCATIONS_DEFAULT`, and `{prefix}_LOCATIONS_<NAME>_<FIELD>`
+    /// for any location name.
+    ///
+    /// Unlike `apply_env_overrides` (which patches a handful of fixed
+    /// variables onto an already-built config), this returns a config of
+    /// its own meant to be layered on top of a file-merged config via
+    /// [`Self::apply_env_layer`] - see [`super::config::Config::from_files_with_env`].
+    /// Env values have no file or span, so diagnostics name the variable via
+    /// `env_source` instead.
+    pub fn from_env(prefix: &str) -> (Self, Vec<Diagnostic>) {
+        let mut config = PartialConfig::default();
+        let mut diagnostics = Vec::new();
+
+        let logging_level_var = format!("{}_LOGGING_LEVEL", prefix);
+        let logging_overrides_prefix = format!("{}_LOGGING_OVERRIDES_", prefix);
+        let http_listen_var = format!("{}_HTTP_LISTEN", prefix);
+        let http_port_var = format!("{}_HTTP_PORT", prefix);
+        let locations_default_var = format!("{}_LOCATIONS_DEFAULT", prefix);
+        let locations_prefix = format!("{}_LOCATIONS_", prefix);
+
+        for (name, raw) in std::env::vars() {
+            if name == logging_level_var {
+                match parse_log_level(&raw) {
+                    Ok(level) => {
+                        config
+                            .logging
+                            .get_or_insert_with(|| PartialLoggingConfig {
+                                level: None,
+                                overrides: None,
+                                file: None,
+                                max_size_bytes: None,
+                                max_files: None,
+                            })
+                            .level = Some(toml::Spanned::new(0..0, level));
+                    }
+                    Err(()) => diagnostics.push(env_parse_error(
+                        "logging.level",
+                        &name,
+                        &raw,
+                        "not one of trace, debug, info, warn, error",
+                    )),
+                }
+            } else if name == http_listen_var {
+                config
+                    .http
+                    .get_or_insert_with(|| PartialHttpConfig {
+                        listen: None,
+                        port: None,
+                    })
+                    .listen = Some(toml::Spanned::new(0..0, raw));
+            } else if name == http_port_var {
+                match raw.parse::<u16>() {
+                    Ok(port) => {
+                        config
+                            .http
+                            .get_or_insert_with(|| PartialHttpConfig {
+                                listen: None,
+                                port: None,
+                            })
+                            .port = Some(toml::Spanned::new(0..0, port));
+                    }
+                    Err(e) => {
+                        diagnostics.push(env_parse_error("http.port", &name, &raw, &e.to_string()))
+                    }
+                }
+            } else if name == locations_default_var {
+                config
+                    .locations
+                    .get_or_insert_with(|| PartialLocationsConfig {
+                        default: None,
+                        locations: HashMap::new(),
+                    })
+                    .default = Some(toml::Spanned::new(0..0, raw));
+            } else if let Some(rest) = name.strip_prefix(&logging_overrides_prefix) {
+                // `__` is the nested-path separator used elsewhere in this
+                // crate's env var naming (see `hearthd_config::env`), chosen
+                // since logger target names already contain `::`.
+                let target = rest.replace("__", "::");
+                match parse_log_level(&raw) {
+                    Ok(level) => {
+                        config
+                            .logging
+                            .get_or_insert_with(|| PartialLoggingConfig {
+                                level: None,
+                                overrides: None,
+                                file: None,
+                                max_size_bytes: None,
+                                max_files: None,
+                            })
+                            .overrides
+                            .get_or_insert_with(HashMap::new)
+                            .insert(target, toml::Spanned::new(0..0, level));
+                    }
+                    Err(()) => diagnostics.push(env_parse_error(
+                        &format!("logging.overrides.{}", target),
+                        &name,
+                        &raw,
+                        "not one of trace, debug, info, warn, error",
+                    )),
+                }
+            } else if let Some(rest) = name.strip_prefix(&locations_prefix) {
+                Self::apply_env_location_field(&mut config, &name, rest, &raw, &mut diagnostics);
+            }
+        }
+
+        (config, diagnostics)
+    }
+
+    /// Parse `{prefix}_LOCATIONS_<NAME>_<FIELD>` (`rest` is everything after
+    /// `{prefix}_LOCATIONS_`) into the matching field of named location
+    /// `<NAME>`, e.g. `HEARTHD_LOCATIONS_HOME_LATITUDE` sets
+    /// `locations.home.latitude`. `<NAME>` is lowercased to match how
+    /// location keys are written in TOML. Unrecognized suffixes are ignored,
+    /// since `rest` may belong to a variable outside our naming scheme.
+    fn apply_env_location_field(
+        config: &mut Self,
+        var_name: &str,
+        rest: &str,
+        raw: &str,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) {
+        const FIELDS: &[&str] = &["LATITUDE", "LONGITUDE", "ELEVATION_M", "TIMEZONE"];
+
+        let Some((location_name, field)) = FIELDS.iter().find_map(|field| {
+            rest.strip_suffix(&format!("_{}", field))
+                .map(|name| (name.to_lowercase(), *field))
+        }) else {
+            return;
+        };
+
+        let location = config
+            .locations
+            .get_or_insert_with(|| PartialLocationsConfig {
+                default: None,
+                locations: HashMap::new(),
+            })
+            .locations
+            .entry(location_name.clone())
+            .or_insert(PartialLocation {
+                latitude: None,
+                longitude: None,
+                elevation_m: None,
+                timezone: None,
+            });
+
+        let field_path = format!("locations.{}.{}", location_name, field.to_lowercase());
+        match field {
+            "TIMEZONE" => location.timezone = Some(toml::Spanned::new(0..0, raw.to_string())),
+            "LATITUDE" | "LONGITUDE" | "ELEVATION_M" => match raw.parse::<f64>() {
+                Ok(value) => {
+                    let spanned = Some(toml::Spanned::new(0..0, value));
+                    match field {
+                        "LATITUDE" => location.latitude = spanned,
+                        "LONGITUDE" => location.longitude = spanned,
+                        "ELEVATION_M" => location.elevation_m = spanned,
+                        _ => unreachable!(),
+                    }
+                }
+                Err(e) => diagnostics.push(env_parse_error(
+                    &field_path,
+                    var_name,
+                    raw,
+                    &format!("not a valid number: {}", e),
+                )),
+            },
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// A `SourceInfo` standing in for a `ValidationError`'s usual file span when
+/// the offending value actually came from an environment variable.
+/// Expand one `imports` entry, resolved against `base_dir` (the importing
+/// file's directory), into the concrete file paths it names:
+/// - a glob pattern in the final path component (e.g. `conf.d/*.toml`)
+///   expands to every matching file in that directory;
+/// - a directory (e.g. `conf.d` or `conf.d/`) expands to every file
+///   directly inside it - the standard `conf.d` drop-in pattern;
+/// - anything else resolves to that single literal path, whether or not it
+///   exists (a missing literal import still surfaces its usual `LoadError`
+///   from `from_file`).
+///
+/// Both glob and directory expansion sort matches by path for deterministic
+/// ordering, since that ordering feeds directly into `merge`'s first-wins
+/// precedence. Either form matching zero files pushes a non-fatal
+/// `Diagnostic::Warning` instead of failing the load.
+fn expand_import(
+    import_path: &str,
+    base_dir: &Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Vec<PathBuf> {
+    let import_path_buf = PathBuf::from(import_path);
+    let resolved = if import_path_buf.is_absolute() {
+        import_path_buf
+    } else {
+        base_dir.join(import_path_buf)
+    };
+
+    let is_glob = import_path.contains(['*', '?', '[']);
+
+    if is_glob {
+        let dir = resolved.parent().unwrap_or_else(|| Path::new("."));
+        let pattern = resolved.file_name().and_then(|f| f.to_str()).unwrap_or("");
+
+        let mut matches: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|f| f.to_str())
+                    .is_some_and(|name| glob_match(pattern, name))
+            })
+            .collect();
+        matches.sort();
+
+        if matches.is_empty() {
+            diagnostics.push(Diagnostic::Warning(Warning::EmptyImport {
+                pattern: resolved,
+            }));
+        }
+        return matches;
+    }
+
+    if resolved.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(&resolved)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        entries.sort();
+
+        if entries.is_empty() {
+            diagnostics.push(Diagnostic::Warning(Warning::EmptyImport {
+                pattern: resolved,
+            }));
+        }
+        return entries;
+    }
+
+    vec![resolved]
+}
+
+/// A minimal shell-style glob matcher supporting `*` (any run of
+/// characters) and `?` (any single character) - enough for `conf.d/*.toml`
+/// style import patterns without pulling in a full glob crate.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    fn match_bytes(pattern: &[u8], name: &[u8]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                match_bytes(&pattern[1..], name)
+                    || (!name.is_empty() && match_bytes(pattern, &name[1..]))
+            }
+            (Some(b'?'), Some(_)) => match_bytes(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => match_bytes(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+
+    match_bytes(pattern.as_bytes(), name.as_bytes())
+}
+
+fn env_source(var_name: &str) -> SourceInfo {
+    SourceInfo {
+        file_path: PathBuf::from(format!("env:{}", var_name)),
+        content: String::new(),
+    }
+}
+
+/// Parse a log level the same case-insensitive way whether it came from a
+/// config file's `level` field or an environment variable.
+fn parse_log_level(raw: &str) -> Result<LogLevel, ()> {
+    match raw.to_lowercase().as_str() {
+        "trace" => Ok(LogLevel::Trace),
+        "debug" => Ok(LogLevel::Debug),
+        "info" => Ok(LogLevel::Info),
+        "warn" => Ok(LogLevel::Warn),
+        "error" => Ok(LogLevel::Error),
+        _ => Err(()),
+    }
+}
+
+/// A `Diagnostic::Error(Error::Validation(..))` naming an environment
+/// variable, rather than a file span, as the source of a bad value.
+fn env_parse_error(field_path: &str, var_name: &str, raw: &str, reason: &str) -> Diagnostic {
+    Diagnostic::Error(Error::Validation(ValidationError {
+        field_path: field_path.to_string(),
+        message: format!("{} value '{}' is {}", var_name, raw, reason),
+        span: None,
+        source: Some(env_source(var_name)),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+
+    #[test]
+    fn test_partial_config_from_file() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.toml");
+        let mut config_file = fs::File::create(&config_path).unwrap();
+        write!(
+            config_file,
+            r#"
+[logging]
+level = "debug"
+
+[locations.home]
+latitude = 59.9139
+longitude = 10.7522
+"#
+        )
+        .unwrap();
+
+        let partial = PartialConfig::from_file(&config_path).unwrap();
+        assert!(partial.logging.is_some());
+        assert!(partial.locations.is_some());
+        assert!(partial.source.is_some());
+
+        let source = partial.source.unwrap();
+        assert_eq!(source.file_path, config_path);
+        assert!(source.content.contains("debug"));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_partial_config_from_file_not_found() {
+        let result = PartialConfig::from_file(Path::new("/nonexistent/config.toml"));
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            LoadError::Io { .. } => {}
+            _ => panic!("Expected Io error"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn from_file_parses_json_by_extension_with_span_less_locations() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_json");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.json");
+        let mut config_file = fs::File::create(&config_path).unwrap();
+        write!(
+            config_file,
+            r#"{{
+                "logging": {{ "level": "debug" }},
+                "http": {{ "port": 9443 }}
+            }}"#
+        )
+        .unwrap();
+
+        let partial = PartialConfig::from_file(&config_path).unwrap();
+        assert_eq!(
+            *partial.logging.unwrap().level.unwrap().get_ref(),
+            LogLevel::Debug
+        );
+        let port = partial.http.unwrap().port.unwrap();
+        assert_eq!(*port.get_ref(), 9443);
+        assert_eq!(port.span(), 0..0);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    #[cfg(not(feature = "json"))]
+    fn from_file_reports_unsupported_format_for_json_without_the_feature() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_json_disabled");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.json");
+        fs::write(&config_path, r#"{"http": {"port": 9443}}"#).unwrap();
+
+        let result = PartialConfig::from_file(&config_path);
+        assert!(matches!(result, Err(LoadError::UnsupportedFormat { .. })));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    #[cfg(feature = "yaml")]
+    fn from_file_parses_yaml_by_extension() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_yaml");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.yaml");
+        let mut config_file = fs::File::create(&config_path).unwrap();
+        write!(
+            config_file,
+            "logging:\n  level: debug\nlocations:\n  home:\n    latitude: 59.9139\n    longitude: 10.7522\n"
+        )
+        .unwrap();
+
+        let partial = PartialConfig::from_file(&config_path).unwrap();
+        assert_eq!(
+            *partial.logging.unwrap().level.unwrap().get_ref(),
+            LogLevel::Debug
+        );
+        let home = partial.locations.unwrap().locations.remove("home").unwrap();
+        assert_eq!(*home.latitude.unwrap().get_ref(), 59.9139);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    #[cfg(feature = "ron")]
+    fn from_file_parses_ron_by_extension() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_ron");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.ron");
+        let mut config_file = fs::File::create(&config_path).unwrap();
+        write!(
+            config_file,
+            r#"(logging: (level: "debug"), http: (port: 9443))"#
+        )
+        .unwrap();
+
+        let partial = PartialConfig::from_file(&config_path).unwrap();
+        assert_eq!(
+            *partial.logging.unwrap().level.unwrap().get_ref(),
+            LogLevel::Debug
+        );
+        assert_eq!(*partial.http.unwrap().port.unwrap().get_ref(), 9443);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    #[cfg(not(feature = "ron"))]
+    fn from_file_reports_unsupported_format_for_ron_without_the_feature() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_ron_disabled");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.ron");
+        fs::write(&config_path, r#"(http: (port: 9443))"#).unwrap();
+
+        let result = PartialConfig::from_file(&config_path);
+        assert!(matches!(result, Err(LoadError::UnsupportedFormat { .. })));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn from_file_reports_an_unknown_extension_naming_it() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_from_file_unknown_ext");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("test.ini");
+        fs::write(&config_path, "level=debug").unwrap();
+
+        let result = PartialConfig::from_file(&config_path);
+        match result {
+            Err(LoadError::UnknownExtension { extension, .. }) => {
+                assert_eq!(extension, "ini");
+            }
+            other => panic!("expected UnknownExtension, got {:?}", other),
+        }
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_partial_config_from_file_parse_error() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_partial_parse_error");
+        fs::create_dir_all(&temp_dir).unwrap();
+
         let config_path = temp_dir.join("invalid.toml");
         let mut config_file = fs::File::create(&config_path).unwrap();
         write!(config_file, "invalid toml ][").unwrap();
@@ -737,7 +1998,8 @@ level = "info"
         )
         .unwrap();
 
-        let configs = PartialConfig::load_with_imports(&[config_path]).unwrap();
+        let (configs, diagnostics) = PartialConfig::load_with_imports(&[config_path]).unwrap();
+        assert!(diagnostics.is_empty());
         assert_eq!(configs.len(), 1);
         assert!(configs[0].logging.is_some());
 
@@ -772,10 +2034,38 @@ longitude = 10.7522
         )
         .unwrap();
 
-        let configs =
+        let (configs, diagnostics) =
             PartialConfig::load_with_imports(&[base_path.clone(), extra_path.clone()]).unwrap();
+        assert!(diagnostics.is_empty());
+        assert_eq!(configs.len(), 2);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_load_with_imports_resolves_an_import_by_its_own_extension() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_load_imports_json");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let extra_path = temp_dir.join("extra.json");
+        fs::write(&extra_path, r#"{"http": {"port": 9443}}"#).unwrap();
+
+        let main_path = temp_dir.join("main.toml");
+        fs::write(
+            &main_path,
+            "imports = [\"extra.json\"]\n[logging]\nlevel = \"info\"\n",
+        )
+        .unwrap();
+
+        let (configs, diagnostics) =
+            PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
+        assert!(diagnostics.is_empty());
         assert_eq!(configs.len(), 2);
 
+        let imported_port = configs[0].http.as_ref().unwrap().port.as_ref().unwrap();
+        assert_eq!(*imported_port.get_ref(), 9443);
+
         fs::remove_dir_all(&temp_dir).ok();
     }
 
@@ -809,7 +2099,9 @@ longitude = 10.7522
         )
         .unwrap();
 
-        let configs = PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
+        let (configs, diagnostics) =
+            PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
+        assert!(diagnostics.is_empty());
         // Should have 2 configs: base (loaded first) and main
         assert_eq!(configs.len(), 2);
         assert!(configs[0].logging.is_some()); // base
@@ -859,30 +2151,120 @@ imports = ["a.toml"]
     }
 
     #[test]
-    fn test_merge_empty_configs() {
-        let configs = vec![];
-        let (result, diagnostics) = PartialConfig::merge(configs);
+    fn glob_import_expands_to_every_matching_file_in_sorted_order() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_load_glob_import");
+        let conf_d = temp_dir.join("conf.d");
+        fs::create_dir_all(&conf_d).unwrap();
 
-        assert!(result.logging.is_none());
-        assert!(result.locations.is_none());
-        assert_eq!(diagnostics.len(), 0);
-    }
+        fs::write(conf_d.join("20-b.toml"), "[logging]\nlevel = \"warn\"\n").unwrap();
+        fs::write(conf_d.join("10-a.toml"), "[logging]\nlevel = \"info\"\n").unwrap();
+        fs::write(conf_d.join("readme.txt"), "not a config").unwrap();
 
-    #[test]
-    fn test_merge_single_config() {
-        let config = PartialConfig {
-            logging: Some(PartialLoggingConfig {
-                level: Some(toml::Spanned::new(0..4, LogLevel::Info)),
-                overrides: None,
-            }),
-            ..Default::default()
-        };
+        let main_path = temp_dir.join("main.toml");
+        fs::write(&main_path, "imports = [\"conf.d/*.toml\"]\n").unwrap();
 
-        let (result, diagnostics) = PartialConfig::merge(vec![config]);
+        let (configs, diagnostics) =
+            PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
 
-        assert!(result.logging.is_some());
-        assert_eq!(diagnostics.len(), 0);
-    }
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        // 10-a.toml, then 20-b.toml (sorted), then main itself.
+        assert_eq!(configs.len(), 3);
+        assert_eq!(
+            *configs[0]
+                .logging
+                .as_ref()
+                .unwrap()
+                .level
+                .as_ref()
+                .unwrap()
+                .get_ref(),
+            LogLevel::Info
+        );
+        assert_eq!(
+            *configs[1]
+                .logging
+                .as_ref()
+                .unwrap()
+                .level
+                .as_ref()
+                .unwrap()
+                .get_ref(),
+            LogLevel::Warn
+        );
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn directory_import_expands_to_every_file_in_it() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_load_directory_import");
+        let conf_d = temp_dir.join("conf.d");
+        fs::create_dir_all(&conf_d).unwrap();
+
+        fs::write(conf_d.join("a.toml"), "[logging]\nlevel = \"debug\"\n").unwrap();
+
+        let main_path = temp_dir.join("main.toml");
+        fs::write(&main_path, "imports = [\"conf.d\"]\n").unwrap();
+
+        let (configs, diagnostics) =
+            PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(configs.len(), 2);
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn an_empty_glob_import_is_a_warning_not_an_error() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_load_empty_glob_import");
+        let conf_d = temp_dir.join("conf.d");
+        fs::create_dir_all(&conf_d).unwrap();
+
+        let main_path = temp_dir.join("main.toml");
+        fs::write(&main_path, "imports = [\"conf.d/*.toml\"]\n").unwrap();
+
+        let (configs, diagnostics) =
+            PartialConfig::load_with_imports(std::slice::from_ref(&main_path)).unwrap();
+
+        assert_eq!(configs.len(), 1, "only main.toml loaded: {:?}", configs);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            Diagnostic::Warning(Warning::EmptyImport { .. })
+        ));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn test_merge_empty_configs() {
+        let configs = vec![];
+        let (result, diagnostics) = PartialConfig::merge(configs);
+
+        assert!(result.logging.is_none());
+        assert!(result.locations.is_none());
+        assert_eq!(diagnostics.len(), 0);
+    }
+
+    #[test]
+    fn test_merge_single_config() {
+        let config = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: Some(toml::Spanned::new(0..4, LogLevel::Info)),
+                overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge(vec![config]);
+
+        assert!(result.logging.is_some());
+        assert_eq!(diagnostics.len(), 0);
+    }
 
     #[test]
     fn test_merge_non_overlapping_configs() {
@@ -890,6 +2272,9 @@ imports = ["a.toml"]
             logging: Some(PartialLoggingConfig {
                 level: Some(toml::Spanned::new(0..4, LogLevel::Info)),
                 overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
             }),
             source: Some(SourceInfo {
                 file_path: PathBuf::from("config1.toml"),
@@ -906,8 +2291,8 @@ imports = ["a.toml"]
                     map.insert(
                         "home".to_string(),
                         PartialLocation {
-                            latitude: Some(59.9139),
-                            longitude: Some(10.7522),
+                            latitude: Some(toml::Spanned::new(20..27, 59.9139)),
+                            longitude: Some(toml::Spanned::new(40..47, 10.7522)),
                             elevation_m: None,
                             timezone: None,
                         },
@@ -942,6 +2327,9 @@ level = "debug"
             logging: Some(PartialLoggingConfig {
                 level: Some(toml::Spanned::new(10..24, LogLevel::Info)),
                 overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
             }),
             source: Some(SourceInfo {
                 file_path: PathBuf::from("/tmp/config1.toml"),
@@ -954,6 +2342,9 @@ level = "debug"
             logging: Some(PartialLoggingConfig {
                 level: Some(toml::Spanned::new(10..25, LogLevel::Debug)),
                 overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
             }),
             source: Some(SourceInfo {
                 file_path: PathBuf::from("/tmp/config2.toml"),
@@ -1011,8 +2402,8 @@ longitude = 11.0
                     map.insert(
                         "home".to_string(),
                         PartialLocation {
-                            latitude: Some(59.9139),
-                            longitude: Some(10.7522),
+                            latitude: Some(toml::Spanned::new(18..25, 59.9139)),
+                            longitude: Some(toml::Spanned::new(37..44, 10.7522)),
                             elevation_m: None,
                             timezone: None,
                         },
@@ -1035,8 +2426,8 @@ longitude = 11.0
                     map.insert(
                         "home".to_string(),
                         PartialLocation {
-                            latitude: Some(60.0),
-                            longitude: Some(11.0),
+                            latitude: Some(toml::Spanned::new(18..22, 60.0)),
+                            longitude: Some(toml::Spanned::new(32..36, 11.0)),
                             elevation_m: None,
                             timezone: None,
                         },
@@ -1056,8 +2447,8 @@ longitude = 11.0
         // First-wins: should keep first location's fields
         let locations = result.locations.unwrap();
         let home = locations.locations.get("home").unwrap();
-        assert_eq!(home.latitude.unwrap(), 59.9139);
-        assert_eq!(home.longitude.unwrap(), 10.7522);
+        assert_eq!(*home.latitude.as_ref().unwrap().get_ref(), 59.9139);
+        assert_eq!(*home.longitude.as_ref().unwrap().get_ref(), 10.7522);
 
         // Should have 2 error diagnostics (one for latitude, one for longitude)
         assert_eq!(diagnostics.len(), 2);
@@ -1078,6 +2469,9 @@ default = "home"
             logging: Some(PartialLoggingConfig {
                 level: Some(toml::Spanned::new(10..24, LogLevel::Info)),
                 overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
             }),
             locations: Some(PartialLocationsConfig {
                 default: Some(toml::Spanned::new(50..54, "home".to_string())),
@@ -1094,6 +2488,9 @@ default = "home"
             logging: Some(PartialLoggingConfig {
                 level: Some(toml::Spanned::new(10..25, LogLevel::Debug)),
                 overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
             }),
             locations: Some(PartialLocationsConfig {
                 default: Some(toml::Spanned::new(50..54, "work".to_string())),
@@ -1112,4 +2509,649 @@ default = "home"
         assert_eq!(diagnostics.len(), 2);
         assert!(diagnostics.iter().all(|d| d.is_error()));
     }
+
+    #[test]
+    fn location_field_conflict_span_is_the_real_parsed_span_not_a_text_search() {
+        // A naive "field_name =" text search breaks on this exact input:
+        // `latitude=` (no space) wouldn't match "latitude =", and a second
+        // location's `longitude` would be found by searching from the
+        // start of the file rather than from this location's own table.
+        let content1 = "[locations.home]\nlatitude=59.9139\n";
+        let content2 = "[locations.away]\nlongitude=1.0\n\n[locations.home]\nlatitude=60.0\n";
+
+        let mut home1 = HashMap::new();
+        home1.insert(
+            "home".to_string(),
+            PartialLocation {
+                latitude: Some(toml::Spanned::new(9..17, 59.9139)),
+                longitude: None,
+                elevation_m: None,
+                timezone: None,
+            },
+        );
+        let config1 = PartialConfig {
+            locations: Some(PartialLocationsConfig {
+                default: None,
+                locations: home1,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config1.toml"),
+                content: content1.to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let mut home2 = HashMap::new();
+        home2.insert(
+            "home".to_string(),
+            PartialLocation {
+                latitude: Some(toml::Spanned::new(46..52, 60.0)),
+                longitude: None,
+                elevation_m: None,
+                timezone: None,
+            },
+        );
+        home2.insert(
+            "away".to_string(),
+            PartialLocation {
+                latitude: None,
+                longitude: Some(toml::Spanned::new(18..28, 1.0)),
+                elevation_m: None,
+                timezone: None,
+            },
+        );
+        let config2 = PartialConfig {
+            locations: Some(PartialLocationsConfig {
+                default: None,
+                locations: home2,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config2.toml"),
+                content: content2.to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (_result, diagnostics) = PartialConfig::merge(vec![config1, config2]);
+
+        assert_eq!(
+            diagnostics.len(),
+            1,
+            "only 'home.latitude' conflicts: {:?}",
+            diagnostics
+        );
+        match &diagnostics[0] {
+            Diagnostic::Error(Error::Merge(err)) => {
+                assert_eq!(err.field_path, "locations.home.latitude");
+                assert_eq!(err.conflicts[0].span, 9..17);
+                assert_eq!(err.conflicts[1].span, 46..52);
+            }
+            other => panic!("expected a merge error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn last_wins_strategy_overrides_silently_with_a_warning_not_an_error() {
+        let content1 = "[http]\nport = 8080\n";
+        let config1 = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(14..18, 8080)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/base.toml"),
+                content: content1.to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let content2 = "[http]\nport = 9090\n";
+        let config2 = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(14..18, 9090)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/override.toml"),
+                content: content2.to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) =
+            PartialConfig::merge_with_strategy(vec![config1, config2], MergeStrategy::LastWins);
+
+        assert_eq!(*result.http.unwrap().port.unwrap().get_ref(), 9090);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        match &diagnostics[0] {
+            Diagnostic::Warning(Warning::FieldOverridden {
+                field_path,
+                overridden,
+                winner,
+            }) => {
+                assert_eq!(field_path, "http.port");
+                assert_eq!(overridden.file_path, PathBuf::from("/tmp/base.toml"));
+                assert_eq!(winner.file_path, PathBuf::from("/tmp/override.toml"));
+            }
+            other => panic!("expected a FieldOverridden warning, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn first_wins_is_still_the_default_for_merge() {
+        let config1 = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 8080)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/base.toml"),
+                content: "port = 8080".to_string(),
+            }),
+            ..Default::default()
+        };
+        let config2 = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 9090)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/override.toml"),
+                content: "port = 9090".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge(vec![config1, config2]);
+
+        assert_eq!(*result.http.unwrap().port.unwrap().get_ref(), 8080);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            Diagnostic::Error(Error::Merge(_))
+        ));
+    }
+
+    #[test]
+    fn merge_layered_applies_the_last_file_as_the_highest_precedence_override() {
+        let defaults = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 8565)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/etc/hearthd/defaults.toml"),
+                content: "port = 8565".to_string(),
+            }),
+            ..Default::default()
+        };
+        let user_override = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 9000)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/home/user/.config/hearthd.toml"),
+                content: "port = 9000".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge_layered(vec![defaults, user_override]);
+
+        assert_eq!(*result.http.unwrap().port.unwrap().get_ref(), 9000);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            Diagnostic::Warning(Warning::FieldOverridden { .. })
+        ));
+    }
+
+    // `apply_env_overrides` reads real process environment variables, so
+    // these tests share a mutex to avoid racing each other under `cargo
+    // test`'s default parallel execution.
+    static ENV_TEST_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+    #[test]
+    fn env_override_wins_over_file_value_and_is_reported_as_info() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_HTTP_PORT", "9443");
+
+        let mut config = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 8080)),
+            }),
+            ..Default::default()
+        };
+        let mut diagnostics = Vec::new();
+        config.apply_env_overrides(&mut diagnostics);
+
+        std::env::remove_var("HEARTHD_HTTP_PORT");
+
+        assert_eq!(*config.http.unwrap().port.unwrap().get_ref(), 9443);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].is_info());
+    }
+
+    #[test]
+    fn malformed_env_override_is_a_validation_error_naming_the_variable() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_HTTP_PORT", "not-a-port");
+
+        let mut config = PartialConfig::default();
+        let mut diagnostics = Vec::new();
+        config.apply_env_overrides(&mut diagnostics);
+
+        std::env::remove_var("HEARTHD_HTTP_PORT");
+
+        assert!(config.http.is_none());
+        assert_eq!(diagnostics.len(), 1);
+        match &diagnostics[0] {
+            Diagnostic::Error(Error::Validation(err)) => {
+                assert_eq!(err.field_path, "http.port");
+                assert_eq!(
+                    err.source.as_ref().unwrap().file_path,
+                    PathBuf::from("env:HEARTHD_HTTP_PORT")
+                );
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn env_logging_level_override_accepts_any_case() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOGGING_LEVEL", "DEBUG");
+
+        let mut config = PartialConfig::default();
+        let mut diagnostics = Vec::new();
+        config.apply_env_overrides(&mut diagnostics);
+
+        std::env::remove_var("HEARTHD_LOGGING_LEVEL");
+
+        assert_eq!(
+            *config.logging.unwrap().level.unwrap().get_ref(),
+            LogLevel::Debug
+        );
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn from_env_reads_a_named_location_field_by_prefix() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOCATIONS_HOME_LATITUDE", "59.9139");
+        std::env::set_var("HEARTHD_HTTP_PORT", "9000");
+
+        let (config, diagnostics) = PartialConfig::from_env("HEARTHD");
+
+        std::env::remove_var("HEARTHD_LOCATIONS_HOME_LATITUDE");
+        std::env::remove_var("HEARTHD_HTTP_PORT");
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert_eq!(*config.http.unwrap().port.unwrap().get_ref(), 9000);
+        let locations = config.locations.unwrap();
+        let home = locations.locations.get("home").unwrap();
+        assert_eq!(*home.latitude.as_ref().unwrap().get_ref(), 59.9139);
+    }
+
+    #[test]
+    fn from_env_reports_a_malformed_location_field_naming_the_variable() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOCATIONS_HOME_LATITUDE", "not-a-number");
+
+        let (_config, diagnostics) = PartialConfig::from_env("HEARTHD");
+
+        std::env::remove_var("HEARTHD_LOCATIONS_HOME_LATITUDE");
+
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        match &diagnostics[0] {
+            Diagnostic::Error(Error::Validation(err)) => {
+                assert_eq!(err.field_path, "locations.home.latitude");
+                assert_eq!(
+                    err.source.as_ref().unwrap().file_path,
+                    PathBuf::from("env:HEARTHD_LOCATIONS_HOME_LATITUDE")
+                );
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn from_env_can_be_merged_as_the_highest_precedence_layer() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_HTTP_PORT", "9000");
+
+        let file_config = PartialConfig {
+            http: Some(PartialHttpConfig {
+                listen: None,
+                port: Some(toml::Spanned::new(0..4, 8565)),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/etc/hearthd.toml"),
+                content: "port = 8565".to_string(),
+            }),
+            ..Default::default()
+        };
+        let (env_config, env_diagnostics) = PartialConfig::from_env("HEARTHD");
+        assert!(env_diagnostics.is_empty());
+
+        std::env::remove_var("HEARTHD_HTTP_PORT");
+
+        let (result, diagnostics) = PartialConfig::merge_layered(vec![file_config, env_config]);
+
+        assert_eq!(*result.http.unwrap().port.unwrap().get_ref(), 9000);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            Diagnostic::Warning(Warning::FieldOverridden { .. })
+        ));
+    }
+
+    #[test]
+    fn from_env_reads_a_logging_override_by_target() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api", "trace");
+
+        let (config, diagnostics) = PartialConfig::from_env("HEARTHD");
+
+        std::env::remove_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api");
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let overrides = config.logging.unwrap().overrides.unwrap();
+        assert_eq!(
+            *overrides.get("hearthd::api").unwrap().get_ref(),
+            LogLevel::Trace
+        );
+    }
+
+    #[test]
+    fn from_env_reports_a_malformed_logging_override_naming_the_variable() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api", "not-a-level");
+
+        let (_config, diagnostics) = PartialConfig::from_env("HEARTHD");
+
+        std::env::remove_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api");
+
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        match &diagnostics[0] {
+            Diagnostic::Error(Error::Validation(err)) => {
+                assert_eq!(err.field_path, "logging.overrides.hearthd::api");
+                assert_eq!(
+                    err.source.as_ref().unwrap().file_path,
+                    PathBuf::from("env:HEARTHD_LOGGING_OVERRIDES_hearthd__api")
+                );
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn apply_env_layer_merges_logging_overrides_onto_a_file_config() {
+        let _guard = ENV_TEST_LOCK.lock().unwrap();
+        std::env::set_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api", "trace");
+
+        let mut file_config = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: Some(toml::Spanned::new(0..4, LogLevel::Info)),
+                overrides: Some(HashMap::from([(
+                    "hearthd::ha".to_string(),
+                    toml::Spanned::new(0..4, LogLevel::Warn),
+                )])),
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            ..Default::default()
+        };
+        let (env_config, env_diagnostics) = PartialConfig::from_env("HEARTHD");
+        assert!(env_diagnostics.is_empty());
+
+        std::env::remove_var("HEARTHD_LOGGING_OVERRIDES_hearthd__api");
+
+        let mut diagnostics = Vec::new();
+        file_config.apply_env_layer(env_config, "HEARTHD", &mut diagnostics);
+
+        let overrides = file_config.logging.unwrap().overrides.unwrap();
+        assert_eq!(
+            *overrides.get("hearthd::ha").unwrap().get_ref(),
+            LogLevel::Warn
+        );
+        assert_eq!(
+            *overrides.get("hearthd::api").unwrap().get_ref(),
+            LogLevel::Trace
+        );
+        assert_eq!(diagnostics.len(), 1);
+        match &diagnostics[0] {
+            Diagnostic::Info(Info::EnvOverride {
+                var_name,
+                field_path,
+            }) => {
+                assert_eq!(var_name, "HEARTHD_LOGGING_OVERRIDES_hearthd__api");
+                assert_eq!(field_path, "logging.overrides.hearthd::api");
+            }
+            other => panic!("expected an EnvOverride info diagnostic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_file_over_the_limit_is_rejected_as_too_large() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_from_file_too_large");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("big.toml");
+        fs::write(&config_path, "name = \"hi\"\n").unwrap();
+
+        let result = PartialConfig::from_file_with_limit(&config_path, Some(4));
+
+        match result {
+            Err(LoadError::TooLarge { limit, .. }) => assert_eq!(limit, 4),
+            other => panic!("expected TooLarge, got {:?}", other),
+        }
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn a_limit_of_none_disables_the_size_check() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_from_file_no_limit");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let config_path = temp_dir.join("big.toml");
+        fs::write(&config_path, "[logging]\nlevel = \"info\"\n").unwrap();
+
+        let partial = PartialConfig::from_file_with_limit(&config_path, None).unwrap();
+        assert!(partial.logging.is_some());
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn load_with_imports_enforces_the_aggregate_size_across_all_imported_files() {
+        let temp_dir = std::env::temp_dir().join("hearthd_test_load_imports_aggregate_limit");
+        fs::create_dir_all(&temp_dir).unwrap();
+
+        let imported_path = temp_dir.join("imported.toml");
+        fs::write(&imported_path, "[logging]\nlevel = \"info\"\n").unwrap();
+
+        let main_path = temp_dir.join("main.toml");
+        fs::write(&main_path, "imports = [\"imported.toml\"]\n").unwrap();
+
+        // Each file is individually small, but their combined size exceeds
+        // this tiny aggregate limit.
+        let result =
+            PartialConfig::load_with_imports_with_limit(std::slice::from_ref(&main_path), Some(8));
+
+        assert!(matches!(result, Err(LoadError::TooLarge { .. })));
+
+        fs::remove_dir_all(&temp_dir).ok();
+    }
+
+    #[test]
+    fn a_global_level_and_a_module_override_can_live_in_separate_files() {
+        // One file sets the global level, another adds a module-specific
+        // override - the two should union rather than one clobbering the
+        // other's whole `logging` section.
+        let base = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: Some(toml::Spanned::new(0..4, LogLevel::Info)),
+                overrides: None,
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/base.toml"),
+                content: "level = \"info\"".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let module_override = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: None,
+                overrides: Some(HashMap::from([(
+                    "hearthd_mqtt".to_string(),
+                    toml::Spanned::new(0..4, LogLevel::Trace),
+                )])),
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/override.toml"),
+                content: "hearthd_mqtt = \"trace\"".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge(vec![base, module_override]);
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let logging = result.logging.unwrap();
+        assert_eq!(*logging.level.unwrap().get_ref(), LogLevel::Info);
+        let overrides = logging.overrides.unwrap();
+        assert_eq!(
+            *overrides.get("hearthd_mqtt").unwrap().get_ref(),
+            LogLevel::Trace
+        );
+    }
+
+    #[test]
+    fn the_same_override_key_in_two_files_conflicts_but_others_still_union() {
+        let config1 = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: None,
+                overrides: Some(HashMap::from([(
+                    "hearthd_mqtt".to_string(),
+                    toml::Spanned::new(0..4, LogLevel::Trace),
+                )])),
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config1.toml"),
+                content: "hearthd_mqtt = \"trace\"".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let config2 = PartialConfig {
+            logging: Some(PartialLoggingConfig {
+                level: None,
+                overrides: Some(HashMap::from([
+                    (
+                        "hearthd_mqtt".to_string(),
+                        toml::Spanned::new(0..4, LogLevel::Debug),
+                    ),
+                    (
+                        "hearthd_ha".to_string(),
+                        toml::Spanned::new(0..4, LogLevel::Warn),
+                    ),
+                ])),
+                file: None,
+                max_size_bytes: None,
+                max_files: None,
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config2.toml"),
+                content: "hearthd_mqtt = \"debug\"\nhearthd_ha = \"warn\"".to_string(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge(vec![config1, config2]);
+
+        // Only the conflicting key produces a diagnostic; the non-conflicting
+        // key from config2 is unioned in untouched.
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            &diagnostics[0],
+            Diagnostic::Error(Error::Merge(MergeError { field_path, .. }))
+                if field_path == "logging.overrides.hearthd_mqtt"
+        ));
+
+        let overrides = result.logging.unwrap().overrides.unwrap();
+        assert_eq!(
+            *overrides.get("hearthd_mqtt").unwrap().get_ref(),
+            LogLevel::Trace,
+            "first-wins keeps config1's value for the conflicting key"
+        );
+        assert_eq!(
+            *overrides.get("hearthd_ha").unwrap().get_ref(),
+            LogLevel::Warn
+        );
+    }
+
+    #[test]
+    fn distinct_locations_defined_in_separate_files_union_together() {
+        let config1 = PartialConfig {
+            locations: Some(PartialLocationsConfig {
+                default: None,
+                locations: HashMap::from([(
+                    "home".to_string(),
+                    PartialLocation {
+                        latitude: Some(toml::Spanned::new(0..4, 59.9139)),
+                        longitude: Some(toml::Spanned::new(0..4, 10.7522)),
+                        elevation_m: None,
+                        timezone: None,
+                    },
+                )]),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config1.toml"),
+                content: String::new(),
+            }),
+            ..Default::default()
+        };
+
+        let config2 = PartialConfig {
+            locations: Some(PartialLocationsConfig {
+                default: None,
+                locations: HashMap::from([(
+                    "work".to_string(),
+                    PartialLocation {
+                        latitude: Some(toml::Spanned::new(0..4, 60.0)),
+                        longitude: Some(toml::Spanned::new(0..4, 11.0)),
+                        elevation_m: None,
+                        timezone: None,
+                    },
+                )]),
+            }),
+            source: Some(SourceInfo {
+                file_path: PathBuf::from("/tmp/config2.toml"),
+                content: String::new(),
+            }),
+            ..Default::default()
+        };
+
+        let (result, diagnostics) = PartialConfig::merge(vec![config1, config2]);
+
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        let locations = result.locations.unwrap().locations;
+        assert!(locations.contains_key("home"));
+        assert!(locations.contains_key("work"));
+    }
 }
@@ -0,0 +1,180 @@
+//! Hot-reload support: watch a config's files on disk and atomically swap
+//! in a freshly-loaded [`Config`] whenever they change, without
+//! restarting the daemon - modeled on bunbun's `arc-swap`-based reload.
+//!
+//! Gated behind the `watch` feature since it pulls in a filesystem-event
+//! dependency (`notify`) that a build without hot-reload shouldn't need.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use notify::RecommendedWatcher;
+use notify::RecursiveMode;
+use notify::Watcher;
+
+use super::diagnostics::Diagnostic;
+use super::partial::PartialConfig;
+use super::Config;
+
+/// How long to wait after the most recent filesystem event before
+/// reloading, so a burst of writes (an editor's temp-file-then-rename, or
+/// several saves in quick succession) collapses into a single reload
+/// instead of one per event.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Owns the background watcher thread spawned by [`Config::watch`].
+/// Dropping it stops the watch; it carries no other API.
+pub struct WatchHandle {
+    _watcher: RecommendedWatcher,
+    stop: Arc<AtomicBool>,
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// The distinct parent directories of `files` - what [`Config::watch`]
+/// actually hands to the `notify` watcher, since watching a file directly
+/// doesn't survive an atomic rename-over-replace (see `Config::watch`'s
+/// comment on this).
+fn parent_dirs(files: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+    files
+        .iter()
+        .filter_map(|path| path.parent().map(Path::to_path_buf))
+        .collect()
+}
+
+/// Whether `event` reports a change to one of the specific `watched` files,
+/// as opposed to some other file in one of their parent directories.
+fn event_touches(event: &notify::Event, watched: &HashSet<PathBuf>) -> bool {
+    event.paths.iter().any(|path| watched.contains(path))
+}
+
+impl Config {
+    /// Watch `paths` - and whatever they `imports`, transitively - for
+    /// changes, reloading and atomically swapping in a fresh [`Config`]
+    /// whenever any of them change.
+    ///
+    /// The returned `Arc<ArcSwap<Config>>` is the live handle: readers
+    /// call `.load()` on it to see the current config. The swap on a
+    /// successful reload is a single atomic pointer store, so a reader
+    /// only ever observes either the whole old config or the whole new
+    /// one, never a mix of the two. If a reload's config fails validation,
+    /// `on_reload_error` is called with its `Vec<Diagnostic>` and the old
+    /// config is left live; if the files can't even be loaded (missing
+    /// file, parse error), `on_reload_error` is called with an empty
+    /// diagnostics list, since none were produced.
+    ///
+    /// Fails outright only if the *initial* load fails - without it there
+    /// would be nothing to watch or hand back.
+    pub fn watch(
+        paths: &[PathBuf],
+        on_reload_error: impl Fn(Vec<Diagnostic>) + Send + 'static,
+    ) -> Result<(Arc<ArcSwap<Config>>, WatchHandle), Box<dyn std::error::Error>> {
+        let (initial, _diagnostics) = Self::from_files(paths)?;
+        let current = Arc::new(ArcSwap::new(Arc::new(initial)));
+
+        let mut watched = Self::watch_set(paths)?;
+        let mut watched_dirs = parent_dirs(&watched);
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+                // An error from the watcher itself (e.g. an inotify queue
+                // overflow) isn't actionable here - the next real event still
+                // triggers a reload, so nothing is permanently missed.
+                if let Ok(event) = res {
+                    let _ = tx.send(event);
+                }
+            })?;
+
+        // Watch each file's *parent directory* rather than the file itself.
+        // inotify watches are inode-based: an editor's atomic
+        // write-temp-then-rename-over-original unlinks the original inode,
+        // which silently tears down a watch on the file directly (no more
+        // events ever arrive) but leaves a directory watch - and the
+        // renamed-in file at the same path - intact. Events are filtered
+        // back down to `watched` in the reload thread below.
+        for dir in &watched_dirs {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_for_thread = Arc::clone(&stop);
+        let current_for_thread = Arc::clone(&current);
+        let owned_paths: Vec<PathBuf> = paths.to_vec();
+
+        std::thread::spawn(move || {
+            while !stop_for_thread.load(Ordering::Relaxed) {
+                let first = match rx.recv_timeout(Duration::from_millis(500)) {
+                    Ok(event) => event,
+                    Err(mpsc::RecvTimeoutError::Timeout) => continue,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                };
+                // A directory watch also reports changes to files we don't
+                // care about (an editor's swap file, an unrelated sibling) -
+                // ignore those rather than treating them as a reload trigger.
+                if !event_touches(&first, &watched) {
+                    continue;
+                }
+                drop(first);
+                // Drain whatever else arrives within the debounce window
+                // before reacting, so a burst of writes triggers one
+                // reload rather than several.
+                while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+                match Self::from_files_allowing_errors(&owned_paths) {
+                    Ok((new_config, diagnostics)) => {
+                        if diagnostics.iter().any(Diagnostic::is_error) {
+                            on_reload_error(diagnostics);
+                        } else {
+                            current_for_thread.store(Arc::new(new_config));
+
+                            // `imports` in the new config may have added
+                            // or dropped files - re-derive the watch set
+                            // so the next edit to one of them is (or
+                            // isn't) picked up.
+                            if let Ok(new_watched) = Self::watch_set(&owned_paths) {
+                                let new_dirs = parent_dirs(&new_watched);
+                                for stale in watched_dirs.difference(&new_dirs) {
+                                    let _ = watcher.unwatch(stale);
+                                }
+                                for added in new_dirs.difference(&watched_dirs) {
+                                    let _ = watcher.watch(added, RecursiveMode::NonRecursive);
+                                }
+                                watched = new_watched;
+                                watched_dirs = new_dirs;
+                            }
+                        }
+                    }
+                    Err(_load_error) => on_reload_error(Vec::new()),
+                }
+            }
+        });
+
+        Ok((
+            current,
+            WatchHandle {
+                _watcher: watcher,
+                stop,
+            },
+        ))
+    }
+
+    /// Every file `paths` actually loads from, including transitively
+    /// imported ones - the set [`Self::watch`] needs to watch.
+    fn watch_set(paths: &[PathBuf]) -> std::io::Result<HashSet<PathBuf>> {
+        let (_configs, _diagnostics, watched) = PartialConfig::load_with_imports_with_paths(paths)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+        Ok(watched.into_iter().collect())
+    }
+}
@@ -0,0 +1,149 @@
+//! Reactive automation subsystem.
+//!
+//! Automations don't poll [`State`] - they declare which entities they
+//! care about via [`Automation::subscriptions`], and the engine notifies
+//! them via [`Automation::on_change`] whenever one of those entities'
+//! value actually changes (an "assertion" replacing an old one, or a
+//! fresh entity appearing). Matching here is purely about *which*
+//! entities an automation hears about; deciding what a change *means*
+//! (e.g. "did this just turn on") is the automation's own job, done in
+//! `on_change` against the `old`/`new` values on [`StateChange`].
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use linkme::distributed_slice;
+
+use super::command::CommandError;
+use super::engine::Engine;
+use super::state::BinarySensorState;
+use super::state::LightState;
+use super::state::State;
+
+/// Which entities an [`Automation`] wants to hear about.
+#[derive(Debug, Clone)]
+pub enum EntityPattern {
+    /// One specific entity, by id (e.g. `binary_sensor.hallway`).
+    Entity(String),
+    /// Every light entity.
+    AllLights,
+    /// Every binary sensor entity.
+    AllBinarySensors,
+}
+
+impl EntityPattern {
+    fn matches_entity(&self, entity_id: &str) -> bool {
+        matches!(self, EntityPattern::Entity(id) if id == entity_id)
+    }
+
+    fn matches_light(&self, entity_id: &str) -> bool {
+        matches!(self, EntityPattern::AllLights) || self.matches_entity(entity_id)
+    }
+
+    fn matches_binary_sensor(&self, entity_id: &str) -> bool {
+        matches!(self, EntityPattern::AllBinarySensors) || self.matches_entity(entity_id)
+    }
+}
+
+/// One entity's before/after value, computed by diffing the [`State`]
+/// snapshot the engine just replaced against the one it replaced it
+/// with. `old` is `None` when the entity had no prior value (first
+/// sighting).
+#[derive(Debug, Clone)]
+pub enum StateChange {
+    Light {
+        entity_id: String,
+        old: Option<LightState>,
+        new: LightState,
+    },
+    BinarySensor {
+        entity_id: String,
+        old: Option<BinarySensorState>,
+        new: BinarySensorState,
+    },
+    Availability {
+        entity_id: String,
+        old: Option<bool>,
+        new: bool,
+    },
+}
+
+impl StateChange {
+    /// The entity this change is about, used by [`Engine`]'s dispatch to
+    /// key the re-entrancy guard.
+    pub(crate) fn entity_id(&self) -> &str {
+        match self {
+            StateChange::Light { entity_id, .. } => entity_id,
+            StateChange::BinarySensor { entity_id, .. } => entity_id,
+            StateChange::Availability { entity_id, .. } => entity_id,
+        }
+    }
+
+    /// Whether `pattern` subscribes to this change.
+    pub(crate) fn matches(&self, pattern: &EntityPattern) -> bool {
+        match self {
+            StateChange::Light { entity_id, .. } => pattern.matches_light(entity_id),
+            StateChange::BinarySensor { entity_id, .. } => pattern.matches_binary_sensor(entity_id),
+            StateChange::Availability { entity_id, .. } => pattern.matches_entity(entity_id),
+        }
+    }
+}
+
+/// The handle an [`Automation`] uses to act on the world from inside
+/// `on_change`. Deliberately narrower than `Engine`'s full surface (no
+/// restart bookkeeping, no raw channel routing) - just enough to read
+/// state and send commands.
+#[derive(Clone)]
+pub struct AutomationContext {
+    engine: Arc<Engine>,
+}
+
+impl AutomationContext {
+    pub(super) fn new(engine: Arc<Engine>) -> Self {
+        Self { engine }
+    }
+
+    /// See [`Engine::send_light_command`].
+    pub async fn send_light_command(
+        &self,
+        entity_id: String,
+        on: bool,
+        brightness: Option<u8>,
+    ) -> Result<(), CommandError> {
+        self.engine
+            .send_light_command(entity_id, on, brightness)
+            .await
+    }
+
+    /// See [`Engine::state_snapshot`].
+    pub fn state_snapshot(&self) -> Arc<State> {
+        self.engine.state_snapshot()
+    }
+}
+
+/// A reactive automation: notified via [`Automation::on_change`] whenever
+/// one of the entities named by [`Automation::subscriptions`] changes
+/// value.
+///
+/// `on_change` runs on its own spawned task (see
+/// [`Engine::register_automations_from_registry`]'s dispatch), so a slow
+/// or misbehaving automation can't stall the engine's event loop.
+#[async_trait]
+pub trait Automation: Send + Sync {
+    /// A stable identifier, used for logging and to key the re-entrancy
+    /// guard that stops an automation's own commands from re-triggering
+    /// it while it's still handling the previous change.
+    fn name(&self) -> &str;
+
+    /// Which entities this automation wants to hear about.
+    fn subscriptions(&self) -> Vec<EntityPattern>;
+
+    /// Called once per matching [`StateChange`].
+    async fn on_change(&self, ctx: AutomationContext, change: StateChange);
+}
+
+/// Statically-registered automations, populated via
+/// `#[distributed_slice(AUTOMATION_REGISTRY)]` the same way integrations
+/// register into [`super::integration::REGISTRY`].
+#[distributed_slice]
+pub static AUTOMATION_REGISTRY: [fn() -> Arc<dyn Automation>];
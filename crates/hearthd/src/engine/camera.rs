@@ -0,0 +1,72 @@
+//! Camera entity for hearthd
+//!
+//! Stores the RTSP (optionally ONVIF-discovered) stream a Home Assistant
+//! integration registers for a camera. The live feed itself is served out to
+//! WebRTC clients by the WHEP endpoints in [`crate::api`]; this entity only
+//! tracks where that stream comes from and whether it's currently active.
+
+use super::entity::Entity;
+
+/// Camera entity backed by an RTSP stream, optionally discovered via ONVIF.
+pub struct Camera {
+    pub entity_id: String,
+    pub name: String,
+    pub integration: String,
+    /// `rtsp://...` source URL the WHEP egress path forwards to viewers.
+    pub stream_url: String,
+    /// ONVIF device service URL, if the integration discovered this camera
+    /// via ONVIF rather than a statically configured RTSP URL.
+    pub onvif_url: Option<String>,
+    pub snapshot_url: Option<String>,
+    pub is_streaming: bool,
+}
+
+impl Camera {
+    pub fn new(entity_id: String, name: String, integration: String, stream_url: String) -> Self {
+        Self {
+            entity_id,
+            name,
+            integration,
+            stream_url,
+            onvif_url: None,
+            snapshot_url: None,
+            is_streaming: false,
+        }
+    }
+
+    /// Update camera details from a JSON attributes object sent by Python.
+    pub fn update_from_attributes(&mut self, attrs: &serde_json::Value) {
+        if let Some(v) = attrs.get("stream_url").and_then(|v| v.as_str()) {
+            self.stream_url = v.to_string();
+        }
+        if let Some(v) = attrs.get("onvif_url").and_then(|v| v.as_str()) {
+            self.onvif_url = Some(v.to_string());
+        }
+        if let Some(v) = attrs.get("snapshot_url").and_then(|v| v.as_str()) {
+            self.snapshot_url = Some(v.to_string());
+        }
+    }
+}
+
+impl Entity for Camera {
+    fn state_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "entity_id": self.entity_id,
+            "name": self.name,
+            "platform": "camera",
+            "state": if self.is_streaming { "streaming" } else { "idle" },
+            "stream_url": self.stream_url,
+            "onvif_url": self.onvif_url,
+            "snapshot_url": self.snapshot_url,
+        })
+    }
+
+    fn platform(&self) -> &'static str {
+        "camera"
+    }
+
+    fn update_from_ha_state(&mut self, state: &str, attributes: &serde_json::Value) {
+        self.is_streaming = state == "streaming";
+        self.update_from_attributes(attributes);
+    }
+}
@@ -0,0 +1,530 @@
+//! Extensible command envelope for engine -> integration commands.
+//!
+//! Commands used to be a single closed enum, so adding a new device class
+//! (a cover, a thermostat, a switch) meant touching the enum, the router
+//! in [`Engine::send_command`](super::Engine::send_command), and every
+//! integration's `handle_message`. Instead, a [`Command`] is any
+//! downcastable type that names its own [`CommandKind`]; an
+//! [`Integration`](super::Integration) declares which kinds it accepts via
+//! `accepted_commands()`, and `send_command` checks a command's kind
+//! against the owning integration's declared list before delivering it,
+//! returning [`CommandError::Unsupported`] otherwise.
+
+use std::any::Any;
+use std::any::TypeId;
+use std::fmt;
+
+/// Identifies a concrete [`Command`] implementation. Backed by the
+/// command type's [`TypeId`] rather than a name drawn from a central enum,
+/// so a new device class is just a new type implementing [`Command`] - it
+/// never requires extending anything here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CommandKind {
+    type_id: TypeId,
+    type_name: &'static str,
+}
+
+impl CommandKind {
+    /// The [`CommandKind`] identifying a concrete [`Command`] implementation.
+    pub fn of<C: Command + 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<C>(),
+            type_name: std::any::type_name::<C>(),
+        }
+    }
+}
+
+impl fmt::Display for CommandKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.type_name)
+    }
+}
+
+/// One-shot acknowledgement channel a [`Command`] may carry so its sender
+/// can learn whether the integration actually applied the command, instead
+/// of only learning it was queued.
+pub type CommandReply = tokio::sync::oneshot::Sender<Result<(), CommandError>>;
+
+/// Why a command went unacknowledged or unhandled.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CommandError {
+    /// The integration handling this command was dropped (task panicked,
+    /// exited, or was never wired up) before it could reply - the caller
+    /// should treat this the same as a failed command rather than hang.
+    #[error("integration went away before acknowledging the command")]
+    IntegrationGone,
+
+    /// The integration ran `handle_message` and it returned an error.
+    #[error("integration failed to handle command: {0}")]
+    Failed(String),
+
+    /// No acknowledgement arrived within the caller's timeout.
+    #[error("timed out waiting for the integration to acknowledge the command")]
+    Timeout,
+
+    /// No integration is registered as owning this entity.
+    #[error("no integration found for entity '{0}'")]
+    UnknownEntity(String),
+
+    /// The integration that owns this entity never declared `kind` among
+    /// its [`Integration::accepted_commands`](super::Integration::accepted_commands).
+    #[error("integration '{integration}' does not accept {kind} commands")]
+    Unsupported {
+        integration: String,
+        kind: CommandKind,
+    },
+}
+
+/// A command the engine can route to the integration owning its
+/// `entity_id()`. `kind()` identifies the concrete type so the engine can
+/// validate it against the owning integration's accepted kinds before
+/// delivery; the receiving [`Integration::handle_message`](super::Integration::handle_message)
+/// then downcasts via [`Command::as_any`] back to that concrete type.
+pub trait Command: Send + Sync + fmt::Debug {
+    /// The entity this command targets, used to look up its owning
+    /// integration.
+    fn entity_id(&self) -> &str;
+
+    /// This command's kind; see [`CommandKind::of`].
+    fn kind(&self) -> CommandKind;
+
+    /// For the receiving integration to downcast back to the concrete
+    /// command type it expects.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Take this command's reply sender, if it has one, leaving `None` in
+    /// its place. Used by the engine's command loop to hold onto the
+    /// sender across the `handle_message` call so it can forward the
+    /// result afterwards, without the integration needing to know replies
+    /// exist.
+    fn take_reply(&mut self) -> Option<CommandReply>;
+}
+
+/// Command to change a light's state.
+pub struct LightCommand {
+    pub entity_id: String,
+    pub on: bool,
+    pub brightness: Option<u8>,
+    /// Set by callers that want to await the integration's
+    /// acknowledgement; `None` keeps today's fire-and-forget path.
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for LightCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LightCommand")
+            .field("entity_id", &self.entity_id)
+            .field("on", &self.on)
+            .field("brightness", &self.brightness)
+            .finish()
+    }
+}
+
+impl Command for LightCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<LightCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+impl LightCommand {
+    /// Lower to the generic [`CallServiceCommand`] a HA-style integration
+    /// actually understands on the wire, for integrations that only declare
+    /// [`CallServiceCommand`] among their `accepted_commands()` rather than
+    /// `LightCommand` itself.
+    pub fn into_call_service(self) -> CallServiceCommand {
+        let mut data = serde_json::Map::new();
+        if let Some(brightness) = self.brightness {
+            data.insert("brightness".to_string(), brightness.into());
+        }
+        CallServiceCommand {
+            entity_id: self.entity_id,
+            domain: "light".to_string(),
+            service: if self.on { "turn_on" } else { "turn_off" }.to_string(),
+            data: serde_json::Value::Object(data),
+            reply: self.reply,
+        }
+    }
+}
+
+/// Generic "call any Home Assistant service" command, the lowest common
+/// denominator every HA-style integration can dispatch regardless of the
+/// entity's platform. `domain`/`service` mirror HA's own
+/// `<domain>.<service>` call convention (e.g. `light.turn_on`,
+/// `cover.set_cover_position`); `data` is the service call's JSON payload.
+pub struct CallServiceCommand {
+    pub entity_id: String,
+    pub domain: String,
+    pub service: String,
+    pub data: serde_json::Value,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for CallServiceCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallServiceCommand")
+            .field("entity_id", &self.entity_id)
+            .field("domain", &self.domain)
+            .field("service", &self.service)
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl Command for CallServiceCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<CallServiceCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+/// Force an entity directly to `state`/`attributes`, bypassing HA's
+/// service-call semantics - the engine-to-integration counterpart of
+/// [`super::FromIntegrationMessage::HaStateUpdated`]. Mainly useful for
+/// integrations (or test doubles) that model entities as plain state
+/// documents rather than services.
+pub struct SetStateCommand {
+    pub entity_id: String,
+    pub state: String,
+    pub attributes: serde_json::Value,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for SetStateCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SetStateCommand")
+            .field("entity_id", &self.entity_id)
+            .field("state", &self.state)
+            .field("attributes", &self.attributes)
+            .finish()
+    }
+}
+
+impl Command for SetStateCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<SetStateCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+/// Command to change a switch's state. A typed shortcut over
+/// `CallService { domain: "switch", service: "turn_on" | "turn_off" }`,
+/// kept separate so an integration that natively understands switches (and
+/// declares `SwitchCommand` in `accepted_commands()`) doesn't have to parse
+/// a generic service call back apart.
+pub struct SwitchCommand {
+    pub entity_id: String,
+    pub on: bool,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for SwitchCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SwitchCommand")
+            .field("entity_id", &self.entity_id)
+            .field("on", &self.on)
+            .finish()
+    }
+}
+
+impl Command for SwitchCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<SwitchCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+impl SwitchCommand {
+    /// Lower to the generic [`CallServiceCommand`] equivalent; see
+    /// [`LightCommand::into_call_service`].
+    pub fn into_call_service(self) -> CallServiceCommand {
+        CallServiceCommand {
+            entity_id: self.entity_id,
+            domain: "switch".to_string(),
+            service: if self.on { "turn_on" } else { "turn_off" }.to_string(),
+            data: serde_json::Value::Object(serde_json::Map::new()),
+            reply: self.reply,
+        }
+    }
+}
+
+/// Command to move a cover (blind, garage door, curtain, ...). `position`
+/// is HA's `0..=100` closed-to-open percentage; `None` just issues the
+/// plain `open_cover`/`close_cover` service for covers that don't report a
+/// position.
+pub struct CoverCommand {
+    pub entity_id: String,
+    pub open: bool,
+    pub position: Option<u8>,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for CoverCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CoverCommand")
+            .field("entity_id", &self.entity_id)
+            .field("open", &self.open)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl Command for CoverCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<CoverCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+impl CoverCommand {
+    /// Lower to the generic [`CallServiceCommand`] equivalent; see
+    /// [`LightCommand::into_call_service`].
+    pub fn into_call_service(self) -> CallServiceCommand {
+        let (service, data) = match self.position {
+            Some(position) => {
+                let mut data = serde_json::Map::new();
+                data.insert("position".to_string(), position.into());
+                ("set_cover_position", data)
+            }
+            None => {
+                let service = if self.open {
+                    "open_cover"
+                } else {
+                    "close_cover"
+                };
+                (service, serde_json::Map::new())
+            }
+        };
+        CallServiceCommand {
+            entity_id: self.entity_id,
+            domain: "cover".to_string(),
+            service: service.to_string(),
+            data: serde_json::Value::Object(data),
+            reply: self.reply,
+        }
+    }
+}
+
+/// Command to adjust a climate device (thermostat, AC unit, ...).
+/// `temperature`/`mode` are independently optional so a caller can set
+/// either or both in one call, matching HA's `climate.set_temperature` /
+/// `climate.set_hvac_mode` services.
+pub struct ClimateCommand {
+    pub entity_id: String,
+    pub temperature: Option<f64>,
+    pub mode: Option<String>,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for ClimateCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClimateCommand")
+            .field("entity_id", &self.entity_id)
+            .field("temperature", &self.temperature)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl Command for ClimateCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<ClimateCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+impl ClimateCommand {
+    /// Lower to the generic [`CallServiceCommand`] equivalent; see
+    /// [`LightCommand::into_call_service`]. Prefers `set_temperature` when a
+    /// target temperature is given (HA allows `hvac_mode` as part of that
+    /// same service call), falling back to `set_hvac_mode` for a mode-only
+    /// change.
+    pub fn into_call_service(self) -> CallServiceCommand {
+        let mut data = serde_json::Map::new();
+        let service = if let Some(temperature) = self.temperature {
+            data.insert("temperature".to_string(), temperature.into());
+            if let Some(mode) = self.mode {
+                data.insert("hvac_mode".to_string(), mode.into());
+            }
+            "set_temperature"
+        } else {
+            if let Some(mode) = self.mode {
+                data.insert("hvac_mode".to_string(), mode.into());
+            }
+            "set_hvac_mode"
+        };
+        CallServiceCommand {
+            entity_id: self.entity_id,
+            domain: "climate".to_string(),
+            service: service.to_string(),
+            data: serde_json::Value::Object(data),
+            reply: self.reply,
+        }
+    }
+}
+
+/// The [`CommandKind`]s an entity can be expected to accept, derived from
+/// the inbound metadata captured on
+/// [`HaEntityRegistered`](super::FromIntegrationMessage::HaEntityRegistered):
+/// its HA `platform` (e.g. `"light"`, `"switch"`, `"cover"`, `"climate"`)
+/// selects that platform's typed convenience command, if any; every entity
+/// also accepts the two generic commands regardless of platform. The
+/// `capabilities` blob is currently advisory only - no platform here reads
+/// back individual feature flags out of it - but is threaded through so a
+/// caller narrowing by e.g. a reported `position` feature has a stable
+/// place to extend this.
+pub fn supported_commands(
+    platform: &str,
+    _capabilities: Option<&serde_json::Value>,
+) -> Vec<CommandKind> {
+    let mut kinds = Vec::new();
+    match platform {
+        "light" => kinds.push(CommandKind::of::<LightCommand>()),
+        "switch" => kinds.push(CommandKind::of::<SwitchCommand>()),
+        "cover" => kinds.push(CommandKind::of::<CoverCommand>()),
+        "climate" => kinds.push(CommandKind::of::<ClimateCommand>()),
+        _ => {}
+    }
+    kinds.push(CommandKind::of::<CallServiceCommand>());
+    kinds.push(CommandKind::of::<SetStateCommand>());
+    kinds
+}
+
+/// Publish an engine-computed entity (e.g. a scene, group, or virtual
+/// switch with no native device of its own) to the integration as Home
+/// Assistant MQTT discovery. `config` is an integration-specific discovery
+/// config (e.g. a serialized `DiscoveryMessage` for the MQTT integration);
+/// `component` is its Home Assistant component type (e.g. "switch", "scene").
+pub struct PublishEntityCommand {
+    pub entity_id: String,
+    pub component: String,
+    pub config: serde_json::Value,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for PublishEntityCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublishEntityCommand")
+            .field("entity_id", &self.entity_id)
+            .field("component", &self.component)
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl Command for PublishEntityCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<PublishEntityCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
+
+/// Withdraw a previously published entity, the inverse of
+/// [`PublishEntityCommand`].
+pub struct RemoveEntityCommand {
+    pub entity_id: String,
+    pub reply: Option<CommandReply>,
+}
+
+impl fmt::Debug for RemoveEntityCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoveEntityCommand")
+            .field("entity_id", &self.entity_id)
+            .finish()
+    }
+}
+
+impl Command for RemoveEntityCommand {
+    fn entity_id(&self) -> &str {
+        &self.entity_id
+    }
+
+    fn kind(&self) -> CommandKind {
+        CommandKind::of::<RemoveEntityCommand>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn take_reply(&mut self) -> Option<CommandReply> {
+        self.reply.take()
+    }
+}
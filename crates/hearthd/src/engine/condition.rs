@@ -0,0 +1,89 @@
+//! Canonical weather-condition vocabulary.
+//!
+//! Source integrations (Met Éireann, OpenWeatherMap, AccuWeather, ...) each
+//! report `condition` using their own provider-specific strings. Rather than
+//! have the Engine and downstream automations special-case every provider,
+//! [`normalize`] maps a raw condition string onto a small fixed [`Condition`]
+//! enum, using a per-integration table where a provider's vocabulary doesn't
+//! match Home Assistant's own condition strings closely enough for
+//! [`DEFAULT_MAP`] to cover it.
+
+use serde::Serialize;
+
+/// A weather condition, normalized across source integrations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Rainy,
+    Pouring,
+    Snowy,
+    Hail,
+    Fog,
+    Lightning,
+    Windy,
+    /// The raw string didn't match any known vocabulary for this
+    /// integration or the default table.
+    Unknown,
+}
+
+/// Home Assistant's own weather condition strings, which most integrations
+/// already emit as-is - this is the fallback table for any integration
+/// without a dedicated entry in [`integration_map`].
+const DEFAULT_MAP: &[(&str, Condition)] = &[
+    ("clear-night", Condition::Clear),
+    ("sunny", Condition::Clear),
+    ("partlycloudy", Condition::PartlyCloudy),
+    ("cloudy", Condition::Cloudy),
+    ("rainy", Condition::Rainy),
+    ("pouring", Condition::Pouring),
+    ("snowy", Condition::Snowy),
+    ("snowy-rainy", Condition::Snowy),
+    ("hail", Condition::Hail),
+    ("fog", Condition::Fog),
+    ("lightning", Condition::Lightning),
+    ("lightning-rainy", Condition::Lightning),
+    ("windy", Condition::Windy),
+    ("windy-variant", Condition::Windy),
+];
+
+/// Met Éireann's `CONDITION_MAP` remaps its own symbol vocabulary onto Home
+/// Assistant's condition strings before they ever reach `hearthd` - this
+/// table exists for the rare provider whose raw strings still need
+/// remapping by the time they get here.
+const MET_EIREANN_MAP: &[(&str, Condition)] = &[
+    ("lightrain", Condition::Rainy),
+    ("rain", Condition::Pouring),
+    ("lightrainshowers", Condition::Rainy),
+    ("rainshowers", Condition::Pouring),
+    ("lightsleet", Condition::Snowy),
+    ("lightsnow", Condition::Snowy),
+    ("snow", Condition::Snowy),
+    ("fair", Condition::Clear),
+    ("partlycloudy", Condition::PartlyCloudy),
+    ("cloudy", Condition::Cloudy),
+    ("fog", Condition::Fog),
+];
+
+/// Select the raw-string-to-[`Condition`] table for `integration`, falling
+/// back to [`DEFAULT_MAP`] for any integration without a dedicated one.
+fn integration_map(integration: &str) -> &'static [(&'static str, Condition)] {
+    match integration {
+        "met_eireann" => MET_EIREANN_MAP,
+        _ => DEFAULT_MAP,
+    }
+}
+
+/// Normalize a raw condition string reported by `integration` into a
+/// [`Condition`], matching case-insensitively since providers are
+/// inconsistent about casing. Returns [`Condition::Unknown`] if nothing
+/// matches.
+pub fn normalize(integration: &str, raw: &str) -> Condition {
+    integration_map(integration)
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(raw))
+        .map(|(_, condition)| *condition)
+        .unwrap_or(Condition::Unknown)
+}
@@ -1,22 +1,38 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::error::Error;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::time::Instant;
 
 use arc_swap::ArcSwap;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
+use tokio::time;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-use super::event::Event;
+use super::automation::Automation;
+use super::automation::AutomationContext;
+use super::automation::StateChange;
+use super::command::Command;
+use super::command::CommandError;
+use super::command::CommandKind;
+use super::command::LightCommand;
+use super::command::PublishEntityCommand;
+use super::command::RemoveEntityCommand;
 use super::integration::FromIntegrationReceiver;
 use super::integration::FromIntegrationSender;
 use super::integration::Integration;
+use super::integration::IntegrationFactoryResult;
 use super::integration::ToIntegrationSender;
 use super::message::FromIntegrationMessage;
-use super::message::ToIntegrationMessage;
 use super::state::BinarySensorState;
 use super::state::LightState;
 use super::state::State;
@@ -30,11 +46,32 @@ pub struct Engine {
     /// Centralized state snapshot (readers load the Arc, writer stores a new one)
     state: ArcSwap<State>,
 
-    /// Map of entity_id -> integration name for routing messages
-    entity_integration_map: std::sync::Mutex<HashMap<String, String>>,
-
-    /// Communication channels to integrations (for commands)
-    integration_channels: HashMap<String, ToIntegrationSender>,
+    /// Map of entity_id -> integration name for routing messages.
+    ///
+    /// `Arc`-wrapped (not just a bare field like `state`) because a
+    /// supervised integration's restart loop runs in its own `'static`
+    /// task and needs to drop this integration's stale entries on
+    /// restart, independent of any borrow of `Engine` itself.
+    entity_integration_map: Arc<StdMutex<HashMap<String, String>>>,
+
+    /// Communication channels to integrations (for commands). `Arc`-wrapped
+    /// for the same reason as `entity_integration_map`: a restart swaps in
+    /// a fresh channel for the name it supervises.
+    integration_channels: Arc<StdMutex<HashMap<String, ToIntegrationSender>>>,
+
+    /// Command kinds each integration declared via
+    /// [`Integration::accepted_commands`] once its current attempt's
+    /// `setup` succeeded, consulted by [`Self::send_command`] to reject a
+    /// command the owning integration never said it could handle. Keyed
+    /// by integration name rather than duplicated per entity - an
+    /// entity's capabilities are just its owning integration's.
+    /// `Arc`-wrapped for the same reason as `entity_integration_map`.
+    integration_capabilities: Arc<StdMutex<HashMap<String, Vec<CommandKind>>>>,
+
+    /// Per-integration restart bookkeeping, queryable via
+    /// [`Self::supervision_state`] so a state snapshot can report
+    /// integration health.
+    supervision: Arc<StdMutex<HashMap<String, SupervisionState>>>,
 
     /// Receive messages from integrations (events)
     message_rx: Mutex<FromIntegrationReceiver>,
@@ -44,36 +81,187 @@ pub struct Engine {
 
     /// Handles for integration tasks
     integration_handles: Vec<JoinHandle<()>>,
+
+    /// Registered automations, notified by [`Self::dispatch_state_change`]
+    /// when a [`StateChange`] matches one of their `subscriptions()`.
+    automations: Vec<Arc<dyn Automation>>,
+
+    /// (automation name, entity id) pairs currently being dispatched,
+    /// guarding against re-entrant triggers: an automation's own command
+    /// can cause another change for the same entity before its first
+    /// `on_change` call returns, which would otherwise re-trigger it
+    /// without bound.
+    dispatching: Arc<StdMutex<HashSet<(String, String)>>>,
 }
 
 /// Capacity for the integration→engine message channel
 /// Provides backpressure when integrations send faster than the engine can process
 const FROM_INTEGRATION_CHANNEL_SIZE: usize = 1024;
 
+/// How long an acknowledged command (see [`Engine::send_light_command`])
+/// waits for the owning integration to reply before giving up.
+const COMMAND_ACK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// When a supervised integration task should be restarted after `setup`
+/// fails, its command loop ends prematurely, or it panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// One failure is terminal - same behavior as an unsupervised task.
+    Never,
+    /// Always restart, with no limit on attempts.
+    Always,
+    /// Restart up to `max_attempts` times total, then give up.
+    OnFailure { max_attempts: u32 },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::OnFailure { max_attempts: 5 }
+    }
+}
+
+/// Restart bookkeeping for one supervised integration, queryable via
+/// [`Engine::supervision_state`].
+#[derive(Debug, Clone, Default)]
+pub struct SupervisionState {
+    /// Number of restart attempts made so far (0 while the current attempt
+    /// is still healthy).
+    pub attempts: u32,
+    /// The error that caused the most recent restart, if any.
+    pub last_error: Option<String>,
+    /// When the next restart attempt is scheduled, if one is pending.
+    pub next_retry_at: Option<Instant>,
+}
+
+/// Base delay for the first restart attempt.
+const BACKOFF_BASE: Duration = Duration::from_millis(500);
+/// Upper bound on the backoff delay, regardless of attempt count.
+const BACKOFF_CAP: Duration = Duration::from_secs(60);
+
+/// Jittered exponential backoff (full jitter: uniform over `[0, capped]`)
+/// for the `attempt`'th restart of the integration named `name`.
+///
+/// This crate has no `rand` dependency wired in yet, so the jitter is
+/// derived from a hash of the integration name, attempt number, and
+/// current time rather than pulling one in for a single call site - good
+/// enough to avoid a restart thundering herd across integrations without
+/// the real thing.
+fn backoff_delay(name: &str, attempt: u32) -> Duration {
+    let exp_ms = BACKOFF_BASE
+        .as_millis()
+        .saturating_mul(1u128 << attempt.min(16));
+    let capped_ms = exp_ms.min(BACKOFF_CAP.as_millis());
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    name.hash(&mut hasher);
+    attempt.hash(&mut hasher);
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .hash(&mut hasher);
+    let jitter_fraction = (hasher.finish() % 1_000) as f64 / 1_000.0;
+
+    Duration::from_millis((capped_ms as f64 * jitter_fraction) as u64)
+}
+
+/// Run one attempt of an integration: `setup`, then the command loop,
+/// then `shutdown`. Returns `Err` for a setup failure, a `handle_message`
+/// that can't even be attempted, or - since this checker has no notion of
+/// a deliberate whole-engine shutdown yet - the command loop ending at
+/// all, which [`Engine::register_supervised`]'s caller decides whether to
+/// restart via its [`RestartPolicy`].
+async fn run_integration_attempt(
+    name: String,
+    mut integration: Box<dyn Integration>,
+    from_integration_tx: FromIntegrationSender,
+    mut to_integration_rx: mpsc::UnboundedReceiver<Box<dyn Command>>,
+    integration_capabilities: Arc<StdMutex<HashMap<String, Vec<CommandKind>>>>,
+) -> Result<(), String> {
+    integration
+        .setup(from_integration_tx)
+        .await
+        .map_err(|e| format!("setup failed: {e}"))?;
+
+    integration_capabilities
+        .lock()
+        .unwrap()
+        .insert(name.clone(), integration.accepted_commands().to_vec());
+
+    while let Some(mut msg) = to_integration_rx.recv().await {
+        // Taken before the message moves into `handle_message` so it
+        // survives to report the outcome - including a panic inside
+        // `handle_message`, which drops `reply` here without sending and
+        // resolves the caller's receiver to `CommandError::IntegrationGone`
+        // rather than hanging.
+        let reply = msg.take_reply();
+        let result = integration.handle_message(msg).await;
+
+        if let Some(reply) = reply {
+            let ack = result
+                .as_ref()
+                .map(|_| ())
+                .map_err(|e| CommandError::Failed(e.to_string()));
+            // Caller dropped its receiver (no longer waiting) - fine to ignore.
+            let _ = reply.send(ack);
+        }
+
+        if let Err(e) = result {
+            warn!("Integration '{}' failed to handle message: {}", name, e);
+        }
+    }
+
+    if let Err(e) = integration.shutdown().await {
+        warn!("Integration '{}' shutdown failed: {}", name, e);
+    }
+
+    Err("command channel closed".to_string())
+}
+
 impl Engine {
     /// Create a new Engine instance
     pub fn new() -> Self {
         let (message_tx, message_rx) = mpsc::channel(FROM_INTEGRATION_CHANNEL_SIZE);
         Self {
             state: ArcSwap::new(Arc::default()),
-            entity_integration_map: std::sync::Mutex::new(HashMap::new()),
-            integration_channels: HashMap::new(),
+            entity_integration_map: Arc::new(StdMutex::new(HashMap::new())),
+            integration_channels: Arc::new(StdMutex::new(HashMap::new())),
+            integration_capabilities: Arc::new(StdMutex::new(HashMap::new())),
+            supervision: Arc::new(StdMutex::new(HashMap::new())),
             message_rx: Mutex::new(message_rx),
             message_tx,
             integration_handles: Vec::new(),
+            automations: Vec::new(),
+            dispatching: Arc::new(StdMutex::new(HashSet::new())),
+        }
+    }
+
+    /// Register one automation, in addition to whatever
+    /// [`Self::register_automations_from_registry`] already added. Kept
+    /// for tests and ad hoc setups; production registration goes through
+    /// the registry.
+    pub fn register_automation(&mut self, automation: Arc<dyn Automation>) {
+        self.automations.push(automation);
+    }
+
+    /// Register every automation in [`super::automation::AUTOMATION_REGISTRY`].
+    pub fn register_automations_from_registry(&mut self) {
+        for constr in super::automation::AUTOMATION_REGISTRY {
+            self.automations.push(constr());
         }
     }
 
     /// Register integrations from configuration
     ///
     /// This is a convenience method that checks the config and registers
-    /// any enabled integrations.
+    /// any enabled integrations, each supervised with the default
+    /// [`RestartPolicy`] (see [`Self::register_supervised`]).
     pub fn register_integrations_from_config(
         &mut self,
-        cfg: &crate::config::Config,
+        cfg: Arc<crate::config::Config>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let ctx = IntegrationContext { config: cfg };
         for constr in super::integration::REGISTRY {
+            let ctx = IntegrationContext { config: &cfg };
             let integration = match constr(&ctx) {
                 Ok(Some(i)) => i,
                 Err(e) => {
@@ -83,89 +271,219 @@ impl Engine {
                 Ok(None) => continue,
             };
             let name = integration.name().to_string();
-            self.register_integration(name, integration);
+
+            let cfg = Arc::clone(&cfg);
+            let factory = move || {
+                let ctx = IntegrationContext { config: &cfg };
+                constr(&ctx)
+            };
+
+            self.register_supervised(name, RestartPolicy::default(), integration, factory);
         }
 
         Ok(())
     }
 
-    /// Register an integration with the engine
+    /// Register an integration with the engine, unsupervised: if `setup`
+    /// fails, the command loop ends, or the task panics, it is logged and
+    /// the integration is gone for good. Kept for callers (tests, ad hoc
+    /// setups) that don't need restart behavior; production registration
+    /// goes through [`Self::register_integrations_from_config`] /
+    /// [`Self::register_supervised`].
+    pub fn register_integration(&mut self, name: String, integration: Box<dyn Integration>) {
+        self.register_supervised(name, RestartPolicy::Never, integration, || {
+            Ok(None) // `RestartPolicy::Never` means this is never called.
+        });
+    }
+
+    /// Register an integration with the engine, supervised by `policy`.
     ///
-    /// This spawns the integration in a background task, wires up channels,
-    /// and starts its setup process.
-    pub fn register_integration(&mut self, name: String, mut integration: Box<dyn Integration>) {
-        let (to_integration_tx, mut to_integration_rx) = mpsc::unbounded_channel();
+    /// Spawns a restart loop that runs `integration` (via
+    /// [`run_integration_attempt`]), and on failure - `setup` erroring, the
+    /// command loop ending, or a panic - re-invokes `factory` for a fresh
+    /// instance and retries after a jittered exponential backoff, up to
+    /// what `policy` allows. Each attempt gets its own command channel, so
+    /// `send_command` to a now-dead attempt's stale sender fails cleanly
+    /// (the old entry is replaced before the new attempt starts) rather
+    /// than silently queuing into nothing; the dead attempt's
+    /// `entity_integration_map` entries are dropped so a re-discovering
+    /// fresh instance doesn't collide with stale ones.
+    pub fn register_supervised(
+        &mut self,
+        name: String,
+        policy: RestartPolicy,
+        integration: Box<dyn Integration>,
+        factory: impl Fn() -> IntegrationFactoryResult + Send + Sync + 'static,
+    ) {
         let from_integration_tx = self.message_tx.clone();
+        let integration_channels = Arc::clone(&self.integration_channels);
+        let integration_capabilities = Arc::clone(&self.integration_capabilities);
+        let entity_integration_map = Arc::clone(&self.entity_integration_map);
+        let supervision = Arc::clone(&self.supervision);
 
-        self.integration_channels
-            .insert(name.clone(), to_integration_tx);
+        supervision
+            .lock()
+            .unwrap()
+            .insert(name.clone(), SupervisionState::default());
 
-        // Spawn integration task
         let handle = tokio::spawn(async move {
-            // Setup integration (gives it the sender for events)
-            if let Err(e) = integration.setup(from_integration_tx).await {
-                warn!("Integration '{}' setup failed: {}", name, e);
-                return;
-            }
+            let mut integration = integration;
+            let mut attempt: u32 = 0;
+
+            loop {
+                let (to_integration_tx, to_integration_rx) = mpsc::unbounded_channel();
+                // Replaces (and so drops) any previous attempt's sender,
+                // so a command routed to it now fails with a closed-channel
+                // error instead of queuing into a task that's already gone.
+                integration_channels
+                    .lock()
+                    .unwrap()
+                    .insert(name.clone(), to_integration_tx);
+
+                let attempt_name = name.clone();
+                let attempt_tx = from_integration_tx.clone();
+                let attempt_capabilities = Arc::clone(&integration_capabilities);
+                let join = tokio::spawn(run_integration_attempt(
+                    attempt_name,
+                    integration,
+                    attempt_tx,
+                    to_integration_rx,
+                    attempt_capabilities,
+                ));
+
+                let error = match join.await {
+                    Ok(Err(e)) => e,
+                    Ok(Ok(())) => unreachable!(
+                        "run_integration_attempt only returns Err - the command loop ending \
+                         is itself treated as a failure, since this engine has no deliberate \
+                         whole-engine shutdown signal yet"
+                    ),
+                    Err(join_err) if join_err.is_panic() => {
+                        format!("panicked: {join_err}")
+                    }
+                    Err(join_err) => format!("task cancelled: {join_err}"),
+                };
 
-            // Process commands from engine
-            while let Some(msg) = to_integration_rx.recv().await {
-                if let Err(e) = integration.handle_message(msg).await {
-                    warn!("Integration '{}' failed to handle message: {}", name, e);
+                warn!("Integration '{}' failed: {}", name, error);
+                attempt += 1;
+                {
+                    let mut sup = supervision.lock().unwrap();
+                    let state = sup.entry(name.clone()).or_default();
+                    state.attempts = attempt;
+                    state.last_error = Some(error);
+                    state.next_retry_at = None;
+                }
+
+                // The dead attempt's entity_integration_map entries are
+                // stale until the fresh attempt rediscovers them.
+                entity_integration_map
+                    .lock()
+                    .unwrap()
+                    .retain(|_, owner| owner != &name);
+                // Likewise its declared capabilities - a fresh attempt
+                // re-declares its own via `run_integration_attempt` once
+                // its `setup` succeeds.
+                integration_capabilities.lock().unwrap().remove(&name);
+
+                let should_restart = match policy {
+                    RestartPolicy::Never => false,
+                    RestartPolicy::Always => true,
+                    RestartPolicy::OnFailure { max_attempts } => attempt < max_attempts,
+                };
+                if !should_restart {
+                    integration_channels.lock().unwrap().remove(&name);
+                    warn!("Integration '{}' exhausted its restart policy, giving up", name);
+                    break;
                 }
-            }
 
-            if let Err(e) = integration.shutdown().await {
-                warn!("Integration '{}' shutdown failed: {}", name, e);
+                let delay = backoff_delay(&name, attempt);
+                {
+                    let mut sup = supervision.lock().unwrap();
+                    if let Some(state) = sup.get_mut(&name) {
+                        state.next_retry_at = Some(Instant::now() + delay);
+                    }
+                }
+                time::sleep(delay).await;
+
+                integration = match factory() {
+                    Ok(Some(i)) => i,
+                    Ok(None) => {
+                        info!(
+                            "Integration '{}' no longer wanted on restart, giving up",
+                            name
+                        );
+                        integration_channels.lock().unwrap().remove(&name);
+                        break;
+                    }
+                    Err(e) => {
+                        warn!("Integration '{}' failed to rebuild for restart: {}", name, e);
+                        integration_channels.lock().unwrap().remove(&name);
+                        break;
+                    }
+                };
             }
         });
 
         self.integration_handles.push(handle);
     }
 
-    /// Send a command to an integration
+    /// Current restart bookkeeping for a supervised integration, or `None`
+    /// if no integration has ever been registered under `name`.
+    pub fn supervision_state(&self, name: &str) -> Option<SupervisionState> {
+        self.supervision.lock().unwrap().get(name).cloned()
+    }
+
+    /// Send a command to the integration that owns its `entity_id()`.
     ///
-    /// Routes the command to the appropriate integration based on entity_id.
-    pub fn send_command(&self, msg: ToIntegrationMessage) -> Result<(), Box<dyn Error + Send>> {
-        // Extract entity_id from command for routing
-        let entity_id = match &msg {
-            ToIntegrationMessage::LightCommand { entity_id, .. } => entity_id.clone(),
-        };
-
-        // Route to the integration that owns this entity
-        let map = self
-            .entity_integration_map
-            .lock()
-            .map_err(|e| -> Box<dyn Error + Send> {
-                Box::new(std::io::Error::other(e.to_string()))
-            })?;
+    /// Looks up the owning integration, checks that it declared `cmd`'s
+    /// [`CommandKind`] among its [`Integration::accepted_commands`]
+    /// (returning [`CommandError::Unsupported`] otherwise), and queues it
+    /// on that integration's channel. This is fire-and-forget: it returns
+    /// as soon as the command is queued, regardless of whether `cmd`
+    /// carries a reply sender. Callers that want to await an
+    /// acknowledgement should go through a helper like
+    /// [`Self::send_light_command`] instead.
+    pub fn send_command(&self, cmd: Box<dyn Command>) -> Result<(), CommandError> {
+        let entity_id = cmd.entity_id().to_string();
+
+        let integration_name = {
+            let map = self.entity_integration_map.lock().unwrap();
+            map.get(&entity_id).cloned()
+        }
+        .ok_or_else(|| CommandError::UnknownEntity(entity_id.clone()))?;
 
-        let integration_name = map
-            .get(&entity_id)
-            .ok_or_else(|| -> Box<dyn Error + Send> {
-                Box::new(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    format!("No integration found for entity: {}", entity_id),
-                ))
-            })?;
+        let kind = cmd.kind();
+        let accepted = self
+            .integration_capabilities
+            .lock()
+            .unwrap()
+            .get(&integration_name)
+            .cloned()
+            .unwrap_or_default();
+        if !accepted.contains(&kind) {
+            return Err(CommandError::Unsupported {
+                integration: integration_name,
+                kind,
+            });
+        }
 
-        let tx = self.integration_channels.get(integration_name).ok_or_else(
-            || -> Box<dyn Error + Send> {
-                Box::new(std::io::Error::new(
-                    std::io::ErrorKind::NotFound,
-                    format!("Integration channel not found: {}", integration_name),
-                ))
-            },
-        )?;
+        let channels = self.integration_channels.lock().unwrap();
+        let tx = channels
+            .get(&integration_name)
+            .ok_or(CommandError::IntegrationGone)?;
 
-        tx.send(msg)
-            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })
+        tx.send(cmd).map_err(|_| CommandError::IntegrationGone)
     }
 
     /// Run the engine's main event loop
     ///
     /// Processes incoming events from integrations and updates state.
-    pub async fn run(&self) -> Result<(), Box<dyn Error + Send>> {
+    ///
+    /// Takes `Arc<Self>` rather than `&self` because dispatching a
+    /// [`StateChange`] to automations spawns `'static` tasks that need
+    /// their own handle back onto the engine (to send commands via
+    /// [`AutomationContext`]) independent of this loop's borrow.
+    pub async fn run(self: &Arc<Self>) -> Result<(), Box<dyn Error + Send>> {
         info!("Engine starting");
 
         // Main event loop - only receives FromIntegration messages
@@ -187,23 +505,131 @@ impl Engine {
         self.state.load_full()
     }
 
-    /// Send a light command to control a light entity
-    pub fn send_light_command(
+    /// Send a light command to control a light entity and await the owning
+    /// integration's acknowledgement.
+    ///
+    /// Unlike [`Self::send_command`], this doesn't return as soon as the
+    /// command is queued: it waits (up to [`COMMAND_ACK_TIMEOUT`]) for the
+    /// integration's `handle_message` to actually run and report whether
+    /// the command succeeded. The integration dying mid-command drops the
+    /// reply sender, which resolves this to `Err(CommandError::IntegrationGone)`
+    /// instead of hanging; exceeding the timeout resolves to
+    /// `Err(CommandError::Timeout)`.
+    pub async fn send_light_command(
         &self,
         entity_id: String,
         on: bool,
         brightness: Option<u8>,
-    ) -> Result<(), Box<dyn Error + Send>> {
-        let cmd = ToIntegrationMessage::LightCommand {
+    ) -> Result<(), CommandError> {
+        let (tx, rx) = oneshot::channel();
+        let cmd = Box::new(LightCommand {
             entity_id,
             on,
             brightness,
-        };
-        self.send_command(cmd)
+            reply: Some(tx),
+        });
+        self.send_command(cmd)?;
+
+        match time::timeout(COMMAND_ACK_TIMEOUT, rx).await {
+            Ok(Ok(ack)) => ack,
+            Ok(Err(_)) => Err(CommandError::IntegrationGone),
+            Err(_) => Err(CommandError::Timeout),
+        }
+    }
+
+    /// Publish an engine-computed entity (e.g. a scene, group, or virtual
+    /// switch) to `integration_name` as Home Assistant MQTT discovery.
+    ///
+    /// Unlike [`Self::send_command`], this is sent directly to the named
+    /// integration rather than routed via `entity_integration_map`: the
+    /// entity doesn't exist in that map until the integration itself
+    /// reports it discovered (see [`FromIntegrationMessage::EntityDiscovered`]),
+    /// which it does as a side effect of handling this message.
+    pub fn publish_entity(
+        &self,
+        integration_name: &str,
+        entity_id: String,
+        component: String,
+        config: serde_json::Value,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let channels = self.integration_channels.lock().unwrap();
+        let tx = channels
+            .get(integration_name)
+            .ok_or_else(|| -> Box<dyn Error + Send> {
+                Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Integration channel not found: {}", integration_name),
+                ))
+            })?;
+
+        tx.send(Box::new(PublishEntityCommand {
+            entity_id,
+            component,
+            config,
+            reply: None,
+        }))
+        .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })
+    }
+
+    /// Withdraw a previously [`Self::publish_entity`]-ed entity.
+    ///
+    /// Routed via `entity_integration_map` like [`Self::send_command`],
+    /// since by the time an entity can be removed it has already been
+    /// reported discovered.
+    pub fn remove_published_entity(&self, entity_id: String) -> Result<(), CommandError> {
+        self.send_command(Box::new(RemoveEntityCommand {
+            entity_id,
+            reply: None,
+        }))
+    }
+
+    /// Notify every registered automation whose `subscriptions()` matches
+    /// `change`, each on its own spawned task: `handle_event` must not
+    /// await an automation's `on_change` directly, since a slow or
+    /// deadlocked automation would otherwise stall the entire event loop.
+    ///
+    /// Guarded by `dispatching` against re-entrant triggers - see its
+    /// field doc on [`Engine`].
+    fn dispatch_state_change(self: &Arc<Self>, change: StateChange) {
+        let entity_id = change.entity_id().to_string();
+
+        for automation in &self.automations {
+            if !automation
+                .subscriptions()
+                .iter()
+                .any(|pattern| change.matches(pattern))
+            {
+                continue;
+            }
+
+            let key = (automation.name().to_string(), entity_id.clone());
+            {
+                let mut dispatching = self.dispatching.lock().unwrap();
+                if !dispatching.insert(key.clone()) {
+                    warn!(
+                        "Automation '{}' still handling a change for '{}', dropping re-entrant trigger",
+                        key.0, key.1
+                    );
+                    continue;
+                }
+            }
+
+            let engine = Arc::clone(self);
+            let automation = Arc::clone(automation);
+            let change = change.clone();
+            tokio::spawn(async move {
+                let ctx = AutomationContext::new(Arc::clone(&engine));
+                automation.on_change(ctx, change).await;
+                engine.dispatching.lock().unwrap().remove(&key);
+            });
+        }
     }
 
     /// Handle an event from an integration
-    async fn handle_event(&self, msg: FromIntegrationMessage) -> Result<(), Box<dyn Error + Send>> {
+    async fn handle_event(
+        self: &Arc<Self>,
+        msg: FromIntegrationMessage,
+    ) -> Result<(), Box<dyn Error + Send>> {
         match msg {
             FromIntegrationMessage::EntityDiscovered {
                 entity_id,
@@ -240,41 +666,77 @@ impl Engine {
                 on,
                 brightness,
             } => {
-                let light_state = LightState { on, brightness };
+                let light_state = LightState {
+                    on,
+                    brightness,
+                    ..Default::default()
+                };
                 info!(
                     "Light state changed: {} -> on={}, brightness={:?}",
                     entity_id, on, brightness
                 );
 
-                {
+                let old = {
                     let mut state = State::clone(&self.state.load());
-                    state.lights.insert(entity_id.clone(), light_state.clone());
+                    let old = state.lights.insert(entity_id.clone(), light_state.clone());
                     self.state.store(Arc::new(state));
-                }
+                    old
+                };
 
-                let _event = Event::LightStateChanged {
+                self.dispatch_state_change(StateChange::Light {
                     entity_id,
-                    state: light_state,
-                };
-                // TODO: Trigger automations based on state change
+                    old,
+                    new: light_state,
+                });
             }
             FromIntegrationMessage::BinarySensorStateChanged { entity_id, on } => {
                 let sensor_state = BinarySensorState { on };
                 info!("Binary sensor state changed: {} -> on={}", entity_id, on);
 
-                {
+                let old = {
                     let mut state = State::clone(&self.state.load());
-                    state
+                    let old = state
                         .binary_sensors
                         .insert(entity_id.clone(), sensor_state.clone());
                     self.state.store(Arc::new(state));
-                }
+                    old
+                };
 
-                let _event = Event::BinarySensorStateChanged {
+                self.dispatch_state_change(StateChange::BinarySensor {
                     entity_id,
-                    state: sensor_state,
+                    old,
+                    new: sensor_state,
+                });
+            }
+            FromIntegrationMessage::EntityAvailabilityChanged {
+                entity_id,
+                available,
+            } => {
+                info!(
+                    "Entity availability changed: {} -> available={}",
+                    entity_id, available
+                );
+
+                let old = {
+                    let mut state = State::clone(&self.state.load());
+                    let old = state.availability.insert(entity_id.clone(), available);
+                    self.state.store(Arc::new(state));
+                    old
                 };
-                // TODO: Trigger automations based on state change
+
+                self.dispatch_state_change(StateChange::Availability {
+                    entity_id,
+                    old,
+                    new: available,
+                });
+            }
+            FromIntegrationMessage::EntityCommandReceived { entity_id, payload } => {
+                info!(
+                    "Command received for published entity {}: {}",
+                    entity_id, payload
+                );
+                // TODO: Interpret the command against the entity's automation
+                // (scene activation, virtual switch toggle, etc.)
             }
         }
         Ok(())
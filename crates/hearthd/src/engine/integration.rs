@@ -4,8 +4,9 @@ use async_trait::async_trait;
 use linkme::distributed_slice;
 use tokio::sync::mpsc;
 
+use super::command::Command;
+use super::command::CommandKind;
 use super::message::FromIntegrationMessage;
-use super::message::ToIntegrationMessage;
 use crate::config::Config;
 
 /// Channel types for messages FROM integrations TO the engine
@@ -14,7 +15,7 @@ pub type FromIntegrationSender = mpsc::Sender<FromIntegrationMessage>;
 pub type FromIntegrationReceiver = mpsc::Receiver<FromIntegrationMessage>;
 
 /// Channel types for messages FROM the engine TO integrations (unbounded - engine must not block)
-pub type ToIntegrationSender = mpsc::UnboundedSender<ToIntegrationMessage>;
+pub type ToIntegrationSender = mpsc::UnboundedSender<Box<dyn Command>>;
 
 /// Result type for integration factory functions
 pub type IntegrationFactoryResult = anyhow::Result<Option<Box<dyn Integration>>>;
@@ -38,13 +39,19 @@ pub trait Integration: Send + Sync {
     /// (discovery, state changes, etc.)
     async fn setup(&mut self, tx: FromIntegrationSender) -> Result<(), Box<dyn Error + Send>>;
 
+    /// Which [`CommandKind`]s this integration's `handle_message` knows how
+    /// to downcast and handle, queried once `setup` succeeds so the engine
+    /// can reject a mismatched command before routing it here (see
+    /// [`super::Engine::send_command`]). Integrations with nothing to
+    /// command (sensor-only ones, e.g. BLE/Modbus) return `&[]`.
+    fn accepted_commands(&self) -> &[CommandKind];
+
     /// Handle a command from the engine
     ///
-    /// The integration should execute the requested action (e.g., turn on a light)
-    async fn handle_message(
-        &mut self,
-        msg: ToIntegrationMessage,
-    ) -> Result<(), Box<dyn Error + Send>>;
+    /// The integration should downcast `cmd` (via [`Command::as_any`]) to
+    /// one of the concrete types named in its own `accepted_commands()`
+    /// and execute the requested action (e.g., turn on a light).
+    async fn handle_message(&mut self, cmd: Box<dyn Command>) -> Result<(), Box<dyn Error + Send>>;
 
     /// Shut down the integration gracefully
     async fn shutdown(&mut self) -> Result<(), Box<dyn Error + Send>>;
@@ -1,8 +1,8 @@
 //! Type-safe message system for hearthd
 //!
-//! Messages are split by direction to enforce correct usage at compile time:
-//! - `FromIntegrationMessage`: Events from integrations to the engine
-//! - `ToIntegrationMessage`: Commands from the engine to integrations
+//! `FromIntegrationMessage` carries events from integrations to the engine.
+//! The engine-to-integration direction is instead an open set of
+//! [`super::command::Command`] implementations - see that module.
 
 /// Device info forwarded from HA integrations.
 #[derive(Debug, Clone)]
@@ -33,6 +33,21 @@ pub enum FromIntegrationMessage {
         brightness: Option<u8>,
     },
 
+    /// A sensor's decoded fields changed (e.g. a BLE sensor's
+    /// temperature/humidity/battery notification)
+    SensorStateChanged {
+        entity_id: String,
+        fields: serde_json::Value,
+    },
+
+    /// An entity's availability changed, tracked via its MQTT
+    /// `availability_topic`
+    EntityAvailabilityChanged { entity_id: String, available: bool },
+
+    /// A command sent to an entity went unconfirmed (e.g. a light command
+    /// with no matching state-topic echo within its timeout)
+    CommandFailed { entity_id: String, reason: String },
+
     /// HA entity registered with metadata
     HaEntityRegistered {
         entity_id: String,
@@ -51,6 +66,23 @@ pub enum FromIntegrationMessage {
         attributes: serde_json::Value,
         last_updated: String,
     },
+
+    /// A command was received on a [`super::command::PublishEntityCommand`]'d
+    /// entity's `command_topic`, forwarded verbatim for the engine to
+    /// interpret (e.g. a scene activation or virtual switch toggle) since
+    /// the integration has no built-in notion of what the entity means.
+    EntityCommandReceived {
+        entity_id: String,
+        payload: serde_json::Value,
+    },
+
+    /// An integration's upstream broker/transport connection went offline
+    /// or came back online, e.g. the MQTT integration's reconnect loop
+    /// losing and re-establishing its session.
+    IntegrationConnectionChanged {
+        integration_name: String,
+        connected: bool,
+    },
 }
 
 impl std::fmt::Debug for FromIntegrationMessage {
@@ -80,6 +112,24 @@ impl std::fmt::Debug for FromIntegrationMessage {
                 .field("on", on)
                 .field("brightness", brightness)
                 .finish(),
+            FromIntegrationMessage::SensorStateChanged { entity_id, fields } => f
+                .debug_struct("SensorStateChanged")
+                .field("entity_id", entity_id)
+                .field("fields", fields)
+                .finish(),
+            FromIntegrationMessage::EntityAvailabilityChanged {
+                entity_id,
+                available,
+            } => f
+                .debug_struct("EntityAvailabilityChanged")
+                .field("entity_id", entity_id)
+                .field("available", available)
+                .finish(),
+            FromIntegrationMessage::CommandFailed { entity_id, reason } => f
+                .debug_struct("CommandFailed")
+                .field("entity_id", entity_id)
+                .field("reason", reason)
+                .finish(),
             FromIntegrationMessage::HaEntityRegistered {
                 entity_id,
                 name,
@@ -104,17 +154,20 @@ impl std::fmt::Debug for FromIntegrationMessage {
                 .field("state", state)
                 .field("last_updated", last_updated)
                 .finish(),
+            FromIntegrationMessage::EntityCommandReceived { entity_id, payload } => f
+                .debug_struct("EntityCommandReceived")
+                .field("entity_id", entity_id)
+                .field("payload", payload)
+                .finish(),
+            FromIntegrationMessage::IntegrationConnectionChanged {
+                integration_name,
+                connected,
+            } => f
+                .debug_struct("IntegrationConnectionChanged")
+                .field("integration_name", integration_name)
+                .field("connected", connected)
+                .finish(),
         }
     }
 }
 
-/// Messages FROM the engine TO integrations (commands)
-#[derive(Debug, Clone)]
-pub enum ToIntegrationMessage {
-    /// Command to change a light's state
-    LightCommand {
-        entity_id: String,
-        on: bool,
-        brightness: Option<u8>,
-    },
-}
@@ -1,10 +1,31 @@
+mod automation;
+pub mod camera;
+mod command;
 mod engine;
+mod entity;
 mod event;
 mod integration;
 mod message;
 pub mod state;
+pub mod units;
+pub mod weather;
 
+pub use automation::AUTOMATION_REGISTRY;
+pub use automation::Automation;
+pub use automation::AutomationContext;
+pub use automation::EntityPattern;
+pub use automation::StateChange;
+pub use camera::Camera;
+pub use command::CallServiceCommand;
+pub use command::Command;
+pub use command::CommandError;
+pub use command::CommandKind;
+pub use command::CommandReply;
+pub use command::LightCommand;
+pub use command::PublishEntityCommand;
+pub use command::RemoveEntityCommand;
 pub use engine::Engine;
+pub use entity::Entity;
 pub use event::Event;
 pub use integration::FromIntegrationSender;
 pub use integration::Integration;
@@ -12,7 +33,9 @@ pub use integration::IntegrationContext;
 pub use integration::IntegrationFactoryResult;
 pub use integration::REGISTRY as INTEGRATION_REGISTRY;
 pub use message::FromIntegrationMessage;
-pub use message::ToIntegrationMessage;
 pub use state::BinarySensorState;
 pub use state::LightState;
+pub use state::SensorState;
 pub use state::State;
+pub use units::UnitSystem;
+pub use weather::ForecastMode;
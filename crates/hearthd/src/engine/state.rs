@@ -11,6 +11,37 @@ pub struct LightState {
 
     /// Brightness level (0-255), if supported.
     pub brightness: Option<u8>,
+
+    /// Color temperature in mireds, if the light supports `color_temp`
+    /// mode. Mutually exclusive with `color_xy`/`color_rgb` in practice,
+    /// but not enforced here since Zigbee2MQTT reports whichever mode the
+    /// light last changed to.
+    pub color_temp: Option<u32>,
+
+    /// CIE 1931 xy chromaticity, if the light supports `xy` color mode.
+    pub color_xy: Option<ColorXy>,
+
+    /// RGB color, if the light supports `rgb` color mode.
+    pub color_rgb: Option<ColorRgb>,
+
+    /// Duration in seconds of the most recently requested or observed
+    /// transition (fade) between states.
+    pub transition: Option<f64>,
+}
+
+/// CIE 1931 xy chromaticity coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, facet::Facet)]
+pub struct ColorXy {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// RGB color, 0-255 per channel.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, facet::Facet)]
+pub struct ColorRgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
 }
 
 /// State of a binary sensor entity.
@@ -21,6 +52,18 @@ pub struct BinarySensorState {
     pub on: bool,
 }
 
+/// State of a numeric sensor entity (battery, illuminance, linkquality,
+/// temperature, etc.)
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, facet::Facet)]
+pub struct SensorState {
+    /// The sensor's current numeric reading.
+    pub value: f64,
+
+    /// Unit of measurement, if the discovery payload advertised one (e.g.
+    /// "%", "lx", "°C").
+    pub unit: Option<String>,
+}
+
 /// Centralized snapshot of the entire engine state.
 ///
 /// This is the `State` that automations receive as their second argument.
@@ -28,4 +71,11 @@ pub struct BinarySensorState {
 pub struct State {
     pub lights: HashMap<String, LightState>,
     pub binary_sensors: HashMap<String, BinarySensorState>,
+
+    /// Per-entity availability, tracked separately from `lights`/
+    /// `binary_sensors` since it's reported by integrations over a
+    /// different channel (e.g. MQTT's `availability_topic`) than state
+    /// updates, and entities with no availability tracking simply never
+    /// appear here.
+    pub availability: HashMap<String, bool>,
 }
@@ -0,0 +1,90 @@
+//! Canonicalizes HA's `native_*` weather values onto a single configured
+//! unit system.
+//!
+//! HA ships a companion `*_unit` attribute alongside each `native_*` value
+//! (e.g. `native_temperature_unit` = `"°F"`), since the source integration's
+//! own unit system can differ from ours. Each `normalize_*` function here
+//! converts a raw value into [`UnitSystem`]'s unit for that quantity; per
+//! HA's own convention, a missing unit attribute means the value is already
+//! in the target system and is passed through unchanged.
+
+use serde::Deserialize;
+
+/// The unit system weather values are normalized into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitSystem {
+    /// Celsius, hPa, m/s, mm.
+    #[default]
+    Metric,
+    /// Fahrenheit, inHg, mph, in.
+    Imperial,
+}
+
+/// Convert a temperature reported in `unit` (HA's `native_temperature_unit`,
+/// e.g. `"°F"`/`"K"`) to `target`'s temperature unit.
+pub fn normalize_temperature(value: f64, unit: Option<&str>, target: UnitSystem) -> f64 {
+    let Some(unit) = unit else {
+        return value;
+    };
+    let celsius = match unit {
+        "°F" | "F" => (value - 32.0) * 5.0 / 9.0,
+        "K" => value - 273.15,
+        _ => value, // "°C" or unrecognized: already Celsius
+    };
+    match target {
+        UnitSystem::Metric => celsius,
+        UnitSystem::Imperial => celsius * 9.0 / 5.0 + 32.0,
+    }
+}
+
+/// Convert a pressure reported in `unit` (HA's `native_pressure_unit`, e.g.
+/// `"inHg"`/`"mmHg"`) to `target`'s pressure unit.
+pub fn normalize_pressure(value: f64, unit: Option<&str>, target: UnitSystem) -> f64 {
+    let Some(unit) = unit else {
+        return value;
+    };
+    let hpa = match unit {
+        "inHg" => value * 33.8639,
+        "mmHg" => value * 1.33322,
+        _ => value, // "hPa"/"mbar" or unrecognized: already hPa
+    };
+    match target {
+        UnitSystem::Metric => hpa,
+        UnitSystem::Imperial => hpa / 33.8639,
+    }
+}
+
+/// Convert a speed reported in `unit` (HA's `native_wind_speed_unit`, e.g.
+/// `"mph"`/`"km/h"`/`"kn"`) to `target`'s speed unit.
+pub fn normalize_speed(value: f64, unit: Option<&str>, target: UnitSystem) -> f64 {
+    let Some(unit) = unit else {
+        return value;
+    };
+    let ms = match unit {
+        "mph" => value * 0.44704,
+        "km/h" | "kmh" => value / 3.6,
+        "kn" => value * 0.514444,
+        _ => value, // "m/s" or unrecognized: already m/s
+    };
+    match target {
+        UnitSystem::Metric => ms,
+        UnitSystem::Imperial => ms / 0.44704,
+    }
+}
+
+/// Convert a precipitation depth reported in `unit` (HA's
+/// `native_precipitation_unit`, e.g. `"in"`) to `target`'s depth unit.
+pub fn normalize_depth(value: f64, unit: Option<&str>, target: UnitSystem) -> f64 {
+    let Some(unit) = unit else {
+        return value;
+    };
+    let mm = match unit {
+        "in" => value * 25.4,
+        _ => value, // "mm" or unrecognized: already mm
+    };
+    match target {
+        UnitSystem::Metric => mm,
+        UnitSystem::Imperial => mm / 25.4,
+    }
+}
@@ -3,14 +3,26 @@
 //! Stores weather state received from HA weather integrations and serializes
 //! it to JSON for the Engine.
 
+use serde::Deserialize;
 use serde::Serialize;
 
+use super::condition::normalize as normalize_condition;
+use super::condition::Condition;
 use super::entity::Entity;
+use super::units::normalize_depth;
+use super::units::normalize_pressure;
+use super::units::normalize_speed;
+use super::units::normalize_temperature;
+use super::units::UnitSystem;
 
 /// Current weather state.
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct WeatherState {
-    pub condition: Option<String>,
+    pub condition: Option<Condition>,
+    /// The provider's own condition string before normalization, kept
+    /// around for debugging when [`Condition`]'s fixed vocabulary loses
+    /// information a provider's string carried.
+    pub condition_raw: Option<String>,
     pub temperature: Option<f64>,
     pub humidity: Option<f64>,
     pub pressure: Option<f64>,
@@ -20,14 +32,64 @@ pub struct WeatherState {
     pub cloud_coverage: Option<f64>,
     pub dew_point: Option<f64>,
     pub uv_index: Option<f64>,
+    /// `datetime` of the next hourly forecast entry expected to bring rain,
+    /// or `None` if the forecast horizon looks dry.
+    pub next_precipitation_at: Option<String>,
+    /// Minutes from now until `next_precipitation_at`, or `None` along with
+    /// it.
+    pub minutes_until_precipitation: Option<i64>,
 }
 
-/// A single forecast entry (daily or hourly).
+/// A forecast entry counts as "precipitation expected" once either of these
+/// is crossed - a token amount of rain, or a good chance of some even if the
+/// amount itself is still in the noise.
+const PRECIPITATION_THRESHOLD_MM: f64 = 0.1;
+const PRECIPITATION_PROBABILITY_THRESHOLD_PERCENT: f64 = 30.0;
+
+/// A single severe-weather alert, e.g. from Météo-France or DWD warnwetter.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeatherAlert {
+    pub event: String,
+    /// Color-coded severity, e.g. "green"/"yellow"/"orange"/"red" -
+    /// providers don't agree on the full set of levels, so this is kept as
+    /// the raw string rather than an enum; see [`severity_rank`] for how
+    /// [`Weather::max_severity`] orders them.
+    pub severity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub coastal: bool,
+}
+
+/// Rank a severity level for comparison, highest first. An unrecognized
+/// level ranks alongside "green" rather than being rejected outright, since
+/// providers occasionally use levels outside the usual four.
+fn severity_rank(severity: &str) -> u8 {
+    match severity.to_ascii_lowercase().as_str() {
+        "red" => 3,
+        "orange" => 2,
+        "yellow" => 1,
+        "green" => 0,
+        _ => 0,
+    }
+}
+
+/// A single forecast entry (daily, hourly, or twice-daily).
 #[derive(Debug, Clone, Serialize)]
 pub struct Forecast {
     pub datetime: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub condition: Option<String>,
+    pub condition: Option<Condition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition_raw: Option<String>,
+    /// Whether this entry covers a daytime or nighttime period - only
+    /// meaningful for `twice_daily` forecasts, where a day is split into a
+    /// day and a night entry.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_daytime: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,30 +114,83 @@ pub struct Forecast {
     pub uv_index: Option<f64>,
 }
 
+/// Which forecast channel(s) a `Weather` entity currently has data for -
+/// mirrors HA's `WeatherEntityFeature.FORECAST_*` flags, since integrations
+/// like AEMET can offer more than one and a consumer needs to pick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForecastMode {
+    Daily,
+    Hourly,
+    TwiceDaily,
+}
+
 /// Weather entity that stores weather data from HA integrations.
 pub struct Weather {
     pub entity_id: String,
     pub name: String,
+    /// Sandbox integration name this entity was created by (e.g.
+    /// `"met_eireann"`), used to pick the right condition vocabulary in
+    /// [`super::condition::normalize`].
+    pub integration: String,
+    /// Unit system `native_*` forecast values are converted into; see
+    /// [`super::units`].
+    pub units: UnitSystem,
     pub state: WeatherState,
     pub forecast_daily: Vec<Forecast>,
     pub forecast_hourly: Vec<Forecast>,
+    pub forecast_twice_daily: Vec<Forecast>,
+    pub alerts: Vec<WeatherAlert>,
 }
 
 impl Weather {
-    pub fn new(entity_id: String, name: String) -> Self {
+    pub fn new(entity_id: String, name: String, integration: String, units: UnitSystem) -> Self {
         Self {
             entity_id,
             name,
+            integration,
+            units,
             state: WeatherState::default(),
             forecast_daily: Vec::new(),
             forecast_hourly: Vec::new(),
+            forecast_twice_daily: Vec::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    /// Which forecast channels this entity currently has data for, in
+    /// `daily`, `hourly`, `twice_daily` order - so a caller can pick
+    /// `HaConfig::preferred_forecast_mode` if it's among them, or otherwise
+    /// the first one available.
+    pub fn forecast_modes(&self) -> Vec<ForecastMode> {
+        let mut modes = Vec::new();
+        if !self.forecast_daily.is_empty() {
+            modes.push(ForecastMode::Daily);
+        }
+        if !self.forecast_hourly.is_empty() {
+            modes.push(ForecastMode::Hourly);
+        }
+        if !self.forecast_twice_daily.is_empty() {
+            modes.push(ForecastMode::TwiceDaily);
         }
+        modes
+    }
+
+    /// The highest-severity currently active alert, if any - e.g. to drive
+    /// which color a UI should show regardless of how many alerts are
+    /// active or in what order the provider listed them.
+    pub fn max_severity(&self) -> Option<&str> {
+        self.alerts
+            .iter()
+            .max_by_key(|a| severity_rank(&a.severity))
+            .map(|a| a.severity.as_str())
     }
 
     /// Update weather state from a JSON attributes object sent by Python.
     pub fn update_from_attributes(&mut self, attrs: &serde_json::Value) {
         if let Some(v) = attrs.get("condition").and_then(|v| v.as_str()) {
-            self.state.condition = Some(v.to_string());
+            self.state.condition = Some(normalize_condition(&self.integration, v));
+            self.state.condition_raw = Some(v.to_string());
         }
         if let Some(v) = attrs.get("temperature").and_then(|v| v.as_f64()) {
             self.state.temperature = Some(v);
@@ -107,55 +222,185 @@ impl Weather {
 
         // Parse daily forecasts
         if let Some(daily) = attrs.get("forecast_daily").and_then(|v| v.as_array()) {
-            self.forecast_daily = daily.iter().filter_map(parse_forecast).collect();
+            self.forecast_daily = daily
+                .iter()
+                .filter_map(|v| parse_forecast(&self.integration, self.units, v))
+                .collect();
         }
 
         // Parse hourly forecasts
         if let Some(hourly) = attrs.get("forecast_hourly").and_then(|v| v.as_array()) {
-            self.forecast_hourly = hourly.iter().filter_map(parse_forecast).collect();
+            self.forecast_hourly = hourly
+                .iter()
+                .filter_map(|v| parse_forecast(&self.integration, self.units, v))
+                .collect();
+            self.forecast_hourly
+                .sort_by(|a, b| a.datetime.cmp(&b.datetime));
+
+            let next_rain = self.forecast_hourly.iter().find(|f| {
+                f.precipitation.unwrap_or(0.0) > PRECIPITATION_THRESHOLD_MM
+                    || f.precipitation_probability.unwrap_or(0.0)
+                        > PRECIPITATION_PROBABILITY_THRESHOLD_PERCENT
+            });
+            match next_rain {
+                Some(forecast) => {
+                    self.state.minutes_until_precipitation =
+                        parse_rfc3339_to_unix(&forecast.datetime).map(|at| {
+                            let now = std::time::SystemTime::now()
+                                .duration_since(std::time::UNIX_EPOCH)
+                                .unwrap_or_default()
+                                .as_secs() as i64;
+                            (at - now) / 60
+                        });
+                    self.state.next_precipitation_at = Some(forecast.datetime.clone());
+                }
+                None => {
+                    self.state.next_precipitation_at = None;
+                    self.state.minutes_until_precipitation = None;
+                }
+            }
         }
+
+        // Parse twice-daily forecasts
+        if let Some(twice_daily) = attrs.get("forecast_twice_daily").and_then(|v| v.as_array()) {
+            self.forecast_twice_daily = twice_daily
+                .iter()
+                .filter_map(|v| parse_forecast(&self.integration, self.units, v))
+                .collect();
+        }
+
+        // Parse active alerts
+        if let Some(alerts) = attrs.get("alerts").and_then(|v| v.as_array()) {
+            self.alerts = alerts.iter().filter_map(parse_alert).collect();
+        }
+    }
+}
+
+/// Parse an RFC 3339 timestamp (`2024-01-15T10:30:00Z`) into Unix seconds.
+/// Forecast `datetime`s are otherwise kept as opaque strings; this one case
+/// needs the actual instant to derive
+/// [`WeatherState::minutes_until_precipitation`].
+fn parse_rfc3339_to_unix(s: &str) -> Option<i64> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 19 {
+        return None;
+    }
+    let year: i64 = s.get(0..4)?.parse().ok()?;
+    if bytes.get(4) != Some(&b'-') {
+        return None;
+    }
+    let month: u32 = s.get(5..7)?.parse().ok()?;
+    if bytes.get(7) != Some(&b'-') {
+        return None;
+    }
+    let day: u32 = s.get(8..10)?.parse().ok()?;
+    if !matches!(bytes.get(10), Some(b'T') | Some(b't') | Some(b' ')) {
+        return None;
+    }
+    let hour: u32 = s.get(11..13)?.parse().ok()?;
+    if bytes.get(13) != Some(&b':') {
+        return None;
+    }
+    let minute: u32 = s.get(14..16)?.parse().ok()?;
+    if bytes.get(16) != Some(&b':') {
+        return None;
+    }
+    let second: u32 = s.get(17..19)?.parse().ok()?;
+
+    days_from_civil(year, month, day)
+        .map(|days| days * 86_400 + hour as i64 * 3_600 + minute as i64 * 60 + second as i64)
+}
+
+/// Days since the Unix epoch for a given civil (proleptic Gregorian) date.
+/// Based on Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
     }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as i64;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
 }
 
-fn parse_forecast(v: &serde_json::Value) -> Option<Forecast> {
+fn parse_forecast(integration: &str, units: UnitSystem, v: &serde_json::Value) -> Option<Forecast> {
     let datetime = v.get("datetime")?.as_str()?.to_string();
+    let condition_raw = v
+        .get("condition")
+        .and_then(|v| v.as_str())
+        .map(String::from);
+
+    let temperature_unit = v.get("native_temperature_unit").and_then(|v| v.as_str());
+    let pressure_unit = v.get("native_pressure_unit").and_then(|v| v.as_str());
+    let wind_speed_unit = v.get("native_wind_speed_unit").and_then(|v| v.as_str());
+    let precipitation_unit = v.get("native_precipitation_unit").and_then(|v| v.as_str());
+
     Some(Forecast {
         datetime,
-        condition: v.get("condition").and_then(|v| v.as_str()).map(String::from),
+        condition: condition_raw
+            .as_deref()
+            .map(|raw| normalize_condition(integration, raw)),
+        condition_raw,
+        is_daytime: v.get("is_daytime").and_then(|v| v.as_bool()),
         temperature: v
             .get("native_temperature")
             .or_else(|| v.get("temperature"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|t| normalize_temperature(t, temperature_unit, units)),
         templow: v
             .get("native_templow")
             .or_else(|| v.get("templow"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|t| normalize_temperature(t, temperature_unit, units)),
         humidity: v.get("humidity").and_then(|v| v.as_f64()),
         precipitation: v
             .get("native_precipitation")
             .or_else(|| v.get("precipitation"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|p| normalize_depth(p, precipitation_unit, units)),
         precipitation_probability: v
             .get("precipitation_probability")
             .and_then(|v| v.as_f64()),
         pressure: v
             .get("native_pressure")
             .or_else(|| v.get("pressure"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|p| normalize_pressure(p, pressure_unit, units)),
         wind_speed: v
             .get("native_wind_speed")
             .or_else(|| v.get("wind_speed"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|s| normalize_speed(s, wind_speed_unit, units)),
         wind_bearing: v.get("wind_bearing").and_then(|v| v.as_f64()),
         wind_gust_speed: v
             .get("native_wind_gust_speed")
             .or_else(|| v.get("wind_gust_speed"))
-            .and_then(|v| v.as_f64()),
+            .and_then(|v| v.as_f64())
+            .map(|s| normalize_speed(s, wind_speed_unit, units)),
         cloud_coverage: v.get("cloud_coverage").and_then(|v| v.as_f64()),
         uv_index: v.get("uv_index").and_then(|v| v.as_f64()),
     })
 }
 
+fn parse_alert(v: &serde_json::Value) -> Option<WeatherAlert> {
+    let event = v.get("event")?.as_str()?.to_string();
+    let severity = v.get("severity")?.as_str()?.to_string();
+    Some(WeatherAlert {
+        event,
+        severity,
+        start: v.get("start").and_then(|v| v.as_str()).map(String::from),
+        end: v.get("end").and_then(|v| v.as_str()).map(String::from),
+        description: v
+            .get("description")
+            .and_then(|v| v.as_str())
+            .map(String::from),
+        coastal: v.get("coastal").and_then(|v| v.as_bool()).unwrap_or(false),
+    })
+}
+
 impl Entity for Weather {
     fn state_json(&self) -> serde_json::Value {
         serde_json::json!({
@@ -165,6 +410,8 @@ impl Entity for Weather {
             "state": self.state,
             "forecast_daily": self.forecast_daily,
             "forecast_hourly": self.forecast_hourly,
+            "forecast_twice_daily": self.forecast_twice_daily,
+            "alerts": self.alerts,
         })
     }
 
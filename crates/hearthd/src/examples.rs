@@ -268,6 +268,66 @@
 //!
 //! ```
 //!
+//! ## Layered Precedence
+//!
+//! The same base/override pair from "Complex Scenario" above is a conflict
+//! under `Config::from_files`'s default strict mode, but succeeds under
+//! `Config::from_files_layered`, which treats an import as the base layer
+//! and lets the importing file override it instead:
+//!
+//! ```
+//! use hearthd::Config;
+//! use std::fs;
+//! use std::io::Write;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//!
+//! let base_path = temp_dir.path().join("base.toml");
+//! let mut base = fs::File::create(&base_path).unwrap();
+//! write!(
+//!     base,
+//!     r#"
+//! [logging]
+//! level = "info"
+//!
+//! [locations.home]
+//! latitude = 59.9139
+//! longitude = 10.7522
+//! "#
+//! ).unwrap();
+//!
+//! let override_path = temp_dir.path().join("override.toml");
+//! let mut override_file = fs::File::create(&override_path).unwrap();
+//! write!(
+//!     override_file,
+//!     r#"
+//! imports = ["{}"]
+//!
+//! [logging]
+//! level = "debug"
+//!
+//! [locations.home]
+//! latitude = 60.0
+//! "#,
+//!     base_path.display()
+//! ).unwrap();
+//!
+//! // Strict mode (the default): same-field redefinition is a conflict.
+//! let strict_result = Config::from_files(&[override_path.clone()]);
+//! assert!(strict_result.is_err());
+//!
+//! // Layered mode: the importing file wins, no conflict.
+//! let (config, diagnostics) = Config::from_files_layered(&[override_path.clone()]).unwrap();
+//! assert!(!diagnostics.iter().any(|d| d.is_error()));
+//! assert_eq!(config.logging.level, hearthd::LogLevel::Debug);
+//!
+//! let diagnostics_str = format!("{:#?}", diagnostics)
+//!     .replace(&base_path.display().to_string(), "base.toml")
+//!     .replace(&override_path.display().to_string(), "override.toml");
+//! insta::assert_snapshot!("layered_precedence_overridden_by", diagnostics_str);
+//!
+//! ```
+//!
 //! ## Successful Config with Warnings
 //!
 //! Configurations can load successfully while still producing warnings:
@@ -463,6 +523,49 @@
 //!
 //! ```
 //!
+//! ## Invalid JSON
+//!
+//! A `.json` file is parsed with `serde_json` instead of `toml`, so its
+//! own syntax errors surface the same way, tagged with the detected format:
+//!
+//! ```
+//! use hearthd::Config;
+//! use std::fs;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//!
+//! let config_path = temp_dir.path().join("config.json");
+//! fs::write(&config_path, r#"{ "locations": { "home": "#).unwrap();
+//!
+//! let result = Config::from_files(&[config_path.clone()]);
+//! assert!(result.is_err());
+//!
+//! let error_str = result.unwrap_err().to_string();
+//! assert!(error_str.contains("JSON"));
+//!
+//! ```
+//!
+//! ## Invalid YAML
+//!
+//! Likewise for `.yaml`/`.yml`, parsed with `serde_yaml`:
+//!
+//! ```
+//! use hearthd::Config;
+//! use std::fs;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//!
+//! let config_path = temp_dir.path().join("config.yaml");
+//! fs::write(&config_path, "logging:\n  level: [unterminated\n").unwrap();
+//!
+//! let result = Config::from_files(&[config_path.clone()]);
+//! assert!(result.is_err());
+//!
+//! let error_str = result.unwrap_err().to_string();
+//! assert!(error_str.contains("YAML"));
+//!
+//! ```
+//!
 //! ## Valid Split Config
 //!
 //! Non-conflicting fields from the same location can be split across files:
@@ -505,6 +608,71 @@
 //!
 //! ```
 //!
+//! ## Provenance
+//!
+//! `Config::from_files_with_provenance` reports which file supplied each
+//! field's winning value, even when a location's fields are split across
+//! files:
+//!
+//! ```
+//! use hearthd::Config;
+//! use std::fs;
+//! use std::io::Write;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//!
+//! let config1_path = temp_dir.path().join("config1.toml");
+//! let mut config1 = fs::File::create(&config1_path).unwrap();
+//! write!(config1, "[locations.home]\nlatitude = 59.9139\n").unwrap();
+//!
+//! let config2_path = temp_dir.path().join("config2.toml");
+//! let mut config2 = fs::File::create(&config2_path).unwrap();
+//! write!(config2, "[locations.home]\nlongitude = 10.7522\n").unwrap();
+//!
+//! let (_config, _diagnostics, provenance) = Config::from_files_with_provenance(&[
+//!     config1_path.clone(),
+//!     config2_path.clone(),
+//! ]).unwrap();
+//!
+//! assert_eq!(
+//!     provenance.get("locations.home.latitude").unwrap().file_path,
+//!     config1_path
+//! );
+//! assert_eq!(
+//!     provenance.get("locations.home.longitude").unwrap().file_path,
+//!     config2_path
+//! );
+//!
+//! ```
+//!
+//! Provenance follows a field through an import as well - it names the
+//! imported file, not the file that imported it:
+//!
+//! ```
+//! use hearthd::Config;
+//! use std::fs;
+//! use std::io::Write;
+//!
+//! let temp_dir = tempfile::tempdir().unwrap();
+//!
+//! let imported_path = temp_dir.path().join("imported.toml");
+//! let mut imported = fs::File::create(&imported_path).unwrap();
+//! write!(imported, "[logging]\nlevel = \"debug\"\n").unwrap();
+//!
+//! let main_path = temp_dir.path().join("main.toml");
+//! let mut main_file = fs::File::create(&main_path).unwrap();
+//! write!(main_file, "imports = [\"{}\"]\n", imported_path.display()).unwrap();
+//!
+//! let (_config, _diagnostics, provenance) =
+//!     Config::from_files_with_provenance(&[main_path.clone()]).unwrap();
+//!
+//! assert_eq!(
+//!     provenance.get("logging.level").unwrap().file_path,
+//!     imported_path
+//! );
+//!
+//! ```
+//!
 //! ## Field Conflict - Same Value
 //!
 //! Even when values are identical, defining the same field twice is a conflict:
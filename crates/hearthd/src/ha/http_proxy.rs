@@ -0,0 +1,423 @@
+//! Single-flight request coalescing for the Python HTTP proxy.
+//!
+//! `Message::HttpRequest` lets Python ask Rust to make outbound HTTP calls on
+//! its behalf, but several integrations often poll the same upstream URL on
+//! overlapping timers. [`HttpProxy`] coalesces concurrent idempotent
+//! requests for the same `(method, url, headers, body)` into a single
+//! `reqwest` call: the first caller performs it, every other caller waiting
+//! on the same key clones its result instead of hitting the network again.
+//! Each caller still gets back its own `Response::HttpResponse` carrying its
+//! own `request_id`.
+//!
+//! Not yet wired up to [`super::Runtime::handle_message`], whose
+//! `Message::HttpRequest` arm is still an unimplemented TODO - see that
+//! function's match arm.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+use std::time::Instant;
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use tokio::sync::broadcast;
+use tokio::sync::Notify;
+
+use super::protocol::HttpMethod;
+use super::protocol::Response;
+
+/// The result of a completed (coalesced or solo) HTTP call, cloned out to
+/// every waiter on the same key.
+#[derive(Debug, Clone)]
+struct HttpOutcome {
+    status: u16,
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// `(method, url, headers, body)`, canonicalized so that two logically
+/// identical requests - in particular, with headers provided in a different
+/// order - coalesce onto the same in-flight call.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CoalesceKey {
+    method: HttpMethod,
+    url: String,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+}
+
+impl CoalesceKey {
+    fn new(
+        method: HttpMethod,
+        url: &str,
+        headers: &HashMap<String, String>,
+        body: &Option<Vec<u8>>,
+    ) -> Self {
+        let mut headers: Vec<(String, String)> = headers
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        headers.sort();
+        Self {
+            method,
+            url: url.to_string(),
+            headers,
+            body: body.clone(),
+        }
+    }
+}
+
+/// Bookkeeping for one in-flight coalesced request.
+#[derive(Clone)]
+struct Inflight {
+    tx: broadcast::Sender<Result<HttpOutcome, String>>,
+    /// Earliest deadline requested by the leader or any follower that has
+    /// joined so far. Each follower shrinks this to its own deadline if
+    /// that's sooner, so the shared network call never runs longer than the
+    /// most impatient waiter's `timeout_ms` - "honor the smallest
+    /// outstanding timeout_ms".
+    deadline: Arc<StdMutex<Instant>>,
+    /// Notified whenever `deadline` shrinks, so the leader's `execute` loop
+    /// - parked in a `select!` against the deadline it last read - wakes up
+    /// and re-arms its sleep against the new value instead of waiting out
+    /// the stale, longer one.
+    deadline_changed: Arc<Notify>,
+}
+
+/// Coalesces concurrent identical outbound HTTP calls. One [`HttpProxy`]
+/// should be shared (e.g. behind an `Arc`) across every sandbox connection
+/// that can originate a `Message::HttpRequest`.
+pub struct HttpProxy {
+    client: reqwest::Client,
+    inflight: DashMap<CoalesceKey, Inflight>,
+    /// Methods eligible for coalescing. Only idempotent methods are safe to
+    /// share a single in-flight call's result across callers by default.
+    coalesce_methods: HashSet<HttpMethod>,
+}
+
+impl Default for HttpProxy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpProxy {
+    /// Create a proxy that coalesces GET and HEAD requests, the only
+    /// methods safe to share between callers without side effects.
+    pub fn new() -> Self {
+        Self::with_coalesced_methods([HttpMethod::Get, HttpMethod::Head].into_iter().collect())
+    }
+
+    /// Create a proxy that coalesces only the given methods - pass an empty
+    /// set to disable coalescing entirely.
+    pub fn with_coalesced_methods(coalesce_methods: HashSet<HttpMethod>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            inflight: DashMap::new(),
+            coalesce_methods,
+        }
+    }
+
+    /// Handle a `Message::HttpRequest`, coalescing it with any in-flight
+    /// identical request if its method is eligible. Always resolves to a
+    /// `Response::HttpResponse` carrying `request_id`, reporting failure via
+    /// its `error` field rather than returning `Err` - that's how the
+    /// protocol already reports proxied HTTP failures back to Python.
+    pub async fn fetch(
+        &self,
+        request_id: String,
+        method: HttpMethod,
+        url: String,
+        headers: HashMap<String, String>,
+        body: Option<Vec<u8>>,
+        timeout_ms: u64,
+    ) -> Response {
+        if !self.coalesce_methods.contains(&method) {
+            let deadline = Arc::new(StdMutex::new(
+                Instant::now() + Duration::from_millis(timeout_ms),
+            ));
+            let outcome = self
+                .execute(
+                    method,
+                    &url,
+                    &headers,
+                    body,
+                    deadline,
+                    Arc::new(Notify::new()),
+                )
+                .await;
+            return Self::to_response(request_id, outcome);
+        }
+
+        let key = CoalesceKey::new(method.clone(), &url, &headers, &body);
+        let requested_deadline = Instant::now() + Duration::from_millis(timeout_ms);
+
+        let (inflight, is_leader) = match self.inflight.entry(key.clone()) {
+            Entry::Occupied(entry) => {
+                let inflight = entry.get().clone();
+                let mut deadline = inflight.deadline.lock().unwrap();
+                if requested_deadline < *deadline {
+                    *deadline = requested_deadline;
+                    drop(deadline);
+                    inflight.deadline_changed.notify_one();
+                } else {
+                    drop(deadline);
+                }
+                (inflight, false)
+            }
+            Entry::Vacant(entry) => {
+                let (tx, _rx) = broadcast::channel(1);
+                let inflight = Inflight {
+                    tx,
+                    deadline: Arc::new(StdMutex::new(requested_deadline)),
+                    deadline_changed: Arc::new(Notify::new()),
+                };
+                entry.insert(inflight.clone());
+                (inflight, true)
+            }
+        };
+
+        if !is_leader {
+            let mut rx = inflight.tx.subscribe();
+            let outcome =
+                match tokio::time::timeout(Duration::from_millis(timeout_ms), rx.recv()).await {
+                    Ok(Ok(outcome)) => outcome,
+                    Ok(Err(_)) => Err("in-flight request sender dropped".to_string()),
+                    Err(_) => Err("timed out waiting for in-flight request".to_string()),
+                };
+            return Self::to_response(request_id, outcome);
+        }
+
+        let outcome = self
+            .execute(
+                method,
+                &url,
+                &headers,
+                body,
+                inflight.deadline.clone(),
+                inflight.deadline_changed.clone(),
+            )
+            .await;
+
+        // Always remove the entry on completion, success or error, so a
+        // failed request never poisons later callers for the same key.
+        self.inflight.remove(&key);
+        let _ = inflight.tx.send(outcome.clone());
+
+        Self::to_response(request_id, outcome)
+    }
+
+    /// Perform the real `reqwest` call, racing it against `deadline` rather
+    /// than a fixed `reqwest` timeout so a follower joining later with a
+    /// sooner deadline can still cut it short. `deadline_changed` is what
+    /// makes that actually work: without it, a follower shrinking `deadline`
+    /// while this loop is parked in `sleep_until` would go unnoticed until
+    /// the stale, longer deadline it's already asleep against elapses.
+    async fn execute(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: &HashMap<String, String>,
+        body: Option<Vec<u8>>,
+        deadline: Arc<StdMutex<Instant>>,
+        deadline_changed: Arc<Notify>,
+    ) -> Result<HttpOutcome, String> {
+        let mut builder = self.client.request(Self::reqwest_method(method), url);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = body {
+            builder = builder.body(body);
+        }
+
+        let send_fut = builder.send();
+        tokio::pin!(send_fut);
+
+        let response = loop {
+            let sleep_until = *deadline.lock().unwrap();
+            tokio::select! {
+                result = &mut send_fut => break result.map_err(|e| e.to_string())?,
+                _ = tokio::time::sleep_until(sleep_until.into()) => {
+                    if Instant::now() >= *deadline.lock().unwrap() {
+                        return Err("timed out waiting for upstream response".to_string());
+                    }
+                    // The deadline shrank again between our read and now -
+                    // loop around and sleep until the newer one instead.
+                }
+                _ = deadline_changed.notified() => {
+                    // A follower shrank the deadline while we were asleep -
+                    // loop around and re-read it instead of waiting out the
+                    // sleep we already armed.
+                }
+            }
+        };
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.to_string(),
+                    value.to_str().unwrap_or_default().to_string(),
+                )
+            })
+            .collect();
+        let body = response.bytes().await.map_err(|e| e.to_string())?.to_vec();
+
+        Ok(HttpOutcome {
+            status,
+            headers,
+            body,
+        })
+    }
+
+    fn reqwest_method(method: HttpMethod) -> reqwest::Method {
+        match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Head => reqwest::Method::HEAD,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+        }
+    }
+
+    fn to_response(request_id: String, outcome: Result<HttpOutcome, String>) -> Response {
+        match outcome {
+            Ok(HttpOutcome {
+                status,
+                headers,
+                body,
+            }) => Response::HttpResponse {
+                request_id,
+                status,
+                headers,
+                body,
+                error: None,
+            },
+            Err(error) => Response::HttpResponse {
+                request_id,
+                status: 0,
+                headers: HashMap::new(),
+                body: Vec::new(),
+                error: Some(error),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// Accepts a single connection, waits for `delay`, then replies with a
+    /// minimal 200 OK - standing in for a slow upstream.
+    async fn spawn_slow_server(delay: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = socket.read(&mut buf).await;
+            tokio::time::sleep(delay).await;
+            let _ = socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok")
+                .await;
+        });
+        format!("http://{addr}/")
+    }
+
+    #[tokio::test]
+    async fn a_followers_shrunk_deadline_cuts_the_shared_call_short() {
+        let url = spawn_slow_server(Duration::from_secs(5)).await;
+        let proxy = Arc::new(HttpProxy::new());
+
+        let leader_proxy = proxy.clone();
+        let leader_url = url.clone();
+        let leader = tokio::spawn(async move {
+            let started = Instant::now();
+            let response = leader_proxy
+                .fetch(
+                    "leader".to_string(),
+                    HttpMethod::Get,
+                    leader_url,
+                    HashMap::new(),
+                    None,
+                    10_000,
+                )
+                .await;
+            (started.elapsed(), response)
+        });
+
+        // Give the leader a chance to register the in-flight entry before
+        // the follower joins it.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        proxy
+            .fetch(
+                "follower".to_string(),
+                HttpMethod::Get,
+                url,
+                HashMap::new(),
+                None,
+                100,
+            )
+            .await;
+
+        let (elapsed, response) = leader.await.unwrap();
+        assert!(
+            elapsed < Duration::from_secs(1),
+            "leader waited {elapsed:?} - the follower's shrunk deadline should have cut the shared call short long before its own 10s timeout"
+        );
+        match response {
+            Response::HttpResponse { error: Some(_), .. } => {}
+            other => panic!("expected a timed-out HttpResponse, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_identical_gets_coalesce_into_one_call() {
+        let url = spawn_slow_server(Duration::from_millis(100)).await;
+        let proxy = Arc::new(HttpProxy::new());
+
+        let mut callers = Vec::new();
+        for i in 0..3 {
+            let proxy = proxy.clone();
+            let url = url.clone();
+            callers.push(tokio::spawn(async move {
+                proxy
+                    .fetch(
+                        format!("req-{i}"),
+                        HttpMethod::Get,
+                        url,
+                        HashMap::new(),
+                        None,
+                        5_000,
+                    )
+                    .await
+            }));
+        }
+
+        for (i, caller) in callers.into_iter().enumerate() {
+            match caller.await.unwrap() {
+                Response::HttpResponse {
+                    request_id,
+                    status,
+                    error: None,
+                    ..
+                } => {
+                    assert_eq!(request_id, format!("req-{i}"));
+                    assert_eq!(status, 200);
+                }
+                other => panic!("expected a successful HttpResponse, got {other:?}"),
+            }
+        }
+        assert!(proxy.inflight.is_empty());
+    }
+}
@@ -4,12 +4,15 @@ use super::Result;
 use super::Error;
 use super::Sandbox;
 
+use tokio::sync::mpsc;
 use tracing::debug;
 use tracing::error;
 
 #[derive(Debug)]
 pub(super) struct Integration {
     sandbox: Sandbox,
+    domain: String,
+    options: serde_json::Value,
     state: State,
 }
 
@@ -21,14 +24,19 @@ enum State {
 }
 
 impl Integration {
-    pub fn new(sandbox: Sandbox) -> Self {
+    pub fn new(sandbox: Sandbox, domain: String, options: serde_json::Value) -> Self {
         Self {
             sandbox,
+            domain,
+            options,
             state: State::NotStarted,
         }
     }
 
-    pub async fn run(&mut self) -> Result<()> {
+    /// Drive this sandbox's setup handshake and, once running, forward
+    /// `responses` (commands [`super::Registry`] has routed to this
+    /// instance) down to it.
+    pub async fn run(&mut self, responses: &mut mpsc::Receiver<Response>) -> Result<()> {
         // state machine:
         // 1. Python sends "Ready" message.
         // 2. We send "SetupIntegration" message.
@@ -43,12 +51,9 @@ impl Integration {
                         Message::Ready => {
                             self.sandbox
                                 .send(Response::SetupIntegration {
-                                    // TODO: we probably need an IntegrationBuilder for this, because the
-                                    // integration needs this context and the Sandbox doesn't. Argh!
-                                    // Hardcode for now.
-                                    domain: "met".into(),
-                                    name: "argh".into(),
-                                    config: serde_json::json!({}),
+                                    domain: self.domain.clone(),
+                                    entry_id: self.sandbox.entry_id().to_string(),
+                                    config: self.options.clone(),
                                 })
                                 .await?;
                             self.state = State::AwaitingSetupStatus;
@@ -63,15 +68,19 @@ impl Integration {
                 State::AwaitingSetupStatus => {
                     match self.sandbox.recv().await? {
                         Message::SetupComplete {
-                            name, platforms
+                            entry_id, platforms
                         } => {
-                            debug!("SetupComplete: {:?}: {:?}", name, platforms);
-                            todo!("next state?");
+                            debug!("SetupComplete: {:?}: {:?}", entry_id, platforms);
+                            self.state = State::Running;
                         },
 
-                        Message::SetupFailed{name, error, error_type, missing_package } => {
-                            error!("SetupFailed: {} {} {:?} {:?}", name, error, error_type, missing_package);
-                            todo!("fail properly");
+                        Message::SetupFailed{entry_id, error, error_type, missing_package } => {
+                            error!("SetupFailed: {} {} {:?} {:?}", entry_id, error, error_type, missing_package);
+                            return Err(Error::SetupFailed {
+                                error,
+                                error_type,
+                                missing_package,
+                            })
                         },
 
                         m => return Err(Error::InvalidMessage {
@@ -81,7 +90,18 @@ impl Integration {
                     }
                 },
 
-                State::Running => todo!(),
+                State::Running => {
+                    tokio::select! {
+                        msg = self.sandbox.recv() => {
+                            // Running-state protocol handling (entity
+                            // updates, etc.) isn't implemented yet.
+                            todo!("handle message while running: {:?}", msg?)
+                        }
+                        Some(response) = responses.recv() => {
+                            self.sandbox.send(response).await?;
+                        }
+                    }
+                }
             }
         }
     }
@@ -4,15 +4,24 @@
 //! in a sandboxed Python environment, communicating with the Rust runtime
 //! via Unix domain sockets.
 
+pub mod clock;
+pub mod http_proxy;
+pub mod req_queue;
 pub mod sandbox;
 
+pub use http_proxy::HttpProxy;
 pub use registry::Registry;
+pub use registry::RouteSender;
+pub use req_queue::ReqQueue;
+pub use runtime::Runtime;
 pub use sandbox::Sandbox;
 pub use sandbox::SandboxBuilder;
+pub use sandbox::SandboxConfig;
 
 mod integration;
 mod protocol;
 mod registry;
+mod runtime;
 
 use integration::Integration;
 
@@ -29,6 +38,22 @@ pub enum Error {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    #[error("invalid sandbox configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("sandbox '{0}' is no longer running")]
+    SandboxGone(String),
+
+    #[error("integration setup failed: {error}")]
+    SetupFailed {
+        error: String,
+        error_type: Option<String>,
+        missing_package: Option<String>,
+    },
+
+    #[error("webhook delivery failed: {0}")]
+    Webhook(protocol::ProtocolError),
 }
 
 pub type Result<T> = ::core::result::Result<T, Error>;
@@ -100,6 +100,29 @@ pub enum Message {
         #[serde(skip_serializing_if = "Option::is_none")]
         error: Option<String>,
     },
+
+    /// Keepalive sent by a remote sandbox backend over its TCP connection.
+    /// Local (subprocess/container) backends never emit this.
+    Heartbeat,
+
+    /// Claim a webhook id for this integration at setup, so
+    /// `POST/PUT /v1/webhook/{webhook_id}` knows which sandbox to forward
+    /// deliveries to.
+    WebhookRegister {
+        entry_id: String,
+        webhook_id: String,
+        /// Restrict delivery to loopback/RFC1918 source addresses.
+        local_only: bool,
+    },
+
+    /// Reply to a `Response::WebhookDelivery`, completing the HTTP request
+    /// that's waiting on it.
+    WebhookResponse {
+        request_id: String,
+        status: u16,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -111,10 +134,11 @@ pub enum LogLevel {
     Error,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum HttpMethod {
     Get,
+    Head,
     Post,
     Put,
     Delete,
@@ -153,6 +177,17 @@ pub enum Response {
     /// Request to unload an integration
     UnloadIntegration { entry_id: String },
 
+    /// Invoke a Home Assistant service on a specific entity -
+    /// `<domain>.<service>`, e.g. `light.turn_on` - forwarded down from an
+    /// engine command routed to this sandbox.
+    CallService {
+        entry_id: String,
+        entity_id: String,
+        domain: String,
+        service: String,
+        data: serde_json::Value,
+    },
+
     /// Timer fired, trigger coordinator update
     TriggerUpdate { timer_id: String, entry_id: String },
 
@@ -179,6 +214,21 @@ pub enum Response {
 
     /// Error response
     Error { message: String },
+
+    /// Keepalive reply to a remote sandbox backend's `Message::Heartbeat`.
+    Heartbeat,
+
+    /// An inbound webhook call forwarded to the owning integration. The
+    /// `request_id` isn't part of the literal HA webhook concept, but is
+    /// needed to correlate the eventual `Message::WebhookResponse` back to
+    /// the HTTP request that's waiting on it.
+    WebhookDelivery {
+        request_id: String,
+        webhook_id: String,
+        method: HttpMethod,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    },
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -192,6 +242,17 @@ pub enum ProtocolError {
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+
+    /// Fired to a [`super::req_queue::ReqQueue`] waiter by its reaper when
+    /// no reply arrived before the registered deadline.
+    #[error("request {request_id} timed out waiting for a reply")]
+    Timeout { request_id: String },
+
+    /// Fired to every outstanding `ReqQueue` waiter when the sandbox they
+    /// were waiting on is torn down (`UnloadComplete`/shutdown) before
+    /// replying.
+    #[error("request {request_id} cancelled: {reason}")]
+    Cancelled { request_id: String, reason: String },
 }
 
 #[allow(dead_code)] // WIP: Will be used for protocol operations
@@ -1,34 +1,199 @@
+use super::protocol::HttpMethod;
+use super::protocol::Response;
+use super::req_queue::ReqQueue;
 use super::Integration;
 use super::Result;
 use super::SandboxBuilder;
 
 use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use tracing::warn;
+use uuid::Uuid;
+
+/// How long a forwarded webhook delivery waits for the owning integration's
+/// reply before the caller gets back `Error::Webhook(ProtocolError::Timeout)`.
+const WEBHOOK_REPLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Body of a completed webhook delivery, handed back to the HTTP caller.
+#[derive(Debug, Clone)]
+pub struct WebhookReply {
+    pub status: u16,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+/// Which instance owns a registered webhook id, and whether it's
+/// restricted to loopback/RFC1918 callers.
+#[derive(Debug, Clone)]
+pub struct WebhookOwner {
+    pub entry_id: String,
+    pub local_only: bool,
+}
+
+/// Handle for routing a command down to one registered sandbox instance,
+/// without exposing the wire protocol to callers outside `ha`.
+#[derive(Clone)]
+pub struct RouteSender {
+    id: String,
+    tx: mpsc::Sender<Response>,
+    /// Webhook deliveries awaiting the integration's `Message::WebhookResponse`
+    /// reply, completed by [`RouteSender::complete_webhook`].
+    webhook_replies: Arc<StdMutex<ReqQueue<WebhookReply>>>,
+}
+
+impl std::fmt::Debug for RouteSender {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RouteSender").field("id", &self.id).finish()
+    }
+}
+
+impl RouteSender {
+    /// Forward a generic HA service call (`<domain>.<service>`) to this
+    /// instance's Python sandbox.
+    pub async fn call_service(
+        &self,
+        entity_id: String,
+        domain: String,
+        service: String,
+        data: serde_json::Value,
+    ) -> Result<()> {
+        self.tx
+            .send(Response::CallService {
+                entry_id: self.id.clone(),
+                entity_id,
+                domain,
+                service,
+                data,
+            })
+            .await
+            .map_err(|_| super::Error::SandboxGone(self.id.clone()))
+    }
+
+    /// Forward an inbound webhook call to this instance's Python sandbox
+    /// and wait for its `Message::WebhookResponse` reply.
+    pub async fn deliver_webhook(
+        &self,
+        webhook_id: String,
+        method: HttpMethod,
+        headers: HashMap<String, String>,
+        body: Vec<u8>,
+    ) -> Result<WebhookReply> {
+        let request_id = Uuid::new_v4().to_string();
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.webhook_replies.lock().unwrap().outgoing.register(
+            request_id.clone(),
+            reply_tx,
+            Instant::now() + WEBHOOK_REPLY_TIMEOUT,
+        );
+
+        self.tx
+            .send(Response::WebhookDelivery {
+                request_id,
+                webhook_id,
+                method,
+                headers,
+                body,
+            })
+            .await
+            .map_err(|_| super::Error::SandboxGone(self.id.clone()))?;
+
+        reply_rx
+            .await
+            .map_err(|_| super::Error::SandboxGone(self.id.clone()))?
+            .map_err(super::Error::Webhook)
+    }
+
+    /// Complete a previously delivered webhook once its
+    /// `Message::WebhookResponse` reply arrives. Not yet called by any
+    /// message-dispatch loop - see this method's introducing commit.
+    pub fn complete_webhook(&self, request_id: &str, reply: WebhookReply) {
+        self.webhook_replies
+            .lock()
+            .unwrap()
+            .outgoing
+            .complete(request_id, reply);
+    }
+}
 
 /// Registry for storing and managing the lifetime of running HA sandboxes.
 #[derive(Debug, Default)]
 pub struct Registry {
-    integrations: BTreeMap<String, Integration>,
+    integrations: BTreeMap<String, (Integration, mpsc::Receiver<Response>)>,
+    senders: BTreeMap<String, RouteSender>,
+    webhooks: BTreeMap<String, WebhookOwner>,
 }
 
 impl Registry {
-    pub async fn register(&mut self, builder: SandboxBuilder) -> super::Result<()> {
+    pub async fn register(
+        &mut self,
+        builder: SandboxBuilder,
+        domain: String,
+        options: serde_json::Value,
+    ) -> super::Result<()> {
         let sb = builder.try_into_sandbox().await?;
-        self.integrations.insert(builder.name, Integration::new(sb));
+        let (tx, rx) = mpsc::channel(16);
+        self.senders.insert(
+            builder.name.clone(),
+            RouteSender {
+                id: builder.name.clone(),
+                tx,
+                webhook_replies: Arc::new(StdMutex::new(ReqQueue::new())),
+            },
+        );
+        self.integrations
+            .insert(builder.name, (Integration::new(sb, domain, options), rx));
         Ok(())
     }
 
-    pub async fn run(&mut self) -> Result<()> {
-        if self.integrations.len() > 1 {
-            todo!("Registry::run with >1 integrations");
-        }
-        if self.integrations.is_empty() {
-            return Ok(());
+    /// Claim `webhook_id` for `entry_id`, called when a
+    /// `Message::WebhookRegister` arrives from that integration's sandbox.
+    pub fn register_webhook(&mut self, webhook_id: String, entry_id: String, local_only: bool) {
+        self.webhooks.insert(
+            webhook_id,
+            WebhookOwner {
+                entry_id,
+                local_only,
+            },
+        );
+    }
+
+    /// Which instance owns `webhook_id`, if one has claimed it.
+    pub fn webhook_owner(&self, webhook_id: &str) -> Option<&WebhookOwner> {
+        self.webhooks.get(webhook_id)
+    }
+
+    /// Route senders for every registered instance, keyed by instance id -
+    /// clone these out before [`Registry::run`] consumes the registry.
+    pub fn senders(&self) -> BTreeMap<String, RouteSender> {
+        self.senders.clone()
+    }
+
+    /// Run every registered sandbox's integration concurrently until they
+    /// all exit.
+    pub async fn run(self) -> Result<()> {
+        let mut set = JoinSet::new();
+        for (name, (mut integration, mut rx)) in self.integrations {
+            set.spawn(async move {
+                let result = integration.run(&mut rx).await;
+                (name, result)
+            });
         }
 
-        if let Some(i) = self.integrations.values_mut().next() {
-            i.run().await
-        } else {
-            Ok(())
+        while let Some(joined) = set.join_next().await {
+            let (name, result) = joined.expect("HA integration task panicked");
+            if let Err(e) = result {
+                warn!("[{}] integration exited: {}", name, e);
+            }
         }
+
+        Ok(())
     }
 }
@@ -0,0 +1,280 @@
+//! Request/response correlation, modeled on `lsp-server`'s `req_queue`.
+//!
+//! The wire protocol (see [`super::protocol`]) scatters `request_id`
+//! correlation across its message types with no central tracking, so late
+//! or duplicate `Response`s and timed-out calls (`GetConfig`,
+//! `HttpRequest`, ...) end up handled ad hoc wherever they're sent.
+//! [`ReqQueue`] centralizes that: [`ReqQueue::outgoing`] tracks requests
+//! this side sent Python and is still awaiting a reply for (with a
+//! deadline, reaped in the background by [`Outgoing::reap_expired`]), and
+//! [`ReqQueue::incoming`] tracks requests Python sent us that we still owe
+//! a response to (no deadline - just bookkeeping for bulk cancellation).
+
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::protocol::ProtocolError;
+
+/// An outgoing request's deadline, ordered soonest-first for use in a
+/// min-heap (mirrors [`super::runtime::Timer`]'s same trick for
+/// `ScheduleUpdate` timers).
+struct Deadline {
+    at: Instant,
+    request_id: String,
+}
+
+impl PartialEq for Deadline {
+    fn eq(&self, other: &Self) -> bool {
+        self.at == other.at
+    }
+}
+impl Eq for Deadline {}
+impl PartialOrd for Deadline {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Deadline {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.at.cmp(&other.at)
+    }
+}
+
+/// Requests this side sent Python, awaiting a correlated reply.
+pub struct Outgoing<T> {
+    pending: HashMap<String, oneshot::Sender<Result<T, ProtocolError>>>,
+    deadlines: BinaryHeap<Reverse<Deadline>>,
+}
+
+impl<T> Default for Outgoing<T> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            deadlines: BinaryHeap::new(),
+        }
+    }
+}
+
+impl<T> Outgoing<T> {
+    /// Register a just-sent request: `reply` is fired with the result once
+    /// a `Response` with this `request_id` arrives (see
+    /// [`Self::complete`]), or with [`ProtocolError::Timeout`] if `deadline`
+    /// passes first (see [`Self::reap_expired`]).
+    pub fn register(
+        &mut self,
+        request_id: String,
+        reply: oneshot::Sender<Result<T, ProtocolError>>,
+        deadline: Instant,
+    ) {
+        self.deadlines.push(Reverse(Deadline {
+            at: deadline,
+            request_id: request_id.clone(),
+        }));
+        self.pending.insert(request_id, reply);
+    }
+
+    /// Complete the request matching `request_id`, if still pending.
+    /// Unknown ids (a duplicate or late reply, or one already reaped for
+    /// timing out) are ignored rather than treated as an error - the
+    /// request body is explicit that this must not panic.
+    pub fn complete(&mut self, request_id: &str, value: T) {
+        if let Some(reply) = self.pending.remove(request_id) {
+            let _ = reply.send(Ok(value));
+        }
+    }
+
+    /// Number of requests still awaiting a reply or timeout.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Fire every expired entry's reply with [`ProtocolError::Timeout`] and
+    /// drop it. Called periodically by a background reaper task - see
+    /// [`ReqQueue::spawn_reaper`].
+    pub fn reap_expired(&mut self, now: Instant) {
+        while let Some(Reverse(deadline)) = self.deadlines.peek() {
+            if deadline.at > now {
+                break;
+            }
+            let Reverse(deadline) = self.deadlines.pop().unwrap();
+            if let Some(reply) = self.pending.remove(&deadline.request_id) {
+                let _ = reply.send(Err(ProtocolError::Timeout {
+                    request_id: deadline.request_id,
+                }));
+            }
+        }
+    }
+
+    /// Fire every still-pending reply with [`ProtocolError::Cancelled`] and
+    /// clear the queue - called when the sandbox they were sent to is torn
+    /// down (`UnloadComplete`/shutdown) before it could reply.
+    pub fn cancel_all(&mut self, reason: &str) {
+        self.deadlines.clear();
+        for (request_id, reply) in self.pending.drain() {
+            let _ = reply.send(Err(ProtocolError::Cancelled {
+                request_id,
+                reason: reason.to_string(),
+            }));
+        }
+    }
+}
+
+/// Requests Python sent us that we still owe a response to. No deadline:
+/// answering them is this side's own responsibility (e.g.
+/// [`super::http_proxy::HttpProxy`] already has its own `timeout_ms`), so
+/// this is just bookkeeping for [`Self::pending_count`] and bulk
+/// cancellation.
+#[derive(Default)]
+pub struct Incoming {
+    pending: HashSet<String>,
+}
+
+impl Incoming {
+    /// Record that we now owe a response for `request_id`.
+    pub fn register(&mut self, request_id: String) {
+        self.pending.insert(request_id);
+    }
+
+    /// Record that `request_id` has been answered.
+    pub fn complete(&mut self, request_id: &str) {
+        self.pending.remove(request_id);
+    }
+
+    /// Number of requests we still owe a response to.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Forget every outstanding request - called alongside
+    /// [`Outgoing::cancel_all`] when the sandbox they came from goes away,
+    /// since there's no one left to send a response to.
+    pub fn cancel_all(&mut self) {
+        self.pending.clear();
+    }
+}
+
+/// Correlates outgoing requests (sent to Python, awaiting a reply) and
+/// incoming ones (sent by Python, awaiting our response) for one sandbox
+/// connection.
+#[derive(Default)]
+pub struct ReqQueue<T> {
+    pub outgoing: Outgoing<T>,
+    pub incoming: Incoming,
+}
+
+impl<T> ReqQueue<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Total requests still in flight in either direction - what the
+    /// integration lifecycle checks before deciding there's nothing left to
+    /// cancel on `UnloadComplete`/shutdown.
+    pub fn pending_count(&self) -> usize {
+        self.outgoing.pending_count() + self.incoming.pending_count()
+    }
+
+    /// Cancel every request in flight in either direction, e.g. on
+    /// `UnloadComplete`/shutdown: outgoing waiters get
+    /// [`ProtocolError::Cancelled`], and incoming bookkeeping is simply
+    /// forgotten since there's no longer anyone to respond to.
+    pub fn cancel_all(&mut self, reason: &str) {
+        self.outgoing.cancel_all(reason);
+        self.incoming.cancel_all();
+    }
+}
+
+impl<T: Send + 'static> ReqQueue<T> {
+    /// Spawn a background task that periodically reaps [`Self::outgoing`]
+    /// entries past their deadline, firing [`ProtocolError::Timeout`] to
+    /// each one. Mirrors the `JoinHandle`-based background tasks already
+    /// spawned in `engine::Engine`. Drop the returned handle to stop it.
+    pub fn spawn_reaper(queue: Arc<StdMutex<Self>>, check_interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(check_interval);
+            loop {
+                ticker.tick().await;
+                queue.lock().unwrap().outgoing.reap_expired(Instant::now());
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn complete_resolves_the_matching_waiter() {
+        let mut outgoing: Outgoing<&'static str> = Outgoing::default();
+        let (tx, rx) = oneshot::channel();
+        outgoing.register("1".to_string(), tx, Instant::now() + Duration::from_secs(5));
+
+        outgoing.complete("1", "pong");
+
+        assert_eq!(rx.await.unwrap().unwrap(), "pong");
+        assert_eq!(outgoing.pending_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn complete_with_unknown_id_is_ignored() {
+        let mut outgoing: Outgoing<&'static str> = Outgoing::default();
+        let (tx, rx) = oneshot::channel();
+        outgoing.register("1".to_string(), tx, Instant::now() + Duration::from_secs(5));
+
+        outgoing.complete("not-registered", "pong");
+
+        assert_eq!(outgoing.pending_count(), 1);
+        drop(outgoing);
+        assert!(rx.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reap_expired_times_out_only_past_deadline_entries() {
+        let mut outgoing: Outgoing<&'static str> = Outgoing::default();
+        let now = Instant::now();
+        let (tx_soon, rx_soon) = oneshot::channel();
+        let (tx_later, rx_later) = oneshot::channel();
+        outgoing.register("soon".to_string(), tx_soon, now + Duration::from_secs(1));
+        outgoing.register("later".to_string(), tx_later, now + Duration::from_secs(60));
+
+        outgoing.reap_expired(now + Duration::from_secs(2));
+
+        assert!(matches!(
+            rx_soon.await.unwrap(),
+            Err(ProtocolError::Timeout { .. })
+        ));
+        assert_eq!(outgoing.pending_count(), 1);
+        drop(outgoing);
+        assert!(rx_later.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_all_fires_every_outgoing_waiter_and_clears_incoming() {
+        let mut queue: ReqQueue<&'static str> = ReqQueue::new();
+        let (tx, rx) = oneshot::channel();
+        queue
+            .outgoing
+            .register("1".to_string(), tx, Instant::now() + Duration::from_secs(5));
+        queue.incoming.register("2".to_string());
+
+        queue.cancel_all("sandbox torn down");
+
+        assert!(matches!(
+            rx.await.unwrap(),
+            Err(ProtocolError::Cancelled { .. })
+        ));
+        assert_eq!(queue.pending_count(), 0);
+    }
+}
@@ -1,8 +1,43 @@
 //! Runtime coordination for Home Assistant integrations.
 
+use super::clock::Clock;
+use super::clock::SystemClock;
 use super::protocol::{Message, Response};
-use crate::config::{LocationConfig, HaIntegrationConfig};
+use super::registry::WebhookOwner;
+use crate::config::{HaIntegrationConfig, LocationConfig};
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// A pending `ScheduleUpdate` timer, ordered by deadline (soonest first)
+/// for use in a min-heap.
+#[derive(Debug, Clone)]
+struct Timer {
+    deadline: Duration,
+    timer_id: String,
+    entry_id: String,
+    /// Re-armed with this interval each time the timer fires.
+    interval: Duration,
+}
+
+impl PartialEq for Timer {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+impl Eq for Timer {}
+impl PartialOrd for Timer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Timer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
 
 /// Entity state and metadata
 #[derive(Debug, Clone)]
@@ -26,6 +61,17 @@ pub struct Runtime {
 
     /// HA integration configurations, indexed by entry_id
     ha_configs: HashMap<String, serde_json::Value>,
+
+    /// Source of monotonic time for the timer subsystem, injectable so
+    /// tests can advance it deterministically instead of sleeping.
+    clock: Box<dyn Clock>,
+
+    /// Pending `ScheduleUpdate` timers, keyed by fire time.
+    timers: BinaryHeap<Reverse<Timer>>,
+
+    /// Webhook ids claimed by integrations via `Message::WebhookRegister`,
+    /// keyed by webhook id.
+    webhooks: HashMap<String, WebhookOwner>,
 }
 
 #[derive(Debug, Clone)]
@@ -35,21 +81,62 @@ struct IntegrationState {
 }
 
 impl Runtime {
-    /// Create a new runtime instance with location config
+    /// Create a new runtime instance with location config, using the
+    /// system clock for timers.
     pub fn new(location: LocationConfig) -> Self {
+        Self::with_clock(location, Box::new(SystemClock::new()))
+    }
+
+    /// Create a new runtime instance with an injected clock, e.g. a
+    /// `MockClock` for deterministic automation tests.
+    pub fn with_clock(location: LocationConfig, clock: Box<dyn Clock>) -> Self {
         Self {
             entities: HashMap::new(),
             integrations: HashMap::new(),
             location,
             ha_configs: HashMap::new(),
+            clock,
+            timers: BinaryHeap::new(),
+            webhooks: HashMap::new(),
         }
     }
 
+    /// Drain and return every timer whose deadline has passed, re-arming
+    /// periodic ones for their next interval. Called by the engine on each
+    /// tick to produce the `Response::TriggerUpdate`s that fire callbacks.
+    pub fn poll_due(&mut self) -> Vec<Response> {
+        let now = self.clock.elapsed();
+        let mut due = Vec::new();
+
+        while let Some(Reverse(timer)) = self.timers.peek() {
+            if timer.deadline > now {
+                break;
+            }
+            let Reverse(timer) = self.timers.pop().unwrap();
+            due.push(Response::TriggerUpdate {
+                timer_id: timer.timer_id.clone(),
+                entry_id: timer.entry_id.clone(),
+            });
+            self.timers.push(Reverse(Timer {
+                deadline: now + timer.interval,
+                ..timer
+            }));
+        }
+
+        due
+    }
+
     /// Register an HA integration config
     pub fn register_ha_config(&mut self, entry_id: String, config: serde_json::Value) {
         self.ha_configs.insert(entry_id, config);
     }
 
+    /// Which instance claimed `webhook_id`, if any, and whether delivery
+    /// should be restricted to loopback/RFC1918 callers.
+    pub fn webhook_owner(&self, webhook_id: &str) -> Option<&WebhookOwner> {
+        self.webhooks.get(webhook_id)
+    }
+
     /// Handle a message from the Python sandbox
     /// Returns an optional response to send back
     pub fn handle_message(&mut self, message: Message) -> Option<Response> {
@@ -85,7 +172,10 @@ impl Runtime {
                 }
                 None
             }
-            Message::SetupComplete { entry_id, platforms: _ } => {
+            Message::SetupComplete {
+                entry_id,
+                platforms: _,
+            } => {
                 tracing::info!("Integration {} setup complete", entry_id);
                 None
             }
@@ -93,7 +183,11 @@ impl Runtime {
                 tracing::error!("Integration {} failed to load: {}", entry_id, error);
                 None
             }
-            Message::Log { level, logger, message } => {
+            Message::Log {
+                level,
+                logger,
+                message,
+            } => {
                 use super::protocol::LogLevel;
                 match level {
                     LogLevel::Debug => tracing::debug!("[{}] {}", logger, message),
@@ -135,10 +229,61 @@ impl Runtime {
 
                 Some(Response::ConfigResponse { request_id, config })
             }
+            Message::ScheduleUpdate {
+                timer_id,
+                entry_id,
+                interval_seconds,
+            } => {
+                let interval = Duration::from_secs(interval_seconds);
+                let deadline = self.clock.elapsed() + interval;
+                self.timers.push(Reverse(Timer {
+                    deadline,
+                    timer_id,
+                    entry_id,
+                    interval,
+                }));
+                None
+            }
+            Message::CancelTimer { timer_id } => {
+                self.timers = self
+                    .timers
+                    .drain()
+                    .filter(|Reverse(timer)| timer.timer_id != timer_id)
+                    .collect();
+                None
+            }
+            // Heartbeats are handled by the sandbox backend itself and
+            // should never reach the runtime, but reply in kind just in
+            // case one slips through.
+            Message::Heartbeat => Some(Response::Heartbeat),
+            Message::WebhookRegister {
+                entry_id,
+                webhook_id,
+                local_only,
+            } => {
+                self.webhooks.insert(
+                    webhook_id,
+                    WebhookOwner {
+                        entry_id,
+                        local_only,
+                    },
+                );
+                None
+            }
+            // Completing the HTTP request that's waiting on this reply
+            // needs the `RouteSender` that issued the `WebhookDelivery`
+            // (see `RouteSender::complete_webhook`), which `Runtime` has no
+            // reference to - left for whatever wires `Runtime` and
+            // `Registry` together.
+            Message::WebhookResponse { request_id, .. } => {
+                tracing::warn!(
+                    "WebhookResponse {} received but Runtime has no route back to the waiting HTTP request",
+                    request_id
+                );
+                None
+            }
             // TODO: Handle remaining message types
             Message::HttpRequest { .. }
-            | Message::ScheduleUpdate { .. }
-            | Message::CancelTimer { .. }
             | Message::UnloadComplete { .. }
             | Message::UpdateComplete { .. } => {
                 tracing::warn!("Unhandled message type: {:?}", message);
@@ -157,3 +302,77 @@ impl Runtime {
         self.entities.values()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ha::clock::MockClock;
+
+    fn test_location() -> LocationConfig {
+        LocationConfig {
+            latitude: 0.0,
+            longitude: 0.0,
+            elevation: 0,
+            timezone: "UTC".to_string(),
+        }
+    }
+
+    fn runtime_with_mock_clock() -> (Runtime, MockClock) {
+        let clock = MockClock::new();
+        let runtime = Runtime::with_clock(test_location(), Box::new(clock.clone()));
+        (runtime, clock)
+    }
+
+    #[test]
+    fn schedule_update_fires_after_interval_elapses() {
+        let (mut runtime, clock) = runtime_with_mock_clock();
+        runtime.handle_message(Message::ScheduleUpdate {
+            timer_id: "t1".into(),
+            entry_id: "e1".into(),
+            interval_seconds: 10,
+        });
+
+        assert!(runtime.poll_due().is_empty());
+
+        clock.advance(Duration::from_secs(10));
+        let due = runtime.poll_due();
+        assert_eq!(due.len(), 1);
+        assert!(matches!(
+            &due[0],
+            Response::TriggerUpdate { timer_id, entry_id }
+                if timer_id == "t1" && entry_id == "e1"
+        ));
+    }
+
+    #[test]
+    fn schedule_update_is_periodic() {
+        let (mut runtime, clock) = runtime_with_mock_clock();
+        runtime.handle_message(Message::ScheduleUpdate {
+            timer_id: "t1".into(),
+            entry_id: "e1".into(),
+            interval_seconds: 5,
+        });
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(runtime.poll_due().len(), 1);
+
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(runtime.poll_due().len(), 1);
+    }
+
+    #[test]
+    fn cancel_timer_removes_pending_timer() {
+        let (mut runtime, clock) = runtime_with_mock_clock();
+        runtime.handle_message(Message::ScheduleUpdate {
+            timer_id: "t1".into(),
+            entry_id: "e1".into(),
+            interval_seconds: 5,
+        });
+        runtime.handle_message(Message::CancelTimer {
+            timer_id: "t1".into(),
+        });
+
+        clock.advance(Duration::from_secs(5));
+        assert!(runtime.poll_due().is_empty());
+    }
+}
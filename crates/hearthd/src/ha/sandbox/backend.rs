@@ -0,0 +1,28 @@
+//! Abstraction over how a sandbox's Python runner is actually executed.
+
+use async_trait::async_trait;
+
+use crate::ha::protocol::Message;
+use crate::ha::protocol::ProtocolError;
+use crate::ha::protocol::Response;
+
+/// A running (or not-yet-started) Python runner, speaking the
+/// newline-delimited-JSON protocol regardless of how it's actually hosted.
+///
+/// Implementations own the transport - a direct subprocess sharing a
+/// socketpair, a container's attach stream, etc. - so [`super::Sandbox`]
+/// can stay agnostic to it.
+#[async_trait]
+pub trait SandboxBackend: Send {
+    /// Start the Python runner and establish the protocol stream.
+    async fn start(&mut self) -> Result<(), ProtocolError>;
+
+    /// Send a response to the Python runner.
+    async fn send(&mut self, response: Response) -> Result<(), ProtocolError>;
+
+    /// Receive a message from the Python runner.
+    async fn recv(&mut self) -> Result<Message, ProtocolError>;
+
+    /// Stop the Python runner, waiting briefly for a graceful exit.
+    async fn stop(&mut self) -> Result<(), ProtocolError>;
+}
@@ -0,0 +1,160 @@
+//! Configuration for selecting and tuning a sandbox's execution backend.
+
+use std::collections::HashMap;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::Validate;
+use hearthd_config::ValidationError;
+use serde::Deserialize;
+
+fn default_memory_limit_mb() -> u64 {
+    512
+}
+
+fn default_cpu_limit() -> f64 {
+    1.0
+}
+
+/// Selects how a sandbox's Python runner is actually executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SandboxBackendKind {
+    /// Spawn the runner as a direct child process sharing a socketpair
+    /// with the parent. No filesystem or network isolation.
+    #[default]
+    Process,
+
+    /// Run the runner inside a Docker/OCI container, with the HA source
+    /// mounted read-only and no network egress.
+    Container,
+
+    /// Run the runner on a remote node, reachable over TCP per the
+    /// matching entry in `nodes`.
+    Remote,
+}
+
+/// A remote node capable of hosting sandboxed runners, addressed by name
+/// from `SandboxConfig::node`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeConfig {
+    /// Hostname or IP address of the node's hearthd-agent.
+    pub host: String,
+
+    /// Port the node's hearthd-agent listens on.
+    pub port: u16,
+}
+
+/// Sandbox backend configuration for an HA integration.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SandboxConfig {
+    #[serde(default)]
+    pub backend: SandboxBackendKind,
+
+    /// Container image to run the Python runner in. Required when
+    /// `backend` is `container`.
+    #[serde(default)]
+    pub image: Option<String>,
+
+    /// Memory limit in megabytes. Enforced only by the container backend.
+    #[serde(default = "default_memory_limit_mb")]
+    pub memory_limit_mb: u64,
+
+    /// CPU limit in cores. Enforced only by the container backend.
+    #[serde(default = "default_cpu_limit")]
+    pub cpu_limit: f64,
+
+    /// Name of the node to spawn the runner on, looked up in `nodes`.
+    /// Required when `backend` is `remote`.
+    #[serde(default)]
+    pub node: Option<String>,
+
+    /// Registry of remote nodes this sandbox's `node` may reference.
+    #[serde(default)]
+    pub nodes: HashMap<String, NodeConfig>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        Self {
+            backend: SandboxBackendKind::default(),
+            image: None,
+            memory_limit_mb: default_memory_limit_mb(),
+            cpu_limit: default_cpu_limit(),
+            node: None,
+            nodes: HashMap::new(),
+        }
+    }
+}
+
+impl Validate for SandboxConfig {
+    fn validate(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        if self.backend == SandboxBackendKind::Container && self.image.is_none() {
+            diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
+                field_path: "sandbox.image".to_string(),
+                message: "image is required when backend is \"container\"".to_string(),
+                span: None,
+                source: None,
+                suggestions: vec![],
+            })));
+        }
+
+        if self.backend == SandboxBackendKind::Remote {
+            match &self.node {
+                None => {
+                    diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
+                        field_path: "sandbox.node".to_string(),
+                        message: "node is required when backend is \"remote\"".to_string(),
+                        span: None,
+                        source: None,
+                        suggestions: vec![],
+                    })));
+                }
+                Some(node) if !self.nodes.contains_key(node) => {
+                    diagnostics.push(Diagnostic::Error(Error::Validation(ValidationError {
+                        field_path: "sandbox.node".to_string(),
+                        message: format!("node '{}' not found in sandbox.nodes", node),
+                        span: None,
+                        source: None,
+                        suggestions: vec![],
+                    })));
+                }
+                Some(_) => {}
+            }
+        }
+
+        diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn process_backend_needs_no_image() {
+        let config = SandboxConfig::default();
+        assert!(config.validate().is_empty());
+    }
+
+    #[test]
+    fn container_backend_without_image_fails_validation() {
+        let config = SandboxConfig {
+            backend: SandboxBackendKind::Container,
+            ..SandboxConfig::default()
+        };
+        assert_eq!(config.validate().len(), 1);
+    }
+
+    #[test]
+    fn container_backend_with_image_passes_validation() {
+        let config = SandboxConfig {
+            backend: SandboxBackendKind::Container,
+            image: Some("hearthd/ha-runner:latest".to_string()),
+            ..SandboxConfig::default()
+        };
+        assert!(config.validate().is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+//! Runs the Python runner inside a Docker/OCI container, trading the
+//! socketpair transport for the container's attach stream in exchange for
+//! filesystem and network isolation.
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use shiplift::builder::ContainerOptionsBuilder;
+use shiplift::builder::RmContainerOptionsBuilder;
+use shiplift::Docker;
+use shiplift::tty::TtyChunk;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::StreamExt;
+
+use super::backend::SandboxBackend;
+use super::config::SandboxConfig;
+use crate::ha::protocol::{Message, ProtocolError, Response};
+
+/// Runs the Python runner inside a container, mounting the HA source
+/// read-only and enforcing memory/CPU limits with no network egress.
+pub struct ContainerBackend {
+    entry_id: String,
+    image: String,
+    ha_source_path: PathBuf,
+    memory_limit_mb: u64,
+    cpu_limit: f64,
+
+    docker: Docker,
+    container_id: Option<String>,
+    attach_stdin: Option<Box<dyn tokio::io::AsyncWrite + Send + Unpin>>,
+    attach_stdout:
+        Option<Box<dyn futures::Stream<Item = shiplift::Result<TtyChunk>> + Send + Unpin>>,
+    /// Bytes received but not yet consumed up to a newline.
+    recv_buffer: Vec<u8>,
+}
+
+impl ContainerBackend {
+    /// Create a new container backend from the sandbox's resolved config.
+    /// `config.image` must be `Some` (validated by [`SandboxConfig::validate`]
+    /// before a container backend is ever constructed).
+    pub fn new(entry_id: String, ha_source_path: PathBuf, config: &SandboxConfig) -> Self {
+        Self {
+            entry_id,
+            image: config.image.clone().unwrap_or_default(),
+            ha_source_path,
+            memory_limit_mb: config.memory_limit_mb,
+            cpu_limit: config.cpu_limit,
+            docker: Docker::new(),
+            container_id: None,
+            attach_stdin: None,
+            attach_stdout: None,
+            recv_buffer: Vec::new(),
+        }
+    }
+
+    /// Read a newline-delimited JSON message out of the attach stream,
+    /// buffering any bytes read past the terminating newline for next time.
+    async fn read_line(&mut self) -> Result<String, ProtocolError> {
+        loop {
+            if let Some(pos) = self.recv_buffer.iter().position(|&b| b == b'\n') {
+                let line = self.recv_buffer.drain(..=pos).collect::<Vec<u8>>();
+                return Ok(String::from_utf8_lossy(&line[..line.len() - 1]).into_owned());
+            }
+
+            let stdout = self.attach_stdout.as_mut().ok_or_else(|| {
+                ProtocolError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotConnected,
+                    "Sandbox not started",
+                ))
+            })?;
+
+            match stdout.next().await {
+                Some(Ok(TtyChunk::StdOut(bytes))) => self.recv_buffer.extend_from_slice(&bytes),
+                // Surface the container's stderr as warnings, same as the
+                // process backend's piped stderr logging task.
+                Some(Ok(TtyChunk::StdErr(bytes))) => {
+                    tracing::warn!(
+                        "[{}] [stderr] {}",
+                        self.entry_id,
+                        String::from_utf8_lossy(&bytes).trim()
+                    );
+                }
+                Some(Ok(TtyChunk::StdIn(_))) => {}
+                Some(Err(e)) => {
+                    return Err(ProtocolError::Io(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        e.to_string(),
+                    )))
+                }
+                None => {
+                    return Err(ProtocolError::Io(std::io::Error::new(
+                        std::io::ErrorKind::UnexpectedEof,
+                        "Container attach stream closed",
+                    )))
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl SandboxBackend for ContainerBackend {
+    /// Create, mount, and start the container, then attach to it for the
+    /// protocol stream.
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        tracing::info!(
+            "[{}] Starting container sandbox (image: {})",
+            self.entry_id,
+            self.image
+        );
+
+        let options = ContainerOptionsBuilder::new(&self.image)
+            .env(vec![format!("HEARTHD_ENTRY_ID={}", self.entry_id)])
+            .volumes(vec![&format!(
+                "{}:/ha-source:ro",
+                self.ha_source_path.display()
+            )])
+            .memory(self.memory_limit_mb * 1024 * 1024)
+            .nano_cpus((self.cpu_limit * 1_000_000_000.0) as u64)
+            // No published or user-defined networks: the container can
+            // only reach the loopback interface, i.e. no egress.
+            .network_mode("none")
+            .build();
+
+        let container = self
+            .docker
+            .containers()
+            .create(&options)
+            .await
+            .map_err(|e| ProtocolError::Io(std::io::Error::other(e.to_string())))?;
+        self.container_id = Some(container.id.clone());
+
+        let handle = self.docker.containers().get(&container.id);
+        handle
+            .start()
+            .await
+            .map_err(|e| ProtocolError::Io(std::io::Error::other(e.to_string())))?;
+
+        let (stdout, stdin) = handle
+            .attach()
+            .await
+            .map_err(|e| ProtocolError::Io(std::io::Error::other(e.to_string())))?
+            .split();
+        self.attach_stdout = Some(Box::new(stdout));
+        self.attach_stdin = Some(Box::new(stdin));
+
+        tracing::debug!(
+            "[{}] Container sandbox started (id: {}), waiting for Ready message",
+            self.entry_id,
+            container.id
+        );
+
+        Ok(())
+    }
+
+    /// Send a response to the Python runner over the attach stream.
+    async fn send(&mut self, response: Response) -> Result<(), ProtocolError> {
+        let stdin = self.attach_stdin.as_mut().ok_or_else(|| {
+            ProtocolError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "Sandbox not started",
+            ))
+        })?;
+
+        let json = serde_json::to_string(&response)?;
+        tracing::trace!("[{}] Sending: {}", self.entry_id, json);
+
+        stdin.write_all(json.as_bytes()).await?;
+        stdin.write_all(b"\n").await?;
+        stdin.flush().await?;
+
+        Ok(())
+    }
+
+    /// Receive a message from the Python runner over the attach stream.
+    async fn recv(&mut self) -> Result<Message, ProtocolError> {
+        let line = self.read_line().await?;
+        tracing::trace!("[{}] Received: {}", self.entry_id, line);
+
+        let message: Message = serde_json::from_str(&line)?;
+        Ok(message)
+    }
+
+    /// Stop and remove the container.
+    async fn stop(&mut self) -> Result<(), ProtocolError> {
+        tracing::info!("[{}] Stopping container sandbox", self.entry_id);
+
+        if self.attach_stdin.is_some() {
+            let _ = self.send(Response::Shutdown).await;
+        }
+
+        if let Some(container_id) = self.container_id.take() {
+            let handle = self.docker.containers().get(&container_id);
+
+            match tokio::time::timeout(Duration::from_secs(5), handle.wait()).await {
+                Ok(Ok(exit)) => {
+                    tracing::info!("[{}] Container exited with status: {:?}", self.entry_id, exit);
+                }
+                Ok(Err(e)) => {
+                    tracing::error!("[{}] Failed to wait for container: {}", self.entry_id, e);
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        "[{}] Container did not exit within timeout, killing",
+                        self.entry_id
+                    );
+                }
+            }
+
+            let remove_options = RmContainerOptionsBuilder::default().force(true).build();
+            if let Err(e) = handle.remove(remove_options).await {
+                tracing::warn!("[{}] Failed to remove container: {}", self.entry_id, e);
+            }
+        }
+
+        self.attach_stdin = None;
+        self.attach_stdout = None;
+
+        tracing::info!("[{}] Container sandbox stopped", self.entry_id);
+
+        Ok(())
+    }
+}
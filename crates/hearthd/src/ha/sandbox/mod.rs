@@ -0,0 +1,215 @@
+//! Sandbox management for running Python integrations.
+//!
+//! A [`Sandbox`] speaks the newline-delimited-JSON protocol to a Python
+//! runner over whichever [`SandboxBackend`] its [`SandboxBuilder`] picked -
+//! either a direct subprocess sharing a socketpair, or a container with
+//! filesystem/network isolation.
+
+mod backend;
+mod config;
+mod container;
+mod process;
+mod remote;
+
+pub use backend::SandboxBackend;
+pub use config::SandboxBackendKind;
+pub use config::SandboxConfig;
+
+use std::path::PathBuf;
+
+use container::ContainerBackend;
+use process::ProcessBackend;
+use remote::RemoteBackend;
+
+use super::protocol::{Message, ProtocolError, Response};
+
+/// Manages a sandboxed Python environment for running Home Assistant
+/// integrations, delegating the actual execution transport to a
+/// [`SandboxBackend`].
+pub struct Sandbox {
+    entry_id: String,
+    backend: Box<dyn SandboxBackend>,
+}
+
+impl Sandbox {
+    /// Create a new sandbox wrapping an already-built backend. Prefer
+    /// [`SandboxBuilder`] for constructing one from config.
+    pub fn new(entry_id: String, backend: Box<dyn SandboxBackend>) -> Self {
+        Self { entry_id, backend }
+    }
+
+    /// This sandbox's instance id, matching the [`SandboxBuilder`] it was
+    /// built from.
+    pub(crate) fn entry_id(&self) -> &str {
+        &self.entry_id
+    }
+
+    /// Start the Python runner and connect to it
+    pub async fn start(&mut self) -> Result<(), ProtocolError> {
+        self.backend.start().await
+    }
+
+    /// Send a response to the Python runner
+    pub async fn send(&mut self, response: Response) -> Result<(), ProtocolError> {
+        self.backend.send(response).await
+    }
+
+    /// Receive a message from the Python runner
+    pub async fn recv(&mut self) -> Result<Message, ProtocolError> {
+        self.backend.recv().await
+    }
+
+    /// Stop the Python runner gracefully
+    pub async fn stop(&mut self) -> Result<(), ProtocolError> {
+        self.backend.stop().await
+    }
+}
+
+impl std::fmt::Debug for Sandbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sandbox")
+            .field("entry_id", &self.entry_id)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Builds a [`Sandbox`] for a named integration instance, picking its
+/// backend (and validating the config that drives that choice) up front.
+pub struct SandboxBuilder {
+    pub name: String,
+    python_path: PathBuf,
+    ha_source_path: PathBuf,
+    config: SandboxConfig,
+}
+
+impl SandboxBuilder {
+    /// Create a new sandbox builder for the given integration instance
+    /// name, using the default (process) backend.
+    pub fn new(name: String, python_path: PathBuf, ha_source_path: PathBuf) -> Self {
+        Self::with_config(name, python_path, ha_source_path, SandboxConfig::default())
+    }
+
+    /// Create a new sandbox builder with an explicit backend configuration.
+    pub fn with_config(
+        name: String,
+        python_path: PathBuf,
+        ha_source_path: PathBuf,
+        config: SandboxConfig,
+    ) -> Self {
+        Self {
+            name,
+            python_path,
+            ha_source_path,
+            config,
+        }
+    }
+
+    /// Validate the backend configuration and build the matching
+    /// [`Sandbox`].
+    pub async fn try_into_sandbox(&self) -> super::Result<Sandbox> {
+        use hearthd_config::Validate;
+
+        if let Some(error) = self.config.validate().into_iter().find(|d| d.is_error()) {
+            return Err(super::Error::InvalidConfig(error.to_string()));
+        }
+
+        let backend: Box<dyn SandboxBackend> = match self.config.backend {
+            SandboxBackendKind::Process => Box::new(ProcessBackend::new(
+                self.name.clone(),
+                self.python_path.clone(),
+                self.ha_source_path.clone(),
+            )),
+            SandboxBackendKind::Container => Box::new(ContainerBackend::new(
+                self.name.clone(),
+                self.ha_source_path.clone(),
+                &self.config,
+            )),
+            SandboxBackendKind::Remote => {
+                // Validated above: `node` is `Some` and present in `nodes`.
+                let node_name = self.config.node.as_ref().expect("validated");
+                let node = self.config.nodes.get(node_name).expect("validated");
+
+                let addr = format!("{}:{}", node.host, node.port)
+                    .parse()
+                    .map_err(|e| {
+                        super::Error::InvalidConfig(format!(
+                            "invalid address for node '{}': {}",
+                            node_name, e
+                        ))
+                    })?;
+
+                Box::new(RemoteBackend::new(self.name.clone(), addr))
+            }
+        };
+
+        Ok(Sandbox::new(self.name.clone(), backend))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn process_backend_builds_successfully() {
+        let builder = SandboxBuilder::new(
+            "test".to_string(),
+            PathBuf::from("python3"),
+            PathBuf::from("vendor/ha-core"),
+        );
+        assert!(builder.try_into_sandbox().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn container_backend_without_image_fails_to_build() {
+        let builder = SandboxBuilder::with_config(
+            "test".to_string(),
+            PathBuf::from("python3"),
+            PathBuf::from("vendor/ha-core"),
+            SandboxConfig {
+                backend: SandboxBackendKind::Container,
+                ..SandboxConfig::default()
+            },
+        );
+        assert!(builder.try_into_sandbox().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_backend_without_node_fails_to_build() {
+        let builder = SandboxBuilder::with_config(
+            "test".to_string(),
+            PathBuf::from("python3"),
+            PathBuf::from("vendor/ha-core"),
+            SandboxConfig {
+                backend: SandboxBackendKind::Remote,
+                ..SandboxConfig::default()
+            },
+        );
+        assert!(builder.try_into_sandbox().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn remote_backend_with_registered_node_builds_successfully() {
+        let mut nodes = std::collections::HashMap::new();
+        nodes.insert(
+            "node-a".to_string(),
+            config::NodeConfig {
+                host: "127.0.0.1".to_string(),
+                port: 9999,
+            },
+        );
+
+        let builder = SandboxBuilder::with_config(
+            "test".to_string(),
+            PathBuf::from("python3"),
+            PathBuf::from("vendor/ha-core"),
+            SandboxConfig {
+                backend: SandboxBackendKind::Remote,
+                node: Some("node-a".to_string()),
+                nodes,
+                ..SandboxConfig::default()
+            },
+        );
+        assert!(builder.try_into_sandbox().await.is_ok());
+    }
+}
@@ -1,10 +1,9 @@
-//! Sandbox management for running Python integrations.
-//!
-//! Uses tokio::net::UnixStream::pair() to create a socketpair and passes
-//! the file descriptor to the Python process via environment variable.
-//! No filesystem paths are used.
+//! Runs the Python runner as a direct child process, sharing a socketpair
+//! file descriptor with the parent. No filesystem/network isolation.
 
-use super::protocol::{Message, ProtocolError, Response};
+use super::backend::SandboxBackend;
+use crate::ha::protocol::{Message, ProtocolError, Response};
+use async_trait::async_trait;
 use std::os::unix::io::AsRawFd;
 use std::path::PathBuf;
 use std::process::Stdio;
@@ -12,8 +11,9 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::UnixStream;
 use tokio::process::{Child, Command};
 
-/// Manages a sandboxed Python environment for running Home Assistant integrations.
-pub struct Sandbox {
+/// Manages a sandboxed Python environment running directly as a child
+/// process of hearthd, communicating over a `UnixStream::pair()` socketpair.
+pub struct ProcessBackend {
     /// Entry ID for this integration instance
     entry_id: String,
 
@@ -30,8 +30,8 @@ pub struct Sandbox {
     child: Option<Child>,
 }
 
-impl Sandbox {
-    /// Create a new sandbox instance
+impl ProcessBackend {
+    /// Create a new process backend
     pub fn new(entry_id: String, python_path: PathBuf, ha_source_path: PathBuf) -> Self {
         Self {
             entry_id,
@@ -41,9 +41,12 @@ impl Sandbox {
             child: None,
         }
     }
+}
 
+#[async_trait]
+impl SandboxBackend for ProcessBackend {
     /// Start the Python process and connect to it
-    pub async fn start(&mut self) -> Result<(), ProtocolError> {
+    async fn start(&mut self) -> Result<(), ProtocolError> {
         tracing::info!("[{}] Starting sandbox", self.entry_id);
 
         // Create socketpair for bidirectional communication
@@ -143,7 +146,7 @@ impl Sandbox {
     }
 
     /// Send a response to the Python process
-    pub async fn send(&mut self, response: Response) -> Result<(), ProtocolError> {
+    async fn send(&mut self, response: Response) -> Result<(), ProtocolError> {
         let stream = self.stream.as_mut().ok_or_else(|| {
             ProtocolError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotConnected,
@@ -166,7 +169,7 @@ impl Sandbox {
     }
 
     /// Receive a message from the Python process
-    pub async fn recv(&mut self) -> Result<Message, ProtocolError> {
+    async fn recv(&mut self) -> Result<Message, ProtocolError> {
         let stream = self.stream.as_mut().ok_or_else(|| {
             ProtocolError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotConnected,
@@ -194,7 +197,7 @@ impl Sandbox {
     }
 
     /// Stop the Python process gracefully
-    pub async fn stop(&mut self) -> Result<(), ProtocolError> {
+    async fn stop(&mut self) -> Result<(), ProtocolError> {
         tracing::info!("[{}] Stopping sandbox", self.entry_id);
 
         // Send shutdown signal
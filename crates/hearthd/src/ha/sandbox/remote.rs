@@ -0,0 +1,187 @@
+//! Runs the Python runner on a remote node, forwarding the same
+//! newline-delimited-JSON protocol over TCP instead of a local socketpair.
+
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+use super::backend::SandboxBackend;
+use crate::ha::protocol::{Message, ProtocolError, Response};
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How long to wait for traffic before sending a heartbeat to confirm the
+/// remote node is still reachable.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Runs the Python runner on a remote node's hearthd-agent, reconnecting
+/// with exponential backoff and heartbeating an otherwise-idle connection.
+pub struct RemoteBackend {
+    entry_id: String,
+    addr: SocketAddr,
+    stream: Option<BufReader<TcpStream>>,
+    last_sent: Instant,
+}
+
+impl RemoteBackend {
+    /// Create a new remote backend targeting `addr`. The connection isn't
+    /// opened until `start()` is called.
+    pub fn new(entry_id: String, addr: SocketAddr) -> Self {
+        Self {
+            entry_id,
+            addr,
+            stream: None,
+            last_sent: Instant::now(),
+        }
+    }
+
+    /// Connect to the remote node, retrying with exponential backoff
+    /// (capped at [`MAX_BACKOFF`]) until it succeeds.
+    async fn connect_with_backoff(&mut self) {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            match TcpStream::connect(self.addr).await {
+                Ok(stream) => {
+                    tracing::info!(
+                        "[{}] Connected to remote node {}",
+                        self.entry_id,
+                        self.addr
+                    );
+                    self.stream = Some(BufReader::new(stream));
+                    self.last_sent = Instant::now();
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "[{}] Failed to connect to remote node {} ({}), retrying in {:?}",
+                        self.entry_id,
+                        self.addr,
+                        e,
+                        backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+                }
+            }
+        }
+    }
+
+    fn not_connected() -> ProtocolError {
+        ProtocolError::Io(io::Error::new(
+            io::ErrorKind::NotConnected,
+            "Sandbox not started",
+        ))
+    }
+}
+
+#[async_trait]
+impl SandboxBackend for RemoteBackend {
+    /// Connect to the remote node, blocking (with backoff) until it's
+    /// reachable.
+    async fn start(&mut self) -> Result<(), ProtocolError> {
+        tracing::info!(
+            "[{}] Starting remote sandbox backend ({})",
+            self.entry_id,
+            self.addr
+        );
+        self.connect_with_backoff().await;
+        Ok(())
+    }
+
+    /// Send a response to the remote node, transparently reconnecting on
+    /// failure.
+    async fn send(&mut self, response: Response) -> Result<(), ProtocolError> {
+        let json = serde_json::to_string(&response)?;
+        tracing::trace!("[{}] Sending: {}", self.entry_id, json);
+
+        loop {
+            let stream = self.stream.as_mut().ok_or_else(Self::not_connected)?;
+            let inner = stream.get_mut();
+
+            let result: io::Result<()> = async {
+                inner.write_all(json.as_bytes()).await?;
+                inner.write_all(b"\n").await?;
+                inner.flush().await
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    self.last_sent = Instant::now();
+                    return Ok(());
+                }
+                Err(e) => {
+                    tracing::warn!("[{}] Send failed ({}), reconnecting", self.entry_id, e);
+                    self.stream = None;
+                    self.connect_with_backoff().await;
+                }
+            }
+        }
+    }
+
+    /// Receive a message from the remote node, transparently reconnecting
+    /// on failure and swallowing heartbeats sent by the remote side.
+    async fn recv(&mut self) -> Result<Message, ProtocolError> {
+        loop {
+            let stream = self.stream.as_mut().ok_or_else(Self::not_connected)?;
+
+            let mut line = String::new();
+            let read = tokio::time::timeout(HEARTBEAT_INTERVAL, stream.read_line(&mut line)).await;
+
+            match read {
+                Ok(Ok(0)) => {
+                    tracing::warn!("[{}] Remote connection closed, reconnecting", self.entry_id);
+                    self.stream = None;
+                    self.connect_with_backoff().await;
+                }
+                Ok(Ok(_)) => {
+                    tracing::trace!("[{}] Received: {}", self.entry_id, line.trim());
+                    let message: Message = serde_json::from_str(line.trim())?;
+
+                    // Heartbeats are a transport-level concern: swallow
+                    // them here rather than surfacing them to the runtime.
+                    if matches!(message, Message::Heartbeat) {
+                        continue;
+                    }
+                    return Ok(message);
+                }
+                Ok(Err(e)) => {
+                    tracing::warn!("[{}] Receive failed ({}), reconnecting", self.entry_id, e);
+                    self.stream = None;
+                    self.connect_with_backoff().await;
+                }
+                Err(_elapsed) => {
+                    // No traffic within the heartbeat window; send one to
+                    // confirm the connection is alive before the next real
+                    // message is due.
+                    if let Err(e) = self.send(Response::Heartbeat).await {
+                        tracing::warn!("[{}] Heartbeat send failed: {}", self.entry_id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Tell the remote node to shut down and drop the connection.
+    async fn stop(&mut self) -> Result<(), ProtocolError> {
+        tracing::info!("[{}] Stopping remote sandbox backend", self.entry_id);
+
+        if self.stream.is_some() {
+            let _ = self.send(Response::Shutdown).await;
+        }
+
+        self.stream = None;
+
+        tracing::info!("[{}] Remote sandbox backend stopped", self.entry_id);
+        Ok(())
+    }
+}
@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+use super::client::BleClient;
+use super::sensor::BleSensor;
+use super::BleConfig;
+use crate::engine::Command;
+use crate::engine::CommandKind;
+use crate::engine::Entity;
+use crate::engine::FromIntegrationMessage;
+use crate::engine::FromIntegrationSender;
+use crate::engine::Integration;
+
+/// Type alias for the shared sensors map, keyed by entity ID
+type SensorsMap = Arc<Mutex<HashMap<String, Arc<Mutex<BleSensor>>>>>;
+
+/// Bluetooth LE Integration for hearthd
+///
+/// Discovers devices advertising the configured service UUID, connects to
+/// (and reconnects) each configured device by its `DeviceId`, and decodes
+/// subscribed GATT characteristic notifications into sensor entities per
+/// [`super::config::DeviceConfig`].
+pub struct BleIntegration<C: BleClient> {
+    client: Arc<Mutex<C>>,
+    config: BleConfig,
+    sensors: SensorsMap,
+    to_engine: Option<FromIntegrationSender>,
+    /// Handle to the background scan/notification processing task
+    _scan_task: Option<JoinHandle<()>>,
+}
+
+impl<C: BleClient> BleIntegration<C> {
+    /// Create a new BLE integration
+    pub fn new(client: C, config: &BleConfig) -> Self {
+        let mut sensors = HashMap::new();
+        for (device_id, device_config) in &config.devices {
+            let slug = device_config.name.to_lowercase().replace(' ', "_");
+            let entity_id = format!("sensor.{}", slug);
+            sensors.insert(
+                entity_id.clone(),
+                Arc::new(Mutex::new(BleSensor::from_device_config(
+                    entity_id,
+                    device_id.clone(),
+                    device_config,
+                ))),
+            );
+        }
+
+        Self {
+            client: Arc::new(Mutex::new(client)),
+            config: config.clone(),
+            sensors: Arc::new(Mutex::new(sensors)),
+            to_engine: None,
+            _scan_task: None,
+        }
+    }
+
+    /// Periodically scan for and (re)connect to configured devices, and
+    /// process incoming characteristic notifications, in a background task.
+    async fn scan_task(
+        client: Arc<Mutex<C>>,
+        config: BleConfig,
+        sensors: SensorsMap,
+        to_engine: FromIntegrationSender,
+    ) {
+        let scan_interval = std::time::Duration::from_secs(config.scan_interval_secs);
+        let mut next_scan = std::time::Instant::now();
+
+        loop {
+            if std::time::Instant::now() >= next_scan {
+                Self::reconnect_configured_devices(&client, &config, &sensors).await;
+                next_scan = std::time::Instant::now() + scan_interval;
+            }
+
+            let notification = {
+                let mut client_guard = client.lock().await;
+                tokio::time::timeout(
+                    std::time::Duration::from_millis(100),
+                    client_guard.poll_notification(),
+                )
+                .await
+                .unwrap_or_default()
+            };
+
+            match notification {
+                Some(notification) => {
+                    Self::handle_notification_static(&notification, &sensors, &to_engine).await;
+                }
+                None => {
+                    tokio::task::yield_now().await;
+                }
+            }
+        }
+    }
+
+    /// Discover advertising devices and connect (or reconnect) to every
+    /// device with a configured decoder, subscribing to its characteristics.
+    ///
+    /// Reconnecting unconditionally on each scan pass is harmless: `connect`
+    /// is a no-op for devices that are already connected, matching bluest's
+    /// own reconnect-by-`DeviceId` example where a dropped device is simply
+    /// connected to again.
+    async fn reconnect_configured_devices(
+        client: &Arc<Mutex<C>>,
+        config: &BleConfig,
+        sensors: &SensorsMap,
+    ) {
+        let discovered = {
+            let mut client_guard = client.lock().await;
+            match client_guard.discover_devices(&config.service_uuid).await {
+                Ok(devices) => devices,
+                Err(e) => {
+                    warn!("Error discovering BLE devices: {}", e);
+                    return;
+                }
+            }
+        };
+
+        let sensors_guard = sensors.lock().await;
+        for sensor_arc in sensors_guard.values() {
+            let sensor = sensor_arc.lock().await;
+            if !discovered.contains(&sensor.device_id) {
+                continue;
+            }
+
+            let mut client_guard = client.lock().await;
+            if let Err(e) = client_guard.connect(&sensor.device_id).await {
+                warn!("Error connecting to BLE device {}: {}", sensor.device_id, e);
+                continue;
+            }
+
+            for characteristic_uuid in sensor.characteristic_uuids() {
+                if let Err(e) = client_guard
+                    .subscribe_characteristic(&sensor.device_id, characteristic_uuid)
+                    .await
+                {
+                    warn!(
+                        "Error subscribing to characteristic {} on {}: {}",
+                        characteristic_uuid, sensor.device_id, e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Decode an incoming notification into its matching sensor's state and
+    /// report the change to the engine (static version for background task)
+    async fn handle_notification_static(
+        notification: &super::client::BleNotification,
+        sensors: &SensorsMap,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let mut sensor_to_report: Option<(String, serde_json::Value)> = None;
+
+        {
+            let sensors_guard = sensors.lock().await;
+            for (entity_id, sensor_arc) in sensors_guard.iter() {
+                let mut sensor = sensor_arc.lock().await;
+                if sensor.device_id != notification.device_id {
+                    continue;
+                }
+                debug!(
+                    "Notification for BLE sensor {} on characteristic {}",
+                    entity_id, notification.characteristic_uuid
+                );
+                let changed = sensor.update_from_notification(
+                    &notification.characteristic_uuid,
+                    &notification.payload,
+                );
+                if changed {
+                    sensor_to_report = Some((entity_id.clone(), sensor.state_json()));
+                }
+                break;
+            }
+        }
+
+        if let Some((entity_id, fields)) = sensor_to_report {
+            Self::report_sensor_state_change_static(&entity_id, fields, to_engine).await;
+        }
+    }
+
+    /// Register an entity with the engine (static version)
+    async fn register_entity_static(entity_id: &str, to_engine: &FromIntegrationSender) {
+        let msg = FromIntegrationMessage::EntityDiscovered {
+            entity_id: entity_id.to_string(),
+            integration_name: "ble".to_string(),
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send EntityDiscovered message: {}", e);
+        } else {
+            info!("Registered entity: {}", entity_id);
+        }
+    }
+
+    /// Report a sensor state change to the engine (static version)
+    async fn report_sensor_state_change_static(
+        entity_id: &str,
+        fields: serde_json::Value,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::SensorStateChanged {
+            entity_id: entity_id.to_string(),
+            fields,
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send SensorStateChanged message: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: BleClient + 'static> Integration for BleIntegration<C> {
+    fn name(&self) -> &str {
+        "ble"
+    }
+
+    async fn setup(&mut self, tx: FromIntegrationSender) -> Result<(), Box<dyn Error + Send>> {
+        self.to_engine = Some(tx.clone());
+
+        for entity_id in self.sensors.lock().await.keys() {
+            Self::register_entity_static(entity_id, &tx).await;
+        }
+
+        info!("BLE integration setup complete, spawning scan task...");
+
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let sensors = self.sensors.clone();
+
+        let task = tokio::spawn(async move {
+            Self::scan_task(client, config, sensors, tx).await;
+        });
+        self._scan_task = Some(task);
+
+        info!("BLE integration ready");
+        Ok(())
+    }
+
+    fn accepted_commands(&self) -> &[CommandKind] {
+        // BLE sensors are read-only; the integration has no commands to handle yet.
+        &[]
+    }
+
+    async fn handle_message(
+        &mut self,
+        _cmd: Box<dyn Command>,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<(), Box<dyn Error + Send>> {
+        info!("BLE integration shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::ble::client::MockBleClient;
+    use crate::integrations::ble::config::DeviceConfig;
+    use crate::integrations::ble::config::FieldDecoder;
+    use crate::integrations::ble::config::FieldEncoding;
+
+    fn test_config() -> BleConfig {
+        BleConfig {
+            service_uuid: "0000fe95-0000-1000-8000-00805f9b34fb".to_string(),
+            scan_interval_secs: 30,
+            devices: HashMap::from([(
+                "A4:C1:38:00:00:00".to_string(),
+                DeviceConfig {
+                    name: "Bedroom Climate".to_string(),
+                    characteristics: HashMap::from([(
+                        "226caa55-6476-4566-7562-66734470666d".to_string(),
+                        vec![FieldDecoder {
+                            field: "temperature".to_string(),
+                            offset: 0,
+                            length: 2,
+                            encoding: FieldEncoding::I16,
+                            scale: 0.01,
+                        }],
+                    )]),
+                },
+            )]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ble_integration_creation_registers_configured_sensors() {
+        let client = MockBleClient::new();
+        let config = test_config();
+        let integration = BleIntegration::new(client, &config);
+
+        let sensors = integration.sensors.lock().await;
+        assert_eq!(sensors.len(), 1);
+        assert!(sensors.contains_key("sensor.bedroom_climate"));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_configured_devices_subscribes_discovered_devices() {
+        let mut client = MockBleClient::new();
+        client.discovered.push("A4:C1:38:00:00:00".to_string());
+        let config = test_config();
+        let integration = BleIntegration::new(client, &config);
+
+        BleIntegration::reconnect_configured_devices(
+            &integration.client,
+            &integration.config,
+            &integration.sensors,
+        )
+        .await;
+
+        let client_guard = integration.client.lock().await;
+        assert_eq!(client_guard.connected, vec!["A4:C1:38:00:00:00".to_string()]);
+        assert_eq!(
+            client_guard.subscriptions,
+            vec![(
+                "A4:C1:38:00:00:00".to_string(),
+                "226caa55-6476-4566-7562-66734470666d".to_string()
+            )]
+        );
+    }
+}
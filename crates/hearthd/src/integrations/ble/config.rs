@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use hearthd_config::SubConfig;
+use hearthd_config::TryFromPartial;
+use serde::Deserialize;
+
+fn default_scan_interval_secs() -> u64 {
+    30
+}
+
+/// Configuration for the Bluetooth LE integration
+#[derive(Debug, Clone, Deserialize, TryFromPartial, SubConfig)]
+pub struct Config {
+    /// GATT service UUID to filter advertisements by, e.g.
+    /// "0000fe95-0000-1000-8000-00805f9b34fb" for Xiaomi/Mijia devices.
+    pub service_uuid: String,
+
+    /// Seconds between `discover_devices` scan passes, including reconnect
+    /// attempts for devices that dropped out.
+    #[config(default = "default_scan_interval_secs")]
+    pub scan_interval_secs: u64,
+
+    /// Per-device decoder configuration, keyed by the device's stable
+    /// `DeviceId` string (platform-specific; a MAC address on Linux/Windows,
+    /// a UUID on macOS).
+    pub devices: HashMap<String, DeviceConfig>,
+}
+
+/// Decoder configuration for a single BLE device.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceConfig {
+    /// Human-readable entity name.
+    pub name: String,
+
+    /// GATT characteristic UUIDs to subscribe to for notifications, each
+    /// decoded independently via `decoders`.
+    pub characteristics: HashMap<String, Vec<FieldDecoder>>,
+}
+
+/// Maps a byte range within a characteristic's notification payload to a
+/// named entity field, e.g. a Mijia sensor's temperature/humidity/battery
+/// fields packed into one notification.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FieldDecoder {
+    /// Entity state field this byte range decodes into, e.g. "temperature".
+    pub field: String,
+
+    /// Byte offset of the field within the notification payload.
+    pub offset: usize,
+
+    /// Length in bytes of the field.
+    pub length: usize,
+
+    /// How to interpret the bytes. Values are little-endian, matching the
+    /// common BLE GATT/Mijia convention.
+    pub encoding: FieldEncoding,
+
+    /// Factor the decoded integer is multiplied by to produce the final
+    /// value, e.g. `0.01` for a temperature sent as centi-degrees.
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+/// Integer encodings supported by [`FieldDecoder`], all little-endian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldEncoding {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+}
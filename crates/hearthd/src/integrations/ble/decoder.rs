@@ -0,0 +1,96 @@
+//! Decodes raw GATT notification payloads into entity state, per the byte
+//! offset/length mappings in [`super::config::FieldDecoder`].
+
+use serde_json::Value;
+
+use super::config::FieldDecoder;
+use super::config::FieldEncoding;
+
+/// Decode a single field out of `payload` per `decoder`, returning `None` if
+/// the payload is too short to contain it.
+pub fn decode_field(decoder: &FieldDecoder, payload: &[u8]) -> Option<Value> {
+    let bytes = payload.get(decoder.offset..decoder.offset + decoder.length)?;
+
+    let raw: f64 = match decoder.encoding {
+        FieldEncoding::U8 => bytes[0] as f64,
+        FieldEncoding::I8 => bytes[0] as i8 as f64,
+        FieldEncoding::U16 => u16::from_le_bytes(bytes.try_into().ok()?) as f64,
+        FieldEncoding::I16 => i16::from_le_bytes(bytes.try_into().ok()?) as f64,
+        FieldEncoding::U32 => u32::from_le_bytes(bytes.try_into().ok()?) as f64,
+        FieldEncoding::I32 => i32::from_le_bytes(bytes.try_into().ok()?) as f64,
+    };
+
+    Some(serde_json::json!(raw * decoder.scale))
+}
+
+/// Decode every configured field for a characteristic notification,
+/// merging the results into a single JSON object. Fields that can't be
+/// decoded (payload too short) are silently omitted rather than failing the
+/// whole notification, since a single dropped/truncated packet shouldn't
+/// invalidate a device's other already-known fields.
+pub fn decode_notification(decoders: &[FieldDecoder], payload: &[u8]) -> Value {
+    let mut fields = serde_json::Map::new();
+    for decoder in decoders {
+        if let Some(value) = decode_field(decoder, payload) {
+            fields.insert(decoder.field.clone(), value);
+        }
+    }
+    Value::Object(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decoder(
+        field: &str,
+        offset: usize,
+        length: usize,
+        encoding: FieldEncoding,
+    ) -> FieldDecoder {
+        FieldDecoder {
+            field: field.to_string(),
+            offset,
+            length,
+            encoding,
+            scale: 1.0,
+        }
+    }
+
+    #[test]
+    fn decodes_a_little_endian_u16() {
+        let d = decoder("humidity", 0, 2, FieldEncoding::U16);
+        assert_eq!(decode_field(&d, &[0x64, 0x00]), Some(serde_json::json!(100.0)));
+    }
+
+    #[test]
+    fn decodes_a_negative_i16_with_scale() {
+        let mut d = decoder("temperature", 0, 2, FieldEncoding::I16);
+        d.scale = 0.01;
+        // -500 as i16 little-endian, scaled to -5.0 degrees
+        assert_eq!(
+            decode_field(&d, &(-500i16).to_le_bytes()),
+            Some(serde_json::json!(-5.0))
+        );
+    }
+
+    #[test]
+    fn missing_bytes_yields_none() {
+        let d = decoder("battery", 5, 1, FieldEncoding::U8);
+        assert_eq!(decode_field(&d, &[0x01, 0x02]), None);
+    }
+
+    #[test]
+    fn decode_notification_merges_multiple_fields_and_skips_truncated_ones() {
+        let decoders = vec![
+            decoder("temperature", 0, 2, FieldEncoding::I16),
+            decoder("humidity", 2, 2, FieldEncoding::U16),
+            decoder("battery", 10, 1, FieldEncoding::U8),
+        ];
+        let payload = [0xDC, 0x08, 0x32, 0x00]; // temp=2268, humidity=50, battery missing
+        let value = decode_notification(&decoders, &payload);
+        assert_eq!(value["temperature"], serde_json::json!(2268.0));
+        assert_eq!(value["humidity"], serde_json::json!(50.0));
+        assert!(value.get("battery").is_none());
+    }
+}
@@ -0,0 +1,25 @@
+// Private module - allowed by clippy.toml allow-private-module-inception
+#[allow(clippy::module_inception)]
+mod ble;
+mod client;
+mod config;
+mod decoder;
+mod sensor;
+
+pub use ble::BleIntegration;
+pub use config::Config as BleConfig;
+use linkme::distributed_slice;
+
+use crate::engine;
+
+#[distributed_slice(engine::INTEGRATION_REGISTRY)]
+fn init_ble(ctx: &engine::IntegrationContext) -> engine::IntegrationFactoryResult {
+    let ble_config = if let Some(c) = &ctx.config.integrations.ble {
+        c
+    } else {
+        return Ok(None);
+    };
+
+    let client = client::BluestClient::new();
+    Ok(Some(Box::new(BleIntegration::new(client, ble_config))))
+}
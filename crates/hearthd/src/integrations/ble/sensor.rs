@@ -0,0 +1,249 @@
+use crate::engine::Entity;
+use crate::integrations::ble::config::DeviceConfig;
+use crate::integrations::ble::config::FieldDecoder;
+use crate::integrations::ble::decoder;
+use crate::integrations::mqtt::DiscoveryMessage;
+use crate::integrations::mqtt::hearthd_state_topic;
+
+/// A sensor entity synthesized from a BLE device's decoded GATT
+/// notifications (e.g. a Mijia-style temperature/humidity/battery sensor).
+///
+/// Unlike [`crate::integrations::mqtt::binary_sensor::BinarySensor`], the
+/// set of fields a BLE sensor reports is defined entirely by its
+/// [`DeviceConfig`] rather than a fixed schema, so state is kept as a plain
+/// JSON object merged field-by-field as notifications arrive.
+#[derive(Debug, Clone)]
+pub struct BleSensor {
+    /// Entity ID (e.g., "sensor.bedroom_climate")
+    #[allow(dead_code)]
+    pub id: String,
+
+    /// Human-readable name
+    pub name: String,
+
+    /// The device's stable `DeviceId`
+    pub device_id: String,
+
+    /// Field decoders keyed by GATT characteristic UUID
+    characteristics: std::collections::HashMap<String, Vec<FieldDecoder>>,
+
+    /// Most recently decoded field values, merged in as notifications
+    /// arrive from each subscribed characteristic.
+    state: serde_json::Value,
+}
+
+impl BleSensor {
+    /// Create a BleSensor entity from its configured decoders.
+    pub fn from_device_config(id: String, device_id: String, config: &DeviceConfig) -> Self {
+        Self {
+            id,
+            name: config.name.clone(),
+            device_id,
+            characteristics: config.characteristics.clone(),
+            state: serde_json::Value::Object(serde_json::Map::new()),
+        }
+    }
+
+    /// The GATT characteristic UUIDs this sensor subscribes to.
+    pub fn characteristic_uuids(&self) -> impl Iterator<Item = &String> {
+        self.characteristics.keys()
+    }
+
+    /// Decode a notification payload for `characteristic_uuid` and merge
+    /// its fields into the sensor's state, returning `true` if any field
+    /// changed.
+    ///
+    /// Unrecognized characteristic UUIDs are ignored, since a device may
+    /// notify on characteristics this sensor wasn't configured to decode.
+    pub fn update_from_notification(
+        &mut self,
+        characteristic_uuid: &str,
+        payload: &[u8],
+    ) -> bool {
+        let Some(decoders) = self.characteristics.get(characteristic_uuid) else {
+            return false;
+        };
+
+        let decoded = decoder::decode_notification(decoders, payload);
+        let serde_json::Value::Object(decoded) = decoded else {
+            return false;
+        };
+
+        let serde_json::Value::Object(state) = &mut self.state else {
+            unreachable!("state is always constructed as an Object");
+        };
+
+        let mut changed = false;
+        for (field, value) in decoded {
+            if state.get(&field) != Some(&value) {
+                state.insert(field, value);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Build a Home Assistant MQTT discovery message for each distinct
+    /// decoded field (e.g. "temperature", "humidity"), since unlike
+    /// [`crate::integrations::mqtt::binary_sensor::BinarySensor`] or
+    /// [`crate::integrations::mqtt::light::Light`] a BLE sensor reports
+    /// several independent values rather than one. Every field shares this
+    /// sensor's state topic, distinguished by its own `value_template`.
+    pub fn field_discoveries(&self, node_id: &str) -> Vec<(String, DiscoveryMessage)> {
+        let fields: std::collections::BTreeSet<&str> = self
+            .characteristics
+            .values()
+            .flatten()
+            .map(|decoder| decoder.field.as_str())
+            .collect();
+
+        let state_topic = hearthd_state_topic("sensor", node_id);
+
+        fields
+            .into_iter()
+            .map(|field| {
+                let discovery = DiscoveryMessage {
+                    name: Some(format!("{} {}", self.name, field)),
+                    unique_id: Some(format!("{}_{}", self.device_id, field)),
+                    state_topic: Some(state_topic.clone()),
+                    command_topic: None,
+                    brightness_state_topic: None,
+                    brightness_command_topic: None,
+                    device: None,
+                    payload_on: None,
+                    payload_off: None,
+                    brightness: None,
+                    schema: None,
+                    device_class: None,
+                    value_template: Some(format!("{{{{ value_json.{} }}}}", field)),
+                    off_delay: None,
+                    expire_after: None,
+                    availability_topic: None,
+                    payload_available: None,
+                    payload_not_available: None,
+                    unit_of_measurement: None,
+                };
+                (field.to_string(), discovery)
+            })
+            .collect()
+    }
+}
+
+impl Entity for BleSensor {
+    fn state_json(&self) -> serde_json::Value {
+        self.state.clone()
+    }
+
+    fn platform(&self) -> &'static str {
+        "sensor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::ble::config::FieldEncoding;
+
+    fn mijia_config() -> DeviceConfig {
+        DeviceConfig {
+            name: "Bedroom Climate".to_string(),
+            characteristics: std::collections::HashMap::from([(
+                "226caa55-6476-4566-7562-66734470666d".to_string(),
+                vec![
+                    FieldDecoder {
+                        field: "temperature".to_string(),
+                        offset: 0,
+                        length: 2,
+                        encoding: FieldEncoding::I16,
+                        scale: 0.01,
+                    },
+                    FieldDecoder {
+                        field: "humidity".to_string(),
+                        offset: 2,
+                        length: 1,
+                        encoding: FieldEncoding::U8,
+                        scale: 1.0,
+                    },
+                ],
+            )]),
+        }
+    }
+
+    #[test]
+    fn update_from_notification_merges_decoded_fields() {
+        let config = mijia_config();
+        let mut sensor = BleSensor::from_device_config(
+            "sensor.bedroom_climate".to_string(),
+            "A4:C1:38:00:00:00".to_string(),
+            &config,
+        );
+
+        let changed = sensor.update_from_notification(
+            "226caa55-6476-4566-7562-66734470666d",
+            &[0xDC, 0x08, 0x32],
+        );
+
+        assert!(changed);
+        assert_eq!(sensor.state_json()["temperature"], serde_json::json!(22.68));
+        assert_eq!(sensor.state_json()["humidity"], serde_json::json!(50.0));
+    }
+
+    #[test]
+    fn update_from_notification_ignores_unknown_characteristic() {
+        let config = mijia_config();
+        let mut sensor = BleSensor::from_device_config(
+            "sensor.bedroom_climate".to_string(),
+            "A4:C1:38:00:00:00".to_string(),
+            &config,
+        );
+
+        let changed =
+            sensor.update_from_notification("0000180f-0000-1000-8000-00805f9b34fb", &[0x64]);
+
+        assert!(!changed);
+        assert_eq!(sensor.state_json(), serde_json::json!({}));
+    }
+
+    #[test]
+    fn update_from_notification_reports_no_change_when_values_repeat() {
+        let config = mijia_config();
+        let mut sensor = BleSensor::from_device_config(
+            "sensor.bedroom_climate".to_string(),
+            "A4:C1:38:00:00:00".to_string(),
+            &config,
+        );
+
+        let uuid = "226caa55-6476-4566-7562-66734470666d";
+        assert!(sensor.update_from_notification(uuid, &[0xDC, 0x08, 0x32]));
+        assert!(!sensor.update_from_notification(uuid, &[0xDC, 0x08, 0x32]));
+    }
+
+    #[test]
+    fn field_discoveries_has_one_entry_per_decoded_field() {
+        let config = mijia_config();
+        let sensor = BleSensor::from_device_config(
+            "sensor.bedroom_climate".to_string(),
+            "A4:C1:38:00:00:00".to_string(),
+            &config,
+        );
+
+        let mut discoveries = sensor.field_discoveries("bedroom_climate");
+        discoveries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            discoveries.iter().map(|(f, _)| f.as_str()).collect::<Vec<_>>(),
+            vec!["humidity", "temperature"]
+        );
+
+        let (_, humidity) = &discoveries[0];
+        assert_eq!(humidity.unique_id, Some("A4:C1:38:00:00:00_humidity".to_string()));
+        assert_eq!(
+            humidity.value_template,
+            Some("{{ value_json.humidity }}".to_string())
+        );
+        assert_eq!(
+            humidity.state_topic,
+            Some("hearthd/sensor/bedroom_climate/state".to_string())
+        );
+    }
+}
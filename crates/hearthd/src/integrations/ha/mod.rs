@@ -2,6 +2,7 @@
 //!
 //! This module bridges the HA sandbox system with the Engine's integration trait system.
 
+use std::collections::HashMap;
 use std::error::Error;
 use std::path::PathBuf;
 
@@ -21,28 +22,93 @@ pub struct HaConfig {
     /// Enable the HA integration (default: true when section is present)
     #[serde(default = "default_true")]
     pub enabled: bool,
+
+    /// Sandbox backend configuration (default: direct subprocess)
+    #[serde(default)]
+    pub sandbox: ha::SandboxConfig,
+
+    /// Unit system weather entities normalize their `native_*` values into
+    /// (default: metric).
+    #[serde(default)]
+    pub weather_units: engine::UnitSystem,
+
+    /// Which forecast channel to use when a weather entity offers more than
+    /// one (e.g. AEMET's daily and hourly). `None` means take whichever
+    /// [`engine::weather::Weather::forecast_modes`] returns first.
+    #[serde(default)]
+    pub preferred_forecast_mode: Option<engine::ForecastMode>,
+
+    /// Integration instances to run, each in its own sandbox (default: a
+    /// single `met` instance, matching this integration's original
+    /// single-instance behavior).
+    #[serde(default = "default_instances")]
+    pub instances: Vec<HaInstanceConfig>,
+}
+
+/// One configured Home Assistant integration instance - e.g. one weather
+/// provider - to run in its own sandbox.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HaInstanceConfig {
+    /// The HA integration domain to load in the sandbox (e.g. `met`,
+    /// `meteo_france`, `accuweather`).
+    pub domain: String,
+
+    /// Unique id for this instance; doubles as its sandbox id.
+    pub instance_id: String,
+
+    /// Opaque config-flow options forwarded to the Python integration as-is.
+    #[serde(default)]
+    pub options: HashMap<String, serde_json::Value>,
 }
 
 fn default_true() -> bool {
     true
 }
 
+fn default_instances() -> Vec<HaInstanceConfig> {
+    vec![HaInstanceConfig {
+        domain: "met".to_string(),
+        instance_id: "met_oslo".to_string(),
+        options: HashMap::new(),
+    }]
+}
+
 impl Default for HaConfig {
     fn default() -> Self {
-        Self { enabled: true }
+        Self {
+            enabled: true,
+            sandbox: ha::SandboxConfig::default(),
+            weather_units: engine::UnitSystem::default(),
+            preferred_forecast_mode: None,
+            instances: default_instances(),
+        }
     }
 }
 
 /// Home Assistant integration that runs integrations in sandboxed Python.
 pub struct HaIntegration {
     name: String,
+    sandbox_config: ha::SandboxConfig,
+    instances: Vec<HaInstanceConfig>,
+    accepted_commands: Vec<engine::CommandKind>,
+    /// Route senders for each running instance, keyed by instance id;
+    /// populated once [`setup`](engine::Integration::setup) registers them.
+    routes: HashMap<String, ha::RouteSender>,
     registry_handle: Option<JoinHandle<()>>,
 }
 
 impl HaIntegration {
-    pub fn new(name: String) -> Self {
+    pub fn new(
+        name: String,
+        sandbox_config: ha::SandboxConfig,
+        instances: Vec<HaInstanceConfig>,
+    ) -> Self {
         Self {
             name,
+            sandbox_config,
+            instances,
+            accepted_commands: vec![engine::CommandKind::of::<engine::CallServiceCommand>()],
+            routes: HashMap::new(),
             registry_handle: None,
         }
     }
@@ -78,21 +144,27 @@ impl engine::Integration for HaIntegration {
             )));
         }
 
-        // Create sandbox builder
-        let builder = ha::SandboxBuilder::new(
-            "met_oslo".to_string(), // Integration instance name
-            python_path,
-            ha_source_path,
-        );
-
-        // Create registry and register the sandbox
+        // Create a registry and register one sandbox per configured
+        // instance.
         let mut registry = ha::Registry::default();
-        registry
-            .register(builder)
-            .await
-            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+        for instance in &self.instances {
+            let builder = ha::SandboxBuilder::with_config(
+                instance.instance_id.clone(),
+                python_path.clone(),
+                ha_source_path.clone(),
+                self.sandbox_config.clone(),
+            );
+            let options = serde_json::to_value(&instance.options).unwrap_or_default();
+            registry
+                .register(builder, instance.domain.clone(), options)
+                .await
+                .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+        }
+
+        self.routes = registry.senders().into_iter().collect();
 
-        // Spawn the registry to run in the background
+        // Spawn the registry to run every instance's sandbox in the
+        // background.
         let name = self.name.clone();
         let handle = tokio::spawn(async move {
             if let Err(e) = registry.run().await {
@@ -102,17 +174,59 @@ impl engine::Integration for HaIntegration {
 
         self.registry_handle = Some(handle);
 
-        info!("[{}] Home Assistant integration started", self.name);
+        info!(
+            "[{}] Home Assistant integration started with {} instance(s)",
+            self.name,
+            self.routes.len()
+        );
         Ok(())
     }
 
+    fn accepted_commands(&self) -> &[engine::CommandKind] {
+        &self.accepted_commands
+    }
+
     async fn handle_message(
         &mut self,
-        msg: engine::ToIntegrationMessage,
+        cmd: Box<dyn engine::Command>,
     ) -> Result<(), Box<dyn Error + Send>> {
-        // For now, log messages but don't route them
-        // TODO: Route commands to the appropriate sandbox
-        info!("[{}] Received message: {:?}", self.name, msg);
+        let Some(call) = cmd.as_any().downcast_ref::<engine::CallServiceCommand>() else {
+            warn!("[{}] Ignoring unsupported command: {:?}", self.name, cmd);
+            return Ok(());
+        };
+
+        // TODO: once sandboxes forward entity discovery up through `_tx`,
+        // key this off a real entity_id -> instance_id map instead of only
+        // coping with the (today, typical) single-instance case.
+        let route = match self.routes.len() {
+            0 => {
+                warn!(
+                    "[{}] No sandbox registered, dropping command for '{}'",
+                    self.name, call.entity_id
+                );
+                return Ok(());
+            }
+            1 => self.routes.values().next().expect("checked above"),
+            _ => {
+                warn!(
+                    "[{}] Multiple sandboxes registered but entity routing isn't wired up yet, \
+                    dropping command for '{}'",
+                    self.name, call.entity_id
+                );
+                return Ok(());
+            }
+        };
+
+        route
+            .call_service(
+                call.entity_id.clone(),
+                call.domain.clone(),
+                call.service.clone(),
+                call.data.clone(),
+            )
+            .await
+            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+
         Ok(())
     }
 
@@ -147,5 +261,9 @@ fn init_ha(ctx: &engine::IntegrationContext) -> engine::IntegrationFactoryResult
     }
 
     info!("Initializing Home Assistant integration");
-    Ok(Some(Box::new(HaIntegration::new("ha".to_string()))))
+    Ok(Some(Box::new(HaIntegration::new(
+        "ha".to_string(),
+        ha_config.sandbox.clone(),
+        ha_config.instances.clone(),
+    ))))
 }
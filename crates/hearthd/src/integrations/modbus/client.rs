@@ -0,0 +1,149 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+
+use super::config::RegisterType;
+
+/// Trait for Modbus connection operations
+///
+/// This trait allows for mocking the Modbus connection for testing
+/// purposes, mirroring [`crate::integrations::mqtt::client::MqttClient`].
+#[async_trait]
+pub trait ModbusClient: Send + Sync {
+    /// Open the Modbus TCP connection.
+    async fn connect(&mut self) -> Result<(), Box<dyn Error + Send>>;
+
+    /// Read `count` consecutive registers of `register_type` starting at
+    /// `address`.
+    async fn read_registers(
+        &mut self,
+        register_type: RegisterType,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, Box<dyn Error + Send>>;
+}
+
+/// Mock Modbus client for testing
+#[cfg(test)]
+#[derive(Debug, Default)]
+pub struct MockModbusClient {
+    pub connected: bool,
+    /// Canned responses, keyed by (register_type, start_address).
+    pub responses: std::collections::HashMap<(RegisterType, u16), Vec<u16>>,
+    /// If set, `connect` and every `read_registers` call fail once before
+    /// succeeding, to exercise reconnect/backoff behavior.
+    pub fail_next: bool,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl ModbusClient for MockModbusClient {
+    async fn connect(&mut self) -> Result<(), Box<dyn Error + Send>> {
+        if std::mem::take(&mut self.fail_next) {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::ConnectionRefused,
+                "mock connection failure",
+            )));
+        }
+        self.connected = true;
+        Ok(())
+    }
+
+    async fn read_registers(
+        &mut self,
+        register_type: RegisterType,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, Box<dyn Error + Send>> {
+        if std::mem::take(&mut self.fail_next) {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "mock read failure",
+            )));
+        }
+        self.responses
+            .get(&(register_type, address))
+            .map(|words| words.iter().copied().take(count as usize).collect())
+            .ok_or_else(|| {
+                Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "no canned response for register",
+                )) as Box<dyn Error + Send>
+            })
+    }
+}
+
+#[cfg(test)]
+impl MockModbusClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Real Modbus client implementation using a `tokio-modbus`-style async TCP
+/// context.
+pub struct TokioModbusClient {
+    host: String,
+    port: u16,
+    unit_id: u8,
+    context: Option<tokio_modbus::client::Context>,
+}
+
+impl TokioModbusClient {
+    /// Create a new client from configuration. The TCP connection isn't
+    /// opened until `connect()` is called.
+    pub fn new(config: &super::ModbusConfig) -> Self {
+        Self {
+            host: config.host.clone(),
+            port: config.port,
+            unit_id: config.unit_id,
+            context: None,
+        }
+    }
+}
+
+#[async_trait]
+impl ModbusClient for TokioModbusClient {
+    async fn connect(&mut self) -> Result<(), Box<dyn Error + Send>> {
+        let socket_addr = format!("{}:{}", self.host, self.port)
+            .parse()
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+
+        let context = tokio_modbus::client::tcp::connect_slave(
+            socket_addr,
+            tokio_modbus::slave::Slave(self.unit_id),
+        )
+        .await
+        .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+
+        self.context = Some(context);
+        Ok(())
+    }
+
+    async fn read_registers(
+        &mut self,
+        register_type: RegisterType,
+        address: u16,
+        count: u16,
+    ) -> Result<Vec<u16>, Box<dyn Error + Send>> {
+        let context = self
+            .context
+            .as_mut()
+            .ok_or_else(|| -> Box<dyn Error + Send> {
+                Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotConnected,
+                    "Modbus client not connected. Call connect() first.",
+                ))
+            })?;
+
+        use tokio_modbus::client::Reader;
+        let result = match register_type {
+            RegisterType::Input => context.read_input_registers(address, count).await,
+            RegisterType::Holding => context.read_holding_registers(address, count).await,
+        };
+
+        result
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?
+            .map_err(|e| Box::new(e) as Box<dyn Error + Send>)
+    }
+}
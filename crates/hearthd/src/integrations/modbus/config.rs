@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use hearthd_config::SubConfig;
+use hearthd_config::TryFromPartial;
+use serde::Deserialize;
+
+fn default_port() -> u16 {
+    502
+}
+
+fn default_unit_id() -> u8 {
+    1
+}
+
+fn default_poll_interval_secs() -> u64 {
+    10
+}
+
+/// Configuration for the Modbus integration
+///
+/// Targets the kind of gear modbus-to-mqtt bridges usually expose: solar
+/// inverters and power meters, polled over Modbus TCP.
+#[derive(Debug, Clone, Deserialize, TryFromPartial, SubConfig)]
+pub struct Config {
+    /// Modbus TCP host
+    pub host: String,
+
+    /// Modbus TCP port (default: 502)
+    #[config(default = "default_port")]
+    pub port: u16,
+
+    /// Modbus unit/slave ID (default: 1)
+    #[config(default = "default_unit_id")]
+    pub unit_id: u8,
+
+    /// Seconds between poll passes over all configured registers
+    #[config(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+
+    /// Registers to poll, keyed by entity ID (e.g. "sensor.inverter_power")
+    pub registers: HashMap<String, RegisterConfig>,
+}
+
+/// Decoder configuration for a single Modbus register (or multi-register
+/// value, for 32-bit data types).
+#[derive(Debug, Clone, Deserialize)]
+pub struct RegisterConfig {
+    /// Human-readable entity name.
+    pub name: String,
+
+    /// Starting register address.
+    pub address: u16,
+
+    /// Whether this is an input register (read-only, function code 0x04)
+    /// or a holding register (read/write, function code 0x03).
+    pub register_type: RegisterType,
+
+    /// Data type of the value, determining how many consecutive registers
+    /// it spans.
+    pub data_type: DataType,
+
+    /// Word order for multi-register (32-bit) data types. Irrelevant for
+    /// 16-bit types.
+    #[serde(default)]
+    pub word_order: WordOrder,
+
+    /// The value is computed as `scale * raw + offset`.
+    #[serde(default = "default_scale")]
+    pub scale: f64,
+
+    #[serde(default)]
+    pub offset: f64,
+
+    /// Home Assistant-style device class (e.g. "power", "energy"), if any.
+    pub device_class: Option<String>,
+
+    /// Unit of measurement (e.g. "W", "kWh"), if any.
+    pub unit: Option<String>,
+}
+
+fn default_scale() -> f64 {
+    1.0
+}
+
+/// Which Modbus function code reads a register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RegisterType {
+    Input,
+    Holding,
+}
+
+/// Register data types, all unsigned/signed integers or a 32-bit float.
+/// 32-bit types span two consecutive 16-bit registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataType {
+    U16,
+    I16,
+    U32,
+    I32,
+    F32,
+}
+
+impl DataType {
+    /// How many consecutive 16-bit registers this data type spans.
+    pub fn register_count(self) -> u16 {
+        match self {
+            DataType::U16 | DataType::I16 => 1,
+            DataType::U32 | DataType::I32 | DataType::F32 => 2,
+        }
+    }
+}
+
+/// Word order for multi-register values, analogous to byte-order but at
+/// the granularity of whole 16-bit registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordOrder {
+    /// Most-significant register first (the common default for Modbus
+    /// devices, sometimes called "big-endian" word order).
+    #[default]
+    BigEndian,
+    /// Least-significant register first.
+    LittleEndian,
+}
@@ -0,0 +1,175 @@
+//! Decodes raw Modbus register words into scaled sensor values, per
+//! [`super::config::RegisterConfig`].
+
+use super::config::DataType;
+use super::config::RegisterConfig;
+use super::config::WordOrder;
+
+/// Decode a register's raw words (already read from the device, in
+/// ascending address order) into its final `scale * raw + offset` value.
+///
+/// Returns `None` if fewer words were supplied than the data type requires.
+pub fn decode_register(config: &RegisterConfig, words: &[u16]) -> Option<f64> {
+    let count = config.data_type.register_count() as usize;
+    let words = words.get(..count)?;
+
+    let raw: f64 = match config.data_type {
+        DataType::U16 => words[0] as f64,
+        DataType::I16 => words[0] as i16 as f64,
+        DataType::U32 => combine_words(words, config.word_order) as f64,
+        DataType::I32 => combine_words(words, config.word_order) as i32 as f64,
+        DataType::F32 => f32::from_bits(combine_words(words, config.word_order)) as f64,
+    };
+
+    Some(config.scale * raw + config.offset)
+}
+
+/// Combine two 16-bit registers into a 32-bit value per `word_order`.
+fn combine_words(words: &[u16], word_order: WordOrder) -> u32 {
+    let (high, low) = match word_order {
+        WordOrder::BigEndian => (words[0], words[1]),
+        WordOrder::LittleEndian => (words[1], words[0]),
+    };
+    ((high as u32) << 16) | (low as u32)
+}
+
+/// A contiguous run of registers of the same [`super::config::RegisterType`]
+/// that can be satisfied by a single Modbus read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegisterBatch {
+    pub register_type: super::config::RegisterType,
+    pub start_address: u16,
+    pub count: u16,
+}
+
+/// Group per-entity register configs into the minimal set of contiguous
+/// reads needed to cover them, one batch per [`super::config::RegisterType`]
+/// run of adjacent addresses.
+///
+/// Registers are sorted by (register_type, address) first, so entities
+/// don't need to be declared in address order in config.
+pub fn batch_registers(
+    registers: &std::collections::HashMap<String, RegisterConfig>,
+) -> Vec<RegisterBatch> {
+    let mut spans: Vec<(super::config::RegisterType, u16, u16)> = registers
+        .values()
+        .map(|r| (r.register_type, r.address, r.address + r.data_type.register_count()))
+        .collect();
+    spans.sort_by_key(|&(register_type, start, _)| (register_type as u8, start));
+
+    let mut batches: Vec<RegisterBatch> = Vec::new();
+    for (register_type, start, end) in spans {
+        if let Some(last) = batches.last_mut() {
+            if last.register_type == register_type && start <= last.start_address + last.count {
+                last.count = last.count.max(end - last.start_address);
+                continue;
+            }
+        }
+        batches.push(RegisterBatch {
+            register_type,
+            start_address: start,
+            count: end - start,
+        });
+    }
+    batches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::modbus::config::RegisterType;
+
+    fn register(
+        address: u16,
+        register_type: RegisterType,
+        data_type: DataType,
+        word_order: WordOrder,
+    ) -> RegisterConfig {
+        RegisterConfig {
+            name: "test".to_string(),
+            address,
+            register_type,
+            data_type,
+            word_order,
+            scale: 1.0,
+            offset: 0.0,
+            device_class: None,
+            unit: None,
+        }
+    }
+
+    #[test]
+    fn decodes_u16() {
+        let r = register(0, RegisterType::Holding, DataType::U16, WordOrder::BigEndian);
+        assert_eq!(decode_register(&r, &[42]), Some(42.0));
+    }
+
+    #[test]
+    fn decodes_i16_negative() {
+        let r = register(0, RegisterType::Holding, DataType::I16, WordOrder::BigEndian);
+        assert_eq!(decode_register(&r, &[(-5i16) as u16]), Some(-5.0));
+    }
+
+    #[test]
+    fn decodes_u32_big_endian_word_order() {
+        let r = register(0, RegisterType::Input, DataType::U32, WordOrder::BigEndian);
+        // 0x0001_0000 = 65536
+        assert_eq!(decode_register(&r, &[0x0001, 0x0000]), Some(65536.0));
+    }
+
+    #[test]
+    fn decodes_u32_little_endian_word_order() {
+        let r = register(0, RegisterType::Input, DataType::U32, WordOrder::LittleEndian);
+        assert_eq!(decode_register(&r, &[0x0000, 0x0001]), Some(65536.0));
+    }
+
+    #[test]
+    fn decodes_f32_with_scale_and_offset() {
+        let mut r = register(0, RegisterType::Input, DataType::F32, WordOrder::BigEndian);
+        r.scale = 2.0;
+        r.offset = 1.0;
+        let bits = 10.0f32.to_bits();
+        let words = [(bits >> 16) as u16, bits as u16];
+        assert_eq!(decode_register(&r, &words), Some(21.0));
+    }
+
+    #[test]
+    fn missing_words_yields_none() {
+        let r = register(0, RegisterType::Holding, DataType::U32, WordOrder::BigEndian);
+        assert_eq!(decode_register(&r, &[1]), None);
+    }
+
+    #[test]
+    fn batches_merge_contiguous_registers_of_the_same_type() {
+        let mut registers = std::collections::HashMap::new();
+        registers.insert(
+            "sensor.a".to_string(),
+            register(0, RegisterType::Input, DataType::U16, WordOrder::BigEndian),
+        );
+        registers.insert(
+            "sensor.b".to_string(),
+            register(1, RegisterType::Input, DataType::U32, WordOrder::BigEndian),
+        );
+        registers.insert(
+            "sensor.c".to_string(),
+            register(10, RegisterType::Input, DataType::U16, WordOrder::BigEndian),
+        );
+
+        let batches = batch_registers(&registers);
+        assert_eq!(
+            batches,
+            vec![
+                RegisterBatch {
+                    register_type: RegisterType::Input,
+                    start_address: 0,
+                    count: 3,
+                },
+                RegisterBatch {
+                    register_type: RegisterType::Input,
+                    start_address: 10,
+                    count: 1,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+mod backoff;
+mod client;
+mod config;
+mod decoder;
+// Private module - allowed by clippy.toml allow-private-module-inception
+#[allow(clippy::module_inception)]
+mod modbus;
+mod sensor;
+
+pub use config::Config as ModbusConfig;
+pub use modbus::ModbusIntegration;
+use linkme::distributed_slice;
+
+use crate::engine;
+
+#[distributed_slice(engine::INTEGRATION_REGISTRY)]
+fn init_modbus(ctx: &engine::IntegrationContext) -> engine::IntegrationFactoryResult {
+    let modbus_config = if let Some(c) = &ctx.config.integrations.modbus {
+        c
+    } else {
+        return Ok(None);
+    };
+
+    let client = client::TokioModbusClient::new(modbus_config);
+    Ok(Some(Box::new(ModbusIntegration::new(client, modbus_config))))
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::info;
+use tracing::warn;
+
+use super::backoff::Backoff;
+use super::client::ModbusClient;
+use super::decoder;
+use super::sensor::ModbusSensor;
+use super::ModbusConfig;
+use crate::engine::Command;
+use crate::engine::CommandKind;
+use crate::engine::Entity;
+use crate::engine::FromIntegrationMessage;
+use crate::engine::FromIntegrationSender;
+use crate::engine::Integration;
+
+/// Initial and maximum reconnect backoff delays.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Type alias for the shared sensors map, keyed by entity ID
+type SensorsMap = Arc<Mutex<HashMap<String, Arc<Mutex<ModbusSensor>>>>>;
+
+/// Modbus TCP Integration for hearthd
+///
+/// Polls the configured registers on a fixed interval, batching contiguous
+/// registers of the same type into single reads, and reports decoded values
+/// as sensor entities. A dropped connection marks every sensor unavailable
+/// and triggers exponential-backoff reconnect attempts.
+pub struct ModbusIntegration<C: ModbusClient> {
+    client: Arc<Mutex<C>>,
+    config: ModbusConfig,
+    sensors: SensorsMap,
+    to_engine: Option<FromIntegrationSender>,
+    /// Handle to the background poll task
+    _poll_task: Option<JoinHandle<()>>,
+}
+
+impl<C: ModbusClient> ModbusIntegration<C> {
+    /// Create a new Modbus integration
+    pub fn new(client: C, config: &ModbusConfig) -> Self {
+        let mut sensors = HashMap::new();
+        for (entity_id, register_config) in &config.registers {
+            sensors.insert(
+                entity_id.clone(),
+                Arc::new(Mutex::new(ModbusSensor::from_register_config(
+                    entity_id.clone(),
+                    register_config.clone(),
+                ))),
+            );
+        }
+
+        Self {
+            client: Arc::new(Mutex::new(client)),
+            config: config.clone(),
+            sensors: Arc::new(Mutex::new(sensors)),
+            to_engine: None,
+            _poll_task: None,
+        }
+    }
+
+    /// Poll the configured registers on a fixed interval, decoding and
+    /// reporting changes, and reconnecting with backoff on failure.
+    async fn poll_task(
+        client: Arc<Mutex<C>>,
+        config: ModbusConfig,
+        sensors: SensorsMap,
+        to_engine: FromIntegrationSender,
+    ) {
+        let poll_interval = Duration::from_secs(config.poll_interval_secs);
+        let batches = decoder::batch_registers(&config.registers);
+        let mut backoff = Backoff::new(INITIAL_BACKOFF, MAX_BACKOFF);
+        let mut connected = false;
+
+        loop {
+            if !connected {
+                let mut client_guard = client.lock().await;
+                match client_guard.connect().await {
+                    Ok(()) => {
+                        connected = true;
+                        backoff.reset();
+                    }
+                    Err(e) => {
+                        drop(client_guard);
+                        warn!("Error connecting to Modbus device: {}", e);
+                        Self::mark_all_unavailable(&sensors, &to_engine).await;
+                        tokio::time::sleep(backoff.next_delay()).await;
+                        continue;
+                    }
+                }
+            }
+
+            if let Err(e) =
+                Self::poll_once(&client, &batches, &config.registers, &sensors, &to_engine).await
+            {
+                warn!("Error polling Modbus registers: {}", e);
+                connected = false;
+                Self::mark_all_unavailable(&sensors, &to_engine).await;
+                tokio::time::sleep(backoff.next_delay()).await;
+                continue;
+            }
+
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Read every batch, decode each configured register out of its batch's
+    /// words, and report any changed sensor state to the engine.
+    async fn poll_once(
+        client: &Arc<Mutex<C>>,
+        batches: &[decoder::RegisterBatch],
+        registers: &HashMap<String, super::config::RegisterConfig>,
+        sensors: &SensorsMap,
+        to_engine: &FromIntegrationSender,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let mut batch_words = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let mut client_guard = client.lock().await;
+            let words = client_guard
+                .read_registers(batch.register_type, batch.start_address, batch.count)
+                .await?;
+            batch_words.push(words);
+        }
+
+        for (entity_id, register_config) in registers {
+            let batch_index = batches.iter().position(|b| {
+                b.register_type == register_config.register_type
+                    && register_config.address >= b.start_address
+                    && register_config.address + register_config.data_type.register_count()
+                        <= b.start_address + b.count
+            });
+            let Some(batch_index) = batch_index else {
+                continue;
+            };
+            let batch = &batches[batch_index];
+            let offset = (register_config.address - batch.start_address) as usize;
+            let count = register_config.data_type.register_count() as usize;
+            let Some(words) = batch_words[batch_index].get(offset..offset + count) else {
+                continue;
+            };
+            let Some(value) = decoder::decode_register(register_config, words) else {
+                continue;
+            };
+
+            let Some(sensor_arc) = sensors.lock().await.get(entity_id).cloned() else {
+                continue;
+            };
+            let mut sensor = sensor_arc.lock().await;
+            if sensor.update_value(value) {
+                let fields = sensor.state_json();
+                drop(sensor);
+                Self::report_sensor_state_change_static(entity_id, fields, to_engine).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark every known sensor unavailable and report the resulting changes.
+    async fn mark_all_unavailable(sensors: &SensorsMap, to_engine: &FromIntegrationSender) {
+        let sensors_guard = sensors.lock().await;
+        for (entity_id, sensor_arc) in sensors_guard.iter() {
+            let mut sensor = sensor_arc.lock().await;
+            if sensor.mark_unavailable() {
+                let fields = sensor.state_json();
+                drop(sensor);
+                Self::report_sensor_state_change_static(entity_id, fields, to_engine).await;
+            }
+        }
+    }
+
+    /// Register an entity with the engine (static version)
+    async fn register_entity_static(entity_id: &str, to_engine: &FromIntegrationSender) {
+        let msg = FromIntegrationMessage::EntityDiscovered {
+            entity_id: entity_id.to_string(),
+            integration_name: "modbus".to_string(),
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send EntityDiscovered message: {}", e);
+        } else {
+            info!("Registered entity: {}", entity_id);
+        }
+    }
+
+    /// Report a sensor state change to the engine (static version)
+    async fn report_sensor_state_change_static(
+        entity_id: &str,
+        fields: serde_json::Value,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::SensorStateChanged {
+            entity_id: entity_id.to_string(),
+            fields,
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send SensorStateChanged message: {}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl<C: ModbusClient + 'static> Integration for ModbusIntegration<C> {
+    fn name(&self) -> &str {
+        "modbus"
+    }
+
+    async fn setup(&mut self, tx: FromIntegrationSender) -> Result<(), Box<dyn Error + Send>> {
+        self.to_engine = Some(tx.clone());
+
+        for entity_id in self.sensors.lock().await.keys() {
+            Self::register_entity_static(entity_id, &tx).await;
+        }
+
+        info!("Modbus integration setup complete, spawning poll task...");
+
+        let client = self.client.clone();
+        let config = self.config.clone();
+        let sensors = self.sensors.clone();
+
+        let task = tokio::spawn(async move {
+            Self::poll_task(client, config, sensors, tx).await;
+        });
+        self._poll_task = Some(task);
+
+        info!("Modbus integration ready");
+        Ok(())
+    }
+
+    fn accepted_commands(&self) -> &[CommandKind] {
+        // Modbus sensors are read-only; the integration has no commands to handle yet.
+        &[]
+    }
+
+    async fn handle_message(
+        &mut self,
+        _cmd: Box<dyn Command>,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<(), Box<dyn Error + Send>> {
+        info!("Modbus integration shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::modbus::client::MockModbusClient;
+    use crate::integrations::modbus::config::DataType;
+    use crate::integrations::modbus::config::RegisterConfig;
+    use crate::integrations::modbus::config::RegisterType;
+    use crate::integrations::modbus::config::WordOrder;
+
+    fn test_config() -> ModbusConfig {
+        ModbusConfig {
+            host: "10.0.0.50".to_string(),
+            port: 502,
+            unit_id: 1,
+            poll_interval_secs: 10,
+            registers: HashMap::from([(
+                "sensor.ac_power".to_string(),
+                RegisterConfig {
+                    name: "AC Power".to_string(),
+                    address: 0,
+                    register_type: RegisterType::Input,
+                    data_type: DataType::U16,
+                    word_order: WordOrder::BigEndian,
+                    scale: 1.0,
+                    offset: 0.0,
+                    device_class: Some("power".to_string()),
+                    unit: Some("W".to_string()),
+                },
+            )]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_modbus_integration_creation_registers_configured_sensors() {
+        let client = MockModbusClient::new();
+        let config = test_config();
+        let integration = ModbusIntegration::new(client, &config);
+
+        let sensors = integration.sensors.lock().await;
+        assert_eq!(sensors.len(), 1);
+        assert!(sensors.contains_key("sensor.ac_power"));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_updates_sensor_from_decoded_register() {
+        let mut client = MockModbusClient::new();
+        client.responses.insert((RegisterType::Input, 0), vec![1500]);
+        let config = test_config();
+        let integration = ModbusIntegration::new(client, &config);
+
+        let batches = decoder::batch_registers(&config.registers);
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        ModbusIntegration::<MockModbusClient>::poll_once(
+            &integration.client,
+            &batches,
+            &config.registers,
+            &integration.sensors,
+            &tx,
+        )
+        .await
+        .unwrap();
+
+        let sensors = integration.sensors.lock().await;
+        let sensor = sensors.get("sensor.ac_power").unwrap().lock().await;
+        assert_eq!(sensor.state_json()["value"], serde_json::json!(1500.0));
+        assert_eq!(sensor.state_json()["available"], serde_json::json!(true));
+    }
+
+    #[tokio::test]
+    async fn test_mark_all_unavailable_reports_change() {
+        let mut client = MockModbusClient::new();
+        client.responses.insert((RegisterType::Input, 0), vec![1500]);
+        let config = test_config();
+        let integration = ModbusIntegration::new(client, &config);
+
+        let batches = decoder::batch_registers(&config.registers);
+        let (tx, _rx) = tokio::sync::mpsc::channel(8);
+        ModbusIntegration::<MockModbusClient>::poll_once(
+            &integration.client,
+            &batches,
+            &config.registers,
+            &integration.sensors,
+            &tx,
+        )
+        .await
+        .unwrap();
+
+        ModbusIntegration::<MockModbusClient>::mark_all_unavailable(&integration.sensors, &tx)
+            .await;
+
+        let sensors = integration.sensors.lock().await;
+        let sensor = sensors.get("sensor.ac_power").unwrap().lock().await;
+        assert_eq!(sensor.state_json()["available"], serde_json::json!(false));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::engine::Entity;
+use crate::integrations::modbus::config::RegisterConfig;
+use crate::integrations::mqtt::DiscoveryMessage;
+use crate::integrations::mqtt::hearthd_state_topic;
+
+/// A sensor entity synthesized from a polled Modbus register (e.g. a solar
+/// inverter's AC power or a power meter's energy total).
+#[derive(Debug, Clone)]
+pub struct ModbusSensor {
+    /// Entity ID (e.g., "sensor.inverter_ac_power")
+    #[allow(dead_code)]
+    pub id: String,
+
+    /// Human-readable name
+    pub name: String,
+
+    /// Decoder configuration for this register
+    config: RegisterConfig,
+
+    /// Most recently decoded value, or `None` before the first successful
+    /// read.
+    value: Option<f64>,
+
+    /// Whether the entity is currently reachable. Cleared when the
+    /// connection is lost so stale readings aren't reported as live.
+    available: bool,
+}
+
+impl ModbusSensor {
+    /// Create a ModbusSensor entity from its register configuration.
+    pub fn from_register_config(id: String, config: RegisterConfig) -> Self {
+        Self {
+            id,
+            name: config.name.clone(),
+            config,
+            value: None,
+            available: false,
+        }
+    }
+
+    pub fn register_config(&self) -> &RegisterConfig {
+        &self.config
+    }
+
+    /// Update the sensor's decoded value, returning `true` if the
+    /// reported state changed (value or availability).
+    pub fn update_value(&mut self, value: f64) -> bool {
+        let changed = self.value != Some(value) || !self.available;
+        self.value = Some(value);
+        self.available = true;
+        changed
+    }
+
+    /// Mark the entity unavailable, e.g. after a connection loss.
+    /// Returns `true` if this is a change from the previous availability.
+    pub fn mark_unavailable(&mut self) -> bool {
+        let changed = self.available;
+        self.available = false;
+        changed
+    }
+
+    /// Build the Home Assistant MQTT discovery message advertising this
+    /// sensor on the broker, so it shows up as a regular numeric `sensor`
+    /// entity alongside anything discovered over MQTT directly.
+    pub fn to_discovery(&self, node_id: &str) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some(self.name.clone()),
+            unique_id: Some(self.id.clone()),
+            state_topic: Some(hearthd_state_topic("sensor", node_id)),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: self.config.device_class.clone(),
+            value_template: Some("{{ value_json.value }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: self.config.unit.clone(),
+        }
+    }
+}
+
+impl Entity for ModbusSensor {
+    fn state_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "value": self.value,
+            "available": self.available,
+            "device_class": self.config.device_class,
+            "unit": self.config.unit,
+        })
+    }
+
+    fn platform(&self) -> &'static str {
+        "sensor"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::modbus::config::DataType;
+    use crate::integrations::modbus::config::RegisterType;
+    use crate::integrations::modbus::config::WordOrder;
+
+    fn register_config() -> RegisterConfig {
+        RegisterConfig {
+            name: "AC Power".to_string(),
+            address: 100,
+            register_type: RegisterType::Input,
+            data_type: DataType::U16,
+            word_order: WordOrder::BigEndian,
+            scale: 1.0,
+            offset: 0.0,
+            device_class: Some("power".to_string()),
+            unit: Some("W".to_string()),
+        }
+    }
+
+    #[test]
+    fn update_value_marks_available_and_reports_change() {
+        let mut sensor =
+            ModbusSensor::from_register_config("sensor.ac_power".to_string(), register_config());
+
+        assert!(sensor.update_value(1500.0));
+        assert_eq!(sensor.state_json()["value"], serde_json::json!(1500.0));
+        assert_eq!(sensor.state_json()["available"], serde_json::json!(true));
+
+        assert!(!sensor.update_value(1500.0));
+        assert!(sensor.update_value(1600.0));
+    }
+
+    #[test]
+    fn mark_unavailable_reports_change_once() {
+        let mut sensor =
+            ModbusSensor::from_register_config("sensor.ac_power".to_string(), register_config());
+        sensor.update_value(1500.0);
+
+        assert!(sensor.mark_unavailable());
+        assert_eq!(sensor.state_json()["available"], serde_json::json!(false));
+        assert!(!sensor.mark_unavailable());
+    }
+
+    #[test]
+    fn to_discovery_carries_device_class_and_unit() {
+        let sensor =
+            ModbusSensor::from_register_config("sensor.ac_power".to_string(), register_config());
+
+        let discovery = sensor.to_discovery("inverter");
+        assert_eq!(discovery.name, Some("AC Power".to_string()));
+        assert_eq!(discovery.unique_id, Some("sensor.ac_power".to_string()));
+        assert_eq!(discovery.device_class, Some("power".to_string()));
+        assert_eq!(discovery.unit_of_measurement, Some("W".to_string()));
+        assert_eq!(
+            discovery.state_topic,
+            Some("hearthd/sensor/inverter/state".to_string())
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt;
+use std::time::Duration;
 
 use serde::Deserialize;
 use serde::Serialize;
@@ -7,6 +8,7 @@ use serde::Serialize;
 use crate::engine::Entity;
 use crate::integrations::mqtt::discovery::DeviceInfo;
 use crate::integrations::mqtt::discovery::DiscoveryMessage;
+use crate::integrations::mqtt::value_template;
 
 /// Device class for binary sensors, matching Home Assistant's binary_sensor device classes.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -99,13 +101,21 @@ pub struct BinarySensorState {
     /// Whether the sensor is active (meaning depends on device class:
     /// motion detected, door open, tamper triggered, etc.)
     pub on: bool,
+
+    /// Whether the entity is currently reachable, tracked via
+    /// `availability_topic` and/or `expire_after`. Entities with neither
+    /// configured are always available.
+    pub available: bool,
+
+    /// Elapsed time (per the integration's clock) at which `on` last
+    /// changed, used to drive `off_delay`.
+    pub last_changed: Option<Duration>,
 }
 
 /// Binary sensor entity (e.g., motion/occupancy sensor)
 #[derive(Debug, Clone)]
 pub struct BinarySensor {
     /// Entity ID (e.g., "binary_sensor.living_room")
-    #[allow(dead_code)]
     pub id: String,
 
     /// Human-readable name
@@ -129,22 +139,41 @@ pub struct BinarySensor {
     /// Topic to receive state updates
     pub state_topic: String,
 
-    /// Value template for extracting state from JSON payload
-    /// e.g., "{{ value_json.occupancy }}" -> key is "occupancy"
+    /// Value template for extracting state from JSON payload, e.g.
+    /// "{{ value_json.occupancy }}". Evaluated with [`value_template`].
     value_template: Option<String>,
-}
 
-/// Extract the JSON key name from a Zigbee2MQTT value template.
-///
-/// Parses templates like `{{ value_json.occupancy }}` and returns `"occupancy"`.
-/// Returns `None` if the template doesn't match the expected format.
-fn parse_value_template_key(template: &str) -> Option<&str> {
-    let inner = template
-        .trim()
-        .strip_prefix("{{")?
-        .strip_suffix("}}")?
-        .trim();
-    inner.strip_prefix("value_json.")
+    /// Payload that represents the "on" state, if configured.
+    #[allow(dead_code)]
+    payload_on: Option<String>,
+
+    /// Auto-reset `on` to `false` this long after it last became `true`, if
+    /// no new "on" payload arrives first (Home Assistant's `off_delay`).
+    off_delay: Option<Duration>,
+
+    /// Mark the entity unavailable if no state update arrives within this
+    /// long, independent of `availability_topic` (Home Assistant's
+    /// `expire_after`).
+    expire_after: Option<Duration>,
+
+    /// Topic carrying this entity's availability, if tracked separately
+    /// from its state topic.
+    pub availability_topic: Option<String>,
+
+    /// Payload on `availability_topic` meaning available. Defaults to
+    /// Home Assistant's own default of `"online"`.
+    payload_available: String,
+
+    /// Payload on `availability_topic` meaning unavailable. Defaults to
+    /// Home Assistant's own default of `"offline"`.
+    payload_not_available: String,
+
+    /// Elapsed time at which the most recent state message arrived, used
+    /// to drive `expire_after`.
+    last_message_at: Option<Duration>,
+
+    /// QoS to use for this sensor's state/availability topics.
+    pub qos: crate::integrations::mqtt::client::QoS,
 }
 
 impl BinarySensor {
@@ -167,44 +196,168 @@ impl BinarySensor {
             .ok_or("Missing state_topic in discovery message")?;
 
         let device_class = discovery.device_class.map(BinarySensorDeviceClass::from);
+        let qos = crate::integrations::mqtt::discovery::qos_from_discovery(discovery.qos);
 
         Ok(Self {
             id,
             name,
             unique_id,
             device_class,
-            state: BinarySensorState::default(),
+            state: BinarySensorState {
+                on: false,
+                available: true,
+                last_changed: None,
+            },
             device_info: discovery.device,
             state_topic,
             value_template: discovery.value_template,
+            payload_on: discovery.payload_on,
+            off_delay: discovery.off_delay.map(Duration::from_secs),
+            expire_after: discovery.expire_after.map(Duration::from_secs),
+            availability_topic: discovery.availability_topic,
+            payload_available: discovery
+                .payload_available
+                .unwrap_or_else(|| "online".to_string()),
+            payload_not_available: discovery
+                .payload_not_available
+                .unwrap_or_else(|| "offline".to_string()),
+            last_message_at: None,
+            qos,
         })
     }
 
-    /// Update the binary sensor state from an MQTT payload
+    /// Update the binary sensor state from an MQTT payload.
     ///
     /// Zigbee2MQTT sends state updates as JSON, e.g.:
     /// {"occupancy": true, "battery": 100, "illuminance": 42, "linkquality": 120}
-    pub fn update_state(&mut self, payload: &[u8]) -> Result<(), Box<dyn Error>> {
+    ///
+    /// The `value_template` (if any) is evaluated against the parsed payload
+    /// via [`value_template::evaluate`], supporting the common Home
+    /// Assistant/Zigbee2MQTT templating subset (nested paths, `is defined`,
+    /// `| default(...)`, and inline ternaries) rather than just a bare
+    /// top-level key. A template whose path can't be resolved leaves the
+    /// state unchanged rather than resetting it to off.
+    ///
+    /// `now` is the elapsed time (per the integration's clock) at which
+    /// this message arrived; it seeds `off_delay`/`expire_after` tracking.
+    /// Returns the next elapsed-time deadline, if any, at which
+    /// [`BinarySensor::check_timers`] should be called again.
+    pub fn update_state(
+        &mut self,
+        payload: &[u8],
+        now: Duration,
+    ) -> Result<Option<Duration>, Box<dyn Error>> {
         let json_str = std::str::from_utf8(payload)?;
         let state_update: serde_json::Value = serde_json::from_str(json_str)?;
 
-        // Determine which JSON key holds the occupancy state
-        let key = self
-            .value_template
-            .as_deref()
-            .and_then(parse_value_template_key)
-            .unwrap_or("state");
-
-        // Extract occupancy from the determined key
-        if let Some(value) = state_update.get(key) {
-            self.state.on = match value {
-                serde_json::Value::Bool(b) => *b,
-                serde_json::Value::String(s) => s == "ON" || s == "true",
-                _ => false,
-            };
+        self.last_message_at = Some(now);
+        self.state.available = true;
+
+        let resolved = match self.value_template.as_deref() {
+            Some(template) => value_template::evaluate(template, &state_update),
+            None => state_update.get("state").cloned(),
+        };
+
+        if let Some(value) = resolved {
+            let on = value_template::as_on_off(&value, self.payload_on.as_deref());
+            if on != self.state.on {
+                self.state.on = on;
+                self.state.last_changed = Some(now);
+            }
+        }
+
+        Ok(self.next_wakeup())
+    }
+
+    /// Update availability from a message on `availability_topic`.
+    ///
+    /// Payloads matching neither `payload_available` nor
+    /// `payload_not_available` are ignored, matching Home Assistant's own
+    /// handling of unrecognized availability payloads.
+    pub fn update_availability(&mut self, payload: &[u8]) {
+        let payload = String::from_utf8_lossy(payload);
+        if *payload == self.payload_available {
+            self.state.available = true;
+        } else if *payload == self.payload_not_available {
+            self.state.available = false;
+        }
+    }
+
+    /// Apply any `off_delay`/`expire_after` timer that has elapsed as of
+    /// `now`, returning the next deadline still pending (if any) so the
+    /// caller can reschedule its wakeup.
+    pub fn check_timers(&mut self, now: Duration) -> Option<Duration> {
+        if let (Some(off_delay), Some(last_changed)) = (self.off_delay, self.state.last_changed) {
+            if self.state.on && now >= last_changed + off_delay {
+                self.state.on = false;
+                self.state.last_changed = Some(now);
+            }
+        }
+
+        if let (Some(expire_after), Some(last_message_at)) =
+            (self.expire_after, self.last_message_at)
+        {
+            if self.state.available && now >= last_message_at + expire_after {
+                self.state.available = false;
+            }
         }
 
-        Ok(())
+        self.next_wakeup()
+    }
+
+    /// Build the Home Assistant MQTT discovery message advertising this
+    /// sensor on the broker, the inverse of [`BinarySensor::from_discovery`].
+    /// The emitted message points at hearthd's own state topic rather than
+    /// the one this sensor was originally discovered from, since hearthd is
+    /// now the entity's source of truth.
+    pub fn to_discovery(&self, node_id: &str) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some(self.name.clone()),
+            unique_id: Some(self.unique_id.clone()),
+            state_topic: Some(super::discovery::hearthd_state_topic("binary_sensor", node_id)),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: self.device_info.clone(),
+            payload_on: Some("true".to_string()),
+            payload_off: Some("false".to_string()),
+            brightness: None,
+            schema: None,
+            device_class: self.device_class.as_ref().map(|c| c.to_string()),
+            value_template: Some("{{ value_json.on }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: super::discovery::qos_to_discovery(self.qos),
+            supported_color_modes: None,
+        }
+    }
+
+    /// The next elapsed-time deadline at which a pending `off_delay` or
+    /// `expire_after` timer should be checked again, if either is armed.
+    fn next_wakeup(&self) -> Option<Duration> {
+        let off_deadline = match (self.off_delay, self.state.last_changed) {
+            (Some(off_delay), Some(last_changed)) if self.state.on => {
+                Some(last_changed + off_delay)
+            }
+            _ => None,
+        };
+        let expire_deadline = match (self.expire_after, self.last_message_at) {
+            (Some(expire_after), Some(last_message_at)) if self.state.available => {
+                Some(last_message_at + expire_after)
+            }
+            _ => None,
+        };
+
+        match (off_deadline, expire_deadline) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(a), None) | (None, Some(a)) => Some(a),
+            (None, None) => None,
+        }
     }
 }
 
@@ -240,6 +393,15 @@ mod tests {
             schema: None,
             device_class: Some("motion".to_string()),
             value_template: Some("{{ value_json.occupancy }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let sensor = BinarySensor::from_discovery(
@@ -272,6 +434,15 @@ mod tests {
             schema: None,
             device_class: None,
             value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let result = BinarySensor::from_discovery(
@@ -298,6 +469,15 @@ mod tests {
             schema: None,
             device_class: Some("motion".to_string()),
             value_template: Some("{{ value_json.occupancy }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let mut sensor = BinarySensor::from_discovery(
@@ -309,7 +489,7 @@ mod tests {
 
         let payload =
             br#"{"occupancy": true, "battery": 95, "illuminance": 42, "linkquality": 120}"#;
-        sensor.update_state(payload).unwrap();
+        sensor.update_state(payload, Duration::ZERO).unwrap();
 
         assert!(sensor.state.on);
     }
@@ -330,6 +510,15 @@ mod tests {
             schema: None,
             device_class: Some("motion".to_string()),
             value_template: Some("{{ value_json.occupancy }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let mut sensor = BinarySensor::from_discovery(
@@ -340,7 +529,7 @@ mod tests {
         .unwrap();
 
         let payload = br#"{"occupancy": false, "battery": 100}"#;
-        sensor.update_state(payload).unwrap();
+        sensor.update_state(payload, Duration::ZERO).unwrap();
 
         assert!(!sensor.state.on);
     }
@@ -361,6 +550,15 @@ mod tests {
             schema: None,
             device_class: None,
             value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let mut sensor = BinarySensor::from_discovery(
@@ -371,28 +569,217 @@ mod tests {
         .unwrap();
 
         let payload = br#"{"state": "ON"}"#;
-        sensor.update_state(payload).unwrap();
+        sensor.update_state(payload, Duration::ZERO).unwrap();
 
         assert!(sensor.state.on);
     }
 
     #[test]
-    fn test_parse_value_template_key() {
+    fn test_update_state_nested_path_template() {
+        let discovery = DiscoveryMessage {
+            name: Some("Contact".to_string()),
+            unique_id: Some("test_sensor".to_string()),
+            state_topic: Some("zigbee2mqtt/sensor".to_string()),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: Some("door".to_string()),
+            value_template: Some("{{ value_json.state.contact }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        };
+
+        let mut sensor = BinarySensor::from_discovery(
+            discovery,
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
+
+        let payload = br#"{"state": {"contact": true}}"#;
+        sensor.update_state(payload, Duration::ZERO).unwrap();
+
+        assert!(sensor.state.on);
+    }
+
+    #[test]
+    fn test_update_state_missing_path_leaves_state_unchanged() {
+        let discovery = DiscoveryMessage {
+            name: Some("Contact".to_string()),
+            unique_id: Some("test_sensor".to_string()),
+            state_topic: Some("zigbee2mqtt/sensor".to_string()),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: Some("door".to_string()),
+            value_template: Some("{{ value_json.contact }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        };
+
+        let mut sensor = BinarySensor::from_discovery(
+            discovery,
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
+        sensor.state.on = true;
+
+        let payload = br#"{"battery": 50}"#;
+        sensor.update_state(payload, Duration::ZERO).unwrap();
+
+        assert!(sensor.state.on, "unresolved path must not reset the state");
+    }
+
+    fn motion_discovery(off_delay: Option<u64>, expire_after: Option<u64>) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some("Motion".to_string()),
+            unique_id: Some("test_sensor".to_string()),
+            state_topic: Some("zigbee2mqtt/sensor".to_string()),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: Some("motion".to_string()),
+            value_template: Some("{{ value_json.occupancy }}".to_string()),
+            off_delay,
+            expire_after,
+            availability_topic: Some("zigbee2mqtt/sensor/availability".to_string()),
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        }
+    }
+
+    #[test]
+    fn test_off_delay_resets_on_after_elapsing() {
+        let mut sensor = BinarySensor::from_discovery(
+            motion_discovery(Some(5), None),
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
+
+        let next = sensor
+            .update_state(br#"{"occupancy": true}"#, Duration::from_secs(10))
+            .unwrap();
+        assert!(sensor.state.on);
+        assert_eq!(next, Some(Duration::from_secs(15)));
+
+        // Not yet elapsed: still on.
         assert_eq!(
-            parse_value_template_key("{{ value_json.occupancy }}"),
-            Some("occupancy")
+            sensor.check_timers(Duration::from_secs(14)),
+            Some(Duration::from_secs(15))
         );
+        assert!(sensor.state.on);
+
+        // Elapsed: auto-resets to off.
+        assert_eq!(sensor.check_timers(Duration::from_secs(15)), None);
+        assert!(!sensor.state.on);
+    }
+
+    #[test]
+    fn test_expire_after_marks_unavailable_without_new_messages() {
+        let mut sensor = BinarySensor::from_discovery(
+            motion_discovery(None, Some(30)),
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
+
+        sensor
+            .update_state(br#"{"occupancy": false}"#, Duration::ZERO)
+            .unwrap();
+        assert!(sensor.state.available);
+
+        sensor.check_timers(Duration::from_secs(29));
+        assert!(sensor.state.available, "not yet expired");
+
+        sensor.check_timers(Duration::from_secs(30));
+        assert!(!sensor.state.available, "expired with no new message");
+    }
+
+    #[test]
+    fn test_availability_topic_tracks_online_offline() {
+        let mut sensor = BinarySensor::from_discovery(
+            motion_discovery(None, None),
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
         assert_eq!(
-            parse_value_template_key("{{value_json.contact}}"),
-            Some("contact")
+            sensor.availability_topic.as_deref(),
+            Some("zigbee2mqtt/sensor/availability")
         );
-        assert_eq!(parse_value_template_key("invalid"), None);
-        assert_eq!(parse_value_template_key("{{ something_else }}"), None);
+
+        sensor.update_availability(b"offline");
+        assert!(!sensor.state.available);
+
+        sensor.update_availability(b"online");
+        assert!(sensor.state.available);
+
+        // Unrecognized payloads are ignored rather than clearing state.
+        sensor.update_availability(b"garbage");
+        assert!(sensor.state.available);
+    }
+
+    #[test]
+    fn test_to_discovery_roundtrips_name_and_device_class() {
+        let sensor = BinarySensor::from_discovery(
+            motion_discovery(None, None),
+            "binary_sensor.test".to_string(),
+            "test".to_string(),
+        )
+        .unwrap();
+
+        let discovery = sensor.to_discovery("test");
+        assert_eq!(discovery.name, Some("Motion".to_string()));
+        assert_eq!(discovery.device_class, Some("motion".to_string()));
+        assert_eq!(
+            discovery.state_topic,
+            Some("hearthd/binary_sensor/test/state".to_string())
+        );
+        assert_eq!(discovery.value_template, Some("{{ value_json.on }}".to_string()));
     }
 
     #[test]
     fn test_state_json() {
-        let state = BinarySensorState { on: true };
+        let state = BinarySensorState {
+            on: true,
+            available: true,
+            last_changed: None,
+        };
         let sensor = BinarySensor {
             id: "binary_sensor.test".to_string(),
             name: "Test".to_string(),
@@ -402,6 +789,14 @@ mod tests {
             device_info: None,
             state_topic: "test".to_string(),
             value_template: None,
+            payload_on: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: "online".to_string(),
+            payload_not_available: "offline".to_string(),
+            last_message_at: None,
+            qos: super::client::QoS::AtMostOnce,
         };
 
         let json = sensor.state_json();
@@ -1,23 +1,181 @@
 use std::error::Error;
+use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use rumqttc::AsyncClient;
 use rumqttc::Event;
+use rumqttc::LastWill;
 use rumqttc::MqttOptions;
 use rumqttc::Packet;
-use rumqttc::QoS;
+pub use rumqttc::QoS;
+use rumqttc::TlsConfiguration;
+use rumqttc::Transport;
+use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
-use tracing;
+use tracing::info;
+use tracing::warn;
+
+use crate::integrations::mqtt::config::MqttVersion;
+
+/// How long a QoS 1+ [`MqttClient::publish`] waits for ack activity from the
+/// broker before giving up and returning anyway; see
+/// [`RumqttcClient::publish`].
+const PUBACK_WAIT: Duration = Duration::from_secs(5);
+
+/// Delay before the first reconnect attempt after losing the connection;
+/// doubles on each consecutive failure (see [`reconnect_backoff`]), and
+/// resets once a `ConnAck` succeeds.
+const RECONNECT_BACKOFF_BASE: Duration = Duration::from_millis(500);
+
+/// Upper bound on the reconnect delay, no matter how many consecutive
+/// failures have occurred.
+const RECONNECT_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Exponential backoff with full jitter for the `attempt`-th consecutive
+/// reconnect failure (0-indexed): picks a random delay in
+/// `[0, min(RECONNECT_BACKOFF_BASE * 2^attempt, RECONNECT_BACKOFF_MAX)]`,
+/// so reconnecting clients don't all retry the broker in lockstep.
+fn reconnect_backoff(attempt: u32) -> Duration {
+    let exp_millis =
+        (RECONNECT_BACKOFF_BASE.as_millis() as u64).saturating_mul(1u64 << attempt.min(16));
+    let cap_millis = exp_millis.min(RECONNECT_BACKOFF_MAX.as_millis() as u64);
+    Duration::from_millis(jitter_millis(cap_millis))
+}
+
+/// A source of jitter that doesn't need a `rand` dependency just for this:
+/// the sub-second component of the system clock, which is unpredictable
+/// enough that concurrent clients won't retry in lockstep.
+fn jitter_millis(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_millis() as u64)
+        .unwrap_or(0);
+    millis % (max + 1)
+}
 
 /// MQTT message received from a subscription
 #[derive(Debug, Clone)]
 pub struct MqttMessage {
     pub topic: String,
     pub payload: Vec<u8>,
-    #[allow(dead_code)]
     pub retain: bool,
+
+    /// QoS the message was delivered at.
+    pub qos: QoS,
+
+    /// MQTT v5 user properties attached to the message, if any (always
+    /// empty over a v3.1.1 connection).
+    pub user_properties: Vec<(String, String)>,
+
+    /// Present for QoS 1+ messages received while the client is configured
+    /// with `manual_ack` (see `MqttConfig::manual_ack`); `None` otherwise,
+    /// including for every message over a client that acks automatically.
+    pub ack: Option<AckHandle>,
+}
+
+impl MqttMessage {
+    /// Confirm delivery of this message to the broker. A no-op if this
+    /// message wasn't received in manual-ack mode (`ack` is `None`) -
+    /// the event loop already acked it automatically on receipt.
+    pub async fn ack(&self) -> Result<(), Box<dyn Error + Send>> {
+        match &self.ack {
+            Some(handle) => handle.ack().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Keyed by the broker-assigned packet id of the publish it came from,
+/// backed by whichever protocol version's client is manually acking -
+/// see [`MqttMessage::ack`].
+#[derive(Clone)]
+pub enum AckHandle {
+    V311 {
+        client: AsyncClient,
+        publish: rumqttc::Publish,
+    },
+    V5 {
+        client: rumqttc::v5::AsyncClient,
+        publish: rumqttc::v5::mqttbytes::v5::Publish,
+    },
+}
+
+impl std::fmt::Debug for AckHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AckHandle::V311 { publish, .. } => {
+                write!(f, "AckHandle::V311 {{ pkid: {} }}", publish.pkid)
+            }
+            AckHandle::V5 { publish, .. } => {
+                write!(f, "AckHandle::V5 {{ pkid: {} }}", publish.pkid)
+            }
+        }
+    }
+}
+
+impl AckHandle {
+    async fn ack(&self) -> Result<(), Box<dyn Error + Send>> {
+        match self {
+            AckHandle::V311 { client, publish } => client
+                .ack(publish)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>),
+            AckHandle::V5 { client, publish } => client
+                .ack(publish)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>),
+        }
+    }
+}
+
+/// How the broker should resend retained messages on a subscription (MQTT
+/// v5 "Retain Handling" subscribe option, part of [`SubscribeProperties`]).
+/// Has no effect over a v3.1.1 connection, which always behaves like
+/// [`RetainHandling::SendAtSubscribe`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetainHandling {
+    /// Send retained messages matching the filter at subscribe time - the
+    /// v3.1.1 behavior, and the default.
+    #[default]
+    SendAtSubscribe,
+    /// Only send retained messages if this subscription didn't already
+    /// exist.
+    SendAtSubscribeIfNew,
+    /// Never send retained messages for this subscription.
+    DoNotSend,
+}
+
+/// MQTT v5 subscribe options, passed to [`MqttClient::subscribe_with`].
+/// Entirely ignored over a v3.1.1 connection.
+#[derive(Debug, Clone, Default)]
+pub struct SubscribeProperties {
+    /// Tag messages delivered through this subscription with an
+    /// identifier, so a subscriber that issued several overlapping
+    /// `subscribe_with` calls can tell which one matched a given message
+    /// without re-parsing its topic.
+    pub subscription_identifier: Option<u32>,
+    /// Don't deliver this client's own publishes back to itself, even if
+    /// they match the subscribed filter.
+    pub no_local: bool,
+    /// See [`RetainHandling`].
+    pub retain_handling: RetainHandling,
+}
+
+/// MQTT v5 publish options, passed to [`MqttClient::publish_with`].
+/// Entirely ignored over a v3.1.1 connection.
+#[derive(Debug, Clone, Default)]
+pub struct PublishProperties {
+    /// Arbitrary key-value metadata attached to the message (e.g. Home
+    /// Assistant discovery origin info, or a correlation id for a
+    /// request/response exchange).
+    pub user_properties: Vec<(String, String)>,
+    /// MIME type describing `payload`'s format, e.g. `"application/json"`.
+    pub content_type: Option<String>,
 }
 
 /// Trait for MQTT client operations
@@ -28,21 +186,74 @@ pub trait MqttClient: Send + Sync {
     /// Connect to the MQTT broker
     async fn connect(&mut self) -> Result<(), Box<dyn Error + Send>>;
 
-    /// Subscribe to an MQTT topic
-    async fn subscribe(&mut self, topic: &str) -> Result<(), Box<dyn Error + Send>>;
+    /// Subscribe to an MQTT topic at the given QoS
+    async fn subscribe(&mut self, topic: &str, qos: QoS) -> Result<(), Box<dyn Error + Send>>;
 
-    /// Publish a message to an MQTT topic
+    /// Subscribe with MQTT v5 subscribe options (see [`SubscribeProperties`]).
+    /// The default implementation ignores `properties` and falls back to
+    /// [`Self::subscribe`] - correct for a v3.1.1-only client, and a safe
+    /// fallback for any client that hasn't been extended to pass them
+    /// through its transport.
+    async fn subscribe_with(
+        &mut self,
+        topic: &str,
+        qos: QoS,
+        properties: SubscribeProperties,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let _ = properties;
+        self.subscribe(topic, qos).await
+    }
+
+    /// Publish a message to an MQTT topic at the given QoS. For QoS 1+,
+    /// implementations should wait for broker ack activity before
+    /// returning, so callers get at-least-once delivery confidence.
     async fn publish(
         &mut self,
         topic: &str,
         payload: &[u8],
+        qos: QoS,
         retain: bool,
     ) -> Result<(), Box<dyn Error + Send>>;
 
+    /// Publish with MQTT v5 publish options (see [`PublishProperties`]).
+    /// The default implementation ignores `properties` and falls back to
+    /// [`Self::publish`].
+    async fn publish_with(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        qos: QoS,
+        retain: bool,
+        properties: PublishProperties,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let _ = properties;
+        self.publish(topic, payload, qos, retain).await
+    }
+
     /// Poll for the next message from subscribed topics
     ///
     /// Returns None if no message is available or if the client should stop
     async fn poll_message(&mut self) -> Option<MqttMessage>;
+
+    /// Poll for the next broker connection state transition (see
+    /// [`ConnectionEvent`]). The default implementation never has one to
+    /// report - correct for a client with no reconnect logic of its own.
+    async fn poll_connection_event(&mut self) -> Option<ConnectionEvent> {
+        None
+    }
+}
+
+/// A broker connection state transition, reported by
+/// [`MqttClient::poll_connection_event`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// The event loop lost the connection (or hasn't established one yet)
+    /// and is retrying with exponential backoff.
+    Offline,
+    /// A fresh, successful CONNACK arrived - every topic previously passed
+    /// to `subscribe`/`subscribe_with` has just been automatically
+    /// re-issued against the broker.
+    Online,
 }
 
 /// Mock MQTT client for testing
@@ -50,8 +261,8 @@ pub trait MqttClient: Send + Sync {
 #[derive(Debug, Default)]
 pub struct MockMqttClient {
     pub messages: Vec<MqttMessage>,
-    pub subscriptions: Vec<String>,
-    pub published: Vec<(String, Vec<u8>, bool)>,
+    pub subscriptions: Vec<(String, QoS)>,
+    pub published: Vec<(String, Vec<u8>, QoS, bool)>,
     pub is_connected: bool,
 }
 
@@ -63,8 +274,8 @@ impl MqttClient for MockMqttClient {
         Ok(())
     }
 
-    async fn subscribe(&mut self, topic: &str) -> Result<(), Box<dyn Error + Send>> {
-        self.subscriptions.push(topic.to_string());
+    async fn subscribe(&mut self, topic: &str, qos: QoS) -> Result<(), Box<dyn Error + Send>> {
+        self.subscriptions.push((topic.to_string(), qos));
         Ok(())
     }
 
@@ -72,10 +283,11 @@ impl MqttClient for MockMqttClient {
         &mut self,
         topic: &str,
         payload: &[u8],
+        qos: QoS,
         retain: bool,
     ) -> Result<(), Box<dyn Error + Send>> {
         self.published
-            .push((topic.to_string(), payload.to_vec(), retain));
+            .push((topic.to_string(), payload.to_vec(), qos, retain));
         Ok(())
     }
 
@@ -98,21 +310,112 @@ impl MockMqttClient {
             topic,
             payload,
             retain,
+            qos: QoS::AtMostOnce,
+            user_properties: Vec::new(),
+            ack: None,
         });
     }
 }
 
+/// Read and parse a PEM certificate chain from `path`, for
+/// `RumqttcClient::build_transport`.
+fn load_pem_certs(path: std::path::PathBuf) -> anyhow::Result<Vec<rustls::Certificate>> {
+    let pem = std::fs::read(&path)
+        .map_err(|e| anyhow::anyhow!("failed to read MQTT client certificate {path:?}: {e}"))?;
+    rustls_pemfile::certs(&mut pem.as_slice())
+        .map(|cert| cert.map(rustls::Certificate))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("failed to parse MQTT client certificate {path:?}: {e}"))
+}
+
+/// Read and parse a PEM private key from `path`, for
+/// `RumqttcClient::build_transport`.
+fn load_pem_private_key(path: std::path::PathBuf) -> anyhow::Result<rustls::PrivateKey> {
+    let pem = std::fs::read(&path)
+        .map_err(|e| anyhow::anyhow!("failed to read MQTT client key {path:?}: {e}"))?;
+    let mut reader = pem.as_slice();
+    let key = rustls_pemfile::pkcs8_private_keys(&mut reader)
+        .map_err(|e| anyhow::anyhow!("failed to parse MQTT client key {path:?}: {e}"))?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("MQTT client key {path:?} contains no PKCS#8 private key"))?;
+    Ok(rustls::PrivateKey(key))
+}
+
+/// Accepts any server certificate without verification, backing
+/// `MqttConfig::tls_insecure_skip_verify`. Never used unless that flag is
+/// explicitly set.
+struct NoServerCertVerification;
+
+impl rustls::client::ServerCertVerifier for NoServerCertVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// A subscription this client has been asked to make, kept around so the
+/// background event loop can replay it against the broker after a
+/// reconnect - the broker doesn't remember subscriptions across a fresh
+/// (non-resumed) session.
+#[derive(Debug, Clone)]
+struct TrackedSubscription {
+    topic: String,
+    qos: QoS,
+
+    /// `Some` if this subscription came in through `subscribe_with`, so
+    /// the replay can carry the same v5 options forward.
+    properties: Option<SubscribeProperties>,
+}
+
 /// Real MQTT client implementation using rumqttc
+///
+/// Supports both MQTT v3.1.1 and v5 ([`MqttVersion`]), selected once at
+/// construction time from `config.protocol_version`. The two protocol
+/// versions use distinct `rumqttc` client/event-loop types, so this struct
+/// holds one or the other (never both) behind its own `Option` pair rather
+/// than a shared field.
 pub struct RumqttcClient {
-    /// MQTT connection options (stored for lazy initialization)
-    mqtt_options: MqttOptions,
+    /// MQTT connection options for a v3.1.1 connection; `None` if this
+    /// client was configured for v5.
+    mqtt_options_v311: Option<MqttOptions>,
+
+    /// MQTT connection options for a v5 connection; `None` if this client
+    /// was configured for v3.1.1.
+    mqtt_options_v5: Option<rumqttc::v5::MqttOptions>,
 
-    /// AsyncClient (created in connect())
-    client: Option<AsyncClient>,
+    /// v3.1.1 AsyncClient (created in connect())
+    client_v311: Option<AsyncClient>,
 
-    /// Message receiver (created in connect())
+    /// v5 AsyncClient (created in connect())
+    client_v5: Option<rumqttc::v5::AsyncClient>,
+
+    /// Message receiver (created in connect()), fed by whichever protocol
+    /// version's event loop is running.
     message_rx: Option<mpsc::UnboundedReceiver<MqttMessage>>,
 
+    /// Connection state transition receiver (created in connect()); see
+    /// [`ConnectionEvent`].
+    connection_rx: Option<mpsc::UnboundedReceiver<ConnectionEvent>>,
+
+    /// Every topic subscribed so far, replayed in full by the event loop
+    /// after each successful reconnect. Shared with the background task
+    /// (which only reads it) behind a `tokio::sync::Mutex` since
+    /// `subscribe`/`subscribe_with` run on the caller's task.
+    subscriptions: Arc<tokio::sync::Mutex<Vec<TrackedSubscription>>>,
+
+    /// Signaled by the background event loop task whenever it observes ack
+    /// activity (PubAck/PubComp), so [`Self::publish`] can wait briefly for
+    /// QoS 1+ confirmation; see [`PUBACK_WAIT`].
+    ack_notify: Arc<Notify>,
+
     /// Background event loop task handle
     event_loop_task: Option<JoinHandle<()>>,
 }
@@ -120,90 +423,491 @@ pub struct RumqttcClient {
 impl RumqttcClient {
     /// Create a new RumqttcClient from configuration
     pub fn new(config: &crate::integrations::mqtt::MqttConfig) -> anyhow::Result<Self> {
-        let mut mqtt_options =
-            MqttOptions::new(config.client_id.clone(), config.broker.clone(), config.port);
+        match config.protocol_version {
+            MqttVersion::V311 => {
+                let mut mqtt_options = MqttOptions::new(
+                    config.client_id.clone(),
+                    config.broker.clone(),
+                    config.port,
+                );
+
+                // Set keep-alive interval
+                mqtt_options.set_keep_alive(Duration::from_secs(30));
+
+                // Allow large MQTT packets (2 MiB) for discovery payloads
+                mqtt_options.set_max_packet_size(2 * 1024 * 1024, 2 * 1024 * 1024);
+
+                // See MqttConfig::manual_ack.
+                mqtt_options.set_manual_acks(config.manual_ack);
+
+                // Announce hearthd as offline until it explicitly says
+                // otherwise: the broker delivers this retained message to
+                // other subscribers if hearthd disconnects without a clean
+                // shutdown. `setup()` publishes "online" once `connect()`
+                // succeeds.
+                mqtt_options.set_last_will(LastWill::new(
+                    config.status_topic.clone(),
+                    b"offline".to_vec(),
+                    QoS::AtLeastOnce,
+                    true,
+                ));
+
+                if let (Some(username), Some(password)) = (&config.username, &config.password) {
+                    let password = password
+                        .resolve()
+                        .map_err(|e| anyhow::anyhow!("failed to resolve MQTT password: {e}"))?;
+                    mqtt_options.set_credentials(username, password);
+                }
 
-        // Set keep-alive interval
-        mqtt_options.set_keep_alive(Duration::from_secs(30));
+                mqtt_options.set_transport(Self::build_transport(config)?);
+
+                Ok(Self {
+                    mqtt_options_v311: Some(mqtt_options),
+                    mqtt_options_v5: None,
+                    client_v311: None,
+                    client_v5: None,
+                    message_rx: None,
+                    connection_rx: None,
+                    subscriptions: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+                    ack_notify: Arc::new(Notify::new()),
+                    event_loop_task: None,
+                })
+            }
+            MqttVersion::V5 => {
+                let mut mqtt_options = rumqttc::v5::MqttOptions::new(
+                    config.client_id.clone(),
+                    config.broker.clone(),
+                    config.port,
+                );
+
+                mqtt_options.set_keep_alive(Duration::from_secs(30));
+
+                // See MqttConfig::manual_ack.
+                mqtt_options.set_manual_acks(config.manual_ack);
+
+                mqtt_options.set_last_will(rumqttc::v5::LastWill::new(
+                    config.status_topic.clone(),
+                    b"offline".to_vec(),
+                    rumqttc::v5::mqttbytes::v5::QoS::AtLeastOnce,
+                    true,
+                    None,
+                ));
+
+                if let (Some(username), Some(password)) = (&config.username, &config.password) {
+                    let password = password
+                        .resolve()
+                        .map_err(|e| anyhow::anyhow!("failed to resolve MQTT password: {e}"))?;
+                    mqtt_options.set_credentials(username, password);
+                }
 
-        // Allow large MQTT packets (2 MiB) for discovery payloads
-        mqtt_options.set_max_packet_size(2 * 1024 * 1024, 2 * 1024 * 1024);
+                mqtt_options.set_transport(Self::build_transport(config)?);
+
+                Ok(Self {
+                    mqtt_options_v311: None,
+                    mqtt_options_v5: Some(mqtt_options),
+                    client_v311: None,
+                    client_v5: None,
+                    message_rx: None,
+                    connection_rx: None,
+                    subscriptions: Arc::new(tokio::sync::Mutex::new(Vec::new())),
+                    ack_notify: Arc::new(Notify::new()),
+                    event_loop_task: None,
+                })
+            }
+        }
+    }
 
-        // Set credentials if provided
-        if let (Some(username), Some(password)) = (&config.username, &config.password) {
-            mqtt_options.set_credentials(username, password);
+    /// Convert a v5 QoS into the unified [`QoS`] type [`MqttMessage`] and
+    /// this trait use for both protocol versions.
+    fn qos_from_v5(qos: rumqttc::v5::mqttbytes::v5::QoS) -> QoS {
+        match qos {
+            rumqttc::v5::mqttbytes::v5::QoS::AtMostOnce => QoS::AtMostOnce,
+            rumqttc::v5::mqttbytes::v5::QoS::AtLeastOnce => QoS::AtLeastOnce,
+            rumqttc::v5::mqttbytes::v5::QoS::ExactlyOnce => QoS::ExactlyOnce,
         }
+    }
 
-        Ok(Self {
-            mqtt_options,
-            client: None,
-            message_rx: None,
-            event_loop_task: None,
-        })
+    /// Convert the unified [`QoS`] into a v5 QoS for outgoing v5 requests.
+    fn qos_to_v5(qos: QoS) -> rumqttc::v5::mqttbytes::v5::QoS {
+        match qos {
+            QoS::AtMostOnce => rumqttc::v5::mqttbytes::v5::QoS::AtMostOnce,
+            QoS::AtLeastOnce => rumqttc::v5::mqttbytes::v5::QoS::AtLeastOnce,
+            QoS::ExactlyOnce => rumqttc::v5::mqttbytes::v5::QoS::ExactlyOnce,
+        }
+    }
+
+    /// Build the `rumqttc` transport for `config`: plaintext TCP if
+    /// `config.ca_cert` isn't set, otherwise TLS (optionally mutual TLS, if
+    /// `client_cert`/`client_key` are also set) verified against a rustls
+    /// `RootCertStore` built from `ca_cert`'s PEM bundle.
+    fn build_transport(
+        config: &crate::integrations::mqtt::MqttConfig,
+    ) -> anyhow::Result<Transport> {
+        let Some(ca_cert) = &config.ca_cert else {
+            return Ok(Transport::Tcp);
+        };
+
+        let ca_pem = std::fs::read(ca_cert.resolve())
+            .map_err(|e| anyhow::anyhow!("failed to read MQTT ca_cert: {e}"))?;
+
+        let mut root_store = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut ca_pem.as_slice()) {
+            let cert = cert.map_err(|e| anyhow::anyhow!("failed to parse MQTT ca_cert: {e}"))?;
+            root_store
+                .add(cert)
+                .map_err(|e| anyhow::anyhow!("failed to trust MQTT ca_cert: {e}"))?;
+        }
+
+        let builder = rustls::ClientConfig::builder().with_root_certificates(root_store);
+
+        let mut tls_config = match (&config.client_cert, &config.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let cert_chain = load_pem_certs(cert_path.resolve())?;
+                let key = load_pem_private_key(key_path.resolve())?;
+                builder
+                    .with_client_auth_cert(cert_chain, key)
+                    .map_err(|e| anyhow::anyhow!("failed to load MQTT client certificate: {e}"))?
+            }
+            _ => builder.with_no_client_auth(),
+        };
+
+        if config.tls_insecure_skip_verify {
+            tls_config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(NoServerCertVerification));
+        }
+
+        Ok(Transport::Tls(TlsConfiguration::Rustls(Arc::new(
+            tls_config,
+        ))))
+    }
+
+    /// Convert [`RetainHandling`] into rumqttc's v5 `RetainForwardRule` for
+    /// an outgoing `subscribe_with` call.
+    fn retain_handling_to_v5(
+        retain_handling: RetainHandling,
+    ) -> rumqttc::v5::mqttbytes::v5::RetainForwardRule {
+        match retain_handling {
+            RetainHandling::SendAtSubscribe => {
+                rumqttc::v5::mqttbytes::v5::RetainForwardRule::OnEverySubscribe
+            }
+            RetainHandling::SendAtSubscribeIfNew => {
+                rumqttc::v5::mqttbytes::v5::RetainForwardRule::OnNewSubscribe
+            }
+            RetainHandling::DoNotSend => rumqttc::v5::mqttbytes::v5::RetainForwardRule::Never,
+        }
+    }
+
+    /// Wait briefly for the background event loop to observe ack activity,
+    /// giving QoS 1+ publishes at-least-once delivery confidence.
+    ///
+    /// This can't correlate the ack to this specific publish (`rumqttc`'s
+    /// `AsyncClient` doesn't hand back the packet id it assigned), so it's
+    /// a best-effort gate rather than a guarantee: it returns as soon as
+    /// *any* ack arrives, or after [`PUBACK_WAIT`] elapses, whichever comes
+    /// first.
+    async fn wait_for_ack(&self) {
+        let _ = tokio::time::timeout(PUBACK_WAIT, self.ack_notify.notified()).await;
+    }
+
+    /// Record a successful `subscribe`/`subscribe_with` call so the event
+    /// loop can replay it after a reconnect, replacing any existing record
+    /// for the same topic.
+    async fn track_subscription(
+        &self,
+        topic: &str,
+        qos: QoS,
+        properties: Option<SubscribeProperties>,
+    ) {
+        let mut subscriptions = self.subscriptions.lock().await;
+        subscriptions.retain(|sub| sub.topic != topic);
+        subscriptions.push(TrackedSubscription {
+            topic: topic.to_string(),
+            qos,
+            properties,
+        });
     }
 }
 
 #[async_trait]
 impl MqttClient for RumqttcClient {
     async fn connect(&mut self) -> Result<(), Box<dyn Error + Send>> {
-        // Create client and event loop
-        let (client, mut event_loop) = AsyncClient::new(self.mqtt_options.clone(), 10);
-
-        // Create channel for messages
         let (message_tx, message_rx) = mpsc::unbounded_channel();
-
-        // Spawn background task to poll event loop
-        let task = tokio::spawn(async move {
-            loop {
-                match event_loop.poll().await {
-                    Ok(Event::Incoming(Packet::Publish(publish))) => {
-                        let msg = MqttMessage {
-                            topic: publish.topic.to_string(),
-                            payload: publish.payload.to_vec(),
-                            retain: publish.retain,
-                        };
-
-                        // Send to channel; if receiver dropped, exit
-                        if message_tx.send(msg).is_err() {
-                            break;
+        let (connection_tx, connection_rx) = mpsc::unbounded_channel();
+        let ack_notify = self.ack_notify.clone();
+        let subscriptions = self.subscriptions.clone();
+
+        if let Some(mqtt_options) = &self.mqtt_options_v311 {
+            let (client, mut event_loop) = AsyncClient::new(mqtt_options.clone(), 10);
+            let ack_client = client.clone();
+            let subscribe_client = client.clone();
+
+            let task = tokio::spawn(async move {
+                let mut online = false;
+                let mut backoff_attempt: u32 = 0;
+
+                loop {
+                    match event_loop.poll().await {
+                        Ok(Event::Incoming(Packet::Publish(publish))) => {
+                            let ack = (publish.qos != QoS::AtMostOnce).then(|| AckHandle::V311 {
+                                client: ack_client.clone(),
+                                publish: publish.clone(),
+                            });
+
+                            let msg = MqttMessage {
+                                topic: publish.topic.to_string(),
+                                payload: publish.payload.to_vec(),
+                                retain: publish.retain,
+                                qos: publish.qos,
+                                user_properties: Vec::new(),
+                                ack,
+                            };
+
+                            if message_tx.send(msg).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(Event::Incoming(Packet::PubAck(_) | Packet::PubComp(_))) => {
+                            ack_notify.notify_waiters();
+                        }
+                        Ok(Event::Incoming(Packet::ConnAck(connack))) => {
+                            if connack.code != rumqttc::ConnectReturnCode::Success {
+                                warn!("MQTT broker rejected connection: {:?}", connack.code);
+                                if online {
+                                    online = false;
+                                    let _ = connection_tx.send(ConnectionEvent::Offline);
+                                }
+                                continue;
+                            }
+
+                            backoff_attempt = 0;
+                            if !online {
+                                online = true;
+                                let _ = connection_tx.send(ConnectionEvent::Online);
+                            }
+
+                            let tracked = subscriptions.lock().await.clone();
+                            for sub in tracked {
+                                if let Err(e) =
+                                    subscribe_client.subscribe(sub.topic.clone(), sub.qos).await
+                                {
+                                    warn!(
+                                        "failed to replay MQTT subscription to {}: {}",
+                                        sub.topic, e
+                                    );
+                                }
+                            }
+                        }
+                        Ok(_) => {
+                            // Ignore other events (pingresp, etc.)
+                        }
+                        Err(e) => {
+                            warn!("MQTT event loop error: {}", e);
+                            if online {
+                                online = false;
+                                let _ = connection_tx.send(ConnectionEvent::Offline);
+                            }
+                            let delay = reconnect_backoff(backoff_attempt);
+                            backoff_attempt = backoff_attempt.saturating_add(1);
+                            tokio::time::sleep(delay).await;
                         }
                     }
-                    Ok(_) => {
-                        // Ignore other events (connack, puback, etc.)
-                    }
-                    Err(e) => {
-                        tracing::warn!("MQTT event loop error: {}", e);
-                        // Sleep briefly before retrying
-                        tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+                info!("MQTT event loop task exiting");
+            });
+
+            self.client_v311 = Some(client);
+            self.message_rx = Some(message_rx);
+            self.connection_rx = Some(connection_rx);
+            self.event_loop_task = Some(task);
+            return Ok(());
+        }
+
+        if let Some(mqtt_options) = &self.mqtt_options_v5 {
+            let (client, mut event_loop) = rumqttc::v5::AsyncClient::new(mqtt_options.clone(), 10);
+            let ack_client = client.clone();
+            let subscribe_client = client.clone();
+
+            let task = tokio::spawn(async move {
+                let mut online = false;
+                let mut backoff_attempt: u32 = 0;
+
+                loop {
+                    match event_loop.poll().await {
+                        Ok(rumqttc::v5::Event::Incoming(
+                            rumqttc::v5::mqttbytes::v5::Packet::Publish(publish),
+                        )) => {
+                            let user_properties = publish
+                                .properties
+                                .as_ref()
+                                .map(|p| p.user_properties.clone())
+                                .unwrap_or_default();
+                            let qos = RumqttcClient::qos_from_v5(publish.qos);
+                            let ack = (qos != QoS::AtMostOnce).then(|| AckHandle::V5 {
+                                client: ack_client.clone(),
+                                publish: publish.clone(),
+                            });
+
+                            let msg = MqttMessage {
+                                topic: String::from_utf8_lossy(&publish.topic).into_owned(),
+                                payload: publish.payload.to_vec(),
+                                retain: publish.retain,
+                                qos,
+                                user_properties,
+                                ack,
+                            };
+
+                            if message_tx.send(msg).is_err() {
+                                break;
+                            }
+                        }
+                        Ok(rumqttc::v5::Event::Incoming(
+                            rumqttc::v5::mqttbytes::v5::Packet::PubAck(_)
+                            | rumqttc::v5::mqttbytes::v5::Packet::PubComp(_),
+                        )) => {
+                            ack_notify.notify_waiters();
+                        }
+                        Ok(rumqttc::v5::Event::Incoming(
+                            rumqttc::v5::mqttbytes::v5::Packet::ConnAck(connack),
+                        )) => {
+                            let success = rumqttc::v5::mqttbytes::v5::ConnectReturnCode::Success;
+                            if connack.code != success {
+                                warn!("MQTT v5 broker rejected connection: {:?}", connack.code);
+                                if online {
+                                    online = false;
+                                    let _ = connection_tx.send(ConnectionEvent::Offline);
+                                }
+                                continue;
+                            }
+
+                            backoff_attempt = 0;
+                            if !online {
+                                online = true;
+                                let _ = connection_tx.send(ConnectionEvent::Online);
+                            }
+
+                            let tracked = subscriptions.lock().await.clone();
+                            for sub in tracked {
+                                let result = if let Some(properties) = &sub.properties {
+                                    let filter = rumqttc::v5::mqttbytes::v5::SubscribeFilter {
+                                        path: sub.topic.clone(),
+                                        qos: RumqttcClient::qos_to_v5(sub.qos),
+                                        nolocal: properties.no_local,
+                                        preserve_retain: false,
+                                        retain_forward_rule: RumqttcClient::retain_handling_to_v5(
+                                            properties.retain_handling,
+                                        ),
+                                    };
+                                    let sub_properties =
+                                        rumqttc::v5::mqttbytes::v5::SubscribeProperties {
+                                            id: properties
+                                                .subscription_identifier
+                                                .map(|id| id as usize),
+                                            user_properties: Vec::new(),
+                                        };
+                                    subscribe_client.subscribe_with(filter, sub_properties).await
+                                } else {
+                                    let qos = RumqttcClient::qos_to_v5(sub.qos);
+                                    subscribe_client.subscribe(sub.topic.clone(), qos).await
+                                };
+
+                                if let Err(e) = result {
+                                    warn!(
+                                        "failed to replay MQTT v5 subscription to {}: {}",
+                                        sub.topic, e
+                                    );
+                                }
+                            }
+                        }
+                        Ok(rumqttc::v5::Event::Incoming(
+                            rumqttc::v5::mqttbytes::v5::Packet::Disconnect(_),
+                        )) => {
+                            if online {
+                                online = false;
+                                let _ = connection_tx.send(ConnectionEvent::Offline);
+                            }
+                        }
+                        Ok(_) => {
+                            // Ignore other events (pingresp, etc.)
+                        }
+                        Err(e) => {
+                            warn!("MQTT v5 event loop error: {}", e);
+                            if online {
+                                online = false;
+                                let _ = connection_tx.send(ConnectionEvent::Offline);
+                            }
+                            let delay = reconnect_backoff(backoff_attempt);
+                            backoff_attempt = backoff_attempt.saturating_add(1);
+                            tokio::time::sleep(delay).await;
+                        }
                     }
                 }
-            }
-            tracing::info!("MQTT event loop task exiting");
-        });
+                info!("MQTT v5 event loop task exiting");
+            });
+
+            self.client_v5 = Some(client);
+            self.message_rx = Some(message_rx);
+            self.connection_rx = Some(connection_rx);
+            self.event_loop_task = Some(task);
+            return Ok(());
+        }
 
-        self.client = Some(client);
-        self.message_rx = Some(message_rx);
-        self.event_loop_task = Some(task);
+        unreachable!("RumqttcClient::new always sets exactly one of mqtt_options_v311/v5")
+    }
 
-        Ok(())
+    async fn subscribe(&mut self, topic: &str, qos: QoS) -> Result<(), Box<dyn Error + Send>> {
+        if let Some(client) = &self.client_v311 {
+            client
+                .subscribe(topic, qos)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+            self.track_subscription(topic, qos, None).await;
+            return Ok(());
+        }
+
+        if let Some(client) = &self.client_v5 {
+            client
+                .subscribe(topic, Self::qos_to_v5(qos))
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+            self.track_subscription(topic, qos, None).await;
+            return Ok(());
+        }
+
+        Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::NotConnected,
+            "MQTT client not connected. Call connect() first.",
+        )))
     }
 
-    async fn subscribe(&mut self, topic: &str) -> Result<(), Box<dyn Error + Send>> {
-        let client = self
-            .client
-            .as_ref()
-            .ok_or_else(|| -> Box<dyn Error + Send> {
-                Box::new(std::io::Error::new(
-                    std::io::ErrorKind::NotConnected,
-                    "MQTT client not connected. Call connect() first.",
-                ))
-            })?;
+    async fn subscribe_with(
+        &mut self,
+        topic: &str,
+        qos: QoS,
+        properties: SubscribeProperties,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let Some(client) = &self.client_v5 else {
+            // No v5-specific options to carry over a v3.1.1 connection.
+            return self.subscribe(topic, qos).await;
+        };
+
+        let filter = rumqttc::v5::mqttbytes::v5::SubscribeFilter {
+            path: topic.to_string(),
+            qos: Self::qos_to_v5(qos),
+            nolocal: properties.no_local,
+            preserve_retain: false,
+            retain_forward_rule: Self::retain_handling_to_v5(properties.retain_handling),
+        };
+        let sub_properties = rumqttc::v5::mqttbytes::v5::SubscribeProperties {
+            id: properties.subscription_identifier.map(|id| id as usize),
+            user_properties: Vec::new(),
+        };
 
         client
-            .subscribe(topic, QoS::AtMostOnce)
+            .subscribe_with(filter, sub_properties)
             .await
             .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
-
+        self.track_subscription(topic, qos, Some(properties)).await;
         Ok(())
     }
 
@@ -211,23 +915,67 @@ impl MqttClient for RumqttcClient {
         &mut self,
         topic: &str,
         payload: &[u8],
+        qos: QoS,
         retain: bool,
     ) -> Result<(), Box<dyn Error + Send>> {
-        let client = self
-            .client
-            .as_ref()
-            .ok_or_else(|| -> Box<dyn Error + Send> {
-                Box::new(std::io::Error::new(
-                    std::io::ErrorKind::NotConnected,
-                    "MQTT client not connected. Call connect() first.",
-                ))
-            })?;
+        if let Some(client) = &self.client_v311 {
+            client
+                .publish(topic, qos, retain, payload)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+        } else if let Some(client) = &self.client_v5 {
+            client
+                .publish(topic, Self::qos_to_v5(qos), retain, payload)
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
+        } else {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                "MQTT client not connected. Call connect() first.",
+            )));
+        }
+
+        if qos != QoS::AtMostOnce {
+            self.wait_for_ack().await;
+        }
+
+        Ok(())
+    }
+
+    async fn publish_with(
+        &mut self,
+        topic: &str,
+        payload: &[u8],
+        qos: QoS,
+        retain: bool,
+        properties: PublishProperties,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let Some(client) = &self.client_v5 else {
+            // No v5-specific options to carry over a v3.1.1 connection.
+            return self.publish(topic, payload, qos, retain).await;
+        };
+
+        let publish_properties = rumqttc::v5::mqttbytes::v5::PublishProperties {
+            user_properties: properties.user_properties,
+            content_type: properties.content_type,
+            ..Default::default()
+        };
 
         client
-            .publish(topic, QoS::AtLeastOnce, retain, payload)
+            .publish_with_properties(
+                topic,
+                Self::qos_to_v5(qos),
+                retain,
+                payload,
+                publish_properties,
+            )
             .await
             .map_err(|e| Box::new(e) as Box<dyn Error + Send>)?;
 
+        if qos != QoS::AtMostOnce {
+            self.wait_for_ack().await;
+        }
+
         Ok(())
     }
 
@@ -237,6 +985,13 @@ impl MqttClient for RumqttcClient {
             None => None,
         }
     }
+
+    async fn poll_connection_event(&mut self) -> Option<ConnectionEvent> {
+        match &mut self.connection_rx {
+            Some(rx) => rx.recv().await,
+            None => None,
+        }
+    }
 }
 
 impl Drop for RumqttcClient {
@@ -1,3 +1,5 @@
+use hearthd_config::ConfigRelativePath;
+use hearthd_config::Secret;
 use hearthd_config::SubConfig;
 use hearthd_config::TryFromPartial;
 use serde::Deserialize;
@@ -6,6 +8,39 @@ fn default_discovery_prefix() -> String {
     "homeassistant".to_string()
 }
 
+fn default_status_topic() -> String {
+    "hearthd/status".to_string()
+}
+
+fn default_command_ack_timeout_secs() -> u64 {
+    2
+}
+
+fn default_protocol_version() -> MqttVersion {
+    MqttVersion::default()
+}
+
+fn default_manual_ack() -> bool {
+    false
+}
+
+fn default_tls_insecure_skip_verify() -> bool {
+    false
+}
+
+/// MQTT protocol version to negotiate with the broker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttVersion {
+    /// MQTT 3.1.1, the only version hearthd supported before this field
+    /// existed.
+    #[default]
+    V311,
+    /// MQTT 5, enabling user-property passthrough on `MqttMessage` and
+    /// PUBACK-confirmed delivery for QoS 1+ commands.
+    V5,
+}
+
 /// Configuration for the MQTT integration
 #[derive(Debug, Clone, Deserialize, TryFromPartial, SubConfig)]
 pub struct Config {
@@ -22,9 +57,60 @@ pub struct Config {
     #[config(default = "default_discovery_prefix")]
     pub discovery_prefix: String,
 
+    /// Topic hearthd sets a Last-Will-and-Testament on: `offline` (retained)
+    /// is set before `client.connect()`, then `online` is published once
+    /// connected, so other MQTT consumers can track hearthd's own liveness
+    /// (default: "hearthd/status").
+    #[config(default = "default_status_topic")]
+    pub status_topic: String,
+
+    /// How long to wait for a non-optimistic light command's state-topic
+    /// echo before reporting it as failed (default: 2 seconds).
+    #[config(default = "default_command_ack_timeout_secs")]
+    pub command_ack_timeout_secs: u64,
+
+    /// MQTT protocol version to negotiate with the broker (default: v3.1.1,
+    /// unaffected by this field being added).
+    #[config(default = "default_protocol_version")]
+    pub protocol_version: MqttVersion,
+
+    /// Disable the event loop's automatic PUBACK/PUBCOMP for QoS 1+
+    /// messages, so a message is only considered delivered once the
+    /// automation it triggered has actually run and called
+    /// [`crate::integrations::mqtt::client::MqttMessage::ack`]. An unacked
+    /// message is redelivered by the broker on reconnect, so a hearthd
+    /// crash mid-automation doesn't silently lose it (default: false, i.e.
+    /// the event loop acks immediately on receipt as before).
+    #[config(default = "default_manual_ack")]
+    pub manual_ack: bool,
+
     /// Optional username for authentication
     pub username: Option<String>,
 
-    /// Optional password for authentication
-    pub password: Option<String>,
+    /// Optional password for authentication: an inline value,
+    /// `"${env:MQTT_PASSWORD}"`, or `{ file = "/run/secrets/mqtt_pw" }`.
+    /// Resolved during loading and redacted from `Debug` output.
+    pub password: Option<Secret>,
+
+    /// CA certificate bundle (PEM) for TLS, resolved relative to the
+    /// directory of the file that set it (not the process CWD or the root
+    /// config's directory) - so `ca_cert = "certs/ca.pem"` in an imported
+    /// `mqtt.toml` resolves next to that file. Setting this is what turns
+    /// on TLS for the connection (broker/port are otherwise dialed in
+    /// plaintext); leave unset to keep talking plaintext.
+    pub ca_cert: Option<ConfigRelativePath>,
+
+    /// Client certificate (PEM) presented for mutual TLS, if the broker
+    /// requires one. Must be set together with `client_key`, and only has
+    /// an effect when `ca_cert` is also set.
+    pub client_cert: Option<ConfigRelativePath>,
+
+    /// Private key (PEM) matching `client_cert`.
+    pub client_key: Option<ConfigRelativePath>,
+
+    /// Skip verifying the broker's TLS certificate chain and hostname.
+    /// Only for testing against a broker with a self-signed certificate -
+    /// never enable this against a real deployment (default: false).
+    #[config(default = "default_tls_insecure_skip_verify")]
+    pub tls_insecure_skip_verify: bool,
 }
@@ -148,6 +148,70 @@ pub struct DiscoveryMessage {
     /// Value template for extracting state from JSON payload
     /// e.g., "{{ value_json.occupancy }}"
     pub value_template: Option<String>,
+
+    /// Seconds to wait after the last "on" payload before automatically
+    /// resetting to "off", for sensors that only ever report a single
+    /// triggered event (e.g. some motion sensors).
+    pub off_delay: Option<u64>,
+
+    /// Seconds after the last state update before the entity is considered
+    /// unavailable, independent of the `availability_topic`.
+    pub expire_after: Option<u64>,
+
+    /// Topic carrying the entity's availability (online/offline), separate
+    /// from its state topic.
+    pub availability_topic: Option<String>,
+
+    /// Payload on `availability_topic` meaning available. Defaults to
+    /// `"online"`, matching Home Assistant's MQTT discovery schema.
+    pub payload_available: Option<String>,
+
+    /// Payload on `availability_topic` meaning unavailable. Defaults to
+    /// `"offline"`, matching Home Assistant's MQTT discovery schema.
+    pub payload_not_available: Option<String>,
+
+    /// Unit of measurement, for numeric `sensor` entities (e.g. "W", "°C").
+    pub unit_of_measurement: Option<String>,
+
+    /// Whether commands to this entity should be reported as applied
+    /// immediately rather than waiting for a state-topic echo. Defaults to
+    /// `false`, matching Home Assistant's MQTT discovery schema.
+    pub optimistic: Option<bool>,
+
+    /// QoS level (0, 1, or 2) to use for this entity's state/command
+    /// topics. Defaults to `0`, matching Home Assistant's MQTT discovery
+    /// schema. See [`qos_from_discovery`].
+    pub qos: Option<u8>,
+
+    /// Color modes supported by a `light` entity (e.g. `["color_temp",
+    /// "xy"]`), matching Home Assistant's `supported_color_modes` MQTT
+    /// discovery field.
+    pub supported_color_modes: Option<Vec<String>>,
+}
+
+/// Map a [`DiscoveryMessage::qos`] value onto an `rumqttc` [`QoS`], falling
+/// back to `AtMostOnce` for both a missing value and any value outside
+/// `0..=2` (Home Assistant's discovery schema doesn't define one).
+pub fn qos_from_discovery(qos: Option<u8>) -> super::client::QoS {
+    use super::client::QoS;
+
+    match qos {
+        Some(1) => QoS::AtLeastOnce,
+        Some(2) => QoS::ExactlyOnce,
+        _ => QoS::AtMostOnce,
+    }
+}
+
+/// The inverse of [`qos_from_discovery`], for entities building their own
+/// outbound [`DiscoveryMessage`].
+pub fn qos_to_discovery(qos: super::client::QoS) -> Option<u8> {
+    use super::client::QoS;
+
+    Some(match qos {
+        QoS::AtMostOnce => 0,
+        QoS::AtLeastOnce => 1,
+        QoS::ExactlyOnce => 2,
+    })
 }
 
 /// Device information from Zigbee2MQTT discovery
@@ -199,6 +263,21 @@ pub fn parse_discovery_topic(topic: &str, prefix: &str) -> Option<(String, Strin
     Some((component, node_id, object_id))
 }
 
+/// Build the discovery config topic hearthd publishes one of its own
+/// entities on: `{prefix}/{component}/{node_id}/{node_id}/config`, the same
+/// `{prefix}/{component}/{node_id}/{object_id}/config` shape
+/// [`parse_discovery_topic`] expects of Zigbee2MQTT-style discovery,
+/// reusing `node_id` as the object id since hearthd advertises one entity
+/// per node.
+pub fn discovery_config_topic(prefix: &str, component: &str, node_id: &str) -> String {
+    format!("{}/{}/{}/{}/config", prefix, component, node_id, node_id)
+}
+
+/// Build the state topic hearthd publishes a bridged entity's state on.
+pub fn hearthd_state_topic(component: &str, node_id: &str) -> String {
+    format!("hearthd/{}/{}/state", component, node_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
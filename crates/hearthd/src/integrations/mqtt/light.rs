@@ -1,5 +1,7 @@
 use std::error::Error;
 
+use crate::engine::state::ColorRgb;
+use crate::engine::state::ColorXy;
 use crate::engine::state::LightState;
 use crate::integrations::mqtt::discovery::DeviceInfo;
 use crate::integrations::mqtt::discovery::DiscoveryMessage;
@@ -8,7 +10,6 @@ use crate::integrations::mqtt::discovery::DiscoveryMessage;
 #[derive(Debug, Clone)]
 pub struct Light {
     /// Entity ID (e.g., "light.living_room")
-    #[allow(dead_code)]
     pub id: String,
 
     /// Human-readable name
@@ -47,6 +48,38 @@ pub struct Light {
 
     /// Whether brightness is supported
     pub supports_brightness: bool,
+
+    /// Whether `color_temp` mode is supported
+    pub supports_color_temp: bool,
+
+    /// Whether `xy`/`rgb` color mode is supported
+    pub supports_color: bool,
+
+    /// Whether the entity is currently reachable, tracked via
+    /// `availability_topic`. Entities with no `availability_topic`
+    /// configured are always available.
+    pub available: bool,
+
+    /// Topic carrying this entity's availability, if tracked separately
+    /// from its state topic.
+    pub availability_topic: Option<String>,
+
+    /// Payload on `availability_topic` meaning available. Defaults to
+    /// Home Assistant's own default of `"online"`.
+    payload_available: String,
+
+    /// Payload on `availability_topic` meaning unavailable. Defaults to
+    /// Home Assistant's own default of `"offline"`.
+    payload_not_available: String,
+
+    /// Whether commands to this light should be reported as applied
+    /// immediately rather than waiting for a `state_topic` echo to confirm
+    /// them. Defaults to `false`, matching Home Assistant's MQTT discovery
+    /// schema.
+    pub optimistic: bool,
+
+    /// QoS to use for this light's state/command/availability topics.
+    pub qos: super::client::QoS,
 }
 
 impl Light {
@@ -74,6 +107,10 @@ impl Light {
 
         let supports_brightness = discovery.brightness.unwrap_or(false);
 
+        let color_modes = discovery.supported_color_modes.unwrap_or_default();
+        let supports_color_temp = color_modes.iter().any(|m| m == "color_temp");
+        let supports_color = color_modes.iter().any(|m| m == "xy" || m == "rgb");
+
         Ok(Self {
             id,
             name,
@@ -87,13 +124,40 @@ impl Light {
             payload_on: discovery.payload_on.unwrap_or_else(|| "ON".to_string()),
             payload_off: discovery.payload_off.unwrap_or_else(|| "OFF".to_string()),
             supports_brightness,
+            supports_color_temp,
+            supports_color,
+            available: true,
+            availability_topic: discovery.availability_topic,
+            payload_available: discovery
+                .payload_available
+                .unwrap_or_else(|| "online".to_string()),
+            payload_not_available: discovery
+                .payload_not_available
+                .unwrap_or_else(|| "offline".to_string()),
+            optimistic: discovery.optimistic.unwrap_or(false),
+            qos: super::discovery::qos_from_discovery(discovery.qos),
         })
     }
 
+    /// Update availability from a message on `availability_topic`.
+    ///
+    /// Payloads matching neither `payload_available` nor
+    /// `payload_not_available` are ignored, matching Home Assistant's own
+    /// handling of unrecognized availability payloads.
+    pub fn update_availability(&mut self, payload: &[u8]) {
+        let payload = String::from_utf8_lossy(payload);
+        if *payload == self.payload_available {
+            self.available = true;
+        } else if *payload == self.payload_not_available {
+            self.available = false;
+        }
+    }
+
     /// Update the light state from an MQTT payload
     ///
     /// Zigbee2MQTT sends state updates as JSON, e.g.:
-    /// {"state": "ON", "brightness": 128}
+    /// {"state": "ON", "brightness": 128, "color_temp": 300,
+    ///  "color": {"x": 0.3, "y": 0.3}, "transition": 1.5}
     pub fn update_state(&mut self, payload: &[u8]) -> Result<(), Box<dyn Error>> {
         let json_str = std::str::from_utf8(payload)?;
         let state_update: serde_json::Value = serde_json::from_str(json_str)?;
@@ -110,9 +174,90 @@ impl Light {
             }
         }
 
+        // Update color temperature if present and supported
+        if self.supports_color_temp {
+            if let Some(color_temp) = state_update.get("color_temp").and_then(|v| v.as_u64()) {
+                self.state.color_temp = Some(color_temp as u32);
+            }
+        }
+
+        // Update xy/rgb color if present and supported
+        if self.supports_color {
+            if let Some(color) = state_update.get("color").and_then(|v| v.as_object()) {
+                if let (Some(x), Some(y)) = (
+                    color.get("x").and_then(|v| v.as_f64()),
+                    color.get("y").and_then(|v| v.as_f64()),
+                ) {
+                    self.state.color_xy = Some(ColorXy { x, y });
+                } else if let (Some(r), Some(g), Some(b)) = (
+                    color.get("r").and_then(|v| v.as_u64()),
+                    color.get("g").and_then(|v| v.as_u64()),
+                    color.get("b").and_then(|v| v.as_u64()),
+                ) {
+                    self.state.color_rgb = Some(ColorRgb {
+                        r: r as u8,
+                        g: g as u8,
+                        b: b as u8,
+                    });
+                }
+            }
+        }
+
+        // Transition is reported independently of which color mode (if
+        // any) the light is in.
+        if let Some(transition) = state_update.get("transition").and_then(|v| v.as_f64()) {
+            self.state.transition = Some(transition);
+        }
+
         Ok(())
     }
 
+    /// The color modes this light supports, in the shape Home Assistant's
+    /// MQTT discovery schema expects for `supported_color_modes`, the
+    /// inverse of the `color_modes` parsing in [`Light::from_discovery`].
+    fn supported_color_modes(&self) -> Option<Vec<String>> {
+        let mut modes = Vec::new();
+        if self.supports_color_temp {
+            modes.push("color_temp".to_string());
+        }
+        if self.supports_color {
+            modes.push("xy".to_string());
+        }
+        if modes.is_empty() { None } else { Some(modes) }
+    }
+
+    /// Build the Home Assistant MQTT discovery message advertising this
+    /// light on the broker, the inverse of [`Light::from_discovery`]. The
+    /// emitted message points at hearthd's own state/command topics, since
+    /// hearthd re-brokers commands for this light rather than the original
+    /// source.
+    pub fn to_discovery(&self, node_id: &str) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some(self.name.clone()),
+            unique_id: Some(self.unique_id.clone()),
+            state_topic: Some(super::discovery::hearthd_state_topic("light", node_id)),
+            command_topic: Some(format!("hearthd/light/{}/set", node_id)),
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: self.device_info.clone(),
+            payload_on: Some(self.payload_on.clone()),
+            payload_off: Some(self.payload_off.clone()),
+            brightness: Some(self.supports_brightness),
+            schema: None,
+            device_class: None,
+            value_template: Some("{{ value_json.state }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: Some(self.optimistic),
+            qos: super::discovery::qos_to_discovery(self.qos),
+            supported_color_modes: self.supported_color_modes(),
+        }
+    }
+
     /// Generate a command payload to set the light state
     pub fn command_payload(&self, state: &LightState) -> Result<Vec<u8>, Box<dyn Error>> {
         let mut payload = serde_json::json!({
@@ -125,6 +270,24 @@ impl Light {
             }
         }
 
+        if self.supports_color_temp {
+            if let Some(color_temp) = state.color_temp {
+                payload["color_temp"] = serde_json::json!(color_temp);
+            }
+        }
+
+        if self.supports_color {
+            if let Some(xy) = state.color_xy {
+                payload["color"] = serde_json::json!({ "x": xy.x, "y": xy.y });
+            } else if let Some(rgb) = state.color_rgb {
+                payload["color"] = serde_json::json!({ "r": rgb.r, "g": rgb.g, "b": rgb.b });
+            }
+        }
+
+        if let Some(transition) = state.transition {
+            payload["transition"] = serde_json::json!(transition);
+        }
+
         Ok(serde_json::to_vec(&payload)?)
     }
 }
@@ -156,6 +319,15 @@ mod tests {
             schema: None,
             device_class: None,
             value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
         };
 
         let mut light =
@@ -168,4 +340,202 @@ mod tests {
         assert!(light.state.on);
         assert_eq!(light.state.brightness, Some(128));
     }
+
+    #[test]
+    fn test_update_availability() {
+        let discovery = DiscoveryMessage {
+            name: Some("Test Light".to_string()),
+            unique_id: Some("test_light".to_string()),
+            state_topic: Some("zigbee2mqtt/light/state".to_string()),
+            command_topic: Some("zigbee2mqtt/light/set".to_string()),
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: Some(true),
+            schema: None,
+            device_class: None,
+            value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: Some("zigbee2mqtt/light/availability".to_string()),
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        };
+
+        let mut light =
+            Light::from_discovery(discovery, "light.test".to_string(), "test_node".to_string())
+                .unwrap();
+        assert!(light.available);
+
+        light.update_availability(b"offline");
+        assert!(!light.available);
+
+        light.update_availability(b"online");
+        assert!(light.available);
+
+        // Unrecognized payloads are ignored rather than clearing state.
+        light.update_availability(b"garbage");
+        assert!(light.available);
+    }
+
+    #[test]
+    fn test_to_discovery_roundtrips_topics_and_brightness() {
+        let discovery = DiscoveryMessage {
+            name: Some("Test Light".to_string()),
+            unique_id: Some("test_light".to_string()),
+            state_topic: Some("zigbee2mqtt/light/state".to_string()),
+            command_topic: Some("zigbee2mqtt/light/set".to_string()),
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: Some(true),
+            schema: None,
+            device_class: None,
+            value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        };
+
+        let light =
+            Light::from_discovery(discovery, "light.test".to_string(), "test_node".to_string())
+                .unwrap();
+
+        let discovery = light.to_discovery("test_node");
+        assert_eq!(discovery.name, Some("Test Light".to_string()));
+        assert_eq!(discovery.brightness, Some(true));
+        assert_eq!(
+            discovery.state_topic,
+            Some("hearthd/light/test_node/state".to_string())
+        );
+        assert_eq!(
+            discovery.command_topic,
+            Some("hearthd/light/test_node/set".to_string())
+        );
+        assert_eq!(
+            discovery.value_template,
+            Some("{{ value_json.state }}".to_string())
+        );
+    }
+
+    fn color_discovery() -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some("Test Light".to_string()),
+            unique_id: Some("test_light".to_string()),
+            state_topic: Some("zigbee2mqtt/light/state".to_string()),
+            command_topic: Some("zigbee2mqtt/light/set".to_string()),
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: Some(true),
+            schema: None,
+            device_class: None,
+            value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: Some(vec!["color_temp".to_string(), "xy".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_update_state_color_temp_and_xy() {
+        let mut light = Light::from_discovery(
+            color_discovery(),
+            "light.test".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+        assert!(light.supports_color_temp);
+        assert!(light.supports_color);
+
+        let payload = br#"{"state": "ON", "color_temp": 300,
+            "color": {"x": 0.3, "y": 0.32}, "transition": 1.5}"#;
+        light.update_state(payload).unwrap();
+
+        assert_eq!(light.state.color_temp, Some(300));
+        assert_eq!(
+            light.state.color_xy,
+            Some(crate::engine::state::ColorXy { x: 0.3, y: 0.32 })
+        );
+        assert_eq!(light.state.transition, Some(1.5));
+    }
+
+    #[test]
+    fn test_update_state_rgb_color_ignored_when_unsupported() {
+        let discovery = DiscoveryMessage {
+            supported_color_modes: None,
+            ..color_discovery()
+        };
+        let mut light = Light::from_discovery(
+            discovery,
+            "light.test".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+        assert!(!light.supports_color);
+
+        let payload = br#"{"color": {"r": 255, "g": 0, "b": 0}}"#;
+        light.update_state(payload).unwrap();
+
+        assert_eq!(light.state.color_rgb, None);
+    }
+
+    #[test]
+    fn test_command_payload_includes_color_temp_and_transition() {
+        let light = Light::from_discovery(
+            color_discovery(),
+            "light.test".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+
+        let mut state = LightState::default();
+        state.on = true;
+        state.color_temp = Some(250);
+        state.transition = Some(2.0);
+
+        let payload = light.command_payload(&state).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+
+        assert_eq!(json["color_temp"], 250);
+        assert_eq!(json["transition"], 2.0);
+    }
+
+    #[test]
+    fn test_to_discovery_roundtrips_supported_color_modes() {
+        let light = Light::from_discovery(
+            color_discovery(),
+            "light.test".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+
+        let discovery = light.to_discovery("test_node");
+        assert_eq!(
+            discovery.supported_color_modes,
+            Some(vec!["color_temp".to_string(), "xy".to_string()])
+        );
+    }
 }
@@ -6,11 +6,18 @@ mod light;
 // Private module - allowed by clippy.toml allow-private-module-inception
 #[allow(clippy::module_inception)]
 mod mqtt;
+mod publisher;
+mod sensor;
+mod value_template;
 
 use anyhow::Context;
 pub use config::Config as MqttConfig;
+pub use discovery::DiscoveryMessage;
+pub use discovery::discovery_config_topic;
+pub use discovery::hearthd_state_topic;
 use linkme::distributed_slice;
 pub use mqtt::MqttIntegration;
+pub use publisher::DiscoveryPublisher;
 
 use crate::engine;
 
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use tokio::sync::Mutex;
@@ -11,17 +12,28 @@ use tracing::warn;
 
 use super::MqttConfig;
 use super::binary_sensor::BinarySensor;
+use super::client::ConnectionEvent;
 use super::client::MqttClient;
 use super::client::MqttMessage;
+use super::client::QoS;
 use super::discovery::DiscoveryMessage;
 use super::discovery::parse_discovery_topic;
+use super::discovery::qos_from_discovery;
 use super::light::Light;
+use super::publisher::DiscoveryPublisher;
+use super::sensor::NumericSensor;
+use crate::engine::Command;
+use crate::engine::CommandKind;
+use crate::engine::Entity;
 use crate::engine::FromIntegrationMessage;
 use crate::engine::FromIntegrationSender;
 use crate::engine::Integration;
-use crate::engine::ToIntegrationMessage;
+use crate::engine::LightCommand;
+use crate::engine::PublishEntityCommand;
+use crate::engine::RemoveEntityCommand;
 use crate::engine::state::BinarySensorState;
 use crate::engine::state::LightState;
+use crate::engine::state::SensorState;
 
 /// Type alias for the shared lights map
 type LightsMap = Arc<Mutex<HashMap<String, Arc<Mutex<Light>>>>>;
@@ -29,6 +41,65 @@ type LightsMap = Arc<Mutex<HashMap<String, Arc<Mutex<Light>>>>>;
 /// Type alias for the shared binary sensors map
 type BinarySensorsMap = Arc<Mutex<HashMap<String, Arc<Mutex<BinarySensor>>>>>;
 
+/// Type alias for the shared numeric sensors map (battery, illuminance,
+/// linkquality, temperature, etc.)
+type SensorsMap = Arc<Mutex<HashMap<String, Arc<Mutex<NumericSensor>>>>>;
+
+/// A light command awaiting confirmation via a state-topic echo.
+#[derive(Debug, Clone)]
+struct PendingLightCommand {
+    /// The state that was commanded, kept for diagnostics if the command
+    /// times out unconfirmed.
+    #[allow(dead_code)]
+    expected: LightState,
+
+    /// Elapsed time (per the integration's clock) at which this command is
+    /// considered unconfirmed.
+    deadline: std::time::Duration,
+}
+
+/// Type alias for the shared pending (unconfirmed) light commands map
+type PendingCommandsMap = Arc<Mutex<HashMap<String, PendingLightCommand>>>;
+
+/// A handle to the entity a routed topic should update, and how: `*State`
+/// variants carry a topic that is the entity's `state_topic`, `*Availability`
+/// variants carry its separate `availability_topic`. `PublishedCommand`
+/// carries just the `entity_id`, since a [`PublishEntityCommand`]-ed entity
+/// has no local struct of its own to update — the command is forwarded
+/// verbatim to the engine instead.
+#[derive(Clone)]
+enum EntityRef {
+    LightState(Arc<Mutex<Light>>),
+    LightAvailability(Arc<Mutex<Light>>),
+    BinarySensorState(Arc<Mutex<BinarySensor>>),
+    BinarySensorAvailability(Arc<Mutex<BinarySensor>>),
+    SensorState(Arc<Mutex<NumericSensor>>),
+    PublishedCommand(String),
+}
+
+/// Type alias for the shared topic-to-entity routing table. Populated
+/// alongside each entity's subscriptions at discovery time and cleaned up on
+/// removal, so the background task can dispatch an incoming message with a
+/// single hash lookup on its topic rather than scanning every known entity.
+type RoutesMap = Arc<Mutex<HashMap<String, EntityRef>>>;
+
+/// Bookkeeping for an entity the engine asked us to publish via
+/// [`MqttIntegration::publish_entity`], so [`MqttIntegration::remove_published_entity`]
+/// knows what to withdraw.
+struct PublishedEntity {
+    /// Home Assistant component type (e.g. "switch", "scene"), needed to
+    /// rebuild the entity's discovery config topic on removal.
+    component: String,
+
+    /// `command_topic` this entity was subscribed on, so its route can be
+    /// dropped from [`RoutesMap`] on removal.
+    command_topic: String,
+}
+
+/// Type alias for the shared map of entities published on the engine's
+/// behalf; see [`PublishedEntity`].
+type PublishedEntitiesMap = Arc<Mutex<HashMap<String, PublishedEntity>>>;
+
 /// MQTT Integration for hearthd
 ///
 /// Handles MQTT communication with Zigbee2MQTT and other MQTT-based devices.
@@ -38,6 +109,24 @@ pub struct MqttIntegration<C: MqttClient> {
     config: MqttConfig,
     lights: LightsMap,
     binary_sensors: BinarySensorsMap,
+    sensors: SensorsMap,
+    /// Topic-to-entity routing table for O(1) state/availability dispatch;
+    /// see [`RoutesMap`].
+    routes: RoutesMap,
+    /// Entities published on the engine's behalf via [`Self::publish_entity`];
+    /// see [`PublishedEntitiesMap`].
+    published: PublishedEntitiesMap,
+    /// Light commands published but not yet confirmed by a matching
+    /// `state_topic` echo, keyed by entity ID.
+    pending_commands: PendingCommandsMap,
+    /// Clock `send_light_command` and the background task both measure
+    /// elapsed time against, so a command's timeout deadline is comparable
+    /// across the two.
+    start: Instant,
+    /// Command kinds this integration handles, returned by
+    /// `Integration::accepted_commands`; computed once in [`Self::new`]
+    /// rather than per call.
+    accepted_commands: Vec<CommandKind>,
     to_engine: Option<FromIntegrationSender>,
     /// Handle to the background message processing task
     _message_task: Option<JoinHandle<()>>,
@@ -51,6 +140,16 @@ impl<C: MqttClient> MqttIntegration<C> {
             config: config.clone(),
             lights: Arc::new(Mutex::new(HashMap::new())) as LightsMap,
             binary_sensors: Arc::new(Mutex::new(HashMap::new())) as BinarySensorsMap,
+            sensors: Arc::new(Mutex::new(HashMap::new())) as SensorsMap,
+            routes: Arc::new(Mutex::new(HashMap::new())) as RoutesMap,
+            published: Arc::new(Mutex::new(HashMap::new())) as PublishedEntitiesMap,
+            pending_commands: Arc::new(Mutex::new(HashMap::new())) as PendingCommandsMap,
+            start: Instant::now(),
+            accepted_commands: vec![
+                CommandKind::of::<LightCommand>(),
+                CommandKind::of::<PublishEntityCommand>(),
+                CommandKind::of::<RemoveEntityCommand>(),
+            ],
             to_engine: None,
             _message_task: None,
         }
@@ -65,9 +164,41 @@ impl<C: MqttClient> MqttIntegration<C> {
         config: MqttConfig,
         lights: LightsMap,
         binary_sensors: BinarySensorsMap,
+        sensors: SensorsMap,
+        routes: RoutesMap,
+        pending_commands: PendingCommandsMap,
+        start: Instant,
         to_engine: FromIntegrationSender,
     ) {
         loop {
+            // Drain any pending connection-state transition first; this is
+            // expected to be empty almost every tick, so a very short
+            // timeout is enough to not delay message polling below.
+            let connection_event = {
+                let mut client_guard = client.lock().await;
+                tokio::time::timeout(
+                    std::time::Duration::from_millis(1),
+                    client_guard.poll_connection_event(),
+                )
+                .await
+                .ok()
+                .flatten()
+            };
+
+            if let Some(event) = connection_event {
+                let connected = matches!(event, ConnectionEvent::Online);
+                info!(
+                    "MQTT broker connection {}",
+                    if connected { "online" } else { "offline" }
+                );
+                let _ = to_engine
+                    .send(FromIntegrationMessage::IntegrationConnectionChanged {
+                        integration_name: "mqtt".to_string(),
+                        connected,
+                    })
+                    .await;
+            }
+
             // Poll for message with a short lock hold time
             // Use tokio::select with a timeout to avoid holding the lock indefinitely
             let msg = {
@@ -92,15 +223,22 @@ impl<C: MqttClient> MqttIntegration<C> {
                             &client,
                             &lights,
                             &binary_sensors,
+                            &sensors,
+                            &routes,
                             &to_engine,
                         )
                         .await
                         {
                             warn!("Error handling discovery message: {}", e);
                         }
-                    } else if let Err(e) =
-                        Self::handle_state_update_static(&msg, &lights, &binary_sensors, &to_engine)
-                            .await
+                    } else if let Err(e) = Self::handle_state_update_static(
+                        &msg,
+                        &routes,
+                        &pending_commands,
+                        &to_engine,
+                        start.elapsed(),
+                    )
+                    .await
                     {
                         warn!("Error handling state update: {}", e);
                     }
@@ -110,6 +248,73 @@ impl<C: MqttClient> MqttIntegration<C> {
                     tokio::task::yield_now().await;
                 }
             }
+
+            // Every poll tick also doubles as the `off_delay`/`expire_after`
+            // timer tick, since discovery payloads rarely need finer
+            // granularity than the ~100ms poll interval above.
+            Self::check_binary_sensor_timers_static(&binary_sensors, start.elapsed(), &to_engine)
+                .await;
+
+            // ...and as the pending light command timeout tick.
+            Self::check_pending_commands_static(&pending_commands, start.elapsed(), &to_engine)
+                .await;
+        }
+    }
+
+    /// Apply any elapsed `off_delay`/`expire_after` timers across all known
+    /// binary sensors, reporting state changes to the engine.
+    async fn check_binary_sensor_timers_static(
+        binary_sensors: &BinarySensorsMap,
+        now: std::time::Duration,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let mut changed = Vec::new();
+
+        {
+            let sensors_guard = binary_sensors.lock().await;
+            for (entity_id, sensor_arc) in sensors_guard.iter() {
+                let mut sensor = sensor_arc.lock().await;
+                let before = sensor.state.clone();
+                sensor.check_timers(now);
+                if sensor.state != before {
+                    changed.push((entity_id.clone(), sensor.state.clone()));
+                }
+            }
+        }
+
+        for (entity_id, state) in changed {
+            Self::report_binary_sensor_state_change_static(&entity_id, &state, to_engine).await;
+        }
+    }
+
+    /// Fail any pending light commands whose confirmation deadline has
+    /// elapsed without a matching `state_topic` echo.
+    async fn check_pending_commands_static(
+        pending_commands: &PendingCommandsMap,
+        now: std::time::Duration,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let timed_out: Vec<String> = {
+            let mut pending_guard = pending_commands.lock().await;
+            let timed_out: Vec<String> = pending_guard
+                .iter()
+                .filter(|(_, pending)| now >= pending.deadline)
+                .map(|(entity_id, _)| entity_id.clone())
+                .collect();
+            for entity_id in &timed_out {
+                pending_guard.remove(entity_id);
+            }
+            timed_out
+        };
+
+        for entity_id in timed_out {
+            warn!("Light command timed out unconfirmed: {}", entity_id);
+            Self::report_command_failed_static(
+                &entity_id,
+                "timed out waiting for state confirmation",
+                to_engine,
+            )
+            .await;
         }
     }
 
@@ -120,6 +325,8 @@ impl<C: MqttClient> MqttIntegration<C> {
         client: &Arc<Mutex<C>>,
         lights: &LightsMap,
         binary_sensors: &BinarySensorsMap,
+        sensors: &SensorsMap,
+        routes: &RoutesMap,
         to_engine: &FromIntegrationSender,
     ) -> Result<(), Box<dyn Error + Send>> {
         // Parse the discovery topic
@@ -139,17 +346,43 @@ impl<C: MqttClient> MqttIntegration<C> {
         );
 
         match component.as_str() {
-            "light" => Self::handle_light_discovery(msg, client, lights, to_engine, &node_id).await,
+            "light" => {
+                Self::handle_light_discovery(
+                    msg,
+                    &config.discovery_prefix,
+                    client,
+                    lights,
+                    routes,
+                    to_engine,
+                    &node_id,
+                )
+                .await
+            }
             "binary_sensor" => {
-                // TODO: Zigbee2MQTT also publishes auxiliary data (battery,
-                // illuminance, linkquality) as separate `sensor` components.
-                // These should be discovered as numeric sensor entities.
                 Self::handle_binary_sensor_discovery(
                     msg,
+                    &config.discovery_prefix,
                     client,
                     binary_sensors,
+                    routes,
+                    to_engine,
+                    &node_id,
+                )
+                .await
+            }
+            "sensor" => {
+                // Zigbee2MQTT publishes auxiliary data (battery, illuminance,
+                // linkquality, temperature, ...) as separate numeric `sensor`
+                // components, one per node per reading.
+                Self::handle_sensor_discovery(
+                    msg,
+                    &config.discovery_prefix,
+                    client,
+                    sensors,
+                    routes,
                     to_engine,
                     &node_id,
+                    &object_id,
                 )
                 .await
             }
@@ -160,19 +393,40 @@ impl<C: MqttClient> MqttIntegration<C> {
         }
     }
 
+    /// Remove an entity's routes, if any, from the topic-to-entity routing
+    /// table. Called when its discovery topic reports removal (empty
+    /// payload).
+    async fn remove_routes_static(
+        routes: &RoutesMap,
+        state_topic: &str,
+        availability_topic: &Option<String>,
+    ) {
+        let mut routes_guard = routes.lock().await;
+        routes_guard.remove(state_topic);
+        if let Some(availability_topic) = availability_topic {
+            routes_guard.remove(availability_topic);
+        }
+    }
+
     /// Handle discovery of a light entity
     async fn handle_light_discovery(
         msg: &MqttMessage,
+        discovery_prefix: &str,
         client: &Arc<Mutex<C>>,
         lights: &LightsMap,
+        routes: &RoutesMap,
         to_engine: &FromIntegrationSender,
         node_id: &str,
     ) -> Result<(), Box<dyn Error + Send>> {
         let entity_id = format!("light.{}", node_id);
 
         if msg.payload.is_empty() {
-            let mut lights_guard = lights.lock().await;
-            if lights_guard.remove(&entity_id).is_some() {
+            let removed = lights.lock().await.remove(&entity_id);
+            if let Some(light_arc) = removed {
+                let light = light_arc.lock().await;
+                Self::remove_routes_static(routes, &light.state_topic, &light.availability_topic)
+                    .await;
+                drop(light);
                 info!("Removed light entity: {}", entity_id);
                 Self::notify_entity_removed_static(&entity_id, to_engine).await;
             }
@@ -191,6 +445,8 @@ impl<C: MqttClient> MqttIntegration<C> {
             })?;
 
         let state_topic = light.state_topic.clone();
+        let availability_topic = light.availability_topic.clone();
+        let qos = light.qos;
         info!("Discovered light entity: {} ({})", light.name, entity_id);
 
         let light_arc = Arc::new(Mutex::new(light));
@@ -200,31 +456,71 @@ impl<C: MqttClient> MqttIntegration<C> {
             lights_guard.insert(entity_id.clone(), light_arc.clone());
         }
 
+        {
+            let mut routes_guard = routes.lock().await;
+            routes_guard.insert(state_topic.clone(), EntityRef::LightState(light_arc.clone()));
+            if let Some(availability_topic) = &availability_topic {
+                routes_guard.insert(
+                    availability_topic.clone(),
+                    EntityRef::LightAvailability(light_arc.clone()),
+                );
+            }
+        }
+
         // Subscribe after map insert so the retained state message finds the
         // entity already in the map, regardless of concurrency model.
         {
             let mut client_guard = client.lock().await;
-            client_guard.subscribe(&state_topic).await?;
+            client_guard.subscribe(&state_topic, qos).await?;
+            if let Some(availability_topic) = &availability_topic {
+                client_guard.subscribe(availability_topic, qos).await?;
+            }
         }
 
         Self::register_entity_static(&entity_id, to_engine).await;
 
+        // Re-advertise this light back to the broker as hearthd's own
+        // entity, so it's usable even once hearthd is the source of truth
+        // rather than the original Zigbee2MQTT discovery.
+        let (discovery, state) = {
+            let light = light_arc.lock().await;
+            let state = serde_json::json!({
+                "state": if light.state.on { "ON" } else { "OFF" },
+                "brightness": light.state.brightness,
+            });
+            (light.to_discovery(node_id), state)
+        };
+        let publisher = DiscoveryPublisher::new(client.clone(), discovery_prefix.to_string());
+        if let Err(e) = publisher.publish("light", node_id, &discovery, &state).await {
+            warn!("Failed to publish light discovery for {}: {}", node_id, e);
+        }
+
         Ok(())
     }
 
     /// Handle discovery of a binary sensor entity (e.g., motion sensor)
     async fn handle_binary_sensor_discovery(
         msg: &MqttMessage,
+        discovery_prefix: &str,
         client: &Arc<Mutex<C>>,
         binary_sensors: &BinarySensorsMap,
+        routes: &RoutesMap,
         to_engine: &FromIntegrationSender,
         node_id: &str,
     ) -> Result<(), Box<dyn Error + Send>> {
         let entity_id = format!("binary_sensor.{}", node_id);
 
         if msg.payload.is_empty() {
-            let mut sensors_guard = binary_sensors.lock().await;
-            if sensors_guard.remove(&entity_id).is_some() {
+            let removed = binary_sensors.lock().await.remove(&entity_id);
+            if let Some(sensor_arc) = removed {
+                let sensor = sensor_arc.lock().await;
+                Self::remove_routes_static(
+                    routes,
+                    &sensor.state_topic,
+                    &sensor.availability_topic,
+                )
+                .await;
+                drop(sensor);
                 info!("Removed binary sensor entity: {}", entity_id);
                 Self::notify_entity_removed_static(&entity_id, to_engine).await;
             }
@@ -244,6 +540,8 @@ impl<C: MqttClient> MqttIntegration<C> {
                 })?;
 
         let state_topic = sensor.state_topic.clone();
+        let availability_topic = sensor.availability_topic.clone();
+        let qos = sensor.qos;
         info!(
             "Discovered binary sensor entity: {} ({})",
             sensor.name, entity_id
@@ -256,34 +554,154 @@ impl<C: MqttClient> MqttIntegration<C> {
             sensors_guard.insert(entity_id.clone(), sensor_arc.clone());
         }
 
+        {
+            let mut routes_guard = routes.lock().await;
+            routes_guard.insert(
+                state_topic.clone(),
+                EntityRef::BinarySensorState(sensor_arc.clone()),
+            );
+            if let Some(availability_topic) = &availability_topic {
+                routes_guard.insert(
+                    availability_topic.clone(),
+                    EntityRef::BinarySensorAvailability(sensor_arc.clone()),
+                );
+            }
+        }
+
         // Subscribe after map insert so the retained state message finds the
         // entity already in the map, regardless of concurrency model.
         {
             let mut client_guard = client.lock().await;
-            client_guard.subscribe(&state_topic).await?;
+            client_guard.subscribe(&state_topic, qos).await?;
+            if let Some(availability_topic) = &availability_topic {
+                client_guard.subscribe(availability_topic, qos).await?;
+            }
         }
 
         Self::register_entity_static(&entity_id, to_engine).await;
 
+        // Re-advertise this sensor back to the broker as hearthd's own
+        // entity, so it's usable even once hearthd is the source of truth
+        // rather than the original Zigbee2MQTT discovery.
+        let (discovery, state) = {
+            let sensor = sensor_arc.lock().await;
+            (sensor.to_discovery(node_id), sensor.state_json())
+        };
+        let publisher = DiscoveryPublisher::new(client.clone(), discovery_prefix.to_string());
+        if let Err(e) = publisher
+            .publish("binary_sensor", node_id, &discovery, &state)
+            .await
+        {
+            warn!("Failed to publish binary sensor discovery for {}: {}", node_id, e);
+        }
+
+        Ok(())
+    }
+
+    /// Handle discovery of a numeric sensor entity (e.g., battery,
+    /// illuminance, linkquality). Unlike lights and binary sensors, a single
+    /// Zigbee2MQTT node publishes one `sensor` component per auxiliary
+    /// reading, so the entity ID incorporates `object_id` as well as
+    /// `node_id`.
+    async fn handle_sensor_discovery(
+        msg: &MqttMessage,
+        discovery_prefix: &str,
+        client: &Arc<Mutex<C>>,
+        sensors: &SensorsMap,
+        routes: &RoutesMap,
+        to_engine: &FromIntegrationSender,
+        node_id: &str,
+        object_id: &str,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let entity_id = format!("sensor.{}_{}", node_id, object_id);
+
+        if msg.payload.is_empty() {
+            let removed = sensors.lock().await.remove(&entity_id);
+            if let Some(sensor_arc) = removed {
+                let sensor = sensor_arc.lock().await;
+                Self::remove_routes_static(routes, &sensor.state_topic, &None).await;
+                drop(sensor);
+                info!("Removed sensor entity: {}", entity_id);
+                Self::notify_entity_removed_static(&entity_id, to_engine).await;
+            }
+            return Ok(());
+        }
+
+        let discovery: DiscoveryMessage = serde_json::from_slice(&msg.payload)
+            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+
+        let sensor =
+            NumericSensor::from_discovery(discovery, entity_id.clone(), node_id.to_string())
+                .map_err(|e| -> Box<dyn Error + Send> {
+                    Box::new(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        e.to_string(),
+                    ))
+                })?;
+
+        let state_topic = sensor.state_topic.clone();
+        let qos = sensor.qos;
+        info!("Discovered sensor entity: {} ({})", sensor.name, entity_id);
+
+        let sensor_arc = Arc::new(Mutex::new(sensor));
+
+        {
+            let mut sensors_guard = sensors.lock().await;
+            sensors_guard.insert(entity_id.clone(), sensor_arc.clone());
+        }
+
+        {
+            let mut routes_guard = routes.lock().await;
+            routes_guard.insert(state_topic.clone(), EntityRef::SensorState(sensor_arc.clone()));
+        }
+
+        // Subscribe after map insert so the retained state message finds the
+        // entity already in the map, regardless of concurrency model.
+        {
+            let mut client_guard = client.lock().await;
+            client_guard.subscribe(&state_topic, qos).await?;
+        }
+
+        Self::register_entity_static(&entity_id, to_engine).await;
+
+        // Re-advertise this sensor back to the broker as hearthd's own
+        // entity, so it's usable even once hearthd is the source of truth
+        // rather than the original Zigbee2MQTT discovery.
+        let (discovery, state) = {
+            let sensor = sensor_arc.lock().await;
+            (sensor.to_discovery(object_id), sensor.state_json())
+        };
+        let publisher = DiscoveryPublisher::new(client.clone(), discovery_prefix.to_string());
+        if let Err(e) = publisher.publish("sensor", object_id, &discovery, &state).await {
+            warn!("Failed to publish sensor discovery for {}: {}", object_id, e);
+        }
+
         Ok(())
     }
 
     /// Handle a state update message (static version for background task)
+    ///
+    /// Dispatches via the [`RoutesMap`] routing table rather than scanning
+    /// every known entity, so a single hash lookup on `msg.topic` identifies
+    /// the one entity (and topic role) to touch.
+    ///
+    /// `now` is the elapsed time (per the background task's clock) at which
+    /// this message arrived, seeding binary sensors' `off_delay`/
+    /// `expire_after` timers.
     async fn handle_state_update_static(
         msg: &MqttMessage,
-        lights: &LightsMap,
-        binary_sensors: &BinarySensorsMap,
+        routes: &RoutesMap,
+        pending_commands: &PendingCommandsMap,
         to_engine: &FromIntegrationSender,
+        now: std::time::Duration,
     ) -> Result<(), Box<dyn Error + Send>> {
-        // Check lights first
-        let mut light_to_update: Option<(String, LightState)> = None;
+        let route = routes.lock().await.get(&msg.topic).cloned();
 
-        {
-            let lights_guard = lights.lock().await;
-            for (entity_id, light_arc) in lights_guard.iter() {
-                let mut light = light_arc.lock().await;
-                if msg.topic == light.state_topic {
-                    debug!("State update for light: {}", entity_id);
+        match route {
+            Some(EntityRef::LightState(light_arc)) => {
+                let (entity_id, state) = {
+                    let mut light = light_arc.lock().await;
+                    debug!("State update for light: {}", light.id);
                     light
                         .update_state(&msg.payload)
                         .map_err(|e| -> Box<dyn Error + Send> {
@@ -292,26 +710,58 @@ impl<C: MqttClient> MqttIntegration<C> {
                                 e.to_string(),
                             ))
                         })?;
-                    light_to_update = Some((entity_id.clone(), light.state.clone()));
-                    break;
-                }
+                    (light.id.clone(), light.state.clone())
+                };
+                // Any echoed state on this light's state_topic confirms its
+                // most recent command, whether or not it matches exactly.
+                pending_commands.lock().await.remove(&entity_id);
+                Self::report_state_change_static(&entity_id, &state, to_engine).await;
             }
-        }
-
-        if let Some((entity_id, state)) = light_to_update {
-            Self::report_state_change_static(&entity_id, &state, to_engine).await;
-            return Ok(());
-        }
-
-        // Check binary sensors
-        let mut sensor_to_update: Option<(String, BinarySensorState)> = None;
-
-        {
-            let sensors_guard = binary_sensors.lock().await;
-            for (entity_id, sensor_arc) in sensors_guard.iter() {
-                let mut sensor = sensor_arc.lock().await;
-                if msg.topic == sensor.state_topic {
-                    debug!("State update for binary sensor: {}", entity_id);
+            Some(EntityRef::LightAvailability(light_arc)) => {
+                let (entity_id, available) = {
+                    let mut light = light_arc.lock().await;
+                    debug!("Availability update for light: {}", light.id);
+                    light.update_availability(&msg.payload);
+                    (light.id.clone(), light.available)
+                };
+                Self::report_availability_changed_static(&entity_id, available, to_engine).await;
+            }
+            Some(EntityRef::BinarySensorState(sensor_arc)) => {
+                let (entity_id, state) = {
+                    let mut sensor = sensor_arc.lock().await;
+                    debug!("State update for binary sensor: {}", sensor.id);
+                    sensor
+                        .update_state(&msg.payload, now)
+                        .map_err(|e| -> Box<dyn Error + Send> {
+                            Box::new(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                e.to_string(),
+                            ))
+                        })?;
+                    (sensor.id.clone(), sensor.state.clone())
+                };
+                Self::report_binary_sensor_state_change_static(&entity_id, &state, to_engine)
+                    .await;
+            }
+            Some(EntityRef::BinarySensorAvailability(sensor_arc)) => {
+                let (entity_id, available, state) = {
+                    let mut sensor = sensor_arc.lock().await;
+                    debug!("Availability update for binary sensor: {}", sensor.id);
+                    sensor.update_availability(&msg.payload);
+                    (
+                        sensor.id.clone(),
+                        sensor.state.available,
+                        sensor.state.clone(),
+                    )
+                };
+                Self::report_availability_changed_static(&entity_id, available, to_engine).await;
+                Self::report_binary_sensor_state_change_static(&entity_id, &state, to_engine)
+                    .await;
+            }
+            Some(EntityRef::SensorState(sensor_arc)) => {
+                let (entity_id, state) = {
+                    let mut sensor = sensor_arc.lock().await;
+                    debug!("State update for sensor: {}", sensor.id);
                     sensor
                         .update_state(&msg.payload)
                         .map_err(|e| -> Box<dyn Error + Send> {
@@ -320,14 +770,17 @@ impl<C: MqttClient> MqttIntegration<C> {
                                 e.to_string(),
                             ))
                         })?;
-                    sensor_to_update = Some((entity_id.clone(), sensor.state.clone()));
-                    break;
-                }
+                    (sensor.id.clone(), sensor.state.clone())
+                };
+                Self::report_sensor_state_change_static(&entity_id, &state, to_engine).await;
             }
-        }
-
-        if let Some((entity_id, state)) = sensor_to_update {
-            Self::report_binary_sensor_state_change_static(&entity_id, &state, to_engine).await;
+            Some(EntityRef::PublishedCommand(entity_id)) => {
+                debug!("Command received for published entity: {}", entity_id);
+                let raw = String::from_utf8_lossy(&msg.payload).into_owned();
+                let payload = serde_json::from_str(&raw).unwrap_or(serde_json::Value::String(raw));
+                Self::report_entity_command_received_static(&entity_id, payload, to_engine).await;
+            }
+            None => {}
         }
 
         Ok(())
@@ -389,8 +842,190 @@ impl<C: MqttClient> MqttIntegration<C> {
         }
     }
 
-    /// Send a command to a light
-    pub async fn send_light_command(
+    /// Report a numeric sensor's reading to the engine (static version).
+    ///
+    /// Reuses the generic [`FromIntegrationMessage::SensorStateChanged`]
+    /// shape that the Modbus and BLE integrations already report through,
+    /// rather than a bespoke MQTT-only variant, packing `value`/`unit` into
+    /// its `fields` object.
+    async fn report_sensor_state_change_static(
+        entity_id: &str,
+        state: &SensorState,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::SensorStateChanged {
+            entity_id: entity_id.to_string(),
+            fields: serde_json::json!({
+                "value": state.value,
+                "unit": state.unit,
+            }),
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send SensorStateChanged message: {}", e);
+        }
+    }
+
+    /// Report an entity's availability change to the engine (static version)
+    async fn report_availability_changed_static(
+        entity_id: &str,
+        available: bool,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::EntityAvailabilityChanged {
+            entity_id: entity_id.to_string(),
+            available,
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send EntityAvailabilityChanged message: {}", e);
+        }
+    }
+
+    /// Report a failed (unconfirmed) light command to the engine (static
+    /// version)
+    async fn report_command_failed_static(
+        entity_id: &str,
+        reason: &str,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::CommandFailed {
+            entity_id: entity_id.to_string(),
+            reason: reason.to_string(),
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send CommandFailed message: {}", e);
+        }
+    }
+
+    /// Forward a command received on a published entity's `command_topic`
+    /// to the engine, for it to interpret (static version)
+    async fn report_entity_command_received_static(
+        entity_id: &str,
+        payload: serde_json::Value,
+        to_engine: &FromIntegrationSender,
+    ) {
+        let msg = FromIntegrationMessage::EntityCommandReceived {
+            entity_id: entity_id.to_string(),
+            payload,
+        };
+        if let Err(e) = to_engine.send(msg).await {
+            warn!("Failed to send EntityCommandReceived message: {}", e);
+        }
+    }
+
+    /// Derive the topic-safe node id portion of an `entity_id` (e.g.
+    /// `"scene.movie_night"` -> `"movie_night"`), mirroring the
+    /// `{component}.{node_id}` convention [`Self::handle_light_discovery`]
+    /// et al. use when registering discovered entities.
+    fn node_id_for_entity(entity_id: &str) -> String {
+        entity_id
+            .split_once('.')
+            .map_or(entity_id, |(_, rest)| rest)
+            .to_string()
+    }
+
+    /// Publish an engine-computed entity (e.g. a scene, group, or virtual
+    /// switch with no native device of its own) as Home Assistant MQTT
+    /// discovery, the same "re-advertise as hearthd's own entity" pattern
+    /// [`Self::handle_light_discovery`] et al. use for entities discovered
+    /// from Zigbee2MQTT — except here hearthd is the entity's only source
+    /// of truth from the start.
+    ///
+    /// `config` is deserialized into a [`DiscoveryMessage`], with its
+    /// `state_topic`/`command_topic` overwritten to point at topics this
+    /// integration owns; the command topic is subscribed to and routed so
+    /// incoming commands are forwarded back to the engine via
+    /// [`FromIntegrationMessage::EntityCommandReceived`].
+    async fn publish_entity(
+        &mut self,
+        entity_id: &str,
+        component: &str,
+        config: serde_json::Value,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let node_id = Self::node_id_for_entity(entity_id);
+
+        let mut discovery: DiscoveryMessage = serde_json::from_value(config)
+            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+
+        let command_topic = format!("hearthd/{}/{}/set", component, node_id);
+        let state_topic = super::discovery::hearthd_state_topic(component, &node_id);
+        let qos = qos_from_discovery(discovery.qos);
+        discovery.state_topic = Some(state_topic);
+        discovery.command_topic = Some(command_topic.clone());
+
+        {
+            let mut client = self.client.lock().await;
+            client.subscribe(&command_topic, qos).await?;
+        }
+
+        self.routes.lock().await.insert(
+            command_topic.clone(),
+            EntityRef::PublishedCommand(entity_id.to_string()),
+        );
+        self.published.lock().await.insert(
+            entity_id.to_string(),
+            PublishedEntity {
+                component: component.to_string(),
+                command_topic,
+            },
+        );
+
+        let publisher =
+            DiscoveryPublisher::new(self.client.clone(), self.config.discovery_prefix.clone());
+        publisher
+            .publish(component, &node_id, &discovery, &serde_json::json!({}))
+            .await?;
+
+        if let Some(to_engine) = &self.to_engine {
+            Self::register_entity_static(entity_id, to_engine).await;
+        }
+
+        info!(
+            "Published discovery for engine entity {} ({})",
+            entity_id, component
+        );
+        Ok(())
+    }
+
+    /// Withdraw a previously [`Self::publish_entity`]-ed entity: publish an
+    /// empty retained payload on its discovery config topic (the standard
+    /// Home Assistant MQTT discovery removal signal) and forget its route.
+    async fn remove_published_entity(
+        &mut self,
+        entity_id: &str,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let Some(published) = self.published.lock().await.remove(entity_id) else {
+            return Ok(());
+        };
+
+        self.routes.lock().await.remove(&published.command_topic);
+
+        let node_id = Self::node_id_for_entity(entity_id);
+        let config_topic = super::discovery::discovery_config_topic(
+            &self.config.discovery_prefix,
+            &published.component,
+            &node_id,
+        );
+        {
+            let mut client = self.client.lock().await;
+            client
+                .publish(&config_topic, b"", QoS::AtLeastOnce, true)
+                .await?;
+        }
+
+        info!("Removed published entity: {}", entity_id);
+        Ok(())
+    }
+
+    /// Send a command to a light.
+    ///
+    /// Optimistic lights (`light.optimistic == true`) are reported back to
+    /// the engine as applied as soon as the command is published. Other
+    /// lights instead register a [`PendingLightCommand`], confirmed by a
+    /// later echo on the light's `state_topic` (see
+    /// [`Self::handle_state_update_static`]) or failed by
+    /// [`Self::check_pending_commands_static`] if no echo arrives within
+    /// `command_ack_timeout_secs`.
+    async fn send_light_command(
         &self,
         light_id: &str,
         state: LightState,
@@ -418,15 +1053,33 @@ impl<C: MqttClient> MqttIntegration<C> {
             })?;
 
         let command_topic = light.command_topic.clone();
+        let optimistic = light.optimistic;
+        let qos = light.qos;
         drop(light); // Release lock before async call
 
         {
             let mut client = self.client.lock().await;
-            client.publish(&command_topic, &payload, false).await?;
+            client.publish(&command_topic, &payload, qos, false).await?;
         }
 
         info!("Sent command to light {}: {:?}", light_id, state);
 
+        if optimistic {
+            if let Some(to_engine) = &self.to_engine {
+                Self::report_state_change_static(light_id, &state, to_engine).await;
+            }
+        } else {
+            let deadline = self.start.elapsed()
+                + std::time::Duration::from_secs(self.config.command_ack_timeout_secs);
+            self.pending_commands.lock().await.insert(
+                light_id.to_string(),
+                PendingLightCommand {
+                    expected: state,
+                    deadline,
+                },
+            );
+        }
+
         Ok(())
     }
 }
@@ -452,18 +1105,32 @@ impl<C: MqttClient + 'static> Integration for MqttIntegration<C> {
         }
         info!("Connected to MQTT broker");
 
-        // Subscribe to discovery topics for lights and binary sensors
+        // Flip our own status topic from the LWT's retained "offline" to
+        // "online", now that we're actually connected.
+        {
+            let mut client = self.client.lock().await;
+            client
+                .publish(&self.config.status_topic, b"online", QoS::AtLeastOnce, true)
+                .await?;
+        }
+
+        // Subscribe to discovery topics for lights, binary sensors, and
+        // auxiliary numeric sensors
         let light_discovery = format!("{}/light/+/+/config", self.config.discovery_prefix);
         let binary_sensor_discovery =
             format!("{}/binary_sensor/+/+/config", self.config.discovery_prefix);
+        let sensor_discovery = format!("{}/sensor/+/+/config", self.config.discovery_prefix);
         info!(
-            "Subscribing to discovery topics: {}, {}",
-            light_discovery, binary_sensor_discovery
+            "Subscribing to discovery topics: {}, {}, {}",
+            light_discovery, binary_sensor_discovery, sensor_discovery
         );
         {
             let mut client = self.client.lock().await;
-            client.subscribe(&light_discovery).await?;
-            client.subscribe(&binary_sensor_discovery).await?;
+            client.subscribe(&light_discovery, QoS::AtMostOnce).await?;
+            client
+                .subscribe(&binary_sensor_discovery, QoS::AtMostOnce)
+                .await?;
+            client.subscribe(&sensor_discovery, QoS::AtMostOnce).await?;
         }
 
         info!("MQTT integration setup complete, spawning message processing task...");
@@ -473,10 +1140,25 @@ impl<C: MqttClient + 'static> Integration for MqttIntegration<C> {
         let config = self.config.clone();
         let lights = self.lights.clone();
         let binary_sensors = self.binary_sensors.clone();
+        let sensors = self.sensors.clone();
+        let routes = self.routes.clone();
+        let pending_commands = self.pending_commands.clone();
+        let start = self.start;
 
         // Spawn background task to process incoming MQTT messages
         let task = tokio::spawn(async move {
-            Self::process_messages_task(client, config, lights, binary_sensors, tx).await;
+            Self::process_messages_task(
+                client,
+                config,
+                lights,
+                binary_sensors,
+                sensors,
+                routes,
+                pending_commands,
+                start,
+                tx,
+            )
+            .await;
         });
         self._message_task = Some(task);
 
@@ -484,23 +1166,34 @@ impl<C: MqttClient + 'static> Integration for MqttIntegration<C> {
         Ok(())
     }
 
+    fn accepted_commands(&self) -> &[CommandKind] {
+        &self.accepted_commands
+    }
+
     async fn handle_message(
         &mut self,
-        msg: ToIntegrationMessage,
+        cmd: Box<dyn Command>,
     ) -> Result<(), Box<dyn Error + Send>> {
-        match msg {
-            ToIntegrationMessage::LightCommand {
-                entity_id,
-                on,
-                brightness,
-            } => {
-                info!(
-                    "Handling light command for {}: on={}, brightness={:?}",
-                    entity_id, on, brightness
-                );
-                let state = LightState { on, brightness };
-                self.send_light_command(&entity_id, state).await?;
-            }
+        if let Some(light_cmd) = cmd.as_any().downcast_ref::<LightCommand>() {
+            info!(
+                "Handling light command for {}: on={}, brightness={:?}",
+                light_cmd.entity_id, light_cmd.on, light_cmd.brightness
+            );
+            let state = LightState {
+                on: light_cmd.on,
+                brightness: light_cmd.brightness,
+                ..Default::default()
+            };
+            self.send_light_command(&light_cmd.entity_id, state).await?;
+        } else if let Some(publish_cmd) = cmd.as_any().downcast_ref::<PublishEntityCommand>() {
+            self.publish_entity(
+                &publish_cmd.entity_id,
+                &publish_cmd.component,
+                publish_cmd.config.clone(),
+            )
+            .await?;
+        } else if let Some(remove_cmd) = cmd.as_any().downcast_ref::<RemoveEntityCommand>() {
+            self.remove_published_entity(&remove_cmd.entity_id).await?;
         }
         Ok(())
     }
@@ -542,8 +1235,16 @@ mod tests {
             port: 1883,
             client_id: "test".to_string(),
             discovery_prefix: "homeassistant".to_string(),
+            status_topic: "hearthd/status".to_string(),
+            command_ack_timeout_secs: 2,
+            protocol_version: super::config::MqttVersion::V311,
+            manual_ack: false,
             username: None,
             password: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tls_insecure_skip_verify: false,
         };
         let integration = MqttIntegration::new(client, &config);
 
@@ -552,5 +1253,346 @@ mod tests {
 
         let binary_sensors = integration.binary_sensors.lock().await;
         assert_eq!(binary_sensors.len(), 0);
+
+        let sensors = integration.sensors.lock().await;
+        assert_eq!(sensors.len(), 0);
+    }
+
+    fn light_discovery(optimistic: Option<bool>) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some("Test Light".to_string()),
+            unique_id: Some("test_light".to_string()),
+            state_topic: Some("zigbee2mqtt/light/state".to_string()),
+            command_topic: Some("zigbee2mqtt/light/set".to_string()),
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: None,
+            value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic,
+            qos: None,
+            supported_color_modes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_light_command_optimistic_skips_pending() {
+        let client = MockMqttClient::new();
+        let config = MqttConfig {
+            broker: "localhost".to_string(),
+            port: 1883,
+            client_id: "test".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+            status_topic: "hearthd/status".to_string(),
+            command_ack_timeout_secs: 2,
+            protocol_version: super::config::MqttVersion::V311,
+            manual_ack: false,
+            username: None,
+            password: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tls_insecure_skip_verify: false,
+        };
+        let integration = MqttIntegration::new(client, &config);
+
+        let light = Light::from_discovery(
+            light_discovery(Some(true)),
+            "light.test".to_string(),
+            "n".to_string(),
+        )
+        .unwrap();
+        integration
+            .lights
+            .lock()
+            .await
+            .insert("light.test".to_string(), Arc::new(Mutex::new(light)));
+
+        let state = LightState {
+            on: true,
+            brightness: None,
+            ..Default::default()
+        };
+        integration
+            .send_light_command("light.test", state)
+            .await
+            .unwrap();
+
+        assert!(
+            !integration
+                .pending_commands
+                .lock()
+                .await
+                .contains_key("light.test")
+        );
+        assert_eq!(integration.client.lock().await.published.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_light_command_non_optimistic_registers_pending() {
+        let client = MockMqttClient::new();
+        let config = MqttConfig {
+            broker: "localhost".to_string(),
+            port: 1883,
+            client_id: "test".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+            status_topic: "hearthd/status".to_string(),
+            command_ack_timeout_secs: 2,
+            protocol_version: super::config::MqttVersion::V311,
+            manual_ack: false,
+            username: None,
+            password: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tls_insecure_skip_verify: false,
+        };
+        let integration = MqttIntegration::new(client, &config);
+
+        let light = Light::from_discovery(
+            light_discovery(Some(false)),
+            "light.test".to_string(),
+            "n".to_string(),
+        )
+        .unwrap();
+        integration
+            .lights
+            .lock()
+            .await
+            .insert("light.test".to_string(), Arc::new(Mutex::new(light)));
+
+        let state = LightState {
+            on: true,
+            brightness: None,
+            ..Default::default()
+        };
+        integration
+            .send_light_command("light.test", state)
+            .await
+            .unwrap();
+
+        assert!(
+            integration
+                .pending_commands
+                .lock()
+                .await
+                .contains_key("light.test")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_state_update_dispatches_via_routes() {
+        let light = Light::from_discovery(
+            light_discovery(None),
+            "light.test".to_string(),
+            "n".to_string(),
+        )
+        .unwrap();
+        let light_arc = Arc::new(Mutex::new(light));
+
+        let routes: RoutesMap = Arc::new(Mutex::new(HashMap::new()));
+        routes.lock().await.insert(
+            "zigbee2mqtt/light/state".to_string(),
+            EntityRef::LightState(light_arc.clone()),
+        );
+
+        let pending_commands: PendingCommandsMap = Arc::new(Mutex::new(HashMap::new()));
+        let (to_engine, mut from_integration) = tokio::sync::mpsc::channel(8);
+
+        let msg = MqttMessage {
+            topic: "zigbee2mqtt/light/state".to_string(),
+            payload: br#"{"state": "ON", "brightness": 128}"#.to_vec(),
+            retain: false,
+            qos: QoS::AtMostOnce,
+            user_properties: Vec::new(),
+            ack: None,
+        };
+
+        MqttIntegration::<MockMqttClient>::handle_state_update_static(
+            &msg,
+            &routes,
+            &pending_commands,
+            &to_engine,
+            std::time::Duration::from_secs(0),
+        )
+        .await
+        .unwrap();
+
+        assert!(light_arc.lock().await.state.on);
+        let reported = from_integration.recv().await.unwrap();
+        match reported {
+            FromIntegrationMessage::LightStateChanged { entity_id, on, .. } => {
+                assert_eq!(entity_id, "light.test");
+                assert!(on);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_entity_subscribes_and_publishes_discovery() {
+        let client = MockMqttClient::new();
+        let config = MqttConfig {
+            broker: "localhost".to_string(),
+            port: 1883,
+            client_id: "test".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+            status_topic: "hearthd/status".to_string(),
+            command_ack_timeout_secs: 2,
+            protocol_version: super::config::MqttVersion::V311,
+            manual_ack: false,
+            username: None,
+            password: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tls_insecure_skip_verify: false,
+        };
+        let mut integration = MqttIntegration::new(client, &config);
+
+        integration
+            .publish_entity(
+                "scene.movie_night",
+                "switch",
+                serde_json::json!({"name": "Movie Night"}),
+            )
+            .await
+            .unwrap();
+
+        assert!(
+            integration
+                .published
+                .lock()
+                .await
+                .contains_key("scene.movie_night")
+        );
+        assert!(
+            integration
+                .routes
+                .lock()
+                .await
+                .contains_key("hearthd/switch/movie_night/set")
+        );
+
+        let client = integration.client.lock().await;
+        assert!(
+            client
+                .subscriptions
+                .iter()
+                .any(|(topic, _)| topic == "hearthd/switch/movie_night/set")
+        );
+        assert_eq!(client.published.len(), 2);
+        assert_eq!(
+            client.published[0].0,
+            "homeassistant/switch/movie_night/movie_night/config"
+        );
+        assert!(client.published[0].3, "config should be published retained");
+    }
+
+    #[tokio::test]
+    async fn test_remove_published_entity_withdraws_discovery() {
+        let client = MockMqttClient::new();
+        let config = MqttConfig {
+            broker: "localhost".to_string(),
+            port: 1883,
+            client_id: "test".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+            status_topic: "hearthd/status".to_string(),
+            command_ack_timeout_secs: 2,
+            protocol_version: super::config::MqttVersion::V311,
+            manual_ack: false,
+            username: None,
+            password: None,
+            ca_cert: None,
+            client_cert: None,
+            client_key: None,
+            tls_insecure_skip_verify: false,
+        };
+        let mut integration = MqttIntegration::new(client, &config);
+
+        integration
+            .publish_entity(
+                "scene.movie_night",
+                "switch",
+                serde_json::json!({"name": "Movie Night"}),
+            )
+            .await
+            .unwrap();
+
+        integration
+            .remove_published_entity("scene.movie_night")
+            .await
+            .unwrap();
+
+        assert!(
+            !integration
+                .published
+                .lock()
+                .await
+                .contains_key("scene.movie_night")
+        );
+        assert!(
+            !integration
+                .routes
+                .lock()
+                .await
+                .contains_key("hearthd/switch/movie_night/set")
+        );
+
+        let client = integration.client.lock().await;
+        let (topic, payload, _qos, retain) = client.published.last().unwrap();
+        assert_eq!(topic, "homeassistant/switch/movie_night/movie_night/config");
+        assert!(payload.is_empty());
+        assert!(retain);
+    }
+
+    #[tokio::test]
+    async fn test_handle_state_update_dispatches_published_command() {
+        let routes: RoutesMap = Arc::new(Mutex::new(HashMap::new()));
+        routes.lock().await.insert(
+            "hearthd/switch/movie_night/set".to_string(),
+            EntityRef::PublishedCommand("scene.movie_night".to_string()),
+        );
+
+        let pending_commands: PendingCommandsMap = Arc::new(Mutex::new(HashMap::new()));
+        let (to_engine, mut from_integration) = tokio::sync::mpsc::channel(8);
+
+        let msg = MqttMessage {
+            topic: "hearthd/switch/movie_night/set".to_string(),
+            payload: br#"{"action": "ON"}"#.to_vec(),
+            retain: false,
+            qos: QoS::AtMostOnce,
+            user_properties: Vec::new(),
+            ack: None,
+        };
+
+        MqttIntegration::<MockMqttClient>::handle_state_update_static(
+            &msg,
+            &routes,
+            &pending_commands,
+            &to_engine,
+            std::time::Duration::from_secs(0),
+        )
+        .await
+        .unwrap();
+
+        let reported = from_integration.recv().await.unwrap();
+        match reported {
+            FromIntegrationMessage::EntityCommandReceived { entity_id, payload } => {
+                assert_eq!(entity_id, "scene.movie_night");
+                assert_eq!(payload, serde_json::json!({"action": "ON"}));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
     }
 }
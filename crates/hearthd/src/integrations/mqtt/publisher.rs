@@ -0,0 +1,150 @@
+//! Publishes hearthd's own entities back to the broker as Home Assistant
+//! MQTT discovery, the inverse direction of `discovery::parse_discovery_topic`
+//! and the `from_discovery` constructors: those consume Zigbee2MQTT-style
+//! discovery, this produces it.
+
+use std::error::Error;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use super::client::MqttClient;
+use super::client::QoS;
+use super::discovery::DiscoveryMessage;
+use super::discovery::discovery_config_topic;
+use super::discovery::qos_from_discovery;
+
+/// Publishes discovery config and retained state for hearthd-managed
+/// entities, so Home Assistant (or anything else speaking the same
+/// discovery protocol) can see hearthd's own re-advertisement of an
+/// entity under its own `hearthd/...` topics.
+pub struct DiscoveryPublisher<C: MqttClient> {
+    client: Arc<Mutex<C>>,
+    discovery_prefix: String,
+}
+
+impl<C: MqttClient> DiscoveryPublisher<C> {
+    /// Create a new publisher sharing an existing MQTT client connection.
+    pub fn new(client: Arc<Mutex<C>>, discovery_prefix: String) -> Self {
+        Self {
+            client,
+            discovery_prefix,
+        }
+    }
+
+    /// Publish a `component`'s (e.g. "light", "binary_sensor", "sensor")
+    /// discovery config for `node_id`, followed by its current state on the
+    /// state topic the discovery message itself advertises. Both are
+    /// published retained, matching how Zigbee2MQTT publishes its own
+    /// discovery.
+    pub async fn publish(
+        &self,
+        component: &str,
+        node_id: &str,
+        discovery: &DiscoveryMessage,
+        state: &serde_json::Value,
+    ) -> Result<(), Box<dyn Error + Send>> {
+        let config_topic = discovery_config_topic(&self.discovery_prefix, component, node_id);
+        let config_payload = serde_json::to_vec(discovery)
+            .map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+
+        let state_topic = discovery
+            .state_topic
+            .as_deref()
+            .ok_or_else(|| -> Box<dyn Error + Send> {
+                Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "discovery message is missing a state_topic",
+                ))
+            })?;
+        let state_payload =
+            serde_json::to_vec(state).map_err(|e| -> Box<dyn Error + Send> { Box::new(e) })?;
+
+        let qos = qos_from_discovery(discovery.qos);
+
+        let mut client = self.client.lock().await;
+        client
+            .publish(&config_topic, &config_payload, QoS::AtLeastOnce, true)
+            .await?;
+        client.publish(state_topic, &state_payload, qos, true).await?;
+
+        debug!(
+            "Published discovery for {} ({}) on {}",
+            component, node_id, config_topic
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrations::mqtt::client::MockMqttClient;
+
+    fn discovery_with_state_topic(state_topic: &str) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some("Test".to_string()),
+            unique_id: Some("test".to_string()),
+            state_topic: Some(state_topic.to_string()),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: None,
+            value_template: None,
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: None,
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn publish_sends_retained_config_and_state() {
+        let client = Arc::new(Mutex::new(MockMqttClient::new()));
+        let publisher = DiscoveryPublisher::new(client.clone(), "homeassistant".to_string());
+
+        let discovery = discovery_with_state_topic("hearthd/binary_sensor/node1/state");
+        let state = serde_json::json!({"on": true});
+
+        publisher
+            .publish("binary_sensor", "node1", &discovery, &state)
+            .await
+            .unwrap();
+
+        let published = &client.lock().await.published;
+        assert_eq!(published.len(), 2);
+        assert_eq!(
+            published[0].0,
+            "homeassistant/binary_sensor/node1/node1/config"
+        );
+        assert!(published[0].3, "config should be published retained");
+        assert_eq!(published[1].0, "hearthd/binary_sensor/node1/state");
+        assert!(published[1].3, "state should be published retained");
+    }
+
+    #[tokio::test]
+    async fn publish_fails_without_state_topic() {
+        let client = Arc::new(Mutex::new(MockMqttClient::new()));
+        let publisher = DiscoveryPublisher::new(client, "homeassistant".to_string());
+
+        let mut discovery = discovery_with_state_topic("unused");
+        discovery.state_topic = None;
+
+        let result = publisher
+            .publish("light", "node1", &discovery, &serde_json::json!({}))
+            .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,222 @@
+use std::error::Error;
+
+use crate::engine::Entity;
+use crate::engine::state::SensorState;
+use crate::integrations::mqtt::discovery::DeviceInfo;
+use crate::integrations::mqtt::discovery::DiscoveryMessage;
+use crate::integrations::mqtt::value_template;
+
+/// Numeric sensor entity (e.g. battery, illuminance, linkquality,
+/// temperature), as distinct from the on/off `binary_sensor` component.
+#[derive(Debug, Clone)]
+pub struct NumericSensor {
+    /// Entity ID (e.g., "sensor.living_room_battery")
+    pub id: String,
+
+    /// Human-readable name
+    pub name: String,
+
+    /// Unique identifier from Zigbee2MQTT
+    #[allow(dead_code)]
+    pub unique_id: String,
+
+    /// Device class (e.g., "battery", "illuminance", "temperature")
+    #[allow(dead_code)]
+    pub device_class: Option<String>,
+
+    /// Current state
+    pub state: SensorState,
+
+    /// Device information
+    #[allow(dead_code)]
+    pub device_info: Option<DeviceInfo>,
+
+    /// Topic to receive state updates
+    pub state_topic: String,
+
+    /// Value template for extracting the numeric reading from the JSON
+    /// payload, e.g. "{{ value_json.battery }}". Evaluated with
+    /// [`value_template`].
+    value_template: Option<String>,
+
+    /// QoS to use for this sensor's state topic.
+    pub qos: crate::integrations::mqtt::client::QoS,
+}
+
+impl NumericSensor {
+    /// Create a NumericSensor entity from a Zigbee2MQTT discovery message
+    pub fn from_discovery(
+        discovery: DiscoveryMessage,
+        id: String,
+        node_id: String,
+    ) -> Result<Self, Box<dyn Error>> {
+        let unique_id = discovery
+            .unique_id
+            .unwrap_or_else(|| format!("{}_sensor", node_id));
+
+        let name = discovery.name.unwrap_or_else(|| format!("Sensor {}", node_id));
+
+        let state_topic = discovery
+            .state_topic
+            .ok_or("Missing state_topic in discovery message")?;
+
+        Ok(Self {
+            id,
+            name,
+            unique_id,
+            device_class: discovery.device_class,
+            state: SensorState {
+                value: 0.0,
+                unit: discovery.unit_of_measurement,
+            },
+            device_info: discovery.device,
+            state_topic,
+            value_template: discovery.value_template,
+            qos: crate::integrations::mqtt::discovery::qos_from_discovery(discovery.qos),
+        })
+    }
+
+    /// Update the sensor's numeric reading from an MQTT payload.
+    ///
+    /// Zigbee2MQTT sends state updates as JSON, e.g.:
+    /// {"battery": 85, "illuminance": 120, "linkquality": 140}
+    ///
+    /// The `value_template` (if any) is evaluated against the parsed payload
+    /// via [`value_template::evaluate`]. A template whose path can't be
+    /// resolved, or that resolves to a non-numeric value, leaves the
+    /// reading unchanged rather than resetting it to zero.
+    pub fn update_state(&mut self, payload: &[u8]) -> Result<(), Box<dyn Error>> {
+        let json_str = std::str::from_utf8(payload)?;
+        let state_update: serde_json::Value = serde_json::from_str(json_str)?;
+
+        let resolved = match self.value_template.as_deref() {
+            Some(template) => value_template::evaluate(template, &state_update),
+            None => state_update.get("value").cloned(),
+        };
+
+        if let Some(value) = resolved.as_ref().and_then(value_template::as_f64) {
+            self.state.value = value;
+        }
+
+        Ok(())
+    }
+
+    /// Build the Home Assistant MQTT discovery message advertising this
+    /// sensor on the broker, the inverse of [`NumericSensor::from_discovery`].
+    /// The emitted message points at hearthd's own state topic rather than
+    /// the one this sensor was originally discovered from, since hearthd is
+    /// now the entity's source of truth.
+    pub fn to_discovery(&self, node_id: &str) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some(self.name.clone()),
+            unique_id: Some(self.unique_id.clone()),
+            state_topic: Some(super::discovery::hearthd_state_topic("sensor", node_id)),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: self.device_info.clone(),
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: self.device_class.clone(),
+            value_template: Some("{{ value_json.value }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: self.state.unit.clone(),
+            optimistic: None,
+            qos: super::discovery::qos_to_discovery(self.qos),
+            supported_color_modes: None,
+        }
+    }
+}
+
+impl Entity for NumericSensor {
+    fn state_json(&self) -> serde_json::Value {
+        serde_json::to_value(&self.state).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery_discovery(unit: Option<&str>) -> DiscoveryMessage {
+        DiscoveryMessage {
+            name: Some("Battery".to_string()),
+            unique_id: Some("test_battery".to_string()),
+            state_topic: Some("zigbee2mqtt/sensor/state".to_string()),
+            command_topic: None,
+            brightness_state_topic: None,
+            brightness_command_topic: None,
+            device: None,
+            payload_on: None,
+            payload_off: None,
+            brightness: None,
+            schema: None,
+            device_class: Some("battery".to_string()),
+            value_template: Some("{{ value_json.battery }}".to_string()),
+            off_delay: None,
+            expire_after: None,
+            availability_topic: None,
+            payload_available: None,
+            payload_not_available: None,
+            unit_of_measurement: unit.map(str::to_string),
+            optimistic: None,
+            qos: None,
+            supported_color_modes: None,
+        }
+    }
+
+    #[test]
+    fn test_update_state_via_value_template() {
+        let mut sensor = NumericSensor::from_discovery(
+            battery_discovery(Some("%")),
+            "sensor.test_battery".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+
+        sensor
+            .update_state(br#"{"battery": 85, "linkquality": 140}"#)
+            .unwrap();
+
+        assert_eq!(sensor.state.value, 85.0);
+        assert_eq!(sensor.state.unit, Some("%".to_string()));
+    }
+
+    #[test]
+    fn test_update_state_ignores_unresolved_template() {
+        let mut sensor = NumericSensor::from_discovery(
+            battery_discovery(None),
+            "sensor.test_battery".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+
+        sensor.update_state(br#"{"linkquality": 140}"#).unwrap();
+
+        assert_eq!(sensor.state.value, 0.0);
+    }
+
+    #[test]
+    fn test_to_discovery_roundtrips_device_class_and_unit() {
+        let sensor = NumericSensor::from_discovery(
+            battery_discovery(Some("%")),
+            "sensor.test_battery".to_string(),
+            "test_node".to_string(),
+        )
+        .unwrap();
+
+        let discovery = sensor.to_discovery("test_node");
+        assert_eq!(discovery.device_class, Some("battery".to_string()));
+        assert_eq!(discovery.unit_of_measurement, Some("%".to_string()));
+        assert_eq!(
+            discovery.state_topic,
+            Some("hearthd/sensor/test_node/state".to_string())
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! A small evaluator for the Home Assistant / Zigbee2MQTT value template
+//! subset used in MQTT discovery's `value_template` field.
+//!
+//! Home Assistant integrations embed full Jinja2 templates here, but in
+//! practice MQTT discovery payloads only ever use a handful of shapes: a
+//! dotted/bracketed path into `value_json`, an `is defined` test, a
+//! `| default(...)` filter, and an inline `'X' if <path> else 'Y'` ternary.
+//! Rather than pull in a full template engine, this module tokenizes and
+//! resolves just that subset directly against the parsed payload.
+
+use serde_json::Value;
+
+/// Evaluate a `{{ ... }}` value template against a parsed JSON payload.
+///
+/// Returns `None` when the template is malformed or the path it references
+/// is absent from `payload` - a missing path means "no state change", not
+/// `false`, so callers should leave the previous state alone in that case.
+pub fn evaluate(template: &str, payload: &Value) -> Option<Value> {
+    let inner = template
+        .trim()
+        .strip_prefix("{{")?
+        .strip_suffix("}}")?
+        .trim();
+
+    if let Some(result) = evaluate_ternary(inner, payload) {
+        return Some(result);
+    }
+
+    let (path_expr, default) = split_default_filter(inner);
+
+    if let Some(path) = path_expr.strip_suffix("is defined").map(str::trim) {
+        return Some(Value::Bool(resolve_path(path, payload).is_some()));
+    }
+
+    match resolve_path(path_expr.trim(), payload) {
+        Some(value) => Some(value.clone()),
+        None => default.map(Value::String),
+    }
+}
+
+/// Coerce a resolved template value to an on/off boolean.
+///
+/// `payload_on` defaults to `"ON"` when not configured, matching
+/// Zigbee2MQTT's own default. Values that don't match either side coerce to
+/// `false` rather than being rejected, since a found-but-unrecognized value
+/// is still a definite (if surprising) state, unlike a path that resolved to
+/// nothing at all.
+pub fn as_on_off(value: &Value, payload_on: Option<&str>) -> bool {
+    let on = payload_on.unwrap_or("ON");
+    match value {
+        Value::Bool(b) => *b,
+        Value::String(s) => s == on || s == "true",
+        Value::Number(n) => n.as_f64().map(|f| f != 0.0).unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// Coerce a resolved template value to a numeric reading.
+///
+/// Zigbee2MQTT reports numeric sensor values as JSON numbers, but some
+/// devices send them as strings (e.g. `"85"`); both are accepted. Returns
+/// `None` for values that aren't numeric at all, so callers can leave the
+/// previous reading in place rather than resetting it to zero.
+pub fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Resolve a dotted/bracketed path (e.g. `value_json.a.b[0]`) against a JSON
+/// value. The leading `value_json` segment, if present, is dropped since it
+/// refers to `payload` itself.
+fn resolve_path<'a>(path: &str, payload: &'a Value) -> Option<&'a Value> {
+    let mut segments = tokenize_path(path)?;
+    if segments.first().map(String::as_str) == Some("value_json") {
+        segments.remove(0);
+    }
+
+    let mut current = payload;
+    for segment in segments {
+        current = match current {
+            Value::Object(map) => map.get(&segment)?,
+            Value::Array(arr) => arr.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Split a path into its dotted/bracketed segments, e.g. `"a.b['c'][0]"` ->
+/// `["a", "b", "c", "0"]`. Returns `None` for an empty path.
+fn tokenize_path(path: &str) -> Option<Vec<String>> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut chars = path.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+            }
+            '[' => {
+                if !current.is_empty() {
+                    segments.push(std::mem::take(&mut current));
+                }
+                let mut key = String::new();
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    key.push(c);
+                }
+                segments.push(
+                    key.trim()
+                        .trim_matches(|c| c == '\'' || c == '"')
+                        .to_string(),
+                );
+            }
+            _ => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+
+    if segments.is_empty() { None } else { Some(segments) }
+}
+
+/// Evaluate an inline `'X' if <cond> else 'Y'` ternary, returning `None` if
+/// `inner` isn't shaped like one.
+fn evaluate_ternary(inner: &str, payload: &Value) -> Option<Value> {
+    let (then_part, rest) = split_once_keyword(inner, " if ")?;
+    let (cond_part, else_part) = split_once_keyword(rest, " else ")?;
+
+    let then_value = parse_literal(then_part.trim())?;
+    let else_value = parse_literal(else_part.trim())?;
+
+    Some(if evaluate_condition(cond_part.trim(), payload) {
+        then_value
+    } else {
+        else_value
+    })
+}
+
+/// Evaluate a ternary's condition: either a `<path> == <literal>` /
+/// `<path> != <literal>` comparison, or a bare path tested for truthiness.
+fn evaluate_condition(cond: &str, payload: &Value) -> bool {
+    for (op, negate) in [(" == ", false), (" != ", true)] {
+        if let Some((lhs, rhs)) = split_once_keyword(cond, op) {
+            let equal = match (resolve_path(lhs.trim(), payload), parse_literal(rhs.trim())) {
+                (Some(lhs), Some(rhs)) => *lhs == rhs,
+                _ => false,
+            };
+            return equal != negate;
+        }
+    }
+    resolve_path(cond, payload).is_some_and(is_truthy)
+}
+
+/// Jinja-style truthiness: `false`, `null`, `0`, and empty strings/collections
+/// are falsy, everything else is truthy.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Bool(b) => *b,
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Number(n) => n.as_f64().is_some_and(|f| f != 0.0),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+    }
+}
+
+/// Parse a Jinja literal: a quoted string, or anything else JSON can parse
+/// (numbers, `true`/`false`, `null`).
+fn parse_literal(s: &str) -> Option<Value> {
+    let s = s.trim();
+    for quote in ['\'', '"'] {
+        if let Some(inner) = s
+            .strip_prefix(quote)
+            .and_then(|s| s.strip_suffix(quote))
+        {
+            return Some(Value::String(inner.to_string()));
+        }
+    }
+    serde_json::from_str(s).ok()
+}
+
+/// Split `s` on the first occurrence of `kw`, like `split_once` but for a
+/// multi-character separator that isn't itself a valid split pattern char.
+fn split_once_keyword<'a>(s: &'a str, kw: &str) -> Option<(&'a str, &'a str)> {
+    s.find(kw).map(|idx| (&s[..idx], &s[idx + kw.len()..]))
+}
+
+/// Split off a trailing `| default('...')` filter, returning the remaining
+/// path expression and the default string if present.
+fn split_default_filter(inner: &str) -> (&str, Option<String>) {
+    let Some(idx) = inner.find('|') else {
+        return (inner, None);
+    };
+    let (path, filter) = (&inner[..idx], inner[idx + 1..].trim());
+
+    let default = filter
+        .strip_prefix("default(")
+        .and_then(|s| s.strip_suffix(')'))
+        .and_then(|args| parse_literal(args.trim()))
+        .and_then(|value| match value {
+            Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        });
+
+    (path.trim(), default)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolves_a_top_level_key() {
+        let payload = json!({"occupancy": true});
+        assert_eq!(
+            evaluate("{{ value_json.occupancy }}", &payload),
+            Some(json!(true))
+        );
+    }
+
+    #[test]
+    fn resolves_a_nested_dotted_path() {
+        let payload = json!({"a": {"b": {"c": "deep"}}});
+        assert_eq!(
+            evaluate("{{ value_json.a.b.c }}", &payload),
+            Some(json!("deep"))
+        );
+    }
+
+    #[test]
+    fn resolves_a_bracketed_path() {
+        let payload = json!({"a": [10, 20]});
+        assert_eq!(
+            evaluate("{{ value_json['a'][1] }}", &payload),
+            Some(json!(20))
+        );
+    }
+
+    #[test]
+    fn missing_path_resolves_to_none() {
+        let payload = json!({"occupancy": true});
+        assert_eq!(evaluate("{{ value_json.missing }}", &payload), None);
+    }
+
+    #[test]
+    fn is_defined_tests_presence_not_value() {
+        let payload = json!({"occupancy": false});
+        assert_eq!(
+            evaluate("{{ value_json.occupancy is defined }}", &payload),
+            Some(json!(true))
+        );
+        assert_eq!(
+            evaluate("{{ value_json.missing is defined }}", &payload),
+            Some(json!(false))
+        );
+    }
+
+    #[test]
+    fn default_filter_applies_when_path_is_missing() {
+        let payload = json!({});
+        assert_eq!(
+            evaluate("{{ value_json.state | default('OFF') }}", &payload),
+            Some(json!("OFF"))
+        );
+    }
+
+    #[test]
+    fn default_filter_is_ignored_when_path_is_present() {
+        let payload = json!({"state": "ON"});
+        assert_eq!(
+            evaluate("{{ value_json.state | default('OFF') }}", &payload),
+            Some(json!("ON"))
+        );
+    }
+
+    #[test]
+    fn ternary_picks_the_truthy_branch() {
+        let payload = json!({"contact": true});
+        assert_eq!(
+            evaluate("{{ 'ON' if value_json.contact else 'OFF' }}", &payload),
+            Some(json!("ON"))
+        );
+    }
+
+    #[test]
+    fn ternary_picks_the_falsy_branch_on_missing_path() {
+        let payload = json!({});
+        assert_eq!(
+            evaluate("{{ 'ON' if value_json.contact else 'OFF' }}", &payload),
+            Some(json!("OFF"))
+        );
+    }
+
+    #[test]
+    fn ternary_supports_equality_comparison() {
+        let payload = json!({"state": "closed"});
+        assert_eq!(
+            evaluate(
+                "{{ 'ON' if value_json.state == 'open' else 'OFF' }}",
+                &payload
+            ),
+            Some(json!("OFF"))
+        );
+    }
+
+    #[test]
+    fn as_on_off_coerces_bool_string_and_number() {
+        assert!(as_on_off(&json!(true), None));
+        assert!(!as_on_off(&json!(false), None));
+        assert!(as_on_off(&json!("ON"), None));
+        assert!(as_on_off(&json!("open"), Some("open")));
+        assert!(as_on_off(&json!(1), None));
+        assert!(!as_on_off(&json!(0), None));
+    }
+}
@@ -8,4 +8,6 @@ pub use config::Config;
 pub use config::Diagnostic;
 pub use config::Diagnostics;
 pub use config::LogLevel;
+pub use config::OutputFormat;
 pub use config::format_diagnostics;
+pub use config::format_diagnostics_json;
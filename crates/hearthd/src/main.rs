@@ -2,8 +2,8 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use hearthd::Config;
-use tokio::signal::unix::SignalKind;
 use tokio::signal::unix::signal;
+use tokio::signal::unix::SignalKind;
 use tracing::debug;
 use tracing::info;
 use tracing::warn;
@@ -23,6 +23,39 @@ struct Cli {
         default_value = "/etc/hearthd/config.toml"
     )]
     config: Vec<PathBuf>,
+
+    /// How to print config diagnostics (warnings raised while loading) at startup.
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: CliOutputFormat,
+
+    /// Increase log verbosity one level per occurrence (e.g. -vv), up to
+    /// `trace`. Mutually exclusive with `--quiet`.
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Decrease log verbosity one level per occurrence (e.g. -qq), down to
+    /// disabling logging entirely once past `error`. Mutually exclusive
+    /// with `--verbose`.
+    #[arg(short, long, action = clap::ArgAction::Count, conflicts_with = "verbose")]
+    quiet: u8,
+}
+
+/// CLI-facing mirror of [`hearthd::OutputFormat`]. Kept separate so that
+/// `hearthd_config` (where `OutputFormat` lives) doesn't need to depend on
+/// clap just to be selectable from the command line.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliOutputFormat {
+    Human,
+    Json,
+}
+
+impl From<CliOutputFormat> for hearthd::OutputFormat {
+    fn from(format: CliOutputFormat) -> Self {
+        match format {
+            CliOutputFormat::Human => hearthd::OutputFormat::Human,
+            CliOutputFormat::Json => hearthd::OutputFormat::Json,
+        }
+    }
 }
 
 #[tokio::main]
@@ -38,9 +71,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     };
 
-    // Set up tracing
+    // Set up tracing, letting -v/-q temporarily crank the configured level
+    // up or down without editing config files.
     let log_targets = {
-        let mut t = TracingTargets::new().with_default(cfg.logging.level);
+        let mut t =
+            TracingTargets::new().with_default(cfg.logging.effective_level(cli.verbose, cli.quiet));
         for (target, lvl) in &cfg.logging.overrides {
             t = t.with_target(target.clone(), *lvl);
         }
@@ -52,9 +87,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
 
     // Display any warnings (errors would have prevented loading)
-    for diagnostic in &diagnostics.0 {
-        if diagnostic.is_warning() {
-            warn!("{}", diagnostic);
+    let output_format: hearthd::OutputFormat = cli.format.into();
+    match output_format {
+        hearthd::OutputFormat::Human => {
+            for diagnostic in &diagnostics.0 {
+                if diagnostic.is_warning() {
+                    warn!("{}", diagnostic);
+                }
+            }
+        }
+        hearthd::OutputFormat::Json => {
+            let warnings: Vec<_> = diagnostics
+                .iter()
+                .filter(|d| d.is_warning())
+                .cloned()
+                .collect();
+            if !warnings.is_empty() {
+                println!("{}", hearthd::format_diagnostics_json(&warnings));
+            }
         }
     }
 
@@ -85,7 +135,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let http_listen = cfg.http.listen.clone();
     let http_port = cfg.http.port;
     let http_server = tokio::spawn(async move {
-        if let Err(e) = hearthd::api::serve(http_listen, http_port, shutdown_rx).await {
+        // TODO: wire cert/key paths through once the config crate exposes
+        // them; plaintext HTTP until then.
+        if let Err(e) = hearthd::api::serve(http_listen, http_port, shutdown_rx, None).await {
             warn!("HTTP API server error: {}", e);
         }
     });
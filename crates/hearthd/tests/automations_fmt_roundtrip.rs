@@ -0,0 +1,68 @@
+//! Round-trip and idempotency coverage for the automations formatter.
+//!
+//! Every `.hearth` file under `tests/automations_corpus/` is parsed,
+//! formatted, and re-parsed; the two ASTs must match ignoring spans
+//! (catching formatter bugs that silently change meaning), and formatting
+//! the re-parsed tree must reproduce the exact same text (catching
+//! non-idempotent output). New language constructs get this coverage for
+//! free just by adding a sample file to the corpus.
+
+use hearthd::automations::SourceFormat;
+use hearthd::automations::SpanlessEq;
+use hearthd::automations::parse;
+
+const MAX_WIDTH: usize = 80;
+
+fn corpus_files() -> Vec<std::path::PathBuf> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/automations_corpus");
+    let mut files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("reading corpus dir {}: {e}", dir.display()))
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "hearth"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn corpus_is_non_empty() {
+    assert!(
+        !corpus_files().is_empty(),
+        "expected at least one .hearth fixture under tests/automations_corpus/"
+    );
+}
+
+#[test]
+fn formatting_round_trips_and_is_idempotent() {
+    for path in corpus_files() {
+        let source = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("reading {}: {e}", path.display()));
+
+        let original = parse(&source)
+            .unwrap_or_else(|errs| panic!("parsing {}: {errs:?}", path.display()));
+
+        let formatted = original.node.format_source(MAX_WIDTH);
+
+        let reparsed = parse(&formatted).unwrap_or_else(|errs| {
+            panic!(
+                "re-parsing formatted output of {}: {errs:?}\n---\n{formatted}",
+                path.display()
+            )
+        });
+
+        assert!(
+            original.node.spanless_eq(&reparsed.node),
+            "formatting {} changed the parsed AST\n--- original ---\n{source}\n--- formatted \
+             ---\n{formatted}",
+            path.display(),
+        );
+
+        let reformatted = reparsed.node.format_source(MAX_WIDTH);
+        assert_eq!(
+            formatted,
+            reformatted,
+            "formatting {} is not idempotent",
+            path.display()
+        );
+    }
+}
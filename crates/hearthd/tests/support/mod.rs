@@ -0,0 +1,124 @@
+//! A small `ui_test`-style fixture harness for automations diagnostics.
+//!
+//! Each fixture is a `.hearth` file under `tests/ui/` whose expected
+//! diagnostics are declared inline, pinned to the line they should be
+//! reported on:
+//!
+//! ```text
+//! let = 1;  //~ ERROR expected
+//! ```
+//!
+//! [`check`] runs the fixture's annotations against whatever diagnostics
+//! the caller's pipeline (`parse`, `desugar`, ...) actually produced,
+//! and fails with a readable diff listing unmatched annotations and
+//! surprise diagnostics.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// The severity an annotation or emitted diagnostic carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ERROR")
+    }
+}
+
+/// One `//~ ERROR message` annotation, pinned to the 1-based source
+/// line it appeared on.
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// One diagnostic a pipeline actually emitted, reduced to what an
+/// [`Annotation`] can be checked against.
+#[derive(Debug, Clone)]
+pub struct Emitted {
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Scan `content` for `//~ ERROR message` annotation comments.
+pub fn parse_annotations(content: &str) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let Some(i) = line.find("//~") else {
+            continue;
+        };
+        let rest = line[i + 3..].trim_start();
+        let Some(message) = rest.strip_prefix("ERROR") else {
+            continue;
+        };
+        annotations.push(Annotation {
+            line: idx + 1,
+            severity: Severity::Error,
+            message: message.trim().to_string(),
+        });
+    }
+    annotations
+}
+
+/// Check `fixture_path`'s inline annotations against `emitted`.
+///
+/// Panics with a readable diff on any mismatch.
+pub fn check(fixture_path: &Path, emitted: &[Emitted]) {
+    let content = fs::read_to_string(fixture_path)
+        .unwrap_or_else(|e| panic!("reading fixture {}: {e}", fixture_path.display()));
+    let expected = parse_annotations(&content);
+
+    let mut matched = vec![false; emitted.len()];
+    let mut unmatched_annotations = Vec::new();
+
+    for annotation in &expected {
+        let hit = emitted.iter().enumerate().find(|(i, e)| {
+            !matched[*i]
+                && e.line == annotation.line
+                && e.severity == annotation.severity
+                && e.message.contains(&annotation.message)
+        });
+        match hit {
+            Some((i, _)) => matched[i] = true,
+            None => unmatched_annotations.push(annotation.clone()),
+        }
+    }
+
+    let surprises: Vec<_> = emitted
+        .iter()
+        .zip(&matched)
+        .filter(|(_, was_matched)| !**was_matched)
+        .map(|(e, _)| e.clone())
+        .collect();
+
+    if !unmatched_annotations.is_empty() || !surprises.is_empty() {
+        let mut diff = String::new();
+        for a in &unmatched_annotations {
+            writeln!(
+                diff,
+                "- expected {} on line {}: {}",
+                a.severity, a.line, a.message
+            )
+            .ok();
+        }
+        for e in &surprises {
+            writeln!(
+                diff,
+                "+ emitted {} on line {}: {}",
+                e.severity, e.line, e.message
+            )
+            .ok();
+        }
+        panic!(
+            "fixture {} has mismatched diagnostics:\n{diff}",
+            fixture_path.display()
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Fixture-driven parser diagnostic tests, in the spirit of `ui_test`:
+//! each `.hearth` file under `tests/ui/` carries its expected
+//! diagnostics inline as `//~ ERROR` annotations pinned to the line
+//! they should be reported on (see `tests/support`), and the real
+//! `automations::parse` pipeline runs against it.
+
+mod support;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use hearthd::automations::parse;
+use hearthd::automations::render_report;
+use support::Emitted;
+use support::Severity;
+
+fn manifest_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+/// Byte offset to 1-based line number, the same convention `//~`
+/// annotations are pinned to.
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())]
+        .bytes()
+        .filter(|&b| b == b'\n')
+        .count()
+        + 1
+}
+
+#[test]
+fn a_binding_with_no_name_is_flagged_at_the_let_statement() {
+    let fixture_path = manifest_path("tests/ui/missing_binding_name.hearth");
+    let content = std::fs::read_to_string(&fixture_path).unwrap();
+
+    let errs = parse(&content).expect_err("missing a name after `let` should fail to parse");
+
+    let emitted: Vec<_> = errs
+        .iter()
+        .map(|err| Emitted {
+            line: line_at(&content, err.span().start),
+            severity: Severity::Error,
+            message: render_report(&content, std::slice::from_ref(err)),
+        })
+        .collect();
+
+    support::check(&fixture_path, &emitted);
+}
@@ -0,0 +1,113 @@
+//! Builds a synthetic single-field TOML document from a command-line
+//! `--set path.to.field=value` override, analogous to [`crate::env`]'s
+//! handling of environment variables but keyed by `.`-delimited dotted
+//! paths instead of `__`, and with the right-hand side taken as a literal
+//! TOML value expression (so a string override must include its own
+//! quotes, e.g. `--set location.timezone="Europe/Berlin"`) instead of
+//! type-inferred the way `env`'s bare values are.
+
+use std::path::PathBuf;
+
+use crate::SourceInfo;
+
+/// One command-line-derived config layer: a synthetic single-field TOML
+/// document, plus the dotted path it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliOverride {
+    /// The dotted path as passed on the command line, e.g.
+    /// `"integrations.ha.met_oslo.enabled"`.
+    pub path: String,
+    /// A single-assignment TOML document equivalent to what this override
+    /// sets, e.g. `"[location]\ntimezone = \"Europe/Berlin\"\n"`.
+    pub toml: String,
+}
+
+impl CliOverride {
+    /// A synthetic source like `command-line:location.timezone`, so a
+    /// diagnostic that references an override-derived value still points
+    /// at where it came from, the same way [`crate::env::EnvVar::source`]
+    /// does for an environment variable.
+    pub fn source(&self) -> SourceInfo {
+        SourceInfo {
+            file_path: PathBuf::from(format!("command-line:{}", self.path)),
+            content: self.toml.clone(),
+        }
+    }
+}
+
+/// Parse a raw `(dotted.path, value)` pair - as from a `--set
+/// path.to.field=value` flag - into a [`CliOverride`]. `value` is
+/// inserted into the synthetic document as-is, so it must already be a
+/// valid TOML value literal (quoted strings, bare numbers/booleans),
+/// matching cargo's `--config` flag rather than `env`'s best-effort type
+/// inference. Returns `None` if `path` is empty or has an empty segment
+/// (a leading, trailing, or doubled `.`); the caller is left to decide
+/// how to surface that as a diagnostic.
+pub fn parse_override(path: &str, value: &str) -> Option<CliOverride> {
+    if path.is_empty() {
+        return None;
+    }
+
+    let keys: Vec<&str> = path.split('.').collect();
+    if keys.iter().any(|key| key.is_empty()) {
+        return None;
+    }
+
+    let (table_path, field) = keys.split_at(keys.len() - 1);
+    let field = field[0];
+
+    let mut toml = String::new();
+    if !table_path.is_empty() {
+        toml.push_str(&format!("[{}]\n", table_path.join(".")));
+    }
+    toml.push_str(&format!("{} = {}\n", field, value));
+
+    Some(CliOverride {
+        path: path.to_string(),
+        toml,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_override_renders_a_top_level_field() {
+        let found = parse_override("name", "\"base\"").unwrap();
+        assert_eq!(found.toml, "name = \"base\"\n");
+    }
+
+    #[test]
+    fn parse_override_renders_a_nested_field() {
+        let found = parse_override("location.timezone", "\"Europe/Berlin\"").unwrap();
+        assert_eq!(found.toml, "[location]\ntimezone = \"Europe/Berlin\"\n");
+    }
+
+    #[test]
+    fn parse_override_renders_a_deeply_nested_hashmap_key() {
+        let found = parse_override("integrations.ha.met_oslo.enabled", "false").unwrap();
+        assert_eq!(found.toml, "[integrations.ha.met_oslo]\nenabled = false\n");
+    }
+
+    #[test]
+    fn parse_override_rejects_an_empty_path() {
+        assert!(parse_override("", "1").is_none());
+    }
+
+    #[test]
+    fn parse_override_rejects_a_doubled_dot() {
+        assert!(parse_override("location..timezone", "1").is_none());
+    }
+
+    #[test]
+    fn source_uses_command_line_prefixed_file_path() {
+        let found = parse_override("location.timezone", "\"Europe/Berlin\"").unwrap();
+        let source = found.source();
+        assert_eq!(
+            source.file_path,
+            PathBuf::from("command-line:location.timezone")
+        );
+        assert_eq!(source.content, found.toml);
+    }
+}
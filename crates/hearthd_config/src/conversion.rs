@@ -0,0 +1,297 @@
+//! Typed value-conversion layer for raw config string fields.
+//!
+//! Config files only give us strings (TOML strings, or values `to_string`'d
+//! during merging); fields like `start_time = "2024-01-15T10:30:00Z"` or
+//! `poll_interval = "30m"` need to become a richer typed value before
+//! `TryFromPartial::try_from_partial` hands them to application code. A
+//! [`Conversion`] describes how to do that for one field, and
+//! [`Conversion::apply`] runs it against a [`Located<String>`] so a failure
+//! (e.g. a malformed timestamp) carries the exact field path, span and
+//! [`SourceInfo`] of the offending text.
+
+use std::time::Duration;
+
+use crate::Diagnostic;
+use crate::Error;
+use crate::Located;
+use crate::ValidationError;
+
+/// A named conversion from a raw config string to a richer typed value.
+///
+/// Intended to be attached to a field (e.g. via a future `#[config(convert =
+/// "duration")]` derive attribute) and invoked from `try_from_partial`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conversion {
+    /// The raw bytes of the string, as-is.
+    Bytes,
+    /// The string, as-is.
+    String,
+    /// A base-10 integer, e.g. `"42"` or `"-7"`.
+    Integer,
+    /// A floating-point number, e.g. `"3.14"`.
+    Float,
+    /// `"true"`/`"false"`.
+    Boolean,
+    /// An RFC 3339 timestamp, e.g. `"2024-01-15T10:30:00Z"`, as Unix seconds.
+    Timestamp,
+    /// A timestamp parsed with a strftime-style pattern (`%Y`, `%m`, `%d`,
+    /// `%H`, `%M`, `%S`), as Unix seconds.
+    TimestampWithFormat { format: String },
+    /// A human duration string like `30min`/`2h`, reusing the Automations
+    /// DSL's time-unit vocabulary (`s`, `min`, `h`, `d`).
+    Duration,
+}
+
+/// The typed value produced by applying a [`Conversion`] to a config string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConvertedValue {
+    Bytes(Vec<u8>),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    /// Unix timestamp, in seconds.
+    Timestamp(i64),
+    Duration(Duration),
+}
+
+impl Conversion {
+    /// Apply this conversion to `value`, reporting failures as a
+    /// `Diagnostic::Error(Error::Validation(..))` that points at `field_path`
+    /// and `value`'s span/source.
+    pub fn apply(&self, field_path: &str, value: &Located<String>) -> Result<ConvertedValue, Diagnostic> {
+        let raw = value.get_ref().as_str();
+
+        let converted = match self {
+            Conversion::Bytes => Some(ConvertedValue::Bytes(raw.as_bytes().to_vec())),
+            Conversion::String => Some(ConvertedValue::String(raw.to_string())),
+            Conversion::Integer => raw.parse().ok().map(ConvertedValue::Integer),
+            Conversion::Float => raw.parse().ok().map(ConvertedValue::Float),
+            Conversion::Boolean => match raw {
+                "true" => Some(ConvertedValue::Boolean(true)),
+                "false" => Some(ConvertedValue::Boolean(false)),
+                _ => None,
+            },
+            Conversion::Timestamp => parse_rfc3339(raw).map(ConvertedValue::Timestamp),
+            Conversion::TimestampWithFormat { format } => {
+                parse_timestamp_with_format(raw, format).map(ConvertedValue::Timestamp)
+            }
+            Conversion::Duration => parse_duration(raw).map(ConvertedValue::Duration),
+        };
+
+        converted.ok_or_else(|| {
+            Diagnostic::Error(Error::Validation(ValidationError {
+                field_path: field_path.to_string(),
+                message: format!("'{}' is not a valid {}", raw, self.describe()),
+                span: Some(value.span().clone()),
+                source: Some(value.source().clone()),
+                suggestions: vec![],
+            }))
+        })
+    }
+
+    /// A short, human-readable name for this conversion's target type, used
+    /// in validation error messages.
+    fn describe(&self) -> &'static str {
+        match self {
+            Conversion::Bytes => "byte string",
+            Conversion::String => "string",
+            Conversion::Integer => "integer",
+            Conversion::Float => "float",
+            Conversion::Boolean => "boolean",
+            Conversion::Timestamp => "RFC 3339 timestamp",
+            Conversion::TimestampWithFormat { .. } => "timestamp",
+            Conversion::Duration => "duration",
+        }
+    }
+}
+
+/// Parse an RFC 3339 timestamp (`2024-01-15T10:30:00Z`) into Unix seconds.
+fn parse_rfc3339(s: &str) -> Option<i64> {
+    let bytes = s.as_bytes();
+    if bytes.len() < 19 {
+        return None;
+    }
+    let year: i64 = s.get(0..4)?.parse().ok()?;
+    if bytes.get(4) != Some(&b'-') {
+        return None;
+    }
+    let month: u32 = s.get(5..7)?.parse().ok()?;
+    if bytes.get(7) != Some(&b'-') {
+        return None;
+    }
+    let day: u32 = s.get(8..10)?.parse().ok()?;
+    if !matches!(bytes.get(10), Some(b'T') | Some(b't') | Some(b' ')) {
+        return None;
+    }
+    let hour: u32 = s.get(11..13)?.parse().ok()?;
+    if bytes.get(13) != Some(&b':') {
+        return None;
+    }
+    let minute: u32 = s.get(14..16)?.parse().ok()?;
+    if bytes.get(16) != Some(&b':') {
+        return None;
+    }
+    let second: u32 = s.get(17..19)?.parse().ok()?;
+
+    days_from_civil(year, month, day).map(|days| {
+        days * 86_400 + hour as i64 * 3_600 + minute as i64 * 60 + second as i64
+    })
+}
+
+/// Parse a timestamp against a minimal strftime-style `format`, supporting
+/// the `%Y`, `%m`, `%d`, `%H`, `%M`, `%S` directives plus literal characters.
+fn parse_timestamp_with_format(s: &str, format: &str) -> Option<i64> {
+    let (mut year, mut month, mut day, mut hour, mut minute, mut second) = (1970i64, 1u32, 1u32, 0u32, 0u32, 0u32);
+
+    let mut s = s;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '%' {
+            let directive = chars.next()?;
+            let width = match directive {
+                'Y' => 4,
+                'm' | 'd' | 'H' | 'M' | 'S' => 2,
+                _ => return None,
+            };
+            if s.len() < width {
+                return None;
+            }
+            let (field, rest) = s.split_at(width);
+            let value: i64 = field.parse().ok()?;
+            match directive {
+                'Y' => year = value,
+                'm' => month = value as u32,
+                'd' => day = value as u32,
+                'H' => hour = value as u32,
+                'M' => minute = value as u32,
+                'S' => second = value as u32,
+                _ => unreachable!(),
+            }
+            s = rest;
+        } else {
+            let mut s_chars = s.chars();
+            if s_chars.next() != Some(c) {
+                return None;
+            }
+            s = s_chars.as_str();
+        }
+    }
+
+    days_from_civil(year, month, day).map(|days| {
+        days * 86_400 + hour as i64 * 3_600 + minute as i64 * 60 + second as i64
+    })
+}
+
+/// Days since the Unix epoch for a given civil (proleptic Gregorian) date.
+/// Based on Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = (y - era * 400) as i64; // [0, 399]
+    let mp = (month as i64 + 9) % 12; // [0, 11], Mar=0 .. Feb=11
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1; // [0, 365]
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy; // [0, 146096]
+    Some(era * 146_097 + doe - 719_468)
+}
+
+/// Parse a human duration string like `30min`/`2h`/`1.5d`, using the same
+/// time-unit suffixes as the Automations DSL's unit literals.
+fn parse_duration(s: &str) -> Option<Duration> {
+    let split_at = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (value, suffix) = s.split_at(split_at);
+    let value: f64 = value.parse().ok()?;
+    let seconds = match suffix {
+        "s" => value,
+        "min" => value * 60.0,
+        "h" => value * 3_600.0,
+        "d" => value * 86_400.0,
+        _ => return None,
+    };
+    if seconds < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceInfo;
+    use std::path::PathBuf;
+
+    fn located(value: &str) -> Located<String> {
+        Located::new(
+            value.to_string(),
+            0..value.len(),
+            SourceInfo {
+                file_path: PathBuf::from("<test>"),
+                content: value.to_string(),
+            },
+        )
+    }
+
+    #[test]
+    fn converts_integer() {
+        let result = Conversion::Integer.apply("count", &located("42")).unwrap();
+        assert_eq!(result, ConvertedValue::Integer(42));
+    }
+
+    #[test]
+    fn converts_boolean() {
+        let result = Conversion::Boolean.apply("enabled", &located("true")).unwrap();
+        assert_eq!(result, ConvertedValue::Boolean(true));
+    }
+
+    #[test]
+    fn rejects_invalid_boolean() {
+        let err = Conversion::Boolean.apply("enabled", &located("yes")).unwrap_err();
+        assert!(matches!(err, Diagnostic::Error(Error::Validation(_))));
+    }
+
+    #[test]
+    fn converts_rfc3339_timestamp() {
+        let result = Conversion::Timestamp
+            .apply("start_time", &located("2024-01-15T10:30:00Z"))
+            .unwrap();
+        assert_eq!(result, ConvertedValue::Timestamp(1_705_314_600));
+    }
+
+    #[test]
+    fn converts_timestamp_with_custom_format() {
+        let conversion = Conversion::TimestampWithFormat {
+            format: "%Y/%m/%d %H:%M:%S".to_string(),
+        };
+        let result = conversion.apply("start_time", &located("2024/01/15 10:30:00")).unwrap();
+        assert_eq!(result, ConvertedValue::Timestamp(1_705_314_600));
+    }
+
+    #[test]
+    fn converts_duration_minutes() {
+        let result = Conversion::Duration.apply("poll_interval", &located("30min")).unwrap();
+        assert_eq!(result, ConvertedValue::Duration(Duration::from_secs(1800)));
+    }
+
+    #[test]
+    fn converts_duration_hours() {
+        let result = Conversion::Duration.apply("timeout", &located("2h")).unwrap();
+        assert_eq!(result, ConvertedValue::Duration(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn rejects_malformed_timestamp_with_field_path_and_span() {
+        let err = Conversion::Timestamp
+            .apply("start_time", &located("not-a-timestamp"))
+            .unwrap_err();
+        match err {
+            Diagnostic::Error(Error::Validation(validation_error)) => {
+                assert_eq!(validation_error.field_path, "start_time");
+                assert_eq!(validation_error.span, Some(0..16));
+            }
+            other => panic!("expected validation error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Builds a config layer from environment variables, so it can be merged on
+//! top of file-derived config with last-wins precedence (see
+//! [`crate::mergeable::PartialMergeableConfig::merge_with_precedence`] and
+//! [`crate::mergeable::MergeableConfig::from_sources`]).
+//!
+//! [`EnvSource`] doesn't need to know anything about a particular
+//! `Partial{Name}` type: it turns each matching environment variable into a
+//! tiny single-field TOML document, which deserializes through the exact
+//! same `toml::Spanned`/nested-struct/`HashMap<K, Struct>` handling a real
+//! config file would, just with a single value set.
+
+use std::path::PathBuf;
+
+use crate::SourceInfo;
+
+/// One environment-variable-derived config layer: a synthetic single-field
+/// TOML document, plus the variable it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvVar {
+    /// The full environment variable name, e.g. `HEARTHD_MQTT__BROKER`.
+    pub name: String,
+    /// A single-assignment TOML document equivalent to what this variable
+    /// sets, e.g. `"[mqtt]\nbroker = \"test.local\"\n"`.
+    pub toml: String,
+}
+
+impl EnvVar {
+    /// A synthetic source like `env:HEARTHD_MQTT__BROKER`, so a diagnostic
+    /// that references an env-derived value still points at where it came
+    /// from, the same way [`crate::Located::with_source`] does for a
+    /// file-derived one.
+    pub fn source(&self) -> SourceInfo {
+        SourceInfo {
+            file_path: PathBuf::from(format!("env:{}", self.name)),
+            content: self.toml.clone(),
+        }
+    }
+}
+
+/// Where the environment-variable layer sits relative to file-derived
+/// config in `load_with_imports_and_env_ordered`'s precedence order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvPrecedence {
+    /// The default: an env var silently overrides whatever value the files
+    /// set, so a deployment can override file-based settings without
+    /// editing TOML.
+    EnvWins,
+    /// The reverse: a file value (if any file sets it) wins over the same
+    /// env var, so a broad environment convention can be overridden by an
+    /// explicit file without unsetting the variable.
+    FilesWin,
+}
+
+/// Scans environment variables for a config layer to merge on top of
+/// file-derived config.
+///
+/// Follows the cargo/config-crate convention: a variable is matched if its
+/// name starts with `{prefix}_`, and the remainder is a `__`-delimited path
+/// of TOML table keys - lowercased, with dashes converted to underscores -
+/// e.g. `HEARTHD_MQTT__BROKER` becomes the document `[mqtt]\nbroker =
+/// "..."`, reaching a nested `SubConfig` field or a `HashMap<K, Struct>` key
+/// at any depth the same way a file's dotted table header would.
+pub struct EnvSource;
+
+impl EnvSource {
+    /// Scan the real process environment.
+    pub fn scan(prefix: &str) -> Vec<EnvVar> {
+        Self::scan_vars(prefix, std::env::vars())
+    }
+
+    /// As [`EnvSource::scan`], but reads from a given iterator of `(name,
+    /// value)` pairs instead of the real environment - used by tests so
+    /// they don't depend on mutating shared process state.
+    pub fn scan_vars<I>(prefix: &str, vars: I) -> Vec<EnvVar>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let prefix = format!("{}_", prefix);
+
+        vars.into_iter()
+            .filter_map(|(name, value)| {
+                let path = name.strip_prefix(&prefix)?;
+                let keys: Vec<String> = path
+                    .split("__")
+                    .map(|segment| segment.to_lowercase().replace('-', "_"))
+                    .collect();
+
+                if keys.iter().any(|key| key.is_empty()) {
+                    return None;
+                }
+
+                let toml = render_toml(&keys, &value);
+                Some(EnvVar { name, toml })
+            })
+            .collect()
+    }
+}
+
+/// Render `keys` (a path into nested TOML tables, the last entry being the
+/// field itself) and `value` as a single-assignment TOML document, e.g.
+/// `(["mqtt", "broker"], "test.local")` becomes `"[mqtt]\nbroker =
+/// \"test.local\"\n"`.
+fn render_toml(keys: &[String], value: &str) -> String {
+    let (table_path, field) = keys.split_at(keys.len() - 1);
+    let field = &field[0];
+
+    let mut doc = String::new();
+    if !table_path.is_empty() {
+        doc.push_str(&format!("[{}]\n", table_path.join(".")));
+    }
+    doc.push_str(&format!("{} = {}\n", field, toml_literal(value)));
+    doc
+}
+
+/// Infer a TOML scalar literal from a raw environment variable string:
+/// `true`/`false` and anything parseable as an integer or float are emitted
+/// bare, everything else is a quoted string.
+fn toml_literal(value: &str) -> String {
+    if value == "true" || value == "false" {
+        return value.to_string();
+    }
+    if value.parse::<i64>().is_ok() || value.parse::<f64>().is_ok() {
+        return value.to_string();
+    }
+
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn scan_vars_ignores_non_matching_prefix() {
+        let found = EnvSource::scan_vars("HEARTHD", vars(&[("OTHER_NAME", "base")]));
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn scan_vars_renders_top_level_field() {
+        let found = EnvSource::scan_vars("HEARTHD", vars(&[("HEARTHD_NAME", "base")]));
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "HEARTHD_NAME");
+        assert_eq!(found[0].toml, "name = \"base\"\n");
+    }
+
+    #[test]
+    fn scan_vars_renders_nested_field() {
+        let found =
+            EnvSource::scan_vars("HEARTHD", vars(&[("HEARTHD_MQTT__BROKER", "test.local")]));
+        assert_eq!(found[0].toml, "[mqtt]\nbroker = \"test.local\"\n");
+    }
+
+    #[test]
+    fn scan_vars_renders_deeply_nested_hashmap_key() {
+        let found = EnvSource::scan_vars(
+            "HEARTHD",
+            vars(&[("HEARTHD_LOCATIONS__HOME__LATITUDE", "59.9139")]),
+        );
+        assert_eq!(found[0].toml, "[locations.home]\nlatitude = 59.9139\n");
+    }
+
+    #[test]
+    fn scan_vars_converts_dashes_to_underscores() {
+        let found = EnvSource::scan_vars("HEARTHD", vars(&[("HEARTHD_MQTT__CLIENT-ID", "abc")]));
+        assert_eq!(found[0].toml, "[mqtt]\nclient_id = \"abc\"\n");
+    }
+
+    #[test]
+    fn scan_vars_infers_bool_and_integer_types() {
+        let found = EnvSource::scan_vars(
+            "HEARTHD",
+            vars(&[
+                ("HEARTHD_HTTP__PORT", "8565"),
+                ("HEARTHD_MQTT__ENABLED", "true"),
+            ]),
+        );
+        let port = found
+            .iter()
+            .find(|v| v.name == "HEARTHD_HTTP__PORT")
+            .unwrap();
+        assert_eq!(port.toml, "[http]\nport = 8565\n");
+        let enabled = found
+            .iter()
+            .find(|v| v.name == "HEARTHD_MQTT__ENABLED")
+            .unwrap();
+        assert_eq!(enabled.toml, "[mqtt]\nenabled = true\n");
+    }
+
+    #[test]
+    fn scan_vars_escapes_quotes_in_string_values() {
+        let found =
+            EnvSource::scan_vars("HEARTHD", vars(&[("HEARTHD_NAME", "a \"quoted\" value")]));
+        assert_eq!(found[0].toml, "name = \"a \\\"quoted\\\" value\"\n");
+    }
+
+    #[test]
+    fn source_uses_env_prefixed_file_path() {
+        let var = EnvVar {
+            name: "HEARTHD_MQTT__BROKER".to_string(),
+            toml: "[mqtt]\nbroker = \"test.local\"\n".to_string(),
+        };
+        let source = var.source();
+        assert_eq!(source.file_path, PathBuf::from("env:HEARTHD_MQTT__BROKER"));
+        assert_eq!(source.content, var.toml);
+    }
+}
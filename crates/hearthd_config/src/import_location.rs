@@ -0,0 +1,276 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+/// Where an `imports` entry points, sniffed from the raw string written in a
+/// config file's `imports` list: an `env:VAR_NAME` prefix is [`Env`](ImportLocation::Env),
+/// an `http://`/`https://` scheme is [`Remote`](ImportLocation::Remote), and
+/// anything else is a [`Local`](ImportLocation::Local) filesystem path.
+///
+/// Threading this (rather than a bare `PathBuf`) through import resolution is
+/// what lets [`may_import`](Self::may_import) enforce that a config loaded
+/// from a `Remote` URL can only pull in further `Remote` imports - never a
+/// `Local` file or `Env` var, which could leak a local secret to whatever
+/// server served the remote config.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ImportLocation {
+    Local(PathBuf),
+    Remote(String),
+    Env(String),
+}
+
+impl ImportLocation {
+    /// Parse a raw `imports` entry into a location, relative to `self` (the
+    /// config that listed it). A relative `Local` import resolves against
+    /// `self`'s parent directory if `self` is itself `Local`; a relative
+    /// `Remote` import resolves against `self`'s URL if `self` is `Remote`.
+    /// `env:` and absolute `http(s)://`/path imports ignore `self` entirely,
+    /// since they're already fully addressed.
+    pub fn resolve(&self, raw: &str) -> ImportLocation {
+        if let Some(var_name) = raw.strip_prefix("env:") {
+            return ImportLocation::Env(var_name.to_string());
+        }
+
+        if raw.starts_with("http://") || raw.starts_with("https://") {
+            return ImportLocation::Remote(raw.to_string());
+        }
+
+        match self {
+            ImportLocation::Remote(base) => ImportLocation::Remote(join_remote(base, raw)),
+            ImportLocation::Local(_) | ImportLocation::Env(_) => {
+                let raw_path = Path::new(raw);
+                if raw_path.is_absolute() {
+                    ImportLocation::Local(raw_path.to_path_buf())
+                } else if let ImportLocation::Local(base) = self {
+                    let parent_dir = base.parent().unwrap_or_else(|| Path::new("."));
+                    ImportLocation::Local(parent_dir.join(raw_path))
+                } else {
+                    // `self` is an `Env` var - there's no parent directory to
+                    // resolve a relative path against, so treat it as
+                    // relative to the process's working directory.
+                    ImportLocation::Local(raw_path.to_path_buf())
+                }
+            }
+        }
+    }
+
+    /// Resolve `raw` against `self` into every location it expands to.
+    /// Most entries resolve to exactly one location, same as [`resolve`](Self::resolve);
+    /// but a `Local` entry that's a glob pattern (`conf.d/*.toml`) or names
+    /// an existing directory (treated as `<dir>/*.toml`, one level deep)
+    /// expands to every matching file, sorted lexicographically so merge
+    /// order - and with it, conflict and override outcomes - stays
+    /// deterministic and reproducible regardless of directory listing
+    /// order. Mirrors Mercurial's `%include` directive, which is far more
+    /// ergonomic for "drop a file in conf.d/" setups than listing every
+    /// file by name.
+    ///
+    /// A glob or directory that currently matches nothing expands to an
+    /// empty list rather than an error - a `conf.d/` with no overrides yet
+    /// is a normal, unremarkable state. A literal path that doesn't exist
+    /// still resolves to itself, exactly like `resolve`, so attempting to
+    /// load it surfaces a `LoadError::Io` as today.
+    pub fn resolve_all(&self, raw: &str) -> Vec<ImportLocation> {
+        let resolved = self.resolve(raw);
+
+        let ImportLocation::Local(path) = &resolved else {
+            return vec![resolved];
+        };
+
+        if path.is_dir() {
+            return glob_sorted(&path.join("*.toml"));
+        }
+
+        if is_glob_pattern(raw) {
+            return glob_sorted(path);
+        }
+
+        vec![resolved]
+    }
+
+    /// Whether `self` is allowed to import `other`. `Remote` locations may
+    /// only import other `Remote` locations; `Local` and `Env` locations may
+    /// import anything. See the type-level doc comment for why.
+    pub fn may_import(&self, other: &ImportLocation) -> bool {
+        match self {
+            ImportLocation::Remote(_) => matches!(other, ImportLocation::Remote(_)),
+            ImportLocation::Local(_) | ImportLocation::Env(_) => true,
+        }
+    }
+
+    /// A canonical form of this location, used to key cycle detection so two
+    /// spellings of the same import (`./foo.toml` vs `foo.toml`) are
+    /// recognized as the same file. `Local` paths are canonicalized the same
+    /// way `load_recursive` always has; `Remote` and `Env` locations are
+    /// already fully addressed, so they're returned unchanged.
+    pub fn normalize(&self) -> ImportLocation {
+        match self {
+            ImportLocation::Local(path) => {
+                ImportLocation::Local(path.canonicalize().unwrap_or_else(|_| path.clone()))
+            }
+            ImportLocation::Remote(_) | ImportLocation::Env(_) => self.clone(),
+        }
+    }
+
+    /// A label for this location suitable as a `SourceInfo::file_path` or a
+    /// `LoadError`'s `path` field: the real path for `Local`, and the URL or
+    /// `env:VAR_NAME` form for `Remote`/`Env`, since neither has a real path
+    /// to point diagnostics at.
+    pub fn label(&self) -> PathBuf {
+        match self {
+            ImportLocation::Local(path) => path.clone(),
+            ImportLocation::Remote(url) => PathBuf::from(url),
+            ImportLocation::Env(var_name) => PathBuf::from(format!("env:{}", var_name)),
+        }
+    }
+}
+
+/// Whether `raw` (the spelling actually written in `imports`, before
+/// `resolve` joins it against a base) contains a glob metacharacter.
+/// Checked against `raw` rather than the resolved path so a base
+/// directory that itself happens to contain `*`/`?`/`[` doesn't turn a
+/// literal import into an accidental glob.
+fn is_glob_pattern(raw: &str) -> bool {
+    raw.contains(['*', '?', '['])
+}
+
+/// Expand `pattern` (an absolute path, possibly containing glob
+/// metacharacters) into every matching file, sorted lexicographically. A
+/// pattern that isn't valid UTF-8, doesn't parse as a glob, or matches
+/// nothing expands to an empty list rather than erroring - see
+/// `resolve_all`.
+fn glob_sorted(pattern: &Path) -> Vec<ImportLocation> {
+    let Some(pattern) = pattern.to_str() else {
+        return Vec::new();
+    };
+
+    let mut matches: Vec<PathBuf> = glob::glob(pattern)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .collect();
+    matches.sort();
+
+    matches.into_iter().map(ImportLocation::Local).collect()
+}
+
+/// Resolve `raw` against `base`, both URLs, the way a browser resolves a
+/// relative link: a scheme-relative `raw` (already absolute, checked by the
+/// caller before reaching here) is returned as-is, otherwise `raw` replaces
+/// everything after the last `/` in `base`'s path.
+fn join_remote(base: &str, raw: &str) -> String {
+    let scheme_end = base.find("://").map(|i| i + 3).unwrap_or(0);
+    match base[scheme_end..].rfind('/') {
+        Some(idx) => format!("{}{}", &base[..scheme_end + idx + 1], raw),
+        None => format!("{}/{}", base.trim_end_matches('/'), raw),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_relative_local_import_against_parent_dir() {
+        let base = ImportLocation::Local(PathBuf::from("/etc/hearthd/config.toml"));
+        assert_eq!(
+            base.resolve("mqtt.toml"),
+            ImportLocation::Local(PathBuf::from("/etc/hearthd/mqtt.toml"))
+        );
+    }
+
+    #[test]
+    fn resolve_relative_remote_import_against_parent_url() {
+        let base = ImportLocation::Remote("https://example.com/configs/base.toml".to_string());
+        assert_eq!(
+            base.resolve("mqtt.toml"),
+            ImportLocation::Remote("https://example.com/configs/mqtt.toml".to_string())
+        );
+    }
+
+    #[test]
+    fn resolve_env_prefixed_import_ignores_base() {
+        let base = ImportLocation::Local(PathBuf::from("/etc/hearthd/config.toml"));
+        assert_eq!(
+            base.resolve("env:HEARTHD_SECRETS"),
+            ImportLocation::Env("HEARTHD_SECRETS".to_string())
+        );
+    }
+
+    #[test]
+    fn remote_may_only_import_remote() {
+        let remote = ImportLocation::Remote("https://example.com/base.toml".to_string());
+        assert!(remote.may_import(&ImportLocation::Remote(
+            "https://example.com/b.toml".to_string()
+        )));
+        assert!(!remote.may_import(&ImportLocation::Local(PathBuf::from("/etc/secret.toml"))));
+        assert!(!remote.may_import(&ImportLocation::Env("SECRET".to_string())));
+    }
+
+    #[test]
+    fn local_may_import_anything() {
+        let local = ImportLocation::Local(PathBuf::from("/etc/hearthd/config.toml"));
+        assert!(local.may_import(&ImportLocation::Remote(
+            "https://example.com/b.toml".to_string()
+        )));
+        assert!(local.may_import(&ImportLocation::Env("SECRET".to_string())));
+        assert!(local.may_import(&ImportLocation::Local(PathBuf::from("/etc/other.toml"))));
+    }
+
+    #[test]
+    fn resolve_all_expands_a_glob_to_sorted_matches() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("b.toml"), "").unwrap();
+        std::fs::write(temp_dir.path().join("a.toml"), "").unwrap();
+        std::fs::write(temp_dir.path().join("c.txt"), "").unwrap();
+
+        let base = ImportLocation::Local(temp_dir.path().join("base.toml"));
+        let expanded = base.resolve_all("*.toml");
+
+        assert_eq!(
+            expanded,
+            vec![
+                ImportLocation::Local(temp_dir.path().join("a.toml")),
+                ImportLocation::Local(temp_dir.path().join("b.toml")),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_all_expands_a_directory_to_its_sorted_toml_children() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let conf_d = temp_dir.path().join("conf.d");
+        std::fs::create_dir(&conf_d).unwrap();
+        std::fs::write(conf_d.join("20-prod.toml"), "").unwrap();
+        std::fs::write(conf_d.join("10-base.toml"), "").unwrap();
+
+        let base = ImportLocation::Local(temp_dir.path().join("base.toml"));
+        let expanded = base.resolve_all("conf.d");
+
+        assert_eq!(
+            expanded,
+            vec![
+                ImportLocation::Local(conf_d.join("10-base.toml")),
+                ImportLocation::Local(conf_d.join("20-prod.toml")),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_all_of_an_unmatched_glob_is_an_empty_list_not_an_error() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let base = ImportLocation::Local(temp_dir.path().join("base.toml"));
+        assert_eq!(base.resolve_all("conf.d/*.toml"), Vec::new());
+    }
+
+    #[test]
+    fn resolve_all_of_a_literal_missing_path_resolves_to_itself() {
+        let base = ImportLocation::Local(PathBuf::from("/etc/hearthd/config.toml"));
+        assert_eq!(
+            base.resolve_all("missing.toml"),
+            vec![ImportLocation::Local(PathBuf::from(
+                "/etc/hearthd/missing.toml"
+            ))]
+        );
+    }
+}
@@ -1,17 +1,55 @@
+mod cli_override;
+mod conversion;
 mod diagnostics;
+mod env;
+mod import_location;
 mod located;
+mod provenance;
+mod relative_path;
+mod secret;
 
+// Re-export the command-line `--set` override layer
+pub use cli_override::parse_override;
+pub use cli_override::CliOverride;
+// Re-export the value-conversion facility
+pub use conversion::Conversion;
+pub use conversion::ConvertedValue;
+// Re-export the environment-variable config layer
+pub use env::EnvPrecedence;
+pub use env::EnvSource;
+pub use env::EnvVar;
+// Re-export the `imports` location type
+pub use import_location::ImportLocation;
+// Re-export per-field provenance types
+pub use provenance::join_path;
+pub use provenance::FieldProvenance;
+pub use provenance::ProvenanceMap;
+// Re-export the config-file-relative path type
+pub use relative_path::ConfigRelativePath;
+// Re-export the redacted-secret field type
+pub use secret::Secret;
+pub use secret::SecretResolveError;
 // Re-export diagnostic types
+pub use diagnostics::format_diagnostics;
+pub use diagnostics::format_diagnostics_json;
 pub use diagnostics::Diagnostic;
+pub use diagnostics::DiagnosticCode;
 pub use diagnostics::Diagnostics;
 pub use diagnostics::Error;
+pub use diagnostics::JsonDiagnostic;
+pub use diagnostics::JsonLocation;
+pub use diagnostics::JsonSeverity;
 pub use diagnostics::LoadError;
 pub use diagnostics::MergeConflictLocation;
 pub use diagnostics::MergeError;
+pub use diagnostics::OutputFormat;
+pub use diagnostics::ParseError;
+pub use diagnostics::RelatedLabel;
+pub use diagnostics::Severity;
+pub use diagnostics::SeverityOverrides;
 pub use diagnostics::SourceInfo;
 pub use diagnostics::ValidationError;
 pub use diagnostics::Warning;
-pub use diagnostics::format_diagnostics;
 pub use hearthd_config_derive::MergeableConfig;
 pub use hearthd_config_derive::SubConfig;
 pub use located::Located;
@@ -79,6 +79,13 @@ impl<T> Located<T> {
             content: self.source.content.clone(),
         }
     }
+
+    /// Convert this Located value into a secondary `RelatedLabel`, for
+    /// attaching a related location (e.g. "first set here") to a
+    /// `MergeError` via `MergeError::with_related`.
+    pub fn to_related_label(&self, label: impl Into<String>) -> crate::RelatedLabel {
+        crate::RelatedLabel::new(self.to_conflict_location(), label)
+    }
 }
 
 impl<T> Deref for Located<T> {
@@ -8,6 +8,36 @@ use crate::LoadError;
 use crate::TryFromPartial;
 use crate::Validate;
 
+/// Which semantics a set of loaded configs should be merged with.
+///
+/// `merge` and `merge_with_precedence` are always both available on a
+/// generated `Partial{TypeName}`; this enum exists for callers that pick the
+/// policy dynamically (e.g. from a CLI flag) rather than calling the method
+/// they want directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Every config is a peer: two files setting the same field is a
+    /// `Diagnostic::Error(Error::Merge)` conflict. See
+    /// [`PartialMergeableConfig::merge`].
+    Strict,
+    /// Configs form an ordered precedence stack - e.g. a committed base
+    /// config, then machine-local overrides, then environment overrides -
+    /// and a later config silently overrides a field an earlier one set.
+    /// See [`PartialMergeableConfig::merge_with_precedence`].
+    LastWins,
+}
+
+/// Lets a partial config be stamped with a source that didn't come from
+/// loading a file - e.g. the synthetic `env:HEARTHD_MQTT__BROKER` source an
+/// [`crate::EnvSource`]-derived layer carries, so diagnostics that reference
+/// it still point at where the value came from.
+///
+/// This is the `PartialMergeableConfig` equivalent of [`crate::Located::with_source`].
+pub trait WithSource: Sized {
+    /// Attach `source` to this partial config, replacing any existing one.
+    fn with_source(self, source: crate::SourceInfo) -> Self;
+}
+
 /// Trait for partial configuration structs that can be loaded and merged.
 ///
 /// This trait is automatically implemented by the `MergeableConfig` derive macro
@@ -23,11 +53,39 @@ pub trait PartialMergeableConfig: Sized {
     /// Load multiple TOML files with recursive import resolution.
     ///
     /// Files are loaded in the order specified, with imports processed recursively.
-    /// Import cycles are detected and reported as errors. Relative import paths
-    /// are resolved relative to the file containing the `imports` field.
+    /// An `imports` entry may be a local path, an `http(s)://` URL, or an
+    /// `env:VAR_NAME` reference - see [`crate::ImportLocation`]. Relative
+    /// imports are resolved relative to the location that listed them (a
+    /// parent directory for a local import, a parent URL for a remote one).
+    /// A file loaded from a remote URL may only import further remote URLs,
+    /// never a local path or env var - see [`crate::ImportLocation::may_import`].
+    ///
+    /// This is partial-tolerant: an IO error, a TOML parse error, an
+    /// import cycle, or a sandboxed import on one file is recorded as a
+    /// `Diagnostic::Error(Error::Load)` rather than aborting the whole load,
+    /// so a single malformed file (or import) doesn't discard the files
+    /// that did parse successfully.
     ///
-    /// Returns a vector of all loaded partial configs (including imported files).
-    fn load_with_imports(paths: &[PathBuf]) -> Result<Vec<Self>, LoadError>;
+    /// Returns the partial configs that loaded successfully (including
+    /// imported files) alongside any per-file load diagnostics.
+    fn load_with_imports(paths: &[PathBuf]) -> (Vec<Self>, Vec<Diagnostic>);
+
+    /// The file this partial config was loaded from, if it was loaded via
+    /// `from_file`/`load_with_imports` rather than constructed some other
+    /// way (e.g. an [`crate::EnvSource`] layer, which has no backing file).
+    fn source_info(&self) -> Option<&crate::SourceInfo>;
+
+    /// Walk this partial config, recording every field it actually sets
+    /// into `out` as `(dotted_path, span, debug value)`, with paths
+    /// prefixed by `prefix` (pass `""` at the root). Nested structs and
+    /// struct-valued maps recurse with an extended prefix; per
+    /// [`crate::join_path`].
+    ///
+    /// Called once per loaded file (i.e. before `merge`/`merge_with_precedence`
+    /// combine them), since that's the last point a field's value and its
+    /// source file are both still attached to the same struct - see
+    /// [`MergeableConfig::resolve_with_provenance`].
+    fn describe_into(&self, prefix: &str, out: &mut Vec<(String, std::ops::Range<usize>, String)>);
 
     /// Merge multiple partial configurations into a single partial config.
     ///
@@ -37,11 +95,63 @@ pub trait PartialMergeableConfig: Sized {
     /// - `HashMap<K, Struct>`: Structs with same key are merged field-by-field recursively
     /// - Nested structs: Merged recursively
     ///
+    /// Files are merged in order, and each file's `unset` directives (see the
+    /// generated `PartialConfig::apply_unset`) are applied before its own
+    /// values are merged in. This lets a later file clear a value an earlier
+    /// one set - Mercurial-style `%unset` - without the clear itself being
+    /// treated as a conflict; a later set in the same or a subsequent file
+    /// can still override the clear.
+    ///
     /// Returns the merged partial config and a vector of diagnostics (warnings and errors).
     /// Empty config files generate warnings.
     fn merge<I>(configs: I) -> (Self, Vec<Diagnostic>)
     where
         I: IntoIterator<Item = Self>;
+
+    /// Merge multiple partial configurations with last-wins precedence.
+    ///
+    /// Unlike `merge`, `configs` is treated as an ordered precedence stack
+    /// rather than a set of peers: for each field, the last config in the
+    /// iterator that sets it wins, silently - no `Diagnostic::Error(Merge)`
+    /// is ever produced. `%unset` directives are still honored in order.
+    ///
+    /// Use this for the common pattern of a committed base config plus
+    /// machine-local and environment override files, where a later layer
+    /// is *expected* to replace values from an earlier one rather than
+    /// conflict with them.
+    fn merge_with_precedence<I>(configs: I) -> Self
+    where
+        I: IntoIterator<Item = Self>;
+
+    /// Merge multiple partial configurations as ordered layers, like
+    /// `merge_with_precedence`, but cap deep-merging of nested structs and
+    /// `HashMap<K, Struct>` entries at `max_depth` levels.
+    ///
+    /// Beyond `max_depth`, a higher-precedence layer's table replaces a
+    /// lower one's wholesale instead of being merged field-by-field. This
+    /// mirrors how editors merge a base/global config with a local one:
+    /// pass `max_depth = 0` so a local override that sets `[database]`
+    /// replaces the whole table rather than splicing individual fields
+    /// into whatever the base config already set.
+    fn merge_layered<I>(layers: I, max_depth: usize) -> Self
+    where
+        I: IntoIterator<Item = Self>;
+
+    /// Merge multiple partial configurations using the given [`MergePolicy`].
+    ///
+    /// A convenience for callers that choose the policy dynamically (e.g.
+    /// from a CLI flag) rather than calling `merge` or
+    /// `merge_with_precedence` directly. `MergePolicy::LastWins` never
+    /// produces diagnostics, matching `merge_with_precedence`.
+    fn merge_with_policy<I>(configs: I, policy: MergePolicy) -> (Self, Vec<Diagnostic>)
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        match policy {
+            MergePolicy::Strict => Self::merge(configs),
+            MergePolicy::LastWins => (Self::merge_with_precedence(configs), Vec::new()),
+        }
+    }
 }
 
 /// Trait for root configuration structs that can be loaded from files.
@@ -104,13 +214,16 @@ where
     /// }
     /// ```
     fn from_files(paths: &[PathBuf]) -> Result<(Self, Diagnostics), Diagnostics> {
-        // Step 1: Load files with import resolution
-        let configs = <Self::PartialConfig as PartialMergeableConfig>::load_with_imports(paths)
-            .map_err(|e| Diagnostics(vec![Diagnostic::Error(crate::Error::Load(e))]))?;
+        // Step 1: Load files with import resolution. Per-file load failures
+        // become diagnostics rather than aborting here, so a malformed file
+        // doesn't hide merge/validation problems in the files that did load.
+        let (configs, mut diagnostics) =
+            <Self::PartialConfig as PartialMergeableConfig>::load_with_imports(paths);
 
         // Step 2: Merge partial configs
-        let (partial, mut diagnostics) =
+        let (partial, merge_diagnostics) =
             <Self::PartialConfig as PartialMergeableConfig>::merge(configs);
+        diagnostics.extend(merge_diagnostics);
 
         // Step 3: Convert from partial to final config
         let config = match Self::try_from_partial(partial) {
@@ -132,4 +245,116 @@ where
             Ok((config, Diagnostics(diagnostics)))
         }
     }
+
+    /// Load configuration from TOML files, then layer environment variables
+    /// matching `env_prefix` on top (see [`crate::EnvSource`]).
+    ///
+    /// This is `from_files` plus one extra layering step between merging and
+    /// validation: the files are still merged with `PartialMergeableConfig::merge`,
+    /// so conflicts between files are reported exactly as they are by
+    /// `from_files`. The environment layer is then merged in with
+    /// `merge_with_precedence` instead, so an env var silently overrides a
+    /// value the files set rather than conflicting with it - env is always
+    /// the highest-precedence layer, following the cargo/config-crate
+    /// convention `env_prefix` names (e.g. `HEARTHD_MQTT__BROKER`).
+    fn from_sources(paths: &[PathBuf], env_prefix: &str) -> Result<(Self, Diagnostics), Diagnostics>
+    where
+        Self::PartialConfig: WithSource + serde::de::DeserializeOwned,
+    {
+        // Step 1: Load files with import resolution, tolerating per-file failures
+        let (configs, mut diagnostics) =
+            <Self::PartialConfig as PartialMergeableConfig>::load_with_imports(paths);
+
+        // Step 2: Merge files strictly, same as `from_files`
+        let (file_partial, merge_diagnostics) =
+            <Self::PartialConfig as PartialMergeableConfig>::merge(configs);
+        diagnostics.extend(merge_diagnostics);
+
+        // Step 3: Build one partial per matching environment variable and
+        // layer them on top with last-wins precedence, so env never
+        // conflicts with a file-set value.
+        let env_partials = crate::EnvSource::scan(env_prefix)
+            .into_iter()
+            .filter_map(|var| {
+                let source = var.source();
+                toml::from_str::<Self::PartialConfig>(&var.toml)
+                    .ok()
+                    .map(|partial| partial.with_source(source))
+            });
+        let partial = <Self::PartialConfig as PartialMergeableConfig>::merge_with_precedence(
+            std::iter::once(file_partial).chain(env_partials),
+        );
+
+        // Step 4: Convert from partial to final config
+        let config = match Self::try_from_partial(partial) {
+            Ok(cfg) => cfg,
+            Err(errs) => {
+                diagnostics.extend(errs);
+                Self::default() // Error recovery: use default
+            }
+        };
+
+        // Step 5: Validate cross-field constraints
+        diagnostics.extend(config.validate());
+
+        // Step 6: Return result based on error status
+        let has_errors = diagnostics.iter().any(|d| d.is_error());
+        if has_errors {
+            Err(Diagnostics(diagnostics))
+        } else {
+            Ok((config, Diagnostics(diagnostics)))
+        }
+    }
+
+    /// Load and merge configuration from `paths`, same as `from_files`, but
+    /// also return a [`crate::ProvenanceMap`] recording which file set the
+    /// final value for every field - the `cargo config get` equivalent for
+    /// `hearthd` configs, meant to back a `hearthd config dump` command.
+    ///
+    /// Per-field source is only available per loaded file, before merging
+    /// conflates them into one partial config, so this walks each file's
+    /// partial config with `PartialMergeableConfig::describe_into` first
+    /// and keeps the highest-layer (last-loaded) entry for each dotted
+    /// field path, matching `merge_with_precedence`'s last-wins semantics.
+    fn resolve_with_provenance(paths: &[PathBuf]) -> (Self, crate::ProvenanceMap, Diagnostics) {
+        let (configs, mut diagnostics) =
+            <Self::PartialConfig as PartialMergeableConfig>::load_with_imports(paths);
+
+        let mut provenance = crate::ProvenanceMap::default();
+        for (layer, config) in configs.iter().enumerate() {
+            let source_file = config
+                .source_info()
+                .map(|s| s.file_path.clone())
+                .unwrap_or_default();
+
+            let mut fields = Vec::new();
+            config.describe_into("", &mut fields);
+            for (path, span, value) in fields {
+                provenance.record(
+                    path,
+                    crate::FieldProvenance {
+                        value,
+                        source_file: source_file.clone(),
+                        span,
+                        layer,
+                    },
+                );
+            }
+        }
+
+        let (partial, merge_diagnostics) =
+            <Self::PartialConfig as PartialMergeableConfig>::merge(configs);
+        diagnostics.extend(merge_diagnostics);
+
+        let config = match Self::try_from_partial(partial) {
+            Ok(cfg) => cfg,
+            Err(errs) => {
+                diagnostics.extend(errs);
+                Self::default() // Error recovery: use default
+            }
+        };
+        diagnostics.extend(config.validate());
+
+        (config, provenance, Diagnostics(diagnostics))
+    }
 }
@@ -0,0 +1,76 @@
+//! Per-field provenance for a resolved config: which file (and layer) set
+//! the final value for each dotted field path, e.g. `mqtt.broker` or
+//! `mqtt.devices.kitchen.name`. This is the `cargo config get` equivalent
+//! for `hearthd` configs, backing [`crate::MergeableConfig::resolve_with_provenance`]
+//! and a future `hearthd config dump` command.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Where a single resolved field's value came from.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldProvenance {
+    /// The field's final value, rendered with `{:?}` (`Debug`) - fields can
+    /// be of any type the caller's config struct defines, so this is the
+    /// only representation available without per-field `Display` bounds.
+    pub value: String,
+    /// The file that supplied this value.
+    pub source_file: PathBuf,
+    /// Byte span of the value within `source_file`'s content, for pointing
+    /// a reader (or an LSP) at the exact line/column.
+    pub span: Range<usize>,
+    /// Index into the layer sequence passed to `resolve_with_provenance`
+    /// (0 = first-loaded file). Lets two files that set the same field be
+    /// resolved "last layer wins", matching `merge_with_precedence`.
+    pub layer: usize,
+}
+
+/// Dotted field path to the [`FieldProvenance`] that ultimately set it,
+/// across every layer passed to `resolve_with_provenance`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProvenanceMap(pub BTreeMap<String, FieldProvenance>);
+
+impl ProvenanceMap {
+    /// Record `provenance` for `path`, keeping whichever of the new value
+    /// and any existing entry has the higher `layer`, so layers can be
+    /// folded in over any order and still land on a last-wins result.
+    pub fn record(&mut self, path: String, provenance: FieldProvenance) {
+        match self.0.get(&path) {
+            Some(existing) if existing.layer > provenance.layer => {}
+            _ => {
+                self.0.insert(path, provenance);
+            }
+        }
+    }
+
+    /// Look up the provenance for a single dotted field path.
+    pub fn get(&self, path: &str) -> Option<&FieldProvenance> {
+        self.0.get(path)
+    }
+
+    /// Serialize as pretty-printed TOML keyed by dotted field path - the
+    /// default format for a `hearthd config dump`.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(&self.0)
+    }
+
+    /// Serialize as pretty-printed JSON, for external tooling (an LSP, a
+    /// validator) that would rather consume JSON than parse TOML.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.0)
+    }
+}
+
+/// Join a dotted-path `prefix` (possibly empty, at the root) with a single
+/// `field` segment. Used by the generated `describe_into` methods to build
+/// up paths like `mqtt.devices.kitchen.name` as they recurse.
+pub fn join_path(prefix: &str, field: &str) -> String {
+    if prefix.is_empty() {
+        field.to_string()
+    } else {
+        format!("{prefix}.{field}")
+    }
+}
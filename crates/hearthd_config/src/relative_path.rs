@@ -0,0 +1,60 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+/// A path-valued config field that resolves relative to the directory of
+/// the TOML file that actually set it, not the process CWD or the root
+/// config's directory.
+///
+/// Mirrors cargo's config-relative paths: a relative `ca_cert =
+/// "certs/ca.pem"` set in an imported `mqtt.toml` resolves next to
+/// `mqtt.toml`, regardless of where the root config or `load_with_imports`'s
+/// caller live. The base directory is attached once per loaded file by the
+/// derive macro's generated `attach_base_dir`, alongside `SourceInfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigRelativePath {
+    raw: String,
+    base_dir: Option<PathBuf>,
+}
+
+impl ConfigRelativePath {
+    /// Record the directory `raw` should be resolved against if it turns
+    /// out to be relative. Called once per loaded file by the generated
+    /// `attach_base_dir`; left unset for a value constructed directly
+    /// rather than loaded from a file.
+    pub fn set_base_dir(&mut self, base_dir: &Path) {
+        self.base_dir = Some(base_dir.to_path_buf());
+    }
+
+    /// Resolve to the path this field actually refers to: unchanged if
+    /// `raw` is absolute, otherwise joined onto the defining file's
+    /// directory (or left as-is, i.e. CWD-relative, if no base directory
+    /// was ever attached).
+    pub fn resolve(&self) -> PathBuf {
+        let path = PathBuf::from(&self.raw);
+        if path.is_absolute() {
+            return path;
+        }
+        match &self.base_dir {
+            Some(dir) => dir.join(path),
+            None => path,
+        }
+    }
+}
+
+/// Custom deserialize so config files write a plain string
+/// (`ca_cert = "certs/ca.pem"`), not a table. The base directory isn't known
+/// yet at this point - see `set_base_dir`.
+impl<'de> Deserialize<'de> for ConfigRelativePath {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        Ok(ConfigRelativePath {
+            raw,
+            base_dir: None,
+        })
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::Diagnostic;
+use crate::Error;
+use crate::SourceInfo;
+use crate::ValidationError;
+
+/// A sensitive config value that's never written to version control as
+/// plaintext: either an inline value (for local/dev use), an environment
+/// variable indirection (`"${env:MQTT_PASSWORD}"`), or a secret file
+/// (`{ file = "/run/secrets/mqtt_pw" }`), resolved during loading.
+///
+/// `Debug` always redacts the value - including the inline form - so a
+/// `Secret` never leaks into logs or the provenance dump by accident.
+#[derive(Clone)]
+pub enum Secret {
+    Inline(String),
+    EnvVar(String),
+    File(PathBuf),
+}
+
+impl Secret {
+    /// Resolve to the underlying plaintext value: look up the environment
+    /// variable or read the secret file, or return the inline value as-is.
+    pub fn resolve(&self) -> Result<String, SecretResolveError> {
+        match self {
+            Secret::Inline(value) => Ok(value.clone()),
+            Secret::EnvVar(var) => {
+                std::env::var(var).map_err(|_| SecretResolveError::MissingEnvVar(var.clone()))
+            }
+            Secret::File(path) => std::fs::read_to_string(path)
+                .map(|contents| contents.trim_end_matches(['\n', '\r']).to_string())
+                .map_err(|e| SecretResolveError::UnreadableFile {
+                    path: path.clone(),
+                    error: e.to_string(),
+                }),
+        }
+    }
+
+    /// Resolve, or turn a resolution failure into a `Diagnostic::Error`
+    /// carrying `field_path`/`span`/`source` - for use from a `TryFromPartial`
+    /// impl, which is the only place a `Secret` field's span and source file
+    /// are both still in scope.
+    pub fn resolve_or_diagnostic(
+        &self,
+        field_path: &str,
+        span: std::ops::Range<usize>,
+        source: Option<&SourceInfo>,
+    ) -> Result<String, Diagnostic> {
+        self.resolve().map_err(|e| {
+            Diagnostic::Error(Error::Validation(ValidationError {
+                field_path: field_path.to_string(),
+                message: e.to_string(),
+                span: Some(span),
+                source: source.cloned(),
+                suggestions: vec![],
+            }))
+        })
+    }
+}
+
+/// Why a `Secret` could not be resolved to its plaintext value.
+#[derive(Debug, Clone)]
+pub enum SecretResolveError {
+    MissingEnvVar(String),
+    UnreadableFile { path: PathBuf, error: String },
+}
+
+impl std::fmt::Display for SecretResolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretResolveError::MissingEnvVar(var) => {
+                write!(f, "environment variable `{var}` is not set")
+            }
+            SecretResolveError::UnreadableFile { path, error } => {
+                write!(f, "could not read secret file `{}`: {error}", path.display())
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+/// Accepts either a plain string (an inline value, or `"${env:VAR}"`) or a
+/// table (`{ file = "..." }`) - config authors write whichever indirection
+/// fits, same as `password = "${env:MQTT_PASSWORD}"` vs.
+/// `password = { file = "/run/secrets/mqtt_pw" }`.
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            File { file: String },
+            Inline(String),
+        }
+
+        Ok(match Repr::deserialize(deserializer)? {
+            Repr::File { file } => Secret::File(PathBuf::from(file)),
+            Repr::Inline(value) => {
+                match value.strip_prefix("${env:").and_then(|v| v.strip_suffix('}')) {
+                    Some(var) => Secret::EnvVar(var.to_string()),
+                    None => Secret::Inline(value),
+                }
+            }
+        })
+    }
+}
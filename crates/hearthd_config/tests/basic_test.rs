@@ -54,7 +54,9 @@ fn test_basic_merge() {
     )
     .unwrap();
 
-    let configs = PartialSimpleConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialSimpleConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialSimpleConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0);
@@ -99,7 +101,9 @@ fn test_conflict_detection() {
     )
     .unwrap();
 
-    let configs = PartialSimpleConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialSimpleConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (_, diagnostics) = PartialSimpleConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 1);
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::fs;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+    pub feature_flags: HashMap<String, bool>,
+}
+
+#[test]
+fn apply_overrides_sets_a_nested_field() {
+    let (overrides, diagnostics) =
+        PartialAppConfig::apply_overrides(&[("database.port", "6543")]).unwrap();
+
+    assert!(diagnostics.is_empty());
+    let db = overrides.database.unwrap();
+    assert_eq!(db.port.unwrap().into_inner(), 6543);
+}
+
+#[test]
+fn apply_overrides_parses_the_value_as_a_toml_literal() {
+    let (overrides, _) = PartialAppConfig::apply_overrides(&[("name", "\"from-cli\"")]).unwrap();
+
+    assert_eq!(overrides.name.unwrap().into_inner(), "from-cli");
+}
+
+#[test]
+fn apply_overrides_wins_over_a_file_through_merge() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(
+        &config_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    let (configs, _) = PartialAppConfig::load_with_imports(&[config_path]);
+    let (overrides, _) = PartialAppConfig::apply_overrides(&[("database.port", "3306")]).unwrap();
+
+    let (merged, diagnostics) =
+        PartialAppConfig::merge(configs.into_iter().chain(std::iter::once(overrides)));
+
+    assert!(diagnostics.is_empty());
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306, "override wins");
+}
+
+#[test]
+fn apply_overrides_reports_a_conflict_with_a_file_value() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(&config_path, "name = \"base\"\n").unwrap();
+
+    let (configs, _) = PartialAppConfig::load_with_imports(&[config_path.clone()]);
+    let (overrides, _) = PartialAppConfig::apply_overrides(&[("name", "\"from-cli\"")]).unwrap();
+
+    let (_, diagnostics) =
+        PartialAppConfig::merge(configs.into_iter().chain(std::iter::once(overrides)));
+
+    assert_eq!(diagnostics.len(), 1);
+    match &diagnostics[0] {
+        hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(merge_error)) => {
+            assert_eq!(merge_error.conflicts[0].file_path, config_path);
+            assert_eq!(
+                merge_error.conflicts[1].file_path,
+                std::path::PathBuf::from("command-line:name")
+            );
+        }
+        other => panic!("expected a Merge conflict, got {other:?}"),
+    }
+}
+
+#[test]
+fn apply_overrides_rejects_an_invalid_path() {
+    let result = PartialAppConfig::apply_overrides(&[("database..port", "1")]);
+    assert!(matches!(
+        result,
+        Err(hearthd_config::LoadError::Parse { .. })
+    ));
+}
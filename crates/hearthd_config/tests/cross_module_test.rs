@@ -48,7 +48,8 @@ fn test_cross_module_config() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config_path]).unwrap();
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[config_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Expected no diagnostics");
@@ -93,7 +94,9 @@ fn test_cross_module_merge() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Expected no diagnostics");
@@ -138,7 +141,9 @@ fn test_cross_module_conflict_detection() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (_, diagnostics) = PartialAppConfig::merge(configs);
 
     // Should detect a conflict on database.port
@@ -0,0 +1,81 @@
+use std::fs;
+
+use hearthd_config::EnvSource;
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+}
+
+#[test]
+fn env_layer_overrides_file_without_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(
+        &config_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    let (file_configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[config_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (file_partial, diagnostics) = PartialAppConfig::merge(file_configs);
+    assert_eq!(diagnostics.len(), 0);
+
+    // Would be a Diagnostic::Error(Merge) if `database.port` were set by a
+    // second file under `merge`, but env is layered with
+    // `merge_with_precedence` instead, so it silently overrides.
+    let env_vars = EnvSource::scan_vars(
+        "APP",
+        vec![("APP_DATABASE__PORT".to_string(), "3306".to_string())],
+    );
+    let env_partials: Vec<PartialAppConfig> = env_vars
+        .into_iter()
+        .map(|var| {
+            let source = var.source();
+            let mut partial: PartialAppConfig = toml::from_str(&var.toml).unwrap();
+            partial.source = Some(source);
+            partial
+        })
+        .collect();
+
+    let merged =
+        PartialAppConfig::merge_with_precedence(std::iter::once(file_partial).chain(env_partials));
+
+    assert_eq!(merged.name.unwrap().into_inner(), "base");
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306, "env layer wins");
+}
+
+#[test]
+fn env_source_ignores_variables_outside_its_prefix() {
+    let env_vars = EnvSource::scan_vars(
+        "APP",
+        vec![
+            ("OTHER_NAME".to_string(), "ignored".to_string()),
+            ("APP_NAME".to_string(), "from-env".to_string()),
+        ],
+    );
+
+    assert_eq!(env_vars.len(), 1);
+    assert_eq!(env_vars[0].name, "APP_NAME");
+    assert_eq!(env_vars[0].toml, "name = \"from-env\"\n");
+}
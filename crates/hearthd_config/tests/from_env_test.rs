@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fs;
+
+use hearthd_config::EnvPrecedence;
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+    pub feature_flags: HashMap<String, bool>,
+}
+
+#[test]
+fn from_env_builds_a_partial_from_matching_variables() {
+    std::env::set_var("FROMENVTEST_NAME", "from-env");
+    std::env::set_var("FROMENVTEST_DATABASE__PORT", "6543");
+
+    let partial = PartialAppConfig::from_env("FROMENVTEST");
+
+    assert_eq!(partial.name.unwrap().into_inner(), "from-env");
+    let db = partial.database.unwrap();
+    assert_eq!(db.port.unwrap().into_inner(), 6543);
+    assert!(db.host.is_none(), "no FROMENVTEST_DATABASE__HOST was set");
+
+    std::env::remove_var("FROMENVTEST_NAME");
+    std::env::remove_var("FROMENVTEST_DATABASE__PORT");
+}
+
+#[test]
+fn load_with_imports_and_env_layers_env_above_files_without_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(
+        &config_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    std::env::set_var("LOADENVTEST_DATABASE__PORT", "3306");
+
+    let (merged, diagnostics) =
+        PartialAppConfig::load_with_imports_and_env(&[config_path], "LOADENVTEST");
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(merged.name.unwrap().into_inner(), "base");
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306, "env layer wins");
+
+    std::env::remove_var("LOADENVTEST_DATABASE__PORT");
+}
+
+#[test]
+fn from_env_collects_hash_map_entries_under_a_shared_prefix() {
+    std::env::set_var("HASHMAPENVTEST_NAME", "from-env");
+    std::env::set_var("HASHMAPENVTEST_FEATURE_FLAGS__DARK_MODE", "true");
+    std::env::set_var("HASHMAPENVTEST_FEATURE_FLAGS__BETA", "false");
+
+    let partial = PartialAppConfig::from_env("HASHMAPENVTEST");
+
+    let flags = partial.feature_flags.unwrap();
+    assert_eq!(*flags.get("dark_mode").unwrap().get_ref(), true);
+    assert_eq!(*flags.get("beta").unwrap().get_ref(), false);
+
+    std::env::remove_var("HASHMAPENVTEST_NAME");
+    std::env::remove_var("HASHMAPENVTEST_FEATURE_FLAGS__DARK_MODE");
+    std::env::remove_var("HASHMAPENVTEST_FEATURE_FLAGS__BETA");
+}
+
+#[test]
+fn load_with_imports_and_env_ordered_lets_a_file_win_over_env() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(
+        &config_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    std::env::set_var("FILESWINTEST_DATABASE__PORT", "3306");
+
+    let (merged, diagnostics) = PartialAppConfig::load_with_imports_and_env_ordered(
+        &[config_path],
+        "FILESWINTEST",
+        EnvPrecedence::FilesWin,
+    );
+
+    assert!(diagnostics.is_empty());
+    let db = merged.database.unwrap();
+    assert_eq!(
+        db.port.unwrap().into_inner(),
+        5432,
+        "the file wins under FilesWin precedence"
+    );
+
+    std::env::remove_var("FILESWINTEST_DATABASE__PORT");
+}
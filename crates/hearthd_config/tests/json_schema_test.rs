@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub log_file: Option<PathBuf>,
+    #[config(default = 8080)]
+    pub port: u16,
+    pub database: DatabaseConfig,
+    pub locations: HashMap<String, DatabaseConfig>,
+    pub tags: Vec<String>,
+}
+
+#[test]
+fn required_excludes_optional_and_defaulted_fields() {
+    let schema = PartialAppConfig::json_schema();
+    let required: Vec<&str> = schema["required"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v.as_str().unwrap())
+        .collect();
+
+    assert!(required.contains(&"name"));
+    assert!(required.contains(&"database"));
+    assert!(required.contains(&"locations"));
+    assert!(required.contains(&"tags"));
+    assert!(!required.contains(&"log_file"), "Option<T> isn't required");
+    assert!(
+        !required.contains(&"port"),
+        "defaulted fields aren't required"
+    );
+}
+
+#[test]
+fn simple_fields_map_to_the_corresponding_json_types() {
+    let schema = PartialAppConfig::json_schema();
+    let properties = &schema["properties"];
+
+    assert_eq!(properties["name"], serde_json::json!({"type": "string"}));
+    assert_eq!(
+        properties["log_file"],
+        serde_json::json!({"type": "string"})
+    );
+    assert_eq!(properties["port"], serde_json::json!({"type": "integer"}));
+    assert_eq!(
+        properties["tags"],
+        serde_json::json!({"type": "array", "items": {"type": "string"}})
+    );
+}
+
+#[test]
+fn a_nested_sub_config_is_hoisted_into_defs_and_referenced() {
+    let schema = PartialAppConfig::json_schema();
+
+    assert_eq!(
+        schema["properties"]["database"],
+        serde_json::json!({"$ref": "#/$defs/DatabaseConfig"})
+    );
+    assert_eq!(
+        schema["$defs"]["DatabaseConfig"]["properties"]["port"],
+        serde_json::json!({"type": "integer"})
+    );
+}
+
+#[test]
+fn a_hash_map_of_structs_references_the_shared_def_instead_of_duplicating_it() {
+    let schema = PartialAppConfig::json_schema();
+
+    assert_eq!(
+        schema["properties"]["locations"],
+        serde_json::json!({
+            "type": "object",
+            "additionalProperties": {"$ref": "#/$defs/DatabaseConfig"},
+        })
+    );
+    // Only one `$defs` entry for `DatabaseConfig`, even though it's
+    // referenced from both `database` and `locations`.
+    assert_eq!(schema["$defs"].as_object().unwrap().len(), 1);
+}
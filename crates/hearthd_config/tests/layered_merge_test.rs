@@ -0,0 +1,124 @@
+use std::fs;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+}
+
+#[test]
+fn depth_zero_replaces_the_whole_table_instead_of_merging_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.toml");
+
+    fs::write(
+        &base_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    // Only sets `database.port`; with unbounded depth this would merge into
+    // the base's `database` table and keep `host = "base-host"`.
+    fs::write(
+        &override_path,
+        r#"
+        [database]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[base_path, override_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+
+    let merged = PartialAppConfig::merge_layered(configs, 0);
+
+    assert_eq!(merged.name.unwrap().into_inner(), "base");
+    let db = merged.database.unwrap();
+    assert_eq!(
+        db.port.unwrap().into_inner(),
+        3306,
+        "override layer still wins"
+    );
+    assert!(
+        db.host.is_none(),
+        "depth 0 replaces the whole `database` table wholesale, so the base's \
+         host doesn't survive alongside the override's port"
+    );
+}
+
+#[test]
+fn depth_one_still_merges_the_nested_struct_field_by_field() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.toml");
+
+    fs::write(
+        &base_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        &override_path,
+        r#"
+        [database]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[base_path, override_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+
+    let merged = PartialAppConfig::merge_layered(configs, 1);
+
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306);
+}
+
+#[test]
+fn merge_layered_never_produces_conflict_diagnostics() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("1.toml");
+    let second_path = temp_dir.path().join("2.toml");
+
+    fs::write(&first_path, "name = \"one\"\n").unwrap();
+    fs::write(&second_path, "name = \"two\"\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+
+    // `merge` would report this as a Diagnostic::Error(Merge) conflict
+    // since both files are peers setting `name`.
+    let merged = PartialAppConfig::merge_layered(configs, 1);
+    assert_eq!(merged.name.unwrap().into_inner(), "two");
+}
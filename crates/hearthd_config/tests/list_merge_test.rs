@@ -0,0 +1,121 @@
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::MergeableConfig;
+use tempfile::TempDir;
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub plugins: Vec<String>,
+    #[config(merge = "replace")]
+    pub allowed_hosts: Vec<String>,
+    #[config(merge = "append")]
+    pub tags: Vec<String>,
+}
+
+#[test]
+fn lists_from_every_file_are_concatenated_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let extra_path = temp_dir.path().join("extra.toml");
+
+    fs::write(
+        &base_path,
+        "name = \"app\"\nplugins = [\"logging\"]\nallowed_hosts = [\"localhost\"]\n",
+    )
+    .unwrap();
+    fs::write(&extra_path, "plugins = [\"metrics\", \"tracing\"]\n").unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[base_path, extra_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(diagnostics.is_empty());
+    let plugins: Vec<String> = merged
+        .plugins
+        .unwrap()
+        .into_iter()
+        .map(|p| p.into_inner())
+        .collect();
+    assert_eq!(plugins, vec!["logging", "metrics", "tracing"]);
+}
+
+#[test]
+fn a_replace_list_defined_in_two_files_is_a_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.toml");
+
+    fs::write(
+        &first_path,
+        "name = \"app\"\nplugins = []\nallowed_hosts = [\"localhost\"]\n",
+    )
+    .unwrap();
+    fs::write(&second_path, "allowed_hosts = [\"example.com\"]\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(&diagnostics[0], Diagnostic::Error(_)));
+}
+
+#[test]
+fn an_append_list_split_across_files_merges_successfully() {
+    let temp_dir = TempDir::new().unwrap();
+    let config1_path = temp_dir.path().join("config1.toml");
+    let config2_path = temp_dir.path().join("config2.toml");
+
+    fs::write(
+        &config1_path,
+        "name = \"app\"\nplugins = []\nallowed_hosts = []\ntags = [\"core\"]\n",
+    )
+    .unwrap();
+    fs::write(&config2_path, "tags = [\"beta\", \"internal\"]\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(diagnostics.is_empty());
+    let tags: Vec<String> = merged
+        .tags
+        .unwrap()
+        .into_iter()
+        .map(|t| t.into_inner())
+        .collect();
+    assert_eq!(tags, vec!["core", "beta", "internal"]);
+}
+
+#[test]
+fn a_replace_list_defined_once_is_not_a_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+
+    fs::write(
+        &base_path,
+        "name = \"app\"\nplugins = []\nallowed_hosts = [\"localhost\", \"example.com\"]\n",
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[base_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(diagnostics.is_empty());
+    let hosts: Vec<String> = merged
+        .allowed_hosts
+        .unwrap()
+        .into_iter()
+        .map(|h| h.into_inner())
+        .collect();
+    assert_eq!(hosts, vec!["localhost", "example.com"]);
+}
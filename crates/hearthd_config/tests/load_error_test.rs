@@ -0,0 +1,124 @@
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::MergeableConfig;
+use hearthd_config::TryFromPartial;
+use hearthd_config::Validate;
+use tempfile::TempDir;
+
+#[derive(Debug, Default, TryFromPartial, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+}
+
+impl Validate for AppConfig {}
+
+#[test]
+fn a_malformed_file_is_a_diagnostic_not_an_abort() {
+    let temp_dir = TempDir::new().unwrap();
+    let good_path = temp_dir.path().join("good.toml");
+    let bad_path = temp_dir.path().join("bad.toml");
+    fs::write(&good_path, "name = \"base\"\n").unwrap();
+    fs::write(&bad_path, "name = [unterminated\n").unwrap();
+
+    let (configs, diagnostics) = PartialAppConfig::load_with_imports(&[good_path, bad_path]);
+
+    assert_eq!(configs.len(), 1, "the good file still loaded");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        matches!(&diagnostics[0], Diagnostic::Error(Error::Load(_))),
+        "{:?}",
+        diagnostics[0]
+    );
+}
+
+#[test]
+fn a_missing_file_is_a_diagnostic_not_an_abort() {
+    let temp_dir = TempDir::new().unwrap();
+    let good_path = temp_dir.path().join("good.toml");
+    let missing_path = temp_dir.path().join("does-not-exist.toml");
+    fs::write(&good_path, "name = \"base\"\n").unwrap();
+
+    let (configs, diagnostics) = PartialAppConfig::load_with_imports(&[missing_path, good_path]);
+
+    assert_eq!(configs.len(), 1);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(&diagnostics[0], Diagnostic::Error(Error::Load(_))));
+}
+
+#[test]
+fn an_import_cycle_is_a_diagnostic_alongside_the_files_that_do_load() {
+    let temp_dir = TempDir::new().unwrap();
+    let a_path = temp_dir.path().join("a.toml");
+    let b_path = temp_dir.path().join("b.toml");
+    let standalone_path = temp_dir.path().join("standalone.toml");
+    fs::write(&a_path, "imports = [\"b.toml\"]\nname = \"a\"\n").unwrap();
+    fs::write(&b_path, "imports = [\"a.toml\"]\n").unwrap();
+    fs::write(&standalone_path, "name = \"standalone\"\n").unwrap();
+
+    let (configs, diagnostics) = PartialAppConfig::load_with_imports(&[a_path, standalone_path]);
+
+    assert_eq!(configs.len(), 1, "only the standalone file loaded");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(&diagnostics[0], Diagnostic::Error(Error::Load(_))));
+}
+
+#[test]
+fn a_diamond_import_contributes_its_config_only_once() {
+    let temp_dir = TempDir::new().unwrap();
+    let a_path = temp_dir.path().join("a.toml");
+    let b_path = temp_dir.path().join("b.toml");
+    let c_path = temp_dir.path().join("c.toml");
+    let d_path = temp_dir.path().join("d.toml");
+    fs::write(&a_path, "imports = [\"b.toml\", \"c.toml\"]\n").unwrap();
+    fs::write(&b_path, "imports = [\"d.toml\"]\n").unwrap();
+    fs::write(&c_path, "imports = [\"d.toml\"]\n").unwrap();
+    fs::write(&d_path, "name = \"d\"\n").unwrap();
+
+    let (configs, diagnostics) = PartialAppConfig::load_with_imports(&[a_path]);
+
+    assert_eq!(
+        configs.len(),
+        4,
+        "a, b, c, and d each load exactly once: {:?}",
+        configs
+    );
+    assert!(
+        diagnostics.is_empty(),
+        "a diamond import should not be reported as a cycle or merge conflict: {:?}",
+        diagnostics
+    );
+
+    let (_config, diagnostics) = AppConfig::from_files(&[temp_dir.path().join("a.toml")])
+        .expect("diamond import must not surface a bogus self-conflict");
+    assert!(diagnostics.0.is_empty());
+}
+
+#[test]
+fn from_files_reports_load_and_merge_diagnostics_together() {
+    let temp_dir = TempDir::new().unwrap();
+    let bad_path = temp_dir.path().join("bad.toml");
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.toml");
+    fs::write(&bad_path, "name = [unterminated\n").unwrap();
+    fs::write(&first_path, "name = \"first\"\n").unwrap();
+    fs::write(&second_path, "name = \"second\"\n").unwrap();
+
+    let err = AppConfig::from_files(&[bad_path, first_path, second_path]).unwrap_err();
+
+    assert!(
+        err.0
+            .iter()
+            .any(|d| matches!(d, Diagnostic::Error(Error::Load(_)))),
+        "expected a load diagnostic for bad.toml: {:?}",
+        err.0
+    );
+    assert!(
+        err.0
+            .iter()
+            .any(|d| matches!(d, Diagnostic::Error(Error::Merge(_)))),
+        "expected a merge conflict between first.toml and second.toml: {:?}",
+        err.0
+    );
+}
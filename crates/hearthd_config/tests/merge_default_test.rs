@@ -0,0 +1,68 @@
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::MergeableConfig;
+use tempfile::TempDir;
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    #[config(default = "localhost".to_string())]
+    pub host: String,
+    #[config(default = 8080)]
+    pub port: u16,
+}
+
+#[test]
+fn an_unset_defaulted_field_falls_back_to_its_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    fs::write(&base_path, "name = \"app\"\n").unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[base_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(merged.host.unwrap().into_inner(), "localhost");
+    assert_eq!(merged.port.unwrap().into_inner(), 8080);
+}
+
+#[test]
+fn a_file_setting_a_defaulted_field_silently_overrides_the_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    fs::write(&base_path, "name = \"app\"\nport = 9090\n").unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[base_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(
+        diagnostics.is_empty(),
+        "a default is not a prior definition, so it must not conflict"
+    );
+    assert_eq!(merged.host.unwrap().into_inner(), "localhost");
+    assert_eq!(merged.port.unwrap().into_inner(), 9090);
+}
+
+#[test]
+fn two_files_setting_a_defaulted_field_still_conflict() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.toml");
+
+    fs::write(&first_path, "name = \"app\"\nport = 9090\n").unwrap();
+    fs::write(&second_path, "port = 9091\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(&diagnostics[0], Diagnostic::Error(_)));
+}
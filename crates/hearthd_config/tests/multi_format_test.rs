@@ -0,0 +1,109 @@
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::MergeableConfig;
+use tempfile::TempDir;
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub port: u16,
+}
+
+#[test]
+fn a_json_file_loads_through_the_same_partial_type() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.json");
+    fs::write(&path, r#"{"name": "app", "port": 8080}"#).unwrap();
+
+    let config = PartialAppConfig::from_file(&path).unwrap();
+
+    assert_eq!(config.name.unwrap().into_inner(), "app");
+    assert_eq!(config.port.unwrap().into_inner(), 8080);
+}
+
+#[test]
+fn a_yaml_file_loads_through_the_same_partial_type() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.yaml");
+    fs::write(&path, "name: app\nport: 9090\n").unwrap();
+
+    let config = PartialAppConfig::from_file(&path).unwrap();
+
+    assert_eq!(config.name.unwrap().into_inner(), "app");
+    assert_eq!(config.port.unwrap().into_inner(), 9090);
+}
+
+#[test]
+fn a_toml_base_and_a_json_override_merge_without_a_format_mismatch() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.json");
+
+    fs::write(&base_path, "name = \"app\"\nport = 8080\n").unwrap();
+    fs::write(&override_path, r#"{"port": 9090}"#).unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[base_path, override_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert!(diagnostics.is_empty());
+    assert_eq!(merged.name.unwrap().into_inner(), "app");
+    assert_eq!(
+        merged.port.unwrap().into_inner(),
+        9090,
+        "the later file wins"
+    );
+}
+
+#[test]
+fn a_field_set_in_both_a_toml_and_a_json_file_still_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.json");
+
+    fs::write(&first_path, "name = \"app\"\nport = 8080\n").unwrap();
+    fs::write(&second_path, r#"{"name": "other"}"#).unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].is_error());
+}
+
+#[test]
+fn a_conflict_from_a_json_file_still_points_at_its_file_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.json");
+
+    fs::write(&first_path, "name = \"app\"\nport = 8080\n").unwrap();
+    fs::write(&second_path, r#"{"name": "other"}"#).unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path.clone(), second_path.clone()]);
+    assert!(load_diagnostics.is_empty());
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 1);
+    let Diagnostic::Error(Error::Merge(merge_error)) = &diagnostics[0] else {
+        panic!("expected a merge conflict, got {:?}", diagnostics[0]);
+    };
+
+    assert_eq!(merge_error.conflicts.len(), 2);
+    assert_eq!(merge_error.conflicts[0].file_path, first_path);
+    assert_eq!(merge_error.conflicts[1].file_path, second_path);
+    assert_eq!(
+        merge_error.conflicts[1].span,
+        0..0,
+        "JSON carries no byte spans, so its conflict location degrades to 0..0"
+    );
+}
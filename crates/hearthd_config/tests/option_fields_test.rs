@@ -43,7 +43,8 @@ fn test_option_simple_type() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path]).unwrap();
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[config1_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Should merge without conflicts");
@@ -74,7 +75,8 @@ fn test_option_complex_struct_basic() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path]).unwrap();
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[config1_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Should merge without conflicts");
@@ -116,7 +118,9 @@ fn test_option_complex_struct_field_merge() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(
@@ -166,7 +170,9 @@ fn test_option_complex_struct_conflict() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert!(
@@ -220,7 +226,9 @@ fn test_multiple_option_complex_structs() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Should merge without conflicts");
@@ -273,7 +281,9 @@ fn test_mixed_option_types() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Should merge without conflicts");
@@ -322,7 +332,9 @@ fn test_option_complex_none_then_some() {
     )
     .unwrap();
 
-    let configs = PartialAppConfig::load_with_imports(&[config1_path, config2_path]).unwrap();
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
     let (merged, diagnostics) = PartialAppConfig::merge(configs);
 
     assert_eq!(diagnostics.len(), 0, "Should merge without conflicts");
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::MergeableConfig;
+use hearthd_config::Warning;
+use tempfile::TempDir;
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    #[config(merge = "override")]
+    pub environment: String,
+    #[config(merge = "override")]
+    pub feature_flags: HashMap<String, bool>,
+}
+
+#[test]
+fn an_override_field_set_in_two_files_silently_takes_the_later_value() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.toml");
+
+    fs::write(&base_path, "name = \"app\"\nenvironment = \"staging\"\n").unwrap();
+    fs::write(&override_path, "environment = \"production\"\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[base_path, override_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(
+        merged.environment.unwrap().into_inner(),
+        "production",
+        "the later file wins"
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0],
+        Diagnostic::Warning(Warning::FieldOverridden { field_path, .. })
+            if field_path == "environment"
+    ));
+}
+
+#[test]
+fn a_non_override_field_defined_twice_still_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("first.toml");
+    let second_path = temp_dir.path().join("second.toml");
+
+    fs::write(&first_path, "name = \"one\"\n").unwrap();
+    fs::write(&second_path, "name = \"two\"\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].is_error());
+}
+
+#[test]
+fn an_importing_file_overrides_a_value_its_import_set() {
+    // `load_with_imports` pushes an import before the file that imports
+    // it, so the importer - not the import - is last in merge order and
+    // wins for an override field. An environment-specific file importing
+    // a shared base this way doesn't need `unset` to replace a value.
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let prod_path = temp_dir.path().join("prod.toml");
+
+    fs::write(&base_path, "name = \"app\"\nenvironment = \"staging\"\n").unwrap();
+    fs::write(
+        &prod_path,
+        "imports = [\"base.toml\"]\nenvironment = \"production\"\n",
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[prod_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(merged.environment.unwrap().into_inner(), "production");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0],
+        Diagnostic::Warning(Warning::FieldOverridden { .. })
+    ));
+}
+
+#[test]
+fn an_override_hash_map_entry_conflict_is_a_warning_per_key() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.toml");
+
+    fs::write(
+        &base_path,
+        "name = \"app\"\n[feature_flags]\ndark_mode = true\nbeta = false\n",
+    )
+    .unwrap();
+    fs::write(&override_path, "[feature_flags]\ndark_mode = false\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[base_path, override_path]);
+    assert!(load_diagnostics.is_empty());
+
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    let flags = merged.feature_flags.unwrap();
+    assert_eq!(
+        *flags.get("dark_mode").unwrap().get_ref(),
+        false,
+        "the later file's value wins for the conflicting key"
+    );
+    assert_eq!(
+        *flags.get("beta").unwrap().get_ref(),
+        false,
+        "the non-conflicting key from the base file is untouched"
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0],
+        Diagnostic::Warning(Warning::FieldOverridden { .. })
+    ));
+}
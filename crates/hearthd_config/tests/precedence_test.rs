@@ -0,0 +1,112 @@
+use std::fs;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+    #[config(merge = "replace")]
+    pub allowed_hosts: Vec<String>,
+}
+
+#[test]
+fn last_layer_silently_overrides_an_earlier_one() {
+    let temp_dir = TempDir::new().unwrap();
+    let defaults_path = temp_dir.path().join("defaults.toml");
+    let overrides_path = temp_dir.path().join("overrides.toml");
+
+    fs::write(
+        &defaults_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    // Would be a Diagnostic::Error(Merge) under `merge`, since both files
+    // set `database.port`.
+    fs::write(
+        &overrides_path,
+        r#"
+        [database]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[defaults_path, overrides_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let merged = PartialAppConfig::merge_with_precedence(configs);
+
+    assert_eq!(merged.name.unwrap().into_inner(), "base");
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306, "override layer wins");
+}
+
+#[test]
+fn precedence_order_is_load_order_not_file_size() {
+    let temp_dir = TempDir::new().unwrap();
+    let first_path = temp_dir.path().join("1.toml");
+    let second_path = temp_dir.path().join("2.toml");
+    let third_path = temp_dir.path().join("3.toml");
+
+    fs::write(&first_path, "name = \"one\"\n").unwrap();
+    fs::write(&second_path, "name = \"two\"\n").unwrap();
+    fs::write(&third_path, "name = \"three\"\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first_path, second_path, third_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let merged = PartialAppConfig::merge_with_precedence(configs);
+
+    assert_eq!(merged.name.unwrap().into_inner(), "three");
+}
+
+#[test]
+fn a_replace_list_under_precedence_is_replaced_wholesale_not_extended() {
+    let temp_dir = TempDir::new().unwrap();
+    let defaults_path = temp_dir.path().join("defaults.toml");
+    let overrides_path = temp_dir.path().join("overrides.toml");
+
+    // Would be a Diagnostic::Error(Merge) under `merge`, since both files
+    // set `allowed_hosts`; under precedence the later layer wins outright.
+    fs::write(
+        &defaults_path,
+        "name = \"base\"\nallowed_hosts = [\"localhost\"]\n",
+    )
+    .unwrap();
+    fs::write(&overrides_path, "allowed_hosts = [\"example.com\"]\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[defaults_path, overrides_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let merged = PartialAppConfig::merge_with_precedence(configs);
+
+    let hosts: Vec<String> = merged
+        .allowed_hosts
+        .unwrap()
+        .into_iter()
+        .map(|h| h.into_inner())
+        .collect();
+    assert_eq!(
+        hosts,
+        vec!["example.com"],
+        "override layer should replace, not extend, the base's list"
+    );
+}
@@ -0,0 +1,88 @@
+use std::fs;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use hearthd_config::TryFromPartial;
+use hearthd_config::Validate;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, Default, TryFromPartial, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+}
+
+impl Validate for AppConfig {}
+
+#[test]
+fn resolve_with_provenance_reports_the_source_file_for_each_field() {
+    let temp_dir = TempDir::new().unwrap();
+    let base_path = temp_dir.path().join("base.toml");
+    let override_path = temp_dir.path().join("override.toml");
+
+    fs::write(
+        &base_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        &override_path,
+        r#"
+        [database]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (config, provenance, diagnostics) =
+        AppConfig::resolve_with_provenance(&[base_path.clone(), override_path.clone()]);
+
+    assert_eq!(diagnostics.0.len(), 0, "{:?}", diagnostics.0);
+    assert_eq!(config.name, "base");
+
+    let name = provenance.get("name").unwrap();
+    assert_eq!(name.source_file, base_path);
+    assert_eq!(name.layer, 0);
+
+    // `database.host` was only ever set by `base.toml`.
+    let host = provenance.get("database.host").unwrap();
+    assert_eq!(host.source_file, base_path);
+    assert_eq!(host.layer, 0);
+
+    // `database.port` was overridden by `override.toml`, the later layer.
+    let port = provenance.get("database.port").unwrap();
+    assert_eq!(port.source_file, override_path);
+    assert_eq!(port.layer, 1);
+    assert_eq!(port.value, "3306");
+}
+
+#[test]
+fn provenance_map_serializes_to_toml_and_json() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("base.toml");
+    fs::write(&config_path, "name = \"base\"\n").unwrap();
+
+    let (_, provenance, diagnostics) = AppConfig::resolve_with_provenance(&[config_path]);
+    assert_eq!(diagnostics.0.len(), 0);
+
+    let toml = provenance.to_toml().unwrap();
+    assert!(toml.contains("name"));
+    assert!(toml.contains("base"));
+
+    let json = provenance.to_json().unwrap();
+    assert!(json.contains("\"name\""));
+    assert!(json.contains("\"base\""));
+}
@@ -0,0 +1,89 @@
+use std::fs;
+
+use hearthd_config::ConfigRelativePath;
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct MqttConfig {
+    pub broker: String,
+    pub ca_cert: Option<ConfigRelativePath>,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub app_name: String,
+    pub mqtt: MqttConfig,
+}
+
+#[test]
+fn a_relative_path_resolves_against_its_defining_file_not_the_root_or_cwd() {
+    let root_dir = TempDir::new().unwrap();
+    let imported_dir = root_dir.path().join("imported");
+    fs::create_dir(&imported_dir).unwrap();
+
+    let root_path = root_dir.path().join("root.toml");
+    let mqtt_path = imported_dir.join("mqtt.toml");
+
+    fs::write(
+        &root_path,
+        r#"
+        app_name = "MyApp"
+        imports = ["imported/mqtt.toml"]
+        "#,
+    )
+    .unwrap();
+    fs::write(
+        &mqtt_path,
+        r#"
+        [mqtt]
+        broker = "localhost"
+        ca_cert = "certs/ca.pem"
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[root_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+    assert_eq!(diagnostics.len(), 0, "Expected no diagnostics");
+
+    let mqtt = merged.mqtt.unwrap();
+    let ca_cert = mqtt.ca_cert.unwrap();
+    assert_eq!(
+        ca_cert.get_ref().resolve(),
+        imported_dir.join("certs/ca.pem"),
+        "ca_cert should resolve against the directory of mqtt.toml, not root.toml"
+    );
+}
+
+#[test]
+fn an_absolute_path_is_left_unchanged() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("root.toml");
+
+    fs::write(
+        &config_path,
+        r#"
+        app_name = "MyApp"
+
+        [mqtt]
+        broker = "localhost"
+        ca_cert = "/etc/ssl/certs/ca.pem"
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[config_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+    assert_eq!(diagnostics.len(), 0, "Expected no diagnostics");
+
+    let ca_cert = merged.mqtt.unwrap().ca_cert.unwrap();
+    assert_eq!(
+        ca_cert.get_ref().resolve(),
+        std::path::PathBuf::from("/etc/ssl/certs/ca.pem")
+    );
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+use hearthd_config::Secret;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Deserialize)]
+struct Wrapper {
+    secret: Secret,
+}
+
+fn parse(toml_str: &str) -> Secret {
+    toml::from_str::<Wrapper>(toml_str).unwrap().secret
+}
+
+#[test]
+fn an_inline_value_resolves_to_itself() {
+    let secret = parse("secret = \"hunter2\"");
+    assert_eq!(secret.resolve().unwrap(), "hunter2");
+}
+
+#[test]
+fn an_env_var_indirection_resolves_from_the_environment() {
+    // SAFETY: test-only, no other thread in this process reads this var.
+    unsafe {
+        std::env::set_var("HEARTHD_CONFIG_TEST_SECRET", "from-env");
+    }
+
+    let secret = parse("secret = \"${env:HEARTHD_CONFIG_TEST_SECRET}\"");
+    assert_eq!(secret.resolve().unwrap(), "from-env");
+
+    unsafe {
+        std::env::remove_var("HEARTHD_CONFIG_TEST_SECRET");
+    }
+}
+
+#[test]
+fn a_missing_env_var_is_a_resolve_error() {
+    let secret = parse("secret = \"${env:HEARTHD_CONFIG_TEST_MISSING}\"");
+    assert!(secret.resolve().is_err());
+}
+
+#[test]
+fn a_file_indirection_reads_the_secret_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let secret_path = temp_dir.path().join("mqtt_pw");
+    fs::write(&secret_path, "from-file\n").unwrap();
+
+    let secret = parse(&format!("secret = {{ file = \"{}\" }}", secret_path.display()));
+    assert_eq!(secret.resolve().unwrap(), "from-file");
+}
+
+#[test]
+fn an_unreadable_file_is_a_resolve_error() {
+    let secret = parse("secret = { file = \"/nonexistent/mqtt_pw\" }");
+    assert!(secret.resolve().is_err());
+}
+
+#[test]
+fn debug_output_always_redacts_the_value() {
+    let secret = parse("secret = \"hunter2\"");
+    assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+    assert!(!format!("{:?}", secret).contains("hunter2"));
+}
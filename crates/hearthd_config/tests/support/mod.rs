@@ -0,0 +1,192 @@
+//! A small `ui_test`-style fixture harness for config diagnostics.
+//!
+//! The tests that exercise `format_diagnostics`'s exact output used to
+//! assert against a giant literal string pasted straight out of a test
+//! run - unreadable, and a pain to update for every cosmetic Ariadne
+//! change. Instead, a fixture under `tests/ui/` declares its expected
+//! diagnostics inline, pinned to the line they should be reported on:
+//!
+//! ```toml
+//! name = "one"
+//! name = "two"  #~ ERROR Field 'name' defined in multiple config files
+//! ```
+//!
+//! [`check_scenario`] runs a set of fixture files through the real
+//! pipeline (whatever the caller passes as `emitted`/`rendered`),
+//! matches every annotation against an emitted diagnostic in the same
+//! file on the same line, and fails with a readable diff listing
+//! unmatched annotations and surprise diagnostics. It also compares the
+//! full rendered Ariadne output against a `.stderr` golden file,
+//! regenerated when `BLESS=1` is set in the environment - so
+//! exact-format regressions don't need a giant string pasted into the
+//! test itself.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The severity an annotation or emitted diagnostic carries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "ERROR",
+            Severity::Warning => "WARN",
+        })
+    }
+}
+
+/// One `#~ SEVERITY message` annotation, pinned to the file and 1-based
+/// source line it appeared on.
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// One diagnostic a pipeline actually emitted, reduced to what an
+/// [`Annotation`] can be checked against.
+#[derive(Debug, Clone)]
+pub struct Emitted {
+    pub file: PathBuf,
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Scan `content` (the contents of `file`) for `#~ ERROR message` /
+/// `#~ WARN message` annotation comments.
+pub fn parse_annotations(file: &Path, content: &str) -> Vec<Annotation> {
+    let mut annotations = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        let rest = match line.find("#~") {
+            Some(i) => line[i + 2..].trim_start(),
+            None => continue,
+        };
+        let (severity, message) = if let Some(message) = rest.strip_prefix("ERROR") {
+            (Severity::Error, message.trim())
+        } else if let Some(message) = rest.strip_prefix("WARN") {
+            (Severity::Warning, message.trim())
+        } else {
+            continue;
+        };
+        annotations.push(Annotation {
+            file: file.to_path_buf(),
+            line: idx + 1,
+            severity,
+            message: message.to_string(),
+        });
+    }
+    annotations
+}
+
+/// Check `fixture_files`' inline annotations against `emitted`, and the
+/// full rendered output against `golden_path`.
+///
+/// Panics with a readable diff on any mismatch.
+pub fn check_scenario(
+    fixture_files: &[PathBuf],
+    emitted: &[Emitted],
+    rendered: &str,
+    golden_path: &Path,
+) {
+    let expected: Vec<Annotation> = fixture_files
+        .iter()
+        .flat_map(|path| {
+            let content = fs::read_to_string(path)
+                .unwrap_or_else(|e| panic!("reading fixture {}: {e}", path.display()));
+            parse_annotations(path, &content)
+        })
+        .collect();
+
+    let mut matched = vec![false; emitted.len()];
+    let mut unmatched_annotations = Vec::new();
+
+    for annotation in &expected {
+        let hit = emitted.iter().enumerate().find(|(i, e)| {
+            !matched[*i]
+                && e.file == annotation.file
+                && e.line == annotation.line
+                && e.severity == annotation.severity
+                && e.message.contains(&annotation.message)
+        });
+        match hit {
+            Some((i, _)) => matched[i] = true,
+            None => unmatched_annotations.push(annotation.clone()),
+        }
+    }
+
+    let surprises: Vec<_> = emitted
+        .iter()
+        .zip(&matched)
+        .filter(|(_, was_matched)| !**was_matched)
+        .map(|(e, _)| e.clone())
+        .collect();
+
+    if !unmatched_annotations.is_empty() || !surprises.is_empty() {
+        let mut diff = String::new();
+        for a in &unmatched_annotations {
+            writeln!(
+                diff,
+                "- expected {} on {}:{}: {}",
+                a.severity,
+                a.file.display(),
+                a.line,
+                a.message
+            )
+            .ok();
+        }
+        for e in &surprises {
+            writeln!(
+                diff,
+                "+ emitted {} on {}:{}: {}",
+                e.severity,
+                e.file.display(),
+                e.line,
+                e.message
+            )
+            .ok();
+        }
+        panic!("mismatched diagnostics:\n{diff}");
+    }
+
+    check_golden(golden_path, rendered);
+}
+
+/// Compare `rendered` against `golden_path`, or write it when `BLESS=1`
+/// is set in the environment. A fixture that hasn't been blessed yet (no
+/// golden file on disk) only gets the annotation check above - the exact
+/// rendered-output snapshot is opt-in, not a prerequisite for a new
+/// fixture to be useful.
+fn check_golden(golden_path: &Path, rendered: &str) {
+    if std::env::var_os("BLESS").is_some() {
+        fs::write(golden_path, rendered)
+            .unwrap_or_else(|e| panic!("writing golden file {}: {e}", golden_path.display()));
+        return;
+    }
+
+    let expected = match fs::read_to_string(golden_path) {
+        Ok(expected) => expected,
+        Err(_) => {
+            eprintln!(
+                "note: no golden file at {} yet - rerun with BLESS=1 to create one",
+                golden_path.display()
+            );
+            return;
+        }
+    };
+    assert_eq!(
+        rendered,
+        expected,
+        "rendered output doesn't match {} - rerun with BLESS=1 to update",
+        golden_path.display()
+    );
+}
@@ -0,0 +1,74 @@
+//! Fixture-driven diagnostic tests, in the spirit of `ui_test`: each
+//! scenario under `tests/ui/` is a set of real config files whose
+//! expected diagnostics are declared inline with `#~ ERROR`/`#~ WARN`
+//! annotations (see `tests/support`). The real `load_with_imports` +
+//! `merge` pipeline runs against them, and the exact rendered
+//! `format_diagnostics` output is checked against a `.stderr` golden
+//! file (regenerate with `BLESS=1`).
+
+mod support;
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use hearthd_config::format_diagnostics;
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::MergeableConfig;
+
+use support::Emitted;
+use support::Severity;
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+}
+
+fn manifest_path(relative: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join(relative)
+}
+
+/// Byte offset to 1-based line number, the same convention `#~`
+/// annotations are pinned to.
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())]
+        .bytes()
+        .filter(|&b| b == b'\n')
+        .count()
+        + 1
+}
+
+#[test]
+fn a_field_set_in_two_files_is_flagged_at_both_definitions() {
+    let first = manifest_path("tests/ui/merge_conflict/first.toml");
+    let second = manifest_path("tests/ui/merge_conflict/second.toml");
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[first.clone(), second.clone()]);
+    assert!(load_diagnostics.is_empty(), "{load_diagnostics:?}");
+
+    let (_merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    let mut emitted = Vec::new();
+    for diagnostic in &diagnostics {
+        let Diagnostic::Error(Error::Merge(merge_error)) = diagnostic else {
+            panic!("unexpected diagnostic: {diagnostic:?}");
+        };
+        for conflict in &merge_error.conflicts {
+            emitted.push(Emitted {
+                file: conflict.file_path.clone(),
+                line: line_at(&conflict.content, conflict.span.start),
+                severity: Severity::Error,
+                message: merge_error.message.clone(),
+            });
+        }
+    }
+
+    let rendered = format_diagnostics(&diagnostics);
+    support::check_scenario(
+        &[first, second],
+        &emitted,
+        &rendered,
+        &manifest_path("tests/ui/merge_conflict.stderr"),
+    );
+}
@@ -0,0 +1,158 @@
+use std::fs;
+
+use hearthd_config::MergeableConfig;
+use hearthd_config::SubConfig;
+use serde::Deserialize;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, SubConfig)]
+pub struct DatabaseConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct AppConfig {
+    pub name: String,
+    pub database: Option<DatabaseConfig>,
+}
+
+#[test]
+fn unset_clears_a_top_level_field_without_conflicting() {
+    let temp_dir = TempDir::new().unwrap();
+    let config1_path = temp_dir.path().join("config1.toml");
+    let config2_path = temp_dir.path().join("config2.toml");
+
+    fs::write(&config1_path, "name = \"base\"\n").unwrap();
+    fs::write(&config2_path, "unset = [\"name\"]\nname = \"override\"\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 0, "unset then set is not a conflict");
+    assert_eq!(merged.name.unwrap().into_inner(), "override");
+
+    fs::remove_dir_all(&temp_dir).ok();
+}
+
+#[test]
+fn unset_clears_a_nested_field_by_dotted_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let config1_path = temp_dir.path().join("config1.toml");
+    let config2_path = temp_dir.path().join("config2.toml");
+
+    fs::write(
+        &config1_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    fs::write(
+        &config2_path,
+        r#"
+        unset = ["database.port"]
+
+        [database]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(
+        diagnostics.len(),
+        0,
+        "clearing database.port before re-setting it is not a conflict: {diagnostics:?}"
+    );
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306);
+
+    fs::remove_dir_all(&temp_dir).ok();
+}
+
+#[test]
+fn unset_without_a_later_set_leaves_the_field_absent() {
+    let temp_dir = TempDir::new().unwrap();
+    let config1_path = temp_dir.path().join("config1.toml");
+    let config2_path = temp_dir.path().join("config2.toml");
+
+    fs::write(
+        &config1_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    fs::write(&config2_path, "unset = [\"database\"]\n").unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 0);
+    assert!(merged.database.is_none());
+
+    fs::remove_dir_all(&temp_dir).ok();
+}
+
+#[test]
+fn unset_local_to_a_sub_table_clears_just_that_table() {
+    let temp_dir = TempDir::new().unwrap();
+    let config1_path = temp_dir.path().join("config1.toml");
+    let config2_path = temp_dir.path().join("config2.toml");
+
+    fs::write(
+        &config1_path,
+        r#"
+        name = "base"
+
+        [database]
+        host = "base-host"
+        port = 5432
+        "#,
+    )
+    .unwrap();
+
+    // Unset scoped within the `[database]` table itself, rather than via a
+    // dotted path at the root.
+    fs::write(
+        &config2_path,
+        r#"
+        [database]
+        unset = ["port"]
+        port = 3306
+        "#,
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) =
+        PartialAppConfig::load_with_imports(&[config1_path, config2_path]);
+    assert_eq!(load_diagnostics.len(), 0);
+    let (merged, diagnostics) = PartialAppConfig::merge(configs);
+
+    assert_eq!(diagnostics.len(), 0, "{diagnostics:?}");
+    let db = merged.database.unwrap();
+    assert_eq!(db.host.unwrap().into_inner(), "base-host");
+    assert_eq!(db.port.unwrap().into_inner(), 3306);
+
+    fs::remove_dir_all(&temp_dir).ok();
+}
@@ -0,0 +1,90 @@
+use std::fs;
+
+use hearthd_config::Diagnostic;
+use hearthd_config::Error;
+use hearthd_config::MergeableConfig;
+use hearthd_config::ValidationError;
+use tempfile::TempDir;
+
+fn check_default_is_a_known_location(config: &PartialAppConfig) -> Vec<Diagnostic> {
+    let Some(default) = config.default_location.as_ref() else {
+        return Vec::new();
+    };
+    let known = config
+        .locations
+        .as_ref()
+        .is_some_and(|locs| locs.iter().any(|loc| loc.get_ref() == default.get_ref()));
+    if known {
+        return Vec::new();
+    }
+    vec![Diagnostic::Error(Error::Validation(ValidationError {
+        field_path: "default_location".to_string(),
+        message: format!(
+            "default location '{}' is not in 'locations'",
+            default.get_ref()
+        ),
+        span: Some(default.span()),
+        source: None,
+        suggestions: vec![],
+    }))]
+}
+
+#[derive(Debug, MergeableConfig)]
+#[config(validate_with = check_default_is_a_known_location)]
+pub struct AppConfig {
+    pub name: String,
+    pub locations: Vec<String>,
+    pub default_location: Option<String>,
+}
+
+#[test]
+fn a_default_location_present_in_locations_passes_validation() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("base.toml");
+    fs::write(
+        &path,
+        "name = \"app\"\nlocations = [\"home\", \"work\"]\ndefault_location = \"home\"\n",
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[path]);
+    assert!(load_diagnostics.is_empty());
+    let (merged, merge_diagnostics) = PartialAppConfig::merge(configs);
+    assert!(merge_diagnostics.is_empty());
+
+    assert!(merged.validate().is_empty());
+}
+
+#[test]
+fn a_default_location_missing_from_locations_fails_validation() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("base.toml");
+    fs::write(
+        &path,
+        "name = \"app\"\nlocations = [\"home\"]\ndefault_location = \"office\"\n",
+    )
+    .unwrap();
+
+    let (configs, load_diagnostics) = PartialAppConfig::load_with_imports(&[path]);
+    assert!(load_diagnostics.is_empty());
+    let (merged, merge_diagnostics) = PartialAppConfig::merge(configs);
+    assert!(merge_diagnostics.is_empty());
+
+    let diagnostics = merged.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert!(matches!(
+        &diagnostics[0],
+        Diagnostic::Error(Error::Validation(err)) if err.field_path == "default_location"
+    ));
+}
+
+#[derive(Debug, MergeableConfig)]
+pub struct NoValidatorConfig {
+    pub name: String,
+}
+
+#[test]
+fn a_struct_without_validate_with_always_passes() {
+    let partial = PartialNoValidatorConfig::default();
+    assert!(partial.validate().is_empty());
+}
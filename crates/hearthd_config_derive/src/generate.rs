@@ -17,6 +17,41 @@ struct FieldInfo {
     name: Ident,
     field_type: FieldType,
     flattened: bool,
+    merge_strategy: FieldMergeStrategy,
+    /// A `#[config(default = <expr>)]` expression, if present. Only
+    /// meaningful on a root `Simple` field: `generate_root_merge_impl` seeds
+    /// `result` with it before any file is merged in, so it acts as a
+    /// synthetic lowest-priority layer that a real file value silently
+    /// overrides rather than conflicts with.
+    default_expr: Option<syn::Expr>,
+    /// Whether the original field type is `Option<T>`. A `FieldType::Simple`
+    /// doesn't distinguish `T` from `Option<T>` (both merge the same way),
+    /// so `generate_json_schema_impl` needs this separately to decide
+    /// whether the field belongs in the schema's `required` list.
+    is_optional: bool,
+}
+
+/// How a field resolves when defined in more than one config file.
+/// Parsed from `#[config(merge = "override")]`; fields without the
+/// attribute keep the default strict-conflict behavior.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldMergeStrategy {
+    /// A second definition is an `Error::Merge` conflict (the default).
+    Conflict,
+    /// A second definition silently wins, reported as a
+    /// `Diagnostic::Warning(Warning::FieldOverridden)`.
+    Override,
+    /// For `List` fields: a second definition is an `Error::Merge`
+    /// conflict, the same as `Conflict`, instead of the default
+    /// concatenate-across-files behavior.
+    Replace,
+    /// Explicit spelling of the default concatenate-across-files behavior
+    /// for `List`/`HashMap` fields (union-by-key for maps), for callers who
+    /// want `#[config(merge = "append")]` on the field rather than relying
+    /// on the unlabeled default. Rejected at derive time on any field whose
+    /// type isn't a collection - see the `field_type` check in
+    /// `expand_mergeable_config`.
+    Append,
 }
 
 enum FieldType {
@@ -32,6 +67,14 @@ enum FieldType {
         value_type: Type,
     },
     Nested(#[allow(dead_code)] Type),
+    /// A `Vec<T>` field, where `T` is a simple (non-nested) type. By
+    /// default, merged by concatenating every config's list together
+    /// (Dhall-style list combination); `#[config(merge = "replace")]`
+    /// switches to requiring a single definition instead, like `Simple`.
+    List {
+        #[allow(dead_code)]
+        elem_type: Type,
+    },
 }
 
 pub fn expand_mergeable_config(input: DeriveInput, is_root: bool) -> Result<TokenStream> {
@@ -50,6 +93,22 @@ pub fn expand_mergeable_config(input: DeriveInput, is_root: bool) -> Result<Toke
     // Use spans unless explicitly disabled with #[config(no_span)]
     let use_spans = !no_span;
 
+    // Check for a `#[config(validate_with = path::to::fn)]` attribute
+    // registering a cross-field invariant checker for this struct.
+    let validate_with: Option<syn::Path> = input.attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("config") {
+            return None;
+        }
+        if let Ok(syn::Meta::NameValue(nv)) = attr.parse_args::<syn::Meta>() {
+            if nv.path.is_ident("validate_with") {
+                if let syn::Expr::Path(expr_path) = &nv.value {
+                    return Some(expr_path.path.clone());
+                }
+            }
+        }
+        None
+    });
+
     // Only support structs
     let fields = match &input.data {
         Data::Struct(data) => match &data.fields {
@@ -85,6 +144,54 @@ pub fn expand_mergeable_config(input: DeriveInput, is_root: bool) -> Result<Toke
             false
         });
 
+        // Check for a `#[config(merge = "override")]` or
+        // `#[config(merge = "replace")]` attribute to opt this field out of
+        // the default conflict-on-redefinition behavior.
+        let merge_strategy = field
+            .attrs
+            .iter()
+            .find_map(|attr| {
+                if !attr.path().is_ident("config") {
+                    return None;
+                }
+                if let Ok(syn::Meta::NameValue(nv)) = attr.parse_args::<syn::Meta>() {
+                    if nv.path.is_ident("merge") {
+                        if let syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(s),
+                            ..
+                        }) = &nv.value
+                        {
+                            if s.value() == "override" {
+                                return Some(FieldMergeStrategy::Override);
+                            }
+                            if s.value() == "replace" {
+                                return Some(FieldMergeStrategy::Replace);
+                            }
+                            if s.value() == "append" {
+                                return Some(FieldMergeStrategy::Append);
+                            }
+                        }
+                    }
+                }
+                None
+            })
+            .unwrap_or(FieldMergeStrategy::Conflict);
+
+        // Check for a `#[config(default = <expr>)]` attribute providing a
+        // compile-time baseline value for this field, ranked below every
+        // file in merge precedence.
+        let default_expr: Option<syn::Expr> = field.attrs.iter().find_map(|attr| {
+            if !attr.path().is_ident("config") {
+                return None;
+            }
+            if let Ok(syn::Meta::NameValue(nv)) = attr.parse_args::<syn::Meta>() {
+                if nv.path.is_ident("default") {
+                    return Some(nv.value.clone());
+                }
+            }
+            None
+        });
+
         let field_type = if is_hashmap(field_ty) {
             let (key_type, value_type) = extract_hashmap_types(field_ty)?;
             // Check if value type is a struct (not a simple type)
@@ -99,6 +206,8 @@ pub fn expand_mergeable_config(input: DeriveInput, is_root: bool) -> Result<Toke
                     value_type,
                 }
             }
+        } else if let Some(elem_type) = is_vec_type(field_ty) {
+            FieldType::List { elem_type }
         } else if is_simple_type(field_ty) {
             FieldType::Simple(field_ty.clone())
         } else if let Some(inner_ty) = is_option_type(field_ty) {
@@ -113,38 +222,292 @@ pub fn expand_mergeable_config(input: DeriveInput, is_root: bool) -> Result<Toke
             FieldType::Nested(field_ty.clone())
         };
 
+        if merge_strategy == FieldMergeStrategy::Append
+            && !matches!(
+                field_type,
+                FieldType::List { .. }
+                    | FieldType::HashMap { .. }
+                    | FieldType::HashMapOfStructs { .. }
+            )
+        {
+            return Err(Error::new_spanned(
+                &field_name,
+                "#[config(merge = \"append\")] is only supported on Vec<T> or map fields",
+            ));
+        }
+
+        if default_expr.is_some() {
+            if !is_root {
+                return Err(Error::new_spanned(
+                    &field_name,
+                    "#[config(default = ...)] is only supported on MergeableConfig (root) fields, not SubConfig fields",
+                ));
+            }
+            if !matches!(field_type, FieldType::Simple(_)) {
+                return Err(Error::new_spanned(
+                    &field_name,
+                    "#[config(default = ...)] is only supported on simple (non-HashMap, non-Vec, non-nested) fields",
+                ));
+            }
+        }
+
+        let is_optional = is_option_type(field_ty).is_some();
+
         field_infos.push(FieldInfo {
             name: field_name,
             field_type,
             flattened,
+            merge_strategy,
+            default_expr,
+            is_optional,
         });
     }
 
     // Generate code
     let partial_struct = generate_partial_struct(name, fields, use_spans)?;
+    let apply_unset_impl = generate_apply_unset_impl(name, &field_infos)?;
+    let describe_impl = generate_describe_impl(name, &field_infos)?;
+    let attach_base_dir_impl = generate_attach_base_dir_impl(name, &field_infos)?;
+    let merge_from_last_wins_impl = generate_merge_from_last_wins_impl(name, &field_infos)?;
+    let merge_from_last_wins_capped_impl =
+        generate_merge_from_last_wins_capped_impl(name, &field_infos)?;
     let merge_impl = if is_root {
         generate_root_merge_impl(name, &field_infos, use_spans)?
     } else {
         generate_sub_merge_impl(name, &field_infos, use_spans)?
     };
+    let merge_with_precedence_impl = if is_root {
+        Some(generate_merge_with_precedence_impl(name)?)
+    } else {
+        None
+    };
+    let merge_layered_impl = if is_root {
+        Some(generate_merge_layered_impl(name)?)
+    } else {
+        None
+    };
     let load_impl = if is_root {
         Some(generate_load_impl(name)?)
     } else {
         None
     };
+    let validate_impl = generate_validate_impl(name, &validate_with);
+    let json_schema_impl = generate_json_schema_impl(name, &field_infos);
     // TryFrom and from_files are implemented manually to handle validation
     let try_from_impl: Option<TokenStream> = None;
     let config_impl: Option<TokenStream> = None;
 
     Ok(quote! {
         #partial_struct
+        #apply_unset_impl
+        #describe_impl
+        #attach_base_dir_impl
+        #merge_from_last_wins_impl
+        #merge_from_last_wins_capped_impl
         #merge_impl
+        #merge_with_precedence_impl
+        #merge_layered_impl
         #load_impl
+        #validate_impl
+        #json_schema_impl
         #try_from_impl
         #config_impl
     })
 }
 
+/// Generate `validate()` on `Partial{config_name}`, the post-merge
+/// cross-field invariant hook requested by `#[config(validate_with =
+/// path::to::fn)]`. Unlike per-field conflict detection in `merge`, this
+/// runs once against the fully merged partial, so it can check
+/// relationships between different fields (e.g. "field A requires field
+/// B"). The registered function takes `&Self` and returns the additional
+/// diagnostics it finds; a struct with no `validate_with` attribute gets
+/// a no-op `validate()` that always returns an empty `Vec`.
+fn generate_validate_impl(config_name: &Ident, validate_with: &Option<syn::Path>) -> TokenStream {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let body = match validate_with {
+        Some(path) => quote! { #path(self) },
+        None => quote! { Vec::new() },
+    };
+
+    quote! {
+        impl #partial_name {
+            pub fn validate(&self) -> Vec<hearthd_config::Diagnostic> {
+                #body
+            }
+        }
+    }
+}
+
+/// Generate `json_schema()` and its recursive `json_schema_object()` helper
+/// on `Partial{config_name}`, a draft-07 JSON Schema describing every field:
+/// required fields (neither `Option<T>` nor `#[config(default = ...)]`) go
+/// in `required`, a nested `SubConfig` field or `HashMap<K, Struct>`'s value
+/// type is hoisted into `$defs` under its Rust type name and referenced via
+/// `$ref` rather than inlined (so a type used in more than one place, or
+/// recursively, only appears once), and a `HashMap<K, Simple>` becomes
+/// `additionalProperties`. `json_schema_object` takes the in-progress
+/// `$defs` map as a parameter so a nested call can register itself into the
+/// same map `json_schema()` ultimately attaches at the top level.
+fn generate_json_schema_impl(config_name: &Ident, field_infos: &[FieldInfo]) -> TokenStream {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let property_entries = field_infos.iter().map(|field| {
+        let name_str = field.name.to_string();
+        let schema_expr = json_schema_for_field(field);
+        quote! {
+            properties.insert(#name_str.to_string(), #schema_expr);
+        }
+    });
+
+    let required_entries = field_infos
+        .iter()
+        .filter(|field| !field.is_optional && field.default_expr.is_none())
+        .map(|field| {
+            let name_str = field.name.to_string();
+            quote! {
+                required.push(#name_str.to_string());
+            }
+        });
+
+    quote! {
+        impl #partial_name {
+            /// A draft-07 JSON Schema object describing this config, with
+            /// every nested `SubConfig` type hoisted into a top-level
+            /// `$defs` map. See `json_schema_object` for how a single
+            /// type's own schema is built.
+            pub fn json_schema() -> serde_json::Value {
+                let mut defs = serde_json::Map::new();
+                let mut schema = Self::json_schema_object(&mut defs);
+
+                if !defs.is_empty() {
+                    if let serde_json::Value::Object(ref mut map) = schema {
+                        map.insert("$defs".to_string(), serde_json::Value::Object(defs));
+                    }
+                }
+
+                schema
+            }
+
+            fn json_schema_object(
+                defs: &mut serde_json::Map<String, serde_json::Value>,
+            ) -> serde_json::Value {
+                let mut properties = serde_json::Map::new();
+                let mut required: Vec<String> = Vec::new();
+
+                #(#property_entries)*
+                #(#required_entries)*
+
+                serde_json::json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                })
+            }
+        }
+    }
+}
+
+/// The `serde_json::Value` expression (evaluated inside `json_schema_object`,
+/// with `defs` in scope) for one field's schema fragment.
+fn json_schema_for_field(field: &FieldInfo) -> TokenStream {
+    match &field.field_type {
+        FieldType::Simple(ty) => json_schema_primitive(ty),
+        FieldType::Nested(ty) => {
+            let inner = is_option_type(ty).unwrap_or_else(|| ty.clone());
+            json_schema_ref(&inner)
+        }
+        FieldType::HashMap { value_type, .. } => {
+            let value_schema = json_schema_primitive(value_type);
+            quote! {
+                serde_json::json!({
+                    "type": "object",
+                    "additionalProperties": #value_schema,
+                })
+            }
+        }
+        FieldType::HashMapOfStructs { value_type, .. } => {
+            let value_ref = json_schema_ref(value_type);
+            quote! {
+                serde_json::json!({
+                    "type": "object",
+                    "additionalProperties": #value_ref,
+                })
+            }
+        }
+        FieldType::List { elem_type } => {
+            let elem_schema = json_schema_primitive(elem_type);
+            quote! {
+                serde_json::json!({
+                    "type": "array",
+                    "items": #elem_schema,
+                })
+            }
+        }
+    }
+}
+
+/// A `{"$ref": "#/$defs/Name"}` fragment for a nested `SubConfig` type,
+/// registering `Partial{Name}::json_schema_object`'s result into `defs`
+/// under `Name` the first time it's referenced.
+fn json_schema_ref(ty: &Type) -> TokenStream {
+    let Some(type_ident) = last_path_ident(ty) else {
+        return quote! { serde_json::json!(true) };
+    };
+    let partial_ident = format_ident!("Partial{}", type_ident);
+    let type_name_str = type_ident.to_string();
+
+    quote! {
+        {
+            if !defs.contains_key(#type_name_str) {
+                let nested = #partial_ident::json_schema_object(defs);
+                defs.insert(#type_name_str.to_string(), nested);
+            }
+            serde_json::json!({"$ref": format!("#/$defs/{}", #type_name_str)})
+        }
+    }
+}
+
+/// The JSON Schema fragment for a simple (non-nested) Rust type, unwrapping
+/// an outer `Option<T>` first. Falls back to `true` (any value permitted)
+/// for a type this crate doesn't recognize, e.g. an opaque `toml::Value`
+/// field - a deliberately permissive default rather than rejecting a
+/// document this crate itself can't validate further.
+fn json_schema_primitive(ty: &Type) -> TokenStream {
+    let inner = is_option_type(ty).unwrap_or_else(|| ty.clone());
+    let ident = last_path_ident(&inner).map(|ident| ident.to_string());
+
+    match ident.as_deref() {
+        Some("bool") => quote! { serde_json::json!({"type": "boolean"}) },
+        Some("String")
+        | Some("str")
+        | Some("PathBuf")
+        | Some("ConfigRelativePath")
+        | Some("LogLevel") => {
+            quote! { serde_json::json!({"type": "string"}) }
+        }
+        Some("f32") | Some("f64") => quote! { serde_json::json!({"type": "number"}) },
+        Some("i8") | Some("i16") | Some("i32") | Some("i64") | Some("i128") | Some("u8")
+        | Some("u16") | Some("u32") | Some("u64") | Some("u128") => {
+            quote! { serde_json::json!({"type": "integer"}) }
+        }
+        // `Secret` deserializes from either a bare string or a `{file = "..."}`/
+        // `{env = "..."}` table - too varied to usefully constrain - and anything
+        // else unrecognized (e.g. an opaque `toml::Value`) is similarly opaque.
+        _ => quote! { serde_json::json!(true) },
+    }
+}
+
+/// The last path segment's identifier, e.g. `DatabaseConfig` for both
+/// `DatabaseConfig` and a fully-qualified `crate::config::DatabaseConfig`.
+fn last_path_ident(ty: &Type) -> Option<Ident> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        return path.segments.last().map(|segment| segment.ident.clone());
+    }
+    None
+}
+
 fn generate_partial_struct(
     config_name: &Ident,
     fields: &syn::punctuated::Punctuated<Field, syn::token::Comma>,
@@ -179,6 +542,14 @@ fn generate_partial_struct(
             } else {
                 quote! { std::collections::HashMap<#key_type, <#value_type as hearthd_config::HasPartialConfig>::PartialConfig> }
             }
+        } else if let Some(elem_type) = is_vec_type(field_ty) {
+            // Only use Spanned if use_spans is true, so appended elements
+            // keep the provenance of the file they came from.
+            if use_spans {
+                quote! { Vec<toml::Spanned<#elem_type>> }
+            } else {
+                quote! { Vec<#elem_type> }
+            }
         } else if let Some(inner_ty) = is_option_type(field_ty) {
             // Option<T> - only use Spanned if use_spans is true
             if is_simple_type(&inner_ty) {
@@ -217,23 +588,510 @@ fn generate_partial_struct(
             }
         };
 
-        partial_fields.push(field_tokens);
-    }
+        partial_fields.push(field_tokens);
+    }
+
+    Ok(quote! {
+        #[derive(Debug, Default, serde::Deserialize)]
+        pub struct #partial_name {
+            #[serde(default)]
+            pub imports: Vec<String>,
+
+            /// Mercurial-style `%unset` directives: dot-separated field
+            /// paths (e.g. `"database.port"`) to clear during merging, so a
+            /// later-loaded file can subtract a value an earlier one set
+            /// instead of only ever being able to add or conflict with it.
+            #[serde(default)]
+            pub unset: Vec<String>,
+
+            #(#partial_fields,)*
+
+            #[serde(skip)]
+            pub source: Option<hearthd_config::SourceInfo>,
+        }
+
+        impl hearthd_config::HasPartialConfig for #config_name {
+            type PartialConfig = #partial_name;
+        }
+    })
+}
+
+/// Generate the `apply_unset` method shared by root and sub partial configs.
+///
+/// `path` is a dot-separated field path (`"database.port"`): the head
+/// segment selects a field on `self`, and any remaining segments are handed
+/// down recursively to that field's own `apply_unset` (nested structs) or
+/// interpreted as "clear the whole thing" (simple fields and maps, which
+/// have no further per-field structure to target individually).
+fn generate_apply_unset_impl(config_name: &Ident, fields: &[FieldInfo]) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let arms: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.to_string();
+            match &field.field_type {
+                FieldType::Simple(_) => quote! {
+                    #name_str => {
+                        self.#name = None;
+                    }
+                },
+                FieldType::Nested(_) => quote! {
+                    #name_str => match rest {
+                        Some(rest) => {
+                            if let Some(entry) = self.#name.as_mut() {
+                                entry.apply_unset(rest);
+                            }
+                        }
+                        None => {
+                            self.#name = None;
+                        }
+                    },
+                },
+                FieldType::HashMap { .. } => quote! {
+                    #name_str => {
+                        if rest.is_none() {
+                            self.#name = None;
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } if field.flattened => quote! {
+                    #name_str => {
+                        if rest.is_none() {
+                            self.#name.clear();
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } => quote! {
+                    #name_str => {
+                        if rest.is_none() {
+                            self.#name = None;
+                        }
+                    }
+                },
+                FieldType::List { .. } => quote! {
+                    #name_str => {
+                        if rest.is_none() {
+                            self.#name = None;
+                        }
+                    }
+                },
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #partial_name {
+            /// Clear a field set by an earlier-merged config, per a
+            /// Mercurial-style `%unset name` directive. See the `unset`
+            /// field's doc comment for the path syntax.
+            pub fn apply_unset(&mut self, path: &str) {
+                let (head, rest) = match path.split_once('.') {
+                    Some((head, rest)) => (head, Some(rest)),
+                    None => (path, None),
+                };
+                match head {
+                    #(#arms)*
+                    _ => {}
+                }
+            }
+        }
+    })
+}
+
+/// Generate the `source_info` and `describe_into` methods shared by root
+/// and sub partial configs, used by `hearthd_config::MergeableConfig::resolve_with_provenance`
+/// to report which file set each field's final value.
+fn generate_describe_impl(config_name: &Ident, fields: &[FieldInfo]) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let mut key_types: Vec<&Type> = Vec::new();
+    for field in fields {
+        if let FieldType::HashMap { key_type, .. } | FieldType::HashMapOfStructs { key_type, .. } =
+            &field.field_type
+        {
+            key_types.push(key_type);
+        }
+    }
+    let where_clause = if key_types.is_empty() {
+        quote! {}
+    } else {
+        quote! { where #(#key_types: std::fmt::Display,)* }
+    };
+
+    let field_descriptions: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            let name_str = name.to_string();
+            match &field.field_type {
+                FieldType::Simple(_) => quote! {
+                    if let Some(value) = &self.#name {
+                        out.push((
+                            hearthd_config::join_path(prefix, #name_str),
+                            value.span().clone(),
+                            format!("{:?}", value.get_ref()),
+                        ));
+                    }
+                },
+                FieldType::Nested(_) => quote! {
+                    if let Some(value) = &self.#name {
+                        value.describe_into(&hearthd_config::join_path(prefix, #name_str), out);
+                    }
+                },
+                FieldType::HashMap { .. } => quote! {
+                    if let Some(map) = &self.#name {
+                        let map_prefix = hearthd_config::join_path(prefix, #name_str);
+                        for (key, value) in map {
+                            out.push((
+                                hearthd_config::join_path(&map_prefix, &key.to_string()),
+                                value.span().clone(),
+                                format!("{:?}", value.get_ref()),
+                            ));
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } if field.flattened => quote! {
+                    let map_prefix = hearthd_config::join_path(prefix, #name_str);
+                    for (key, value) in &self.#name {
+                        value.describe_into(&hearthd_config::join_path(&map_prefix, &key.to_string()), out);
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } => quote! {
+                    if let Some(map) = &self.#name {
+                        let map_prefix = hearthd_config::join_path(prefix, #name_str);
+                        for (key, value) in map {
+                            value.describe_into(&hearthd_config::join_path(&map_prefix, &key.to_string()), out);
+                        }
+                    }
+                },
+                FieldType::List { .. } => quote! {
+                    if let Some(list) = &self.#name {
+                        let list_prefix = hearthd_config::join_path(prefix, #name_str);
+                        for (index, value) in list.iter().enumerate() {
+                            out.push((
+                                hearthd_config::join_path(&list_prefix, &index.to_string()),
+                                value.span().clone(),
+                                format!("{:?}", value.get_ref()),
+                            ));
+                        }
+                    }
+                },
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #partial_name #where_clause {
+            /// The file this partial config was loaded from, if any - see
+            /// `hearthd_config::PartialMergeableConfig::source_info`.
+            pub fn source_info(&self) -> Option<&hearthd_config::SourceInfo> {
+                self.source.as_ref()
+            }
+
+            /// Record every field this file/layer actually sets into `out`
+            /// as `(dotted_path, span, debug value)` - see
+            /// `hearthd_config::PartialMergeableConfig::describe_into`.
+            pub fn describe_into(&self, prefix: &str, out: &mut Vec<(String, std::ops::Range<usize>, String)>) {
+                #(#field_descriptions)*
+            }
+        }
+    })
+}
+
+/// Generate `attach_base_dir`, which walks every
+/// `hearthd_config::ConfigRelativePath` field (recursing into nested structs
+/// and struct-valued maps) and records the directory of the file that
+/// defined it, so a relative path resolves against that file rather than
+/// the process CWD or the root config's directory.
+fn generate_attach_base_dir_impl(config_name: &Ident, fields: &[FieldInfo]) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let field_attachments: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            match &field.field_type {
+                FieldType::Simple(ty) if is_config_relative_path(ty) => quote! {
+                    if let Some(value) = &mut self.#name {
+                        value.get_mut().set_base_dir(dir);
+                    }
+                },
+                FieldType::Simple(_) => quote! {},
+                FieldType::Nested(_) => quote! {
+                    if let Some(value) = &mut self.#name {
+                        value.attach_base_dir(dir);
+                    }
+                },
+                FieldType::HashMap { .. } => quote! {},
+                FieldType::HashMapOfStructs { .. } if field.flattened => quote! {
+                    for value in self.#name.values_mut() {
+                        value.attach_base_dir(dir);
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } => quote! {
+                    if let Some(map) = &mut self.#name {
+                        for value in map.values_mut() {
+                            value.attach_base_dir(dir);
+                        }
+                    }
+                },
+                FieldType::List { .. } => quote! {},
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #partial_name {
+            /// Resolve any `hearthd_config::ConfigRelativePath` fields
+            /// against `dir`, the directory of the file that set them.
+            /// Called once per loaded file, alongside attaching `source`.
+            pub fn attach_base_dir(&mut self, dir: &std::path::Path) {
+                #(#field_attachments)*
+            }
+        }
+    })
+}
+
+/// Generate the `merge_from_last_wins` method shared by root and sub partial
+/// configs: the last-wins counterpart to `merge_from`/`merge`, with no
+/// conflict diagnostics. [`generate_merge_with_precedence_impl`] drives this
+/// at the root via repeated calls, one per precedence layer.
+fn generate_merge_from_last_wins_impl(
+    config_name: &Ident,
+    fields: &[FieldInfo],
+) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let field_merges: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            match &field.field_type {
+                FieldType::Simple(_) => quote! {
+                    if let Some(value) = other.#name {
+                        self.#name = Some(value);
+                    }
+                },
+                FieldType::Nested(_) => quote! {
+                    if let Some(value) = other.#name {
+                        self.#name.get_or_insert_with(Default::default).merge_from_last_wins(value);
+                    }
+                },
+                FieldType::HashMap { .. } => quote! {
+                    if let Some(map) = other.#name {
+                        let self_map = self.#name.get_or_insert_with(std::collections::HashMap::new);
+                        for (key, value) in map {
+                            self_map.insert(key, value);
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } if field.flattened => quote! {
+                    for (key, partial_value) in other.#name {
+                        self.#name.entry(key).or_default().merge_from_last_wins(partial_value);
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } => quote! {
+                    if let Some(map) = other.#name {
+                        let self_map = self.#name.get_or_insert_with(std::collections::HashMap::new);
+                        for (key, partial_value) in map {
+                            self_map.entry(key).or_default().merge_from_last_wins(partial_value);
+                        }
+                    }
+                },
+                FieldType::List { .. } if field.merge_strategy == FieldMergeStrategy::Replace => quote! {
+                    if let Some(list) = other.#name {
+                        self.#name = Some(list);
+                    }
+                },
+                FieldType::List { .. } => quote! {
+                    if let Some(list) = other.#name {
+                        self.#name.get_or_insert_with(Vec::new).extend(list);
+                    }
+                },
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #partial_name {
+            /// Merge `other` into `self` with last-wins precedence: a field
+            /// `other` sets overwrites (or, for maps and nested structs, is
+            /// merged into) whatever `self` already has - never a conflict.
+            pub fn merge_from_last_wins(&mut self, other: Self) {
+                self.imports.extend(other.imports.clone());
+                for path in &other.unset {
+                    self.apply_unset(path);
+                }
+                self.unset.extend(other.unset.clone());
+
+                #(#field_merges)*
+            }
+        }
+    })
+}
+
+/// Generate the `merge_from_last_wins_capped` method shared by root and sub
+/// partial configs: like `merge_from_last_wins`, but a nested struct or a
+/// `HashMap<K, Struct>` entry is only deep-merged while `depth < max_depth`;
+/// once the cap is reached, the higher-precedence value replaces the lower
+/// one wholesale instead of recursing field-by-field. [`generate_merge_layered_impl`]
+/// drives this at the root, starting `depth` at 0.
+fn generate_merge_from_last_wins_capped_impl(
+    config_name: &Ident,
+    fields: &[FieldInfo],
+) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    let field_merges: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let name = &field.name;
+            match &field.field_type {
+                FieldType::Simple(_) => quote! {
+                    if let Some(value) = other.#name {
+                        self.#name = Some(value);
+                    }
+                },
+                FieldType::Nested(_) => quote! {
+                    if let Some(value) = other.#name {
+                        if depth < max_depth {
+                            self.#name
+                                .get_or_insert_with(Default::default)
+                                .merge_from_last_wins_capped(value, depth + 1, max_depth);
+                        } else {
+                            self.#name = Some(value);
+                        }
+                    }
+                },
+                FieldType::HashMap { .. } => quote! {
+                    if let Some(map) = other.#name {
+                        let self_map = self.#name.get_or_insert_with(std::collections::HashMap::new);
+                        for (key, value) in map {
+                            self_map.insert(key, value);
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } if field.flattened => quote! {
+                    for (key, partial_value) in other.#name {
+                        if depth < max_depth {
+                            self.#name
+                                .entry(key)
+                                .or_default()
+                                .merge_from_last_wins_capped(partial_value, depth + 1, max_depth);
+                        } else {
+                            self.#name.insert(key, partial_value);
+                        }
+                    }
+                },
+                FieldType::HashMapOfStructs { .. } => quote! {
+                    if let Some(map) = other.#name {
+                        let self_map = self.#name.get_or_insert_with(std::collections::HashMap::new);
+                        for (key, partial_value) in map {
+                            if depth < max_depth {
+                                self_map
+                                    .entry(key)
+                                    .or_default()
+                                    .merge_from_last_wins_capped(partial_value, depth + 1, max_depth);
+                            } else {
+                                self_map.insert(key, partial_value);
+                            }
+                        }
+                    }
+                },
+                FieldType::List { .. } if field.merge_strategy == FieldMergeStrategy::Replace => quote! {
+                    if let Some(list) = other.#name {
+                        self.#name = Some(list);
+                    }
+                },
+                FieldType::List { .. } => quote! {
+                    if let Some(list) = other.#name {
+                        self.#name.get_or_insert_with(Vec::new).extend(list);
+                    }
+                },
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        impl #partial_name {
+            /// Merge `other` into `self` with last-wins precedence, like
+            /// `merge_from_last_wins`, but stop deep-merging nested structs
+            /// and struct-valued maps once `depth` reaches `max_depth`: the
+            /// higher-precedence value replaces the lower one wholesale
+            /// instead of being merged field-by-field beyond that point.
+            pub fn merge_from_last_wins_capped(&mut self, other: Self, depth: usize, max_depth: usize) {
+                self.imports.extend(other.imports.clone());
+                for path in &other.unset {
+                    self.apply_unset(path);
+                }
+                self.unset.extend(other.unset.clone());
+
+                #(#field_merges)*
+            }
+        }
+    })
+}
+
+/// Generate the root-only `merge_with_precedence` entry point: folds
+/// [`generate_merge_from_last_wins_impl`]'s `merge_from_last_wins` over an
+/// ordered sequence of configs, highest-precedence (last) wins.
+fn generate_merge_with_precedence_impl(config_name: &Ident) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
 
     Ok(quote! {
-        #[derive(Debug, Default, serde::Deserialize)]
-        pub struct #partial_name {
-            #[serde(default)]
-            pub imports: Vec<String>,
-
-            #(#partial_fields,)*
-
-            #[serde(skip)]
-            pub source: Option<hearthd_config::SourceInfo>,
+        impl #partial_name {
+            /// Merge `configs` with last-wins precedence: the last config in
+            /// the sequence to set a field wins, silently - no
+            /// `hearthd_config::Diagnostic::Error` is ever produced. Use this
+            /// instead of `merge` when `configs` is an ordered precedence
+            /// stack (defaults, then sources, then local overrides) rather
+            /// than peers that must agree.
+            pub fn merge_with_precedence<I>(configs: I) -> Self
+            where
+                I: IntoIterator<Item = Self>,
+            {
+                let mut result = Self::default();
+                for config in configs {
+                    result.merge_from_last_wins(config);
+                }
+                result
+            }
         }
+    })
+}
 
-        impl hearthd_config::HasPartialConfig for #config_name {
-            type PartialConfig = #partial_name;
+/// Generate the root-only `merge_layered` entry point: like
+/// `merge_with_precedence`, folds layers last-wins, but bounds how many
+/// levels of nested structs and struct-valued maps get deep-merged via
+/// [`generate_merge_from_last_wins_capped_impl`]'s `merge_from_last_wins_capped`.
+/// Beyond `max_depth`, a higher-precedence layer replaces a lower one's
+/// table wholesale rather than merging it leaf-by-leaf - e.g. with
+/// `max_depth = 0`, an override file that sets `[database]` replaces the
+/// whole `database` table instead of merging individual fields into it.
+fn generate_merge_layered_impl(config_name: &Ident) -> Result<TokenStream> {
+    let partial_name = format_ident!("Partial{}", config_name);
+
+    Ok(quote! {
+        impl #partial_name {
+            /// Merge `layers` in precedence order (last wins), like
+            /// `merge_with_precedence`, but cap deep-merging of nested
+            /// structs and struct-valued maps at `max_depth` levels. Beyond
+            /// that depth a higher-precedence layer's table replaces a
+            /// lower one's wholesale rather than being merged field-by-field,
+            /// so a deliberately complete override table isn't surprised by
+            /// a stray leaf surviving from an earlier layer.
+            pub fn merge_layered<I>(layers: I, max_depth: usize) -> Self
+            where
+                I: IntoIterator<Item = Self>,
+            {
+                let mut result = Self::default();
+                for layer in layers {
+                    result.merge_from_last_wins_capped(layer, 0, max_depth);
+                }
+                result
+            }
         }
     })
 }
@@ -290,12 +1148,79 @@ fn generate_root_merge_impl(
         })
         .collect();
 
+    // A field with `#[config(default = ...)]` gets a companion bool
+    // tracking whether its current value still came from that default
+    // rather than a file, so the first real file value can silently
+    // replace it instead of being treated as a conflict.
+    let default_tracking_vars: Vec<_> = fields
+        .iter()
+        .filter(|f| f.default_expr.is_some())
+        .map(|f| {
+            let var_name = format_ident!("{}_is_default", f.name);
+            quote! { let mut #var_name: bool = false; }
+        })
+        .collect();
+
+    // Seed `result` with each field's default, below any file in
+    // precedence, before merging in the actual configs.
+    let default_seeds: Vec<_> = fields
+        .iter()
+        .filter_map(|f| {
+            let default_expr = f.default_expr.as_ref()?;
+            let name = &f.name;
+            let name_str = name.to_string();
+            let loc_var = format_ident!("{}_loc", name);
+            let is_default_var = format_ident!("{}_is_default", name);
+            let value_expr = if use_spans {
+                quote! { toml::Spanned::new(0..0, (#default_expr)) }
+            } else {
+                quote! { (#default_expr) }
+            };
+            Some(quote! {
+                result.#name = Some(#value_expr);
+                #loc_var = Some(hearthd_config::MergeConflictLocation {
+                    file_path: std::path::PathBuf::from(format!("<default:{}>", #name_str)),
+                    span: 0..0,
+                    content: String::new(),
+                });
+                #is_default_var = true;
+            })
+        })
+        .collect();
+
     // Generate merge logic for each field
     let merge_logic: Vec<_> = fields
         .iter()
         .map(|f| generate_field_merge(f, use_spans))
         .collect::<Result<Vec<_>>>()?;
 
+    // An `%unset` directive clears `result.<field>` via `apply_unset`, but
+    // the conflict tracking for top-level fields lives in a separate local
+    // (`<field>_loc`/`_locs`/`_field_locs`), not in `result` itself. Reset it
+    // too so the next file's value is treated as a fresh first occurrence
+    // rather than a conflict with whatever was just cleared.
+    let unset_reset_arms: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let name = &f.name;
+            let name_str = name.to_string();
+            match &f.field_type {
+                FieldType::HashMap { .. } => {
+                    let var_name = format_ident!("{}_locs", name);
+                    quote! { #name_str => { #var_name.clear(); } }
+                }
+                FieldType::HashMapOfStructs { .. } | FieldType::Nested(_) => {
+                    let var_name = format_ident!("{}_field_locs", name);
+                    quote! { #name_str => { #var_name.clear(); } }
+                }
+                _ => {
+                    let var_name = format_ident!("{}_loc", name);
+                    quote! { #name_str => { #var_name = None; } }
+                }
+            }
+        })
+        .collect();
+
     // Generate empty check
     let empty_checks: Vec<_> = fields
         .iter()
@@ -322,8 +1247,11 @@ fn generate_root_merge_impl(
                 let mut result = Self::default();
                 let mut diagnostics = Vec::new();
                 let mut imports = Vec::new();
+                let mut unset = Vec::new();
 
                 #(#tracking_vars)*
+                #(#default_tracking_vars)*
+                #(#default_seeds)*
 
                 for config in configs {
                     imports.extend(config.imports.clone());
@@ -333,7 +1261,7 @@ fn generate_root_merge_impl(
                         content: String::new(),
                     });
 
-                    let is_empty = #(#empty_checks)&&* && config.imports.is_empty();
+                    let is_empty = #(#empty_checks)&&* && config.imports.is_empty() && config.unset.is_empty();
 
                     if is_empty {
                         diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::EmptyConfig {
@@ -341,10 +1269,24 @@ fn generate_root_merge_impl(
                         }));
                     }
 
+                    // Apply this file's `%unset` directives before merging
+                    // in its own values, so a clear is itself overridable by
+                    // a later set in the very same file.
+                    for path in &config.unset {
+                        result.apply_unset(path);
+                        let head = path.split('.').next().unwrap_or(path.as_str());
+                        match head {
+                            #(#unset_reset_arms)*
+                            _ => {}
+                        }
+                    }
+                    unset.extend(config.unset.clone());
+
                     #(#merge_logic)*
                 }
 
                 result.imports = imports;
+                result.unset = unset;
                 (result, diagnostics)
             }
         }
@@ -394,6 +1336,13 @@ fn generate_sub_merge_impl(
                 field_prefix: &str,
                 diagnostics: &mut Vec<hearthd_config::Diagnostic>,
             ) {
+                // Same ordering as the root merge: apply `%unset` directives
+                // from `other` before merging in its own values, so a clear
+                // and a set in the same file compose as "clear, then set".
+                for path in &other.unset {
+                    self.apply_unset(path);
+                }
+
                 #(#merge_fields)*
             }
         }
@@ -404,10 +1353,34 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
     let name = &field.name;
     let name_str = name.to_string();
 
+    let is_override = field.merge_strategy == FieldMergeStrategy::Override;
+
     match &field.field_type {
         FieldType::Simple(_) => {
             if use_spans {
                 // For Spanned types, detect conflicts
+                let conflict_arm = if is_override {
+                    quote! {
+                        diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::FieldOverridden {
+                            field_path,
+                            overridden: first_loc,
+                            winner: conflict_loc.clone(),
+                        }));
+                        self.#name = Some(value);
+                        field_locs.insert(#name_str.to_string(), conflict_loc);
+                    }
+                } else {
+                    quote! {
+                        let message = format!("Field '{}' defined in multiple config files", field_path);
+                        diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                            field_path,
+                            message,
+                            conflicts: vec![first_loc, conflict_loc],
+                            related: vec![],
+                            suggestions: vec![],
+                        })));
+                    }
+                };
                 Ok(quote! {
                     if let Some(value) = std::mem::take(&mut other.#name) {
                         if self.#name.is_none() {
@@ -436,23 +1409,39 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
                                 span: value.span(),
                                 content: source_info.content.clone(),
                             };
-                            let message = format!("Field '{}' defined in multiple config files", field_path);
-                            diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                field_path,
-                                message,
-                                conflicts: vec![first_loc, conflict_loc],
-                            })));
+                            #conflict_arm
                         }
                     }
                 })
             } else {
                 // For plain types (no Spanned), still detect conflicts but without span info
+                let conflict_arm = if is_override {
+                    quote! {
+                        diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::FieldOverridden {
+                            field_path,
+                            overridden: first_loc,
+                            winner: conflict_loc.clone(),
+                        }));
+                        self.#name = Some(_value);
+                        field_locs.insert(#name_str.to_string(), conflict_loc);
+                    }
+                } else {
+                    quote! {
+                        let message = format!("Field '{}' defined in multiple config files", field_path);
+                        diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                            field_path,
+                            message,
+                            conflicts: vec![first_loc, conflict_loc],
+                            related: vec![],
+                            suggestions: vec![],
+                        })));
+                    }
+                };
                 Ok(quote! {
                     if let Some(_value) = std::mem::take(&mut other.#name) {
                         if self.#name.is_some() {
                             // Conflict detected - field already set
                             let field_path = format!("{}.{}", field_prefix, #name_str);
-                            let message = format!("Field '{}' defined in multiple config files", field_path);
                             let conflict_loc = hearthd_config::MergeConflictLocation {
                                 file_path: source_info.file_path.clone(),
                                 span: 0..0, // No span info for plain types
@@ -465,11 +1454,7 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
                                     content: String::new(),
                                 }
                             });
-                            diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                field_path,
-                                message,
-                                conflicts: vec![first_loc, conflict_loc],
-                            })));
+                            #conflict_arm
                         } else {
                             // First occurrence - record it
                             self.#name = Some(_value);
@@ -485,6 +1470,28 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
             }
         }
         FieldType::HashMap { .. } => {
+            let conflict_arm = if is_override {
+                quote! {
+                    diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::FieldOverridden {
+                        field_path,
+                        overridden: prev_loc.clone(),
+                        winner: conflict_loc.clone(),
+                    }));
+                    self_map.insert(key.clone(), value);
+                    field_locs.insert(key_str, conflict_loc);
+                }
+            } else {
+                quote! {
+                    let message = format!("Field '{}' defined in multiple config files", field_path);
+                    diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                        field_path,
+                        message,
+                        conflicts: vec![prev_loc.clone(), conflict_loc],
+                        related: vec![],
+                        suggestions: vec![],
+                    })));
+                }
+            };
             if use_spans {
                 Ok(quote! {
                     if let Some(map) = other.#name {
@@ -493,24 +1500,19 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
                         }
 
                         let self_map = self.#name.as_mut().unwrap();
-                        for (key, value_spanned) in map {
+                        for (key, value) in map {
                             let field_path = format!("{}.{}.{}", field_prefix, #name_str, key);
                             let conflict_loc = hearthd_config::MergeConflictLocation {
                                 file_path: source_info.file_path.clone(),
-                                span: value_spanned.span(),
+                                span: value.span(),
                                 content: source_info.content.clone(),
                             };
 
                             let key_str = key.to_string();
                             if let Some(prev_loc) = field_locs.get(&key_str) {
-                                let message = format!("Field '{}' defined in multiple config files", field_path);
-                                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                    field_path,
-                                    message,
-                                    conflicts: vec![prev_loc.clone(), conflict_loc],
-                                })));
+                                #conflict_arm
                             } else {
-                                self_map.insert(key.clone(), value_spanned);
+                                self_map.insert(key.clone(), value);
                                 field_locs.insert(key_str, conflict_loc);
                             }
                         }
@@ -534,12 +1536,7 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
 
                             let key_str = key.to_string();
                             if let Some(prev_loc) = field_locs.get(&key_str) {
-                                let message = format!("Field '{}' defined in multiple config files", field_path);
-                                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                    field_path,
-                                    message,
-                                    conflicts: vec![prev_loc.clone(), conflict_loc],
-                                })));
+                                #conflict_arm
                             } else {
                                 self_map.insert(key.clone(), value);
                                 field_locs.insert(key_str, conflict_loc);
@@ -606,16 +1603,106 @@ fn generate_sub_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenS
                 }
             })
         }
+        FieldType::List { .. } => {
+            let is_replace = field.merge_strategy == FieldMergeStrategy::Replace;
+            if !is_replace {
+                // Default: concatenate every file's list together
+                // (Dhall-style list combination) - no conflict to track.
+                return Ok(quote! {
+                    if let Some(list) = std::mem::take(&mut other.#name) {
+                        self.#name.get_or_insert_with(Vec::new).extend(list);
+                    }
+                });
+            }
+
+            let conflict_arm = quote! {
+                let message = format!("Field '{}' defined in multiple config files", field_path);
+                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                    field_path,
+                    message,
+                    conflicts: vec![first_loc, conflict_loc],
+                    related: vec![],
+                    suggestions: vec![],
+                })));
+            };
+            let span_expr = if use_spans {
+                quote! { list.first().map(|v| v.span()).unwrap_or(0..0) }
+            } else {
+                quote! { 0..0 }
+            };
+            Ok(quote! {
+                if let Some(list) = std::mem::take(&mut other.#name) {
+                    let conflict_loc = hearthd_config::MergeConflictLocation {
+                        file_path: source_info.file_path.clone(),
+                        span: #span_expr,
+                        content: source_info.content.clone(),
+                    };
+                    if self.#name.is_none() {
+                        self.#name = Some(list);
+                        field_locs.insert(#name_str.to_string(), conflict_loc);
+                    } else {
+                        let field_path = format!("{}.{}", field_prefix, #name_str);
+                        let first_loc = field_locs.get(#name_str).cloned().unwrap_or_else(|| {
+                            hearthd_config::MergeConflictLocation {
+                                file_path: std::path::PathBuf::new(),
+                                span: 0..0,
+                                content: String::new(),
+                            }
+                        });
+                        #conflict_arm
+                    }
+                }
+            })
+        }
     }
 }
 
 fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStream> {
     let name = &field.name;
     let name_str = name.to_string();
+    let is_override = field.merge_strategy == FieldMergeStrategy::Override;
 
     match &field.field_type {
         FieldType::Simple(_) => {
             let loc_var = format_ident!("{}_loc", name);
+            let conflict_arm = if is_override {
+                quote! {
+                    diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::FieldOverridden {
+                        field_path: #name_str.to_string(),
+                        overridden: prev_loc.clone(),
+                        winner: conflict_loc.clone(),
+                    }));
+                    result.#name = Some(value);
+                    #loc_var = Some(conflict_loc);
+                }
+            } else {
+                quote! {
+                    diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                        field_path: #name_str.to_string(),
+                        message: format!("Field '{}' defined in multiple config files", #name_str),
+                        conflicts: vec![prev_loc.clone(), conflict_loc],
+                        related: vec![],
+                        suggestions: vec![],
+                    })));
+                }
+            };
+            // A value that's only present because of `#[config(default =
+            // ...)]` isn't a real prior definition - the first file to set
+            // the field silently wins instead of conflicting with it.
+            let occupied_arm = if field.default_expr.is_some() {
+                let is_default_var = format_ident!("{}_is_default", name);
+                quote! {
+                    if #is_default_var {
+                        result.#name = Some(value);
+                        #loc_var = Some(conflict_loc);
+                        #is_default_var = false;
+                    } else {
+                        #conflict_arm
+                    }
+                }
+            } else {
+                conflict_arm
+            };
             if use_spans {
                 Ok(quote! {
                     if let Some(value) = config.#name {
@@ -626,11 +1713,7 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
                         };
 
                         if let Some(prev_loc) = #loc_var.as_ref() {
-                            diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                field_path: #name_str.to_string(),
-                                message: format!("Field '{}' defined in multiple config files", #name_str),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
+                            #occupied_arm
                         } else {
                             result.#name = Some(value);
                             #loc_var = Some(conflict_loc);
@@ -647,11 +1730,7 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
                         };
 
                         if let Some(prev_loc) = #loc_var.as_ref() {
-                            diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                field_path: #name_str.to_string(),
-                                message: format!("Field '{}' defined in multiple config files", #name_str),
-                                conflicts: vec![prev_loc.clone(), conflict_loc],
-                            })));
+                            #occupied_arm
                         } else {
                             result.#name = Some(value);
                             #loc_var = Some(conflict_loc);
@@ -662,6 +1741,27 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
         }
         FieldType::HashMap { .. } => {
             let locs_var = format_ident!("{}_locs", name);
+            let conflict_arm = if is_override {
+                quote! {
+                    diagnostics.push(hearthd_config::Diagnostic::Warning(hearthd_config::Warning::FieldOverridden {
+                        field_path,
+                        overridden: prev_loc.clone(),
+                        winner: conflict_loc.clone(),
+                    }));
+                    result_map.insert(key.clone(), value);
+                    #locs_var.insert(key, conflict_loc);
+                }
+            } else {
+                quote! {
+                    diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                        field_path,
+                        message: format!("Map entry '{}' in '{}' defined in multiple config files", key, #name_str),
+                        conflicts: vec![prev_loc.clone(), conflict_loc],
+                        related: vec![],
+                        suggestions: vec![],
+                    })));
+                }
+            };
             if use_spans {
                 Ok(quote! {
                     if let Some(map) = config.#name {
@@ -670,22 +1770,18 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
                         }
 
                         let result_map = result.#name.as_mut().unwrap();
-                        for (key, value_spanned) in map {
+                        for (key, value) in map {
                             let conflict_loc = hearthd_config::MergeConflictLocation {
                                 file_path: source_info.file_path.clone(),
-                                span: value_spanned.span(),
+                                span: value.span(),
                                 content: source_info.content.clone(),
                             };
 
                             let field_path = format!("{}.{}", #name_str, key);
                             if let Some(prev_loc) = #locs_var.get(&key) {
-                                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                    field_path,
-                                    message: format!("Map entry '{}' in '{}' defined in multiple config files", key, #name_str),
-                                    conflicts: vec![prev_loc.clone(), conflict_loc],
-                                })));
+                                #conflict_arm
                             } else {
-                                result_map.insert(key.clone(), value_spanned);
+                                result_map.insert(key.clone(), value);
                                 #locs_var.insert(key, conflict_loc);
                             }
                         }
@@ -708,11 +1804,7 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
 
                             let field_path = format!("{}.{}", #name_str, key);
                             if let Some(prev_loc) = #locs_var.get(&key) {
-                                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
-                                    field_path,
-                                    message: format!("Map entry '{}' in '{}' defined in multiple config files", key, #name_str),
-                                    conflicts: vec![prev_loc.clone(), conflict_loc],
-                                })));
+                                #conflict_arm
                             } else {
                                 result_map.insert(key.clone(), value);
                                 #locs_var.insert(key, conflict_loc);
@@ -777,6 +1869,50 @@ fn generate_field_merge(field: &FieldInfo, use_spans: bool) -> Result<TokenStrea
                 }
             })
         }
+        FieldType::List { .. } => {
+            let loc_var = format_ident!("{}_loc", name);
+            let is_replace = field.merge_strategy == FieldMergeStrategy::Replace;
+            if !is_replace {
+                // Default: concatenate every file's list together
+                // (Dhall-style list combination) - no conflict to track.
+                return Ok(quote! {
+                    if let Some(list) = config.#name {
+                        result.#name.get_or_insert_with(Vec::new).extend(list);
+                    }
+                });
+            }
+
+            let conflict_arm = quote! {
+                diagnostics.push(hearthd_config::Diagnostic::Error(hearthd_config::Error::Merge(hearthd_config::MergeError {
+                    field_path: #name_str.to_string(),
+                    message: format!("Field '{}' defined in multiple config files", #name_str),
+                    conflicts: vec![prev_loc.clone(), conflict_loc],
+                    related: vec![],
+                    suggestions: vec![],
+                })));
+            };
+            let span_expr = if use_spans {
+                quote! { list.first().map(|v| v.span()).unwrap_or(0..0) }
+            } else {
+                quote! { 0..0 }
+            };
+            Ok(quote! {
+                if let Some(list) = config.#name {
+                    let conflict_loc = hearthd_config::MergeConflictLocation {
+                        file_path: source_info.file_path.clone(),
+                        span: #span_expr,
+                        content: source_info.content.clone(),
+                    };
+
+                    if let Some(prev_loc) = #loc_var.as_ref() {
+                        #conflict_arm
+                    } else {
+                        result.#name = Some(list);
+                        #loc_var = Some(conflict_loc);
+                    }
+                }
+            })
+        }
     }
 }
 
@@ -785,71 +1921,527 @@ fn generate_load_impl(config_name: &Ident) -> Result<TokenStream> {
 
     Ok(quote! {
         impl #partial_name {
+            /// Deserialize `content` into `Self`, dispatching on `path`'s
+            /// extension: `.json` goes through `serde_json`, `.yaml`/`.yml`
+            /// through `serde_yaml`, and anything else (including `.toml`)
+            /// falls back to TOML. `Self`'s fields stay `toml::Spanned<T>`
+            /// regardless of format - `Spanned`'s `Deserialize` impl
+            /// degrades gracefully on deserializers that don't carry byte
+            /// spans, producing a `0..0` span - so a single partial type
+            /// can be fed either a spanned TOML document or a span-less
+            /// JSON/YAML one and `generate_field_merge`'s conflict
+            /// reporting keeps working either way.
+            fn parse_content(path: &std::path::Path, content: &str) -> Result<Self, hearthd_config::LoadError> {
+                match path.extension().and_then(|ext| ext.to_str()) {
+                    Some("json") => {
+                        serde_json::from_str(content).map_err(|e| hearthd_config::LoadError::Parse {
+                            path: path.to_path_buf(),
+                            error: e.to_string(),
+                        })
+                    }
+                    Some("yaml") | Some("yml") => {
+                        serde_yaml::from_str(content).map_err(|e| hearthd_config::LoadError::Parse {
+                            path: path.to_path_buf(),
+                            error: e.to_string(),
+                        })
+                    }
+                    _ => toml::from_str(content).map_err(|e| hearthd_config::LoadError::Parse {
+                        path: path.to_path_buf(),
+                        error: e.to_string(),
+                    }),
+                }
+            }
+
             pub fn from_file(path: &std::path::Path) -> Result<Self, hearthd_config::LoadError> {
                 let content = std::fs::read_to_string(path).map_err(|e| hearthd_config::LoadError::Io {
                     path: path.to_path_buf(),
                     error: e.to_string(),
                 })?;
 
-                let mut config: Self = toml::from_str(&content).map_err(|e| hearthd_config::LoadError::Parse {
-                    path: path.to_path_buf(),
-                    error: e.to_string(),
-                })?;
+                let mut config: Self = Self::parse_content(path, &content)?;
 
                 config.source = Some(hearthd_config::SourceInfo {
                     file_path: path.to_path_buf(),
                     content,
                 });
+                config.attach_base_dir(path.parent().unwrap_or_else(|| std::path::Path::new(".")));
+
+                Ok(config)
+            }
+
+            /// Parse `overrides` - each a `(dotted.path, toml_value)` pair
+            /// as from a `--set path=value` CLI flag (see
+            /// `hearthd_config::parse_override`) - into a top-priority
+            /// layer and merge it through the same conflict-checking
+            /// `merge` every file and import goes through, so two `--set`
+            /// flags for the same field - or a `--set` and a file both
+            /// setting it - report an `Error::Merge` diagnostic pointing
+            /// at each value's `command-line:<path>` source, exactly like
+            /// `format_diagnostics` already renders for file-derived
+            /// conflicts. `value`'s right-hand side is parsed as a literal
+            /// TOML value (quoted strings, bare numbers/booleans); an
+            /// invalid path or an unparseable value surfaces as a
+            /// `hearthd_config::LoadError::Parse` rather than a panic.
+            pub fn apply_overrides(
+                overrides: &[(&str, &str)],
+            ) -> Result<(Self, Vec<hearthd_config::Diagnostic>), hearthd_config::LoadError> {
+                let mut configs = Vec::with_capacity(overrides.len());
+
+                for (path, value) in overrides {
+                    let override_ = hearthd_config::parse_override(path, value).ok_or_else(|| {
+                        hearthd_config::LoadError::Parse {
+                            path: std::path::PathBuf::from(format!("command-line:{}", path)),
+                            error: format!("invalid override path {:?}: empty segment", path),
+                        }
+                    })?;
+
+                    let file_path = std::path::PathBuf::from(format!("command-line:{}", path));
+                    let mut config: Self = Self::parse_content(&file_path, &override_.toml)?;
+                    config.source = Some(override_.source());
+                    configs.push(config);
+                }
+
+                Ok(Self::merge(configs))
+            }
+
+            /// Load a single config from a resolved `ImportLocation` rather
+            /// than a bare path: a `Local` location is read straight through
+            /// `from_file`, a `Remote` location is fetched over HTTP, and an
+            /// `Env` location's *value* (not a path it names) is parsed
+            /// directly as the TOML content. `attach_base_dir` only runs for
+            /// `Local` locations, since only they have a directory for
+            /// `ConfigRelativePath` fields to resolve against.
+            fn from_location(
+                location: &hearthd_config::ImportLocation,
+            ) -> Result<Self, hearthd_config::LoadError> {
+                if let hearthd_config::ImportLocation::Local(path) = location {
+                    return Self::from_file(path);
+                }
+
+                let file_path = location.label();
+                let content = match location {
+                    hearthd_config::ImportLocation::Local(_) => unreachable!(),
+                    hearthd_config::ImportLocation::Remote(url) => {
+                        ureq::get(url)
+                            .call()
+                            .map_err(|e| hearthd_config::LoadError::Fetch {
+                                location: file_path.clone(),
+                                error: e.to_string(),
+                            })?
+                            .into_string()
+                            .map_err(|e| hearthd_config::LoadError::Fetch {
+                                location: file_path.clone(),
+                                error: e.to_string(),
+                            })?
+                    }
+                    hearthd_config::ImportLocation::Env(var_name) => {
+                        std::env::var(var_name).map_err(|e| hearthd_config::LoadError::EnvVar {
+                            name: var_name.clone(),
+                            error: e.to_string(),
+                        })?
+                    }
+                };
+
+                // An `Env` location's content is a synthetic single-field
+                // TOML document rendered by `EnvSource`, not a file with a
+                // meaningful extension, so it always parses as TOML; only
+                // `Remote` dispatches on the URL's extension like `from_file`.
+                let mut config: Self = match location {
+                    hearthd_config::ImportLocation::Env(_) => {
+                        toml::from_str(&content).map_err(|e| hearthd_config::LoadError::Parse {
+                            path: file_path.clone(),
+                            error: e.to_string(),
+                        })?
+                    }
+                    _ => Self::parse_content(&file_path, &content)?,
+                };
+
+                config.source = Some(hearthd_config::SourceInfo {
+                    file_path,
+                    content,
+                });
 
                 Ok(config)
             }
 
-            pub fn load_with_imports(paths: &[std::path::PathBuf]) -> Result<Vec<Self>, hearthd_config::LoadError> {
+            /// Load `paths` (and their transitive imports), tolerating
+            /// per-file failures: an IO error, a TOML parse error, an
+            /// import cycle, or a sandboxed import is recorded as a
+            /// `Diagnostic::Error(Error::Load)` rather than aborting the
+            /// whole load, so one malformed file doesn't hide problems in
+            /// the others.
+            ///
+            /// A diamond import (two files both importing a shared third
+            /// file) contributes that shared file's config at most once -
+            /// see `load_recursive`'s `loaded` cache.
+            ///
+            /// Precedence is deterministic and import-order-dependent: each
+            /// `paths` entry is pushed onto the returned `Vec<Self>` only
+            /// after its own `imports` have been resolved and pushed first
+            /// (see `load_recursive`), so an importing file always sits
+            /// after everything it imports, and among the `paths` passed
+            /// in, a later one always sits after an earlier one's whole
+            /// import tree. `merge` processes the returned configs in that
+            /// same order, so for an `#[config(merge = "override")]` field
+            /// or an `unset` directive - where order decides the outcome -
+            /// an importing file (or a later `paths` entry) always wins
+            /// over what it imports (or an earlier entry).
+            pub fn load_with_imports(
+                paths: &[std::path::PathBuf],
+            ) -> (Vec<Self>, Vec<hearthd_config::Diagnostic>) {
+                let (configs, diagnostics, _touched_files) = Self::load_with_imports_tracking(paths);
+                (configs, diagnostics)
+            }
+
+            /// Same as `load_with_imports`, but also returns the
+            /// canonicalized, deduplicated set of every local file
+            /// `load_recursive` visited - `paths` themselves plus every
+            /// transitively resolved `import_path` - regardless of whether
+            /// that file loaded successfully. `watch_with_imports` uses this
+            /// to know exactly which files to register filesystem watches
+            /// on, and to re-derive that set after a reload since an edit
+            /// may have added or removed imports.
+            fn load_with_imports_tracking(
+                paths: &[std::path::PathBuf],
+            ) -> (Vec<Self>, Vec<hearthd_config::Diagnostic>, Vec<std::path::PathBuf>) {
                 let mut visited = std::collections::HashSet::new();
+                let mut loaded = std::collections::HashSet::new();
                 let mut all_configs = Vec::new();
+                let mut diagnostics = Vec::new();
+                let mut touched_files = Vec::new();
 
                 for path in paths {
-                    Self::load_recursive(path, &mut visited, &mut all_configs)?;
+                    let location = hearthd_config::ImportLocation::Local(path.clone());
+                    Self::load_recursive(
+                        &location,
+                        &mut visited,
+                        &mut loaded,
+                        &mut all_configs,
+                        &mut diagnostics,
+                        &mut touched_files,
+                    );
+                }
+
+                (all_configs, diagnostics, touched_files)
+            }
+
+            /// Watch every file in `paths`' transitive import graph (per
+            /// `load_with_imports_tracking`) with the `notify` crate and
+            /// re-run `load_with_imports` + `merge` whenever one changes,
+            /// handing the freshly merged config and its diagnostics to
+            /// `callback` along with a generation counter that increments
+            /// on every reload (starting from `0` for the initial load), so
+            /// a consumer can discard a reload that's already been
+            /// superseded by a newer one racing ahead of it.
+            ///
+            /// Rapid successive events - an editor's save-as-temp-then-
+            /// rename, or several files in a `conf.d/` directory changing
+            /// together - are coalesced by draining the event channel for
+            /// `debounce` after the first event in a burst before reloading
+            /// once. Because a reload can add or drop imports, the watch
+            /// set is rebuilt from the new transitive file list after every
+            /// reload rather than fixed at start time: a file deleted out
+            /// from under a watch surfaces as a `LoadError::Io` diagnostic
+            /// (from `from_file`'s failed read) on the next reload rather
+            /// than panicking, and an import cycle introduced by an edit is
+            /// still caught by `load_recursive`'s existing `ImportCycle`
+            /// check.
+            ///
+            /// Blocks the calling thread for as long as the watch runs; run
+            /// it on a dedicated thread.
+            pub fn watch_with_imports<F>(
+                paths: &[std::path::PathBuf],
+                debounce: std::time::Duration,
+                mut callback: F,
+            ) -> notify::Result<()>
+            where
+                F: FnMut(Self, Vec<hearthd_config::Diagnostic>, u64),
+            {
+                let paths = paths.to_vec();
+                let (tx, rx) = std::sync::mpsc::channel();
+
+                let mut watcher = notify::recommended_watcher(move |event| {
+                    let _ = tx.send(event);
+                })?;
+
+                let reload = |watcher: &mut notify::RecommendedWatcher,
+                               watched: &[std::path::PathBuf]| {
+                    for path in watched {
+                        let _ = watcher.unwatch(path);
+                    }
+
+                    let (configs, diagnostics, touched_files) =
+                        Self::load_with_imports_tracking(&paths);
+                    let (merged, merge_diagnostics) = Self::merge(configs);
+
+                    let mut all_diagnostics = diagnostics;
+                    all_diagnostics.extend(merge_diagnostics);
+
+                    for path in &touched_files {
+                        let _ = watcher.watch(path, notify::RecursiveMode::NonRecursive);
+                    }
+
+                    (merged, all_diagnostics, touched_files)
+                };
+
+                let mut watched = Vec::new();
+                let (merged, diagnostics, touched_files) = reload(&mut watcher, &watched);
+                watched = touched_files;
+                let mut generation = 0u64;
+                callback(merged, diagnostics, generation);
+
+                loop {
+                    let Ok(first) = rx.recv() else {
+                        return Ok(());
+                    };
+                    if first.is_err() {
+                        continue;
+                    }
+                    while rx.recv_timeout(debounce).is_ok() {}
+
+                    let (merged, diagnostics, touched_files) = reload(&mut watcher, &watched);
+                    watched = touched_files;
+                    generation += 1;
+                    callback(merged, diagnostics, generation);
+                }
+            }
+
+            /// Like `watch_with_imports`, but also runs the merged partial
+            /// through `T::try_from_partial` on every reload and hands
+            /// `callback` the fully validated `T` rather than the raw
+            /// `Self` partial - mirroring config-rs's watch example, where
+            /// a consumer (e.g. a running daemon) only ever sees a config
+            /// it could actually have started with.
+            ///
+            /// `initial` seeds the "last-good" config served before the
+            /// first successful reload and on every reload whose
+            /// `try_from_partial` fails: its diagnostics are appended to
+            /// that reload's load/merge diagnostics and handed to
+            /// `callback` alongside the still-current `initial`/last-good
+            /// value, so an editor typo never tears down a running
+            /// daemon's config - it just surfaces as diagnostics until
+            /// the file is fixed.
+            ///
+            /// Blocks the calling thread for as long as the watch runs; run
+            /// it on a dedicated thread.
+            pub fn watch<T, F>(
+                paths: &[std::path::PathBuf],
+                debounce: std::time::Duration,
+                initial: T,
+                mut callback: F,
+            ) -> notify::Result<()>
+            where
+                T: hearthd_config::TryFromPartial<PartialConfig = Self>,
+                F: FnMut(&T, Vec<hearthd_config::Diagnostic>, u64),
+            {
+                let paths = paths.to_vec();
+                let (tx, rx) = std::sync::mpsc::channel();
+
+                let mut watcher = notify::recommended_watcher(move |event| {
+                    let _ = tx.send(event);
+                })?;
+
+                let reload = |watcher: &mut notify::RecommendedWatcher,
+                               watched: &[std::path::PathBuf]| {
+                    for path in watched {
+                        let _ = watcher.unwatch(path);
+                    }
+
+                    let (configs, diagnostics, touched_files) =
+                        Self::load_with_imports_tracking(&paths);
+                    let (merged, merge_diagnostics) = Self::merge(configs);
+
+                    let mut all_diagnostics = diagnostics;
+                    all_diagnostics.extend(merge_diagnostics);
+
+                    for path in &touched_files {
+                        let _ = watcher.watch(path, notify::RecursiveMode::NonRecursive);
+                    }
+
+                    (merged, all_diagnostics, touched_files)
+                };
+
+                let mut last_good = initial;
+                let mut watched = Vec::new();
+                let mut generation = 0u64;
+
+                let (merged, mut diagnostics, touched_files) = reload(&mut watcher, &watched);
+                watched = touched_files;
+                match T::try_from_partial(merged) {
+                    Ok(config) => last_good = config,
+                    Err(validation_diagnostics) => diagnostics.extend(validation_diagnostics),
+                }
+                callback(&last_good, diagnostics, generation);
+
+                loop {
+                    let Ok(first) = rx.recv() else {
+                        return Ok(());
+                    };
+                    if first.is_err() {
+                        continue;
+                    }
+                    while rx.recv_timeout(debounce).is_ok() {}
+
+                    let (merged, mut diagnostics, touched_files) = reload(&mut watcher, &watched);
+                    watched = touched_files;
+                    match T::try_from_partial(merged) {
+                        Ok(config) => last_good = config,
+                        Err(validation_diagnostics) => diagnostics.extend(validation_diagnostics),
+                    }
+                    generation += 1;
+                    callback(&last_good, diagnostics, generation);
                 }
+            }
+
+            /// Build a single partial config from every environment variable
+            /// matching `prefix` (see `hearthd_config::EnvSource`), so it can
+            /// be layered on top of file-derived config with last-wins
+            /// precedence. Each matching variable becomes its own
+            /// single-field document - e.g. `{prefix}_NAME` sets `name`,
+            /// `{prefix}_DATABASE__PORT` sets `database.port` - parsed and
+            /// folded together with `merge_with_precedence`, so two
+            /// variables setting different fields never conflict with each
+            /// other. A variable that fails to parse (the wrong type for
+            /// its field) is silently skipped rather than surfaced as a
+            /// diagnostic, matching `from_file`'s "best effort" env layer;
+            /// use `load_with_imports_and_env` to get a fully wired
+            /// files-then-env pipeline.
+            pub fn from_env(prefix: &str) -> Self {
+                let layers = hearthd_config::EnvSource::scan(prefix)
+                    .into_iter()
+                    .filter_map(|var| {
+                        let mut partial: Self = toml::from_str(&var.toml).ok()?;
+                        partial.source = Some(var.source());
+                        Some(partial)
+                    });
+
+                Self::merge_with_precedence(layers)
+            }
+
+            /// Load `paths` and merge them strictly (same as `from_files`'s
+            /// first two steps), then layer environment variables matching
+            /// `env_prefix` on top via `merge_with_precedence` - see
+            /// `from_env`. The env layer is always highest-precedence: it
+            /// silently overrides a value the files set rather than
+            /// conflicting with it, so a deployment can override file-based
+            /// settings without editing TOML. Use
+            /// `load_with_imports_and_env_ordered` for the reverse ordering.
+            pub fn load_with_imports_and_env(
+                paths: &[std::path::PathBuf],
+                env_prefix: &str,
+            ) -> (Self, Vec<hearthd_config::Diagnostic>) {
+                Self::load_with_imports_and_env_ordered(
+                    paths,
+                    env_prefix,
+                    hearthd_config::EnvPrecedence::EnvWins,
+                )
+            }
 
-                Ok(all_configs)
+            /// Same as `load_with_imports_and_env`, but lets the caller
+            /// choose which layer wins a conflict via `precedence` instead
+            /// of always defaulting to the env layer. `FilesWin` is for
+            /// setups where environment variables are a broad convention
+            /// (e.g. set fleet-wide) that an explicit config file should
+            /// still be able to override.
+            pub fn load_with_imports_and_env_ordered(
+                paths: &[std::path::PathBuf],
+                env_prefix: &str,
+                precedence: hearthd_config::EnvPrecedence,
+            ) -> (Self, Vec<hearthd_config::Diagnostic>) {
+                let (configs, diagnostics) = Self::load_with_imports(paths);
+                let (file_partial, merge_diagnostics) = Self::merge(configs);
+
+                let mut all_diagnostics = diagnostics;
+                all_diagnostics.extend(merge_diagnostics);
+
+                let env_partial = Self::from_env(env_prefix);
+                let layers = match precedence {
+                    hearthd_config::EnvPrecedence::EnvWins => [file_partial, env_partial],
+                    hearthd_config::EnvPrecedence::FilesWin => [env_partial, file_partial],
+                };
+
+                let merged = Self::merge_with_precedence(layers);
+                (merged, all_diagnostics)
             }
 
             fn load_recursive(
-                path: &std::path::Path,
-                visited: &mut std::collections::HashSet<std::path::PathBuf>,
+                location: &hearthd_config::ImportLocation,
+                visited: &mut std::collections::HashSet<hearthd_config::ImportLocation>,
+                loaded: &mut std::collections::HashSet<hearthd_config::ImportLocation>,
                 configs: &mut Vec<Self>,
-            ) -> Result<(), hearthd_config::LoadError> {
-                let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+                diagnostics: &mut Vec<hearthd_config::Diagnostic>,
+                touched_files: &mut Vec<std::path::PathBuf>,
+            ) {
+                let normalized = location.normalize();
+
+                if visited.contains(&normalized) {
+                    diagnostics.push(hearthd_config::Diagnostic::Error(
+                        hearthd_config::Error::Load(hearthd_config::LoadError::ImportCycle {
+                            path: normalized.label(),
+                            cycle: visited.iter().map(|loc| loc.label()).collect(),
+                        }),
+                    ));
+                    return;
+                }
 
-                if visited.contains(&canonical_path) {
-                    return Err(hearthd_config::LoadError::ImportCycle {
-                        path: canonical_path.clone(),
-                        cycle: visited.iter().cloned().collect(),
-                    });
+                // A diamond import: this file already finished loading (and
+                // contributed its config) via an earlier branch. Skip it
+                // silently rather than re-parsing and re-merging it, which
+                // would surface as a bogus merge conflict between a file
+                // and itself.
+                if loaded.contains(&normalized) {
+                    return;
                 }
 
-                visited.insert(canonical_path.clone());
+                visited.insert(normalized.clone());
 
-                let config = Self::from_file(path)?;
+                // Record the file as touched before attempting to load it,
+                // so a file that currently fails to read (e.g. it was
+                // deleted) is still watched and can trigger a reload once
+                // it comes back.
+                if let hearthd_config::ImportLocation::Local(path) = &normalized {
+                    touched_files.push(path.clone());
+                }
 
-                for import_path in &config.imports {
-                    let import_path_buf = std::path::PathBuf::from(import_path);
+                let config = match Self::from_location(location) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        diagnostics.push(hearthd_config::Diagnostic::Error(
+                            hearthd_config::Error::Load(e),
+                        ));
+                        visited.remove(&normalized);
+                        return;
+                    }
+                };
 
-                    let resolved_path = if import_path_buf.is_absolute() {
-                        import_path_buf
-                    } else {
-                        let parent_dir = path.parent().unwrap_or_else(|| std::path::Path::new("."));
-                        parent_dir.join(import_path_buf)
-                    };
+                for import_path in &config.imports {
+                    for import_location in location.resolve_all(import_path) {
+                        if !location.may_import(&import_location) {
+                            diagnostics.push(hearthd_config::Diagnostic::Error(
+                                hearthd_config::Error::Load(
+                                    hearthd_config::LoadError::ImportNotAllowed {
+                                        from: location.label(),
+                                        to: import_location.label(),
+                                    },
+                                ),
+                            ));
+                            continue;
+                        }
 
-                    Self::load_recursive(&resolved_path, visited, configs)?;
+                        Self::load_recursive(
+                            &import_location,
+                            visited,
+                            loaded,
+                            configs,
+                            diagnostics,
+                            touched_files,
+                        );
+                    }
                 }
 
                 configs.push(config);
-                visited.remove(&canonical_path);
-
-                Ok(())
+                visited.remove(&normalized);
+                loaded.insert(normalized);
             }
         }
     })
@@ -881,6 +2473,26 @@ fn extract_hashmap_types(ty: &Type) -> Result<(Type, Type)> {
     Err(Error::new_spanned(ty, "Expected HashMap<K, V>"))
 }
 
+/// Detects a bare `Vec<T>` field, returning `T`. Unlike `is_option_type`,
+/// this doesn't unwrap an outer `Option` first - `Option<Vec<T>>` is left
+/// as `Nested`, matching how `Option<SomeStruct>` is handled.
+fn is_vec_type(ty: &Type) -> Option<Type> {
+    if let Type::Path(TypePath { path, .. }) = ty {
+        if let Some(segment) = path.segments.last() {
+            if segment.ident == "Vec" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if args.args.len() == 1 {
+                        if let GenericArgument::Type(inner) = &args.args[0] {
+                            return Some(inner.clone());
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
 fn is_option_type(ty: &Type) -> Option<Type> {
     if let Type::Path(TypePath { path, .. }) = ty {
         if let Some(segment) = path.segments.last() {
@@ -898,6 +2510,20 @@ fn is_option_type(ty: &Type) -> Option<Type> {
     None
 }
 
+/// Whether `ty` is `ConfigRelativePath` or `Option<ConfigRelativePath>` -
+/// used by `generate_attach_base_dir_impl` to pick out the fields that need
+/// their base directory recorded.
+fn is_config_relative_path(ty: &Type) -> bool {
+    let unwrapped = is_option_type(ty);
+    let inner = unwrapped.as_ref().unwrap_or(ty);
+    if let Type::Path(TypePath { path, .. }) = inner {
+        if let Some(segment) = path.segments.last() {
+            return segment.ident == "ConfigRelativePath";
+        }
+    }
+    false
+}
+
 fn is_simple_type(ty: &Type) -> bool {
     if let Type::Path(TypePath { path, .. }) = ty {
         if let Some(segment) = path.segments.last() {
@@ -920,8 +2546,46 @@ fn is_simple_type(ty: &Type) -> bool {
                     | "String"
                     | "str"
                     | "LogLevel" // Custom simple enum types
+                    | "ConfigRelativePath" // Resolved against its defining file's directory
+                    | "Secret" // Resolved (and redacted) during loading
+                    | "PathBuf" // A bare filesystem path, not resolved against the config's directory
             );
         }
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use syn::{parse_quote, DeriveInput};
+
+    use super::expand_mergeable_config;
+
+    #[test]
+    fn append_merge_on_a_simple_field_is_a_compile_error() {
+        let input: DeriveInput = parse_quote! {
+            struct Config {
+                #[config(merge = "append")]
+                port: u16,
+            }
+        };
+
+        let result = expand_mergeable_config(input, true);
+
+        assert!(result.is_err());
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("append"));
+    }
+
+    #[test]
+    fn append_merge_on_a_vec_field_is_accepted() {
+        let input: DeriveInput = parse_quote! {
+            struct Config {
+                #[config(merge = "append")]
+                plugins: Vec<String>,
+            }
+        };
+
+        assert!(expand_mergeable_config(input, true).is_ok());
+    }
+}
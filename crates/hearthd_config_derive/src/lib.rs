@@ -1,6 +1,6 @@
 use proc_macro::TokenStream;
-use syn::DeriveInput;
 use syn::parse_macro_input;
+use syn::DeriveInput;
 
 mod generate;
 
@@ -13,9 +13,43 @@ mod generate;
 ///
 /// - `Partial{TypeName}`: A version of your struct where all fields are `Option<T>` and
 ///   wrapped in `toml::Spanned<T>` for source location tracking
-/// - `from_file(path)`: Load a single TOML file
-/// - `load_with_imports(paths)`: Load multiple files with recursive import resolution
+/// - `from_file(path)`: Load a single config file. Dispatches on `path`'s extension -
+///   `.json` and `.yaml`/`.yml` deserialize through `serde_json`/`serde_yaml`, anything
+///   else (including `.toml`) through `toml` - into the same `Partial{TypeName}`, so a
+///   base TOML file and a JSON override can be stitched together in one `load_with_imports`
+///   call. Formats other than TOML don't carry byte spans, so their values get a `0..0`
+///   `MergeConflictLocation` span; `file_path`/`content` are still filled in correctly.
+/// - `load_with_imports(paths)`: Load multiple files with recursive import resolution,
+///   tolerating per-file failures as diagnostics instead of aborting the whole load
 /// - `merge(configs)`: Merge multiple partial configs with conflict detection
+/// - `apply_unset(path)`: Clear a field (optionally a dotted nested path like
+///   `"database.port"`) per an `unset = ["..."]` directive in a later file
+/// - `validate(&self)`: Run the `#[config(validate_with = ...)]` cross-field checker (if
+///   any) registered for this struct against the fully merged partial, returning an empty
+///   `Vec` when none is registered
+/// - `load_with_imports_and_env(paths, env_prefix)` / `load_with_imports_and_env_ordered(paths, env_prefix, precedence)`:
+///   Load and merge `paths`, then layer an `env_prefix`-matching environment-variable partial
+///   on top. The former always has the env layer win a conflict; the latter takes a
+///   `hearthd_config::EnvPrecedence` to make files win instead.
+/// - `json_schema()`: A draft-07 JSON Schema `serde_json::Value` describing this config -
+///   required fields (neither `Option<T>` nor defaulted) in `required`, nested `SubConfig`
+///   types hoisted into `$defs` and referenced via `$ref`. Since this type is itself the
+///   root of the config tree, its `json_schema()` is the schema for the whole document.
+/// - `watch_with_imports(paths, debounce, callback)`: Like `load_with_imports` + `merge`,
+///   but keeps running - it registers `notify` watches on the transitive import set and
+///   invokes `callback` with a freshly merged config, its diagnostics, and a generation
+///   counter every time one of those files changes, debouncing rapid successive events and
+///   re-deriving the watch set after each reload since imports may have changed
+/// - `watch(paths, debounce, initial, callback)`: Like `watch_with_imports`, but also runs
+///   each reload through `T::try_from_partial` and hands `callback` the validated `T`
+///   instead of the raw partial. `initial` seeds the config served before the first reload
+///   and on any reload whose validation fails - its diagnostics are appended so a bad edit
+///   surfaces as diagnostics without interrupting whatever was last serving successfully.
+/// - `apply_overrides(&[(dotted_path, toml_value)])`: Parse CLI `--set path=value` flags
+///   (see `hearthd_config::parse_override`) into a top-priority layer and merge it through
+///   the same conflict-checking `merge` every file goes through, so a `--set` conflicting
+///   with a file (or another `--set`) reports an `Error::Merge` pointing at a synthetic
+///   `command-line:<path>` source.
 ///
 /// # Attributes
 ///
@@ -24,6 +58,30 @@ mod generate;
 /// - `#[config(default = "function_name")]`: Specify a default function for a required field.
 ///   The function will be called if the field is missing from the config. No validation error
 ///   will be generated for missing fields with defaults.
+/// - `#[config(default = <expr>)]` (on a `Simple` field): seed `result` with `expr` before any
+///   file is merged in, so the field has a baseline value ranked below every file in merge
+///   precedence. Unlike the `TryFromPartial`-only `default = "function_name"` form above, this
+///   value participates in `merge()` itself: a file that sets the field silently replaces the
+///   default instead of conflicting with it, and a second file doing the same still conflicts
+///   with the first as normal.
+/// - `#[config(merge = "override")]`: On a `Simple` or `HashMap` field (or `HashMap` entry),
+///   a second definition silently replaces the first and is reported as a
+///   `Diagnostic::Warning(Warning::FieldOverridden)` instead of the default
+///   `Error::Merge` conflict. Use this for fields meant to be layered, e.g. a
+///   base file intentionally overlaid by an environment-specific one.
+/// - `#[config(merge = "replace")]`: On a `Vec<T>` field, require a single
+///   definition (an `Error::Merge` conflict if defined twice) instead of the
+///   default behavior of concatenating every config's list together.
+/// - `#[config(merge = "append")]`: The explicit spelling of the default
+///   concatenate-across-files behavior for `Vec<T>` fields (and the default
+///   union-by-key behavior for `HashMap` fields), for callers who'd rather
+///   name it on the field than rely on the unlabeled default. Rejected with
+///   a compile error on any field whose type isn't a `Vec<T>` or `HashMap`.
+/// - `#[config(validate_with = path::to::fn)]`: Register a cross-field invariant checker -
+///   a `fn(&Partial{TypeName}) -> Vec<Diagnostic>` - run once against the fully merged
+///   partial by the generated `validate()`. Unlike `merge`'s per-field conflict detection,
+///   this can check relationships between different fields (e.g. mutually exclusive fields,
+///   a reference field that must name an entry that exists elsewhere in the config).
 ///
 /// # Example
 ///
@@ -34,6 +92,10 @@ mod generate;
 /// struct Config {
 ///     port: u16,
 ///     host: String,
+///     #[config(merge = "override")]
+///     environment: Option<String>,
+///     #[config(merge = "append")]
+///     plugins: Vec<String>,
 ///     #[serde(flatten)]
 ///     locations: HashMap<String, Location>,
 /// }
@@ -45,8 +107,8 @@ mod generate;
 ///     longitude: f64,
 /// }
 ///
-/// let configs = PartialConfig::load_with_imports(&["config.toml"])?;
-/// let merged = PartialConfig::merge(configs)?;
+/// let (configs, load_diagnostics) = PartialConfig::load_with_imports(&["config.toml"]);
+/// let (merged, merge_diagnostics) = PartialConfig::merge(configs);
 /// let config: Config = merged.try_into()?;
 /// ```
 #[proc_macro_derive(MergeableConfig, attributes(config))]
@@ -70,20 +132,38 @@ pub fn derive_mergeable_config(input: TokenStream) -> TokenStream {
 /// - `Partial{TypeName}`: A version of your struct where all fields are `Option<T>` and
 ///   wrapped in `toml::Spanned<T>` for source location tracking
 /// - `merge_from(other, path)`: Merge another partial config into this one with conflict detection
+/// - `validate(&self)`: Run the `#[config(validate_with = ...)]` cross-field checker (if
+///   any) registered for this struct, returning an empty `Vec` when none is registered
+/// - `json_schema()`: A draft-07 JSON Schema `serde_json::Value` for this type alone, with
+///   its own nested `SubConfig` types (if any) hoisted into `$defs`. A parent
+///   `MergeableConfig`/`SubConfig` that embeds this type as a field instead calls the
+///   lower-level `json_schema_object` to merge this type's `$defs` into its own.
 ///
 /// # Merging Behavior
 ///
 /// - Simple fields: First value wins, later assignments are conflicts
 /// - `HashMap<K, SimpleValue>`: Keys can be defined in multiple files, conflicts per key
 /// - `HashMap<K, Struct>`: Structs with same key are merged field-by-field recursively
+/// - `Vec<T>`: Concatenated across every file by default (Dhall-style list combination);
+///   `#[config(merge = "append")]` spells this out explicitly, and
+///   `#[config(merge = "replace")]` requires a single definition instead
 /// - Nested structs: Merged recursively
+/// - `unset = ["name"]`: Clears `name` (relative to this struct) before the
+///   rest of this file's fields are merged in
 ///
 /// # Attributes
 ///
 /// - `#[config(no_span)]`: Disable `Spanned` wrapping for this struct
 /// - `#[config(default = "function_name")]`: Specify a default function for a required field.
 ///   The function will be called if the field is missing from the config.
+/// - `#[config(merge = "append")]`: The explicit spelling of the default
+///   concatenate-across-files behavior for `Vec<T>` fields (and the default
+///   union-by-key behavior for `HashMap` fields). Rejected with a compile
+///   error on any field whose type isn't a `Vec<T>` or `HashMap`.
 /// - `#[serde(flatten)]`: Mark HashMap fields that are flattened in the parent struct
+/// - `#[config(validate_with = path::to::fn)]`: Register a cross-field invariant checker -
+///   a `fn(&Partial{TypeName}) -> Vec<Diagnostic>` - run once against this struct's merged
+///   fields by the generated `validate()`.
 ///
 /// # Example
 ///